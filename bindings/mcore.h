@@ -1,10 +1,21 @@
 #pragma once
+// Hand-kept declarations for the Zig UI's @cImport. Other hosts use the
+// generated zello.h (`cargo xtask bindings`), which also checks layouts.
 #include <stddef.h>
 #ifdef __cplusplus
 extern "C" {
 #endif
 
+// Version of this header's API: (major << 16) | minor. Pass it to every
+// create call (desc.api_version, mcore_create_headless); an engine built with
+// a different major version, or an older minor one, refuses with
+// MCORE_ERROR_VERSION_MISMATCH instead of misreading the host's structs.
+#define MCORE_API_VERSION_MAJOR 4
+#define MCORE_API_VERSION_MINOR 17
+#define MCORE_API_VERSION 0x00040011  // (MAJOR << 16) | MINOR
+
 typedef struct mcore_context mcore_context_t;
+typedef struct mcore_engine mcore_engine_t;
 
 typedef enum {
   MCORE_PLATFORM_MACOS = 1,
@@ -25,15 +36,74 @@ typedef union {
   mcore_macos_surface_t macos;
 } mcore_surface_union_t;
 
+typedef enum {
+  MCORE_POWER_DEFAULT = 0,           // high performance
+  MCORE_POWER_LOW_POWER = 1,         // integrated GPU, spares the battery
+  MCORE_POWER_HIGH_PERFORMANCE = 2,  // discrete GPU where there is one
+} mcore_power_preference_t;
+
 typedef struct {
+  unsigned int api_version;  // MCORE_API_VERSION
   mcore_platform_t platform;
   mcore_surface_union_t u;
+  mcore_power_preference_t power_preference;
+  unsigned int adapter;  // 1-based index into mcore_enumerate_adapters; 0 picks by power_preference
+  unsigned int flags;    // MCORE_CREATE_* flags
 } mcore_surface_desc_t;
 
+typedef struct {
+  unsigned int api_version;  // MCORE_API_VERSION
+  mcore_power_preference_t power_preference;
+  unsigned int adapter;  // as in mcore_surface_desc_t
+  unsigned int flags;    // MCORE_CREATE_* flags
+} mcore_engine_desc_t;
+
+// Creation flags (mcore_surface_desc_t.flags, mcore_engine_desc_t.flags, mcore_create_headless)
+#define MCORE_CREATE_SOFTWARE_FALLBACK (1 << 0)  // software adapter when no GPU is usable (VMs, CI)
+#define MCORE_CREATE_CPU_PIPELINE      (1 << 1)  // Vello's pipeline stages on the CPU even with a GPU
+
+typedef enum {
+  MCORE_RENDER_MODE_GPU = 0,
+  MCORE_RENDER_MODE_CPU_PIPELINE = 1,  // Vello stages on the CPU, rasterization on the GPU
+  MCORE_RENDER_MODE_SOFTWARE = 2,      // software adapter: everything on the CPU
+} mcore_render_mode_t;
+
+typedef enum {
+  MCORE_DEVICE_OTHER = 0,
+  MCORE_DEVICE_INTEGRATED_GPU = 1,
+  MCORE_DEVICE_DISCRETE_GPU = 2,
+  MCORE_DEVICE_VIRTUAL_GPU = 3,
+  MCORE_DEVICE_CPU = 4,
+} mcore_device_type_t;
+
+typedef enum {
+  MCORE_GPU_BACKEND_OTHER = 0,
+  MCORE_GPU_BACKEND_METAL = 1,
+  MCORE_GPU_BACKEND_VULKAN = 2,
+  MCORE_GPU_BACKEND_DX12 = 3,
+  MCORE_GPU_BACKEND_GL = 4,
+} mcore_gpu_backend_t;
+
+typedef struct {
+  char name[128];  // NUL-terminated, truncated to fit
+  unsigned int vendor;
+  unsigned int device;
+  mcore_device_type_t device_type;
+  mcore_gpu_backend_t backend;
+} mcore_adapter_info_t;
+
+typedef struct {
+  mcore_adapter_info_t adapter;
+  unsigned int max_texture_dimension_2d;
+  unsigned int max_storage_buffer_binding_size;
+  unsigned long long max_buffer_size;
+  unsigned char timestamp_queries;  // GPU frame timings available (mcore_frame_stats gpu_ms)
+} mcore_gpu_info_t;
+
 typedef struct { float r,g,b,a; } mcore_rgba_t;
 
 typedef struct {
-  float x, y, w, h;
+  float x, y, w, h;  // logical px
   float radius;
   mcore_rgba_t fill;
 } mcore_rounded_rect_t;
@@ -44,17 +114,66 @@ typedef struct {
   const char* name;
 } mcore_font_blob_t;
 
+// Alignment of lines within wrap_width (within the widest line if wrap_width
+// is <= 0 or >= 100000, i.e. unwrapped). Start and end follow the direction.
+typedef enum {
+  MCORE_TEXT_ALIGN_START = 0,
+  MCORE_TEXT_ALIGN_CENTER = 1,
+  MCORE_TEXT_ALIGN_END = 2,
+  MCORE_TEXT_ALIGN_JUSTIFY = 3,
+} mcore_text_align_t;
+
+typedef enum {
+  MCORE_TEXT_OVERFLOW_CLIP = 0,
+  MCORE_TEXT_OVERFLOW_ELLIPSIS = 1,  // last visible line ends with "…"
+  MCORE_TEXT_OVERFLOW_FADE = 2,      // last visible line fades out
+} mcore_text_overflow_t;
+
+// Paragraph direction; auto takes it from the first strong character
+typedef enum {
+  MCORE_TEXT_DIRECTION_AUTO = 0,
+  MCORE_TEXT_DIRECTION_LTR = 1,
+  MCORE_TEXT_DIRECTION_RTL = 2,
+} mcore_text_direction_t;
+
 typedef struct {
   const char* utf8;
   float wrap_width;
   float font_size_px;
   int font_id;
+  float weight;           // 100-900, 0 = the font's default face (regular unless loaded otherwise)
+  unsigned char italic;   // 0 or 1
+  float stretch;          // width ratio 0.5-2.0, 0 = normal
+  float letter_spacing;   // extra logical px after each character
+  float word_spacing;     // extra logical px after each word
+  float line_height_multiplier;  // multiple of font size, 0 = font default
+  unsigned char align;    // mcore_text_align_t
+  unsigned int max_lines; // 0 = no limit
+  unsigned char overflow; // mcore_text_overflow_t, applied past max_lines
+  unsigned char direction; // mcore_text_direction_t
+  // Tabs advance to each of tab_stops (logical px from the line start, may be
+  // null), then to multiples of tab_width past the last; 0 = 8 spaces
+  float tab_width;
+  const float* tab_stops;
+  unsigned int tab_stop_count;
 } mcore_text_req_t;
 
+// Style for a byte range of a text request (rich text)
+typedef struct {
+  unsigned int start;   // byte offset, inclusive
+  unsigned int end;     // byte offset, exclusive
+  mcore_rgba_t color;
+  float weight;         // 100-900, 0 = inherit
+  unsigned char italic;
+  unsigned char underline;
+  int font_id;          // -1 = inherit
+} mcore_text_span_t;
+
 typedef struct {
   float advance_w;
   float advance_h;
   int line_count;
+  unsigned char truncated;  // 1 if lines past max_lines were dropped
 } mcore_text_metrics_t;
 
 typedef struct {
@@ -62,17 +181,47 @@ typedef struct {
   float height;
 } mcore_text_size_t;
 
+// Caret position in logical pixels; y is the top of the caret's line
+typedef struct {
+  float x;
+  float y;
+  float height;
+} mcore_caret_rect_t;
+
+typedef struct {
+    float x;
+    float y;
+    float width;
+    float height;
+} mcore_rect_t;
+
 typedef struct {
   unsigned int total_measure_calls;
   unsigned int total_offset_calls;
 } mcore_text_stats_t;
 
+// Shaped-layout cache: current size, limits (max_bytes 0 = unbounded) and
+// counters since the last mcore_reset_text_stats
+typedef struct {
+  unsigned int entries;
+  unsigned int max_entries;
+  unsigned long long bytes;       // estimated
+  unsigned long long max_bytes;
+  unsigned long long hits;
+  unsigned long long misses;
+  unsigned long long evictions;
+} mcore_text_cache_stats_t;
+
 typedef enum {
   MCORE_DRAW_CMD_ROUNDED_RECT = 0,
   MCORE_DRAW_CMD_TEXT = 1,
   MCORE_DRAW_CMD_PUSH_CLIP = 2,
   MCORE_DRAW_CMD_POP_CLIP = 3,
   MCORE_DRAW_CMD_STYLED_RECT = 4,
+  MCORE_DRAW_CMD_PUSH_CLIP_ROUNDED = 5,  // Uses x, y, width, height, radius
+  // Rounded-rect outline stroked with a gradient: x, y, width, height, radius,
+  // border_width (centered on the edge), font_id = gradient ID
+  MCORE_DRAW_CMD_GRADIENT_RING = 6,
 } mcore_draw_cmd_kind_t;
 
 typedef struct {
@@ -96,10 +245,135 @@ typedef struct {
   float shadow_color[4];
   unsigned char has_shadow;  // 0 or 1
 
-  unsigned char _padding[2];
+  unsigned char text_align;      // mcore_text_align_t (text commands)
+  unsigned char text_direction;  // mcore_text_direction_t (text commands)
 } mcore_draw_command_t;
 
-typedef enum { MCORE_OK = 0, MCORE_ERR = 1 } mcore_status_t;
+// ---------------------------------------------------------------------------
+// Command stream v2 (mcore_render_commands_v2)
+//
+// A byte buffer of records, each an mcore_cmd_header_t, then `size` payload
+// bytes, then zero padding to the next multiple of 4. Payloads are the
+// mcore_cmd_*_t structs below in native byte order; coordinates are logical px.
+//
+// Stable ABI: tags and payload fields are only ever added. New fields go at
+// the end of a payload: fields missing from a shorter payload read as 0 and
+// extra trailing bytes are ignored, so hosts and engines of different ages
+// interoperate. Records with unknown tags are skipped. Text is addressed from
+// the end of its payload. MCORE_COMMAND_STREAM_VERSION changes only if this
+// framing does.
+// ---------------------------------------------------------------------------
+#define MCORE_COMMAND_STREAM_VERSION 1
+
+typedef struct {
+  unsigned short tag;    // mcore_cmd_tag_t
+  unsigned short flags;  // reserved, 0
+  unsigned int size;     // payload bytes, excluding padding
+} mcore_cmd_header_t;
+
+typedef enum {
+  MCORE_CMD_FILL_RECT = 1,       // mcore_cmd_fill_t
+  MCORE_CMD_STROKE_RECT = 2,     // mcore_cmd_stroke_t
+  MCORE_CMD_SHADOW = 3,          // mcore_cmd_shadow_t
+  MCORE_CMD_TEXT = 4,            // mcore_cmd_text_t, then text_len bytes of UTF-8
+  MCORE_CMD_PUSH_CLIP = 5,       // mcore_cmd_clip_t
+  MCORE_CMD_POP_CLIP = 6,        // no payload
+  MCORE_CMD_GRADIENT = 7,        // mcore_cmd_gradient_t
+  MCORE_CMD_IMAGE = 8,           // mcore_cmd_image_t
+  MCORE_CMD_PUSH_TRANSFORM = 9,  // mcore_cmd_transform_t
+  MCORE_CMD_POP_TRANSFORM = 10,  // no payload
+  MCORE_CMD_VIDEO = 11,          // mcore_cmd_video_t
+} mcore_cmd_tag_t;
+
+// Rounded-rect fill
+typedef struct {
+  float x, y, width, height, radius;
+  float color[4];
+} mcore_cmd_fill_t;
+
+// Rounded-rect outline, line_width centered on the edge
+typedef struct {
+  float x, y, width, height, radius;
+  float line_width;
+  float color[4];
+} mcore_cmd_stroke_t;
+
+// Blurred rounded rect (drop shadow)
+typedef struct {
+  float x, y, width, height, radius;
+  float blur;
+  float color[4];
+} mcore_cmd_shadow_t;
+
+typedef struct {
+  float x, y;
+  float font_size;
+  float wrap_width;       // 0 = no wrapping
+  int font_id;
+  float color[4];
+  unsigned int align;     // mcore_text_align_t
+  unsigned int direction; // mcore_text_direction_t
+  unsigned int text_len;  // UTF-8 bytes at the end of the payload
+} mcore_cmd_text_t;
+
+// Clip to a rounded rect until the matching MCORE_CMD_POP_CLIP
+typedef struct {
+  float x, y, width, height, radius;
+} mcore_cmd_clip_t;
+
+// Rounded rect painted with a registered gradient; stroked if line_width > 0
+typedef struct {
+  float x, y, width, height, radius;
+  int gradient_id;
+  float line_width;
+} mcore_cmd_gradient_t;
+
+// Registered image stretched over a rect. With a tile size, one copy of the
+// image is drawn at that size from (x + tile_x, y + tile_y) and the rest of
+// the rect is filled by the extend modes (pattern backgrounds).
+typedef struct {
+  float x, y, width, height;
+  int image_id;
+  unsigned int sampling;  // MCORE_IMAGE_SAMPLING_*
+  unsigned int extend_x;  // MCORE_IMAGE_EXTEND_*
+  unsigned int extend_y;
+  float tile_x, tile_y;            // relative to x, y
+  float tile_width, tile_height;   // 0: the rect's size
+} mcore_cmd_image_t;
+
+// Image filtering: bilinear (default), hard pixel edges for pixel art, or
+// bilinear from a pre-shrunk copy near the drawn size, so large photos drawn
+// small don't shimmer (the copies are built on first use and kept with the
+// image, up to a third more memory)
+#define MCORE_IMAGE_SAMPLING_LINEAR 0
+#define MCORE_IMAGE_SAMPLING_NEAREST 1
+#define MCORE_IMAGE_SAMPLING_MIPMAP 2
+// What fills the rect past the tile: its edge pixels (default), copies, or
+// mirrored copies
+#define MCORE_IMAGE_EXTEND_CLAMP 0
+#define MCORE_IMAGE_EXTEND_REPEAT 1
+#define MCORE_IMAGE_EXTEND_REFLECT 2
+
+// Applies to the following commands (not hit regions) until the matching
+// MCORE_CMD_POP_TRANSFORM: (x, y) -> (a*x + c*y + e, b*x + d*y + f)
+typedef struct {
+  float matrix[6];  // a, b, c, d, e, f; translation in logical px
+} mcore_cmd_transform_t;
+
+// A video's latest frame (mcore_video_frame_submit) fitted to a rect
+typedef struct {
+  float x, y, width, height;
+  int video_id;
+  unsigned int fit;  // MCORE_VIDEO_FIT_*
+} mcore_cmd_video_t;
+
+typedef enum {
+  MCORE_OK = 0,
+  MCORE_ERR = 1,
+  MCORE_ERR_NOT_IN_FRAME = 2,        // drawing or present outside begin_frame/end_frame_present
+  MCORE_ERR_FRAME_IN_PROGRESS = 3,   // begin_frame before the previous frame was presented
+  MCORE_FRAME_SKIPPED = 4,           // not an error: damage tracking found nothing to redraw
+} mcore_status_t;
 
 // Text input events
 typedef enum {
@@ -109,6 +383,17 @@ typedef enum {
   TEXT_EVENT_MOVE_CURSOR = 3,
   TEXT_EVENT_SET_CURSOR = 4,
   TEXT_EVENT_INSERT_TEXT = 5,
+  TEXT_EVENT_BACKSPACE_WORD = 6,  // Option+Backspace
+  TEXT_EVENT_DELETE_WORD = 7,     // Option+Delete
+  TEXT_EVENT_SELECT_ALL = 8,      // Cmd+A
+  TEXT_EVENT_CUT = 9,             // Cmd+X, via the system clipboard
+  TEXT_EVENT_COPY = 10,           // Cmd+C
+  TEXT_EVENT_PASTE = 11,          // Cmd+V
+  TEXT_EVENT_UNDO = 12,           // Cmd+Z; typing/deleting runs undo as one step
+  TEXT_EVENT_REDO = 13,           // Cmd+Shift+Z
+  TEXT_EVENT_IME_SET_COMPOSITION = 14,  // text_ptr = preedit, cursor_position = IME caret within it
+  TEXT_EVENT_IME_COMMIT = 15,     // text_ptr = final text
+  TEXT_EVENT_IME_CANCEL = 16,
 } mcore_text_event_kind_t;
 
 typedef enum {
@@ -116,54 +401,428 @@ typedef enum {
   CURSOR_RIGHT = 1,
   CURSOR_HOME = 2,
   CURSOR_END = 3,
+  CURSOR_WORD_LEFT = 4,   // Option+Left
+  CURSOR_WORD_RIGHT = 5,  // Option+Right
+  // LEFT/RIGHT and the WORD_ variants move visually: in a right-to-left
+  // paragraph CURSOR_LEFT moves toward the end of the text. These move
+  // logically whatever the direction. HOME/END are always the text's
+  // start/end.
+  CURSOR_BACKWARD = 6,
+  CURSOR_FORWARD = 7,
+  CURSOR_WORD_BACKWARD = 8,
+  CURSOR_WORD_FORWARD = 9,
 } mcore_cursor_direction_t;
 
 typedef struct {
   mcore_text_event_kind_t kind;
   unsigned int char_code;  // For INSERT_CHAR
   mcore_cursor_direction_t direction;  // For MOVE_CURSOR
-  unsigned char extend_selection;  // Shift key held
+  unsigned char extend_selection;  // Shift held: MOVE_CURSOR/SET_CURSOR extend the selection
   int cursor_position;  // For SET_CURSOR
   const char* text_ptr;  // For INSERT_TEXT
 } mcore_text_event_t;
 
 // Lifecycle
+// The engine's MCORE_API_VERSION, to compare before creating anything
+unsigned int mcore_api_version(void);
 mcore_context_t* mcore_create(const mcore_surface_desc_t* desc);
+// GPUs mcore_create can use: fills up to capacity entries, returns the total
+// (pass capacity 0 to count). Select entry i with desc.adapter = i + 1.
+unsigned int mcore_enumerate_adapters(mcore_adapter_info_t* out, unsigned int capacity);
+// The adapter a context renders with and its device limits
+mcore_status_t mcore_gpu_info(mcore_context_t* ctx, mcore_gpu_info_t* out);
+// Whether the context renders on a GPU or fell back to the CPU
+mcore_render_mode_t mcore_render_mode(mcore_context_t* ctx);
+// No window: frames render into an offscreen texture (physical px), read back
+// with mcore_read_pixels. For CI screenshot tests and thumbnail rendering.
+mcore_context_t* mcore_create_headless(unsigned int api_version, unsigned int width_px, unsigned int height_px, float scale_factor, unsigned int flags);
 void             mcore_destroy(mcore_context_t* ctx);
+// Shared engine: one GPU device, Vello pipelines, fonts and images for all the
+// contexts created from it (one per window). Font and image IDs are valid in
+// every context of the engine. The desc's power_preference, adapter and flags
+// are ignored by mcore_engine_create_context, which returns null if the
+// engine's GPU can't present to the window. Contexts may outlive the engine.
+mcore_engine_t*  mcore_engine_create(const mcore_engine_desc_t* desc);
+mcore_context_t* mcore_engine_create_context(mcore_engine_t* engine, const mcore_surface_desc_t* desc);
+void             mcore_engine_destroy(mcore_engine_t* engine);
+// Last presented frame of a headless context as RGBA8, top row first;
+// len >= width_px * height_px * 4
+mcore_status_t mcore_read_pixels(mcore_context_t* ctx, unsigned char* out, size_t len);
+
+// Screenshots: the last presented frame as RGBA8, top row first; len >=
+// width_px * height_px * 4. Windowed contexts capture the rendered scene
+// before particles and dithering are composited.
+mcore_status_t mcore_capture_frame(mcore_context_t* ctx, unsigned char* out, size_t len);
+// The same, written to a PNG file (needs MCORE_CAP_IMAGE_DECODE)
+mcore_status_t mcore_capture_frame_png(mcore_context_t* ctx, const char* path);
 
 // Resize/DPI
+// Every drawing and measuring call takes logical px; the engine maps them to
+// the surface with the scale factor. Resizing with a new scale_factor (the
+// window moved to another display) keeps the host's coordinates valid: text
+// is reshaped at the new scale, except layout handles created before it.
 void mcore_resize(mcore_context_t* ctx, const mcore_surface_desc_t* desc);
+// Physical px per logical px
+float mcore_get_scale(mcore_context_t* ctx);
+// Switch scale factors (the window moved between displays) keeping the
+// surface's logical size: its physical size scales with the ratio and text
+// is reshaped. Update the layer's contentsScale too. Returns
+// MCORE_ERR_FRAME_IN_PROGRESS inside a frame.
+mcore_status_t mcore_set_scale_factor(mcore_context_t* ctx, float scale);
 
 // Resources
+// Returns a font ID (0, 1, 2, ...) usable as font_id; -1 or unknown IDs use system-ui.
+// Registering the same bytes again returns the existing ID.
 int mcore_font_register(mcore_context_t* ctx, const mcore_font_blob_t* blob);
+// Font ID for an installed family ("SF Pro", "Helvetica Neue"), no blob needed.
+// weight (100-900, 0 = regular) / italic pick the face used when text requests
+// leave them unset. Returns -1 if the family isn't installed. Loading the same
+// family/weight/italic again returns the existing ID.
+int mcore_font_load_system(mcore_context_t* ctx, const char* name, float weight, unsigned char italic);
+// Installed family names (sorted), e.g. for font pickers. family_name copies a
+// null-terminated name and returns its length, or -1 if index is out of range.
+int mcore_font_system_family_count(mcore_context_t* ctx);
+int mcore_font_system_family_name(mcore_context_t* ctx, int index, char* buf, int buf_len);
 
 // Frame
-void mcore_begin_frame(mcore_context_t* ctx, double time_seconds);
+// Drawing calls (rects, text, clips, command buffers, images, fragments,
+// text inputs, log views) only work between mcore_begin_frame and
+// mcore_end_frame_present, or while recording a fragment. Outside a frame
+// they draw nothing and set mcore_last_error (those returning a status
+// return MCORE_ERR_NOT_IN_FRAME). A second begin_frame without a present
+// discards the unpresented frame and returns MCORE_ERR_FRAME_IN_PROGRESS;
+// presenting without begin_frame returns MCORE_ERR_NOT_IN_FRAME.
+mcore_status_t mcore_begin_frame(mcore_context_t* ctx, double time_seconds);
 void mcore_rect_rounded(mcore_context_t* ctx, const mcore_rounded_rect_t* rect);
 void mcore_text_layout(mcore_context_t* ctx, const mcore_text_req_t* req, mcore_text_metrics_t* out);
-void mcore_measure_text(mcore_context_t* ctx, const char* text, float font_size, float max_width, mcore_text_size_t* out);
+void mcore_measure_text(mcore_context_t* ctx, const char* text, float font_size, float max_width, int font_id, mcore_text_size_t* out);
+// Same result, but a single line is measured by summing cached per-word
+// advances rather than building a layout; for speculative measuring during
+// layout. Text with line breaks, or wider than max_width, takes the full path.
+void mcore_measure_text_fast(mcore_context_t* ctx, const char* text, float font_size, float max_width, int font_id, mcore_text_size_t* out);
 void mcore_text_draw(mcore_context_t* ctx, const mcore_text_req_t* req, float x, float y, mcore_rgba_t color);
+void mcore_text_draw_spans(mcore_context_t* ctx, const mcore_text_req_t* req, const mcore_text_span_t* spans, size_t span_count, float x, float y, mcore_rgba_t default_color);
 void mcore_render_commands(mcore_context_t* ctx, const mcore_draw_command_t* commands, int count);
+// Draw a v2 command stream of `len` bytes; pass MCORE_COMMAND_STREAM_VERSION.
+// A malformed stream draws nothing and returns MCORE_ERR (see mcore_last_error).
+mcore_status_t mcore_render_commands_v2(mcore_context_t* ctx, const void* data, size_t len, unsigned int version);
+// `clear` fills the surface behind the scene exactly as given (no
+// animation); it doesn't depend on the frame time.
 mcore_status_t mcore_end_frame_present(mcore_context_t* ctx, mcore_rgba_t clear);
+// A fixed background for every following frame, used in place of the `clear`
+// passed to end_frame_present and submit_frame. Components must be finite.
+mcore_status_t mcore_set_clear_color(mcore_context_t* ctx, mcore_rgba_t color);
+
+// Threading
+// Every call locks the context, so calls from different threads serialize,
+// but one frame (begin_frame, drawing, present) must come from one thread.
+// By default that's the host's thread, which blocks while presenting. With
+// the render thread running, hosts queue whole frames as v2 command streams
+// from any thread instead; the engine-owned thread builds, renders and
+// presents them, and begin_frame / drawing / present from other threads
+// return MCORE_ERR. Callbacks fired while rendering (redraw, scroll edges,
+// log) come from the render thread; its render failures go to the log callback.
+mcore_status_t mcore_render_thread_start(mcore_context_t* ctx);
+// Presents the last queued frame, then joins the thread (mcore_destroy stops it too)
+mcore_status_t mcore_render_thread_stop(mcore_context_t* ctx);
+// Copies the stream; a frame still waiting when the next arrives is replaced.
+// A malformed stream is rejected here with MCORE_ERR.
+mcore_status_t mcore_submit_frame(mcore_context_t* ctx, double time_seconds, const void* data, size_t len, unsigned int version, mcore_rgba_t clear);
+// Blocks until every frame queued so far is presented
+mcore_status_t mcore_render_thread_flush(mcore_context_t* ctx);
+
+// Text layout handles
+// Shape once, then measure / hit test / draw without re-shaping.
+// Handles capture the scale factor at creation; recreate them after DPI changes.
+typedef struct mcore_text_layout mcore_text_layout_t;
+
+mcore_text_layout_t* mcore_text_layout_create(mcore_context_t* ctx, const mcore_text_req_t* req);
+// Rich text: span colors are baked in, so layout_draw ignores its color for these
+mcore_text_layout_t* mcore_text_layout_create_styled(mcore_context_t* ctx, const mcore_text_req_t* req, const mcore_text_span_t* spans, size_t span_count, mcore_rgba_t default_color);
+void mcore_text_layout_destroy(mcore_text_layout_t* layout);
+void mcore_text_layout_metrics(const mcore_text_layout_t* layout, mcore_text_metrics_t* out);
+// (x, y) in logical pixels relative to the layout origin; returns a byte offset
+int mcore_text_layout_hit_test(const mcore_text_layout_t* layout, float x, float y);
+void mcore_text_layout_caret(const mcore_text_layout_t* layout, int byte_offset, mcore_caret_rect_t* out);
+// Writes up to max_rects selection rects for start..end; returns the total count
+int mcore_text_layout_selection_rects(const mcore_text_layout_t* layout, int start, int end, mcore_rect_t* out, int max_rects);
+void mcore_text_layout_draw(mcore_context_t* ctx, const mcore_text_layout_t* layout, float x, float y, mcore_rgba_t color);
+
+// Text blocks (chat messages, rendered markdown)
+// Headings, paragraphs and list items shaped and stacked in one handle, so a
+// message is measured and drawn with one call apiece. Like layout handles,
+// blocks capture the scale factor at creation.
+typedef enum {
+  MCORE_TEXT_BLOCK_PARAGRAPH = 0,
+  MCORE_TEXT_BLOCK_HEADING = 1,   // level 1-6
+  MCORE_TEXT_BLOCK_BULLET = 2,    // level = nesting depth
+  MCORE_TEXT_BLOCK_NUMBERED = 3,  // level = nesting depth, number shown
+} mcore_text_block_kind_t;
+
+#define MCORE_TEXT_RUN_BOLD      (1 << 0)
+#define MCORE_TEXT_RUN_ITALIC    (1 << 1)
+#define MCORE_TEXT_RUN_UNDERLINE (1 << 2)
+#define MCORE_TEXT_RUN_CODE      (1 << 3)  // code font and color over the code background
+#define MCORE_TEXT_RUN_COLOR     (1 << 4)  // use the run's color
+
+// Styling for a byte range of a paragraph
+typedef struct {
+  unsigned int start;
+  unsigned int end;
+  unsigned int flags;   // MCORE_TEXT_RUN_*
+  mcore_rgba_t color;   // with MCORE_TEXT_RUN_COLOR
+} mcore_text_run_t;
+
+typedef struct {
+  const char* utf8;
+  unsigned int kind;    // mcore_text_block_kind_t
+  unsigned int level;   // heading level 1-6, or list nesting depth (0 = top)
+  unsigned int number;  // numbered list items
+  unsigned int run_count;
+  const mcore_text_run_t* runs;  // may be null when run_count is 0
+} mcore_text_block_paragraph_t;
+
+// Logical px
+typedef struct {
+  float font_size_px;
+  int font_id;
+  int code_font_id;             // -1 = font_id
+  float line_height_multiplier; // 0 = font default
+  float paragraph_spacing;      // consecutive list items sit closer
+  float list_indent;            // per nesting level, markers included
+  mcore_rgba_t color;
+  mcore_rgba_t code_color;
+  mcore_rgba_t code_background;
+} mcore_text_block_style_t;
+
+typedef struct mcore_text_block mcore_text_block_t;
+
+// Wraps at width (logical px, 0 = no wrapping); headings scale font_size_px and are bold
+mcore_text_block_t* mcore_text_block_create(mcore_context_t* ctx, const mcore_text_block_paragraph_t* paragraphs, size_t paragraph_count, const mcore_text_block_style_t* style, float width);
+void mcore_text_block_destroy(mcore_text_block_t* block);
+// Widest line and total height
+void mcore_text_block_size(const mcore_text_block_t* block, mcore_text_size_t* out);
+// Paragraph bounds relative to the block origin; list items start after their marker
+mcore_status_t mcore_text_block_paragraph_rect(const mcore_text_block_t* block, unsigned int index, mcore_rect_t* out);
+// Nearest paragraph (written to paragraph, may be null) and byte offset in it; -1 if empty
+int mcore_text_block_hit_test(const mcore_text_block_t* block, float x, float y, int* paragraph);
+void mcore_text_block_draw(mcore_context_t* ctx, const mcore_text_block_t* block, float x, float y);
+
+// Code views
+// Monospace code with syntax colors, drawn straight from a buffer: lines have
+// a fixed height and don't wrap, so only the lines in the viewport are
+// shaped, however large the buffer. Ligatures are off, one column per character.
+typedef struct {
+  const unsigned char* text;  // UTF-8, not null-terminated; may be null when len is 0
+  size_t len;
+  float font_size_px;
+  int font_id;
+  float line_height_multiplier;  // multiple of font size, 0 = font default
+  unsigned int tab_size;         // columns between tab stops, 0 = 8
+  unsigned int line_numbers;     // 1 = draw line numbers in line_number_color
+  float scroll_y;                // how far the viewport is scrolled down
+  float viewport_height;
+  mcore_rgba_t color;            // text outside every token
+  mcore_rgba_t line_number_color;
+} mcore_code_req_t;
+
+// Syntax color for a byte range of the buffer
+typedef struct {
+  unsigned int start;
+  unsigned int end;
+  mcore_rgba_t color;
+} mcore_code_token_t;
+
+typedef struct {
+  unsigned int line_count;
+  float line_height;
+  float gutter_width;    // where the code starts; 0 without line numbers
+  float content_height;  // line_count * line_height
+} mcore_code_metrics_t;
+
+void mcore_code_measure(mcore_context_t* ctx, const mcore_code_req_t* req, mcore_code_metrics_t* out);
+// (x, y) is the viewport's top left. Tokens must be sorted by start and not
+// overlap. Lines aren't clipped to the viewport's width; push a clip rect.
+void mcore_code_draw(mcore_context_t* ctx, const mcore_code_req_t* req, const mcore_code_token_t* tokens, size_t token_count, float x, float y);
+
+// Text windows (log viewers, large documents)
+// The document's hard lines are indexed once; only the range of lines set
+// with set_lines is shaped, and lines already shaped for the previous range
+// are reused. Line indices are hard lines, stable as the window moves.
+typedef struct mcore_text_window mcore_text_window_t;
+
+// Copies req->utf8; lines wrap at req->wrap_width in req's style (max_lines ignored)
+mcore_text_window_t* mcore_text_window_create(const mcore_text_req_t* req);
+void mcore_text_window_destroy(mcore_text_window_t* window);
+unsigned int mcore_text_window_line_count(const mcore_text_window_t* window);
+// Line containing a byte offset, for turning byte ranges into lines
+unsigned int mcore_text_window_line_at(const mcore_text_window_t* window, size_t byte_offset);
+// Byte range of a line without its line break
+mcore_status_t mcore_text_window_line_range(const mcore_text_window_t* window, unsigned int line, size_t* out_start, size_t* out_end);
+// Shape lines first..first+count (clamped); writes each line's height to
+// heights (room for count, may be null) and returns the total height
+float mcore_text_window_set_lines(mcore_context_t* ctx, mcore_text_window_t* window, unsigned int first, unsigned int count, float* heights);
+// (x, y) relative to the top of the shaped lines; returns a document byte offset, -1 if none are shaped
+int mcore_text_window_hit_test(const mcore_text_window_t* window, float x, float y);
+// Draws the shaped lines from (x, y) down
+void mcore_text_window_draw(mcore_context_t* ctx, const mcore_text_window_t* window, float x, float y, mcore_rgba_t color);
 
 // Text input
 unsigned char mcore_text_input_event(mcore_context_t* ctx, unsigned long long id, const mcore_text_event_t* event);
+
+// Caret-follow scrolling: give an input a viewport (logical px; wrap_width 0
+// for a single line, width or height 0 removes it) and use event_ex, which
+// scrolls the viewport to keep the caret visible after each event and returns
+// the offset to draw the content at (text origin = viewport origin - scroll).
+typedef struct {
+    unsigned char changed;   // Same as mcore_text_input_event's return value
+    unsigned char scrolled;  // The scroll offset moved
+    unsigned char _padding[2];
+    float scroll_x;
+    float scroll_y;
+} mcore_text_event_result_t;
+void mcore_text_input_set_viewport(mcore_context_t* ctx, unsigned long long id, float width, float height, float font_size, float wrap_width);
+unsigned char mcore_text_input_event_ex(mcore_context_t* ctx, unsigned long long id, const mcore_text_event_t* event, mcore_text_event_result_t* out);
+// The current offset (0, 0 without a viewport)
+void mcore_text_input_get_scroll(mcore_context_t* ctx, unsigned long long id, float* out_x, float* out_y);
+// Single-line shorthand: sets a one-line viewport `width` wide (the field
+// minus its padding) and returns the horizontal offset that keeps the caret
+// in view, like NSTextField (text x = content x - offset)
+float mcore_text_input_scroll_x(mcore_context_t* ctx, unsigned long long id, float width, float font_size);
+
+// Draw a text input from its engine state in one call: content (masked if
+// secure), selection, underlined IME preedit and a caret that blinks on frame
+// time (redraws are requested for each blink; solid with reduce motion).
+// Sets the viewport from the box minus padding and records the origin for
+// the _root queries. Logical px; drawn immediately, clipped to the box.
+typedef struct {
+    float x, y, width, height;  // Field box
+    float padding_x, padding_y;
+    float font_size;
+    float wrap_width;           // 0: one line, centered vertically
+    mcore_rgba_t text_color;
+    mcore_rgba_t selection_color;
+    mcore_rgba_t caret_color;
+    unsigned char focused;      // Draw the selection and caret
+    unsigned char _padding[3];
+} mcore_text_input_style_t;
+void mcore_text_input_draw(mcore_context_t* ctx, unsigned long long id, const mcore_text_input_style_t* style);
 int mcore_text_input_get(mcore_context_t* ctx, unsigned long long id, char* buf, int buf_len);
 int mcore_text_input_cursor(mcore_context_t* ctx, unsigned long long id);
 void mcore_text_input_set(mcore_context_t* ctx, unsigned long long id, const char* text);
+// Undo steps kept per input (default 100, 0 disables). mcore_text_input_set clears history.
+void mcore_text_input_set_undo_limit(mcore_context_t* ctx, unsigned long long id, unsigned int limit);
+// Password field: drawn and hit tested as bullets, edited on the real content;
+// cut/copy do nothing. reveal_ms > 0 shows each typed character that long
+// (a redraw is requested to mask it). Safe to call every frame.
+void mcore_text_input_set_secure(mcore_context_t* ctx, unsigned long long id, unsigned char secure, unsigned int reveal_ms);
 
-// Text selection
+// Constraints on typed, pasted and IME-committed text (mcore_text_input_set is
+// exempt). Limits are 0 for unlimited. Disallowed characters and whatever
+// doesn't fit (by whole graphemes) are dropped, and the event returns 0 if
+// nothing was inserted.
+typedef enum {
+    MCORE_CHARS_ANY = 0,
+    MCORE_CHARS_NUMERIC = 1,   // 0-9
+    MCORE_CHARS_DECIMAL = 2,   // 0-9, one '.', a leading '-'
+    MCORE_CHARS_HEX = 3,       // 0-9, a-f, A-F
+} mcore_char_class_t;
+void mcore_text_input_set_constraints(mcore_context_t* ctx, unsigned long long id, unsigned int max_bytes, unsigned int max_graphemes, unsigned char char_class);
+
+typedef enum {
+    MCORE_REJECTED_NONE = 0,
+    MCORE_REJECTED_LENGTH = 1,
+    MCORE_REJECTED_CHARACTER = 2,
+} mcore_text_rejection_t;
+// Latest rejection since the previous call, then resets
+unsigned char mcore_text_input_take_rejection(mcore_context_t* ctx, unsigned long long id);
+
+// Input context hints, stored with the input's state. Hosts read them when an
+// input gains focus to configure NSTextInputContext / UIKit keyboards.
+typedef enum {
+    MCORE_KEYBOARD_DEFAULT = 0,
+    MCORE_KEYBOARD_EMAIL = 1,
+    MCORE_KEYBOARD_NUMBER = 2,
+    MCORE_KEYBOARD_DECIMAL = 3,
+    MCORE_KEYBOARD_URL = 4,
+} mcore_keyboard_type_t;
+typedef enum {
+    MCORE_AUTOCORRECT_DEFAULT = 0,
+    MCORE_AUTOCORRECT_ON = 1,
+    MCORE_AUTOCORRECT_OFF = 2,
+} mcore_autocorrect_t;
+void mcore_text_input_set_hints(mcore_context_t* ctx, unsigned long long id, unsigned char keyboard, unsigned char autocorrect);
+// Paragraph direction (mcore_text_direction_t, default auto): decides which
+// way the arrow keys move and how mcore_text_input_draw aligns the content
+void mcore_text_input_set_direction(mcore_context_t* ctx, unsigned long long id, unsigned char direction);
+// 1 if the content currently lays out right to left
+unsigned char mcore_text_input_is_rtl(mcore_context_t* ctx, unsigned long long id);
+
+typedef struct {
+    unsigned char keyboard;     // mcore_keyboard_type_t
+    unsigned char autocorrect;  // mcore_autocorrect_t
+    unsigned char secure;       // Password field: enable secure event input
+    unsigned char ime_enabled;  // 0 for secure, non-default keyboard and character-constrained inputs
+} mcore_text_input_hints_t;
+// Unknown IDs report the defaults
+mcore_status_t mcore_text_input_get_hints(mcore_context_t* ctx, unsigned long long id, mcore_text_input_hints_t* out);
+
+// Text selection (byte offsets). get_selection returns 0 when nothing is selected;
+// set_cursor_pos with extend_selection (Shift+click, drag) extends from the anchor
+// set by start_selection, or from the caret.
 unsigned char mcore_text_input_get_selection(mcore_context_t* ctx, unsigned long long id, int* out_start, int* out_end);
 void mcore_text_input_set_cursor_pos(mcore_context_t* ctx, unsigned long long id, int byte_offset, unsigned char extend_selection);
 int mcore_text_input_get_selected_text(mcore_context_t* ctx, unsigned long long id, char* buf, int buf_len);
 void mcore_text_input_start_selection(mcore_context_t* ctx, unsigned long long id, int byte_offset);
 
+typedef enum {
+    MCORE_CURSOR_ARROW = 0,
+    MCORE_CURSOR_IBEAM = 1,
+    MCORE_CURSOR_POINTING_HAND = 2,
+    MCORE_CURSOR_CROSSHAIR = 3,
+    MCORE_CURSOR_OPEN_HAND = 4,
+    MCORE_CURSOR_CLOSED_HAND = 5,
+    MCORE_CURSOR_NOT_ALLOWED = 6,
+    MCORE_CURSOR_RESIZE_LEFT_RIGHT = 7,
+    MCORE_CURSOR_RESIZE_UP_DOWN = 8,
+    MCORE_CURSOR_RESIZE_NWSE = 9,   // diagonal, top-left to bottom-right
+    MCORE_CURSOR_RESIZE_NESW = 10,  // diagonal, top-right to bottom-left
+} mcore_cursor_t;
+
+// Mouse pointer shape (NSCursor on macOS; main thread only). AppKit resets it
+// when the pointer crosses cursor rects, so set it again on mouse moves. Other
+// platforms just record it for get_cursor. `cursor` is an mcore_cursor_t
+// value; unknown ones return MCORE_ERR with MCORE_ERROR_INVALID_ARG.
+mcore_status_t mcore_set_cursor(unsigned char cursor);
+mcore_cursor_t mcore_get_cursor(void);
+
+// System clipboard (NSPasteboard on macOS, in-process elsewhere), also used by
+// the CUT/COPY/PASTE text events. get_text copies a null-terminated string
+// (truncated to fit) and returns its full length, or -1 if there is no text.
+mcore_status_t mcore_clipboard_set_text(const char* text);
+int mcore_clipboard_get_text(char* buf, int buf_len);
+
 // Text measurement at cursor
 float mcore_measure_text_to_byte_offset(mcore_context_t* ctx, const char* text, float font_size, int byte_offset);
+// Caret for a byte offset in (possibly wrapped) text shaped from req; handles
+// ligatures, kerning and RTL. Offsets inside a cluster snap to its start.
+void mcore_text_caret(mcore_context_t* ctx, const mcore_text_req_t* req, int byte_offset, mcore_caret_rect_t* out);
+// (x, y) in logical pixels relative to the text origin; returns the byte offset
+// under the point in text wrapped at req->wrap_width (clamps to first/last line)
+int mcore_text_hit_test(mcore_context_t* ctx, const mcore_text_req_t* req, float x, float y);
+// Highlight rects covering bytes start..end across wrapped lines (logical, relative
+// to the text origin). Writes up to max_rects and returns the total count.
+int mcore_text_selection_rects(mcore_context_t* ctx, const mcore_text_req_t* req, int start, int end, mcore_rect_t* out, int max_rects);
 
 // Text measurement statistics (for instrumentation)
 void mcore_get_text_stats(mcore_context_t* ctx, mcore_text_stats_t* out);
-void mcore_reset_text_stats(mcore_context_t* ctx);
+void mcore_reset_text_stats(mcore_context_t* ctx);  // also zeroes the cache counters
+
+// Per-context budget for the shaped-layout cache, for memory-constrained hosts.
+// max_entries 0 = default (256); max_bytes 0 = no byte limit. Glyphs are cached
+// by the renderer and aren't covered.
+mcore_status_t mcore_text_cache_set_budget(mcore_context_t* ctx, unsigned int max_entries, unsigned long long max_bytes);
+mcore_status_t mcore_text_cache_get_stats(mcore_context_t* ctx, mcore_text_cache_stats_t* out);
 
 // IME (Input Method Editor) support
 typedef struct {
@@ -183,27 +842,717 @@ void mcore_ime_clear_preedit(mcore_context_t* ctx, unsigned long long id);
 // Get IME preedit text if any
 unsigned char mcore_ime_get_preedit(mcore_context_t* ctx, unsigned long long id, char* buf, int buf_len, int* out_cursor_offset);
 
+// Text to draw for an input: content with the preedit spliced in where it will
+// land, masked for secure inputs. Draw [preedit_start, preedit_end) underlined (equal when not composing)
+// and the caret at out_caret. Returns bytes written.
+int mcore_text_input_get_display(mcore_context_t* ctx, unsigned long long id, char* buf, int buf_len,
+                                 int* out_caret, int* out_preedit_start, int* out_preedit_end);
+
+// Selection within the display text (offsets differ from content offsets in
+// secure inputs); returns 0 when nothing is selected.
+unsigned char mcore_text_input_get_display_selection(mcore_context_t* ctx, unsigned long long id, int* out_start, int* out_end);
+
+// Content byte offset under logical x (relative to the text origin) of what the
+// input draws, for set_cursor_pos/start_selection on clicks and drags.
+int mcore_text_input_hit_test(mcore_context_t* ctx, unsigned long long id, float font_size, float x);
+
+// Caret of a single-line input (default font at font_size) relative to its text
+// origin, in logical pixels; follows the IME caret.
+mcore_status_t mcore_text_input_caret_rect(mcore_context_t* ctx, unsigned long long id, float font_size, mcore_caret_rect_t* out);
+
+// Transformed text inputs: text queries above return geometry local to the text
+// origin. Call set_origin while drawing, with the origin in the current hit
+// transform's local space (see mcore_hit_push_transform); the engine records
+// that transform, and the _root variants work in window logical px, so inputs
+// in a zoomed or panned viewport keep working. Use caret_rect_root to place the
+// IME candidate window.
+void mcore_text_input_set_origin(mcore_context_t* ctx, unsigned long long id, float x, float y);
+// Fails if the input has no origin yet
+mcore_status_t mcore_text_input_caret_rect_root(mcore_context_t* ctx, unsigned long long id, float font_size, mcore_caret_rect_t* out);
+// Content byte offset under a window point; -1 if the input has no origin yet
+int mcore_text_input_hit_test_root(mcore_context_t* ctx, unsigned long long id, float font_size, float x, float y);
+
+// Post-processing
+// Enable (1) or disable (0) noise dithering in the final blit to hide gradient banding
+void mcore_set_dither(mcore_context_t* ctx, unsigned char enabled);
+// Antialiasing: area (default, fastest) or MSAA for exact coverage at shared
+// edges. MSAA pipelines are built on first use; set_aa_mode returns MCORE_ERR
+// and keeps the current mode if the device can't run them.
+typedef enum {
+  MCORE_AA_AREA = 0,
+  MCORE_AA_MSAA8 = 1,
+  MCORE_AA_MSAA16 = 2,
+} mcore_aa_mode_t;
+mcore_status_t mcore_set_aa_mode(mcore_context_t* ctx, unsigned char mode);
+unsigned char mcore_get_aa_mode(mcore_context_t* ctx);
+// Text rendering: how glyphs are fit to the pixel grid. The default (no
+// hinting, unquantized positions) keeps exact shapes for high-DPI screens;
+// hinting with whole-pixel positions makes small text crisper on low-DPI ones.
+typedef struct {
+  unsigned int hinting;             // 1 = hint outlines, snap baselines to whole pixels
+  unsigned int subpixel_positions;  // glyph x positions per pixel: 0 = unquantized, 1 = whole pixels, up to 16
+} mcore_text_rendering_t;
+mcore_status_t mcore_set_text_rendering(mcore_context_t* ctx, const mcore_text_rendering_t* rendering);
+mcore_status_t mcore_get_text_rendering(mcore_context_t* ctx, mcore_text_rendering_t* out);
+// Damage tracking (off by default): when a frame's command buffers, size,
+// scale and clear color match the last presented frame, end_frame_present
+// skips rendering and returns MCORE_FRAME_SKIPPED. Frames using direct
+// drawing calls (text, clips, images, filters, ...) are always presented.
+void mcore_set_damage_tracking(mcore_context_t* ctx, unsigned char enabled);
+
+// Replay recording (for pixel-diff testing with `cargo xtask replay-diff`)
+// Captures every frame's mcore_render_commands buffers and clear color.
+mcore_status_t mcore_replay_record_start(mcore_context_t* ctx, const char* path);
+// Writes the file; returns the frame count, or -1 (see mcore_last_error)
+int mcore_replay_record_stop(mcore_context_t* ctx);
+// Scene dumps: the current frame's command buffers (or the last presented
+// frame's, between frames) as a one-frame recording, for bug reports.
+// `mcore-replay` renders one headlessly; mcore_scene_load draws one into the
+// current frame.
+mcore_status_t mcore_scene_dump(mcore_context_t* ctx, const char* path);
+mcore_status_t mcore_scene_load(mcore_context_t* ctx, const char* path);
+
+// Crash bundles: on an unrecoverable error (GPU device lost, 3 failed frames in
+// a row, a panic) write dir/mcore-crash-<time>/{report.txt,trace.txt} with the
+// reason, GPU config, frame stats and recent FFI calls (see mcore_trace_enable),
+// then call callback(reason, bundle_dir). A null dir turns it off.
+typedef void (*mcore_crash_callback_t)(const char* reason, const char* bundle_dir);
+mcore_status_t mcore_set_crash_dump(mcore_context_t* ctx, const char* dir, mcore_crash_callback_t callback);
+
+// Engine diagnostics. Without a callback they go to stderr. Runs without the
+// engine lock held; message is only valid during the call.
+enum { MCORE_LOG_DEBUG = 0, MCORE_LOG_INFO = 1, MCORE_LOG_WARN = 2, MCORE_LOG_ERROR = 3 };
+typedef void (*mcore_log_callback_t)(unsigned char level, const char* message);
+void mcore_set_log_callback(mcore_context_t* ctx, mcore_log_callback_t callback);
+
+// Development watchdog: warn (via the log callback) about every frame whose
+// build + render exceeds budget_ms, with a breakdown by phase (build, command
+// encoding, custom textures, render). 0 turns it off (the default).
+void mcore_set_frame_budget(mcore_context_t* ctx, float budget_ms);
+
+// Debug overlay drawn by the engine over every frame; 0 turns it off
+#define MCORE_DEBUG_OVERLAY_FPS_GRAPH     (1 << 0)  // frame interval graph and average fps
+#define MCORE_DEBUG_OVERLAY_COMMANDS      (1 << 1)  // draw commands this frame
+#define MCORE_DEBUG_OVERLAY_CLIP_DEPTH    (1 << 2)  // deepest clip nesting, unbalanced clips
+#define MCORE_DEBUG_OVERLAY_TEXT_CACHE    (1 << 3)  // text layout cache hit rate
+#define MCORE_DEBUG_OVERLAY_CLIP_OUTLINES (1 << 4)  // outline every clip rect
+void mcore_set_debug_overlay(mcore_context_t* ctx, unsigned int flags);
+
+// Frame statistics, for FPS/profiler overlays and regression checks
+typedef struct {
+  unsigned long long frame;     // frames presented so far
+  float build_ms;               // CPU: begin_frame to end_frame_present
+  float encode_ms;              // part of build_ms spent encoding command buffers
+  float custom_ms;              // CPU: custom widget textures
+  float render_ms;              // CPU: render submission and present
+  float gpu_ms;                 // GPU time of a recent frame, -1 if unsupported
+  unsigned int commands;
+  unsigned int encode_calls;
+  unsigned int paths;           // Vello scene elements
+  unsigned int path_segments;
+  unsigned int clips;
+  unsigned int draw_objects;
+  unsigned int image_count;
+  unsigned long long image_bytes;    // decoded pixels of registered images
+                                     // and their atlas pages
+  unsigned long long texture_bytes;  // render targets and custom widget textures
+  unsigned int layer_warnings;  // clip pops with nothing pushed, plus clips left
+                                // open at present (the engine fixes both)
+} mcore_frame_stats_t;
+
+// Stats for the last rendered frame; scene counts cover the scene being
+// built, so call between end_frame_present and the next begin_frame
+mcore_status_t mcore_frame_stats(mcore_context_t* ctx, mcore_frame_stats_t* out);
+
 // Clipping
 void mcore_push_clip_rect(mcore_context_t* ctx, float x, float y, float width, float height);
+void mcore_push_clip_rounded_rect(mcore_context_t* ctx, float x, float y, float width, float height, float radius);
+// A pop with no clip pushed is ignored (MCORE_ERROR_INVALID_STATE); clips
+// still open at end_frame_present are closed. Both count in layer_warnings.
 void mcore_pop_clip(mcore_context_t* ctx);
 
+// Backdrop blur (frosted-glass panels)
+// Blurs what's been drawn so far inside the rounded rect; what's drawn until
+// the matching mcore_pop_clip is clipped to it. blur_radius is the Gaussian's
+// standard deviation in logical px. Not allowed inside a clip or a fragment
+// recording (MCORE_ERROR_INVALID_STATE). Each layer adds a render pass over
+// the whole frame, so keep them few.
+mcore_status_t mcore_push_blur_layer(mcore_context_t* ctx, float x, float y, float width, float height,
+                                     float corner_radius, float blur_radius);
+
+// Color filters
+// Everything drawn while a filter is pushed (shapes, text, images, fragments,
+// videos, custom textures) is transformed by the filter as one layer.
+// `matrix` is a 4x5 row-major color matrix (20 floats, like SVG feColorMatrix)
+// applied to non-premultiplied colors. Pushes and pops draw nothing outside a
+// frame and aren't allowed while recording a fragment
+// (MCORE_ERROR_INVALID_STATE). Like blur layers, each push and pop adds a
+// render pass over the whole frame, so keep them few.
+void mcore_push_color_filter(mcore_context_t* ctx, const float* matrix);
+// Desaturate content: amount 0.0 = unchanged, 1.0 = fully grayscale
+void mcore_push_grayscale_filter(mcore_context_t* ctx, float amount);
+void mcore_pop_color_filter(mcore_context_t* ctx);
+
+// Paths (for clipping to arbitrary shapes)
+#define MCORE_PATH_VERB_MOVE_TO  0  // 1 point
+#define MCORE_PATH_VERB_LINE_TO  1  // 1 point
+#define MCORE_PATH_VERB_QUAD_TO  2  // 2 points (control, end)
+#define MCORE_PATH_VERB_CUBIC_TO 3  // 3 points (control1, control2, end)
+#define MCORE_PATH_VERB_CLOSE    4  // 0 points
+
+// Register a path. `points` holds point_count (x, y) pairs in logical px.
+// Returns a path ID (>= 0) or -1 on error
+int mcore_path_create(mcore_context_t* ctx, const unsigned char* verbs, int verb_count, const float* points, int point_count);
+void mcore_path_destroy(mcore_context_t* ctx, int path_id);
+
+// Gradient brushes, laid out relative to the bounds of the shape they paint
+// (see MCORE_DRAW_CMD_GRADIENT_RING).
+typedef enum {
+    MCORE_GRADIENT_LINEAR = 0,  // angle_deg: direction, 0 = left to right, 90 = top to bottom
+    MCORE_GRADIENT_SWEEP = 1,   // angle_deg: where offset 0 starts, 0 = 3 o'clock, clockwise
+} mcore_gradient_kind_t;
+
+typedef struct {
+    float offset;  // 0..1
+    mcore_rgba_t color;
+} mcore_gradient_stop_t;
+
+typedef struct {
+    unsigned char kind;  // mcore_gradient_kind_t
+    unsigned char _padding[3];
+    float angle_deg;
+    const mcore_gradient_stop_t* stops;  // At least 2
+    unsigned int stop_count;
+} mcore_gradient_desc_t;
+
+// Returns a gradient ID (>= 0) or -1 on error
+int mcore_gradient_create(mcore_context_t* ctx, const mcore_gradient_desc_t* desc);
+void mcore_gradient_destroy(mcore_context_t* ctx, int gradient_id);
+
+// Append-only log views (chat transcripts, build output)
+// Each entry is shaped once when appended; drawing only touches the entries
+// in the visible window, so cost doesn't grow with the length of the log.
+// Returns a view ID (>= 0) or -1 on error. max_entries = 0 keeps everything,
+// otherwise the oldest entries are dropped.
+int mcore_log_view_create(mcore_context_t* ctx, float font_size, int font_id, float wrap_width, unsigned int max_entries);
+void mcore_log_view_destroy(mcore_context_t* ctx, int view_id);
+// One entry may span several lines
+mcore_status_t mcore_log_view_append(mcore_context_t* ctx, int view_id, const char* utf8, mcore_rgba_t color);
+void mcore_log_view_clear(mcore_context_t* ctx, int view_id);
+// Reshapes every entry: call on resize, not every frame
+void mcore_log_view_set_width(mcore_context_t* ctx, int view_id, float wrap_width);
+// Tab stops for every entry, as in mcore_text_req_t; reshapes every entry
+void mcore_log_view_set_tabs(mcore_context_t* ctx, int view_id, float tab_width, const float* tab_stops, unsigned int tab_stop_count);
+// Height of all kept entries (logical px), e.g. to scroll to the bottom
+float mcore_log_view_content_height(mcore_context_t* ctx, int view_id);
+// Draw the part of the log visible in the (x, y, width, height) window, with
+// the content scrolled up by scroll_y, clipped to the window
+mcore_status_t mcore_log_view_draw(mcore_context_t* ctx, int view_id, float x, float y, float width, float height, float scroll_y);
+
+// Push a clip layer shaped like a registered path, translated by (x, y)
+// Returns MCORE_ERR and pushes nothing if the path ID is unknown
+mcore_status_t mcore_push_clip_path(mcore_context_t* ctx, int path_id, float x, float y);
+
+// Retained scene fragments
+// Record drawing once, replay it each frame without re-encoding.
+// Everything drawn between begin and end is captured instead of drawn.
+// End a recording before mcore_begin_frame or mcore_end_frame_present; both
+// return MCORE_ERR (MCORE_ERROR_INVALID_STATE) while one is open.
+mcore_status_t mcore_fragment_begin(mcore_context_t* ctx);
+// Returns the fragment ID (>= 0), or -1 if no recording was in progress
+int mcore_fragment_end(mcore_context_t* ctx);
+void mcore_fragment_destroy(mcore_context_t* ctx, int fragment_id);
+// Rasterize (0, 0, width, height) of a fragment (logical px, at the current
+// scale) once into a new image, so static content redraws as one image blit.
+// Returns an image ID (free with mcore_image_release) or -1 on error.
+int mcore_fragment_to_image(mcore_context_t* ctx, int fragment_id, float width, float height);
+
 // Diagnostics
+// Failing calls record an error code and message for their thread. Every call
+// checks its pointers: a null context, struct or non-empty array returns the
+// call's failure value (MCORE_ERR, -1, NULL, 0) and records
+// MCORE_ERROR_INVALID_ARG; the *_destroy calls accept NULL as a no-op. A
+// panic inside any call is caught before it reaches the host: the call
+// returns its failure value and records MCORE_ERROR_PANIC.
+typedef enum {
+  MCORE_ERROR_NONE = 0,
+  MCORE_ERROR_OTHER = 1,          // see the message
+  MCORE_ERROR_INVALID_ARG = 2,    // null pointer, out-of-range value, malformed buffer
+  MCORE_ERROR_NOT_FOUND = 3,      // no image/path/gradient/... with that ID
+  MCORE_ERROR_INVALID_STATE = 4,  // e.g. drawing outside a frame
+  MCORE_ERROR_WGPU = 5,           // adapter/device creation, readback, device lost
+  MCORE_ERROR_VELLO = 6,
+  MCORE_ERROR_SURFACE_LOST = 7,   // surface lost or outdated: resize or recreate it
+  MCORE_ERROR_OUT_OF_MEMORY = 8,
+  MCORE_ERROR_UTF8 = 9,           // a string argument wasn't UTF-8 (treated as empty)
+  MCORE_ERROR_IO = 10,
+  MCORE_ERROR_UNSUPPORTED = 11,   // format, platform or feature not in this build
+  MCORE_ERROR_PANIC = 12,         // an engine bug: the call panicked and returned its failure value
+  MCORE_ERROR_VERSION_MISMATCH = 13,  // host built against an incompatible MCORE_API_VERSION
+} mcore_error_code_t;
+
+// Valid until the next mcore_last_error call on the same thread
 const char* mcore_last_error(void);
+mcore_error_code_t mcore_last_error_code(void);
+// Copies the message into buf (null-terminated, truncated to len) and returns
+// its full length, 0 if there's no error; pass buf = NULL to size it
+size_t mcore_last_error_copy(char* buf, size_t len);
+void mcore_clear_error(void);
+// Static description of a code; never null, never freed
+const char* mcore_error_message(mcore_error_code_t code);
+// FFI call tracing: keep the last `capacity` calls with their arguments (0 = off;
+// MCORE_TRACE=<capacity> enables it at startup). While tracing, calls on a
+// destroyed context print the history and abort. dump copies the history
+// (truncated to fit) and returns its full length, or -1 if tracing is off.
+void mcore_trace_enable(unsigned int capacity);
+int mcore_trace_dump(char* buf, int buf_len);
+
+// Optional subsystems compiled into this build (cargo features). Entry points
+// exist in every build; without the subsystem they fail or do nothing.
+typedef enum {
+  MCORE_CAP_IMAGE_DECODE = 1 << 0,   // mcore_image_load_file, mcore_capture_frame_png (image-decode feature)
+  MCORE_CAP_ACCESSIBILITY = 1 << 1,  // mcore_a11y_* (a11y feature)
+  MCORE_CAP_LAYOUT = 1 << 2,         // mcore_layout_* (layout feature)
+  MCORE_CAP_WEBP_DECODE = 1 << 3,    // WebP in the image loaders (webp feature)
+  MCORE_CAP_AVIF_DECODE = 1 << 4,    // AVIF in the image loaders (avif feature)
+} mcore_capability_t;
+
+unsigned int mcore_get_capabilities(void);
 
 // ============================================================================
-// Accessibility (AccessKit)
+// Gesture Recognition
 // ============================================================================
 
+typedef enum {
+    MCORE_POINTER_DOWN = 0,
+    MCORE_POINTER_MOVE = 1,
+    MCORE_POINTER_UP = 2,
+    MCORE_POINTER_CANCEL = 3,
+} mcore_pointer_phase_t;
+
+typedef enum {
+    MCORE_GESTURE_TAP = 0,
+    MCORE_GESTURE_DOUBLE_TAP = 1,
+    MCORE_GESTURE_LONG_PRESS = 2,
+    MCORE_GESTURE_PAN_BEGIN = 3,
+    MCORE_GESTURE_PAN_UPDATE = 4,
+    MCORE_GESTURE_PAN_END = 5,
+} mcore_gesture_kind_t;
+
 typedef struct {
-    float x;
-    float y;
+    unsigned char kind;   // mcore_gesture_kind_t
+    float x, y;           // Current pointer position (logical px)
+    float dx, dy;         // Translation since press (pans only)
+    float velocity_x;     // Logical px/s (pans only)
+    float velocity_y;
+} mcore_gesture_t;
+
+// Feed raw pointer events (logical px, seconds). Long-presses also fire from mcore_begin_frame.
+void mcore_gesture_pointer_event(mcore_context_t* ctx, mcore_pointer_phase_t phase, float x, float y, double time_seconds);
+
+// Pop the next recognized gesture. Returns 1 if one was pending, 0 otherwise
+unsigned char mcore_gesture_next(mcore_context_t* ctx, mcore_gesture_t* out);
+
+// ============================================================================
+// Hit Regions & Pointer Routing
+// ============================================================================
+
+// Register a hit region for the current frame (logical px, in the current hit transform).
+// Regions are cleared by mcore_begin_frame and respect the active clip.
+// Higher z wins; equal z resolves to the region registered last.
+void mcore_hit_region(mcore_context_t* ctx, unsigned long long id, float x, float y, float width, float height, int z);
+
+// Translation + uniform scale for subsequently registered regions
+void mcore_hit_push_transform(mcore_context_t* ctx, float dx, float dy, float scale);
+void mcore_hit_pop_transform(mcore_context_t* ctx);
+
+// The composed transform: root = local * scale + (dx, dy). Maps local text
+// query results (carets, selection rects) to window coordinates.
+typedef struct {
+    float dx;
+    float dy;
+    float scale;
+} mcore_hit_transform_t;
+void mcore_hit_current_transform(mcore_context_t* ctx, mcore_hit_transform_t* out);
+
+typedef struct {
+    unsigned long long id;
+    int z;
+    float local_x;  // the point relative to the region's top-left, in its local space
+    float local_y;
+} mcore_hit_result_t;
+
+// Topmost region under (x, y) in window logical px, respecting the clip and
+// transform each region was registered with. Between frames this queries the
+// last frame's regions. Returns 1 and fills out on a hit, 0 otherwise.
+unsigned char mcore_hit_test(mcore_context_t* ctx, float x, float y, mcore_hit_result_t* out);
+
+typedef enum {
+    MCORE_ROUTED_DOWN = 0,
+    MCORE_ROUTED_MOVE = 1,
+    MCORE_ROUTED_UP = 2,
+    MCORE_ROUTED_CANCEL = 3,
+    MCORE_ROUTED_ENTER = 4,
+    MCORE_ROUTED_LEAVE = 5,
+} mcore_routed_phase_t;
+
+// Callback signature: void callback(region_id, phase, local_x, local_y)
+// The pressed region captures moves and the release even outside its bounds.
+void mcore_pointer_set_callback(mcore_context_t* ctx, void (*callback)(unsigned long long, unsigned char, float, float));
+
+// Route a pointer event (window logical px). Callbacks run after the engine lock is released.
+void mcore_pointer_event(mcore_context_t* ctx, mcore_pointer_phase_t phase, float x, float y);
+
+// ============================================================================
+// Scroll Physics
+// ============================================================================
+// Momentum, rubber-band overscroll and edge callbacks for scroll containers.
+// All values are logical px; momentum advances in mcore_begin_frame.
+
+typedef enum {
+    MCORE_SCROLL_EDGE_TOP = 0,
+    MCORE_SCROLL_EDGE_BOTTOM = 1,
+    MCORE_SCROLL_EDGE_LEFT = 2,
+    MCORE_SCROLL_EDGE_RIGHT = 3,
+} mcore_scroll_edge_t;
+
+typedef struct {
+    float offset_x, offset_y;      // May be outside the content while overscrolled
+    float velocity_x, velocity_y;  // px/s
+    unsigned char animating;       // Keep producing frames while 1
+} mcore_scroll_state_t;
+
+void mcore_scroll_set_extent(mcore_context_t* ctx, unsigned long long id, float viewport_w, float viewport_h, float content_w, float content_h);
+// Mouse wheel: moves immediately, clamped, cancels momentum
+void mcore_scroll_by(mcore_context_t* ctx, unsigned long long id, float dx, float dy);
+
+typedef enum {
+    MCORE_SCROLL_PHASE_WHEEL = 0,      // discrete wheel step, same as mcore_scroll_by
+    MCORE_SCROLL_PHASE_BEGAN = 1,      // fingers down on the trackpad
+    MCORE_SCROLL_PHASE_CHANGED = 2,
+    MCORE_SCROLL_PHASE_ENDED = 3,      // fingers lifted: the engine flings
+    MCORE_SCROLL_PHASE_CANCELLED = 4,
+} mcore_scroll_phase_t;
+
+// Scroll event with the content delta (positive scrolls toward the end) and
+// the event time on the begin_frame clock. Trackpad gestures rubber-band past
+// the edges and get the engine's momentum on release, so drop the OS's
+// momentum-phase events. Apply mcore_scroll_get's offset as a transform.
+void mcore_scroll_event(mcore_context_t* ctx, unsigned long long id, float dx, float dy, mcore_scroll_phase_t phase, double time_seconds);
+
+typedef enum {
+    MCORE_SCROLL_AXIS_X = 0,
+    MCORE_SCROLL_AXIS_Y = 1,
+} mcore_scroll_axis_t;
+
+// Snap points: flings are retargeted to come to rest on the nearest one.
+// interval 0 / count 0 clears snapping for that axis.
+void mcore_scroll_set_snap_interval(mcore_context_t* ctx, unsigned long long id, mcore_scroll_axis_t axis, float interval);
+void mcore_scroll_set_snap_offsets(mcore_context_t* ctx, unsigned long long id, mcore_scroll_axis_t axis, const float* offsets, size_t count);
+
+// Drags take the translation since the press (pan gesture dx/dy) and end with the pointer velocity
+void mcore_scroll_drag_begin(mcore_context_t* ctx, unsigned long long id);
+void mcore_scroll_drag_update(mcore_context_t* ctx, unsigned long long id, float translation_x, float translation_y);
+void mcore_scroll_drag_end(mcore_context_t* ctx, unsigned long long id, float velocity_x, float velocity_y);
+// Returns 1 if the container exists
+unsigned char mcore_scroll_get(mcore_context_t* ctx, unsigned long long id, mcore_scroll_state_t* out);
+void mcore_scroll_remove(mcore_context_t* ctx, unsigned long long id);
+
+// Callback signature: void callback(container_id, edge, velocity)
+// Fires once each time content starts overscrolling past an edge; runs without the engine lock held.
+void mcore_scroll_set_edge_callback(mcore_context_t* ctx, void (*callback)(unsigned long long, unsigned char, float));
+
+// ============================================================================
+// Layout (needs MCORE_CAP_LAYOUT)
+// ============================================================================
+// Flexbox layout of a node tree the host mirrors with its own IDs. Set each
+// node's style and children, compute from a root, then read back rects
+// relative to that root. Leaves aren't measured: give text leaves the size
+// from mcore_text_measure.
+
+typedef enum {
+    MCORE_LAYOUT_DIRECTION_ROW = 0,
+    MCORE_LAYOUT_DIRECTION_COLUMN = 1,
+    MCORE_LAYOUT_DIRECTION_ROW_REVERSE = 2,
+    MCORE_LAYOUT_DIRECTION_COLUMN_REVERSE = 3,
+} mcore_layout_direction_t;
+
+// Main-axis distribution (justify-content)
+typedef enum {
+    MCORE_LAYOUT_JUSTIFY_START = 0,
+    MCORE_LAYOUT_JUSTIFY_END = 1,
+    MCORE_LAYOUT_JUSTIFY_CENTER = 2,
+    MCORE_LAYOUT_JUSTIFY_SPACE_BETWEEN = 3,
+    MCORE_LAYOUT_JUSTIFY_SPACE_AROUND = 4,
+    MCORE_LAYOUT_JUSTIFY_SPACE_EVENLY = 5,
+} mcore_layout_justify_t;
+
+// Cross-axis alignment (align-items, align-self)
+typedef enum {
+    MCORE_LAYOUT_ALIGN_AUTO = 0,      // stretch for align_items; the parent's align_items for align_self
+    MCORE_LAYOUT_ALIGN_STRETCH = 1,
+    MCORE_LAYOUT_ALIGN_START = 2,
+    MCORE_LAYOUT_ALIGN_END = 3,
+    MCORE_LAYOUT_ALIGN_CENTER = 4,
+    MCORE_LAYOUT_ALIGN_BASELINE = 5,
+} mcore_layout_align_t;
+
+// Sizes below are auto when negative
+#define MCORE_LAYOUT_AUTO (-1.0f)
+
+typedef struct {
+    float top;
+    float right;
+    float bottom;
+    float left;
+} mcore_edges_t;
+
+// Logical px. Start from mcore_layout_style_default(): a zeroed style has
+// zero width and height.
+typedef struct {
+    unsigned char direction;    // mcore_layout_direction_t
+    unsigned char wrap;         // 1 wraps items onto more lines
+    unsigned char justify;      // mcore_layout_justify_t
+    unsigned char align_items;  // mcore_layout_align_t
+    unsigned char align_self;   // mcore_layout_align_t
+    unsigned char hidden;       // 1 takes the node out of the layout (empty rect)
     float width;
     float height;
-} mcore_rect_t;
+    float min_width;
+    float min_height;
+    float max_width;
+    float max_height;
+    float grow;
+    float shrink;
+    float basis;
+    mcore_edges_t padding;
+    mcore_edges_t margin;
+    float row_gap;
+    float column_gap;
+} mcore_layout_style_t;
+
+// CSS defaults: auto sizes, row direction, no grow, shrink 1
+mcore_layout_style_t mcore_layout_style_default(void);
+
+// Create node `id` or replace its style
+mcore_status_t mcore_layout_node_set(mcore_context_t* ctx, unsigned long long id, const mcore_layout_style_t* style);
+// Replace a node's children, in order; a child with another parent moves. Cycles fail.
+mcore_status_t mcore_layout_set_children(mcore_context_t* ctx, unsigned long long id, const unsigned long long* children, size_t count);
+// Remove a node; its children stay, without a parent
+void mcore_layout_node_remove(mcore_context_t* ctx, unsigned long long id);
+// Lay out the tree under root_id to fill width x height
+mcore_status_t mcore_layout_compute(mcore_context_t* ctx, unsigned long long root_id, float width, float height);
+// A node's rect from the last compute of its root, relative to the root.
+// Returns 1 if it has one.
+unsigned char mcore_layout_get(mcore_context_t* ctx, unsigned long long id, mcore_rect_t* out);
+
+// ============================================================================
+// Immediate-Mode UI
+// ============================================================================
+// Widgets for small tools without a host toolkit. Inside a frame, call
+// mcore_ui_begin, then widgets top to bottom, then mcore_ui_end; each widget
+// lays itself out below the last, handles this frame's pointer, draws, and
+// returns MCORE_UI_* flags. IDs name widgets across frames (a text field's ID
+// is also its mcore_text_input_* ID).
+
+#define MCORE_UI_HOVERED (1u << 0)
+#define MCORE_UI_PRESSED (1u << 1)  // pressed on it and still held
+#define MCORE_UI_CLICKED (1u << 2)  // pressed and released on it this frame
+#define MCORE_UI_FOCUSED (1u << 3)
+
+typedef struct {
+    float font_size;
+    float padding;          // inside buttons and text fields
+    float spacing;          // between widgets
+    float corner_radius;
+    mcore_rgba_t text;
+    mcore_rgba_t button;
+    mcore_rgba_t button_hover;
+    mcore_rgba_t button_pressed;
+    mcore_rgba_t field;
+    mcore_rgba_t accent;    // focus ring, caret and (tinted) selection
+} mcore_ui_theme_t;
+
+typedef struct {
+    float pointer_x;
+    float pointer_y;
+    unsigned char pointer_down;  // 1 while the primary button is held
+    unsigned char _padding[3];
+} mcore_ui_input_t;
+
+mcore_ui_theme_t mcore_ui_theme_default(void);
+void mcore_ui_set_theme(mcore_context_t* ctx, const mcore_ui_theme_t* theme);
+
+// Start a column `width` wide at x, y (logical px) with this frame's pointer
+mcore_status_t mcore_ui_begin(mcore_context_t* ctx, float x, float y, float width, const mcore_ui_input_t* input);
+// Wrapped text
+void mcore_ui_label(mcore_context_t* ctx, const char* text);
+// Button sized to its label; check MCORE_UI_CLICKED
+unsigned int mcore_ui_button(mcore_context_t* ctx, unsigned long long id, const char* label);
+// Single-line field (width 0 fills the column). Route key events for
+// mcore_ui_focused() to mcore_text_input_event_ex; read it with mcore_text_input_get.
+unsigned int mcore_ui_text_input(mcore_context_t* ctx, unsigned long long id, float width);
+// Returns the height the column's widgets took
+float mcore_ui_end(mcore_context_t* ctx);
+// The focused widget's ID, 0 if none
+unsigned long long mcore_ui_focused(mcore_context_t* ctx);
+
+// ============================================================================
+// Redraw Scheduling
+// ============================================================================
+// Engine animations (scroll momentum, long-press timers) schedule frames
+// themselves; hosts only need to draw when told instead of every vsync.
+// Times are on the mcore_begin_frame clock; a time at or before the last
+// frame means "next vsync".
+
+// Callback signature: void callback(time_seconds). Fires only when the earliest
+// deadline moves earlier; runs without the engine lock held.
+void mcore_set_redraw_callback(mcore_context_t* ctx, void (*callback)(double));
+void mcore_request_frame_at(mcore_context_t* ctx, double time_seconds);
+void mcore_request_redraw(mcore_context_t* ctx);
+// Earliest pending frame time, or -1 when idle (for polling hosts)
+double mcore_next_frame_time(mcore_context_t* ctx);
+
+// Display refresh rate (Hz). Report it at startup and whenever it changes
+// (window moved to another monitor, ProMotion 120 <-> 60); defaults to 60.
+void mcore_set_refresh_rate(mcore_context_t* ctx, float hz);
+float mcore_get_refresh_rate(mcore_context_t* ctx);
+// Callback signature: void callback(hz). Fires on change, without the engine lock held.
+void mcore_set_refresh_rate_callback(mcore_context_t* ctx, void (*callback)(float));
+
+// Window fully occluded or minimized (1) / visible again (0). While occluded,
+// end_frame_present skips rendering but frames still update state; becoming
+// visible requests a frame.
+void mcore_set_occluded(mcore_context_t* ctx, unsigned char occluded);
+unsigned char mcore_is_occluded(mcore_context_t* ctx);
+
+// ============================================================================
+// Custom Texture Widgets
+// ============================================================================
+// Render your own content (3D views, map tiles) into an engine-owned texture
+// that is composited like an image.
+
+typedef struct {
+    void* native_texture;   // id<MTLTexture> on macOS; RGBA8 unorm, premultiplied alpha
+    unsigned int width;     // physical px
+    unsigned int height;
+    double time_seconds;    // frame time from mcore_begin_frame
+} mcore_texture_target_t;
+
+// Runs inside mcore_end_frame_present with the engine lock held: render into the
+// texture, commit (waitUntilScheduled) and return without calling into the engine.
+typedef void (*mcore_texture_callback_t)(void* user_data, const mcore_texture_target_t* target);
+
+// Returns a widget ID (>= 0) or -1 on error
+int mcore_custom_texture_create(mcore_context_t* ctx, unsigned int width_px, unsigned int height_px, mcore_texture_callback_t callback, void* user_data);
+mcore_status_t mcore_custom_texture_resize(mcore_context_t* ctx, int id, unsigned int width_px, unsigned int height_px);
+void mcore_custom_texture_destroy(mcore_context_t* ctx, int id);
+// Draw the texture stretched into a logical-pixel rect
+void mcore_custom_texture_draw(mcore_context_t* ctx, int id, float x, float y, float width, float height);
+
+// ============================================================================
+// Video
+// ============================================================================
+// Composite a host player's frames (AVPlayerItemVideoOutput, a capture
+// session) inside the scene. Each submitted frame is copied and converted to
+// RGB on the GPU at the next rendered frame; only the latest is kept.
+
+#define MCORE_VIDEO_FORMAT_BGRA 0  // kCVPixelFormatType_32BGRA
+#define MCORE_VIDEO_FORMAT_NV12 1  // '420v' / '420f': luma, then interleaved CbCr
+#define MCORE_VIDEO_MATRIX_BT709 0 // HD (default)
+#define MCORE_VIDEO_MATRIX_BT601 1 // SD
+// How a frame fills the drawn rect: letterboxed (default), cropped to cover
+// it, or stretched
+#define MCORE_VIDEO_FIT_CONTAIN 0
+#define MCORE_VIDEO_FIT_COVER 1
+#define MCORE_VIDEO_FIT_STRETCH 2
+
+typedef struct {
+  unsigned int format;      // MCORE_VIDEO_FORMAT_*; ignored for IOSurfaces
+  unsigned int width;
+  unsigned int height;
+  unsigned int matrix;      // MCORE_VIDEO_MATRIX_*, for NV12
+  unsigned int full_range;  // NV12: 0-255 rather than 16-235; ignored for IOSurfaces
+  unsigned int _reserved;
+  const unsigned char* planes[2];  // each stride * rows bytes
+  unsigned int strides[2];         // bytes per row
+  void* iosurface;  // IOSurfaceRef (CVPixelBufferGetIOSurface); used instead of
+                    // planes when set. BGRA, 420v and 420f are supported.
+} mcore_video_frame_t;
+
+// Returns a video ID (>= 0) or -1 on error. Draws nothing until a frame arrives.
+int mcore_video_create(mcore_context_t* ctx);
+// Copies the frame; the host's buffer can be reused once this returns
+mcore_status_t mcore_video_frame_submit(mcore_context_t* ctx, int video_id, const mcore_video_frame_t* frame);
+// Draw the latest frame into a logical-pixel rect, like an MCORE_CMD_VIDEO record
+void mcore_video_draw(mcore_context_t* ctx, int video_id, float x, float y, float width, float height, unsigned int fit);
+void mcore_video_destroy(mcore_context_t* ctx, int video_id);
+
+// ============================================================================
+// Particles
+// ============================================================================
+// GPU-simulated sprites drawn beneath or above the vector scene (confetti,
+// ambient effects). Emitters request frames while they have live particles.
+
+typedef enum {
+    MCORE_PARTICLE_LAYER_BENEATH = 0,  // visible through transparent scene areas
+    MCORE_PARTICLE_LAYER_ABOVE = 1,
+} mcore_particle_layer_t;
+
+typedef struct {
+    float x, y;                 // emitter origin (logical px)
+    unsigned int count;         // 1..65536
+    float lifetime;             // seconds per particle
+    float speed_min, speed_max; // logical px/s
+    float direction;            // radians, 0 = right, PI/2 = down
+    float spread;               // radians around direction
+    float gravity;              // logical px/s^2, positive = down
+    float size;                 // logical px
+    mcore_rgba_t colors[4];     // color ramp over each particle's life
+    unsigned char looping;      // 0 = one burst, 1 = continuous
+    mcore_particle_layer_t layer;
+} mcore_particle_emitter_t;
+
+// Returns an emitter ID (>= 0) or -1 on error. Bursts clean up after themselves.
+// With reduce motion on, bursts are dropped and looping emitters are held
+// (not drawn, no frames requested) until it's off.
+int mcore_particles_emit(mcore_context_t* ctx, const mcore_particle_emitter_t* desc);
+void mcore_particles_stop(mcore_context_t* ctx, int id);
+
+// ============================================================================
+// Accessibility (AccessKit)
+// ============================================================================
+
+// Node roles (mcore_a11y_node_t.role); anything else is reported as unknown
+enum {
+  MCORE_A11Y_ROLE_WINDOW = 0,
+  MCORE_A11Y_ROLE_BUTTON = 1,
+  MCORE_A11Y_ROLE_TEXT_INPUT = 2,
+  MCORE_A11Y_ROLE_LABEL = 3,
+  MCORE_A11Y_ROLE_GROUP = 4,
+  MCORE_A11Y_ROLE_IMAGE = 5,
+  MCORE_A11Y_ROLE_CHECKBOX = 6,
+  MCORE_A11Y_ROLE_SWITCH = 7,
+  MCORE_A11Y_ROLE_SLIDER = 8,
+  MCORE_A11Y_ROLE_LINK = 9,
+  MCORE_A11Y_ROLE_HEADING = 10,
+  MCORE_A11Y_ROLE_LIST = 11,
+  MCORE_A11Y_ROLE_LIST_ITEM = 12,
+  MCORE_A11Y_ROLE_SCROLL_VIEW = 13,
+};
+
+// Node states (mcore_a11y_node_t.states)
+#define MCORE_A11Y_STATE_DISABLED  (1 << 0)
+#define MCORE_A11Y_STATE_CHECKED   (1 << 1)  // checkboxes and switches; unchecked without it
+#define MCORE_A11Y_STATE_MIXED     (1 << 2)  // partly checked
+#define MCORE_A11Y_STATE_SELECTED  (1 << 3)
+#define MCORE_A11Y_STATE_EXPANDED  (1 << 4)
+#define MCORE_A11Y_STATE_COLLAPSED (1 << 5)  // expandable but closed
+#define MCORE_A11Y_STATE_HIDDEN    (1 << 6)
 
 typedef struct {
     unsigned long long id;
-    unsigned char role;  // Maps to AccessKit Role enum
+    unsigned char role;  // MCORE_A11Y_ROLE_*
     const char* label;
     mcore_rect_t bounds;
     unsigned int actions;  // Bitfield of supported actions
@@ -212,6 +1561,7 @@ typedef struct {
     const char* value;
     int text_selection_start;
     int text_selection_end;
+    unsigned int states;  // MCORE_A11Y_STATE_* flags
 } mcore_a11y_node_t;
 
 // Initialize accessibility for a given NSView
@@ -226,11 +1576,31 @@ void mcore_a11y_update(
     unsigned long long focus_id
 );
 
+// Move accessibility focus to a node of the last tree without resending it
+void mcore_a11y_set_focus(mcore_context_t* ctx, unsigned long long focus_id);
+
 // Set callback for accessibility actions
 // Callback signature: void callback(unsigned long long widget_id, unsigned char action_code)
 // Action codes: 0 = Focus, 1 = Click
 void mcore_a11y_set_action_callback(void (*callback)(unsigned long long, unsigned char));
 
+// System accessibility preferences (queried from NSWorkspace on macOS)
+typedef struct {
+    unsigned char reduce_motion;
+    unsigned char increase_contrast;
+    unsigned char reduce_transparency;
+} mcore_a11y_prefs_t;
+
+// Re-query the OS preferences (call when the system reports a change)
+void mcore_a11y_get_preferences(mcore_context_t* ctx, mcore_a11y_prefs_t* out);
+
+// Override the preferences (testing, or platforms the engine can't query)
+void mcore_a11y_set_preferences(mcore_context_t* ctx, const mcore_a11y_prefs_t* prefs);
+
+// Multiplier for animation motion: 0.0 when reduce-motion is on, else 1.0
+// Engine-driven animations apply this automatically; hosts can use it for their own tweens
+float mcore_animation_scale(mcore_context_t* ctx);
+
 // ============================================================================
 // Color Support
 // ============================================================================
@@ -261,6 +1631,30 @@ unsigned char mcore_color_parse(const unsigned char* css_str, size_t len, mcore_
 // t should be in range [0.0, 1.0]
 void mcore_color_lerp(const mcore_color_t* a, const mcore_color_t* b, float t, mcore_color_t* out);
 
+// Color spaces for mcore_color_convert and windows
+typedef enum {
+    MCORE_COLOR_SPACE_SRGB = 0,
+    MCORE_COLOR_SPACE_DISPLAY_P3 = 1,
+} mcore_color_space_t;
+
+// Convert a color specified in `space` into the values a window in `target`
+// expects (see mcore_get_color_space). Colors already in `target` pass
+// through unchanged; Display P3 colors outside the sRGB gamut are clamped.
+// Spaces are mcore_color_space_t values; unknown ones return MCORE_ERR with
+// MCORE_ERROR_INVALID_ARG.
+// Example: mcore_color_convert(MCORE_COLOR_SPACE_DISPLAY_P3, mcore_get_color_space(ctx), &brand_p3, &out)
+mcore_status_t mcore_color_convert(unsigned char space, unsigned char target, const mcore_color_t* in, mcore_color_t* out);
+
+// Windows are tagged sRGB, so colors look the same on every display. In
+// Display P3, colors and images are read as P3 values, reaching past sRGB on
+// wide-gamut displays. Headless contexts return MCORE_ERR
+// (MCORE_ERROR_UNSUPPORTED) and stay in sRGB. `space` is an
+// mcore_color_space_t value; unknown ones are MCORE_ERROR_INVALID_ARG.
+mcore_status_t mcore_set_color_space(mcore_context_t* ctx, unsigned char space);
+mcore_color_space_t mcore_get_color_space(mcore_context_t* ctx);
+// 1 if the main display can show Display P3 colors
+unsigned char mcore_display_supports_p3(void);
+
 // Convert from RGBA8 (0-255) to mcore_color_t (0.0-1.0)
 void mcore_color_from_rgba8(unsigned char r, unsigned char g, unsigned char b, unsigned char a, mcore_color_t* out);
 
@@ -272,8 +1666,10 @@ void mcore_color_from_rgba8(unsigned char r, unsigned char g, unsigned char b, u
 #define MCORE_IMAGE_FORMAT_RGB8  0
 #define MCORE_IMAGE_FORMAT_RGBA8 1
 
-// Alpha type constants
-#define MCORE_IMAGE_ALPHA_OPAQUE 0  // No alpha channel
+// Alpha type constants. Images are converted to straight alpha on register, so
+// pass whatever the source produced (CGImage/Core Graphics data is usually
+// premultiplied, PNG decoders straight); mislabeling shows as dark fringes.
+#define MCORE_IMAGE_ALPHA_OPAQUE 0  // No alpha channel (RGBA alpha is ignored)
 #define MCORE_IMAGE_ALPHA_PREMUL 1  // Premultiplied alpha
 #define MCORE_IMAGE_ALPHA_ALPHA  2  // Straight alpha
 
@@ -305,26 +1701,90 @@ typedef struct {
 // Register an image and copy pixel data to Rust
 // Returns an image ID (>= 0) or -1 on error
 // The `data` pointer can be freed after this function returns
+// Images up to 128px a side are also packed into shared atlas pages, so a
+// frame drawing hundreds of icons uploads a few textures instead of one each
 int mcore_image_register(mcore_context_t* ctx, const mcore_image_desc_t* desc);
 
 // Increment reference count (call when widget stores image ID)
 void mcore_image_retain(mcore_context_t* ctx, int image_id);
 
-// Decrement reference count, free when 0 (call in widget deinit)
+// Decrement reference count, free when 0 or keep cached within the image
+// budget (see mcore_image_set_budget) (call in widget deinit)
 void mcore_image_release(mcore_context_t* ctx, int image_id);
 
 // Draw an image with transform
 void mcore_image_draw(mcore_context_t* ctx, int image_id, const mcore_image_transform_t* transform);
+// Draw like an MCORE_CMD_IMAGE record, with sampling and tiling (logical px).
+// MCORE_ERR if the image doesn't exist or is empty.
+mcore_status_t mcore_image_draw_rect(mcore_context_t* ctx, const mcore_cmd_image_t* cmd);
 
-// Load and register an image from a file path (JPEG, PNG, GIF, BMP, etc.)
+// Load and register an image from a file path (JPEG, PNG, GIF, BMP, ICO, TIFF;
+// WebP and AVIF with MCORE_CAP_WEBP_DECODE / MCORE_CAP_AVIF_DECODE)
 // Returns image info (id, width, height). id is -1 on error.
-// The image is automatically decoded to RGBA8
+// The image is automatically decoded to RGBA8 and turned upright per its EXIF
+// orientation, so width and height are the upright size
 mcore_image_info_t mcore_image_load_file(mcore_context_t* ctx, const char* path);
 
 // Get image dimensions by ID
 // Returns 1 on success, 0 if image not found
 unsigned char mcore_image_get_info(mcore_context_t* ctx, int image_id, mcore_image_info_t* out);
 
+// Register encoded image bytes (JPEG, PNG, ...) and decode them on a
+// background thread pool, so feeds don't hitch while scrolling. The ID is
+// returned at once (-1 on error; MCORE_ERROR_UNSUPPORTED without image
+// decoding) and can be drawn, retained and released right away: until the
+// decode finishes, mcore_image_draw_rect and image commands fill their rect
+// with the placeholder color, and get_info returns 0. `callback` (may be
+// NULL) then runs ON THE DECODE THREAD with the size, or 0 x 0 if decoding
+// failed and the ID was freed. `data` can be freed after this returns.
+typedef void (*mcore_image_decoded_fn)(int image_id, unsigned int width, unsigned int height, void* user_data);
+int mcore_image_register_async(mcore_context_t* ctx, const unsigned char* data, size_t len,
+                               mcore_image_decoded_fn callback, void* user_data);
+// Thumbnails: decode shrunk so neither side exceeds max_dimension, keeping
+// the aspect ratio (smaller images are left as they are). A 12MP photo listed
+// at 512px then takes ~1MB instead of ~48MB, and uploads as fast.
+mcore_image_info_t mcore_image_load_thumbnail(mcore_context_t* ctx, const char* path, unsigned int max_dimension);
+int mcore_image_register_thumbnail_async(mcore_context_t* ctx, const unsigned char* data, size_t len,
+                                         unsigned int max_dimension, mcore_image_decoded_fn callback,
+                                         void* user_data);
+// Color drawn in place of images still decoding (transparent by default)
+void mcore_image_set_placeholder(mcore_context_t* ctx, mcore_rgba_t color);
+
+// Image memory budget. Released images stay cached (retain them again while
+// mcore_image_get_info still finds them) until the budget needs their room;
+// then the least recently drawn are evicted, followed by mip levels. Images
+// in use are never evicted. The default, 0, frees released images at once.
+// The cache is shared by the contexts of an engine. Atlas pages aren't
+// counted: they never shrink, so they take up to 32MB on top of the budget.
+void mcore_image_set_budget(mcore_context_t* ctx, unsigned long long bytes);
+
+typedef struct {
+  unsigned long long bytes;           // decoded pixels held, mip levels included
+  unsigned long long released_bytes;  // part of bytes held by released images
+  unsigned long long budget_bytes;
+  unsigned long long evictions;       // released images freed to stay within budget
+  unsigned int image_count;
+  unsigned int released_count;
+  unsigned int pending_count;         // images still decoding
+  unsigned int atlas_pages;           // shared textures small images are packed
+                                      // into (up to 4MB each, 8 in all); kept
+                                      // once allocated, not in bytes or the
+                                      // budget (in frame stats image_bytes)
+} mcore_image_cache_stats_t;
+
+mcore_status_t mcore_image_cache_stats(mcore_context_t* ctx, mcore_image_cache_stats_t* out);
+
+// Give memory back when the system runs low (e.g. from a
+// DISPATCH_SOURCE_TYPE_MEMORYPRESSURE source): WARNING frees released
+// images, CRITICAL also drops every mip level (rebuilt as draws need them).
+// Returns the bytes freed.
+#define MCORE_MEMORY_PRESSURE_WARNING 1
+#define MCORE_MEMORY_PRESSURE_CRITICAL 2
+unsigned long long mcore_memory_pressure(mcore_context_t* ctx, unsigned int level);
+
+// Replay a recorded fragment (see mcore_fragment_begin) with a transform
+void mcore_fragment_draw(mcore_context_t* ctx, int fragment_id, const mcore_image_transform_t* transform);
+
 #ifdef __cplusplus
 }
 #endif
@@ -0,0 +1,22 @@
+// swift-tools-version:5.9
+// Safe Swift wrapper over the mcore C API.
+// Build the XCFramework first: cd rust/engine && cargo xtask xcframework
+import PackageDescription
+
+let package = Package(
+    name: "McoreKit",
+    platforms: [.macOS(.v13), .iOS(.v16)],
+    products: [
+        .library(name: "McoreKit", targets: ["McoreKit"]),
+    ],
+    targets: [
+        .binaryTarget(
+            name: "Mcore",
+            path: "../../rust/engine/target/xcframework/Mcore.xcframework"
+        ),
+        .target(
+            name: "McoreKit",
+            dependencies: ["Mcore"]
+        ),
+    ]
+)
@@ -0,0 +1,442 @@
+import Mcore
+import QuartzCore
+
+/// Error reported by the engine (the message from mcore_last_error)
+public struct McoreError: Error, CustomStringConvertible {
+    public let code: McoreErrorCode
+    public let message: String
+
+    public var description: String { message }
+
+    static func last(or fallback: String) -> McoreError {
+        let code = mcore_last_error_code()
+        guard let message = mcore_last_error() else {
+            return McoreError(code: code, message: fallback)
+        }
+        return McoreError(code: code, message: String(cString: message))
+    }
+}
+
+public struct RGBA: Equatable {
+    public var r: Float
+    public var g: Float
+    public var b: Float
+    public var a: Float
+
+    public init(_ r: Float, _ g: Float, _ b: Float, _ a: Float = 1) {
+        self.r = r
+        self.g = g
+        self.b = b
+        self.a = a
+    }
+
+    var raw: McoreRgba { McoreRgba(r: r, g: g, b: b, a: a) }
+}
+
+/// Optional engine features compiled into this build
+public struct Capabilities: OptionSet {
+    public let rawValue: UInt32
+
+    public init(rawValue: UInt32) { self.rawValue = rawValue }
+
+    public static let imageDecode = Capabilities(rawValue: 1)
+    public static let accessibility = Capabilities(rawValue: 2)
+
+    public static var current: Capabilities { Capabilities(rawValue: mcore_get_capabilities()) }
+}
+
+/// A GPU the engine can render with
+public struct Adapter {
+    public let name: String
+    public let deviceType: McoreDeviceType
+    public let backend: McoreGpuBackend
+
+    init(_ info: McoreAdapterInfo) {
+        var nameTuple = info.name
+        name = withUnsafeBytes(of: &nameTuple) { String(cString: $0.bindMemory(to: CChar.self).baseAddress!) }
+        deviceType = info.device_type
+        backend = info.backend
+    }
+
+    /// In the order `Context.init(adapter:)` indexes
+    public static var all: [Adapter] {
+        let count = mcore_enumerate_adapters(nil, 0)
+        var infos = [McoreAdapterInfo](repeating: McoreAdapterInfo(), count: Int(count))
+        let filled = infos.withUnsafeMutableBufferPointer { mcore_enumerate_adapters($0.baseAddress, count) }
+        return infos.prefix(Int(min(count, filled))).map(Adapter.init)
+    }
+}
+
+/// One GPU device, with fonts and images, shared by the contexts of several
+/// windows. Font and image IDs from any of its contexts work in all of them.
+public final class Engine {
+    let raw: OpaquePointer
+
+    public init(power: McorePowerPreference = McorePowerPreference_Default, adapter: Int? = nil, softwareFallback: Bool = false) throws {
+        var desc = McoreEngineDesc()
+        desc.api_version = UInt32(MCORE_API_VERSION)
+        desc.power_preference = power
+        desc.adapter = adapter.map { UInt32($0 + 1) } ?? 0
+        desc.flags = softwareFallback ? UInt32(MCORE_CREATE_SOFTWARE_FALLBACK) : 0
+        guard let raw = mcore_engine_create(&desc) else {
+            throw McoreError.last(or: "mcore_engine_create failed")
+        }
+        self.raw = raw
+    }
+
+    deinit {
+        mcore_engine_destroy(raw)
+    }
+}
+
+/// An engine context rendering into a CAMetalLayer.
+/// Not thread-safe from Swift's point of view: use it from the thread that drives frames.
+public final class Context {
+    let raw: OpaquePointer
+
+    /// Sizes are physical pixels; `scale` is the backing scale factor.
+    /// `adapter` indexes `Adapter.all`; nil picks a GPU by `power`.
+    public init(
+        layer: CAMetalLayer,
+        view: UnsafeMutableRawPointer? = nil,
+        scale: Float,
+        widthPx: Int,
+        heightPx: Int,
+        power: McorePowerPreference = McorePowerPreference_Default,
+        adapter: Int? = nil,
+        softwareFallback: Bool = false
+    ) throws {
+        var desc = Context.surfaceDesc(layer: layer, view: view, scale: scale, widthPx: widthPx, heightPx: heightPx)
+        desc.power_preference = power
+        desc.adapter = adapter.map { UInt32($0 + 1) } ?? 0
+        desc.flags = softwareFallback ? UInt32(MCORE_CREATE_SOFTWARE_FALLBACK) : 0
+        guard let raw = mcore_create(&desc) else {
+            throw McoreError.last(or: "mcore_create failed")
+        }
+        self.raw = raw
+    }
+
+    /// A context for a window of `engine`, sharing its device, fonts and images
+    public init(
+        engine: Engine,
+        layer: CAMetalLayer,
+        view: UnsafeMutableRawPointer? = nil,
+        scale: Float,
+        widthPx: Int,
+        heightPx: Int
+    ) throws {
+        var desc = Context.surfaceDesc(layer: layer, view: view, scale: scale, widthPx: widthPx, heightPx: heightPx)
+        guard let raw = mcore_engine_create_context(engine.raw, &desc) else {
+            throw McoreError.last(or: "mcore_engine_create_context failed")
+        }
+        self.raw = raw
+    }
+
+    /// A context with no window, rendering offscreen for `readPixels()`.
+    /// `softwareFallback` renders on the CPU where there's no GPU (CI).
+    public init(headlessWidthPx widthPx: Int, heightPx: Int, scale: Float = 1, softwareFallback: Bool = true) throws {
+        let flags: UInt32 = softwareFallback ? UInt32(MCORE_CREATE_SOFTWARE_FALLBACK) : 0
+        guard let raw = mcore_create_headless(UInt32(MCORE_API_VERSION), UInt32(widthPx), UInt32(heightPx), scale, flags) else {
+            throw McoreError.last(or: "mcore_create_headless failed")
+        }
+        self.raw = raw
+    }
+
+    deinit {
+        mcore_destroy(raw)
+    }
+
+    /// The last presented frame of a headless context, as RGBA8 rows
+    public func readPixels(widthPx: Int, heightPx: Int) throws -> [UInt8] {
+        var pixels = [UInt8](repeating: 0, count: widthPx * heightPx * 4)
+        let status = pixels.withUnsafeMutableBufferPointer { buf in
+            mcore_read_pixels(raw, buf.baseAddress, buf.count)
+        }
+        guard status == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_read_pixels failed")
+        }
+        return pixels
+    }
+
+    /// The last presented frame as RGBA8 rows (sizes in physical pixels)
+    public func captureFrame(widthPx: Int, heightPx: Int) throws -> [UInt8] {
+        var pixels = [UInt8](repeating: 0, count: widthPx * heightPx * 4)
+        let status = pixels.withUnsafeMutableBufferPointer { buf in
+            mcore_capture_frame(raw, buf.baseAddress, buf.count)
+        }
+        guard status == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_capture_frame failed")
+        }
+        return pixels
+    }
+
+    /// Save the last presented frame as a PNG
+    public func saveScreenshot(to path: String) throws {
+        guard mcore_capture_frame_png(raw, path) == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_capture_frame_png failed")
+        }
+    }
+
+    public func resize(layer: CAMetalLayer, view: UnsafeMutableRawPointer? = nil, scale: Float, widthPx: Int, heightPx: Int) {
+        var desc = Context.surfaceDesc(layer: layer, view: view, scale: scale, widthPx: widthPx, heightPx: heightPx)
+        mcore_resize(raw, &desc)
+    }
+
+    /// Physical pixels per logical pixel
+    public var scale: Float { mcore_get_scale(raw) }
+
+    /// Move to another display's scale factor without a resize; the surface
+    /// keeps its logical size. Call outside a frame.
+    public func setScale(_ scale: Float) throws {
+        guard mcore_set_scale_factor(raw, scale) == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_set_scale_factor failed")
+        }
+    }
+
+    // MARK: Frames
+
+    public func beginFrame(time: Double) {
+        mcore_begin_frame(raw, time)
+    }
+
+    /// Record commands into the current frame (coordinates in logical pixels)
+    public func render(_ commands: [DrawCommand]) {
+        DrawCommand.withRaw(commands) { buffer in
+            mcore_render_commands(raw, buffer.baseAddress, Int32(buffer.count))
+        }
+    }
+
+    /// Returns false if damage tracking skipped an unchanged frame
+    @discardableResult
+    public func endFrame(clear: RGBA) throws -> Bool {
+        let status = mcore_end_frame_present(raw, clear.raw)
+        guard status == McoreStatus_Ok || status == McoreStatus_FrameSkipped else {
+            throw McoreError.last(or: "mcore_end_frame_present failed")
+        }
+        return status == McoreStatus_Ok
+    }
+
+    /// Render and present on an engine-owned thread; frames are then queued
+    /// with `submitFrame` from any thread
+    public func startRenderThread() throws {
+        guard mcore_render_thread_start(raw) == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_render_thread_start failed")
+        }
+    }
+
+    public func stopRenderThread() {
+        mcore_render_thread_stop(raw)
+    }
+
+    /// Queue a frame (a v2 command stream) for the render thread
+    public func submitFrame(time: Double, stream: [UInt8], clear: RGBA) throws {
+        let status = stream.withUnsafeBufferPointer { buf in
+            mcore_submit_frame(raw, time, buf.baseAddress, buf.count, UInt32(MCORE_COMMAND_STREAM_VERSION), clear.raw)
+        }
+        guard status == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_submit_frame failed")
+        }
+    }
+
+    /// Wait until the queued frames are presented
+    public func flushRenderThread() {
+        mcore_render_thread_flush(raw)
+    }
+
+    public struct DebugOverlay: OptionSet {
+        public let rawValue: UInt32
+        public init(rawValue: UInt32) { self.rawValue = rawValue }
+
+        public static let fpsGraph = DebugOverlay(rawValue: 1 << 0)
+        public static let commands = DebugOverlay(rawValue: 1 << 1)
+        public static let clipDepth = DebugOverlay(rawValue: 1 << 2)
+        public static let textCache = DebugOverlay(rawValue: 1 << 3)
+        public static let clipOutlines = DebugOverlay(rawValue: 1 << 4)
+        public static let all: DebugOverlay = [.fpsGraph, .commands, .clipDepth, .textCache, .clipOutlines]
+    }
+
+    /// Engine-drawn overlay for diagnosing layout and performance
+    public func setDebugOverlay(_ overlay: DebugOverlay) {
+        mcore_set_debug_overlay(raw, overlay.rawValue)
+    }
+
+    public var renderMode: McoreRenderMode { mcore_render_mode(raw) }
+
+    /// The GPU this context renders with and its device limits
+    public func gpuInfo() throws -> McoreGpuInfo {
+        var info = McoreGpuInfo()
+        guard mcore_gpu_info(raw, &info) == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_gpu_info failed")
+        }
+        return info
+    }
+
+    /// Timings, scene size and memory of the last rendered frame; call after
+    /// `endFrame` for the presented frame's scene counts
+    public func frameStats() throws -> McoreFrameStats {
+        var stats = McoreFrameStats()
+        guard mcore_frame_stats(raw, &stats) == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_frame_stats failed")
+        }
+        return stats
+    }
+
+    /// Antialiasing method; setting an MSAA mode the device can't run throws
+    /// and keeps the current one
+    public func setAAMode(_ mode: McoreAaMode) throws {
+        guard mcore_set_aa_mode(raw, UInt8(mode.rawValue)) == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_set_aa_mode failed")
+        }
+    }
+
+    public var aaMode: McoreAaMode { McoreAaMode(rawValue: UInt32(mcore_get_aa_mode(raw))) }
+
+    /// Skip presenting frames whose command buffers didn't change
+    public func setDamageTracking(_ enabled: Bool) {
+        mcore_set_damage_tracking(raw, enabled ? 1 : 0)
+    }
+
+    // MARK: Gradients
+
+    public enum GradientKind: UInt8 {
+        /// `angle` is the direction: 0 = left to right, 90 = top to bottom
+        case linear = 0
+        /// `angle` is where the first stop starts: 0 = 3 o'clock, clockwise
+        case sweep = 1
+    }
+
+    /// Register a gradient for DrawCommand.gradientRing; stops are (offset 0...1, color)
+    public func createGradient(_ kind: GradientKind, angle: Float = 0, stops: [(Float, RGBA)]) throws -> Int32 {
+        let raw = stops.map { McoreGradientStop(offset: $0.0, color: $0.1.raw) }
+        let id = raw.withUnsafeBufferPointer { buffer -> Int32 in
+            var desc = McoreGradientDesc()
+            desc.kind = kind.rawValue
+            desc.angle_deg = angle
+            desc.stops = buffer.baseAddress
+            desc.stop_count = UInt32(buffer.count)
+            return mcore_gradient_create(self.raw, &desc)
+        }
+        guard id >= 0 else {
+            throw McoreError.last(or: "mcore_gradient_create failed")
+        }
+        return id
+    }
+
+    public func destroyGradient(_ id: Int32) {
+        mcore_gradient_destroy(raw, id)
+    }
+
+    // MARK: Log views
+
+    /// An append-only text view whose entries are shaped once, on append;
+    /// `maxEntries` = 0 keeps every entry
+    public func createLogView(fontSize: Float, fontID: Int32 = -1, wrapWidth: Float, maxEntries: UInt32 = 0) throws -> Int32 {
+        let id = mcore_log_view_create(raw, fontSize, fontID, wrapWidth, maxEntries)
+        guard id >= 0 else {
+            throw McoreError.last(or: "mcore_log_view_create failed")
+        }
+        return id
+    }
+
+    public func destroyLogView(_ id: Int32) {
+        mcore_log_view_destroy(raw, id)
+    }
+
+    public func appendLog(_ id: Int32, _ text: String, color: RGBA) throws {
+        guard mcore_log_view_append(raw, id, text, color.raw) == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_log_view_append failed")
+        }
+    }
+
+    public func clearLog(_ id: Int32) {
+        mcore_log_view_clear(raw, id)
+    }
+
+    /// Reshapes every entry; call on resize, not every frame
+    public func setLogWidth(_ id: Int32, wrapWidth: Float) {
+        mcore_log_view_set_width(raw, id, wrapWidth)
+    }
+
+    /// Tabs advance to each of `stops`, then to multiples of `tabWidth`
+    /// (0 = 8 spaces); reshapes every entry
+    public func setLogTabs(_ id: Int32, tabWidth: Float, stops: [Float] = []) {
+        stops.withUnsafeBufferPointer { buf in
+            mcore_log_view_set_tabs(raw, id, tabWidth, buf.baseAddress, UInt32(buf.count))
+        }
+    }
+
+    public func logContentHeight(_ id: Int32) -> Float {
+        mcore_log_view_content_height(raw, id)
+    }
+
+    /// Draw the part of the log visible through `frame`, scrolled by `scrollY`
+    public func drawLog(_ id: Int32, in frame: CGRect, scrollY: Float) throws {
+        let status = mcore_log_view_draw(
+            raw, id, Float(frame.minX), Float(frame.minY), Float(frame.width), Float(frame.height), scrollY)
+        guard status == McoreStatus_Ok else {
+            throw McoreError.last(or: "mcore_log_view_draw failed")
+        }
+    }
+
+    // MARK: Text
+
+    /// Logical size of `text`, wrapping at `maxWidth` (0 for no wrapping)
+    public func measureText(_ text: String, fontSize: Float, maxWidth: Float = 0, fontID: Int32 = -1) -> (width: Float, height: Float) {
+        var out = McoreTextSize()
+        mcore_measure_text(raw, text, fontSize, maxWidth, fontID, &out)
+        return (out.width, out.height)
+    }
+
+    /// measureText for single lines, from cached word advances (falls back
+    /// to a full layout when the text wraps at `maxWidth`)
+    public func measureTextFast(_ text: String, fontSize: Float, maxWidth: Float, fontID: Int32 = -1) -> (width: Float, height: Float) {
+        var out = McoreTextSize()
+        mcore_measure_text_fast(raw, text, fontSize, maxWidth, fontID, &out)
+        return (out.width, out.height)
+    }
+
+    /// Caret rectangle (logical, relative to the text origin) for a byte offset
+    public func caret(in text: String, fontSize: Float, wrapWidth: Float = 0, byteOffset: Int) -> CGRect {
+        var out = McoreCaretRect()
+        withTextReq(text, fontSize: fontSize, wrapWidth: wrapWidth) { req in
+            mcore_text_caret(raw, req, Int32(byteOffset), &out)
+        }
+        return CGRect(x: CGFloat(out.x), y: CGFloat(out.y), width: 0, height: CGFloat(out.height))
+    }
+
+    /// Byte offset under a logical point in wrapped text
+    public func hitTest(_ text: String, fontSize: Float, wrapWidth: Float = 0, x: Float, y: Float) -> Int {
+        withTextReq(text, fontSize: fontSize, wrapWidth: wrapWidth) { req in
+            Int(mcore_text_hit_test(raw, req, x, y))
+        }
+    }
+
+    public func textInput(id: UInt64) -> TextInput {
+        TextInput(context: self, id: id)
+    }
+
+    private func withTextReq<T>(_ text: String, fontSize: Float, wrapWidth: Float, _ body: (UnsafePointer<McoreTextReq>) -> T) -> T {
+        text.withCString { utf8 in
+            var req = McoreTextReq()
+            req.utf8 = utf8
+            req.font_size_px = fontSize
+            req.wrap_width = wrapWidth
+            req.font_id = -1
+            return body(&req)
+        }
+    }
+
+    private static func surfaceDesc(layer: CAMetalLayer, view: UnsafeMutableRawPointer?, scale: Float, widthPx: Int, heightPx: Int) -> McoreSurfaceDesc {
+        var desc = McoreSurfaceDesc()
+        desc.api_version = UInt32(MCORE_API_VERSION)
+        desc.platform = McorePlatform_MacOS
+        desc.u.macos = McoreMacSurface(
+            ns_view: view,
+            ca_metal_layer: Unmanaged.passUnretained(layer).toOpaque(),
+            scale_factor: scale,
+            width_px: Int32(widthPx),
+            height_px: Int32(heightPx)
+        )
+        return desc
+    }
+}
@@ -0,0 +1,85 @@
+import Foundation
+import Mcore
+
+public enum TextAlign: UInt8 {
+    case start = 0
+    case center = 1
+    case end = 2
+    case justify = 3
+}
+
+/// Paragraph direction; `.auto` follows the first strong character
+public enum TextDirection: UInt8 {
+    case auto = 0
+    case ltr = 1
+    case rtl = 2
+}
+
+/// A draw command in logical pixels, converted to McoreDrawCommand on submit
+public enum DrawCommand {
+    case roundedRect(CGRect, radius: Float, fill: RGBA)
+    case text(String, at: CGPoint, fontSize: Float, wrapWidth: Float = 0, color: RGBA, align: TextAlign = .start, direction: TextDirection = .auto)
+    case pushClip(CGRect, radius: Float = 0)
+    case popClip
+    /// Rounded-rect outline stroked (centered on the edge) with a gradient from Context.createGradient
+    case gradientRing(CGRect, radius: Float, width: Float, gradient: Int32)
+
+    /// Lay out `commands` as C structs for the duration of `body`.
+    /// Text pointers only need to live for the mcore_render_commands call.
+    static func withRaw<T>(_ commands: [DrawCommand], _ body: (UnsafeBufferPointer<McoreDrawCommand>) -> T) -> T {
+        var strings: [UnsafeMutablePointer<CChar>] = []
+        defer { strings.forEach { free($0) } }
+
+        let raw = commands.map { command -> McoreDrawCommand in
+            var cmd = McoreDrawCommand()
+            switch command {
+            case let .roundedRect(rect, radius, fill):
+                cmd.setKind(McoreDrawCmdKind_RoundedRect)
+                cmd.setFrame(rect)
+                cmd.radius = radius
+                cmd.color = (fill.r, fill.g, fill.b, fill.a)
+            case let .text(text, origin, fontSize, wrapWidth, color, align, direction):
+                let utf8 = strdup(text)!
+                strings.append(utf8)
+                cmd.setKind(McoreDrawCmdKind_Text)
+                cmd.x = Float(origin.x)
+                cmd.y = Float(origin.y)
+                cmd.text_ptr = UnsafePointer(utf8)
+                cmd.font_size = fontSize
+                cmd.wrap_width = wrapWidth
+                cmd.font_id = -1
+                cmd.color = (color.r, color.g, color.b, color.a)
+                cmd.text_align = align.rawValue
+                cmd.text_direction = direction.rawValue
+            case let .pushClip(rect, radius):
+                cmd.setKind(radius > 0 ? McoreDrawCmdKind_PushClipRounded : McoreDrawCmdKind_PushClip)
+                cmd.setFrame(rect)
+                cmd.radius = radius
+            case .popClip:
+                cmd.setKind(McoreDrawCmdKind_PopClip)
+            case let .gradientRing(rect, radius, width, gradient):
+                cmd.setKind(McoreDrawCmdKind_GradientRing)
+                cmd.setFrame(rect)
+                cmd.radius = radius
+                cmd.border_width = width
+                cmd.font_id = gradient
+            }
+            return cmd
+        }
+        return raw.withUnsafeBufferPointer(body)
+    }
+}
+
+private extension McoreDrawCommand {
+    /// `kind` is a byte in the C struct
+    mutating func setKind(_ kind: McoreDrawCmdKind) {
+        self.kind = UInt8(kind.rawValue)
+    }
+
+    mutating func setFrame(_ rect: CGRect) {
+        x = Float(rect.minX)
+        y = Float(rect.minY)
+        width = Float(rect.width)
+        height = Float(rect.height)
+    }
+}
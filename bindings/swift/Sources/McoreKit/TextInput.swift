@@ -0,0 +1,320 @@
+import Mcore
+import CoreGraphics
+
+/// Editing state for one text field, owned by the engine and keyed by `id`
+public struct TextInput {
+    public enum Direction {
+        /// Arrow keys move visually (left moves forward in right-to-left text)
+        case left, right, home, end, wordLeft, wordRight
+        /// Logical movement, whatever the direction
+        case backward, forward, wordBackward, wordForward
+
+        var raw: McoreCursorDirection {
+            switch self {
+            case .left: return McoreCursorDirection_Left
+            case .right: return McoreCursorDirection_Right
+            case .home: return McoreCursorDirection_Home
+            case .end: return McoreCursorDirection_End
+            case .wordLeft: return McoreCursorDirection_WordLeft
+            case .wordRight: return McoreCursorDirection_WordRight
+            case .backward: return McoreCursorDirection_Backward
+            case .forward: return McoreCursorDirection_Forward
+            case .wordBackward: return McoreCursorDirection_WordBackward
+            case .wordForward: return McoreCursorDirection_WordForward
+            }
+        }
+    }
+
+    let context: Context
+    public let id: UInt64
+
+    /// Current contents
+    public var text: String {
+        get {
+            // Grow the buffer until the whole string fits
+            var capacity = 256
+            while true {
+                var buf = [UInt8](repeating: 0, count: capacity)
+                let len = Int(mcore_text_input_get(context.raw, id, &buf, Int32(capacity)))
+                if len < capacity - 1 {
+                    return String(decoding: buf.prefix(max(len, 0)), as: UTF8.self)
+                }
+                capacity *= 2
+            }
+        }
+        nonmutating set {
+            mcore_text_input_set(context.raw, id, newValue)
+        }
+    }
+
+    /// Caret position as a UTF-8 byte offset
+    public var cursor: Int {
+        Int(mcore_text_input_cursor(context.raw, id))
+    }
+
+    /// Selected UTF-8 byte range, if any
+    public var selection: Range<Int>? {
+        var start: Int32 = 0
+        var end: Int32 = 0
+        guard mcore_text_input_get_selection(context.raw, id, &start, &end) != 0 else {
+            return nil
+        }
+        return Int(start)..<Int(end)
+    }
+
+    /// Returns true if the text changed
+    @discardableResult
+    public func insert(_ string: String) -> Bool {
+        string.withCString { utf8 in
+            var event = McoreTextEvent()
+            event.kind = McoreTextEventKind_InsertText
+            event.text_ptr = utf8
+            return send(&event)
+        }
+    }
+
+    @discardableResult
+    public func backspace(word: Bool = false) -> Bool {
+        var event = McoreTextEvent()
+        event.kind = word ? McoreTextEventKind_BackspaceWord : McoreTextEventKind_Backspace
+        return send(&event)
+    }
+
+    @discardableResult
+    public func delete(word: Bool = false) -> Bool {
+        var event = McoreTextEvent()
+        event.kind = word ? McoreTextEventKind_DeleteWord : McoreTextEventKind_Delete
+        return send(&event)
+    }
+
+    public func moveCursor(_ direction: Direction, extendSelection: Bool = false) {
+        var event = McoreTextEvent()
+        event.kind = McoreTextEventKind_MoveCursor
+        event.direction = direction.raw
+        event.extend_selection = extendSelection ? 1 : 0
+        send(&event)
+    }
+
+    public func selectAll() {
+        var event = McoreTextEvent()
+        event.kind = McoreTextEventKind_SelectAll
+        send(&event)
+    }
+
+    /// Cut, copy and paste through the system clipboard
+    @discardableResult
+    public func cut() -> Bool {
+        var event = McoreTextEvent()
+        event.kind = McoreTextEventKind_Cut
+        return send(&event)
+    }
+
+    public func copy() {
+        var event = McoreTextEvent()
+        event.kind = McoreTextEventKind_Copy
+        send(&event)
+    }
+
+    @discardableResult
+    public func paste() -> Bool {
+        var event = McoreTextEvent()
+        event.kind = McoreTextEventKind_Paste
+        return send(&event)
+    }
+
+    /// Undo/redo the last edit; returns false when there was nothing to do
+    @discardableResult
+    public func undo() -> Bool {
+        var event = McoreTextEvent()
+        event.kind = McoreTextEventKind_Undo
+        return send(&event)
+    }
+
+    @discardableResult
+    public func redo() -> Bool {
+        var event = McoreTextEvent()
+        event.kind = McoreTextEventKind_Redo
+        return send(&event)
+    }
+
+    /// Password mode: drawn as bullets, cut/copy disabled. `reveal` shows each
+    /// typed character for that many seconds first (0 never shows it).
+    public func setSecure(_ secure: Bool, reveal: Double = 0) {
+        mcore_text_input_set_secure(context.raw, id, secure ? 1 : 0, UInt32(max(reveal, 0) * 1000))
+    }
+
+    public enum CharClass: UInt8 {
+        case any = 0, numeric, decimal, hex
+    }
+
+    public enum Rejection: UInt8 {
+        case length = 1, character
+    }
+
+    /// Limit typed and pasted text (nil = unlimited); setText is exempt
+    public func setConstraints(maxBytes: Int? = nil, maxGraphemes: Int? = nil, chars: CharClass = .any) {
+        mcore_text_input_set_constraints(
+            context.raw, id, UInt32(max(maxBytes ?? 0, 0)), UInt32(max(maxGraphemes ?? 0, 0)), chars.rawValue)
+    }
+
+    /// Why the latest insertion was cut short since the last call, if it was
+    public func takeRejection() -> Rejection? {
+        Rejection(rawValue: mcore_text_input_take_rejection(context.raw, id))
+    }
+
+    public enum KeyboardType: UInt8 {
+        case `default` = 0, email, number, decimal, url
+    }
+
+    public enum Autocorrect: UInt8 {
+        case `default` = 0, on, off
+    }
+
+    public struct Hints {
+        public var keyboard: KeyboardType
+        public var autocorrect: Autocorrect
+        /// Password field: use secure entry
+        public var secure: Bool
+        /// Whether input methods should be active for this field
+        public var imeEnabled: Bool
+    }
+
+    /// Keyboard and autocorrection hints, stored with the input's state
+    public func setHints(keyboard: KeyboardType = .default, autocorrect: Autocorrect = .default) {
+        mcore_text_input_set_hints(context.raw, id, keyboard.rawValue, autocorrect.rawValue)
+    }
+
+    /// Paragraph direction: which way the arrow keys move and how `draw` aligns
+    public func setDirection(_ direction: TextDirection) {
+        mcore_text_input_set_direction(context.raw, id, direction.rawValue)
+    }
+
+    /// Whether the content currently lays out right to left
+    public var isRightToLeft: Bool {
+        mcore_text_input_is_rtl(context.raw, id) != 0
+    }
+
+    /// Configuration for UITextInputTraits / NSTextInputContext while focused
+    public var hints: Hints {
+        var out = McoreTextInputHints()
+        mcore_text_input_get_hints(context.raw, id, &out)
+        return Hints(
+            keyboard: KeyboardType(rawValue: out.keyboard) ?? .default,
+            autocorrect: Autocorrect(rawValue: out.autocorrect) ?? .default,
+            secure: out.secure != 0,
+            imeEnabled: out.ime_enabled != 0)
+    }
+
+    /// Undo steps kept (default 100, 0 disables undo)
+    public func setUndoLimit(_ limit: Int) {
+        mcore_text_input_set_undo_limit(context.raw, id, UInt32(max(limit, 0)))
+    }
+
+    /// IME composition: show `text` as underlined preedit with the IME caret at
+    /// `caret` (a UTF-8 byte offset within it), then commit or cancel it
+    public func setComposition(_ text: String, caret: Int) {
+        text.withCString { utf8 in
+            var event = McoreTextEvent()
+            event.kind = McoreTextEventKind_ImeSetComposition
+            event.text_ptr = utf8
+            event.cursor_position = Int32(caret)
+            send(&event)
+        }
+    }
+
+    @discardableResult
+    public func commitComposition(_ text: String) -> Bool {
+        text.withCString { utf8 in
+            var event = McoreTextEvent()
+            event.kind = McoreTextEventKind_ImeCommit
+            event.text_ptr = utf8
+            return send(&event)
+        }
+    }
+
+    public func cancelComposition() {
+        var event = McoreTextEvent()
+        event.kind = McoreTextEventKind_ImeCancel
+        send(&event)
+    }
+
+    /// Caret (logical, relative to the text origin) for positioning the IME
+    /// candidate window; follows the caret inside a composition
+    public func caretRect(fontSize: Float) -> CGRect {
+        var out = McoreCaretRect()
+        mcore_text_input_caret_rect(context.raw, id, fontSize, &out)
+        return CGRect(x: CGFloat(out.x), y: CGFloat(out.y), width: 0, height: CGFloat(out.height))
+    }
+
+    /// Record where the text is drawn this frame, in the current hit transform's
+    /// local space, so the root-space queries below account for zoom and pan
+    public func setOrigin(x: Float, y: Float) {
+        mcore_text_input_set_origin(context.raw, id, x, y)
+    }
+
+    /// Caret in window logical coordinates; nil until setOrigin has been called
+    public func caretRectInWindow(fontSize: Float) -> CGRect? {
+        var out = McoreCaretRect()
+        guard mcore_text_input_caret_rect_root(context.raw, id, fontSize, &out) == McoreStatus_Ok else { return nil }
+        return CGRect(x: CGFloat(out.x), y: CGFloat(out.y), width: 0, height: CGFloat(out.height))
+    }
+
+    /// Content byte offset under a window point; nil until setOrigin has been called
+    public func hitTestInWindow(fontSize: Float, x: Float, y: Float) -> Int? {
+        let offset = mcore_text_input_hit_test_root(context.raw, id, fontSize, x, y)
+        return offset >= 0 ? Int(offset) : nil
+    }
+
+    /// Place the caret at a byte offset (e.g. from Context.hitTest)
+    public func setCursor(_ byteOffset: Int, extendSelection: Bool = false) {
+        mcore_text_input_set_cursor_pos(context.raw, id, Int32(byteOffset), extendSelection ? 1 : 0)
+    }
+
+    /// Keep the caret visible in a viewport (logical px); wrapWidth 0 lays the
+    /// content out on one line. Read the offset back from `scrollOffset`.
+    public func setViewport(width: Float, height: Float, fontSize: Float, wrapWidth: Float = 0) {
+        mcore_text_input_set_viewport(context.raw, id, width, height, fontSize, wrapWidth)
+    }
+
+    /// Offset into the content to draw at, updated after each event
+    public var scrollOffset: CGPoint {
+        var x: Float = 0
+        var y: Float = 0
+        mcore_text_input_get_scroll(context.raw, id, &x, &y)
+        return CGPoint(x: CGFloat(x), y: CGFloat(y))
+    }
+
+    /// Single-line fields: the horizontal offset that keeps the caret inside
+    /// a content area `width` wide (sets a one-line viewport)
+    public func scrollX(width: Float, fontSize: Float) -> Float {
+        mcore_text_input_scroll_x(context.raw, id, width, fontSize)
+    }
+
+    /// Draw the field from engine state: text, selection, IME preedit and a
+    /// blinking caret, scrolled to keep the caret in view (logical px)
+    public func draw(
+        in frame: CGRect, padding: CGSize = CGSize(width: 8, height: 4), fontSize: Float, wrapWidth: Float = 0,
+        textColor: RGBA, selectionColor: RGBA, caretColor: RGBA, focused: Bool
+    ) {
+        var style = McoreTextInputStyle()
+        style.x = Float(frame.minX)
+        style.y = Float(frame.minY)
+        style.width = Float(frame.width)
+        style.height = Float(frame.height)
+        style.padding_x = Float(padding.width)
+        style.padding_y = Float(padding.height)
+        style.font_size = fontSize
+        style.wrap_width = wrapWidth
+        style.text_color = textColor.raw
+        style.selection_color = selectionColor.raw
+        style.caret_color = caretColor.raw
+        style.focused = focused ? 1 : 0
+        mcore_text_input_draw(context.raw, id, &style)
+    }
+
+    @discardableResult
+    private func send(_ event: inout McoreTextEvent) -> Bool {
+        var result = McoreTextEventResult()
+        return mcore_text_input_event_ex(context.raw, id, &event, &result) != 0
+    }
+}
@@ -0,0 +1,2615 @@
+// Generated by `cargo xtask bindings` from rust/engine/src/lib.rs. Do not edit.
+// C declarations of the engine ABI for 64-bit hosts. The static asserts at the
+// end check the layouts the generator computed wherever this is compiled.
+
+#ifndef ZELLO_H
+#define ZELLO_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Fall back to a software adapter (and Vello's CPU pipeline) when no GPU
+// adapter is usable, e.g. in VMs and CI
+#define MCORE_CREATE_SOFTWARE_FALLBACK 0x1 // 1 << 0
+
+// Run Vello's pipeline stages on the CPU even with a GPU
+#define MCORE_CREATE_CPU_PIPELINE 0x2 // 1 << 1
+
+// Changes only if the stream's framing does: tags and payload fields are
+// only ever added
+#define MCORE_COMMAND_STREAM_VERSION 1
+
+// Version of the C API: (major << 16) | minor. Minor versions only add entry
+// points; changing an existing struct or signature bumps the major.
+#define MCORE_API_VERSION_MAJOR 4
+#define MCORE_API_VERSION_MINOR 17
+#define MCORE_API_VERSION 0x40011 // (API_VERSION_MAJOR << 16) | API_VERSION_MINOR
+
+// McoreTextRun flags
+#define MCORE_TEXT_RUN_BOLD 0x1 // 1 << 0
+#define MCORE_TEXT_RUN_ITALIC 0x2 // 1 << 1
+#define MCORE_TEXT_RUN_UNDERLINE 0x4 // 1 << 2
+
+// Inline code: the style's code font and colors
+#define MCORE_TEXT_RUN_CODE 0x8 // 1 << 3
+
+// Use the run's color instead of the style's
+#define MCORE_TEXT_RUN_COLOR 0x10 // 1 << 4
+
+// What kind of failure the last error on a thread was (mcore_last_error_code)
+typedef enum McoreErrorCode {
+  McoreErrorCode_None = 0,
+  McoreErrorCode_Other = 1,
+  McoreErrorCode_InvalidArg = 2,
+  McoreErrorCode_NotFound = 3,
+  McoreErrorCode_InvalidState = 4,
+  McoreErrorCode_Wgpu = 5,
+  McoreErrorCode_Vello = 6,
+  McoreErrorCode_SurfaceLost = 7,
+  McoreErrorCode_OutOfMemory = 8,
+  McoreErrorCode_Utf8 = 9,
+  McoreErrorCode_Io = 10,
+  McoreErrorCode_Unsupported = 11,
+  McoreErrorCode_Panic = 12,
+  McoreErrorCode_VersionMismatch = 13,
+} McoreErrorCode;
+
+// Optional subsystems, reported by mcore_get_capabilities as a bitmask
+typedef enum McoreCapability {
+  McoreCapability_ImageDecode = 1,
+  McoreCapability_Accessibility = 2,
+  McoreCapability_Layout = 4,
+  McoreCapability_WebpDecode = 8,
+  McoreCapability_AvifDecode = 16,
+} McoreCapability;
+
+typedef enum McorePlatform {
+  McorePlatform_MacOS = 1,
+  McorePlatform_Windows = 2,
+  McorePlatform_X11 = 3,
+  McorePlatform_Wayland = 4,
+} McorePlatform;
+
+// Which GPU a context renders with when McoreSurfaceDesc::adapter is 0
+typedef enum McorePowerPreference {
+  McorePowerPreference_Default = 0,
+  McorePowerPreference_LowPower = 1,
+  McorePowerPreference_HighPerformance = 2,
+} McorePowerPreference;
+
+// How a context rasterizes frames (mcore_render_mode)
+typedef enum McoreRenderMode {
+  McoreRenderMode_Gpu = 0,
+  McoreRenderMode_CpuPipeline = 1,
+  McoreRenderMode_Software = 2,
+} McoreRenderMode;
+
+// McoreAdapterInfo::device_type values
+typedef enum McoreDeviceType {
+  McoreDeviceType_Other = 0,
+  McoreDeviceType_IntegratedGpu = 1,
+  McoreDeviceType_DiscreteGpu = 2,
+  McoreDeviceType_VirtualGpu = 3,
+  McoreDeviceType_Cpu = 4,
+} McoreDeviceType;
+
+// McoreAdapterInfo::backend values
+typedef enum McoreGpuBackend {
+  McoreGpuBackend_Other = 0,
+  McoreGpuBackend_Metal = 1,
+  McoreGpuBackend_Vulkan = 2,
+  McoreGpuBackend_Dx12 = 3,
+  McoreGpuBackend_Gl = 4,
+} McoreGpuBackend;
+
+// Horizontal alignment within the wrap width
+typedef enum McoreTextAlign {
+  McoreTextAlign_Start = 0,
+  McoreTextAlign_Center = 1,
+  McoreTextAlign_End = 2,
+  McoreTextAlign_Justify = 3,
+} McoreTextAlign;
+
+// Paragraph direction of a text request
+typedef enum McoreTextDirection {
+  McoreTextDirection_Auto = 0,
+  McoreTextDirection_Ltr = 1,
+  McoreTextDirection_Rtl = 2,
+} McoreTextDirection;
+
+// How text past max_lines is truncated
+typedef enum McoreTextOverflow {
+  McoreTextOverflow_Clip = 0,
+  McoreTextOverflow_Ellipsis = 1,
+  McoreTextOverflow_Fade = 2,
+} McoreTextOverflow;
+
+// What an McoreDrawCommand draws
+typedef enum McoreDrawCmdKind {
+  McoreDrawCmdKind_RoundedRect = 0,
+  McoreDrawCmdKind_Text = 1,
+  McoreDrawCmdKind_PushClip = 2,
+  McoreDrawCmdKind_PopClip = 3,
+  McoreDrawCmdKind_StyledRect = 4,
+  McoreDrawCmdKind_PushClipRounded = 5,
+  McoreDrawCmdKind_GradientRing = 6,
+} McoreDrawCmdKind;
+
+typedef enum McoreCmdTag {
+  McoreCmdTag_FillRect = 1,
+  McoreCmdTag_StrokeRect = 2,
+  McoreCmdTag_Shadow = 3,
+  McoreCmdTag_Text = 4,
+  McoreCmdTag_PushClip = 5,
+  McoreCmdTag_PopClip = 6,
+  McoreCmdTag_Gradient = 7,
+  McoreCmdTag_Image = 8,
+  McoreCmdTag_PushTransform = 9,
+  McoreCmdTag_PopTransform = 10,
+  McoreCmdTag_Video = 11,
+} McoreCmdTag;
+
+typedef enum McoreStatus {
+  McoreStatus_Ok = 0,
+  McoreStatus_Err = 1,
+  McoreStatus_NotInFrame = 2,
+  McoreStatus_FrameInProgress = 3,
+  McoreStatus_FrameSkipped = 4,
+} McoreStatus;
+
+// Paragraph kinds for McoreTextBlockParagraph
+typedef enum McoreTextBlockKind {
+  McoreTextBlockKind_Paragraph = 0,
+  McoreTextBlockKind_Heading = 1,
+  McoreTextBlockKind_Bullet = 2,
+  McoreTextBlockKind_Numbered = 3,
+} McoreTextBlockKind;
+
+// Antialiasing methods (mcore_set_aa_mode)
+typedef enum McoreAaMode {
+  McoreAaMode_Area = 0,
+  McoreAaMode_Msaa8 = 1,
+  McoreAaMode_Msaa16 = 2,
+} McoreAaMode;
+
+typedef enum McoreTextEventKind {
+  McoreTextEventKind_InsertChar = 0,
+  McoreTextEventKind_Backspace = 1,
+  McoreTextEventKind_Delete = 2,
+  McoreTextEventKind_MoveCursor = 3,
+  McoreTextEventKind_SetCursor = 4,
+  McoreTextEventKind_InsertText = 5,
+  McoreTextEventKind_BackspaceWord = 6,
+  McoreTextEventKind_DeleteWord = 7,
+  McoreTextEventKind_SelectAll = 8,
+  McoreTextEventKind_Cut = 9,
+  McoreTextEventKind_Copy = 10,
+  McoreTextEventKind_Paste = 11,
+  McoreTextEventKind_Undo = 12,
+  McoreTextEventKind_Redo = 13,
+  McoreTextEventKind_ImeSetComposition = 14,
+  McoreTextEventKind_ImeCommit = 15,
+  McoreTextEventKind_ImeCancel = 16,
+} McoreTextEventKind;
+
+typedef enum McoreCursorDirection {
+  McoreCursorDirection_Left = 0,
+  McoreCursorDirection_Right = 1,
+  McoreCursorDirection_Home = 2,
+  McoreCursorDirection_End = 3,
+  McoreCursorDirection_WordLeft = 4,
+  McoreCursorDirection_WordRight = 5,
+  McoreCursorDirection_Backward = 6,
+  McoreCursorDirection_Forward = 7,
+  McoreCursorDirection_WordBackward = 8,
+  McoreCursorDirection_WordForward = 9,
+} McoreCursorDirection;
+
+typedef enum McoreCursor {
+  McoreCursor_Arrow = 0,
+  McoreCursor_IBeam = 1,
+  McoreCursor_PointingHand = 2,
+  McoreCursor_Crosshair = 3,
+  McoreCursor_OpenHand = 4,
+  McoreCursor_ClosedHand = 5,
+  McoreCursor_NotAllowed = 6,
+  McoreCursor_ResizeLeftRight = 7,
+  McoreCursor_ResizeUpDown = 8,
+  McoreCursor_ResizeNwse = 9,
+  McoreCursor_ResizeNesw = 10,
+} McoreCursor;
+
+typedef enum McorePointerPhase {
+  McorePointerPhase_Down = 0,
+  McorePointerPhase_Move = 1,
+  McorePointerPhase_Up = 2,
+  McorePointerPhase_Cancel = 3,
+} McorePointerPhase;
+
+typedef enum McoreScrollPhase {
+  McoreScrollPhase_Wheel = 0,
+  McoreScrollPhase_Began = 1,
+  McoreScrollPhase_Changed = 2,
+  McoreScrollPhase_Ended = 3,
+  McoreScrollPhase_Cancelled = 4,
+} McoreScrollPhase;
+
+typedef enum McoreScrollAxis {
+  McoreScrollAxis_X = 0,
+  McoreScrollAxis_Y = 1,
+} McoreScrollAxis;
+
+typedef enum McoreLayoutDirection {
+  McoreLayoutDirection_Row = 0,
+  McoreLayoutDirection_Column = 1,
+  McoreLayoutDirection_RowReverse = 2,
+  McoreLayoutDirection_ColumnReverse = 3,
+} McoreLayoutDirection;
+
+// Main-axis distribution (justify-content)
+typedef enum McoreLayoutJustify {
+  McoreLayoutJustify_Start = 0,
+  McoreLayoutJustify_End = 1,
+  McoreLayoutJustify_Center = 2,
+  McoreLayoutJustify_SpaceBetween = 3,
+  McoreLayoutJustify_SpaceAround = 4,
+  McoreLayoutJustify_SpaceEvenly = 5,
+} McoreLayoutJustify;
+
+// Cross-axis alignment (align-items, align-self)
+typedef enum McoreLayoutAlign {
+  McoreLayoutAlign_Auto = 0,
+  McoreLayoutAlign_Stretch = 1,
+  McoreLayoutAlign_Start = 2,
+  McoreLayoutAlign_End = 3,
+  McoreLayoutAlign_Center = 4,
+  McoreLayoutAlign_Baseline = 5,
+} McoreLayoutAlign;
+
+typedef enum McoreParticleLayer {
+  McoreParticleLayer_Beneath = 0,
+  McoreParticleLayer_Above = 1,
+} McoreParticleLayer;
+
+// Color space tag for color inputs
+typedef enum McoreColorSpace {
+  McoreColorSpace_Srgb = 0,
+  McoreColorSpace_DisplayP3 = 1,
+} McoreColorSpace;
+
+typedef struct McoreMacSurface McoreMacSurface;
+typedef union McoreSurfaceUnion McoreSurfaceUnion;
+typedef struct McoreSurfaceDesc McoreSurfaceDesc;
+typedef struct McoreEngineDesc McoreEngineDesc;
+typedef struct McoreAdapterInfo McoreAdapterInfo;
+typedef struct McoreGpuInfo McoreGpuInfo;
+typedef struct McoreRgba McoreRgba;
+typedef struct McoreRoundedRect McoreRoundedRect;
+typedef struct McoreFontBlob McoreFontBlob;
+typedef struct McoreTextReq McoreTextReq;
+typedef struct McoreTextSpan McoreTextSpan;
+typedef struct McoreTextMetrics McoreTextMetrics;
+typedef struct McoreTextSize McoreTextSize;
+typedef struct McoreCaretRect McoreCaretRect;
+typedef struct McoreTextStats McoreTextStats;
+typedef struct McoreHitTransform McoreHitTransform;
+typedef struct McoreHitResult McoreHitResult;
+typedef struct McoreTextCacheStats McoreTextCacheStats;
+typedef struct McoreDrawCommand McoreDrawCommand;
+typedef struct McoreCmdHeader McoreCmdHeader;
+typedef struct McoreCmdFill McoreCmdFill;
+typedef struct McoreCmdStroke McoreCmdStroke;
+typedef struct McoreCmdShadow McoreCmdShadow;
+typedef struct McoreCmdText McoreCmdText;
+typedef struct McoreCmdClip McoreCmdClip;
+typedef struct McoreCmdGradient McoreCmdGradient;
+typedef struct McoreCmdImage McoreCmdImage;
+typedef struct McoreCmdTransform McoreCmdTransform;
+typedef struct McoreCmdVideo McoreCmdVideo;
+typedef struct McoreColor McoreColor;
+typedef struct McoreContext McoreContext;
+// A GPU device, Vello's pipelines, fonts and images shared by the contexts
+// created from it (one per window), instead of each context opening its own
+typedef struct McoreEngine McoreEngine;
+// A shaped text layout owned by the host
+// Shape once, then measure, hit test and draw it as often as needed.
+typedef struct McoreTextLayout McoreTextLayout;
+typedef struct McoreTextRun McoreTextRun;
+typedef struct McoreTextBlockParagraph McoreTextBlockParagraph;
+typedef struct McoreTextBlockStyle McoreTextBlockStyle;
+// Laid out rich text paragraphs owned by the host
+// Like a layout handle, it captures the scale factor at creation.
+typedef struct McoreTextBlock McoreTextBlock;
+typedef struct McoreCodeReq McoreCodeReq;
+typedef struct McoreCodeToken McoreCodeToken;
+typedef struct McoreCodeMetrics McoreCodeMetrics;
+// A large document shaped a range of lines at a time
+typedef struct McoreTextWindow McoreTextWindow;
+typedef struct McoreGradientStop McoreGradientStop;
+typedef struct McoreGradientDesc McoreGradientDesc;
+typedef struct McoreFrameStats McoreFrameStats;
+typedef struct McoreTextRendering McoreTextRendering;
+typedef struct McoreTextEvent McoreTextEvent;
+typedef struct McoreTextEventResult McoreTextEventResult;
+typedef struct McoreTextInputStyle McoreTextInputStyle;
+typedef struct McoreTextInputHints McoreTextInputHints;
+typedef struct McoreImePreedit McoreImePreedit;
+typedef struct McoreGesture McoreGesture;
+typedef struct McoreScrollState McoreScrollState;
+typedef struct McoreEdges McoreEdges;
+typedef struct McoreLayoutStyle McoreLayoutStyle;
+typedef struct McoreUiTheme McoreUiTheme;
+typedef struct McoreUiInput McoreUiInput;
+typedef struct McoreTextureTarget McoreTextureTarget;
+typedef struct McoreVideoFrame McoreVideoFrame;
+typedef struct McoreParticleEmitter McoreParticleEmitter;
+typedef struct McoreA11yNode McoreA11yNode;
+typedef struct McoreRect McoreRect;
+typedef struct McoreA11yPrefs McoreA11yPrefs;
+typedef struct McoreImageDesc McoreImageDesc;
+typedef struct McoreImageTransform McoreImageTransform;
+typedef struct McoreImageInfo McoreImageInfo;
+typedef struct McoreImageCacheStats McoreImageCacheStats;
+
+typedef void (*McoreCrashCallback)(const char*, const char*);
+
+typedef void (*McoreTextureCallback)(void*, const McoreTextureTarget*);
+
+struct McoreMacSurface {
+  void* ns_view; // NSView*
+  void* ca_metal_layer; // CAMetalLayer*
+  float scale_factor;
+  int32_t width_px;
+  int32_t height_px;
+};
+
+union McoreSurfaceUnion {
+  McoreMacSurface macos;
+};
+
+struct McoreSurfaceDesc {
+  // MCORE_API_VERSION as the host saw it. First, so hosts built before
+  // the field existed fail the check instead of misreading the rest.
+  uint32_t api_version;
+  McorePlatform platform;
+  McoreSurfaceUnion u;
+  McorePowerPreference power_preference;
+  // 1-based index into mcore_enumerate_adapters, or 0 to pick by
+  // power_preference
+  uint32_t adapter;
+  // MCORE_CREATE_* flags
+  uint32_t flags;
+};
+
+// How mcore_engine_create picks the GPU its contexts share
+struct McoreEngineDesc {
+  // MCORE_API_VERSION as the host saw it
+  uint32_t api_version;
+  McorePowerPreference power_preference;
+  // 1-based index into mcore_enumerate_adapters, or 0 to pick by
+  // power_preference
+  uint32_t adapter;
+  // MCORE_CREATE_* flags
+  uint32_t flags;
+};
+
+struct McoreAdapterInfo {
+  // NUL-terminated, truncated to fit
+  uint8_t name[128];
+  uint32_t vendor;
+  uint32_t device;
+  McoreDeviceType device_type;
+  McoreGpuBackend backend;
+};
+
+// The adapter a context renders with and the device limits the engine got
+struct McoreGpuInfo {
+  McoreAdapterInfo adapter;
+  uint32_t max_texture_dimension_2d;
+  uint32_t max_storage_buffer_binding_size;
+  uint64_t max_buffer_size;
+  // GPU frame timings are available (McoreFrameStats::gpu_ms)
+  uint8_t timestamp_queries;
+};
+
+struct McoreRgba {
+  float r;
+  float g;
+  float b;
+  float a;
+};
+
+struct McoreRoundedRect {
+  float x;
+  float y;
+  float w;
+  float h;
+  float radius;
+  McoreRgba fill;
+};
+
+struct McoreFontBlob {
+  const uint8_t* data;
+  size_t len;
+  const char* name;
+};
+
+struct McoreTextReq {
+  const char* utf8;
+  float wrap_width;
+  float font_size_px;
+  int32_t font_id;
+  // CSS-style weight (100-900), 0 for the font's default face
+  float weight;
+  uint8_t italic;
+  // Width ratio (0.5-2.0), 0 for normal
+  float stretch;
+  // Extra space after each character / word, logical pixels
+  float letter_spacing;
+  float word_spacing;
+  // Multiple of the font size, 0 for the font's default line height
+  float line_height_multiplier;
+  // McoreTextAlign value
+  uint8_t align;
+  // Most lines to show, 0 for no limit
+  uint32_t max_lines;
+  // McoreTextOverflow value, applied past max_lines
+  uint8_t overflow;
+  // McoreTextDirection value
+  uint8_t direction;
+  // Spacing of repeating tab stops (logical px), 0 for 8 spaces
+  float tab_width;
+  // Explicit tab stops (logical px from the line start) before the
+  // repeating ones; may be null when tab_stop_count is 0
+  const float* tab_stops;
+  uint32_t tab_stop_count;
+};
+
+// Style applied to a byte range of a text request
+struct McoreTextSpan {
+  uint32_t start;
+  uint32_t end;
+  McoreRgba color;
+  // CSS-style weight (100-900), 0 to inherit
+  float weight;
+  uint8_t italic;
+  uint8_t underline;
+  // Font for this range, -1 to inherit
+  int32_t font_id;
+};
+
+struct McoreTextMetrics {
+  float advance_w;
+  float advance_h;
+  int32_t line_count;
+  // 1 if lines past max_lines were dropped
+  uint8_t truncated;
+};
+
+struct McoreTextSize {
+  float width;
+  float height;
+};
+
+// Caret position in logical pixels; y is the top of the caret's line
+struct McoreCaretRect {
+  float x;
+  float y;
+  float height;
+};
+
+struct McoreTextStats {
+  uint32_t total_measure_calls;
+  uint32_t total_offset_calls;
+};
+
+// Translation + uniform scale from the hit transform stack
+struct McoreHitTransform {
+  float dx;
+  float dy;
+  float scale;
+};
+
+// The region under a point (mcore_hit_test)
+struct McoreHitResult {
+  uint64_t id;
+  int32_t z;
+  // The point in the region's local space, origin at its top-left
+  float local_x;
+  float local_y;
+};
+
+// Shaped-layout cache counters (hits/misses/evictions since the last reset)
+struct McoreTextCacheStats {
+  uint32_t entries;
+  uint32_t max_entries;
+  // Estimated bytes held by cached layouts
+  uint64_t bytes;
+  uint64_t max_bytes;
+  uint64_t hits;
+  uint64_t misses;
+  uint64_t evictions;
+};
+
+struct McoreDrawCommand {
+  // McoreDrawCmdKind value
+  uint8_t kind;
+  float x;
+  float y;
+  float width;
+  float height;
+  float radius;
+  float color[4];
+  const char* text_ptr;
+  float font_size;
+  float wrap_width;
+  int32_t font_id;
+  float border_width;
+  float border_color[4];
+  uint8_t has_border;
+  float shadow_offset_x;
+  float shadow_offset_y;
+  float shadow_blur;
+  float shadow_color[4];
+  uint8_t has_shadow;
+  // McoreTextAlign value (text commands)
+  uint8_t text_align;
+  // McoreTextDirection value (text commands)
+  uint8_t text_direction;
+};
+
+// Precedes each record's payload in a command stream
+struct McoreCmdHeader {
+  // McoreCmdTag value
+  uint16_t tag;
+  // Reserved, 0
+  uint16_t flags;
+  // Payload bytes, not counting the padding to the next record
+  uint32_t size;
+};
+
+// Rounded-rect fill (logical px)
+struct McoreCmdFill {
+  float x;
+  float y;
+  float width;
+  float height;
+  float radius;
+  float color[4];
+};
+
+// Rounded-rect outline, line_width centered on the edge
+struct McoreCmdStroke {
+  float x;
+  float y;
+  float width;
+  float height;
+  float radius;
+  float line_width;
+  float color[4];
+};
+
+// Blurred rounded rect, for drop shadows
+struct McoreCmdShadow {
+  float x;
+  float y;
+  float width;
+  float height;
+  float radius;
+  float blur;
+  float color[4];
+};
+
+// Text at (x, y); the UTF-8 is the last text_len bytes of the payload
+struct McoreCmdText {
+  float x;
+  float y;
+  float font_size;
+  // 0 for no wrapping
+  float wrap_width;
+  int32_t font_id;
+  float color[4];
+  // McoreTextAlign value
+  uint32_t align;
+  // McoreTextDirection value
+  uint32_t direction;
+  uint32_t text_len;
+};
+
+// Clip to a rounded rect until the matching PopClip
+struct McoreCmdClip {
+  float x;
+  float y;
+  float width;
+  float height;
+  float radius;
+};
+
+// Rounded rect painted with a registered gradient: filled, or stroked
+// line_width wide when that's above 0
+struct McoreCmdGradient {
+  float x;
+  float y;
+  float width;
+  float height;
+  float radius;
+  int32_t gradient_id;
+  float line_width;
+};
+
+// Registered image over a rect: stretched to it, or with a tile size drawn
+// at that size from (x + tile_x, y + tile_y) and extended past its edges
+struct McoreCmdImage {
+  float x;
+  float y;
+  float width;
+  float height;
+  int32_t image_id;
+  // MCORE_IMAGE_SAMPLING_*
+  uint32_t sampling;
+  // MCORE_IMAGE_EXTEND_*, per axis
+  uint32_t extend_x;
+  uint32_t extend_y;
+  // Where one copy of the image goes, relative to x, y; a size of 0 is
+  // the rect's
+  float tile_x;
+  float tile_y;
+  float tile_width;
+  float tile_height;
+};
+
+// Affine transform applied to the following commands until the matching
+// PopTransform, composed with any already pushed: [a, b, c, d, e, f] maps
+// (x, y) to (a*x + c*y + e, b*x + d*y + f), translation in logical px
+struct McoreCmdTransform {
+  float matrix[6];
+};
+
+// A video's latest frame (mcore_video_frame_submit) fitted to a rect
+struct McoreCmdVideo {
+  float x;
+  float y;
+  float width;
+  float height;
+  int32_t video_id;
+  // MCORE_VIDEO_FIT_*
+  uint32_t fit;
+};
+
+// Color type - just an RGBA tuple
+// Same layout as peniko::Color which is an array [r, g, b, a]
+struct McoreColor {
+  float r;
+  float g;
+  float b;
+  float a;
+};
+
+// Styling for a byte range of a paragraph
+struct McoreTextRun {
+  uint32_t start;
+  uint32_t end;
+  // MCORE_TEXT_RUN_* flags
+  uint32_t flags;
+  // Used with MCORE_TEXT_RUN_COLOR
+  McoreRgba color;
+};
+
+struct McoreTextBlockParagraph {
+  const char* utf8;
+  // McoreTextBlockKind value
+  uint32_t kind;
+  // Heading level (1-6) or list nesting depth (0 for the top level)
+  uint32_t level;
+  // Shown by numbered list items
+  uint32_t number;
+  uint32_t run_count;
+  // May be null when run_count is 0
+  const McoreTextRun* runs;
+};
+
+// Styling shared by a block's paragraphs (logical px)
+struct McoreTextBlockStyle {
+  float font_size_px;
+  int32_t font_id;
+  // Font for code runs, -1 for font_id
+  int32_t code_font_id;
+  // Multiple of the font size, 0 for the font's default line height
+  float line_height_multiplier;
+  // Space between paragraphs; consecutive list items sit closer
+  float paragraph_spacing;
+  // Indent per list nesting level, markers included
+  float list_indent;
+  McoreRgba color;
+  McoreRgba code_color;
+  McoreRgba code_background;
+};
+
+// Monospace code drawn from a buffer, shaping only the lines in the viewport
+// (logical px)
+struct McoreCodeReq {
+  // UTF-8, not null-terminated; may be null when len is 0
+  const uint8_t* text;
+  size_t len;
+  float font_size_px;
+  int32_t font_id;
+  // Multiple of the font size, 0 for the font's default line height
+  float line_height_multiplier;
+  // Columns between tab stops, 0 for 8
+  uint32_t tab_size;
+  // 1 to draw line numbers in line_number_color
+  uint32_t line_numbers;
+  // How far the viewport is scrolled down, and its height
+  float scroll_y;
+  float viewport_height;
+  McoreRgba color;
+  McoreRgba line_number_color;
+};
+
+// A syntax color for a byte range of a code buffer
+struct McoreCodeToken {
+  uint32_t start;
+  uint32_t end;
+  McoreRgba color;
+};
+
+// Logical px
+struct McoreCodeMetrics {
+  uint32_t line_count;
+  float line_height;
+  // Where the code starts; 0 without line numbers
+  float gutter_width;
+  // line_count * line_height, for sizing a scroll view
+  float content_height;
+};
+
+struct McoreGradientStop {
+  // Position along the gradient, 0..=1
+  float offset;
+  McoreRgba color;
+};
+
+struct McoreGradientDesc {
+  // 0 = linear, 1 = sweep (conic)
+  uint8_t kind;
+  uint8_t _padding[3];
+  // Linear: direction (0 = left to right, 90 = top to bottom).
+  // Sweep: where offset 0 starts (0 = 3 o'clock), going clockwise.
+  float angle_deg;
+  const McoreGradientStop* stops;
+  uint32_t stop_count;
+};
+
+// Timings, scene size and memory of the last rendered frame
+struct McoreFrameStats {
+  // Frames presented so far
+  uint64_t frame;
+  // CPU milliseconds from mcore_begin_frame to mcore_end_frame_present
+  float build_ms;
+  // Part of build_ms the engine spent encoding command buffers
+  float encode_ms;
+  // CPU milliseconds rendering custom widget textures
+  float custom_ms;
+  // CPU milliseconds submitting the render and presenting
+  float render_ms;
+  // GPU milliseconds of a recent frame (a frame or two behind), or -1
+  // where the GPU can't time itself
+  float gpu_ms;
+  uint32_t commands;
+  uint32_t encode_calls;
+  // Vello scene elements
+  uint32_t paths;
+  uint32_t path_segments;
+  uint32_t clips;
+  uint32_t draw_objects;
+  uint32_t image_count;
+  // Decoded pixels of registered images and their atlas pages
+  uint64_t image_bytes;
+  // Render targets and custom widget textures
+  uint64_t texture_bytes;
+  // Clip pops with nothing pushed and clips left open at
+  // mcore_end_frame_present, both corrected by the engine (layers.rs)
+  uint32_t layer_warnings;
+};
+
+// Glyph fitting for a context's text
+struct McoreTextRendering {
+  // 1 to hint outlines and snap baselines to whole pixels
+  uint32_t hinting;
+  // Horizontal glyph positions per pixel: 0 unquantized, 1 whole pixels,
+  // up to 16
+  uint32_t subpixel_positions;
+};
+
+struct McoreTextEvent {
+  McoreTextEventKind kind;
+  uint32_t char_code;
+  McoreCursorDirection direction;
+  uint8_t extend_selection;
+  int32_t cursor_position;
+  const char* text_ptr;
+};
+
+// Result of mcore_text_input_event_ex
+struct McoreTextEventResult {
+  // The text changed (mcore_text_input_event's return value)
+  uint8_t changed;
+  // The scroll offset moved to follow the caret
+  uint8_t scrolled;
+  uint8_t _padding[2];
+  // Offset into the content to draw at (logical px); 0 without a viewport
+  float scroll_x;
+  float scroll_y;
+};
+
+// How mcore_text_input_draw lays out and colors a field (logical px)
+struct McoreTextInputStyle {
+  // Field box; everything is clipped to it
+  float x;
+  float y;
+  float width;
+  float height;
+  float padding_x;
+  float padding_y;
+  float font_size;
+  // 0: one line, centered vertically. Otherwise wrap at this width.
+  float wrap_width;
+  McoreRgba text_color;
+  McoreRgba selection_color;
+  McoreRgba caret_color;
+  // Draw the selection and blinking caret
+  uint8_t focused;
+  uint8_t _padding[3];
+};
+
+// What a host should configure for a focused text input
+struct McoreTextInputHints {
+  // MCORE_KEYBOARD_*
+  uint8_t keyboard;
+  // MCORE_AUTOCORRECT_*
+  uint8_t autocorrect;
+  // Password field (mcore_text_input_set_secure): use secure event input
+  uint8_t secure;
+  // Input methods should be active (off for secure, non-default keyboard
+  // and character-constrained inputs)
+  uint8_t ime_enabled;
+};
+
+struct McoreImePreedit {
+  const char* text;
+  int32_t cursor_offset;
+};
+
+struct McoreGesture {
+  uint8_t kind; // 0 = Tap, 1 = DoubleTap, 2 = LongPress, 3 = PanBegin, 4 = PanUpdate, 5 = PanEnd
+  float x;
+  float y;
+  float dx;
+  float dy;
+  float velocity_x;
+  float velocity_y;
+};
+
+struct McoreScrollState {
+  float offset_x;
+  float offset_y;
+  float velocity_x;
+  float velocity_y;
+  uint8_t animating;
+};
+
+struct McoreEdges {
+  float top;
+  float right;
+  float bottom;
+  float left;
+};
+
+// A layout node's flex properties (logical px). Start from
+// mcore_layout_style_default: a zeroed style has zero width and height.
+struct McoreLayoutStyle {
+  // McoreLayoutDirection
+  uint8_t direction;
+  // 1 wraps items onto more lines
+  uint8_t wrap;
+  // McoreLayoutJustify
+  uint8_t justify;
+  // McoreLayoutAlign
+  uint8_t align_items;
+  // McoreLayoutAlign
+  uint8_t align_self;
+  // 1 takes the node out of the layout (it gets an empty rect)
+  uint8_t hidden;
+  // Sizes; negative (MCORE_LAYOUT_AUTO) is auto
+  float width;
+  float height;
+  float min_width;
+  float min_height;
+  float max_width;
+  float max_height;
+  float grow;
+  float shrink;
+  // Negative is auto
+  float basis;
+  McoreEdges padding;
+  McoreEdges margin;
+  float row_gap;
+  float column_gap;
+};
+
+// Colors and metrics of mcore_ui_* widgets (logical px)
+struct McoreUiTheme {
+  float font_size;
+  // Inside buttons and text fields
+  float padding;
+  // Between widgets
+  float spacing;
+  float corner_radius;
+  McoreRgba text;
+  McoreRgba button;
+  McoreRgba button_hover;
+  McoreRgba button_pressed;
+  McoreRgba field;
+  // Focus ring and caret; the selection is a translucent tint of it
+  McoreRgba accent;
+};
+
+// Pointer state for mcore_ui_begin (logical px)
+struct McoreUiInput {
+  float pointer_x;
+  float pointer_y;
+  // 1 while the primary button is held
+  uint8_t pointer_down;
+  uint8_t _padding[3];
+};
+
+// Passed to a custom texture callback each frame
+struct McoreTextureTarget {
+  // Native texture (id<MTLTexture> on macOS), RGBA8 unorm, premultiplied alpha
+  void* native_texture;
+  uint32_t width;
+  uint32_t height;
+  double time_seconds;
+};
+
+// A decoded video frame: pixels in memory, or an IOSurface
+struct McoreVideoFrame {
+  // MCORE_VIDEO_FORMAT_*; ignored for IOSurfaces, whose own format is used
+  uint32_t format;
+  uint32_t width;
+  uint32_t height;
+  // MCORE_VIDEO_MATRIX_*, for NV12
+  uint32_t matrix;
+  // NV12 only: non-zero if luma and chroma span 0-255 rather than
+  // 16-235 / 16-240; ignored for IOSurfaces
+  uint32_t full_range;
+  uint32_t _reserved;
+  // BGRA: the pixels. NV12: the luma plane, then interleaved Cb, Cr.
+  // Each plane holds stride * rows bytes.
+  const uint8_t* planes[2];
+  // Bytes per row of each plane
+  uint32_t strides[2];
+  // IOSurfaceRef (e.g. from CVPixelBufferGetIOSurface); when set, the
+  // frame is read from it and `planes` are ignored
+  void* iosurface;
+};
+
+// Particle emitter description (logical pixels, seconds, radians)
+struct McoreParticleEmitter {
+  float x;
+  float y;
+  uint32_t count;
+  float lifetime;
+  float speed_min;
+  float speed_max;
+  // 0 = right, PI/2 = down
+  float direction;
+  float spread;
+  // Logical px/s^2, positive = down
+  float gravity;
+  float size;
+  // Color ramp over each particle's life
+  McoreRgba colors[4];
+  uint8_t looping;
+  McoreParticleLayer layer;
+};
+
+struct McoreRect {
+  float x;
+  float y;
+  float width;
+  float height;
+};
+
+// System accessibility preferences
+struct McoreA11yPrefs {
+  uint8_t reduce_motion;
+  uint8_t increase_contrast;
+  uint8_t reduce_transparency;
+};
+
+struct McoreImageDesc {
+  const uint8_t* data;
+  uint32_t data_len;
+  uint32_t width;
+  uint32_t height;
+  uint8_t format;
+  uint8_t alpha_type;
+};
+
+struct McoreImageTransform {
+  float x;
+  float y;
+  float scale;
+  float rotation_deg;
+};
+
+struct McoreImageInfo {
+  int32_t image_id;
+  uint32_t width;
+  uint32_t height;
+};
+
+// Occupancy of the image cache, shared by the contexts of an engine
+struct McoreImageCacheStats {
+  // Decoded pixels held, mip levels included
+  uint64_t bytes;
+  // Part of bytes held by released images kept for reuse
+  uint64_t released_bytes;
+  uint64_t budget_bytes;
+  // Released images freed so far to stay within the budget
+  uint64_t evictions;
+  uint32_t image_count;
+  uint32_t released_count;
+  // Images still decoding
+  uint32_t pending_count;
+  // Shared textures small images are packed into, up to 4MB each and 8
+  // in all. Pages are kept once allocated and aren't counted in bytes or
+  // against the budget (they are in mcore_frame_stats image_bytes).
+  uint32_t atlas_pages;
+};
+
+// Represents a single accessibility node sent from Zig
+struct McoreA11yNode {
+  uint64_t id;
+  uint8_t role; // Maps to accesskit::Role
+  const char* label;
+  McoreRect bounds;
+  uint32_t actions; // Bitfield of supported actions
+  const uint64_t* children;
+  int32_t children_count;
+  const char* value;
+  int32_t text_selection_start;
+  int32_t text_selection_end;
+  // MCORE_A11Y_STATE_* flags
+  uint32_t states;
+};
+
+// Functions
+
+// The last error on this thread. The string stays valid until the next
+// mcore_last_error call on the same thread; mcore_last_error_copy copies it
+// into a buffer the caller owns instead.
+const char* mcore_last_error(void);
+
+// Kind of the last error on this thread, MCORE_ERROR_NONE if there was none.
+// Fallible calls set it along with the message when they fail.
+McoreErrorCode mcore_last_error_code(void);
+
+// Copy the last error message on this thread into `buf` (`len` bytes,
+// null-terminated, truncated if needed). Returns the message length without
+// the terminator, 0 if there's no error; call with a null buf to size it.
+size_t mcore_last_error_copy(char* buf, size_t len);
+
+// Static description of an McoreErrorCode; never null, never freed
+const char* mcore_error_message(uint32_t code);
+
+// Forget the last error on this thread
+void mcore_clear_error(void);
+
+// Record every FFI call into a ring of the last `capacity` calls, for
+// mcore_trace_dump (0 turns tracing off). MCORE_TRACE=<capacity> in the
+// environment enables it at startup. While tracing, calls on a destroyed
+// context print the history and abort instead of corrupting memory.
+void mcore_trace_enable(uint32_t capacity);
+
+// Copy the recorded call history (oldest first, one call per line) into
+// buf, null-terminated and truncated to fit. Returns the full length in
+// bytes, or -1 if tracing is off.
+int32_t mcore_trace_dump(uint8_t* buf, int32_t buf_len);
+
+// Which optional subsystems this build includes (McoreCapability bits).
+// FFI entry points exist in every build; without the subsystem they fail
+// with an error or do nothing.
+uint32_t mcore_get_capabilities(void);
+
+// The API version this engine was built with (MCORE_API_VERSION in its
+// header)
+uint32_t mcore_api_version(void);
+
+McoreContext* mcore_create(const McoreSurfaceDesc* desc);
+
+// Open the GPU device that contexts from mcore_engine_create_context share.
+// Returns null on error.
+McoreEngine* mcore_engine_create(const McoreEngineDesc* desc);
+
+// Create a context for a window that renders with `engine`'s device and
+// shares its fonts and images: font and image IDs registered through any of
+// the engine's contexts work in all of them. The desc's power_preference,
+// adapter and flags are ignored. Returns null on error, e.g. when the
+// engine's GPU can't present to the window.
+McoreContext* mcore_engine_create_context(McoreEngine* engine, const McoreSurfaceDesc* desc);
+
+// Release the engine handle. Contexts created from it keep working and
+// keep the shared device alive until they're destroyed.
+void mcore_engine_destroy(McoreEngine* engine);
+
+// Create a context with no window: frames render into an offscreen texture
+// of `width_px` x `height_px`, read back with mcore_read_pixels. For CI
+// screenshot tests and server-side thumbnails. `api_version` is
+// MCORE_API_VERSION and `flags` are MCORE_CREATE_* flags. Returns null on
+// error.
+McoreContext* mcore_create_headless(uint32_t api_version, uint32_t width_px, uint32_t height_px, float scale_factor, uint32_t flags);
+
+// Fill up to `capacity` entries of `out` with the GPUs available to
+// mcore_create and return how many there are (call with capacity 0 to
+// count). Entry i is selected with McoreSurfaceDesc::adapter = i + 1.
+uint32_t mcore_enumerate_adapters(McoreAdapterInfo* out, uint32_t capacity);
+
+// Whether a context renders on a GPU or fell back to the CPU
+McoreRenderMode mcore_render_mode(McoreContext* ctx);
+
+// The adapter and device limits a context renders with
+McoreStatus mcore_gpu_info(McoreContext* ctx, McoreGpuInfo* out);
+
+// Copy the last presented frame of a headless context into `out` as
+// tightly packed RGBA8 rows, top row first. `len` must be at least
+// width_px * height_px * 4.
+McoreStatus mcore_read_pixels(McoreContext* ctx, uint8_t* out, size_t len);
+
+// Copy the last presented frame into `out` as tightly packed RGBA8 rows, top
+// row first, for screenshots and visual regression tests. `len` must be at
+// least width_px * height_px * 4. Windowed contexts capture the rendered
+// scene before particles and dithering are composited.
+McoreStatus mcore_capture_frame(McoreContext* ctx, uint8_t* out, size_t len);
+
+// mcore_capture_frame written to a PNG file (needs the image-decode feature)
+McoreStatus mcore_capture_frame_png(McoreContext* ctx, const char* path);
+
+void mcore_destroy(McoreContext* ctx);
+
+// New surface size and scale factor, e.g. after the window moved to a
+// display with another scale. Drawing calls take logical pixels, so the host's
+// coordinates stay the same; text is reshaped at the new scale on the next
+// frame (text layout handles keep the scale they were created at).
+void mcore_resize(McoreContext* ctx, const McoreSurfaceDesc* desc);
+
+// Move to another scale factor (the window changed displays) without a
+// resize: the surface keeps its logical size, so its physical size changes
+// by the ratio of the scales, and text is reshaped at the new scale. The host
+// still updates its layer's contents scale. Not callable inside a frame.
+McoreStatus mcore_set_scale_factor(McoreContext* ctx, float scale);
+
+// The scale factor (physical px per logical px) drawing calls are mapped with
+float mcore_get_scale(McoreContext* ctx);
+
+McoreStatus mcore_begin_frame(McoreContext* ctx, double time_seconds);
+
+void mcore_rect_rounded(McoreContext* ctx, const McoreRoundedRect* rect);
+
+int32_t mcore_font_register(McoreContext* ctx, const McoreFontBlob* blob);
+
+// Give an installed font family (e.g. "SF Pro", "Helvetica Neue") a font ID
+// without embedding its data. `weight` (100-900, 0 for regular) and `italic`
+// pick the face used when text requests leave them unset.
+// Returns a font ID, or -1 if the family isn't installed.
+int32_t mcore_font_load_system(McoreContext* ctx, const char* name, float weight, uint8_t italic);
+
+// Number of installed font families (for font pickers)
+int32_t mcore_font_system_family_count(McoreContext* ctx);
+
+// Copy the name of installed family `index` (sorted) into buf, null-terminated.
+// Returns the number of bytes copied (excluding the terminator), or -1 if
+// index is out of range.
+int32_t mcore_font_system_family_name(McoreContext* ctx, int32_t index, uint8_t* buf, int32_t buf_len);
+
+void mcore_text_layout(McoreContext* ctx, const McoreTextReq* req, McoreTextMetrics* out);
+
+// Shape text into a layout handle. Free it with mcore_text_layout_destroy.
+// The handle captures the current scale factor; recreate it after a DPI change.
+McoreTextLayout* mcore_text_layout_create(McoreContext* ctx, const McoreTextReq* req);
+
+// Shape rich text into a layout handle. Spans are byte ranges into req.utf8;
+// text outside every span uses `default_color`. Span colors are baked in, so
+// mcore_text_layout_draw ignores its color argument for these handles.
+McoreTextLayout* mcore_text_layout_create_styled(McoreContext* ctx, const McoreTextReq* req, const McoreTextSpan* spans, size_t span_count, McoreRgba default_color);
+
+// Free a layout handle
+void mcore_text_layout_destroy(McoreTextLayout* layout);
+
+// Get the logical size and line count of a layout handle
+void mcore_text_layout_metrics(const McoreTextLayout* layout, McoreTextMetrics* out);
+
+// Map a logical point (relative to the layout origin) to a byte offset
+int32_t mcore_text_layout_hit_test(const McoreTextLayout* layout, float x, float y);
+
+// Caret geometry for a byte offset in a layout handle (logical, relative to the layout origin)
+void mcore_text_layout_caret(const McoreTextLayout* layout, int32_t byte_offset, McoreCaretRect* out);
+
+// Selection highlight rectangles for a byte range of a layout handle
+// (logical, relative to the layout origin). Writes up to `max_rects` and
+// returns the total count, so a host can retry with a larger buffer.
+int32_t mcore_text_layout_selection_rects(const McoreTextLayout* layout, int32_t start, int32_t end, McoreRect* out, int32_t max_rects);
+
+// Draw a layout handle at a logical position
+void mcore_text_layout_draw(McoreContext* ctx, const McoreTextLayout* layout, float x, float y, McoreRgba color);
+
+// Lay out headings, paragraphs and list items stacked in one handle,
+// wrapping at `width` (logical px, 0 for no wrapping). Free it with
+// mcore_text_block_destroy.
+McoreTextBlock* mcore_text_block_create(McoreContext* ctx, const McoreTextBlockParagraph* paragraphs, size_t paragraph_count, const McoreTextBlockStyle* style, float width);
+
+// Free a text block
+void mcore_text_block_destroy(McoreTextBlock* block);
+
+// Logical size of a text block: its widest line and total height
+void mcore_text_block_size(const McoreTextBlock* block, McoreTextSize* out);
+
+// Where paragraph `index` landed (logical, relative to the block origin;
+// list items start after their marker)
+McoreStatus mcore_text_block_paragraph_rect(const McoreTextBlock* block, uint32_t index, McoreRect* out);
+
+// Map a logical point (relative to the block origin) to the nearest
+// paragraph and a byte offset in its text. Writes the paragraph index to
+// `paragraph` (may be null) and returns the offset, or -1 for an empty block.
+int32_t mcore_text_block_hit_test(const McoreTextBlock* block, float x, float y, int32_t* paragraph);
+
+// Draw a text block at a logical position
+void mcore_text_block_draw(McoreContext* ctx, const McoreTextBlock* block, float x, float y);
+
+// Line count, line height and gutter width of a code request, for sizing
+// its scroll view
+void mcore_code_measure(McoreContext* ctx, const McoreCodeReq* req, McoreCodeMetrics* out);
+
+// Draw the lines of a code request visible in its viewport, whose top left
+// is at logical (x, y). Tokens must be sorted by start and not overlap;
+// text outside them uses req.color. Lines aren't clipped to the viewport's
+// width; push a clip rect for that.
+void mcore_code_draw(McoreContext* ctx, const McoreCodeReq* req, const McoreCodeToken* tokens, size_t token_count, float x, float y);
+
+// Index a document's lines for windowed layout; nothing is shaped until
+// mcore_text_window_set_lines. Lines wrap at req.wrap_width and use its
+// style; req.max_lines is ignored. The text is copied. Free the window with
+// mcore_text_window_destroy.
+McoreTextWindow* mcore_text_window_create(const McoreTextReq* req);
+
+// Free a text window
+void mcore_text_window_destroy(McoreTextWindow* window);
+
+// Hard lines in the document (one more than its line breaks)
+uint32_t mcore_text_window_line_count(const McoreTextWindow* window);
+
+// Line containing a byte offset of the document, for turning a byte range
+// into lines
+uint32_t mcore_text_window_line_at(const McoreTextWindow* window, size_t byte_offset);
+
+// Byte range of a line, without its line break
+McoreStatus mcore_text_window_line_range(const McoreTextWindow* window, uint32_t line, size_t* out_start, size_t* out_end);
+
+// Shape lines first..first + count (clamped to the document) for drawing
+// and hit testing. Lines shaped for the previous range are reused, so
+// scrolling only shapes the lines coming into view. Writes each line's
+// logical height to `heights` (room for `count`, may be null) and returns
+// the range's total height.
+float mcore_text_window_set_lines(McoreContext* ctx, McoreTextWindow* window, uint32_t first, uint32_t count, float* heights);
+
+// Map a logical point (relative to the top of the shaped lines) to a byte
+// offset of the document; -1 when no lines are shaped
+int32_t mcore_text_window_hit_test(const McoreTextWindow* window, float x, float y);
+
+// Draw the shaped lines with the first one's top left at a logical position
+void mcore_text_window_draw(McoreContext* ctx, const McoreTextWindow* window, float x, float y, McoreRgba color);
+
+void mcore_measure_text(McoreContext* ctx, const char* text, float font_size, float max_width, int32_t font_id, McoreTextSize* out);
+
+// Same as mcore_measure_text, but single lines are measured from cached
+// per-word advances instead of a full layout. Cheap enough for speculative
+// measuring during layout; text that wraps or has line breaks takes the
+// full path.
+void mcore_measure_text_fast(McoreContext* ctx, const char* text, float font_size, float max_width, int32_t font_id, McoreTextSize* out);
+
+float mcore_measure_text_to_byte_offset(McoreContext* ctx, const char* text, float font_size, int32_t byte_offset);
+
+// Caret geometry for a byte offset in text shaped from `req` (wrapping at
+// req.wrap_width), relative to the text origin in logical pixels
+void mcore_text_caret(McoreContext* ctx, const McoreTextReq* req, int32_t byte_offset, McoreCaretRect* out);
+
+// Map a logical point (relative to the text origin) to a byte offset in text
+// shaped from `req`, wrapping at req.wrap_width
+int32_t mcore_text_hit_test(McoreContext* ctx, const McoreTextReq* req, float x, float y);
+
+// Selection highlight rectangles for the byte range start..end of text shaped
+// from `req` (logical, relative to the text origin), one or more per line.
+// Writes up to `max_rects` and returns the total count.
+int32_t mcore_text_selection_rects(McoreContext* ctx, const McoreTextReq* req, int32_t start, int32_t end, McoreRect* out, int32_t max_rects);
+
+void mcore_get_text_stats(McoreContext* ctx, McoreTextStats* out);
+
+void mcore_reset_text_stats(McoreContext* ctx);
+
+// Bound this context's shaped-layout cache. max_entries 0 restores the
+// default count; max_bytes 0 removes the byte limit. Glyph rasterization is
+// cached by the renderer and isn't covered by this budget.
+McoreStatus mcore_text_cache_set_budget(McoreContext* ctx, uint32_t max_entries, uint64_t max_bytes);
+
+McoreStatus mcore_text_cache_get_stats(McoreContext* ctx, McoreTextCacheStats* out);
+
+void mcore_text_draw(McoreContext* ctx, const McoreTextReq* req, float x, float y, McoreRgba color);
+
+// Draw rich text with per-range styling in one pass (shaped every call;
+// use mcore_text_layout_create_styled for text that doesn't change)
+void mcore_text_draw_spans(McoreContext* ctx, const McoreTextReq* req, const McoreTextSpan* spans, size_t span_count, float x, float y, McoreRgba default_color);
+
+void mcore_push_clip_rect(McoreContext* ctx, float x, float y, float width, float height);
+
+// Push a clip layer shaped like a rounded rectangle
+void mcore_push_clip_rounded_rect(McoreContext* ctx, float x, float y, float width, float height, float radius);
+
+// Register a vector path for later use (e.g. clipping)
+// `verbs` holds `verb_count` MCORE_PATH_VERB_* bytes, `points` holds `point_count` (x, y) pairs in logical px
+// Returns a path ID (>= 0) or -1 on error
+int32_t mcore_path_create(McoreContext* ctx, const uint8_t* verbs, int32_t verb_count, const float* points, int32_t point_count);
+
+// Free a registered path
+void mcore_path_destroy(McoreContext* ctx, int32_t path_id);
+
+// Push a clip layer shaped like a registered path (offset by x, y, logical px)
+// Returns Err (and pushes nothing) if the path ID is unknown, so the caller must not pop
+McoreStatus mcore_push_clip_path(McoreContext* ctx, int32_t path_id, float x, float y);
+
+// Pop the innermost clip; with none pushed this frame the pop is ignored and
+// counted in McoreFrameStats::layer_warnings
+void mcore_pop_clip(McoreContext* ctx);
+
+// Push a backdrop blur layer: what's been drawn so far is blurred inside the
+// rounded rect, and what's drawn until the matching mcore_pop_clip is
+// clipped to it (frosted-glass panels). `blur_radius` is the Gaussian's
+// standard deviation in logical px. Not allowed inside clips or fragments.
+McoreStatus mcore_push_blur_layer(McoreContext* ctx, float x, float y, float width, float height, float corner_radius, float blur_radius);
+
+// Push a color filter layer: everything drawn until the matching pop
+// (images, fragments, videos and custom textures included) is transformed by
+// `matrix`, a 4x5 row-major color matrix (20 floats)
+void mcore_push_color_filter(McoreContext* ctx, const float* matrix);
+
+// Push a grayscale filter layer (amount 0.0 = unchanged, 1.0 = fully desaturated)
+// Handy for disabled-state regions
+void mcore_push_grayscale_filter(McoreContext* ctx, float amount);
+
+// Pop the innermost color filter layer
+void mcore_pop_color_filter(McoreContext* ctx);
+
+void mcore_render_commands(McoreContext* ctx, const McoreDrawCommand* commands, int32_t count);
+
+// Draw a v2 command stream: `len` bytes of records (see McoreCmdHeader and
+// command_stream.rs). A malformed stream is rejected before anything is drawn.
+McoreStatus mcore_render_commands_v2(McoreContext* ctx, const uint8_t* data, size_t len, uint32_t version);
+
+// Register a gradient brush, laid out relative to the bounds of the shape it
+// paints. Draw commands reference it by ID (GradientRing: font_id).
+// Returns a gradient ID (>= 0) or -1 on error
+int32_t mcore_gradient_create(McoreContext* ctx, const McoreGradientDesc* desc);
+
+// Free a registered gradient
+void mcore_gradient_destroy(McoreContext* ctx, int32_t gradient_id);
+
+// Create an append-only log view. Entries wrap at `wrap_width` (logical px);
+// beyond `max_entries` (0 = unbounded) the oldest are dropped.
+// Returns a view ID (>= 0) or -1 on error.
+int32_t mcore_log_view_create(McoreContext* ctx, float font_size, int32_t font_id, float wrap_width, uint32_t max_entries);
+
+void mcore_log_view_destroy(McoreContext* ctx, int32_t view_id);
+
+// Shape and append one entry (it may span several lines)
+McoreStatus mcore_log_view_append(McoreContext* ctx, int32_t view_id, const char* utf8, McoreRgba color);
+
+void mcore_log_view_clear(McoreContext* ctx, int32_t view_id);
+
+// Change the wrap width (logical px). Reshapes every entry, so call it on
+// resize rather than every frame.
+void mcore_log_view_set_width(McoreContext* ctx, int32_t view_id, float wrap_width);
+
+// Set the tab stops entries are laid out with (see McoreTextReq), reshaping
+// the entries already appended
+void mcore_log_view_set_tabs(McoreContext* ctx, int32_t view_id, float tab_width, const float* tab_stops, uint32_t tab_stop_count);
+
+// Height of all kept entries (logical px; 0 for unknown IDs)
+float mcore_log_view_content_height(McoreContext* ctx, int32_t view_id);
+
+// Draw the entries visible in a `height`-tall window at (x, y) whose
+// content is scrolled by `scroll_y` (logical px), clipped to the window.
+// Only the visible entries are encoded, however long the log gets.
+McoreStatus mcore_log_view_draw(McoreContext* ctx, int32_t view_id, float x, float y, float width, float height, float scroll_y);
+
+// Start recording a fragment. All drawing calls until mcore_fragment_end
+// (including mcore_render_commands) are captured instead of drawn. Frames
+// can't begin or present until the recording ends.
+McoreStatus mcore_fragment_begin(McoreContext* ctx);
+
+// Finish recording and return the fragment ID (>= 0), or -1 if not recording
+int32_t mcore_fragment_end(McoreContext* ctx);
+
+// Replay a recorded fragment into the current frame with a transform
+// The transform position is in logical pixels, like mcore_image_draw
+void mcore_fragment_draw(McoreContext* ctx, int32_t fragment_id, const McoreImageTransform* transform);
+
+// Free a recorded fragment
+void mcore_fragment_destroy(McoreContext* ctx, int32_t fragment_id);
+
+// Render a recorded fragment once, offscreen, into a new image covering
+// (0, 0, width, height) of the fragment (logical px, rasterized at the
+// current scale). Expensive static content (charts, blurred backgrounds) can
+// then be drawn as one image blit. Returns an image ID (release it with
+// mcore_image_release) or -1 on error; snapshot again when the content changes.
+int32_t mcore_fragment_to_image(McoreContext* ctx, int32_t fragment_id, float width, float height);
+
+// Fill the surface behind every following frame with `color`, in place of
+// the clear color passed to mcore_end_frame_present and mcore_submit_frame,
+// for hosts with one fixed background. Components must be finite.
+McoreStatus mcore_set_clear_color(McoreContext* ctx, McoreRgba color);
+
+// Render and present the frame over `clear` (or the mcore_set_clear_color
+// color, once set), which is used exactly as given: it doesn't depend on
+// the frame time
+McoreStatus mcore_end_frame_present(McoreContext* ctx, McoreRgba clear);
+
+// Build, render and present frames on an engine-owned thread (see
+// render_thread.rs). Until mcore_render_thread_stop, frames are queued with
+// mcore_submit_frame and other threads can't begin frames or draw.
+McoreStatus mcore_render_thread_start(McoreContext* ctx);
+
+// Present the last queued frame, then end the render thread; frames are
+// driven by the host again. Blocks until the thread exits.
+McoreStatus mcore_render_thread_stop(McoreContext* ctx);
+
+// Queue a frame for the render thread: `len` bytes of v2 command stream,
+// copied before returning, drawn at `time_seconds` over `clear`. Callable
+// from any thread. A frame still waiting when the next arrives is replaced.
+// The stream is validated here; failures while rendering go to the log
+// callback.
+McoreStatus mcore_submit_frame(McoreContext* ctx, double time_seconds, const uint8_t* data, size_t len, uint32_t version, McoreRgba clear);
+
+// Block until every frame queued so far is presented (e.g. before reading
+// back a frame or resizing in step with the window)
+McoreStatus mcore_render_thread_flush(McoreContext* ctx);
+
+// Start recording command buffers to `path` (see replay.rs). Every frame's
+// mcore_render_commands calls and clear color are captured until
+// mcore_replay_record_stop writes the file.
+McoreStatus mcore_replay_record_start(McoreContext* ctx, const char* path);
+
+// Stop recording and write the file. Returns the number of frames written,
+// or -1 if not recording or the write failed.
+int32_t mcore_replay_record_stop(McoreContext* ctx);
+
+// Write the current frame's command buffers (the frame being built, or the
+// last presented one between frames) to `path` as a one-frame recording.
+// `mcore-replay` renders it headlessly; mcore_scene_load draws it back.
+McoreStatus mcore_scene_dump(McoreContext* ctx, const char* path);
+
+// Draw the commands of a scene dump (or the first frame of a recording)
+// into the current frame. Its clear color and surface size are not applied.
+McoreStatus mcore_scene_load(McoreContext* ctx, const char* path);
+
+// Write a diagnostic bundle into `dir` when the engine hits an unrecoverable
+// error (GPU device lost, repeated render failures, a panic), then call
+// `callback` with the reason and bundle path. A null dir turns it off.
+McoreStatus mcore_set_crash_dump(McoreContext* ctx, const char* dir, void (*callback)(const char*, const char*));
+
+// Receive engine diagnostics as (level, message): 0 debug, 1 info, 2 warn,
+// 3 error. Without a callback they go to stderr. Runs without the engine
+// lock held; the message is only valid during the call.
+void mcore_set_log_callback(McoreContext* ctx, void (*callback)(uint8_t, const char*));
+
+// Log a warning with a per-phase breakdown for every frame whose build and
+// render take longer than `budget_ms` (see watchdog.rs). 0 turns it off.
+// Meant for development builds of the host.
+void mcore_set_frame_budget(McoreContext* ctx, float budget_ms);
+
+// Draw an engine-side debug overlay over every frame: a bitmask of
+// MCORE_DEBUG_OVERLAY_* flags (see overlay.rs), 0 turns it off
+void mcore_set_debug_overlay(McoreContext* ctx, uint32_t flags);
+
+// Fill `out` with the last rendered frame's statistics. Scene counts cover
+// the scene being built, so call it between mcore_end_frame_present and the
+// next mcore_begin_frame to get the presented frame's.
+McoreStatus mcore_frame_stats(McoreContext* ctx, McoreFrameStats* out);
+
+// Enable or disable dithering in the final blit pass.
+// Useful for dark themes with large gradients, which band visibly on 8-bit displays.
+void mcore_set_dither(McoreContext* ctx, uint8_t enabled);
+
+// Choose how frames are antialiased (McoreAaMode value). MSAA pipelines are
+// built the first time they're chosen; if the device can't run them this
+// returns Err and the mode is unchanged.
+McoreStatus mcore_set_aa_mode(McoreContext* ctx, uint8_t mode);
+
+// The current McoreAaMode value
+uint8_t mcore_get_aa_mode(McoreContext* ctx);
+
+// Choose how this context's text is fit to the pixel grid. Hinting with
+// whole-pixel positions makes small text crisper on low-DPI screens; the
+// default (no hinting, unquantized positions) keeps exact shapes and
+// spacing, which suits high-DPI screens and animated text.
+McoreStatus mcore_set_text_rendering(McoreContext* ctx, const McoreTextRendering* rendering);
+
+// The context's current text rendering options
+McoreStatus mcore_get_text_rendering(McoreContext* ctx, McoreTextRendering* out);
+
+// Choose the color space (an McoreColorSpace value) the window's colors are
+// shown in: sRGB (the default) or Display P3, where colors and images are
+// read as P3 values. Headless contexts and platforms without color-managed
+// windows return Err with MCORE_ERROR_UNSUPPORTED and stay in sRGB.
+McoreStatus mcore_set_color_space(McoreContext* ctx, uint8_t space);
+
+// The window's color space (sRGB for headless contexts)
+McoreColorSpace mcore_get_color_space(McoreContext* ctx);
+
+// 1 if the main display can show Display P3 colors (macOS), 0 otherwise
+uint8_t mcore_display_supports_p3(void);
+
+// Skip rendering frames whose command buffers match the last presented
+// frame: mcore_end_frame_present returns MCORE_FRAME_SKIPPED and the previous
+// frame stays on screen (see damage.rs). Frames with direct drawing calls
+// are always presented. Off by default.
+void mcore_set_damage_tracking(McoreContext* ctx, uint8_t enabled);
+
+// Handle a text input event for a specific widget ID
+// Returns true if the text changed
+uint8_t mcore_text_input_event(McoreContext* ctx, uint64_t id, const McoreTextEvent* event);
+
+// mcore_text_input_event, then scroll the input's viewport (see
+// mcore_text_input_set_viewport) so the caret stays visible, and report the
+// offset to draw at
+uint8_t mcore_text_input_event_ex(McoreContext* ctx, uint64_t id, const McoreTextEvent* event, McoreTextEventResult* out);
+
+// Give a text input a viewport (logical px) the engine keeps its caret
+// visible in, laid out at font_size and wrapped at wrap_width (0 = a single
+// line). The scroll offset follows the caret after every
+// mcore_text_input_event_ex, and immediately here (e.g. after a resize).
+// A width or height of 0 removes the viewport.
+void mcore_text_input_set_viewport(McoreContext* ctx, uint64_t id, float width, float height, float font_size, float wrap_width);
+
+// Draw a text input from its engine state in one call: content (masked if
+// secure), selection, underlined IME preedit and a blinking caret, scrolled
+// to keep the caret visible. Also records the placement for the _root
+// queries. The caret blinks on frame time and requests redraws for its
+// transitions, so hosts only need to draw frames when asked.
+void mcore_text_input_draw(McoreContext* ctx, uint64_t id, const McoreTextInputStyle* style);
+
+// A text input's scroll offset (logical px; 0, 0 without a viewport)
+void mcore_text_input_get_scroll(McoreContext* ctx, uint64_t id, float* out_x, float* out_y);
+
+// Single-line fields: keep the caret inside a field `width` wide (logical px,
+// excluding padding) and return the horizontal offset to draw the content
+// at (text x = field content x - offset). Sets a one-line viewport, so
+// later events made with mcore_text_input_event_ex follow the caret too.
+float mcore_text_input_scroll_x(McoreContext* ctx, uint64_t id, float width, float font_size);
+
+// Get the current text content for a widget ID
+// Returns the number of bytes written (excluding null terminator)
+int32_t mcore_text_input_get(McoreContext* ctx, uint64_t id, uint8_t* buf, int32_t buf_len);
+
+// Get the cursor position (byte offset) for a widget ID
+int32_t mcore_text_input_cursor(McoreContext* ctx, uint64_t id);
+
+// Set the text content for a widget ID
+void mcore_text_input_set(McoreContext* ctx, uint64_t id, const char* text);
+
+// Set how many undo steps a text input keeps (default 100, 0 disables undo)
+void mcore_text_input_set_undo_limit(McoreContext* ctx, uint64_t id, uint32_t limit);
+
+// Make a text input a password field: it draws (and hit tests) as bullets
+// while editing works on the real content, and cut/copy are disabled. With
+// reveal_ms > 0 each typed character shows for that long before masking.
+void mcore_text_input_set_secure(McoreContext* ctx, uint64_t id, uint8_t secure, uint32_t reveal_ms);
+
+// Limit what typing, pasting and IME commits can put in a text input:
+// max_bytes / max_graphemes (0 = unlimited) and a character class
+// (MCORE_CHARS_* in mcore.h). Disallowed characters and whatever doesn't
+// fit are dropped; see mcore_text_input_take_rejection. Content set with
+// mcore_text_input_set is not constrained.
+void mcore_text_input_set_constraints(McoreContext* ctx, uint64_t id, uint32_t max_bytes, uint32_t max_graphemes, uint8_t char_class);
+
+// Why the latest insertion into a text input was cut short since the last
+// call (MCORE_REJECTED_*; 0 if nothing was rejected). Hosts poll this
+// after events to flash or shake the field.
+uint8_t mcore_text_input_take_rejection(McoreContext* ctx, uint64_t id);
+
+// Keyboard type and autocorrection hints for a text input, kept with its
+// state. Hosts read them back with mcore_text_input_get_hints on focus.
+void mcore_text_input_set_hints(McoreContext* ctx, uint64_t id, uint8_t keyboard, uint8_t autocorrect);
+
+// Paragraph direction of a text input (McoreTextDirection): decides which
+// way the arrow keys move and how wrapped content is aligned. Auto (the
+// default) follows the content's first strong character.
+void mcore_text_input_set_direction(McoreContext* ctx, uint64_t id, uint8_t direction);
+
+// Whether a text input's content currently lays out right to left, e.g. to
+// align a host-drawn field (0 for unknown IDs)
+uint8_t mcore_text_input_is_rtl(McoreContext* ctx, uint64_t id);
+
+// Input context configuration for a text input (defaults for unknown IDs)
+McoreStatus mcore_text_input_get_hints(McoreContext* ctx, uint64_t id, McoreTextInputHints* out);
+
+// Get selection range for a text input widget
+// Returns true if there is a selection, and fills out_start and out_end with the byte offsets
+uint8_t mcore_text_input_get_selection(McoreContext* ctx, uint64_t id, int32_t* out_start, int32_t* out_end);
+
+// Set cursor position and optionally start a selection
+void mcore_text_input_set_cursor_pos(McoreContext* ctx, uint64_t id, int32_t byte_offset, uint8_t extend_selection);
+
+// Get the selected text (returns length, copies into buffer)
+int32_t mcore_text_input_get_selected_text(McoreContext* ctx, uint64_t id, char* buf, int32_t buf_len);
+
+// Start a selection at a specific position (for mouse down)
+// Sets both cursor and anchor to the same position, clearing any existing selection
+void mcore_text_input_start_selection(McoreContext* ctx, uint64_t id, int32_t byte_offset);
+
+// Set the mouse pointer shape (an McoreCursor value; NSCursor on macOS, call
+// on the main thread). AppKit resets it when the pointer crosses cursor
+// rects, so set it again on mouse moves. Elsewhere the shape is only
+// recorded for mcore_get_cursor.
+McoreStatus mcore_set_cursor(uint8_t cursor);
+
+// The shape last passed to mcore_set_cursor (Arrow before any)
+McoreCursor mcore_get_cursor(void);
+
+// Put plain text on the system clipboard
+McoreStatus mcore_clipboard_set_text(const char* text);
+
+// Copy the clipboard's text into buf, null-terminated and truncated to fit.
+// Returns the full length in bytes, or -1 if the clipboard holds no text.
+int32_t mcore_clipboard_get_text(uint8_t* buf, int32_t buf_len);
+
+// Set IME preedit (composition) text for a text input
+void mcore_ime_set_preedit(McoreContext* ctx, uint64_t id, const McoreImePreedit* preedit);
+
+// Commit IME text (finalize composition)
+void mcore_ime_commit(McoreContext* ctx, uint64_t id, const char* text);
+
+// Clear IME preedit state
+void mcore_ime_clear_preedit(McoreContext* ctx, uint64_t id);
+
+// Get IME preedit text if any
+// Returns 1 if there is preedit text, 0 otherwise
+uint8_t mcore_ime_get_preedit(McoreContext* ctx, uint64_t id, char* buf, int32_t buf_len, int32_t* out_cursor_offset);
+
+// Get the text a text input should draw: its content with any preedit
+// spliced in at the caret, as bullets for secure inputs. Writes the caret
+// and preedit byte range within that text (preedit_start == preedit_end
+// when not composing).
+// Returns the number of bytes written (excluding null terminator)
+int32_t mcore_text_input_get_display(McoreContext* ctx, uint64_t id, uint8_t* buf, int32_t buf_len, int32_t* out_caret, int32_t* out_preedit_start, int32_t* out_preedit_end);
+
+// Selection as a byte range of the text from mcore_text_input_get_display
+// (which differs from content offsets in secure inputs and while composing).
+// Returns 1 if there is a selection.
+uint8_t mcore_text_input_get_display_selection(McoreContext* ctx, uint64_t id, int32_t* out_start, int32_t* out_end);
+
+// Content byte offset under logical x (relative to the text origin) in a
+// single-line text input drawn with the default font at font_size. Hit
+// tests what is drawn, so it works for secure inputs and during composition;
+// pass the result to mcore_text_input_set_cursor_pos/start_selection.
+int32_t mcore_text_input_hit_test(McoreContext* ctx, uint64_t id, float font_size, float x);
+
+// Caret rectangle of a single-line text input, in logical pixels relative
+// to its text origin, following the IME caret while composing. Hosts add the
+// text origin and convert to screen coordinates for the candidate window
+// (NSTextInputClient firstRectForCharacterRange).
+McoreStatus mcore_text_input_caret_rect(McoreContext* ctx, uint64_t id, float font_size, McoreCaretRect* out);
+
+// Record where a text input's text is drawn this frame: its origin in the
+// current local space of the hit transform stack (mcore_hit_push_transform),
+// which is captured with it. The _root queries below then work in window
+// coordinates, so inputs inside a zoomed or panned viewport stay usable.
+void mcore_text_input_set_origin(McoreContext* ctx, uint64_t id, float x, float y);
+
+// Caret of a text input in root (window) logical coordinates, scaled by the
+// transform it was drawn under (see mcore_text_input_set_origin)
+McoreStatus mcore_text_input_caret_rect_root(McoreContext* ctx, uint64_t id, float font_size, McoreCaretRect* out);
+
+// Content byte offset under a root (window) logical point, mapped through
+// the transform the input was drawn under. Returns -1 if the input has no
+// origin yet.
+int32_t mcore_text_input_hit_test_root(McoreContext* ctx, uint64_t id, float font_size, float x, float y);
+
+// Feed a raw pointer event (logical pixels, time in seconds) to the gesture recognizer
+// Long-presses also fire from mcore_begin_frame when the pointer is held still
+void mcore_gesture_pointer_event(McoreContext* ctx, McorePointerPhase phase, float x, float y, double time_seconds);
+
+// Pop the next recognized gesture
+// Returns 1 and fills `out` if a gesture was pending, 0 otherwise
+uint8_t mcore_gesture_next(McoreContext* ctx, McoreGesture* out);
+
+// Register a hit region for this frame (logical pixels, in the current hit transform)
+// The active clip (from push_clip calls/commands) and transform are captured.
+// Higher z wins; equal z resolves to the region registered last.
+void mcore_hit_region(McoreContext* ctx, uint64_t id, float x, float y, float width, float height, int32_t z);
+
+// Push a translation + uniform scale applied to subsequently registered hit regions
+void mcore_hit_push_transform(McoreContext* ctx, float dx, float dy, float scale);
+
+void mcore_hit_pop_transform(McoreContext* ctx);
+
+// The composed hit transform stack: root = local * scale + (dx, dy).
+// Text query results (carets, selection rects, layout metrics) are local to
+// the text origin; hosts map them with this to get window coordinates.
+void mcore_hit_current_transform(McoreContext* ctx, McoreHitTransform* out);
+
+// Find the topmost region under a point (window logical pixels): the one
+// with the highest z, then the latest registered, among regions whose clip
+// contains the point. Between frames this sees the last frame's regions.
+// Returns 1 and fills `out` on a hit, 0 if no region is there.
+uint8_t mcore_hit_test(McoreContext* ctx, float x, float y, McoreHitResult* out);
+
+// Set the callback receiving routed pointer events
+// Callback signature: (region_id, phase, local_x, local_y)
+// Phases: 0 = Down, 1 = Move, 2 = Up, 3 = Cancel, 4 = Enter, 5 = Leave
+void mcore_pointer_set_callback(McoreContext* ctx, void (*callback)(uint64_t, uint8_t, float, float));
+
+// Route a pointer event (window logical pixels) through the hit regions
+// The callback is invoked after the engine lock is released, so it may call back into the engine.
+void mcore_pointer_event(McoreContext* ctx, McorePointerPhase phase, float x, float y);
+
+// Set a scroll container's viewport and content sizes (logical pixels),
+// creating it on first use
+void mcore_scroll_set_extent(McoreContext* ctx, uint64_t id, float viewport_w, float viewport_h, float content_w, float content_h);
+
+// Scroll immediately by a delta (mouse wheel), clamped, cancelling momentum
+void mcore_scroll_by(McoreContext* ctx, uint64_t id, float dx, float dy);
+
+// Feed a scroll event: the content delta (logical px, positive scrolls
+// toward the end) with the gesture phase and the event time (seconds, on the
+// mcore_begin_frame clock). Trackpad gestures drag the content with
+// rubber-banding and fling with the engine's momentum when they end, so hosts
+// should drop the OS's own momentum events. Edge callbacks run after the lock
+// is released.
+void mcore_scroll_event(McoreContext* ctx, uint64_t id, float dx, float dy, McoreScrollPhase phase, double time_seconds);
+
+// Snap one axis to multiples of an item extent (0 clears snapping)
+void mcore_scroll_set_snap_interval(McoreContext* ctx, uint64_t id, McoreScrollAxis axis, float interval);
+
+// Snap one axis to explicit offsets (count 0 clears snapping)
+void mcore_scroll_set_snap_offsets(McoreContext* ctx, uint64_t id, McoreScrollAxis axis, const float* offsets, size_t count);
+
+void mcore_scroll_drag_begin(McoreContext* ctx, uint64_t id);
+
+// Update a drag with the pointer translation since the drag began
+// (the dx/dy of a pan gesture). Edge callbacks run after the lock is released.
+void mcore_scroll_drag_update(McoreContext* ctx, uint64_t id, float translation_x, float translation_y);
+
+// End a drag with the pointer velocity (logical px/s) to start a fling.
+// Momentum advances in mcore_begin_frame; with reduced motion the content settles instantly.
+void mcore_scroll_drag_end(McoreContext* ctx, uint64_t id, float velocity_x, float velocity_y);
+
+// Read a container's offset (may be outside the content while overscrolled).
+// Returns 1 if the container exists, 0 otherwise.
+uint8_t mcore_scroll_get(McoreContext* ctx, uint64_t id, McoreScrollState* out);
+
+void mcore_scroll_remove(McoreContext* ctx, uint64_t id);
+
+// Set the callback fired when a container starts overscrolling past an edge
+void mcore_scroll_set_edge_callback(McoreContext* ctx, void (*callback)(uint64_t, uint8_t, float));
+
+// The style every field of which is at its CSS default (auto sizes, no grow,
+// shrink 1, row direction)
+McoreLayoutStyle mcore_layout_style_default(void);
+
+// Create layout node `id` (a host-chosen ID) or replace its style
+McoreStatus mcore_layout_node_set(McoreContext* ctx, uint64_t id, const McoreLayoutStyle* style);
+
+// Replace a node's children, in order. A child that had another parent moves.
+McoreStatus mcore_layout_set_children(McoreContext* ctx, uint64_t id, const uint64_t* children, size_t count);
+
+// Remove a node; its children stay, without a parent
+void mcore_layout_node_remove(McoreContext* ctx, uint64_t id);
+
+// Lay out the tree under `root_id` to fill `width` x `height` (logical px)
+McoreStatus mcore_layout_compute(McoreContext* ctx, uint64_t root_id, float width, float height);
+
+// Read a node's rect from the last mcore_layout_compute of its root,
+// relative to that root. Returns 1 and fills `out` if it has one, 0 otherwise.
+uint8_t mcore_layout_get(McoreContext* ctx, uint64_t id, McoreRect* out);
+
+McoreUiTheme mcore_ui_theme_default(void);
+
+void mcore_ui_set_theme(McoreContext* ctx, const McoreUiTheme* theme);
+
+// Start a column of widgets `width` wide at x, y, with this frame's pointer.
+// Call inside a frame; widgets draw into it as they're called.
+McoreStatus mcore_ui_begin(McoreContext* ctx, float x, float y, float width, const McoreUiInput* input);
+
+// A wrapped paragraph of text
+void mcore_ui_label(McoreContext* ctx, const char* text);
+
+// A push button sized to its label. Returns MCORE_UI_* response flags;
+// MCORE_UI_CLICKED is set on the frame it's clicked.
+uint32_t mcore_ui_button(McoreContext* ctx, uint64_t id, const char* label);
+
+// A single-line text field `width` wide (0 fills the column), backed by the
+// mcore_text_input_* state of the same ID. Pressing it focuses it; send key
+// events for mcore_ui_focused's ID with mcore_text_input_event_ex and read
+// the text with mcore_text_input_get. Returns MCORE_UI_* response flags.
+uint32_t mcore_ui_text_input(McoreContext* ctx, uint64_t id, float width);
+
+// Finish the column. Returns the height its widgets took (logical px).
+float mcore_ui_end(McoreContext* ctx);
+
+// The focused widget's ID, 0 if none is focused
+uint64_t mcore_ui_focused(McoreContext* ctx);
+
+// Set the callback told when the engine needs a frame.
+// Callback signature: callback(time_seconds) on the mcore_begin_frame clock;
+// a time at or before the last frame means "next vsync". It runs without the
+// engine lock held, on whichever thread made the triggering call.
+void mcore_set_redraw_callback(McoreContext* ctx, void (*callback)(double));
+
+// Request a frame at (or after) an absolute time
+void mcore_request_frame_at(McoreContext* ctx, double time_seconds);
+
+// Request a frame as soon as possible
+void mcore_request_redraw(McoreContext* ctx);
+
+// Earliest time a frame is needed, or -1 if the engine is idle (for hosts that poll)
+double mcore_next_frame_time(McoreContext* ctx);
+
+// Report the refresh rate of the display showing the surface (Hz), e.g. when
+// the window moves between monitors or ProMotion switches between 120 and 60.
+// Engine animations step at the new rate and the refresh rate callback fires
+// if it changed.
+void mcore_set_refresh_rate(McoreContext* ctx, float hz);
+
+// Refresh rate of the current display (Hz), 60 until the host reports one
+float mcore_get_refresh_rate(McoreContext* ctx);
+
+// Set the callback told when the refresh rate changes, so host tickers can
+// adapt their step size. Callback signature: callback(hz); runs without the
+// engine lock held.
+void mcore_set_refresh_rate_callback(McoreContext* ctx, void (*callback)(float));
+
+// Tell the engine whether the window is fully occluded or minimized. While
+// occluded, mcore_end_frame_present skips rendering and presenting (state
+// updates and frame building still work) and animations stop requesting
+// frames. Becoming visible requests a frame with the up-to-date state.
+void mcore_set_occluded(McoreContext* ctx, uint8_t occluded);
+
+uint8_t mcore_is_occluded(McoreContext* ctx);
+
+// Create a texture-backed widget of the given physical size.
+// `callback(user_data, target)` runs inside mcore_end_frame_present with the
+// engine lock held: render into target->native_texture, commit your command
+// buffer (waitUntilScheduled) and return without calling back into the engine.
+// Returns a widget ID (>= 0) or -1 on error.
+int32_t mcore_custom_texture_create(McoreContext* ctx, uint32_t width_px, uint32_t height_px, McoreTextureCallback callback, void* user_data);
+
+// Recreate a widget's texture at a new physical size
+McoreStatus mcore_custom_texture_resize(McoreContext* ctx, int32_t id, uint32_t width_px, uint32_t height_px);
+
+void mcore_custom_texture_destroy(McoreContext* ctx, int32_t id);
+
+// Composite a widget's texture into a logical-pixel rectangle
+void mcore_custom_texture_draw(McoreContext* ctx, int32_t id, float x, float y, float width, float height);
+
+// Create a video to submit frames to. It draws nothing until the first
+// frame arrives. Returns a video ID (>= 0) or -1 on error.
+int32_t mcore_video_create(McoreContext* ctx);
+
+// Make `frame` the one the video shows from the next rendered frame. The
+// pixels are copied, so the host's buffer can be reused once this returns.
+// Can be called from any thread (e.g. a display link callback).
+McoreStatus mcore_video_frame_submit(McoreContext* ctx, int32_t video_id, const McoreVideoFrame* frame);
+
+// Draw a video's latest frame into a logical-pixel rect, fitted per
+// MCORE_VIDEO_FIT_*, like an MCORE_CMD_VIDEO record
+void mcore_video_draw(McoreContext* ctx, int32_t video_id, float x, float y, float width, float height, uint32_t fit);
+
+void mcore_video_destroy(McoreContext* ctx, int32_t video_id);
+
+// Start a particle emitter. Bursts remove themselves once every particle has
+// died; looping emitters run until mcore_particles_stop. With reduce motion
+// on, bursts are dropped and looping emitters wait until it's off.
+// Returns an emitter ID (>= 0) or -1 on error.
+int32_t mcore_particles_emit(McoreContext* ctx, const McoreParticleEmitter* desc);
+
+// Stop an emitter immediately
+void mcore_particles_stop(McoreContext* ctx, int32_t id);
+
+// Initialize the accessibility adapter for a given NSView
+// This should be called after creating the window but before showing it
+//
+// # Safety
+// ns_view must be a valid pointer to an NSView instance
+void mcore_a11y_init(McoreContext* ctx, void* ns_view);
+
+// Update the accessibility tree
+// Zig builds an array of nodes and sends them all at once
+void mcore_a11y_update(McoreContext* ctx, const McoreA11yNode* nodes, int32_t node_count, uint64_t root_id, uint64_t focus_id);
+
+// Move accessibility focus to a node of the last tree (e.g. after keyboard
+// navigation) without resending the tree
+void mcore_a11y_set_focus(McoreContext* ctx, uint64_t focus_id);
+
+// Set callback for accessibility actions (focus, click, etc.)
+void mcore_a11y_set_action_callback(void (*callback)(uint64_t, uint8_t));
+
+// Re-query the OS accessibility preferences and return them
+// Call when the app is notified of a change (e.g. on
+// NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification)
+void mcore_a11y_get_preferences(McoreContext* ctx, McoreA11yPrefs* out);
+
+// Override the accessibility preferences (for testing, or platforms the engine can't query)
+void mcore_a11y_set_preferences(McoreContext* ctx, const McoreA11yPrefs* prefs);
+
+// Multiplier engine-driven animations apply to their motion
+// Returns 0.0 when reduce-motion is on (animations jump to their end state), else 1.0
+float mcore_animation_scale(McoreContext* ctx);
+
+// Parse a CSS color string into McoreColor
+// Supports: oklch(), rgb(), rgba(), hex (#rrggbb), named colors, hsl(), lab(), lch(), etc.
+// Returns 1 on success, 0 on parse error
+uint8_t mcore_color_parse(const uint8_t* css_str, size_t len, McoreColor* out);
+
+// Interpolate between two colors using perceptually-correct Oklab space
+// This produces much better results than naive RGB interpolation
+void mcore_color_lerp(const McoreColor* a, const McoreColor* b, float t, McoreColor* out);
+
+// Convert a color given in `space` to the values a window in `target`
+// expects (see mcore_get_color_space). Colors already in `target` pass
+// through unchanged, so wide-gamut P3 values survive in P3 windows;
+// out-of-gamut results (e.g. saturated P3 reds in sRGB) are clamped.
+McoreStatus mcore_color_convert(uint8_t space, uint8_t target, const McoreColor* input, McoreColor* out);
+
+// Convert from RGBA8 (0-255) to McoreColor (0.0-1.0)
+void mcore_color_from_rgba8(uint8_t r, uint8_t g, uint8_t b, uint8_t a, McoreColor* out);
+
+// Register an image and copy pixel data to Rust
+// Returns an image ID (>= 0) or -1 on error
+// The `data` pointer can be freed after this function returns
+int32_t mcore_image_register(McoreContext* ctx, const McoreImageDesc* desc);
+
+// Increment reference count for an image
+void mcore_image_retain(McoreContext* ctx, int32_t image_id);
+
+// Decrement reference count, free when 0 (or keep cached within the budget,
+// see mcore_image_set_budget)
+void mcore_image_release(McoreContext* ctx, int32_t image_id);
+
+// Draw an image with transform
+void mcore_image_draw(McoreContext* ctx, int32_t image_id, const McoreImageTransform* transform);
+
+// Draw an image over a rect with its sampling and tiling options, like an
+// MCORE_CMD_IMAGE record (logical px)
+McoreStatus mcore_image_draw_rect(McoreContext* ctx, const McoreCmdImage* cmd);
+
+// Load and register an image from a file path (JPEG, PNG, etc.), turned
+// upright per its EXIF orientation
+// Returns image info (id, width, height). id is -1 on error.
+McoreImageInfo mcore_image_load_file(McoreContext* ctx, const char* path);
+
+// mcore_image_load_file, shrunk while decoding so neither side exceeds
+// `max_dimension` (aspect ratio kept; smaller images are left as they are).
+// List thumbnails of large photos then cost a fraction of the memory and
+// upload.
+McoreImageInfo mcore_image_load_thumbnail(McoreContext* ctx, const char* path, uint32_t max_dimension);
+
+// Get image dimensions by ID
+// Returns 1 on success, 0 if image not found
+uint8_t mcore_image_get_info(McoreContext* ctx, int32_t image_id, McoreImageInfo* out);
+
+// Register encoded image bytes (JPEG, PNG, ...) and decode them on a
+// background thread. Returns the image's ID (-1 on error) at once; until
+// decoding finishes, draws of it fill their rect with the placeholder color.
+// `callback` (optional) then runs on the decode thread with the ID and size,
+// or a size of 0 x 0 if decoding failed, in which case the ID is freed.
+// `data` can be freed after this returns.
+int32_t mcore_image_register_async(McoreContext* ctx, const uint8_t* data, size_t len, void (*callback)(int32_t, uint32_t, uint32_t, void*), void* user_data);
+
+// mcore_image_register_async, shrunk while decoding so neither side exceeds
+// `max_dimension` (see mcore_image_load_thumbnail)
+int32_t mcore_image_register_thumbnail_async(McoreContext* ctx, const uint8_t* data, size_t len, uint32_t max_dimension, void (*callback)(int32_t, uint32_t, uint32_t, void*), void* user_data);
+
+// Color drawn in place of images still decoding (transparent by default)
+void mcore_image_set_placeholder(McoreContext* ctx, McoreRgba color);
+
+// Cap the decoded pixels the image cache holds. Released images stay cached
+// (and can be retained again) until the budget needs their room; then they
+// are evicted least recently drawn first, followed by mip levels. Images in
+// use are never evicted. The default, 0, frees released images at once.
+// Atlas pages aren't counted: they never shrink, so they take up to 32 MiB
+// on top of the budget (see atlas_pages).
+void mcore_image_set_budget(McoreContext* ctx, uint64_t bytes);
+
+McoreStatus mcore_image_cache_stats(McoreContext* ctx, McoreImageCacheStats* out);
+
+// Give memory back when the system runs low: MCORE_MEMORY_PRESSURE_WARNING
+// frees released images, MCORE_MEMORY_PRESSURE_CRITICAL also drops every
+// mip level. Call it from the platform's memory warning (e.g. a
+// DISPATCH_SOURCE_TYPE_MEMORYPRESSURE source). Returns the bytes freed.
+uint64_t mcore_memory_pressure(McoreContext* ctx, uint32_t level);
+
+#ifdef __cplusplus
+}  // extern "C"
+#define ZELLO_STATIC_ASSERT static_assert
+#define ZELLO_ALIGNOF alignof
+#else
+#define ZELLO_STATIC_ASSERT _Static_assert
+#define ZELLO_ALIGNOF _Alignof
+#endif
+
+// Layout checks (same numbers as rust/engine/src/abi_layout.rs)
+ZELLO_STATIC_ASSERT(sizeof(void*) == 8, "zello.h describes the ABI of 64-bit targets");
+ZELLO_STATIC_ASSERT(sizeof(McoreErrorCode) == 4, "McoreErrorCode size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreErrorCode) == 4, "McoreErrorCode alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreCapability) == 4, "McoreCapability size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCapability) == 4, "McoreCapability alignment");
+ZELLO_STATIC_ASSERT(sizeof(McorePlatform) == 4, "McorePlatform size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McorePlatform) == 4, "McorePlatform alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreMacSurface) == 32, "McoreMacSurface size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreMacSurface) == 8, "McoreMacSurface alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreMacSurface, ns_view) == 0, "McoreMacSurface.ns_view offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreMacSurface, ca_metal_layer) == 8, "McoreMacSurface.ca_metal_layer offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreMacSurface, scale_factor) == 16, "McoreMacSurface.scale_factor offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreMacSurface, width_px) == 20, "McoreMacSurface.width_px offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreMacSurface, height_px) == 24, "McoreMacSurface.height_px offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreSurfaceUnion) == 32, "McoreSurfaceUnion size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreSurfaceUnion) == 8, "McoreSurfaceUnion alignment");
+ZELLO_STATIC_ASSERT(sizeof(McorePowerPreference) == 4, "McorePowerPreference size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McorePowerPreference) == 4, "McorePowerPreference alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreSurfaceDesc) == 56, "McoreSurfaceDesc size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreSurfaceDesc) == 8, "McoreSurfaceDesc alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreSurfaceDesc, api_version) == 0, "McoreSurfaceDesc.api_version offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreSurfaceDesc, platform) == 4, "McoreSurfaceDesc.platform offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreSurfaceDesc, u) == 8, "McoreSurfaceDesc.u offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreSurfaceDesc, power_preference) == 40, "McoreSurfaceDesc.power_preference offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreSurfaceDesc, adapter) == 44, "McoreSurfaceDesc.adapter offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreSurfaceDesc, flags) == 48, "McoreSurfaceDesc.flags offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreEngineDesc) == 16, "McoreEngineDesc size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreEngineDesc) == 4, "McoreEngineDesc alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreEngineDesc, api_version) == 0, "McoreEngineDesc.api_version offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreEngineDesc, power_preference) == 4, "McoreEngineDesc.power_preference offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreEngineDesc, adapter) == 8, "McoreEngineDesc.adapter offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreEngineDesc, flags) == 12, "McoreEngineDesc.flags offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreRenderMode) == 4, "McoreRenderMode size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreRenderMode) == 4, "McoreRenderMode alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreDeviceType) == 4, "McoreDeviceType size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreDeviceType) == 4, "McoreDeviceType alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreGpuBackend) == 4, "McoreGpuBackend size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreGpuBackend) == 4, "McoreGpuBackend alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreAdapterInfo) == 144, "McoreAdapterInfo size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreAdapterInfo) == 4, "McoreAdapterInfo alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreAdapterInfo, name) == 0, "McoreAdapterInfo.name offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreAdapterInfo, vendor) == 128, "McoreAdapterInfo.vendor offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreAdapterInfo, device) == 132, "McoreAdapterInfo.device offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreAdapterInfo, device_type) == 136, "McoreAdapterInfo.device_type offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreAdapterInfo, backend) == 140, "McoreAdapterInfo.backend offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreGpuInfo) == 168, "McoreGpuInfo size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreGpuInfo) == 8, "McoreGpuInfo alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreGpuInfo, adapter) == 0, "McoreGpuInfo.adapter offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGpuInfo, max_texture_dimension_2d) == 144, "McoreGpuInfo.max_texture_dimension_2d offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGpuInfo, max_storage_buffer_binding_size) == 148, "McoreGpuInfo.max_storage_buffer_binding_size offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGpuInfo, max_buffer_size) == 152, "McoreGpuInfo.max_buffer_size offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGpuInfo, timestamp_queries) == 160, "McoreGpuInfo.timestamp_queries offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreRgba) == 16, "McoreRgba size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreRgba) == 4, "McoreRgba alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreRgba, r) == 0, "McoreRgba.r offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRgba, g) == 4, "McoreRgba.g offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRgba, b) == 8, "McoreRgba.b offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRgba, a) == 12, "McoreRgba.a offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreRoundedRect) == 36, "McoreRoundedRect size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreRoundedRect) == 4, "McoreRoundedRect alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreRoundedRect, x) == 0, "McoreRoundedRect.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRoundedRect, y) == 4, "McoreRoundedRect.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRoundedRect, w) == 8, "McoreRoundedRect.w offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRoundedRect, h) == 12, "McoreRoundedRect.h offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRoundedRect, radius) == 16, "McoreRoundedRect.radius offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRoundedRect, fill) == 20, "McoreRoundedRect.fill offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreFontBlob) == 24, "McoreFontBlob size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreFontBlob) == 8, "McoreFontBlob alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreFontBlob, data) == 0, "McoreFontBlob.data offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFontBlob, len) == 8, "McoreFontBlob.len offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFontBlob, name) == 16, "McoreFontBlob.name offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextReq) == 80, "McoreTextReq size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextReq) == 8, "McoreTextReq alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, utf8) == 0, "McoreTextReq.utf8 offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, wrap_width) == 8, "McoreTextReq.wrap_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, font_size_px) == 12, "McoreTextReq.font_size_px offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, font_id) == 16, "McoreTextReq.font_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, weight) == 20, "McoreTextReq.weight offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, italic) == 24, "McoreTextReq.italic offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, stretch) == 28, "McoreTextReq.stretch offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, letter_spacing) == 32, "McoreTextReq.letter_spacing offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, word_spacing) == 36, "McoreTextReq.word_spacing offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, line_height_multiplier) == 40, "McoreTextReq.line_height_multiplier offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, align) == 44, "McoreTextReq.align offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, max_lines) == 48, "McoreTextReq.max_lines offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, overflow) == 52, "McoreTextReq.overflow offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, direction) == 53, "McoreTextReq.direction offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, tab_width) == 56, "McoreTextReq.tab_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, tab_stops) == 64, "McoreTextReq.tab_stops offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextReq, tab_stop_count) == 72, "McoreTextReq.tab_stop_count offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextAlign) == 4, "McoreTextAlign size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextAlign) == 4, "McoreTextAlign alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextDirection) == 4, "McoreTextDirection size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextDirection) == 4, "McoreTextDirection alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextOverflow) == 4, "McoreTextOverflow size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextOverflow) == 4, "McoreTextOverflow alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextSpan) == 36, "McoreTextSpan size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextSpan) == 4, "McoreTextSpan alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextSpan, start) == 0, "McoreTextSpan.start offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextSpan, end) == 4, "McoreTextSpan.end offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextSpan, color) == 8, "McoreTextSpan.color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextSpan, weight) == 24, "McoreTextSpan.weight offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextSpan, italic) == 28, "McoreTextSpan.italic offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextSpan, underline) == 29, "McoreTextSpan.underline offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextSpan, font_id) == 32, "McoreTextSpan.font_id offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextMetrics) == 16, "McoreTextMetrics size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextMetrics) == 4, "McoreTextMetrics alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextMetrics, advance_w) == 0, "McoreTextMetrics.advance_w offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextMetrics, advance_h) == 4, "McoreTextMetrics.advance_h offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextMetrics, line_count) == 8, "McoreTextMetrics.line_count offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextMetrics, truncated) == 12, "McoreTextMetrics.truncated offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextSize) == 8, "McoreTextSize size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextSize) == 4, "McoreTextSize alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextSize, width) == 0, "McoreTextSize.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextSize, height) == 4, "McoreTextSize.height offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCaretRect) == 12, "McoreCaretRect size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCaretRect) == 4, "McoreCaretRect alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCaretRect, x) == 0, "McoreCaretRect.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCaretRect, y) == 4, "McoreCaretRect.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCaretRect, height) == 8, "McoreCaretRect.height offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextStats) == 8, "McoreTextStats size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextStats) == 4, "McoreTextStats alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextStats, total_measure_calls) == 0, "McoreTextStats.total_measure_calls offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextStats, total_offset_calls) == 4, "McoreTextStats.total_offset_calls offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreHitTransform) == 12, "McoreHitTransform size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreHitTransform) == 4, "McoreHitTransform alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreHitTransform, dx) == 0, "McoreHitTransform.dx offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreHitTransform, dy) == 4, "McoreHitTransform.dy offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreHitTransform, scale) == 8, "McoreHitTransform.scale offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreHitResult) == 24, "McoreHitResult size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreHitResult) == 8, "McoreHitResult alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreHitResult, id) == 0, "McoreHitResult.id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreHitResult, z) == 8, "McoreHitResult.z offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreHitResult, local_x) == 12, "McoreHitResult.local_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreHitResult, local_y) == 16, "McoreHitResult.local_y offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextCacheStats) == 48, "McoreTextCacheStats size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextCacheStats) == 8, "McoreTextCacheStats alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextCacheStats, entries) == 0, "McoreTextCacheStats.entries offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextCacheStats, max_entries) == 4, "McoreTextCacheStats.max_entries offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextCacheStats, bytes) == 8, "McoreTextCacheStats.bytes offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextCacheStats, max_bytes) == 16, "McoreTextCacheStats.max_bytes offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextCacheStats, hits) == 24, "McoreTextCacheStats.hits offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextCacheStats, misses) == 32, "McoreTextCacheStats.misses offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextCacheStats, evictions) == 40, "McoreTextCacheStats.evictions offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreDrawCmdKind) == 4, "McoreDrawCmdKind size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreDrawCmdKind) == 4, "McoreDrawCmdKind alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreDrawCommand) == 120, "McoreDrawCommand size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreDrawCommand) == 8, "McoreDrawCommand alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, kind) == 0, "McoreDrawCommand.kind offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, x) == 4, "McoreDrawCommand.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, y) == 8, "McoreDrawCommand.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, width) == 12, "McoreDrawCommand.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, height) == 16, "McoreDrawCommand.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, radius) == 20, "McoreDrawCommand.radius offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, color) == 24, "McoreDrawCommand.color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, text_ptr) == 40, "McoreDrawCommand.text_ptr offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, font_size) == 48, "McoreDrawCommand.font_size offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, wrap_width) == 52, "McoreDrawCommand.wrap_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, font_id) == 56, "McoreDrawCommand.font_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, border_width) == 60, "McoreDrawCommand.border_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, border_color) == 64, "McoreDrawCommand.border_color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, has_border) == 80, "McoreDrawCommand.has_border offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, shadow_offset_x) == 84, "McoreDrawCommand.shadow_offset_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, shadow_offset_y) == 88, "McoreDrawCommand.shadow_offset_y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, shadow_blur) == 92, "McoreDrawCommand.shadow_blur offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, shadow_color) == 96, "McoreDrawCommand.shadow_color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, has_shadow) == 112, "McoreDrawCommand.has_shadow offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, text_align) == 113, "McoreDrawCommand.text_align offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreDrawCommand, text_direction) == 114, "McoreDrawCommand.text_direction offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdHeader) == 8, "McoreCmdHeader size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdHeader) == 4, "McoreCmdHeader alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdHeader, tag) == 0, "McoreCmdHeader.tag offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdHeader, flags) == 2, "McoreCmdHeader.flags offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdHeader, size) == 4, "McoreCmdHeader.size offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdTag) == 4, "McoreCmdTag size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdTag) == 4, "McoreCmdTag alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdFill) == 36, "McoreCmdFill size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdFill) == 4, "McoreCmdFill alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdFill, x) == 0, "McoreCmdFill.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdFill, y) == 4, "McoreCmdFill.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdFill, width) == 8, "McoreCmdFill.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdFill, height) == 12, "McoreCmdFill.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdFill, radius) == 16, "McoreCmdFill.radius offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdFill, color) == 20, "McoreCmdFill.color offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdStroke) == 40, "McoreCmdStroke size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdStroke) == 4, "McoreCmdStroke alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdStroke, x) == 0, "McoreCmdStroke.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdStroke, y) == 4, "McoreCmdStroke.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdStroke, width) == 8, "McoreCmdStroke.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdStroke, height) == 12, "McoreCmdStroke.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdStroke, radius) == 16, "McoreCmdStroke.radius offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdStroke, line_width) == 20, "McoreCmdStroke.line_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdStroke, color) == 24, "McoreCmdStroke.color offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdShadow) == 40, "McoreCmdShadow size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdShadow) == 4, "McoreCmdShadow alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdShadow, x) == 0, "McoreCmdShadow.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdShadow, y) == 4, "McoreCmdShadow.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdShadow, width) == 8, "McoreCmdShadow.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdShadow, height) == 12, "McoreCmdShadow.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdShadow, radius) == 16, "McoreCmdShadow.radius offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdShadow, blur) == 20, "McoreCmdShadow.blur offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdShadow, color) == 24, "McoreCmdShadow.color offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdText) == 48, "McoreCmdText size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdText) == 4, "McoreCmdText alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdText, x) == 0, "McoreCmdText.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdText, y) == 4, "McoreCmdText.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdText, font_size) == 8, "McoreCmdText.font_size offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdText, wrap_width) == 12, "McoreCmdText.wrap_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdText, font_id) == 16, "McoreCmdText.font_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdText, color) == 20, "McoreCmdText.color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdText, align) == 36, "McoreCmdText.align offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdText, direction) == 40, "McoreCmdText.direction offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdText, text_len) == 44, "McoreCmdText.text_len offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdClip) == 20, "McoreCmdClip size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdClip) == 4, "McoreCmdClip alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdClip, x) == 0, "McoreCmdClip.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdClip, y) == 4, "McoreCmdClip.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdClip, width) == 8, "McoreCmdClip.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdClip, height) == 12, "McoreCmdClip.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdClip, radius) == 16, "McoreCmdClip.radius offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdGradient) == 28, "McoreCmdGradient size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdGradient) == 4, "McoreCmdGradient alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdGradient, x) == 0, "McoreCmdGradient.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdGradient, y) == 4, "McoreCmdGradient.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdGradient, width) == 8, "McoreCmdGradient.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdGradient, height) == 12, "McoreCmdGradient.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdGradient, radius) == 16, "McoreCmdGradient.radius offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdGradient, gradient_id) == 20, "McoreCmdGradient.gradient_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdGradient, line_width) == 24, "McoreCmdGradient.line_width offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdImage) == 48, "McoreCmdImage size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdImage) == 4, "McoreCmdImage alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, x) == 0, "McoreCmdImage.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, y) == 4, "McoreCmdImage.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, width) == 8, "McoreCmdImage.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, height) == 12, "McoreCmdImage.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, image_id) == 16, "McoreCmdImage.image_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, sampling) == 20, "McoreCmdImage.sampling offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, extend_x) == 24, "McoreCmdImage.extend_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, extend_y) == 28, "McoreCmdImage.extend_y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, tile_x) == 32, "McoreCmdImage.tile_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, tile_y) == 36, "McoreCmdImage.tile_y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, tile_width) == 40, "McoreCmdImage.tile_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdImage, tile_height) == 44, "McoreCmdImage.tile_height offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdTransform) == 24, "McoreCmdTransform size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdTransform) == 4, "McoreCmdTransform alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdTransform, matrix) == 0, "McoreCmdTransform.matrix offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCmdVideo) == 24, "McoreCmdVideo size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCmdVideo) == 4, "McoreCmdVideo alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdVideo, x) == 0, "McoreCmdVideo.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdVideo, y) == 4, "McoreCmdVideo.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdVideo, width) == 8, "McoreCmdVideo.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdVideo, height) == 12, "McoreCmdVideo.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdVideo, video_id) == 16, "McoreCmdVideo.video_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCmdVideo, fit) == 20, "McoreCmdVideo.fit offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreColor) == 16, "McoreColor size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreColor) == 4, "McoreColor alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreColor, r) == 0, "McoreColor.r offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreColor, g) == 4, "McoreColor.g offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreColor, b) == 8, "McoreColor.b offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreColor, a) == 12, "McoreColor.a offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreStatus) == 4, "McoreStatus size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreStatus) == 4, "McoreStatus alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextBlockKind) == 4, "McoreTextBlockKind size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextBlockKind) == 4, "McoreTextBlockKind alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextRun) == 28, "McoreTextRun size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextRun) == 4, "McoreTextRun alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextRun, start) == 0, "McoreTextRun.start offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextRun, end) == 4, "McoreTextRun.end offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextRun, flags) == 8, "McoreTextRun.flags offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextRun, color) == 12, "McoreTextRun.color offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextBlockParagraph) == 32, "McoreTextBlockParagraph size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextBlockParagraph) == 8, "McoreTextBlockParagraph alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockParagraph, utf8) == 0, "McoreTextBlockParagraph.utf8 offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockParagraph, kind) == 8, "McoreTextBlockParagraph.kind offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockParagraph, level) == 12, "McoreTextBlockParagraph.level offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockParagraph, number) == 16, "McoreTextBlockParagraph.number offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockParagraph, run_count) == 20, "McoreTextBlockParagraph.run_count offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockParagraph, runs) == 24, "McoreTextBlockParagraph.runs offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextBlockStyle) == 72, "McoreTextBlockStyle size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextBlockStyle) == 4, "McoreTextBlockStyle alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockStyle, font_size_px) == 0, "McoreTextBlockStyle.font_size_px offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockStyle, font_id) == 4, "McoreTextBlockStyle.font_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockStyle, code_font_id) == 8, "McoreTextBlockStyle.code_font_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockStyle, line_height_multiplier) == 12, "McoreTextBlockStyle.line_height_multiplier offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockStyle, paragraph_spacing) == 16, "McoreTextBlockStyle.paragraph_spacing offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockStyle, list_indent) == 20, "McoreTextBlockStyle.list_indent offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockStyle, color) == 24, "McoreTextBlockStyle.color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockStyle, code_color) == 40, "McoreTextBlockStyle.code_color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextBlockStyle, code_background) == 56, "McoreTextBlockStyle.code_background offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCodeReq) == 80, "McoreCodeReq size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCodeReq) == 8, "McoreCodeReq alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, text) == 0, "McoreCodeReq.text offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, len) == 8, "McoreCodeReq.len offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, font_size_px) == 16, "McoreCodeReq.font_size_px offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, font_id) == 20, "McoreCodeReq.font_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, line_height_multiplier) == 24, "McoreCodeReq.line_height_multiplier offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, tab_size) == 28, "McoreCodeReq.tab_size offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, line_numbers) == 32, "McoreCodeReq.line_numbers offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, scroll_y) == 36, "McoreCodeReq.scroll_y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, viewport_height) == 40, "McoreCodeReq.viewport_height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, color) == 44, "McoreCodeReq.color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeReq, line_number_color) == 60, "McoreCodeReq.line_number_color offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCodeToken) == 24, "McoreCodeToken size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCodeToken) == 4, "McoreCodeToken alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeToken, start) == 0, "McoreCodeToken.start offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeToken, end) == 4, "McoreCodeToken.end offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeToken, color) == 8, "McoreCodeToken.color offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCodeMetrics) == 16, "McoreCodeMetrics size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCodeMetrics) == 4, "McoreCodeMetrics alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeMetrics, line_count) == 0, "McoreCodeMetrics.line_count offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeMetrics, line_height) == 4, "McoreCodeMetrics.line_height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeMetrics, gutter_width) == 8, "McoreCodeMetrics.gutter_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreCodeMetrics, content_height) == 12, "McoreCodeMetrics.content_height offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreGradientStop) == 20, "McoreGradientStop size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreGradientStop) == 4, "McoreGradientStop alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreGradientStop, offset) == 0, "McoreGradientStop.offset offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGradientStop, color) == 4, "McoreGradientStop.color offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreGradientDesc) == 24, "McoreGradientDesc size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreGradientDesc) == 8, "McoreGradientDesc alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreGradientDesc, kind) == 0, "McoreGradientDesc.kind offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGradientDesc, _padding) == 1, "McoreGradientDesc._padding offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGradientDesc, angle_deg) == 4, "McoreGradientDesc.angle_deg offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGradientDesc, stops) == 8, "McoreGradientDesc.stops offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGradientDesc, stop_count) == 16, "McoreGradientDesc.stop_count offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreFrameStats) == 80, "McoreFrameStats size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreFrameStats) == 8, "McoreFrameStats alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, frame) == 0, "McoreFrameStats.frame offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, build_ms) == 8, "McoreFrameStats.build_ms offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, encode_ms) == 12, "McoreFrameStats.encode_ms offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, custom_ms) == 16, "McoreFrameStats.custom_ms offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, render_ms) == 20, "McoreFrameStats.render_ms offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, gpu_ms) == 24, "McoreFrameStats.gpu_ms offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, commands) == 28, "McoreFrameStats.commands offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, encode_calls) == 32, "McoreFrameStats.encode_calls offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, paths) == 36, "McoreFrameStats.paths offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, path_segments) == 40, "McoreFrameStats.path_segments offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, clips) == 44, "McoreFrameStats.clips offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, draw_objects) == 48, "McoreFrameStats.draw_objects offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, image_count) == 52, "McoreFrameStats.image_count offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, image_bytes) == 56, "McoreFrameStats.image_bytes offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, texture_bytes) == 64, "McoreFrameStats.texture_bytes offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreFrameStats, layer_warnings) == 72, "McoreFrameStats.layer_warnings offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreAaMode) == 4, "McoreAaMode size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreAaMode) == 4, "McoreAaMode alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextRendering) == 8, "McoreTextRendering size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextRendering) == 4, "McoreTextRendering alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextRendering, hinting) == 0, "McoreTextRendering.hinting offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextRendering, subpixel_positions) == 4, "McoreTextRendering.subpixel_positions offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextEventKind) == 4, "McoreTextEventKind size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextEventKind) == 4, "McoreTextEventKind alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreCursorDirection) == 4, "McoreCursorDirection size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCursorDirection) == 4, "McoreCursorDirection alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextEvent) == 32, "McoreTextEvent size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextEvent) == 8, "McoreTextEvent alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEvent, kind) == 0, "McoreTextEvent.kind offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEvent, char_code) == 4, "McoreTextEvent.char_code offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEvent, direction) == 8, "McoreTextEvent.direction offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEvent, extend_selection) == 12, "McoreTextEvent.extend_selection offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEvent, cursor_position) == 16, "McoreTextEvent.cursor_position offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEvent, text_ptr) == 24, "McoreTextEvent.text_ptr offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextEventResult) == 12, "McoreTextEventResult size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextEventResult) == 4, "McoreTextEventResult alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEventResult, changed) == 0, "McoreTextEventResult.changed offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEventResult, scrolled) == 1, "McoreTextEventResult.scrolled offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEventResult, _padding) == 2, "McoreTextEventResult._padding offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEventResult, scroll_x) == 4, "McoreTextEventResult.scroll_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextEventResult, scroll_y) == 8, "McoreTextEventResult.scroll_y offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextInputStyle) == 84, "McoreTextInputStyle size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextInputStyle) == 4, "McoreTextInputStyle alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, x) == 0, "McoreTextInputStyle.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, y) == 4, "McoreTextInputStyle.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, width) == 8, "McoreTextInputStyle.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, height) == 12, "McoreTextInputStyle.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, padding_x) == 16, "McoreTextInputStyle.padding_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, padding_y) == 20, "McoreTextInputStyle.padding_y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, font_size) == 24, "McoreTextInputStyle.font_size offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, wrap_width) == 28, "McoreTextInputStyle.wrap_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, text_color) == 32, "McoreTextInputStyle.text_color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, selection_color) == 48, "McoreTextInputStyle.selection_color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, caret_color) == 64, "McoreTextInputStyle.caret_color offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, focused) == 80, "McoreTextInputStyle.focused offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputStyle, _padding) == 81, "McoreTextInputStyle._padding offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextInputHints) == 4, "McoreTextInputHints size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextInputHints) == 1, "McoreTextInputHints alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputHints, keyboard) == 0, "McoreTextInputHints.keyboard offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputHints, autocorrect) == 1, "McoreTextInputHints.autocorrect offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputHints, secure) == 2, "McoreTextInputHints.secure offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextInputHints, ime_enabled) == 3, "McoreTextInputHints.ime_enabled offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreCursor) == 4, "McoreCursor size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreCursor) == 4, "McoreCursor alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreImePreedit) == 16, "McoreImePreedit size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreImePreedit) == 8, "McoreImePreedit alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreImePreedit, text) == 0, "McoreImePreedit.text offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImePreedit, cursor_offset) == 8, "McoreImePreedit.cursor_offset offset");
+ZELLO_STATIC_ASSERT(sizeof(McorePointerPhase) == 4, "McorePointerPhase size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McorePointerPhase) == 4, "McorePointerPhase alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreGesture) == 28, "McoreGesture size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreGesture) == 4, "McoreGesture alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreGesture, kind) == 0, "McoreGesture.kind offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGesture, x) == 4, "McoreGesture.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGesture, y) == 8, "McoreGesture.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGesture, dx) == 12, "McoreGesture.dx offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGesture, dy) == 16, "McoreGesture.dy offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGesture, velocity_x) == 20, "McoreGesture.velocity_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreGesture, velocity_y) == 24, "McoreGesture.velocity_y offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreScrollState) == 20, "McoreScrollState size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreScrollState) == 4, "McoreScrollState alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreScrollState, offset_x) == 0, "McoreScrollState.offset_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreScrollState, offset_y) == 4, "McoreScrollState.offset_y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreScrollState, velocity_x) == 8, "McoreScrollState.velocity_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreScrollState, velocity_y) == 12, "McoreScrollState.velocity_y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreScrollState, animating) == 16, "McoreScrollState.animating offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreScrollPhase) == 4, "McoreScrollPhase size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreScrollPhase) == 4, "McoreScrollPhase alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreScrollAxis) == 4, "McoreScrollAxis size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreScrollAxis) == 4, "McoreScrollAxis alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreLayoutDirection) == 4, "McoreLayoutDirection size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreLayoutDirection) == 4, "McoreLayoutDirection alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreLayoutJustify) == 4, "McoreLayoutJustify size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreLayoutJustify) == 4, "McoreLayoutJustify alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreLayoutAlign) == 4, "McoreLayoutAlign size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreLayoutAlign) == 4, "McoreLayoutAlign alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreEdges) == 16, "McoreEdges size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreEdges) == 4, "McoreEdges alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreEdges, top) == 0, "McoreEdges.top offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreEdges, right) == 4, "McoreEdges.right offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreEdges, bottom) == 8, "McoreEdges.bottom offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreEdges, left) == 12, "McoreEdges.left offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreLayoutStyle) == 84, "McoreLayoutStyle size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreLayoutStyle) == 4, "McoreLayoutStyle alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, direction) == 0, "McoreLayoutStyle.direction offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, wrap) == 1, "McoreLayoutStyle.wrap offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, justify) == 2, "McoreLayoutStyle.justify offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, align_items) == 3, "McoreLayoutStyle.align_items offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, align_self) == 4, "McoreLayoutStyle.align_self offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, hidden) == 5, "McoreLayoutStyle.hidden offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, width) == 8, "McoreLayoutStyle.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, height) == 12, "McoreLayoutStyle.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, min_width) == 16, "McoreLayoutStyle.min_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, min_height) == 20, "McoreLayoutStyle.min_height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, max_width) == 24, "McoreLayoutStyle.max_width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, max_height) == 28, "McoreLayoutStyle.max_height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, grow) == 32, "McoreLayoutStyle.grow offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, shrink) == 36, "McoreLayoutStyle.shrink offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, basis) == 40, "McoreLayoutStyle.basis offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, padding) == 44, "McoreLayoutStyle.padding offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, margin) == 60, "McoreLayoutStyle.margin offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, row_gap) == 76, "McoreLayoutStyle.row_gap offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreLayoutStyle, column_gap) == 80, "McoreLayoutStyle.column_gap offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreUiTheme) == 112, "McoreUiTheme size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreUiTheme) == 4, "McoreUiTheme alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, font_size) == 0, "McoreUiTheme.font_size offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, padding) == 4, "McoreUiTheme.padding offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, spacing) == 8, "McoreUiTheme.spacing offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, corner_radius) == 12, "McoreUiTheme.corner_radius offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, text) == 16, "McoreUiTheme.text offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, button) == 32, "McoreUiTheme.button offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, button_hover) == 48, "McoreUiTheme.button_hover offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, button_pressed) == 64, "McoreUiTheme.button_pressed offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, field) == 80, "McoreUiTheme.field offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiTheme, accent) == 96, "McoreUiTheme.accent offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreUiInput) == 12, "McoreUiInput size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreUiInput) == 4, "McoreUiInput alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiInput, pointer_x) == 0, "McoreUiInput.pointer_x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiInput, pointer_y) == 4, "McoreUiInput.pointer_y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiInput, pointer_down) == 8, "McoreUiInput.pointer_down offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreUiInput, _padding) == 9, "McoreUiInput._padding offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreTextureTarget) == 24, "McoreTextureTarget size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreTextureTarget) == 8, "McoreTextureTarget alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextureTarget, native_texture) == 0, "McoreTextureTarget.native_texture offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextureTarget, width) == 8, "McoreTextureTarget.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextureTarget, height) == 12, "McoreTextureTarget.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreTextureTarget, time_seconds) == 16, "McoreTextureTarget.time_seconds offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreVideoFrame) == 56, "McoreVideoFrame size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreVideoFrame) == 8, "McoreVideoFrame alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreVideoFrame, format) == 0, "McoreVideoFrame.format offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreVideoFrame, width) == 4, "McoreVideoFrame.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreVideoFrame, height) == 8, "McoreVideoFrame.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreVideoFrame, matrix) == 12, "McoreVideoFrame.matrix offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreVideoFrame, full_range) == 16, "McoreVideoFrame.full_range offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreVideoFrame, _reserved) == 20, "McoreVideoFrame._reserved offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreVideoFrame, planes) == 24, "McoreVideoFrame.planes offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreVideoFrame, strides) == 40, "McoreVideoFrame.strides offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreVideoFrame, iosurface) == 48, "McoreVideoFrame.iosurface offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreParticleLayer) == 4, "McoreParticleLayer size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreParticleLayer) == 4, "McoreParticleLayer alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreParticleEmitter) == 112, "McoreParticleEmitter size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreParticleEmitter) == 4, "McoreParticleEmitter alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, x) == 0, "McoreParticleEmitter.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, y) == 4, "McoreParticleEmitter.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, count) == 8, "McoreParticleEmitter.count offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, lifetime) == 12, "McoreParticleEmitter.lifetime offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, speed_min) == 16, "McoreParticleEmitter.speed_min offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, speed_max) == 20, "McoreParticleEmitter.speed_max offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, direction) == 24, "McoreParticleEmitter.direction offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, spread) == 28, "McoreParticleEmitter.spread offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, gravity) == 32, "McoreParticleEmitter.gravity offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, size) == 36, "McoreParticleEmitter.size offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, colors) == 40, "McoreParticleEmitter.colors offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, looping) == 104, "McoreParticleEmitter.looping offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreParticleEmitter, layer) == 108, "McoreParticleEmitter.layer offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreA11yNode) == 88, "McoreA11yNode size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreA11yNode) == 8, "McoreA11yNode alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, id) == 0, "McoreA11yNode.id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, role) == 8, "McoreA11yNode.role offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, label) == 16, "McoreA11yNode.label offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, bounds) == 24, "McoreA11yNode.bounds offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, actions) == 40, "McoreA11yNode.actions offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, children) == 48, "McoreA11yNode.children offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, children_count) == 56, "McoreA11yNode.children_count offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, value) == 64, "McoreA11yNode.value offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, text_selection_start) == 72, "McoreA11yNode.text_selection_start offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, text_selection_end) == 76, "McoreA11yNode.text_selection_end offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yNode, states) == 80, "McoreA11yNode.states offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreRect) == 16, "McoreRect size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreRect) == 4, "McoreRect alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreRect, x) == 0, "McoreRect.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRect, y) == 4, "McoreRect.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRect, width) == 8, "McoreRect.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreRect, height) == 12, "McoreRect.height offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreA11yPrefs) == 3, "McoreA11yPrefs size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreA11yPrefs) == 1, "McoreA11yPrefs alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yPrefs, reduce_motion) == 0, "McoreA11yPrefs.reduce_motion offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yPrefs, increase_contrast) == 1, "McoreA11yPrefs.increase_contrast offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreA11yPrefs, reduce_transparency) == 2, "McoreA11yPrefs.reduce_transparency offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreColorSpace) == 4, "McoreColorSpace size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreColorSpace) == 4, "McoreColorSpace alignment");
+ZELLO_STATIC_ASSERT(sizeof(McoreImageDesc) == 24, "McoreImageDesc size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreImageDesc) == 8, "McoreImageDesc alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageDesc, data) == 0, "McoreImageDesc.data offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageDesc, data_len) == 8, "McoreImageDesc.data_len offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageDesc, width) == 12, "McoreImageDesc.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageDesc, height) == 16, "McoreImageDesc.height offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageDesc, format) == 20, "McoreImageDesc.format offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageDesc, alpha_type) == 21, "McoreImageDesc.alpha_type offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreImageTransform) == 16, "McoreImageTransform size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreImageTransform) == 4, "McoreImageTransform alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageTransform, x) == 0, "McoreImageTransform.x offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageTransform, y) == 4, "McoreImageTransform.y offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageTransform, scale) == 8, "McoreImageTransform.scale offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageTransform, rotation_deg) == 12, "McoreImageTransform.rotation_deg offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreImageInfo) == 12, "McoreImageInfo size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreImageInfo) == 4, "McoreImageInfo alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageInfo, image_id) == 0, "McoreImageInfo.image_id offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageInfo, width) == 4, "McoreImageInfo.width offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageInfo, height) == 8, "McoreImageInfo.height offset");
+ZELLO_STATIC_ASSERT(sizeof(McoreImageCacheStats) == 48, "McoreImageCacheStats size");
+ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF(McoreImageCacheStats) == 8, "McoreImageCacheStats alignment");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageCacheStats, bytes) == 0, "McoreImageCacheStats.bytes offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageCacheStats, released_bytes) == 8, "McoreImageCacheStats.released_bytes offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageCacheStats, budget_bytes) == 16, "McoreImageCacheStats.budget_bytes offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageCacheStats, evictions) == 24, "McoreImageCacheStats.evictions offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageCacheStats, image_count) == 32, "McoreImageCacheStats.image_count offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageCacheStats, released_count) == 36, "McoreImageCacheStats.released_count offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageCacheStats, pending_count) == 40, "McoreImageCacheStats.pending_count offset");
+ZELLO_STATIC_ASSERT(offsetof(McoreImageCacheStats, atlas_pages) == 44, "McoreImageCacheStats.atlas_pages offset");
+#undef ZELLO_STATIC_ASSERT
+#undef ZELLO_ALIGNOF
+
+#endif  // ZELLO_H
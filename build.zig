@@ -39,6 +39,7 @@ pub fn build(b: *std.Build) void {
         exe.linkFramework("AppKit");
         exe.linkFramework("QuartzCore");
         exe.linkFramework("Metal");
+        exe.linkFramework("Carbon");
     }
 
     b.installArtifact(exe);
@@ -86,4 +87,23 @@ pub fn build(b: *std.Build) void {
     const test_colors_run = b.addRunArtifact(test_colors);
     const test_colors_step = b.step("test-colors", "Run color API tests");
     test_colors_step.dependOn(&test_colors_run.step);
+
+    // ABI mirror layout tests (src/renderer/mcore.zig is generated by
+    // `cargo xtask bindings`; no engine library needed)
+    const abi_tests = b.addTest(.{
+        .root_module = b.createModule(.{
+            .root_source_file = b.path("src/renderer/mcore.zig"),
+            .target = target,
+            .optimize = optimize,
+        }),
+    });
+    const abi_tests_run = b.addRunArtifact(abi_tests);
+    const abi_tests_step = b.step("test-abi", "Check the generated Zig ABI mirror against the engine layout");
+    abi_tests_step.dependOn(&abi_tests_run.step);
+
+    // The generated C header checks its layouts with static asserts, so
+    // compiling it is the test
+    const header_check = b.addSystemCommand(&.{ b.graph.zig_exe, "cc", "-fsyntax-only", "-x", "c", "-std=c11" });
+    header_check.addFileArg(b.path("bindings/zello.h"));
+    abi_tests_step.dependOn(&header_check.step);
 }
@@ -1,12 +1,23 @@
 // Accessibility support via AccessKit
 use accesskit::{
-    Action, ActionHandler, ActionRequest, ActivationHandler, NodeId,
-    TreeUpdate,
+    Action, ActionHandler, ActionRequest, ActivationHandler, Node, NodeId,
+    Rect, Role, Toggled, Tree, TreeUpdate,
 };
 use accesskit_macos::SubclassingAdapter;
 use parking_lot::Mutex;
 use std::sync::Arc;
 
+use crate::{utf8_arg, McoreA11yNode};
+
+// Node states (McoreA11yNode::states, MCORE_A11Y_STATE_* in mcore.h)
+pub const STATE_DISABLED: u32 = 1 << 0;
+pub const STATE_CHECKED: u32 = 1 << 1;
+pub const STATE_MIXED: u32 = 1 << 2;
+pub const STATE_SELECTED: u32 = 1 << 3;
+pub const STATE_EXPANDED: u32 = 1 << 4;
+pub const STATE_COLLAPSED: u32 = 1 << 5;
+pub const STATE_HIDDEN: u32 = 1 << 6;
+
 // Global callback for accessibility actions
 static ACTION_CALLBACK: Mutex<Option<extern "C" fn(u64, u8)>> = Mutex::new(None);
 
@@ -31,8 +42,9 @@ impl AccessibilityState {
         self.current_tree = Some(tree);
     }
 
+    /// The last tree, with the current focus
     pub fn get_tree(&self) -> Option<TreeUpdate> {
-        self.current_tree.clone()
+        self.current_tree.clone().map(|tree| TreeUpdate { focus: self.focus, ..tree })
     }
 
     pub fn set_focus(&mut self, focus: NodeId) {
@@ -137,22 +149,129 @@ impl AccessibilityAdapter {
         }
     }
 
-    /// Update focus state
+    /// Update focus state; the platform only needs the focus change, not
+    /// the whole tree
     pub fn update_focus(&self, focus: NodeId) {
-        let tree = {
+        let has_tree = {
             let mut state = self.state.lock();
             state.set_focus(focus);
-            state.get_tree()
+            state.current_tree.is_some()
         };
 
-        if let Some(adapter) = &self.adapter {
-            if let Some(tree) = tree {
-                let mut adapter = adapter.lock();
-                adapter.update_if_active(|| tree);
+        if let (Some(adapter), true) = (&self.adapter, has_tree) {
+            let mut adapter = adapter.lock();
+            adapter.update_if_active(|| TreeUpdate { nodes: Vec::new(), tree: None, focus });
+        }
+    }
+
+}
+
+/// Convert the host's nodes (mcore_a11y_update) to an AccessKit tree update
+pub fn tree_update(nodes_slice: &[McoreA11yNode], root_id: u64, focus_id: u64) -> TreeUpdate {
+    let mut ak_nodes = Vec::new();
+
+    for c_node in nodes_slice {
+        let node_id = NodeId(c_node.id);
+
+        // Map role
+        let role = match c_node.role {
+            0 => Role::Window,
+            1 => Role::Button,
+            2 => Role::TextInput,
+            3 => Role::Label,
+            4 => Role::Group,
+            5 => Role::Image,
+            6 => Role::CheckBox,
+            7 => Role::Switch,
+            8 => Role::Slider,
+            9 => Role::Link,
+            10 => Role::Heading,
+            11 => Role::List,
+            12 => Role::ListItem,
+            13 => Role::ScrollView,
+            _ => Role::Unknown,
+        };
+
+        let mut node = Node::new(role);
+
+        // Set label
+        if !c_node.label.is_null() {
+            let label = utf8_arg(c_node.label);
+            if !label.is_empty() {
+                node.set_label(label.to_string());
+            }
+        }
+
+        // Set value (for text inputs)
+        if !c_node.value.is_null() {
+            let value = utf8_arg(c_node.value);
+            if !value.is_empty() {
+                node.set_value(value.to_string());
             }
         }
+
+        // Set bounds
+        node.set_bounds(Rect {
+            x0: c_node.bounds.x as f64,
+            y0: c_node.bounds.y as f64,
+            x1: (c_node.bounds.x + c_node.bounds.width) as f64,
+            y1: (c_node.bounds.y + c_node.bounds.height) as f64,
+        });
+
+        // Set children
+        if !c_node.children.is_null() && c_node.children_count > 0 {
+            let children = unsafe {
+                std::slice::from_raw_parts(c_node.children, c_node.children_count as usize)
+            };
+            let child_ids: Vec<NodeId> = children.iter().map(|&id| NodeId(id)).collect();
+            node.set_children(child_ids);
+        }
+
+        // Set actions (bitfield)
+        if c_node.actions & 0x01 != 0 {  // Focus
+            node.add_action(Action::Focus);
+        }
+        if c_node.actions & 0x02 != 0 {  // Click
+            node.add_action(Action::Click);
+        }
+
+        let states = c_node.states;
+        if states & STATE_DISABLED != 0 {
+            node.set_disabled();
+        }
+        if states & STATE_MIXED != 0 {
+            node.set_toggled(Toggled::Mixed);
+        } else if states & STATE_CHECKED != 0 {
+            node.set_toggled(Toggled::True);
+        } else if matches!(role, Role::CheckBox | Role::Switch) {
+            node.set_toggled(Toggled::False);
+        }
+        if states & STATE_SELECTED != 0 {
+            node.set_selected(true);
+        }
+        if states & STATE_EXPANDED != 0 {
+            node.set_expanded(true);
+        } else if states & STATE_COLLAPSED != 0 {
+            node.set_expanded(false);
+        }
+        if states & STATE_HIDDEN != 0 {
+            node.set_hidden();
+        }
+
+        // TODO: Set text selection for text inputs
+        // Text selection in AccessKit is more complex than just byte offsets
+        // It requires TextPosition with node IDs and character indices
+        // We'll implement this properly later when we have text run nodes
+        let _ = (c_node.text_selection_start, c_node.text_selection_end);
+
+        ak_nodes.push((node_id, node));
     }
 
+    TreeUpdate {
+        nodes: ak_nodes,
+        tree: Some(Tree::new(NodeId(root_id))),
+        focus: NodeId(focus_id),
+    }
 }
 
 /// Set the global callback for accessibility actions
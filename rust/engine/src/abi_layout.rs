@@ -0,0 +1,924 @@
+//! Generated by `cargo xtask bindings` from rust/engine/src/lib.rs. Do not edit.
+//! Engine side of the ABI layout tests; src/renderer/mcore.zig asserts the same numbers.
+
+use std::mem::{align_of, offset_of, size_of};
+
+use crate::*;
+
+#[test]
+fn constant_values() {
+    assert_eq!(CREATE_SOFTWARE_FALLBACK, 0x1);
+    assert_eq!(CREATE_CPU_PIPELINE, 0x2);
+    assert_eq!(COMMAND_STREAM_VERSION, 0x1);
+    assert_eq!(API_VERSION_MAJOR, 0x4);
+    assert_eq!(API_VERSION_MINOR, 0x11);
+    assert_eq!(API_VERSION, 0x40011);
+    assert_eq!(TEXT_RUN_BOLD, 0x1);
+    assert_eq!(TEXT_RUN_ITALIC, 0x2);
+    assert_eq!(TEXT_RUN_UNDERLINE, 0x4);
+    assert_eq!(TEXT_RUN_CODE, 0x8);
+    assert_eq!(TEXT_RUN_COLOR, 0x10);
+}
+
+#[test]
+fn mcore_error_code_layout() {
+    assert_eq!(size_of::<McoreErrorCode>(), 4);
+    assert_eq!(align_of::<McoreErrorCode>(), 4);
+}
+
+#[test]
+fn mcore_capability_layout() {
+    assert_eq!(size_of::<McoreCapability>(), 4);
+    assert_eq!(align_of::<McoreCapability>(), 4);
+}
+
+#[test]
+fn mcore_platform_layout() {
+    assert_eq!(size_of::<McorePlatform>(), 4);
+    assert_eq!(align_of::<McorePlatform>(), 4);
+}
+
+#[test]
+fn mcore_mac_surface_layout() {
+    assert_eq!(size_of::<McoreMacSurface>(), 32);
+    assert_eq!(align_of::<McoreMacSurface>(), 8);
+    assert_eq!(offset_of!(McoreMacSurface, ns_view), 0);
+    assert_eq!(offset_of!(McoreMacSurface, ca_metal_layer), 8);
+    assert_eq!(offset_of!(McoreMacSurface, scale_factor), 16);
+    assert_eq!(offset_of!(McoreMacSurface, width_px), 20);
+    assert_eq!(offset_of!(McoreMacSurface, height_px), 24);
+}
+
+#[test]
+fn mcore_surface_union_layout() {
+    assert_eq!(size_of::<McoreSurfaceUnion>(), 32);
+    assert_eq!(align_of::<McoreSurfaceUnion>(), 8);
+}
+
+#[test]
+fn mcore_power_preference_layout() {
+    assert_eq!(size_of::<McorePowerPreference>(), 4);
+    assert_eq!(align_of::<McorePowerPreference>(), 4);
+}
+
+#[test]
+fn mcore_surface_desc_layout() {
+    assert_eq!(size_of::<McoreSurfaceDesc>(), 56);
+    assert_eq!(align_of::<McoreSurfaceDesc>(), 8);
+    assert_eq!(offset_of!(McoreSurfaceDesc, api_version), 0);
+    assert_eq!(offset_of!(McoreSurfaceDesc, platform), 4);
+    assert_eq!(offset_of!(McoreSurfaceDesc, u), 8);
+    assert_eq!(offset_of!(McoreSurfaceDesc, power_preference), 40);
+    assert_eq!(offset_of!(McoreSurfaceDesc, adapter), 44);
+    assert_eq!(offset_of!(McoreSurfaceDesc, flags), 48);
+}
+
+#[test]
+fn mcore_engine_desc_layout() {
+    assert_eq!(size_of::<McoreEngineDesc>(), 16);
+    assert_eq!(align_of::<McoreEngineDesc>(), 4);
+    assert_eq!(offset_of!(McoreEngineDesc, api_version), 0);
+    assert_eq!(offset_of!(McoreEngineDesc, power_preference), 4);
+    assert_eq!(offset_of!(McoreEngineDesc, adapter), 8);
+    assert_eq!(offset_of!(McoreEngineDesc, flags), 12);
+}
+
+#[test]
+fn mcore_render_mode_layout() {
+    assert_eq!(size_of::<McoreRenderMode>(), 4);
+    assert_eq!(align_of::<McoreRenderMode>(), 4);
+}
+
+#[test]
+fn mcore_device_type_layout() {
+    assert_eq!(size_of::<McoreDeviceType>(), 4);
+    assert_eq!(align_of::<McoreDeviceType>(), 4);
+}
+
+#[test]
+fn mcore_gpu_backend_layout() {
+    assert_eq!(size_of::<McoreGpuBackend>(), 4);
+    assert_eq!(align_of::<McoreGpuBackend>(), 4);
+}
+
+#[test]
+fn mcore_adapter_info_layout() {
+    assert_eq!(size_of::<McoreAdapterInfo>(), 144);
+    assert_eq!(align_of::<McoreAdapterInfo>(), 4);
+    assert_eq!(offset_of!(McoreAdapterInfo, name), 0);
+    assert_eq!(offset_of!(McoreAdapterInfo, vendor), 128);
+    assert_eq!(offset_of!(McoreAdapterInfo, device), 132);
+    assert_eq!(offset_of!(McoreAdapterInfo, device_type), 136);
+    assert_eq!(offset_of!(McoreAdapterInfo, backend), 140);
+}
+
+#[test]
+fn mcore_gpu_info_layout() {
+    assert_eq!(size_of::<McoreGpuInfo>(), 168);
+    assert_eq!(align_of::<McoreGpuInfo>(), 8);
+    assert_eq!(offset_of!(McoreGpuInfo, adapter), 0);
+    assert_eq!(offset_of!(McoreGpuInfo, max_texture_dimension_2d), 144);
+    assert_eq!(offset_of!(McoreGpuInfo, max_storage_buffer_binding_size), 148);
+    assert_eq!(offset_of!(McoreGpuInfo, max_buffer_size), 152);
+    assert_eq!(offset_of!(McoreGpuInfo, timestamp_queries), 160);
+}
+
+#[test]
+fn mcore_rgba_layout() {
+    assert_eq!(size_of::<McoreRgba>(), 16);
+    assert_eq!(align_of::<McoreRgba>(), 4);
+    assert_eq!(offset_of!(McoreRgba, r), 0);
+    assert_eq!(offset_of!(McoreRgba, g), 4);
+    assert_eq!(offset_of!(McoreRgba, b), 8);
+    assert_eq!(offset_of!(McoreRgba, a), 12);
+}
+
+#[test]
+fn mcore_rounded_rect_layout() {
+    assert_eq!(size_of::<McoreRoundedRect>(), 36);
+    assert_eq!(align_of::<McoreRoundedRect>(), 4);
+    assert_eq!(offset_of!(McoreRoundedRect, x), 0);
+    assert_eq!(offset_of!(McoreRoundedRect, y), 4);
+    assert_eq!(offset_of!(McoreRoundedRect, w), 8);
+    assert_eq!(offset_of!(McoreRoundedRect, h), 12);
+    assert_eq!(offset_of!(McoreRoundedRect, radius), 16);
+    assert_eq!(offset_of!(McoreRoundedRect, fill), 20);
+}
+
+#[test]
+fn mcore_font_blob_layout() {
+    assert_eq!(size_of::<McoreFontBlob>(), 24);
+    assert_eq!(align_of::<McoreFontBlob>(), 8);
+    assert_eq!(offset_of!(McoreFontBlob, data), 0);
+    assert_eq!(offset_of!(McoreFontBlob, len), 8);
+    assert_eq!(offset_of!(McoreFontBlob, name), 16);
+}
+
+#[test]
+fn mcore_text_req_layout() {
+    assert_eq!(size_of::<McoreTextReq>(), 80);
+    assert_eq!(align_of::<McoreTextReq>(), 8);
+    assert_eq!(offset_of!(McoreTextReq, utf8), 0);
+    assert_eq!(offset_of!(McoreTextReq, wrap_width), 8);
+    assert_eq!(offset_of!(McoreTextReq, font_size_px), 12);
+    assert_eq!(offset_of!(McoreTextReq, font_id), 16);
+    assert_eq!(offset_of!(McoreTextReq, weight), 20);
+    assert_eq!(offset_of!(McoreTextReq, italic), 24);
+    assert_eq!(offset_of!(McoreTextReq, stretch), 28);
+    assert_eq!(offset_of!(McoreTextReq, letter_spacing), 32);
+    assert_eq!(offset_of!(McoreTextReq, word_spacing), 36);
+    assert_eq!(offset_of!(McoreTextReq, line_height_multiplier), 40);
+    assert_eq!(offset_of!(McoreTextReq, align), 44);
+    assert_eq!(offset_of!(McoreTextReq, max_lines), 48);
+    assert_eq!(offset_of!(McoreTextReq, overflow), 52);
+    assert_eq!(offset_of!(McoreTextReq, direction), 53);
+    assert_eq!(offset_of!(McoreTextReq, tab_width), 56);
+    assert_eq!(offset_of!(McoreTextReq, tab_stops), 64);
+    assert_eq!(offset_of!(McoreTextReq, tab_stop_count), 72);
+}
+
+#[test]
+fn mcore_text_align_layout() {
+    assert_eq!(size_of::<McoreTextAlign>(), 4);
+    assert_eq!(align_of::<McoreTextAlign>(), 4);
+}
+
+#[test]
+fn mcore_text_direction_layout() {
+    assert_eq!(size_of::<McoreTextDirection>(), 4);
+    assert_eq!(align_of::<McoreTextDirection>(), 4);
+}
+
+#[test]
+fn mcore_text_overflow_layout() {
+    assert_eq!(size_of::<McoreTextOverflow>(), 4);
+    assert_eq!(align_of::<McoreTextOverflow>(), 4);
+}
+
+#[test]
+fn mcore_text_span_layout() {
+    assert_eq!(size_of::<McoreTextSpan>(), 36);
+    assert_eq!(align_of::<McoreTextSpan>(), 4);
+    assert_eq!(offset_of!(McoreTextSpan, start), 0);
+    assert_eq!(offset_of!(McoreTextSpan, end), 4);
+    assert_eq!(offset_of!(McoreTextSpan, color), 8);
+    assert_eq!(offset_of!(McoreTextSpan, weight), 24);
+    assert_eq!(offset_of!(McoreTextSpan, italic), 28);
+    assert_eq!(offset_of!(McoreTextSpan, underline), 29);
+    assert_eq!(offset_of!(McoreTextSpan, font_id), 32);
+}
+
+#[test]
+fn mcore_text_metrics_layout() {
+    assert_eq!(size_of::<McoreTextMetrics>(), 16);
+    assert_eq!(align_of::<McoreTextMetrics>(), 4);
+    assert_eq!(offset_of!(McoreTextMetrics, advance_w), 0);
+    assert_eq!(offset_of!(McoreTextMetrics, advance_h), 4);
+    assert_eq!(offset_of!(McoreTextMetrics, line_count), 8);
+    assert_eq!(offset_of!(McoreTextMetrics, truncated), 12);
+}
+
+#[test]
+fn mcore_text_size_layout() {
+    assert_eq!(size_of::<McoreTextSize>(), 8);
+    assert_eq!(align_of::<McoreTextSize>(), 4);
+    assert_eq!(offset_of!(McoreTextSize, width), 0);
+    assert_eq!(offset_of!(McoreTextSize, height), 4);
+}
+
+#[test]
+fn mcore_caret_rect_layout() {
+    assert_eq!(size_of::<McoreCaretRect>(), 12);
+    assert_eq!(align_of::<McoreCaretRect>(), 4);
+    assert_eq!(offset_of!(McoreCaretRect, x), 0);
+    assert_eq!(offset_of!(McoreCaretRect, y), 4);
+    assert_eq!(offset_of!(McoreCaretRect, height), 8);
+}
+
+#[test]
+fn mcore_text_stats_layout() {
+    assert_eq!(size_of::<McoreTextStats>(), 8);
+    assert_eq!(align_of::<McoreTextStats>(), 4);
+    assert_eq!(offset_of!(McoreTextStats, total_measure_calls), 0);
+    assert_eq!(offset_of!(McoreTextStats, total_offset_calls), 4);
+}
+
+#[test]
+fn mcore_hit_transform_layout() {
+    assert_eq!(size_of::<McoreHitTransform>(), 12);
+    assert_eq!(align_of::<McoreHitTransform>(), 4);
+    assert_eq!(offset_of!(McoreHitTransform, dx), 0);
+    assert_eq!(offset_of!(McoreHitTransform, dy), 4);
+    assert_eq!(offset_of!(McoreHitTransform, scale), 8);
+}
+
+#[test]
+fn mcore_hit_result_layout() {
+    assert_eq!(size_of::<McoreHitResult>(), 24);
+    assert_eq!(align_of::<McoreHitResult>(), 8);
+    assert_eq!(offset_of!(McoreHitResult, id), 0);
+    assert_eq!(offset_of!(McoreHitResult, z), 8);
+    assert_eq!(offset_of!(McoreHitResult, local_x), 12);
+    assert_eq!(offset_of!(McoreHitResult, local_y), 16);
+}
+
+#[test]
+fn mcore_text_cache_stats_layout() {
+    assert_eq!(size_of::<McoreTextCacheStats>(), 48);
+    assert_eq!(align_of::<McoreTextCacheStats>(), 8);
+    assert_eq!(offset_of!(McoreTextCacheStats, entries), 0);
+    assert_eq!(offset_of!(McoreTextCacheStats, max_entries), 4);
+    assert_eq!(offset_of!(McoreTextCacheStats, bytes), 8);
+    assert_eq!(offset_of!(McoreTextCacheStats, max_bytes), 16);
+    assert_eq!(offset_of!(McoreTextCacheStats, hits), 24);
+    assert_eq!(offset_of!(McoreTextCacheStats, misses), 32);
+    assert_eq!(offset_of!(McoreTextCacheStats, evictions), 40);
+}
+
+#[test]
+fn mcore_draw_cmd_kind_layout() {
+    assert_eq!(size_of::<McoreDrawCmdKind>(), 4);
+    assert_eq!(align_of::<McoreDrawCmdKind>(), 4);
+}
+
+#[test]
+fn mcore_draw_command_layout() {
+    assert_eq!(size_of::<McoreDrawCommand>(), 120);
+    assert_eq!(align_of::<McoreDrawCommand>(), 8);
+    assert_eq!(offset_of!(McoreDrawCommand, kind), 0);
+    assert_eq!(offset_of!(McoreDrawCommand, x), 4);
+    assert_eq!(offset_of!(McoreDrawCommand, y), 8);
+    assert_eq!(offset_of!(McoreDrawCommand, width), 12);
+    assert_eq!(offset_of!(McoreDrawCommand, height), 16);
+    assert_eq!(offset_of!(McoreDrawCommand, radius), 20);
+    assert_eq!(offset_of!(McoreDrawCommand, color), 24);
+    assert_eq!(offset_of!(McoreDrawCommand, text_ptr), 40);
+    assert_eq!(offset_of!(McoreDrawCommand, font_size), 48);
+    assert_eq!(offset_of!(McoreDrawCommand, wrap_width), 52);
+    assert_eq!(offset_of!(McoreDrawCommand, font_id), 56);
+    assert_eq!(offset_of!(McoreDrawCommand, border_width), 60);
+    assert_eq!(offset_of!(McoreDrawCommand, border_color), 64);
+    assert_eq!(offset_of!(McoreDrawCommand, has_border), 80);
+    assert_eq!(offset_of!(McoreDrawCommand, shadow_offset_x), 84);
+    assert_eq!(offset_of!(McoreDrawCommand, shadow_offset_y), 88);
+    assert_eq!(offset_of!(McoreDrawCommand, shadow_blur), 92);
+    assert_eq!(offset_of!(McoreDrawCommand, shadow_color), 96);
+    assert_eq!(offset_of!(McoreDrawCommand, has_shadow), 112);
+    assert_eq!(offset_of!(McoreDrawCommand, text_align), 113);
+    assert_eq!(offset_of!(McoreDrawCommand, text_direction), 114);
+}
+
+#[test]
+fn mcore_cmd_header_layout() {
+    assert_eq!(size_of::<McoreCmdHeader>(), 8);
+    assert_eq!(align_of::<McoreCmdHeader>(), 4);
+    assert_eq!(offset_of!(McoreCmdHeader, tag), 0);
+    assert_eq!(offset_of!(McoreCmdHeader, flags), 2);
+    assert_eq!(offset_of!(McoreCmdHeader, size), 4);
+}
+
+#[test]
+fn mcore_cmd_tag_layout() {
+    assert_eq!(size_of::<McoreCmdTag>(), 4);
+    assert_eq!(align_of::<McoreCmdTag>(), 4);
+}
+
+#[test]
+fn mcore_cmd_fill_layout() {
+    assert_eq!(size_of::<McoreCmdFill>(), 36);
+    assert_eq!(align_of::<McoreCmdFill>(), 4);
+    assert_eq!(offset_of!(McoreCmdFill, x), 0);
+    assert_eq!(offset_of!(McoreCmdFill, y), 4);
+    assert_eq!(offset_of!(McoreCmdFill, width), 8);
+    assert_eq!(offset_of!(McoreCmdFill, height), 12);
+    assert_eq!(offset_of!(McoreCmdFill, radius), 16);
+    assert_eq!(offset_of!(McoreCmdFill, color), 20);
+}
+
+#[test]
+fn mcore_cmd_stroke_layout() {
+    assert_eq!(size_of::<McoreCmdStroke>(), 40);
+    assert_eq!(align_of::<McoreCmdStroke>(), 4);
+    assert_eq!(offset_of!(McoreCmdStroke, x), 0);
+    assert_eq!(offset_of!(McoreCmdStroke, y), 4);
+    assert_eq!(offset_of!(McoreCmdStroke, width), 8);
+    assert_eq!(offset_of!(McoreCmdStroke, height), 12);
+    assert_eq!(offset_of!(McoreCmdStroke, radius), 16);
+    assert_eq!(offset_of!(McoreCmdStroke, line_width), 20);
+    assert_eq!(offset_of!(McoreCmdStroke, color), 24);
+}
+
+#[test]
+fn mcore_cmd_shadow_layout() {
+    assert_eq!(size_of::<McoreCmdShadow>(), 40);
+    assert_eq!(align_of::<McoreCmdShadow>(), 4);
+    assert_eq!(offset_of!(McoreCmdShadow, x), 0);
+    assert_eq!(offset_of!(McoreCmdShadow, y), 4);
+    assert_eq!(offset_of!(McoreCmdShadow, width), 8);
+    assert_eq!(offset_of!(McoreCmdShadow, height), 12);
+    assert_eq!(offset_of!(McoreCmdShadow, radius), 16);
+    assert_eq!(offset_of!(McoreCmdShadow, blur), 20);
+    assert_eq!(offset_of!(McoreCmdShadow, color), 24);
+}
+
+#[test]
+fn mcore_cmd_text_layout() {
+    assert_eq!(size_of::<McoreCmdText>(), 48);
+    assert_eq!(align_of::<McoreCmdText>(), 4);
+    assert_eq!(offset_of!(McoreCmdText, x), 0);
+    assert_eq!(offset_of!(McoreCmdText, y), 4);
+    assert_eq!(offset_of!(McoreCmdText, font_size), 8);
+    assert_eq!(offset_of!(McoreCmdText, wrap_width), 12);
+    assert_eq!(offset_of!(McoreCmdText, font_id), 16);
+    assert_eq!(offset_of!(McoreCmdText, color), 20);
+    assert_eq!(offset_of!(McoreCmdText, align), 36);
+    assert_eq!(offset_of!(McoreCmdText, direction), 40);
+    assert_eq!(offset_of!(McoreCmdText, text_len), 44);
+}
+
+#[test]
+fn mcore_cmd_clip_layout() {
+    assert_eq!(size_of::<McoreCmdClip>(), 20);
+    assert_eq!(align_of::<McoreCmdClip>(), 4);
+    assert_eq!(offset_of!(McoreCmdClip, x), 0);
+    assert_eq!(offset_of!(McoreCmdClip, y), 4);
+    assert_eq!(offset_of!(McoreCmdClip, width), 8);
+    assert_eq!(offset_of!(McoreCmdClip, height), 12);
+    assert_eq!(offset_of!(McoreCmdClip, radius), 16);
+}
+
+#[test]
+fn mcore_cmd_gradient_layout() {
+    assert_eq!(size_of::<McoreCmdGradient>(), 28);
+    assert_eq!(align_of::<McoreCmdGradient>(), 4);
+    assert_eq!(offset_of!(McoreCmdGradient, x), 0);
+    assert_eq!(offset_of!(McoreCmdGradient, y), 4);
+    assert_eq!(offset_of!(McoreCmdGradient, width), 8);
+    assert_eq!(offset_of!(McoreCmdGradient, height), 12);
+    assert_eq!(offset_of!(McoreCmdGradient, radius), 16);
+    assert_eq!(offset_of!(McoreCmdGradient, gradient_id), 20);
+    assert_eq!(offset_of!(McoreCmdGradient, line_width), 24);
+}
+
+#[test]
+fn mcore_cmd_image_layout() {
+    assert_eq!(size_of::<McoreCmdImage>(), 48);
+    assert_eq!(align_of::<McoreCmdImage>(), 4);
+    assert_eq!(offset_of!(McoreCmdImage, x), 0);
+    assert_eq!(offset_of!(McoreCmdImage, y), 4);
+    assert_eq!(offset_of!(McoreCmdImage, width), 8);
+    assert_eq!(offset_of!(McoreCmdImage, height), 12);
+    assert_eq!(offset_of!(McoreCmdImage, image_id), 16);
+    assert_eq!(offset_of!(McoreCmdImage, sampling), 20);
+    assert_eq!(offset_of!(McoreCmdImage, extend_x), 24);
+    assert_eq!(offset_of!(McoreCmdImage, extend_y), 28);
+    assert_eq!(offset_of!(McoreCmdImage, tile_x), 32);
+    assert_eq!(offset_of!(McoreCmdImage, tile_y), 36);
+    assert_eq!(offset_of!(McoreCmdImage, tile_width), 40);
+    assert_eq!(offset_of!(McoreCmdImage, tile_height), 44);
+}
+
+#[test]
+fn mcore_cmd_transform_layout() {
+    assert_eq!(size_of::<McoreCmdTransform>(), 24);
+    assert_eq!(align_of::<McoreCmdTransform>(), 4);
+    assert_eq!(offset_of!(McoreCmdTransform, matrix), 0);
+}
+
+#[test]
+fn mcore_cmd_video_layout() {
+    assert_eq!(size_of::<McoreCmdVideo>(), 24);
+    assert_eq!(align_of::<McoreCmdVideo>(), 4);
+    assert_eq!(offset_of!(McoreCmdVideo, x), 0);
+    assert_eq!(offset_of!(McoreCmdVideo, y), 4);
+    assert_eq!(offset_of!(McoreCmdVideo, width), 8);
+    assert_eq!(offset_of!(McoreCmdVideo, height), 12);
+    assert_eq!(offset_of!(McoreCmdVideo, video_id), 16);
+    assert_eq!(offset_of!(McoreCmdVideo, fit), 20);
+}
+
+#[test]
+fn mcore_color_layout() {
+    assert_eq!(size_of::<McoreColor>(), 16);
+    assert_eq!(align_of::<McoreColor>(), 4);
+    assert_eq!(offset_of!(McoreColor, r), 0);
+    assert_eq!(offset_of!(McoreColor, g), 4);
+    assert_eq!(offset_of!(McoreColor, b), 8);
+    assert_eq!(offset_of!(McoreColor, a), 12);
+}
+
+#[test]
+fn mcore_status_layout() {
+    assert_eq!(size_of::<McoreStatus>(), 4);
+    assert_eq!(align_of::<McoreStatus>(), 4);
+}
+
+#[test]
+fn mcore_text_block_kind_layout() {
+    assert_eq!(size_of::<McoreTextBlockKind>(), 4);
+    assert_eq!(align_of::<McoreTextBlockKind>(), 4);
+}
+
+#[test]
+fn mcore_text_run_layout() {
+    assert_eq!(size_of::<McoreTextRun>(), 28);
+    assert_eq!(align_of::<McoreTextRun>(), 4);
+    assert_eq!(offset_of!(McoreTextRun, start), 0);
+    assert_eq!(offset_of!(McoreTextRun, end), 4);
+    assert_eq!(offset_of!(McoreTextRun, flags), 8);
+    assert_eq!(offset_of!(McoreTextRun, color), 12);
+}
+
+#[test]
+fn mcore_text_block_paragraph_layout() {
+    assert_eq!(size_of::<McoreTextBlockParagraph>(), 32);
+    assert_eq!(align_of::<McoreTextBlockParagraph>(), 8);
+    assert_eq!(offset_of!(McoreTextBlockParagraph, utf8), 0);
+    assert_eq!(offset_of!(McoreTextBlockParagraph, kind), 8);
+    assert_eq!(offset_of!(McoreTextBlockParagraph, level), 12);
+    assert_eq!(offset_of!(McoreTextBlockParagraph, number), 16);
+    assert_eq!(offset_of!(McoreTextBlockParagraph, run_count), 20);
+    assert_eq!(offset_of!(McoreTextBlockParagraph, runs), 24);
+}
+
+#[test]
+fn mcore_text_block_style_layout() {
+    assert_eq!(size_of::<McoreTextBlockStyle>(), 72);
+    assert_eq!(align_of::<McoreTextBlockStyle>(), 4);
+    assert_eq!(offset_of!(McoreTextBlockStyle, font_size_px), 0);
+    assert_eq!(offset_of!(McoreTextBlockStyle, font_id), 4);
+    assert_eq!(offset_of!(McoreTextBlockStyle, code_font_id), 8);
+    assert_eq!(offset_of!(McoreTextBlockStyle, line_height_multiplier), 12);
+    assert_eq!(offset_of!(McoreTextBlockStyle, paragraph_spacing), 16);
+    assert_eq!(offset_of!(McoreTextBlockStyle, list_indent), 20);
+    assert_eq!(offset_of!(McoreTextBlockStyle, color), 24);
+    assert_eq!(offset_of!(McoreTextBlockStyle, code_color), 40);
+    assert_eq!(offset_of!(McoreTextBlockStyle, code_background), 56);
+}
+
+#[test]
+fn mcore_code_req_layout() {
+    assert_eq!(size_of::<McoreCodeReq>(), 80);
+    assert_eq!(align_of::<McoreCodeReq>(), 8);
+    assert_eq!(offset_of!(McoreCodeReq, text), 0);
+    assert_eq!(offset_of!(McoreCodeReq, len), 8);
+    assert_eq!(offset_of!(McoreCodeReq, font_size_px), 16);
+    assert_eq!(offset_of!(McoreCodeReq, font_id), 20);
+    assert_eq!(offset_of!(McoreCodeReq, line_height_multiplier), 24);
+    assert_eq!(offset_of!(McoreCodeReq, tab_size), 28);
+    assert_eq!(offset_of!(McoreCodeReq, line_numbers), 32);
+    assert_eq!(offset_of!(McoreCodeReq, scroll_y), 36);
+    assert_eq!(offset_of!(McoreCodeReq, viewport_height), 40);
+    assert_eq!(offset_of!(McoreCodeReq, color), 44);
+    assert_eq!(offset_of!(McoreCodeReq, line_number_color), 60);
+}
+
+#[test]
+fn mcore_code_token_layout() {
+    assert_eq!(size_of::<McoreCodeToken>(), 24);
+    assert_eq!(align_of::<McoreCodeToken>(), 4);
+    assert_eq!(offset_of!(McoreCodeToken, start), 0);
+    assert_eq!(offset_of!(McoreCodeToken, end), 4);
+    assert_eq!(offset_of!(McoreCodeToken, color), 8);
+}
+
+#[test]
+fn mcore_code_metrics_layout() {
+    assert_eq!(size_of::<McoreCodeMetrics>(), 16);
+    assert_eq!(align_of::<McoreCodeMetrics>(), 4);
+    assert_eq!(offset_of!(McoreCodeMetrics, line_count), 0);
+    assert_eq!(offset_of!(McoreCodeMetrics, line_height), 4);
+    assert_eq!(offset_of!(McoreCodeMetrics, gutter_width), 8);
+    assert_eq!(offset_of!(McoreCodeMetrics, content_height), 12);
+}
+
+#[test]
+fn mcore_gradient_stop_layout() {
+    assert_eq!(size_of::<McoreGradientStop>(), 20);
+    assert_eq!(align_of::<McoreGradientStop>(), 4);
+    assert_eq!(offset_of!(McoreGradientStop, offset), 0);
+    assert_eq!(offset_of!(McoreGradientStop, color), 4);
+}
+
+#[test]
+fn mcore_gradient_desc_layout() {
+    assert_eq!(size_of::<McoreGradientDesc>(), 24);
+    assert_eq!(align_of::<McoreGradientDesc>(), 8);
+    assert_eq!(offset_of!(McoreGradientDesc, kind), 0);
+    assert_eq!(offset_of!(McoreGradientDesc, _padding), 1);
+    assert_eq!(offset_of!(McoreGradientDesc, angle_deg), 4);
+    assert_eq!(offset_of!(McoreGradientDesc, stops), 8);
+    assert_eq!(offset_of!(McoreGradientDesc, stop_count), 16);
+}
+
+#[test]
+fn mcore_frame_stats_layout() {
+    assert_eq!(size_of::<McoreFrameStats>(), 80);
+    assert_eq!(align_of::<McoreFrameStats>(), 8);
+    assert_eq!(offset_of!(McoreFrameStats, frame), 0);
+    assert_eq!(offset_of!(McoreFrameStats, build_ms), 8);
+    assert_eq!(offset_of!(McoreFrameStats, encode_ms), 12);
+    assert_eq!(offset_of!(McoreFrameStats, custom_ms), 16);
+    assert_eq!(offset_of!(McoreFrameStats, render_ms), 20);
+    assert_eq!(offset_of!(McoreFrameStats, gpu_ms), 24);
+    assert_eq!(offset_of!(McoreFrameStats, commands), 28);
+    assert_eq!(offset_of!(McoreFrameStats, encode_calls), 32);
+    assert_eq!(offset_of!(McoreFrameStats, paths), 36);
+    assert_eq!(offset_of!(McoreFrameStats, path_segments), 40);
+    assert_eq!(offset_of!(McoreFrameStats, clips), 44);
+    assert_eq!(offset_of!(McoreFrameStats, draw_objects), 48);
+    assert_eq!(offset_of!(McoreFrameStats, image_count), 52);
+    assert_eq!(offset_of!(McoreFrameStats, image_bytes), 56);
+    assert_eq!(offset_of!(McoreFrameStats, texture_bytes), 64);
+    assert_eq!(offset_of!(McoreFrameStats, layer_warnings), 72);
+}
+
+#[test]
+fn mcore_aa_mode_layout() {
+    assert_eq!(size_of::<McoreAaMode>(), 4);
+    assert_eq!(align_of::<McoreAaMode>(), 4);
+}
+
+#[test]
+fn mcore_text_rendering_layout() {
+    assert_eq!(size_of::<McoreTextRendering>(), 8);
+    assert_eq!(align_of::<McoreTextRendering>(), 4);
+    assert_eq!(offset_of!(McoreTextRendering, hinting), 0);
+    assert_eq!(offset_of!(McoreTextRendering, subpixel_positions), 4);
+}
+
+#[test]
+fn mcore_text_event_kind_layout() {
+    assert_eq!(size_of::<McoreTextEventKind>(), 4);
+    assert_eq!(align_of::<McoreTextEventKind>(), 4);
+}
+
+#[test]
+fn mcore_cursor_direction_layout() {
+    assert_eq!(size_of::<McoreCursorDirection>(), 4);
+    assert_eq!(align_of::<McoreCursorDirection>(), 4);
+}
+
+#[test]
+fn mcore_text_event_layout() {
+    assert_eq!(size_of::<McoreTextEvent>(), 32);
+    assert_eq!(align_of::<McoreTextEvent>(), 8);
+    assert_eq!(offset_of!(McoreTextEvent, kind), 0);
+    assert_eq!(offset_of!(McoreTextEvent, char_code), 4);
+    assert_eq!(offset_of!(McoreTextEvent, direction), 8);
+    assert_eq!(offset_of!(McoreTextEvent, extend_selection), 12);
+    assert_eq!(offset_of!(McoreTextEvent, cursor_position), 16);
+    assert_eq!(offset_of!(McoreTextEvent, text_ptr), 24);
+}
+
+#[test]
+fn mcore_text_event_result_layout() {
+    assert_eq!(size_of::<McoreTextEventResult>(), 12);
+    assert_eq!(align_of::<McoreTextEventResult>(), 4);
+    assert_eq!(offset_of!(McoreTextEventResult, changed), 0);
+    assert_eq!(offset_of!(McoreTextEventResult, scrolled), 1);
+    assert_eq!(offset_of!(McoreTextEventResult, _padding), 2);
+    assert_eq!(offset_of!(McoreTextEventResult, scroll_x), 4);
+    assert_eq!(offset_of!(McoreTextEventResult, scroll_y), 8);
+}
+
+#[test]
+fn mcore_text_input_style_layout() {
+    assert_eq!(size_of::<McoreTextInputStyle>(), 84);
+    assert_eq!(align_of::<McoreTextInputStyle>(), 4);
+    assert_eq!(offset_of!(McoreTextInputStyle, x), 0);
+    assert_eq!(offset_of!(McoreTextInputStyle, y), 4);
+    assert_eq!(offset_of!(McoreTextInputStyle, width), 8);
+    assert_eq!(offset_of!(McoreTextInputStyle, height), 12);
+    assert_eq!(offset_of!(McoreTextInputStyle, padding_x), 16);
+    assert_eq!(offset_of!(McoreTextInputStyle, padding_y), 20);
+    assert_eq!(offset_of!(McoreTextInputStyle, font_size), 24);
+    assert_eq!(offset_of!(McoreTextInputStyle, wrap_width), 28);
+    assert_eq!(offset_of!(McoreTextInputStyle, text_color), 32);
+    assert_eq!(offset_of!(McoreTextInputStyle, selection_color), 48);
+    assert_eq!(offset_of!(McoreTextInputStyle, caret_color), 64);
+    assert_eq!(offset_of!(McoreTextInputStyle, focused), 80);
+    assert_eq!(offset_of!(McoreTextInputStyle, _padding), 81);
+}
+
+#[test]
+fn mcore_text_input_hints_layout() {
+    assert_eq!(size_of::<McoreTextInputHints>(), 4);
+    assert_eq!(align_of::<McoreTextInputHints>(), 1);
+    assert_eq!(offset_of!(McoreTextInputHints, keyboard), 0);
+    assert_eq!(offset_of!(McoreTextInputHints, autocorrect), 1);
+    assert_eq!(offset_of!(McoreTextInputHints, secure), 2);
+    assert_eq!(offset_of!(McoreTextInputHints, ime_enabled), 3);
+}
+
+#[test]
+fn mcore_cursor_layout() {
+    assert_eq!(size_of::<McoreCursor>(), 4);
+    assert_eq!(align_of::<McoreCursor>(), 4);
+}
+
+#[test]
+fn mcore_ime_preedit_layout() {
+    assert_eq!(size_of::<McoreImePreedit>(), 16);
+    assert_eq!(align_of::<McoreImePreedit>(), 8);
+    assert_eq!(offset_of!(McoreImePreedit, text), 0);
+    assert_eq!(offset_of!(McoreImePreedit, cursor_offset), 8);
+}
+
+#[test]
+fn mcore_pointer_phase_layout() {
+    assert_eq!(size_of::<McorePointerPhase>(), 4);
+    assert_eq!(align_of::<McorePointerPhase>(), 4);
+}
+
+#[test]
+fn mcore_gesture_layout() {
+    assert_eq!(size_of::<McoreGesture>(), 28);
+    assert_eq!(align_of::<McoreGesture>(), 4);
+    assert_eq!(offset_of!(McoreGesture, kind), 0);
+    assert_eq!(offset_of!(McoreGesture, x), 4);
+    assert_eq!(offset_of!(McoreGesture, y), 8);
+    assert_eq!(offset_of!(McoreGesture, dx), 12);
+    assert_eq!(offset_of!(McoreGesture, dy), 16);
+    assert_eq!(offset_of!(McoreGesture, velocity_x), 20);
+    assert_eq!(offset_of!(McoreGesture, velocity_y), 24);
+}
+
+#[test]
+fn mcore_scroll_state_layout() {
+    assert_eq!(size_of::<McoreScrollState>(), 20);
+    assert_eq!(align_of::<McoreScrollState>(), 4);
+    assert_eq!(offset_of!(McoreScrollState, offset_x), 0);
+    assert_eq!(offset_of!(McoreScrollState, offset_y), 4);
+    assert_eq!(offset_of!(McoreScrollState, velocity_x), 8);
+    assert_eq!(offset_of!(McoreScrollState, velocity_y), 12);
+    assert_eq!(offset_of!(McoreScrollState, animating), 16);
+}
+
+#[test]
+fn mcore_scroll_phase_layout() {
+    assert_eq!(size_of::<McoreScrollPhase>(), 4);
+    assert_eq!(align_of::<McoreScrollPhase>(), 4);
+}
+
+#[test]
+fn mcore_scroll_axis_layout() {
+    assert_eq!(size_of::<McoreScrollAxis>(), 4);
+    assert_eq!(align_of::<McoreScrollAxis>(), 4);
+}
+
+#[test]
+fn mcore_layout_direction_layout() {
+    assert_eq!(size_of::<McoreLayoutDirection>(), 4);
+    assert_eq!(align_of::<McoreLayoutDirection>(), 4);
+}
+
+#[test]
+fn mcore_layout_justify_layout() {
+    assert_eq!(size_of::<McoreLayoutJustify>(), 4);
+    assert_eq!(align_of::<McoreLayoutJustify>(), 4);
+}
+
+#[test]
+fn mcore_layout_align_layout() {
+    assert_eq!(size_of::<McoreLayoutAlign>(), 4);
+    assert_eq!(align_of::<McoreLayoutAlign>(), 4);
+}
+
+#[test]
+fn mcore_edges_layout() {
+    assert_eq!(size_of::<McoreEdges>(), 16);
+    assert_eq!(align_of::<McoreEdges>(), 4);
+    assert_eq!(offset_of!(McoreEdges, top), 0);
+    assert_eq!(offset_of!(McoreEdges, right), 4);
+    assert_eq!(offset_of!(McoreEdges, bottom), 8);
+    assert_eq!(offset_of!(McoreEdges, left), 12);
+}
+
+#[test]
+fn mcore_layout_style_layout() {
+    assert_eq!(size_of::<McoreLayoutStyle>(), 84);
+    assert_eq!(align_of::<McoreLayoutStyle>(), 4);
+    assert_eq!(offset_of!(McoreLayoutStyle, direction), 0);
+    assert_eq!(offset_of!(McoreLayoutStyle, wrap), 1);
+    assert_eq!(offset_of!(McoreLayoutStyle, justify), 2);
+    assert_eq!(offset_of!(McoreLayoutStyle, align_items), 3);
+    assert_eq!(offset_of!(McoreLayoutStyle, align_self), 4);
+    assert_eq!(offset_of!(McoreLayoutStyle, hidden), 5);
+    assert_eq!(offset_of!(McoreLayoutStyle, width), 8);
+    assert_eq!(offset_of!(McoreLayoutStyle, height), 12);
+    assert_eq!(offset_of!(McoreLayoutStyle, min_width), 16);
+    assert_eq!(offset_of!(McoreLayoutStyle, min_height), 20);
+    assert_eq!(offset_of!(McoreLayoutStyle, max_width), 24);
+    assert_eq!(offset_of!(McoreLayoutStyle, max_height), 28);
+    assert_eq!(offset_of!(McoreLayoutStyle, grow), 32);
+    assert_eq!(offset_of!(McoreLayoutStyle, shrink), 36);
+    assert_eq!(offset_of!(McoreLayoutStyle, basis), 40);
+    assert_eq!(offset_of!(McoreLayoutStyle, padding), 44);
+    assert_eq!(offset_of!(McoreLayoutStyle, margin), 60);
+    assert_eq!(offset_of!(McoreLayoutStyle, row_gap), 76);
+    assert_eq!(offset_of!(McoreLayoutStyle, column_gap), 80);
+}
+
+#[test]
+fn mcore_ui_theme_layout() {
+    assert_eq!(size_of::<McoreUiTheme>(), 112);
+    assert_eq!(align_of::<McoreUiTheme>(), 4);
+    assert_eq!(offset_of!(McoreUiTheme, font_size), 0);
+    assert_eq!(offset_of!(McoreUiTheme, padding), 4);
+    assert_eq!(offset_of!(McoreUiTheme, spacing), 8);
+    assert_eq!(offset_of!(McoreUiTheme, corner_radius), 12);
+    assert_eq!(offset_of!(McoreUiTheme, text), 16);
+    assert_eq!(offset_of!(McoreUiTheme, button), 32);
+    assert_eq!(offset_of!(McoreUiTheme, button_hover), 48);
+    assert_eq!(offset_of!(McoreUiTheme, button_pressed), 64);
+    assert_eq!(offset_of!(McoreUiTheme, field), 80);
+    assert_eq!(offset_of!(McoreUiTheme, accent), 96);
+}
+
+#[test]
+fn mcore_ui_input_layout() {
+    assert_eq!(size_of::<McoreUiInput>(), 12);
+    assert_eq!(align_of::<McoreUiInput>(), 4);
+    assert_eq!(offset_of!(McoreUiInput, pointer_x), 0);
+    assert_eq!(offset_of!(McoreUiInput, pointer_y), 4);
+    assert_eq!(offset_of!(McoreUiInput, pointer_down), 8);
+    assert_eq!(offset_of!(McoreUiInput, _padding), 9);
+}
+
+#[test]
+fn mcore_texture_target_layout() {
+    assert_eq!(size_of::<McoreTextureTarget>(), 24);
+    assert_eq!(align_of::<McoreTextureTarget>(), 8);
+    assert_eq!(offset_of!(McoreTextureTarget, native_texture), 0);
+    assert_eq!(offset_of!(McoreTextureTarget, width), 8);
+    assert_eq!(offset_of!(McoreTextureTarget, height), 12);
+    assert_eq!(offset_of!(McoreTextureTarget, time_seconds), 16);
+}
+
+#[test]
+fn mcore_video_frame_layout() {
+    assert_eq!(size_of::<McoreVideoFrame>(), 56);
+    assert_eq!(align_of::<McoreVideoFrame>(), 8);
+    assert_eq!(offset_of!(McoreVideoFrame, format), 0);
+    assert_eq!(offset_of!(McoreVideoFrame, width), 4);
+    assert_eq!(offset_of!(McoreVideoFrame, height), 8);
+    assert_eq!(offset_of!(McoreVideoFrame, matrix), 12);
+    assert_eq!(offset_of!(McoreVideoFrame, full_range), 16);
+    assert_eq!(offset_of!(McoreVideoFrame, _reserved), 20);
+    assert_eq!(offset_of!(McoreVideoFrame, planes), 24);
+    assert_eq!(offset_of!(McoreVideoFrame, strides), 40);
+    assert_eq!(offset_of!(McoreVideoFrame, iosurface), 48);
+}
+
+#[test]
+fn mcore_particle_layer_layout() {
+    assert_eq!(size_of::<McoreParticleLayer>(), 4);
+    assert_eq!(align_of::<McoreParticleLayer>(), 4);
+}
+
+#[test]
+fn mcore_particle_emitter_layout() {
+    assert_eq!(size_of::<McoreParticleEmitter>(), 112);
+    assert_eq!(align_of::<McoreParticleEmitter>(), 4);
+    assert_eq!(offset_of!(McoreParticleEmitter, x), 0);
+    assert_eq!(offset_of!(McoreParticleEmitter, y), 4);
+    assert_eq!(offset_of!(McoreParticleEmitter, count), 8);
+    assert_eq!(offset_of!(McoreParticleEmitter, lifetime), 12);
+    assert_eq!(offset_of!(McoreParticleEmitter, speed_min), 16);
+    assert_eq!(offset_of!(McoreParticleEmitter, speed_max), 20);
+    assert_eq!(offset_of!(McoreParticleEmitter, direction), 24);
+    assert_eq!(offset_of!(McoreParticleEmitter, spread), 28);
+    assert_eq!(offset_of!(McoreParticleEmitter, gravity), 32);
+    assert_eq!(offset_of!(McoreParticleEmitter, size), 36);
+    assert_eq!(offset_of!(McoreParticleEmitter, colors), 40);
+    assert_eq!(offset_of!(McoreParticleEmitter, looping), 104);
+    assert_eq!(offset_of!(McoreParticleEmitter, layer), 108);
+}
+
+#[test]
+fn mcore_a11y_node_layout() {
+    assert_eq!(size_of::<McoreA11yNode>(), 88);
+    assert_eq!(align_of::<McoreA11yNode>(), 8);
+    assert_eq!(offset_of!(McoreA11yNode, id), 0);
+    assert_eq!(offset_of!(McoreA11yNode, role), 8);
+    assert_eq!(offset_of!(McoreA11yNode, label), 16);
+    assert_eq!(offset_of!(McoreA11yNode, bounds), 24);
+    assert_eq!(offset_of!(McoreA11yNode, actions), 40);
+    assert_eq!(offset_of!(McoreA11yNode, children), 48);
+    assert_eq!(offset_of!(McoreA11yNode, children_count), 56);
+    assert_eq!(offset_of!(McoreA11yNode, value), 64);
+    assert_eq!(offset_of!(McoreA11yNode, text_selection_start), 72);
+    assert_eq!(offset_of!(McoreA11yNode, text_selection_end), 76);
+    assert_eq!(offset_of!(McoreA11yNode, states), 80);
+}
+
+#[test]
+fn mcore_rect_layout() {
+    assert_eq!(size_of::<McoreRect>(), 16);
+    assert_eq!(align_of::<McoreRect>(), 4);
+    assert_eq!(offset_of!(McoreRect, x), 0);
+    assert_eq!(offset_of!(McoreRect, y), 4);
+    assert_eq!(offset_of!(McoreRect, width), 8);
+    assert_eq!(offset_of!(McoreRect, height), 12);
+}
+
+#[test]
+fn mcore_a11y_prefs_layout() {
+    assert_eq!(size_of::<McoreA11yPrefs>(), 3);
+    assert_eq!(align_of::<McoreA11yPrefs>(), 1);
+    assert_eq!(offset_of!(McoreA11yPrefs, reduce_motion), 0);
+    assert_eq!(offset_of!(McoreA11yPrefs, increase_contrast), 1);
+    assert_eq!(offset_of!(McoreA11yPrefs, reduce_transparency), 2);
+}
+
+#[test]
+fn mcore_color_space_layout() {
+    assert_eq!(size_of::<McoreColorSpace>(), 4);
+    assert_eq!(align_of::<McoreColorSpace>(), 4);
+}
+
+#[test]
+fn mcore_image_desc_layout() {
+    assert_eq!(size_of::<McoreImageDesc>(), 24);
+    assert_eq!(align_of::<McoreImageDesc>(), 8);
+    assert_eq!(offset_of!(McoreImageDesc, data), 0);
+    assert_eq!(offset_of!(McoreImageDesc, data_len), 8);
+    assert_eq!(offset_of!(McoreImageDesc, width), 12);
+    assert_eq!(offset_of!(McoreImageDesc, height), 16);
+    assert_eq!(offset_of!(McoreImageDesc, format), 20);
+    assert_eq!(offset_of!(McoreImageDesc, alpha_type), 21);
+}
+
+#[test]
+fn mcore_image_transform_layout() {
+    assert_eq!(size_of::<McoreImageTransform>(), 16);
+    assert_eq!(align_of::<McoreImageTransform>(), 4);
+    assert_eq!(offset_of!(McoreImageTransform, x), 0);
+    assert_eq!(offset_of!(McoreImageTransform, y), 4);
+    assert_eq!(offset_of!(McoreImageTransform, scale), 8);
+    assert_eq!(offset_of!(McoreImageTransform, rotation_deg), 12);
+}
+
+#[test]
+fn mcore_image_info_layout() {
+    assert_eq!(size_of::<McoreImageInfo>(), 12);
+    assert_eq!(align_of::<McoreImageInfo>(), 4);
+    assert_eq!(offset_of!(McoreImageInfo, image_id), 0);
+    assert_eq!(offset_of!(McoreImageInfo, width), 4);
+    assert_eq!(offset_of!(McoreImageInfo, height), 8);
+}
+
+#[test]
+fn mcore_image_cache_stats_layout() {
+    assert_eq!(size_of::<McoreImageCacheStats>(), 48);
+    assert_eq!(align_of::<McoreImageCacheStats>(), 8);
+    assert_eq!(offset_of!(McoreImageCacheStats, bytes), 0);
+    assert_eq!(offset_of!(McoreImageCacheStats, released_bytes), 8);
+    assert_eq!(offset_of!(McoreImageCacheStats, budget_bytes), 16);
+    assert_eq!(offset_of!(McoreImageCacheStats, evictions), 24);
+    assert_eq!(offset_of!(McoreImageCacheStats, image_count), 32);
+    assert_eq!(offset_of!(McoreImageCacheStats, released_count), 36);
+    assert_eq!(offset_of!(McoreImageCacheStats, pending_count), 40);
+    assert_eq!(offset_of!(McoreImageCacheStats, atlas_pages), 44);
+}
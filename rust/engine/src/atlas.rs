@@ -0,0 +1,277 @@
+/// Shared pages for small images (see ImageManager)
+///
+/// Vello uploads every image a scene uses on each render, one texture write
+/// apiece, and icon-heavy UIs draw hundreds of tiny ones. Small registered
+/// images are therefore also packed into shared pages; draws a page can serve
+/// (no tiling or mip levels) fill from the page with the image's offset
+/// folded into the brush transform, so a frame uploads a few pages whatever
+/// the icon count.
+///
+/// Pages start small and double up to PAGE_SIZE as they fill. Images are
+/// packed on shelves, each framed by a gutter repeating its edge pixels so
+/// bilinear filtering at its edges doesn't pick up its neighbors.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use peniko::{Blob, ImageData};
+use vello::peniko::{ImageAlphaType, ImageFormat};
+
+/// Images with a side longer than this get no atlas slot
+pub const MAX_SIDE: u32 = 128;
+const PAGE_SIZE: u32 = 1024;
+const FIRST_PAGE_SIZE: u32 = 256;
+const MAX_PAGES: usize = 8;
+const GUTTER: u32 = 1;
+
+/// Where an image's pixels sit in a page (gutter excluded)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Slot {
+    pub page: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// Where the unused part of the shelf starts
+    end: u32,
+    /// Cells freed by removed images: x and width
+    free: Vec<(u32, u32)>,
+}
+
+struct Page {
+    size: u32,
+    /// RGBA8, size x size; allocated on first use
+    pixels: Vec<u8>,
+    shelves: Vec<Shelf>,
+    images: usize,
+    /// The page as an image, until pixels change
+    image: Option<ImageData>,
+}
+
+impl Page {
+    fn new() -> Self {
+        Self { size: FIRST_PAGE_SIZE, pixels: Vec::new(), shelves: Vec::new(), images: 0, image: None }
+    }
+
+    /// Top-left of a free `width` x `height` cell
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let size = self.size;
+        // Shelves a bit taller than the cell are fine; much taller waste rows
+        let fits = |shelf: &Shelf| shelf.height >= height && shelf.height <= height + height / 2 + 2;
+        for shelf in self.shelves.iter_mut().filter(|shelf| fits(shelf)) {
+            if let Some(i) = shelf.free.iter().position(|&(_, free)| free >= width) {
+                let (x, free) = shelf.free[i];
+                if free == width {
+                    shelf.free.remove(i);
+                } else {
+                    shelf.free[i] = (x + width, free - width);
+                }
+                return Some((x, shelf.y));
+            }
+            if size - shelf.end >= width {
+                shelf.end += width;
+                return Some((shelf.end - width, shelf.y));
+            }
+        }
+        let top = self.shelves.last().map_or(0, |shelf| shelf.y + shelf.height);
+        if size - top < height || size < width {
+            return None;
+        }
+        self.shelves.push(Shelf { y: top, height, end: width, free: Vec::new() });
+        Some((0, top))
+    }
+
+    fn release(&mut self, x: u32, y: u32, width: u32) {
+        if let Some(shelf) = self.shelves.iter_mut().find(|shelf| shelf.y == y) {
+            shelf.free.push((x, width));
+        }
+        self.images -= 1;
+        if self.images == 0 {
+            *self = Self::new();
+        }
+    }
+
+    /// Double the page, keeping everything where it is
+    fn grow(&mut self) -> bool {
+        if self.size >= PAGE_SIZE {
+            return false;
+        }
+        let (old, size) = (self.size as usize, self.size as usize * 2);
+        if !self.pixels.is_empty() {
+            let mut pixels = vec![0; size * size * 4];
+            for (row, src) in self.pixels.chunks_exact(old * 4).enumerate() {
+                pixels[row * size * 4..][..old * 4].copy_from_slice(src);
+            }
+            self.pixels = pixels;
+        }
+        self.size = size as u32;
+        self.image = None;
+        true
+    }
+
+    /// Copy straight-alpha RGBA8 `pixels` to the cell at `x`, `y`, framed by
+    /// the gutter
+    fn write(&mut self, x: u32, y: u32, pixels: &[u8], width: u32, height: u32) {
+        let size = self.size as usize;
+        if self.pixels.is_empty() {
+            self.pixels = vec![0; size * size * 4];
+        }
+        let (w, h, g) = (width as i64, height as i64, GUTTER as i64);
+        for row in -g..h + g {
+            let src_row = row.clamp(0, h - 1) as usize;
+            let dst_row = (y as i64 + g + row) as usize;
+            for col in -g..w + g {
+                let src = (src_row * width as usize + col.clamp(0, w - 1) as usize) * 4;
+                let dst = (dst_row * size + (x as i64 + g + col) as usize) * 4;
+                self.pixels[dst..dst + 4].copy_from_slice(&pixels[src..src + 4]);
+            }
+        }
+        self.image = None;
+    }
+}
+
+#[derive(Default)]
+pub struct Atlas {
+    pages: Vec<Page>,
+    slots: HashMap<i32, Slot>,
+}
+
+impl Atlas {
+    /// Whether a width x height image gets a slot
+    pub fn fits(width: u32, height: u32) -> bool {
+        width > 0 && height > 0 && width <= MAX_SIDE && height <= MAX_SIDE
+    }
+
+    /// Pack image `id`'s straight-alpha RGBA8 pixels; None if it's too big
+    /// or every page is full
+    pub fn insert(&mut self, id: i32, pixels: &[u8], width: u32, height: u32) -> Option<Slot> {
+        if !Self::fits(width, height) || pixels.len() != (width * height * 4) as usize {
+            return None;
+        }
+        let (cell_width, cell_height) = (width + 2 * GUTTER, height + 2 * GUTTER);
+        let mut placed = None;
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            loop {
+                if let Some(cell) = page.allocate(cell_width, cell_height) {
+                    placed = Some((index, cell));
+                    break;
+                }
+                if !page.grow() {
+                    break;
+                }
+            }
+            if placed.is_some() {
+                break;
+            }
+        }
+        if placed.is_none() && self.pages.len() < MAX_PAGES {
+            let mut page = Page::new();
+            let cell = page.allocate(cell_width, cell_height)?;
+            self.pages.push(page);
+            placed = Some((self.pages.len() - 1, cell));
+        }
+        let (index, (x, y)) = placed?;
+        let page = &mut self.pages[index];
+        page.write(x, y, pixels, width, height);
+        page.images += 1;
+        let slot = Slot { page: index, x: x + GUTTER, y: y + GUTTER, width, height };
+        self.slots.insert(id, slot);
+        Some(slot)
+    }
+
+    pub fn remove(&mut self, id: i32) {
+        if let Some(slot) = self.slots.remove(&id) {
+            self.pages[slot.page].release(slot.x - GUTTER, slot.y - GUTTER, slot.width + 2 * GUTTER);
+        }
+    }
+
+    pub fn slot(&self, id: i32) -> Option<Slot> {
+        self.slots.get(&id).copied()
+    }
+
+    /// Page `index` as an image (a cheap clone after the first call since it
+    /// last changed)
+    pub fn page(&mut self, index: usize) -> Option<ImageData> {
+        let page = self.pages.get_mut(index)?;
+        let size = page.size;
+        let pixels = &page.pixels;
+        let image = page.image.get_or_insert_with(|| ImageData {
+            data: Blob::new(Arc::new(pixels.clone())),
+            format: ImageFormat::Rgba8,
+            width: size,
+            height: size,
+            alpha_type: ImageAlphaType::Alpha,
+        });
+        Some(image.clone())
+    }
+
+    /// Pages with at least one image
+    pub fn pages(&self) -> usize {
+        self.pages.iter().filter(|page| page.images > 0).count()
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.pages.iter().map(|page| page.pixels.len() as u64).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> Vec<u8> {
+        vec![value; (width * height * 4) as usize]
+    }
+
+    fn pixel(atlas: &mut Atlas, page: usize, x: u32, y: u32) -> [u8; 4] {
+        let image = atlas.page(page).unwrap();
+        let i = ((y * image.width + x) * 4) as usize;
+        image.data.data()[i..i + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_packing_with_gutters() {
+        let mut atlas = Atlas::default();
+        let a = atlas.insert(1, &solid(16, 16, 10), 16, 16).unwrap();
+        let b = atlas.insert(2, &solid(16, 16, 20), 16, 16).unwrap();
+        assert_eq!((a.page, a.x, a.y), (0, 1, 1));
+        assert_eq!((b.x, b.y), (19, 1), "next cell on the shelf, past both gutters");
+        assert!(atlas.insert(3, &solid(200, 8, 0), 200, 8).is_none(), "too big for the atlas");
+
+        // Gutters repeat the edge pixels
+        assert_eq!(pixel(&mut atlas, 0, 0, 0), [10; 4]);
+        assert_eq!(pixel(&mut atlas, 0, 17, 5), [10; 4]);
+        assert_eq!(pixel(&mut atlas, 0, 18, 5), [20; 4]);
+
+        // Freed cells are reused
+        atlas.remove(1);
+        assert_eq!(atlas.slot(1), None);
+        let c = atlas.insert(4, &solid(8, 14, 30), 8, 14).unwrap();
+        assert_eq!((c.x, c.y), (1, 1));
+        assert_eq!(atlas.pages(), 1);
+    }
+
+    #[test]
+    fn test_pages_grow_then_overflow() {
+        let mut atlas = Atlas::default();
+        let icon = solid(126, 126, 255);
+        // 128px cells: 4 x 4 fit a 512px page, 8 x 8 a full one
+        for id in 0..64 {
+            assert_eq!(atlas.insert(id, &icon, 126, 126).unwrap().page, 0);
+        }
+        assert_eq!(atlas.page(0).unwrap().width, PAGE_SIZE);
+        let first = atlas.insert(64, &icon, 126, 126).unwrap();
+        assert_eq!(first.page, 1);
+        assert_eq!(atlas.pages(), 2);
+
+        // A page left empty is reset to its first size
+        atlas.remove(64);
+        assert_eq!(atlas.pages(), 1);
+        assert_eq!(atlas.bytes(), (PAGE_SIZE * PAGE_SIZE * 4) as u64);
+    }
+}
@@ -0,0 +1,106 @@
+//! Render recorded command buffers and diff the results
+//!
+//! ```text
+//! mcore-replay render <recording> <out-dir>
+//! mcore-replay diff <dir-a> <dir-b> [--tolerance N] [--out <dir>]
+//! ```
+//!
+//! `render` writes one frame-NNNN.pam per recorded frame. `diff` compares
+//! same-named frames in two directories, optionally writing highlighted diff
+//! images, and exits non-zero if any frame differs. `cargo xtask replay-diff`
+//! runs both against two engine builds.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use masonry_core_capi::replay;
+
+const USAGE: &str = "usage: mcore-replay render <recording> <out-dir>\n       mcore-replay diff <dir-a> <dir-b> [--tolerance N] [--out <dir>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("render") if args.len() == 3 => render(Path::new(&args[1]), Path::new(&args[2])),
+        Some("diff") if args.len() >= 3 => diff(&args[1..]),
+        _ => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("mcore-replay: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn frame_name(index: usize) -> String {
+    format!("frame-{:04}.pam", index)
+}
+
+fn render(recording: &Path, out_dir: &Path) -> Result<bool, String> {
+    let recording = replay::Recording::load(recording)?;
+    let frames = replay::render(&recording)?;
+
+    fs::create_dir_all(out_dir).map_err(|e| e.to_string())?;
+    for (index, frame) in frames.iter().enumerate() {
+        replay::write_pam(&out_dir.join(frame_name(index)), frame).map_err(|e| e.to_string())?;
+    }
+    eprintln!("Rendered {} frames to {}", frames.len(), out_dir.display());
+    Ok(true)
+}
+
+fn diff(args: &[String]) -> Result<bool, String> {
+    let (dir_a, dir_b) = (PathBuf::from(&args[0]), PathBuf::from(&args[1]));
+    let mut tolerance = 0u8;
+    let mut out_dir = None;
+
+    let mut rest = args[2..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--tolerance" => {
+                let value = rest.next().ok_or("--tolerance needs a value")?;
+                tolerance = value.parse().map_err(|_| format!("bad tolerance {}", value))?;
+            }
+            "--out" => out_dir = Some(PathBuf::from(rest.next().ok_or("--out needs a directory")?)),
+            other => return Err(format!("unknown option {}\n{}", other, USAGE)),
+        }
+    }
+    if let Some(out_dir) = &out_dir {
+        fs::create_dir_all(out_dir).map_err(|e| e.to_string())?;
+    }
+
+    let mut identical = true;
+    for index in 0.. {
+        let name = frame_name(index);
+        let (path_a, path_b) = (dir_a.join(&name), dir_b.join(&name));
+        match (path_a.exists(), path_b.exists()) {
+            (false, false) => break,
+            (true, true) => {}
+            _ => {
+                println!("{}: missing on one side", name);
+                identical = false;
+                continue;
+            }
+        }
+
+        let result = replay::diff(&replay::read_pam(&path_a)?, &replay::read_pam(&path_b)?, tolerance);
+        if result.mismatched == 0 {
+            continue;
+        }
+
+        identical = false;
+        println!("{}: {} pixels differ (max channel delta {})", name, result.mismatched, result.max_delta);
+        if let Some(out_dir) = &out_dir {
+            replay::write_pam(&out_dir.join(&name), &result.image).map_err(|e| e.to_string())?;
+        }
+    }
+
+    if identical {
+        println!("All frames match");
+    }
+    Ok(identical)
+}
@@ -3,6 +3,19 @@ var src_texture: texture_2d<f32>;
 @group(0) @binding(1)
 var src_sampler: sampler;
 
+struct BlitParams {
+    // Non-zero enables ordered noise dithering to hide gradient banding
+    dither: u32,
+    // Non-zero when the surface format encodes sRGB on write: Vello's output
+    // is already encoded, so decode it first
+    decode_srgb: u32,
+    _pad0: u32,
+    _pad1: u32,
+}
+
+@group(0) @binding(2)
+var<uniform> params: BlitParams;
+
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) uv: vec2<f32>,
@@ -18,7 +31,30 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     return out;
 }
 
+// Cheap per-pixel hash in [0, 1)
+fn hash12(p: vec2<f32>) -> f32 {
+    var p3 = fract(vec3<f32>(p.xyx) * 0.1031);
+    p3 = p3 + dot(p3, p3.yzx + 33.33);
+    return fract((p3.x + p3.y) * p3.z);
+}
+
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+    let low = c / 12.92;
+    let high = pow((c + vec3<f32>(0.055)) / 1.055, vec3<f32>(2.4));
+    return select(high, low, c <= vec3<f32>(0.04045));
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(src_texture, src_sampler, in.uv);
+    var color = textureSample(src_texture, src_sampler, in.uv);
+    if (params.dither != 0u) {
+        // Triangular-PDF noise of +/- one 8-bit step breaks up banding
+        // without visibly brightening or darkening flat areas
+        let n = hash12(in.position.xy) + hash12(in.position.xy + vec2<f32>(17.0, 59.0)) - 1.0;
+        color = vec4<f32>(color.rgb + vec3<f32>(n / 255.0), color.a);
+    }
+    if (params.decode_srgb != 0u) {
+        color = vec4<f32>(srgb_to_linear(clamp(color.rgb, vec3<f32>(0.0), vec3<f32>(1.0))), color.a);
+    }
+    return color;
 }
@@ -0,0 +1,348 @@
+/// Backdrop blur layers (mcore_push_blur_layer) and color filter layers
+/// (mcore_push_color_filter)
+///
+/// A blur layer cuts the frame's scene in two. The part drawn before it is
+/// rendered, the layer's rounded rect of that is blurred in place with a
+/// separable Gaussian (a horizontal pass into a scratch texture, a vertical
+/// one back into the frame), and the part after it is rendered over
+/// transparency and blended on top. Pushing or popping a color filter cuts
+/// the scene the same way without the blur; the part after the cut is
+/// blended through the filter's color matrix, so images, fragments, videos
+/// and custom textures are filtered along with everything else. Every cut
+/// costs another Vello render of the frame, so these layers should be few.
+
+use vello::Scene;
+
+use crate::filter::ColorMatrix;
+
+/// A layer's rect and blur in logical px; `radius` is the Gaussian's
+/// standard deviation, as in CSS blur()
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BlurRegion {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub corner_radius: f32,
+    pub radius: f32,
+}
+
+/// The frame's scene as drawn before a cut, and what happens at the cut
+pub struct Backdrop {
+    pub scene: Scene,
+    /// The blur layer's region, None for a color filter cut
+    pub blur: Option<BlurRegion>,
+    /// The color filter the scene after the cut is blended through
+    pub filter: Option<ColorMatrix>,
+}
+
+/// Samples per side are capped; wider blurs spread them further apart
+const MAX_TAPS: u32 = 32;
+
+/// Uniforms for one pass (must match `PassParams` in blur.wgsl)
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct PassParams {
+    rect: [f32; 4],
+    step: [f32; 2],
+    sigma: f32,
+    corner_radius: f32,
+    taps: u32,
+    masked: u32,
+    _pad: [u32; 2],
+    /// Composite color matrix (ColorMatrix::rows)
+    color_matrix: [[f32; 4]; 5],
+}
+
+/// Textures the size of the frame
+struct Targets {
+    size: (u32, u32),
+    /// Horizontal pass output
+    scratch: wgpu::TextureView,
+    /// The scene above a layer, before it's blended over the frame
+    overlay: wgpu::TextureView,
+}
+
+/// Pipelines and textures, created on the first cut
+pub struct BackdropBlur {
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    blur_pipeline: wgpu::RenderPipeline,
+    /// Vertical pass, clipped to the rounded rect
+    masked_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+    /// Horizontal pass, vertical pass and composite uniforms
+    params: [wgpu::Buffer; 3],
+    targets: Option<Targets>,
+}
+
+impl BackdropBlur {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Blur Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("blur.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Blur Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Blur Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label, entry_point, blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(entry_point),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        blend: Some(blend),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+        // The masked pass's alpha is the rect's coverage; the frame keeps
+        // the backdrop's opacity
+        let masked_blend = wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+        };
+        let blur_pipeline = create_pipeline("Blur Pipeline", "fs_blur", wgpu::BlendState::REPLACE);
+        let masked_pipeline = create_pipeline("Blur Masked Pipeline", "fs_blur", masked_blend);
+        // Vello writes straight (non-premultiplied) alpha
+        let composite_pipeline = create_pipeline("Blur Composite Pipeline", "fs_composite", wgpu::BlendState::ALPHA_BLENDING);
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Blur Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let params = ["Blur Params (horizontal)", "Blur Params (vertical)", "Composite Params"].map(|label| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: std::mem::size_of::<PassParams>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+
+        Self {
+            bind_group_layout,
+            sampler,
+            blur_pipeline,
+            masked_pipeline,
+            composite_pipeline,
+            params,
+            targets: None,
+        }
+    }
+
+    /// Make the scratch and overlay textures match the frame size
+    pub fn prepare(&mut self, device: &wgpu::Device, size: (u32, u32)) {
+        if self.targets.as_ref().is_some_and(|targets| targets.size == size) {
+            return;
+        }
+        let create = |label, usage| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: wgpu::Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING | usage,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        self.targets = Some(Targets {
+            size,
+            scratch: create("Blur Scratch", wgpu::TextureUsages::RENDER_ATTACHMENT),
+            // Vello renders into it with compute shaders
+            overlay: create("Blur Overlay", wgpu::TextureUsages::STORAGE_BINDING),
+        });
+    }
+
+    /// The texture to render the scene above a layer into (after prepare)
+    pub fn overlay(&self) -> &wgpu::TextureView {
+        &self.targets.as_ref().expect("prepare is called first").overlay
+    }
+
+    fn bind_group(&self, device: &wgpu::Device, source: &wgpu::TextureView, params: &wgpu::Buffer) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Blur Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(source) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                wgpu::BindGroupEntry { binding: 2, resource: params.as_entire_binding() },
+            ],
+        })
+    }
+
+    /// Blur `region` of `frame` in place (after prepare)
+    pub fn blur(&self, device: &wgpu::Device, queue: &wgpu::Queue, frame: &wgpu::TextureView, region: &BlurRegion, scale: f32) {
+        let targets = self.targets.as_ref().expect("prepare is called first");
+        let (w, h) = targets.size;
+        let rect = [region.x * scale, region.y * scale, (region.x + region.width) * scale, (region.y + region.height) * scale];
+        let sigma = region.radius * scale;
+        let taps = ((3.0 * sigma).ceil() as u32).clamp(1, MAX_TAPS);
+
+        // The horizontal pass covers the rows the vertical one samples
+        let reach = 3.0 * sigma + 2.0;
+        let Some(rows) = scissor(rect[0], rect[1] - reach, rect[2], rect[3] + reach, (w, h)) else {
+            return;
+        };
+        let Some(region_px) = scissor(rect[0], rect[1], rect[2], rect[3], (w, h)) else {
+            return;
+        };
+
+        let pass_params = |step: [f32; 2], masked: bool| PassParams {
+            rect,
+            step,
+            sigma,
+            corner_radius: region.corner_radius * scale,
+            taps,
+            masked: masked as u32,
+            _pad: [0; 2],
+            color_matrix: ColorMatrix::IDENTITY.rows(),
+        };
+        queue.write_buffer(&self.params[0], 0, bytemuck::bytes_of(&pass_params([1.0 / w as f32, 0.0], false)));
+        queue.write_buffer(&self.params[1], 0, bytemuck::bytes_of(&pass_params([0.0, 1.0 / h as f32], true)));
+
+        let passes = [
+            (frame, &targets.scratch, &self.params[0], &self.blur_pipeline, rows),
+            (&targets.scratch, frame, &self.params[1], &self.masked_pipeline, region_px),
+        ];
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Blur Encoder") });
+        for (source, target, params, pipeline, [x, y, width, height]) in passes {
+            let bind_group = self.bind_group(device, source, params);
+            let mut rpass = begin_pass(&mut encoder, target, "Blur Pass");
+            rpass.set_pipeline(pipeline);
+            rpass.set_bind_group(0, &bind_group, &[]);
+            rpass.set_scissor_rect(x, y, width, height);
+            rpass.draw(0..3, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+
+    /// Blend the overlay over `frame`, through `filter` if there is one
+    pub fn composite(&self, device: &wgpu::Device, queue: &wgpu::Queue, frame: &wgpu::TextureView, filter: Option<&ColorMatrix>) {
+        let params = PassParams {
+            color_matrix: filter.unwrap_or(&ColorMatrix::IDENTITY).rows(),
+            ..bytemuck::Zeroable::zeroed()
+        };
+        queue.write_buffer(&self.params[2], 0, bytemuck::bytes_of(&params));
+        let bind_group = self.bind_group(device, self.overlay(), &self.params[2]);
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Blur Composite Encoder") });
+        {
+            let mut rpass = begin_pass(&mut encoder, frame, "Blur Composite Pass");
+            rpass.set_pipeline(&self.composite_pipeline);
+            rpass.set_bind_group(0, &bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+}
+
+/// A pass drawing over what's already in `target`
+fn begin_pass<'a>(encoder: &'a mut wgpu::CommandEncoder, target: &wgpu::TextureView, label: &str) -> wgpu::RenderPass<'a> {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+            depth_slice: None,
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    })
+}
+
+/// The pixels covering x0, y0 - x1, y1 within the target, as a scissor rect
+/// (x, y, width, height); None if that's empty
+fn scissor(x0: f32, y0: f32, x1: f32, y1: f32, (w, h): (u32, u32)) -> Option<[u32; 4]> {
+    let clamp = |v: f32, max: u32| v.clamp(0.0, max as f32) as u32;
+    let (left, top) = (clamp(x0.floor(), w), clamp(y0.floor(), h));
+    let (right, bottom) = (clamp(x1.ceil(), w), clamp(y1.ceil(), h));
+    (right > left && bottom > top).then(|| [left, top, right - left, bottom - top])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scissor_clamps_to_the_target() {
+        assert_eq!(scissor(10.2, 20.7, 30.5, 40.0, (100, 100)), Some([10, 20, 21, 20]));
+        assert_eq!(scissor(-5.0, -5.0, 200.0, 50.0, (100, 100)), Some([0, 0, 100, 50]));
+        assert_eq!(scissor(120.0, 0.0, 150.0, 10.0, (100, 100)), None);
+        assert_eq!(scissor(10.0, 10.0, 10.0, 20.0, (100, 100)), None);
+    }
+}
@@ -0,0 +1,92 @@
+// Backdrop blur and layer composite passes (see blur.rs)
+
+@group(0) @binding(0)
+var src_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var src_sampler: sampler;
+
+struct PassParams {
+    // Blurred region in physical px: x0, y0, x1, y1
+    rect: vec4<f32>,
+    // One texel along the blur axis, in uv
+    step: vec2<f32>,
+    // Gaussian standard deviation in physical px
+    sigma: f32,
+    corner_radius: f32,
+    // Samples on each side of the center, spread over 3 sigma
+    taps: u32,
+    // Non-zero clips the output to the rounded rect, with antialiased edges
+    masked: u32,
+    _pad0: u32,
+    _pad1: u32,
+    // Composite color matrix: a row of channel weights per output channel,
+    // then the offsets
+    color_matrix: array<vec4<f32>, 5>,
+}
+
+@group(0) @binding(2)
+var<uniform> params: PassParams;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let x = f32(vertex_index & 1u);
+    let y = f32((vertex_index >> 1u) & 1u);
+    out.position = vec4<f32>(x * 4.0 - 1.0, 1.0 - y * 4.0, 0.0, 1.0);
+    return out;
+}
+
+fn gaussian(x: f32) -> f32 {
+    return exp(-0.5 * x * x / (params.sigma * params.sigma));
+}
+
+// 1 inside the rounded rect, 0 outside, fading over a pixel at the edge
+fn coverage(p: vec2<f32>) -> f32 {
+    let half_size = (params.rect.zw - params.rect.xy) * 0.5;
+    let center = params.rect.xy + half_size;
+    let radius = min(params.corner_radius, min(half_size.x, half_size.y));
+    let q = abs(p - center) - half_size + vec2<f32>(radius);
+    let distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+    return clamp(0.5 - distance, 0.0, 1.0);
+}
+
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    let uv = in.position.xy / vec2<f32>(textureDimensions(src_texture));
+    let spacing = 3.0 * params.sigma / f32(max(params.taps, 1u));
+
+    var sum = textureSampleLevel(src_texture, src_sampler, uv, 0.0);
+    var total = 1.0;
+    for (var i = 1u; i <= params.taps; i = i + 1u) {
+        let offset = f32(i) * spacing;
+        let weight = gaussian(offset);
+        sum = sum + weight * textureSampleLevel(src_texture, src_sampler, uv + params.step * offset, 0.0);
+        sum = sum + weight * textureSampleLevel(src_texture, src_sampler, uv - params.step * offset, 0.0);
+        total = total + 2.0 * weight;
+    }
+    let color = sum / total;
+
+    if (params.masked != 0u) {
+        return vec4<f32>(color.rgb, coverage(in.position.xy));
+    }
+    return color;
+}
+
+// The scene after a cut, filtered and blended over the frame. Vello writes
+// straight alpha, which is what the matrix expects.
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    let uv = in.position.xy / vec2<f32>(textureDimensions(src_texture));
+    let color = textureSampleLevel(src_texture, src_sampler, uv, 0.0);
+    // Only drawn content is filtered, not the transparency around it
+    if (color.a <= 0.0) {
+        return color;
+    }
+    let m = params.color_matrix;
+    let filtered = vec4<f32>(dot(m[0], color), dot(m[1], color), dot(m[2], color), dot(m[3], color)) + m[4];
+    return clamp(filtered, vec4<f32>(0.0), vec4<f32>(1.0));
+}
@@ -0,0 +1,227 @@
+/// Small least-recently-used cache
+///
+/// Used for per-context caches (e.g. text layouts) that are hit many times per
+/// frame with a handful of distinct keys. Eviction scans for the oldest entry,
+/// which is cheap at the capacities we use. Besides an entry count, the cache
+/// can be bounded by the (caller-estimated) bytes its values hold.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+struct Entry<V> {
+    value: V,
+    last_used: u64,
+    bytes: usize,
+}
+
+/// Counters for tuning a cache's limits
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub max_entries: usize,
+    pub bytes: usize,
+    /// usize::MAX when unbounded
+    pub max_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+pub struct LruCache<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    capacity: usize,
+    /// Upper bound on the summed entry sizes (usize::MAX for none)
+    max_bytes: usize,
+    bytes: usize,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+            max_bytes: usize::MAX,
+            bytes: 0,
+            tick: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    /// Change the entry and byte limits, evicting down to them right away
+    pub fn set_limits(&mut self, capacity: usize, max_bytes: usize) {
+        self.capacity = capacity;
+        self.max_bytes = max_bytes;
+        self.evict_to_limits();
+    }
+
+    /// Look up a value, marking it as recently used and counting the hit/miss
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.tick += 1;
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = self.tick;
+                self.hits += 1;
+                Some(&entry.value)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Insert a value, evicting the least recently used entry if over capacity
+    #[allow(dead_code)]
+    pub fn insert(&mut self, key: K, value: V) {
+        self.insert_sized(key, value, 0);
+    }
+
+    /// Insert a value holding about `bytes` of memory, evicting least recently
+    /// used entries until both limits hold. A value larger than the whole byte
+    /// budget is still kept until the next insert, so lookups can return it.
+    pub fn insert_sized(&mut self, key: K, value: V, bytes: usize) {
+        self.tick += 1;
+        let entry = Entry { value, last_used: self.tick, bytes };
+        if let Some(old) = self.entries.insert(key, entry) {
+            self.bytes -= old.bytes;
+        }
+        self.bytes += bytes;
+        self.evict_to_limits();
+    }
+
+    fn evict_to_limits(&mut self) {
+        while self.entries.len() > self.capacity || (self.bytes > self.max_bytes && self.entries.len() > 1) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            match oldest.and_then(|k| self.entries.remove(&k)) {
+                Some(entry) => {
+                    self.bytes -= entry.bytes;
+                    self.evictions += 1;
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Look up a value without touching recency or hit/miss counters
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|e| &e.value)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    #[allow(dead_code)]
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            max_entries: self.capacity,
+            bytes: self.bytes,
+            max_bytes: self.max_bytes,
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+        }
+    }
+
+    /// Zero the hit/miss/eviction counters (entries are kept)
+    pub fn reset_counters(&mut self) {
+        self.hits = 0;
+        self.misses = 0;
+        self.evictions = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_insert() {
+        let mut cache = LruCache::new(4);
+        assert!(cache.get(&1).is_none());
+        cache.insert(1, "one");
+        assert_eq!(cache.get(&1), Some(&"one"));
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, 10);
+        cache.insert(2, 20);
+
+        // Touch 1 so 2 becomes the oldest
+        assert!(cache.get(&1).is_some());
+        cache.insert(3, 30);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&2).is_none());
+        assert!(cache.get(&1).is_some());
+        assert!(cache.get(&3).is_some());
+    }
+
+    #[test]
+    fn test_byte_budget() {
+        let mut cache = LruCache::new(8);
+        cache.set_limits(8, 100);
+        cache.insert_sized(1, (), 40);
+        cache.insert_sized(2, (), 40);
+        cache.insert_sized(3, (), 40);
+
+        // Oldest entry goes to get back under 100 bytes
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes, stats.evictions), (2, 80, 1));
+        assert!(cache.peek(&1).is_none());
+
+        // Replacing an entry doesn't double count it
+        cache.insert_sized(3, (), 10);
+        assert_eq!(cache.stats().bytes, 50);
+
+        // Tightening the limits evicts immediately
+        cache.set_limits(1, 100);
+        assert_eq!(cache.stats().entries, 1);
+        assert!(cache.peek(&3).is_some());
+
+        cache.reset_counters();
+        assert_eq!(cache.stats().evictions, 0);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, 10);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
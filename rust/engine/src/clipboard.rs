@@ -0,0 +1,132 @@
+/// System clipboard (plain text)
+///
+/// NSPasteboard's general pasteboard on macOS. Other platforms get an
+/// in-process clipboard, so copy/paste still works within the app (and in
+/// headless tests).
+
+/// Replace the clipboard contents with `text`
+pub fn set_text(text: &str) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        macos::set_text(text)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        *fallback::CLIPBOARD.lock() = Some(text.to_string());
+        true
+    }
+}
+
+/// The clipboard's text, if it holds any
+pub fn get_text() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::get_text()
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        fallback::CLIPBOARD.lock().clone()
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod fallback {
+    pub static CLIPBOARD: parking_lot::Mutex<Option<String>> = parking_lot::Mutex::new(None);
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::{c_char, c_void, CStr};
+
+    /// NSUTF8StringEncoding
+    const UTF8_ENCODING: usize = 4;
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+        fn objc_autoreleasePoolPush() -> *mut c_void;
+        fn objc_autoreleasePoolPop(pool: *mut c_void);
+    }
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        static NSPasteboardTypeString: *mut c_void;
+    }
+
+    unsafe fn send_id(receiver: *mut c_void, selector: &CStr) -> *mut c_void {
+        let sel = sel_registerName(selector.as_ptr());
+        let f: unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        f(receiver, sel)
+    }
+
+    unsafe fn send_id_arg(receiver: *mut c_void, selector: &CStr, arg: *mut c_void) -> *mut c_void {
+        let sel = sel_registerName(selector.as_ptr());
+        let f: unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void) -> *mut c_void =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        f(receiver, sel, arg)
+    }
+
+    /// Runs `f` inside an autorelease pool, since the engine may be called
+    /// from threads without one
+    fn with_pool<T>(f: impl FnOnce() -> T) -> T {
+        unsafe {
+            let pool = objc_autoreleasePoolPush();
+            let result = f();
+            objc_autoreleasePoolPop(pool);
+            result
+        }
+    }
+
+    unsafe fn general_pasteboard() -> *mut c_void {
+        let class = objc_getClass(c"NSPasteboard".as_ptr());
+        if class.is_null() {
+            return std::ptr::null_mut();
+        }
+        send_id(class, c"generalPasteboard")
+    }
+
+    pub fn set_text(text: &str) -> bool {
+        with_pool(|| unsafe {
+            let pasteboard = general_pasteboard();
+            let string_class = objc_getClass(c"NSString".as_ptr());
+            if pasteboard.is_null() || string_class.is_null() {
+                return false;
+            }
+
+            // [[NSString alloc] initWithBytes:length:encoding:]
+            let sel = sel_registerName(c"initWithBytes:length:encoding:".as_ptr());
+            let init: unsafe extern "C" fn(*mut c_void, *mut c_void, *const u8, usize, usize) -> *mut c_void =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            let string = init(send_id(string_class, c"alloc"), sel, text.as_ptr(), text.len(), UTF8_ENCODING);
+            if string.is_null() {
+                return false;
+            }
+
+            send_id(pasteboard, c"clearContents");
+            let sel = sel_registerName(c"setString:forType:".as_ptr());
+            let set: unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void, *mut c_void) -> u8 =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            let ok = set(pasteboard, sel, string, NSPasteboardTypeString) != 0;
+            send_id(string, c"release");
+            ok
+        })
+    }
+
+    pub fn get_text() -> Option<String> {
+        with_pool(|| unsafe {
+            let pasteboard = general_pasteboard();
+            if pasteboard.is_null() {
+                return None;
+            }
+            let string = send_id_arg(pasteboard, c"stringForType:", NSPasteboardTypeString);
+            if string.is_null() {
+                return None;
+            }
+            let utf8 = send_id(string, c"UTF8String") as *const c_char;
+            (!utf8.is_null()).then(|| CStr::from_ptr(utf8).to_string_lossy().into_owned())
+        })
+    }
+}
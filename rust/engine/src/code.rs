@@ -0,0 +1,217 @@
+/// Code views: monospace text with syntax colors and optional line numbers,
+/// drawn straight from an editor's buffer
+///
+/// Shaping a whole file to show a screenful of it doesn't scale to large
+/// buffers. Code doesn't wrap and every line has the same height, so the
+/// lines in the viewport follow from the scroll offset alone: a byte scan for
+/// line breaks finds them, and only they are decoded and shaped, each with
+/// its slice of the token colors. Ligatures are off, so every character
+/// keeps its own column.
+
+use std::ops::Range;
+
+use peniko::Color;
+use vello::Scene;
+
+use crate::text::{self, TabStops, TextAlign, TextContext, TextSpan, TextStyle};
+
+/// Space between the line numbers and the code, in ems
+const GUTTER_GAP: f32 = 1.0;
+/// Line numbers get at least this many digits, so short files don't shift
+/// the code when they reach line 10
+const MIN_DIGITS: usize = 2;
+
+/// A syntax color for a byte range of the buffer
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub range: Range<usize>,
+    pub color: Color,
+}
+
+#[derive(Clone, Debug)]
+pub struct CodeStyle {
+    pub text: TextStyle,
+    /// Text outside every token
+    pub color: Color,
+    /// Line number color, None for no line numbers
+    pub line_numbers: Option<Color>,
+}
+
+impl CodeStyle {
+    /// Without line numbers, tabs every 8 columns
+    pub fn new(font_size: f32, font_id: i32, color: Color) -> Self {
+        let text = TextStyle { ligatures: false, ..TextStyle::new(font_size, font_id) };
+        Self { text, color, line_numbers: None }
+    }
+
+    /// Tab stops every `columns` columns (0 keeps the default)
+    pub fn set_tab_size(&mut self, text_cx: &mut TextContext, columns: u32, scale: f32) {
+        if columns > 0 {
+            let width = text::space_width(text_cx, &self.text, scale) * columns as f32;
+            self.text.tabs = TabStops::new(width, &[]);
+        }
+    }
+}
+
+/// Logical px
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Metrics {
+    pub line_count: usize,
+    pub line_height: f32,
+    /// Where the code starts; 0 without line numbers
+    pub gutter_width: f32,
+}
+
+impl Metrics {
+    pub fn content_height(&self) -> f32 {
+        self.line_count as f32 * self.line_height
+    }
+}
+
+/// Lines in `text`: one more than its line breaks
+pub fn line_count(text: &[u8]) -> usize {
+    text.iter().filter(|&&b| b == b'\n').count() + 1
+}
+
+/// Lines overlapping a viewport `height` tall scrolled down `scroll_y`
+fn visible_lines(line_height: f32, scroll_y: f32, height: f32, line_count: usize) -> Range<usize> {
+    if !(line_height > 0.0) || !(height > 0.0) {
+        return 0..0;
+    }
+    let first = (scroll_y / line_height).floor().max(0.0) as usize;
+    let end = ((scroll_y + height) / line_height).ceil().max(0.0) as usize;
+    first.min(line_count)..end.min(line_count)
+}
+
+/// Byte ranges of lines `lines` (0-based), without their line breaks,
+/// scanning no further than the last one
+fn line_ranges(text: &[u8], lines: Range<usize>) -> Vec<Range<usize>> {
+    let mut ranges = Vec::with_capacity(lines.len());
+    let mut start = 0;
+    for line in 0..lines.end {
+        let end = text[start..].iter().position(|&b| b == b'\n').map_or(text.len(), |i| start + i);
+        if line >= lines.start {
+            let content_end = if end > start && text[end - 1] == b'\r' { end - 1 } else { end };
+            ranges.push(start..content_end);
+        }
+        if end == text.len() {
+            break;
+        }
+        start = end + 1;
+    }
+    ranges
+}
+
+/// Spans for the tokens overlapping `line`, relative to its start. Tokens
+/// are sorted and don't overlap, so the first is found by binary search.
+fn line_spans(tokens: &[Token], line: Range<usize>) -> Vec<TextSpan> {
+    let first = tokens.partition_point(|token| token.range.end <= line.start);
+    tokens[first..]
+        .iter()
+        .take_while(|token| token.range.start < line.end)
+        .filter(|token| token.range.end > line.start)
+        .map(|token| TextSpan {
+            range: token.range.start.max(line.start) - line.start..token.range.end.min(line.end) - line.start,
+            color: token.color,
+            weight: None,
+            italic: false,
+            underline: false,
+            font_id: None,
+        })
+        .collect()
+}
+
+fn digits(mut n: usize) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+pub fn metrics(text_cx: &mut TextContext, text: &[u8], style: &CodeStyle, scale: f32) -> Metrics {
+    let line_count = line_count(text);
+    let (_, line_height) = text::measure_text(text_cx, "0", &style.text, text::NO_WRAP_WIDTH, scale);
+    let gutter_width = match style.line_numbers {
+        Some(_) => {
+            let widest = "0".repeat(digits(line_count).max(MIN_DIGITS));
+            let (width, _) = text::measure_text(text_cx, &widest, &style.text, text::NO_WRAP_WIDTH, scale);
+            width + GUTTER_GAP * style.text.font_size
+        }
+        None => 0.0,
+    };
+    Metrics { line_count, line_height, gutter_width }
+}
+
+/// Draw the lines of `text` visible in a viewport `height` tall scrolled down
+/// `scroll_y`, with the viewport's top left at logical (x, y). `tokens` must
+/// be sorted by start and not overlap.
+pub fn draw(
+    scene: &mut Scene,
+    text_cx: &mut TextContext,
+    text: &[u8],
+    tokens: &[Token],
+    style: &CodeStyle,
+    x: f32,
+    y: f32,
+    scroll_y: f32,
+    height: f32,
+    scale: f32,
+) {
+    let metrics = metrics(text_cx, text, style, scale);
+    let lines = visible_lines(metrics.line_height, scroll_y, height, metrics.line_count);
+    let number_style = TextStyle { align: TextAlign::End, ..style.text };
+    let number_width = metrics.gutter_width - GUTTER_GAP * style.text.font_size;
+
+    for (line, range) in lines.clone().zip(line_ranges(text, lines)) {
+        let top = y + line as f32 * metrics.line_height - scroll_y;
+        if let Some(color) = style.line_numbers {
+            let number = (line + 1).to_string();
+            text::draw_text(scene, text_cx, &number, x * scale, top * scale, &number_style, number_width, color, scale);
+        }
+
+        // Invalid UTF-8 is replaced, so tokens may land a little off on that line
+        let source = String::from_utf8_lossy(&text[range.clone()]);
+        let spans = line_spans(tokens, range);
+        let shaped = text::build_styled_layout(text_cx, &source, &spans, &style.text, text::NO_WRAP_WIDTH, style.color, scale);
+        let rendering = text_cx.rendering;
+        text::draw_styled_layout(scene, &shaped, (x + metrics.gutter_width) * scale, top * scale, rendering);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_lines_follow_scroll() {
+        assert_eq!(visible_lines(20.0, 0.0, 100.0, 1000), 0..5);
+        assert_eq!(visible_lines(20.0, 30.0, 100.0, 1000), 1..7);
+        assert_eq!(visible_lines(20.0, -50.0, 100.0, 1000), 0..3);
+        assert_eq!(visible_lines(20.0, 19_950.0, 100.0, 1000), 997..1000);
+        assert_eq!(visible_lines(0.0, 0.0, 100.0, 1000), 0..0);
+    }
+
+    #[test]
+    fn test_line_ranges_stop_at_the_window() {
+        let text = b"one\r\ntwo\n\nfour\nfive";
+        assert_eq!(line_count(text), 5);
+        assert_eq!(line_ranges(text, 0..2), [0..3, 5..8]);
+        assert_eq!(line_ranges(text, 2..4), [9..9, 10..14]);
+        assert_eq!(line_ranges(text, 4..9), [15..19]);
+        assert_eq!(line_ranges(b"trailing\n", 1..2), [9..9]);
+    }
+
+    #[test]
+    fn test_tokens_clipped_to_lines() {
+        let red = Color::new([1.0, 0.0, 0.0, 1.0]);
+        let tokens: Vec<Token> = [0..3, 4..12, 14..16].into_iter().map(|range| Token { range, color: red }).collect();
+        let ranges = |line| line_spans(&tokens, line).into_iter().map(|span| span.range).collect::<Vec<_>>();
+        assert_eq!(ranges(0..6), [0..3, 4..6]);
+        assert_eq!(ranges(7..13), [0..5]);
+        assert!(ranges(20..25).is_empty());
+        assert_eq!(digits(9), 1);
+        assert_eq!(digits(100), 3);
+    }
+}
@@ -0,0 +1,148 @@
+/// Output color space (mcore_set_color_space)
+///
+/// Vello renders the colors it's given as 8-bit sRGB-encoded values. How the
+/// compositor reads them depends on the window's CAMetalLayer: untagged, the
+/// values go straight to the display, so on a P3 panel every color comes out
+/// oversaturated. The engine tags the layer with the context's color space;
+/// in Display P3 the host's colors and images are read as P3 values, which
+/// reach past sRGB on wide-gamut displays.
+///
+/// The surface format is separate: if the only formats offered are sRGB ones
+/// the GPU encodes on write, and the blit decodes first so it isn't done twice.
+
+use std::ffi::c_void;
+use std::ptr::NonNull;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorSpace {
+    Srgb = 0,
+    DisplayP3 = 1,
+}
+
+/// The window's CAMetalLayer
+pub struct MetalLayer(NonNull<c_void>);
+
+// SAFETY: only its colorspace property is set, which CALayer allows from any
+// thread
+unsafe impl Send for MetalLayer {}
+
+impl MetalLayer {
+    pub fn new(layer: *mut c_void) -> Option<Self> {
+        NonNull::new(layer).map(Self)
+    }
+
+    /// Have the compositor read the layer's pixels in `space`; false if the
+    /// platform has no such setting
+    pub fn tag(&self, space: ColorSpace) -> bool {
+        #[cfg(target_os = "macos")]
+        {
+            macos::tag(self.0.as_ptr(), space)
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = space;
+            false
+        }
+    }
+}
+
+/// Whether the main display shows (most of) Display P3
+pub fn display_supports_p3() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        macos::display_supports_p3()
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        false
+    }
+}
+
+/// The surface format to present with, and whether the blit must decode
+/// sRGB first because the format encodes on write; 8-bit formats without
+/// encoding are preferred
+pub fn pick_surface_format(formats: &[wgpu::TextureFormat]) -> Option<(wgpu::TextureFormat, bool)> {
+    let preferred = [wgpu::TextureFormat::Bgra8Unorm, wgpu::TextureFormat::Rgba8Unorm];
+    let format = preferred
+        .into_iter()
+        .find(|format| formats.contains(format))
+        .or_else(|| formats.first().copied())?;
+    Some((format, format.is_srgb()))
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::ColorSpace;
+    use std::ffi::{c_char, c_void};
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        static kCGColorSpaceSRGB: *const c_void;
+        static kCGColorSpaceDisplayP3: *const c_void;
+        fn CGColorSpaceCreateWithName(name: *const c_void) -> *mut c_void;
+        fn CGColorSpaceRelease(space: *mut c_void);
+    }
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {}
+
+    /// NSDisplayGamutP3
+    const DISPLAY_GAMUT_P3: i64 = 2;
+
+    pub fn tag(layer: *mut c_void, space: ColorSpace) -> bool {
+        unsafe {
+            let name = match space {
+                ColorSpace::Srgb => kCGColorSpaceSRGB,
+                ColorSpace::DisplayP3 => kCGColorSpaceDisplayP3,
+            };
+            let color_space = CGColorSpaceCreateWithName(name);
+            if color_space.is_null() {
+                return false;
+            }
+            let set: unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void) =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            set(layer, sel_registerName(c"setColorspace:".as_ptr()), color_space);
+            CGColorSpaceRelease(color_space);
+            true
+        }
+    }
+
+    pub fn display_supports_p3() -> bool {
+        unsafe {
+            let class = objc_getClass(c"NSScreen".as_ptr());
+            if class.is_null() {
+                return false;
+            }
+            let send_id: unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            let screen = send_id(class, sel_registerName(c"mainScreen".as_ptr()));
+            if screen.is_null() {
+                return false;
+            }
+            let can_represent: unsafe extern "C" fn(*mut c_void, *mut c_void, i64) -> u8 =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            can_represent(screen, sel_registerName(c"canRepresentDisplayGamut:".as_ptr()), DISPLAY_GAMUT_P3) != 0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wgpu::TextureFormat;
+
+    #[test]
+    fn test_surface_format_prefers_unencoded_8_bit() {
+        let formats = [TextureFormat::Bgra8UnormSrgb, TextureFormat::Rgba16Float, TextureFormat::Bgra8Unorm];
+        assert_eq!(pick_surface_format(&formats), Some((TextureFormat::Bgra8Unorm, false)));
+        assert_eq!(pick_surface_format(&[TextureFormat::Rgba8UnormSrgb]), Some((TextureFormat::Rgba8UnormSrgb, true)));
+        assert_eq!(pick_surface_format(&[]), None);
+    }
+}
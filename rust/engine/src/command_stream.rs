@@ -0,0 +1,307 @@
+/// Draw command stream, version 1 (mcore_render_commands_v2)
+///
+/// McoreDrawCommand is one fixed struct for every primitive, so each new
+/// primitive means new fields and a new layout for every host. A stream is
+/// instead a byte buffer of variable-size records:
+///
+///   McoreCmdHeader { u16 tag, u16 flags, u32 size }   8 bytes
+///   payload                                           `size` bytes
+///   padding to the next multiple of 4
+///
+/// Payloads are the McoreCmd* structs in native byte order. Every field is 4
+/// bytes wide, so they have no padding and the same layout on every target.
+/// The format stays compatible in both directions by only ever adding:
+///
+///   - new primitives get new tags; unknown tags are skipped by size
+///   - new fields go at the end of a payload; fields missing from a shorter
+///     payload (an older host) read as 0, extra bytes (a newer host) are ignored
+///   - trailing data (text) is addressed from the end of the payload, so it
+///     stays findable when the fixed part grows
+///
+/// The version only changes if the framing itself does. Streams are parsed
+/// in full before anything is drawn, so a malformed one draws nothing.
+/// Coordinates are logical px; transforms apply to the commands after them,
+/// but not to hit regions (use mcore_hit_push_transform for those).
+/// Replay recordings only capture mcore_render_commands buffers, not streams.
+
+use bytemuck::{Pod, Zeroable};
+use peniko::kurbo::{Affine, Rect, RoundedRect, Stroke};
+use peniko::{BlendMode, Color, Fill};
+
+use crate::engine::Engine;
+use crate::{hit, image, text, video};
+use crate::{
+    McoreCmdClip, McoreCmdFill, McoreCmdGradient, McoreCmdHeader, McoreCmdImage, McoreCmdShadow, McoreCmdStroke,
+    McoreCmdTag, McoreCmdText, McoreCmdTransform, McoreCmdVideo,
+};
+
+pub const VERSION: u32 = crate::COMMAND_STREAM_VERSION;
+
+const HEADER_SIZE: usize = std::mem::size_of::<McoreCmdHeader>();
+
+/// A parsed record, borrowing its text from the stream
+#[derive(Copy, Clone)]
+pub enum Command<'a> {
+    Fill(McoreCmdFill),
+    Stroke(McoreCmdStroke),
+    Shadow(McoreCmdShadow),
+    Text(McoreCmdText, &'a str),
+    PushClip(McoreCmdClip),
+    PopClip,
+    Gradient(McoreCmdGradient),
+    Image(McoreCmdImage),
+    PushTransform(McoreCmdTransform),
+    PopTransform,
+    Video(McoreCmdVideo),
+}
+
+/// Parse a whole stream, skipping records with unknown tags
+pub fn parse(bytes: &[u8]) -> Result<Vec<Command<'_>>, String> {
+    let mut commands = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let header = bytes
+            .get(offset..offset + HEADER_SIZE)
+            .ok_or_else(|| format!("truncated record header at byte {offset}"))?;
+        let tag = u16::from_ne_bytes([header[0], header[1]]);
+        let size = u32::from_ne_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let start = offset + HEADER_SIZE;
+        let payload = start
+            .checked_add(size)
+            .and_then(|end| bytes.get(start..end))
+            .ok_or_else(|| format!("record at byte {offset} runs past the end of the stream ({size} byte payload)"))?;
+
+        if let Some(command) = parse_record(tag, payload).map_err(|e| format!("record at byte {offset}: {e}"))? {
+            commands.push(command);
+        }
+        offset = (start + size).next_multiple_of(4);
+    }
+    Ok(commands)
+}
+
+fn parse_record(tag: u16, payload: &[u8]) -> Result<Option<Command<'_>>, String> {
+    const FILL: u16 = McoreCmdTag::FillRect as u16;
+    const STROKE: u16 = McoreCmdTag::StrokeRect as u16;
+    const SHADOW: u16 = McoreCmdTag::Shadow as u16;
+    const TEXT: u16 = McoreCmdTag::Text as u16;
+    const PUSH_CLIP: u16 = McoreCmdTag::PushClip as u16;
+    const POP_CLIP: u16 = McoreCmdTag::PopClip as u16;
+    const GRADIENT: u16 = McoreCmdTag::Gradient as u16;
+    const IMAGE: u16 = McoreCmdTag::Image as u16;
+    const PUSH_TRANSFORM: u16 = McoreCmdTag::PushTransform as u16;
+    const POP_TRANSFORM: u16 = McoreCmdTag::PopTransform as u16;
+    const VIDEO: u16 = McoreCmdTag::Video as u16;
+
+    Ok(Some(match tag {
+        FILL => Command::Fill(read(payload)),
+        STROKE => Command::Stroke(read(payload)),
+        SHADOW => Command::Shadow(read(payload)),
+        TEXT => {
+            let cmd: McoreCmdText = read(payload);
+            let text_len = cmd.text_len as usize;
+            let text_start = payload
+                .len()
+                .checked_sub(text_len)
+                .ok_or_else(|| format!("text_len {text_len} is longer than the payload"))?;
+            // The text can't overlap the fields that say where it is
+            let fixed: McoreCmdText = read(&payload[..text_start]);
+            if fixed.text_len != cmd.text_len {
+                return Err("text overlaps the text command's fields".to_string());
+            }
+            let text = std::str::from_utf8(&payload[text_start..]).map_err(|e| format!("text is not UTF-8: {e}"))?;
+            Command::Text(fixed, text)
+        }
+        PUSH_CLIP => Command::PushClip(read(payload)),
+        POP_CLIP => Command::PopClip,
+        GRADIENT => Command::Gradient(read(payload)),
+        IMAGE => Command::Image(read(payload)),
+        PUSH_TRANSFORM => Command::PushTransform(read(payload)),
+        POP_TRANSFORM => Command::PopTransform,
+        VIDEO => Command::Video(read(payload)),
+        _ => return Ok(None),
+    }))
+}
+
+/// A payload struct from however many of its bytes the payload has, the rest zeroed
+fn read<T: Pod>(payload: &[u8]) -> T {
+    let mut value = T::zeroed();
+    let bytes = bytemuck::bytes_of_mut(&mut value);
+    let n = bytes.len().min(payload.len());
+    bytes[..n].copy_from_slice(&payload[..n]);
+    value
+}
+
+fn rounded_rect(x: f32, y: f32, width: f32, height: f32, radius: f32) -> RoundedRect {
+    RoundedRect::new(x as f64, y as f64, (x + width) as f64, (y + height) as f64, radius as f64)
+}
+
+/// Draw parsed commands into the current scene. Pushes left unpopped at the
+/// end are the host's to balance, like clips in mcore_render_commands; pops
+/// with no clip pushed are skipped (see layers.rs).
+pub fn draw(eng: &mut Engine, commands: &[Command]) {
+    let scale = eng.gfx.scale();
+    let device = Affine::scale(scale as f64);
+    let mut transforms = vec![Affine::IDENTITY];
+
+    for command in commands {
+        let local = *transforms.last().expect("the identity is never popped");
+        let transform = device * local;
+        match *command {
+            Command::Fill(c) => {
+                let shape = rounded_rect(c.x, c.y, c.width, c.height, c.radius);
+                eng.scene.fill(Fill::NonZero, transform, Color::new(c.color), None, &shape);
+            }
+            Command::Stroke(c) => {
+                if c.line_width <= 0.0 {
+                    continue;
+                }
+                let shape = rounded_rect(c.x, c.y, c.width, c.height, c.radius);
+                let stroke = Stroke::new(c.line_width as f64);
+                eng.scene.stroke(&stroke, transform, Color::new(c.color), None, &shape);
+            }
+            Command::Shadow(c) => {
+                let rect = Rect::new(c.x as f64, c.y as f64, (c.x + c.width) as f64, (c.y + c.height) as f64);
+                eng.scene.draw_blurred_rounded_rect(transform, rect, Color::new(c.color), c.blur as f64, c.radius as f64);
+            }
+            Command::Text(c, utf8) => {
+                let style = text::TextStyle {
+                    align: text::TextAlign::from_u8(c.align.min(u8::MAX as u32) as u8),
+                    direction: text::TextDirection::from_u8(c.direction.min(u8::MAX as u32) as u8),
+                    ..text::TextStyle::new(c.font_size, c.font_id)
+                };
+                let wrap_width = if c.wrap_width > 0.0 { c.wrap_width } else { text::NO_WRAP_WIDTH };
+                // Layouts are shaped in physical px
+                let placement = transform * Affine::translate((c.x as f64, c.y as f64)) * Affine::scale(1.0 / scale as f64);
+                text::draw_text_transformed(
+                    &mut eng.scene,
+                    &mut eng.text_cx,
+                    utf8,
+                    placement,
+                    &style,
+                    wrap_width,
+                    Color::new(c.color),
+                    scale,
+                );
+            }
+            Command::PushClip(c) => {
+                let shape = rounded_rect(c.x, c.y, c.width, c.height, c.radius);
+                eng.scene.push_layer(BlendMode::default(), 1.0, transform, &shape);
+                eng.layers.push(transform, shape);
+                let bounds = local.transform_rect_bbox(shape.rect());
+                eng.hits.push_clip(hit::Rect::from_xywh(
+                    bounds.x0 as f32,
+                    bounds.y0 as f32,
+                    bounds.width() as f32,
+                    bounds.height() as f32,
+                ));
+            }
+            Command::PopClip => {
+                if !eng.layers.pop() {
+                    continue;
+                }
+                eng.scene.pop_layer();
+                eng.hits.pop_clip();
+            }
+            Command::Gradient(c) => {
+                let Some(gradient) = eng.gradients.get(c.gradient_id) else {
+                    continue;
+                };
+                let shape = rounded_rect(c.x, c.y, c.width, c.height, c.radius);
+                let brush = gradient.brush(shape.rect());
+                if c.line_width > 0.0 {
+                    eng.scene.stroke(&Stroke::new(c.line_width as f64), transform, &brush, None, &shape);
+                } else {
+                    eng.scene.fill(Fill::NonZero, transform, &brush, None, &shape);
+                }
+            }
+            Command::Image(c) => {
+                let (dest, tile) = c.rects();
+                image::draw(&mut eng.scene, &eng.images, c.image_id, transform, dest, tile, c.sampler());
+            }
+            Command::PushTransform(c) => {
+                let m = c.matrix.map(|v| v as f64);
+                transforms.push(local * Affine::new(m));
+            }
+            Command::PopTransform => {
+                if transforms.len() > 1 {
+                    transforms.pop();
+                }
+            }
+            Command::Video(c) => {
+                video::draw(&mut eng.scene, &eng.custom_textures, &eng.videos, c.video_id, transform, c.rect(), c.fit_mode());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(stream: &mut Vec<u8>, tag: u16, payload: &[u8]) {
+        stream.extend_from_slice(&tag.to_ne_bytes());
+        stream.extend_from_slice(&0u16.to_ne_bytes());
+        stream.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+        stream.extend_from_slice(payload);
+        stream.resize(stream.len().next_multiple_of(4), 0);
+    }
+
+    fn fill(x: f32) -> McoreCmdFill {
+        McoreCmdFill { x, y: 2.0, width: 3.0, height: 4.0, radius: 0.0, color: [1.0; 4] }
+    }
+
+    #[test]
+    fn records_are_framed_by_size() {
+        let mut stream = Vec::new();
+        record(&mut stream, McoreCmdTag::FillRect as u16, bytemuck::bytes_of(&fill(1.0)));
+        // A primitive from a newer host is skipped
+        record(&mut stream, 999, &[7; 13]);
+        let text = McoreCmdText { text_len: 5, ..Zeroable::zeroed() };
+        let mut payload = bytemuck::bytes_of(&text).to_vec();
+        payload.extend_from_slice(b"hello");
+        record(&mut stream, McoreCmdTag::Text as u16, &payload);
+        record(&mut stream, McoreCmdTag::PopClip as u16, &[]);
+
+        let commands = parse(&stream).unwrap();
+        assert_eq!(commands.len(), 3);
+        assert!(matches!(commands[0], Command::Fill(c) if c.x == 1.0 && c.height == 4.0));
+        assert!(matches!(commands[1], Command::Text(c, "hello") if c.text_len == 5));
+        assert!(matches!(commands[2], Command::PopClip));
+    }
+
+    #[test]
+    fn payloads_may_be_shorter_or_longer() {
+        let mut stream = Vec::new();
+        // An older host: no color field, which reads as transparent
+        let short = &bytemuck::bytes_of(&fill(1.0))[..20];
+        record(&mut stream, McoreCmdTag::FillRect as u16, short);
+        // A newer host with a field appended
+        let mut long = bytemuck::bytes_of(&fill(2.0)).to_vec();
+        long.extend_from_slice(&5.0f32.to_ne_bytes());
+        record(&mut stream, McoreCmdTag::FillRect as u16, &long);
+
+        let commands = parse(&stream).unwrap();
+        assert!(matches!(commands[0], Command::Fill(c) if c.width == 3.0 && c.color == [0.0; 4]));
+        assert!(matches!(commands[1], Command::Fill(c) if c.x == 2.0 && c.color == [1.0; 4]));
+    }
+
+    #[test]
+    fn malformed_streams_are_rejected() {
+        let mut stream = Vec::new();
+        record(&mut stream, McoreCmdTag::FillRect as u16, bytemuck::bytes_of(&fill(1.0)));
+        assert!(parse(&stream[..stream.len() - 4]).unwrap_err().contains("runs past the end"));
+        assert!(parse(&stream[..5]).unwrap_err().contains("truncated record header"));
+
+        let mut stream = Vec::new();
+        let text = McoreCmdText { text_len: 100, ..Zeroable::zeroed() };
+        record(&mut stream, McoreCmdTag::Text as u16, bytemuck::bytes_of(&text));
+        assert!(parse(&stream).is_err());
+
+        let mut stream = Vec::new();
+        let text = McoreCmdText { text_len: 2, ..Zeroable::zeroed() };
+        let mut payload = bytemuck::bytes_of(&text).to_vec();
+        payload.extend_from_slice(&[0xff, 0xfe]);
+        record(&mut stream, McoreCmdTag::Text as u16, &payload);
+        assert!(parse(&stream).unwrap_err().contains("UTF-8"));
+    }
+}
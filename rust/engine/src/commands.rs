@@ -0,0 +1,123 @@
+/// Fixed-size draw commands (mcore_render_commands)
+///
+/// The first command format: every primitive is one McoreDrawCommand,
+/// told apart by `kind`, in logical px. Replay recordings and scene dumps
+/// capture these buffers and draw them back through here. Newer hosts use
+/// the variable-length stream in command_stream.rs instead.
+
+use peniko::kurbo::{Affine, Rect, RoundedRect, Stroke};
+use peniko::{BlendMode, Color, Fill};
+
+use crate::engine::Engine;
+use crate::{hit, text, utf8_arg, McoreDrawCmdKind, McoreDrawCommand};
+
+/// Scale a command's rect from logical to physical px
+fn rounded_rect(cmd: &McoreDrawCommand, scale: f32) -> RoundedRect {
+    RoundedRect::new(
+        (cmd.x * scale) as f64,
+        (cmd.y * scale) as f64,
+        ((cmd.x + cmd.width) * scale) as f64,
+        ((cmd.y + cmd.height) * scale) as f64,
+        (cmd.radius * scale) as f64,
+    )
+}
+
+/// Draw `commands` into the current scene. Clips left open are the host's to
+/// balance; pops with no clip pushed are skipped (see layers.rs). Unknown
+/// kinds are ignored.
+pub fn draw(eng: &mut Engine, commands: &[McoreDrawCommand]) {
+    const ROUNDED_RECT: u8 = McoreDrawCmdKind::RoundedRect as u8;
+    const TEXT: u8 = McoreDrawCmdKind::Text as u8;
+    const PUSH_CLIP: u8 = McoreDrawCmdKind::PushClip as u8;
+    const POP_CLIP: u8 = McoreDrawCmdKind::PopClip as u8;
+    const STYLED_RECT: u8 = McoreDrawCmdKind::StyledRect as u8;
+    const PUSH_CLIP_ROUNDED: u8 = McoreDrawCmdKind::PushClipRounded as u8;
+    const GRADIENT_RING: u8 = McoreDrawCmdKind::GradientRing as u8;
+
+    let scale = eng.gfx.scale();
+    let identity = Affine::IDENTITY;
+
+    for cmd in commands {
+        match cmd.kind {
+            ROUNDED_RECT => {
+                eng.scene.fill(Fill::NonZero, identity, Color::new(cmd.color), None, &rounded_rect(cmd, scale));
+            }
+            TEXT => {
+                let style = text::TextStyle {
+                    align: text::TextAlign::from_u8(cmd.text_align),
+                    direction: text::TextDirection::from_u8(cmd.text_direction),
+                    ..text::TextStyle::new(cmd.font_size, cmd.font_id)
+                };
+                text::draw_text(
+                    &mut eng.scene,
+                    &mut eng.text_cx,
+                    utf8_arg(cmd.text_ptr),
+                    cmd.x * scale,
+                    cmd.y * scale,
+                    &style,
+                    cmd.wrap_width,
+                    Color::new(cmd.color),
+                    scale,
+                );
+            }
+            PUSH_CLIP => {
+                let clip = rounded_rect(cmd, scale).rect();
+                eng.scene.push_layer(BlendMode::default(), 1.0, identity, &clip);
+                eng.layers.push(identity, clip);
+                eng.hits.push_clip(hit::Rect::from_xywh(cmd.x, cmd.y, cmd.width, cmd.height));
+            }
+            POP_CLIP => {
+                if !eng.layers.pop() {
+                    continue;
+                }
+                eng.scene.pop_layer();
+                eng.hits.pop_clip();
+            }
+            STYLED_RECT => {
+                // Shadow, fill, then an optional border
+                let shape = rounded_rect(cmd, scale);
+                if cmd.has_shadow != 0 {
+                    let shadow = Rect::new(
+                        ((cmd.x + cmd.shadow_offset_x) * scale) as f64,
+                        ((cmd.y + cmd.shadow_offset_y) * scale) as f64,
+                        ((cmd.x + cmd.width + cmd.shadow_offset_x) * scale) as f64,
+                        ((cmd.y + cmd.height + cmd.shadow_offset_y) * scale) as f64,
+                    );
+                    eng.scene.draw_blurred_rounded_rect(
+                        identity,
+                        shadow,
+                        Color::new(cmd.shadow_color),
+                        (cmd.shadow_blur * scale) as f64,
+                        (cmd.radius * scale) as f64,
+                    );
+                }
+                eng.scene.fill(Fill::NonZero, identity, Color::new(cmd.color), None, &shape);
+                if cmd.has_border != 0 && cmd.border_width > 0.0 {
+                    let stroke = Stroke::new((cmd.border_width * scale) as f64);
+                    eng.scene.stroke(&stroke, identity, Color::new(cmd.border_color), None, &shape);
+                }
+            }
+            PUSH_CLIP_ROUNDED => {
+                let clip = rounded_rect(cmd, scale);
+                eng.scene.push_layer(BlendMode::default(), 1.0, identity, &clip);
+                eng.layers.push(identity, clip);
+                eng.hits.push_clip(hit::Rect::from_xywh(cmd.x, cmd.y, cmd.width, cmd.height));
+            }
+            GRADIENT_RING => {
+                // A rounded-rect outline stroked with a registered gradient
+                // (font_id); border_width is centered on the edge
+                let Some(gradient) = eng.gradients.get(cmd.font_id) else {
+                    continue;
+                };
+                if cmd.border_width <= 0.0 {
+                    continue;
+                }
+                let shape = rounded_rect(cmd, scale);
+                let brush = gradient.brush(shape.rect());
+                let stroke = Stroke::new((cmd.border_width * scale) as f64);
+                eng.scene.stroke(&stroke, identity, &brush, None, &shape);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,185 @@
+/// Diagnostic bundles for unrecoverable errors
+///
+/// Once a host sets a directory (mcore_set_crash_dump), a lost GPU device,
+/// repeated render failures or a panic write a bundle there:
+///
+///   mcore-crash-<unix seconds>/report.txt   reason, GPU/surface config, stats
+///   mcore-crash-<unix seconds>/trace.txt    recent FFI calls (see trace.rs)
+///
+/// and the host's callback gets the reason and bundle path, so end-user bug
+/// reports carry what the engine knew when it failed. Panics can't take the
+/// engine lock (the panicking thread may hold it), so end_frame keeps a small
+/// snapshot of the stats up to date instead.
+
+use std::ffi::CString;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+
+use crate::trace;
+use crate::McoreCrashCallback as CrashCallback;
+
+/// Consecutive failed frames treated as unrecoverable
+pub const MAX_RENDER_FAILURES: u32 = 3;
+
+struct Config {
+    dir: PathBuf,
+    callback: Option<CrashCallback>,
+    /// Gfx::describe at the time the dump was configured
+    gfx: String,
+}
+
+/// Per-frame state kept current for bundles written from a panic
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Snapshot {
+    pub time_s: f64,
+    pub frames_presented: u64,
+    pub render_failures: u32,
+    pub refresh_rate: f32,
+    pub occluded: bool,
+    pub text_measure_calls: u32,
+    pub text_offset_calls: u32,
+    pub images: usize,
+    pub text_inputs: usize,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CONFIG: Mutex<Option<Config>> = Mutex::new(None);
+static SNAPSHOT: Mutex<Option<Snapshot>> = Mutex::new(None);
+static PANIC_HOOK: Once = Once::new();
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Write bundles into `dir` from now on (None stops)
+pub fn configure(dir: Option<PathBuf>, callback: Option<CrashCallback>, gfx: String) {
+    *CONFIG.lock() = dir.map(|dir| Config { dir, callback, gfx });
+    ENABLED.store(CONFIG.lock().is_some(), Ordering::Relaxed);
+    PANIC_HOOK.call_once(install_panic_hook);
+}
+
+pub fn update_snapshot(snapshot: Snapshot) {
+    *SNAPSHOT.lock() = Some(snapshot);
+}
+
+/// A written bundle, reported to the host once the engine lock is released
+pub struct Report {
+    reason: CString,
+    path: CString,
+    callback: CrashCallback,
+}
+
+impl Report {
+    pub fn fire(self) {
+        (self.callback)(self.reason.as_ptr(), self.path.as_ptr());
+    }
+}
+
+/// Write a bundle for `reason`; returns the callback to fire, if any
+pub fn write_bundle(reason: &str) -> Option<Report> {
+    let (dir, callback, gfx) = {
+        let config = CONFIG.lock();
+        let config = config.as_ref()?;
+        (config.dir.clone(), config.callback, config.gfx.clone())
+    };
+    let snapshot = *SNAPSHOT.lock();
+
+    let path = match write_files(&dir, reason, &gfx, snapshot) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("mcore: failed to write crash bundle to {}: {e}", dir.display());
+            return None;
+        }
+    };
+
+    let callback = callback?;
+    Some(Report {
+        reason: CString::new(reason.replace('\0', "")).expect("nul bytes were removed"),
+        path: CString::new(path.to_string_lossy().replace('\0', "")).expect("nul bytes were removed"),
+        callback,
+    })
+}
+
+fn write_files(dir: &Path, reason: &str, gfx: &str, snapshot: Option<Snapshot>) -> std::io::Result<PathBuf> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let mut path = dir.join(format!("mcore-crash-{now}"));
+    // Several failures in one second each get their own bundle
+    let mut n = 1;
+    while path.exists() {
+        path = dir.join(format!("mcore-crash-{now}-{n}"));
+        n += 1;
+    }
+    std::fs::create_dir_all(&path)?;
+
+    std::fs::write(path.join("report.txt"), format_report(reason, gfx, snapshot, now))?;
+    let trace = trace::dump().unwrap_or_else(|| "FFI tracing was off (enable with MCORE_TRACE=<n>)\n".to_string());
+    std::fs::write(path.join("trace.txt"), trace)?;
+    Ok(path)
+}
+
+fn format_report(reason: &str, gfx: &str, snapshot: Option<Snapshot>, unix_time: u64) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "reason: {reason}");
+    let _ = writeln!(out, "time: {unix_time} (unix)");
+    let _ = writeln!(out, "engine: {} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(out, "os: {} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let _ = writeln!(out, "capabilities: {:#x}", crate::mcore_get_capabilities());
+    let _ = writeln!(out, "\n[gpu]\n{gfx}");
+    match snapshot {
+        Some(s) => {
+            let _ = writeln!(out, "\n[last frame]");
+            let _ = writeln!(out, "time_s: {:.3}", s.time_s);
+            let _ = writeln!(out, "frames_presented: {}", s.frames_presented);
+            let _ = writeln!(out, "consecutive_render_failures: {}", s.render_failures);
+            let _ = writeln!(out, "refresh_rate: {}", s.refresh_rate);
+            let _ = writeln!(out, "occluded: {}", s.occluded);
+            let _ = writeln!(out, "text_measure_calls: {}", s.text_measure_calls);
+            let _ = writeln!(out, "text_offset_calls: {}", s.text_offset_calls);
+            let _ = writeln!(out, "images: {}", s.images);
+            let _ = writeln!(out, "text_inputs: {}", s.text_inputs);
+        }
+        None => {
+            let _ = writeln!(out, "\n[last frame]\nno frame presented yet");
+        }
+    }
+    out
+}
+
+/// Entry points catch panics (ffi_boundary), but the engine may be left
+/// half-updated, so write the bundle here, before the default hook prints
+/// the message
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if enabled() {
+            let backtrace = std::backtrace::Backtrace::force_capture();
+            if let Some(report) = write_bundle(&format!("panic: {info}\n\n{backtrace}")) {
+                report.fire();
+            }
+        }
+        previous(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_includes_reason_gpu_and_stats() {
+        let snapshot = Snapshot { frames_presented: 42, render_failures: 3, ..Default::default() };
+        let report = format_report("device lost", "adapter: Test GPU", Some(snapshot), 1_700_000_000);
+        assert!(report.starts_with("reason: device lost\n"));
+        assert!(report.contains("[gpu]\nadapter: Test GPU"));
+        assert!(report.contains("frames_presented: 42"));
+        assert!(report.contains("consecutive_render_failures: 3"));
+
+        let report = format_report("panic", "", None, 0);
+        assert!(report.contains("no frame presented yet"));
+    }
+}
@@ -0,0 +1,133 @@
+/// Mouse pointer shape (mcore_set_cursor)
+///
+/// NSCursor on macOS. AppKit puts the arrow back when the pointer crosses
+/// cursor rects, so hosts set the shape again as the pointer moves. Other
+/// platforms only remember the shape, for hosts to apply (mcore_get_cursor).
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Cursor {
+    Arrow = 0,
+    IBeam = 1,
+    PointingHand = 2,
+    Crosshair = 3,
+    OpenHand = 4,
+    ClosedHand = 5,
+    NotAllowed = 6,
+    ResizeLeftRight = 7,
+    ResizeUpDown = 8,
+    /// Diagonal, top-left to bottom-right
+    ResizeNwse = 9,
+    /// Diagonal, top-right to bottom-left
+    ResizeNesw = 10,
+}
+
+impl Cursor {
+    const ALL: [Cursor; 11] = [
+        Cursor::Arrow,
+        Cursor::IBeam,
+        Cursor::PointingHand,
+        Cursor::Crosshair,
+        Cursor::OpenHand,
+        Cursor::ClosedHand,
+        Cursor::NotAllowed,
+        Cursor::ResizeLeftRight,
+        Cursor::ResizeUpDown,
+        Cursor::ResizeNwse,
+        Cursor::ResizeNesw,
+    ];
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(Cursor::Arrow as u8);
+
+/// Show `cursor`; false if the platform cursor couldn't be set
+pub fn set(cursor: Cursor) -> bool {
+    CURRENT.store(cursor as u8, Ordering::Relaxed);
+    #[cfg(target_os = "macos")]
+    {
+        macos::set(cursor)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        true
+    }
+}
+
+/// The shape last passed to set
+pub fn current() -> Cursor {
+    Cursor::ALL[CURRENT.load(Ordering::Relaxed) as usize]
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::Cursor;
+    use std::ffi::{c_char, c_void, CStr};
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+        fn objc_autoreleasePoolPush() -> *mut c_void;
+        fn objc_autoreleasePoolPop(pool: *mut c_void);
+    }
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {}
+
+    unsafe fn send_id(receiver: *mut c_void, selector: &CStr) -> *mut c_void {
+        let sel = sel_registerName(selector.as_ptr());
+        let f: unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        f(receiver, sel)
+    }
+
+    unsafe fn responds_to(receiver: *mut c_void, selector: &CStr) -> bool {
+        let sel = sel_registerName(c"respondsToSelector:".as_ptr());
+        let f: unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void) -> u8 =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        f(receiver, sel, sel_registerName(selector.as_ptr())) != 0
+    }
+
+    /// NSCursor class method for each shape. AppKit has no public diagonal
+    /// resize cursors, so those use the private ones window edges show,
+    /// falling back to the arrow.
+    fn selectors(cursor: Cursor) -> (&'static CStr, Option<&'static CStr>) {
+        match cursor {
+            Cursor::Arrow => (c"arrowCursor", None),
+            Cursor::IBeam => (c"IBeamCursor", None),
+            Cursor::PointingHand => (c"pointingHandCursor", None),
+            Cursor::Crosshair => (c"crosshairCursor", None),
+            Cursor::OpenHand => (c"openHandCursor", None),
+            Cursor::ClosedHand => (c"closedHandCursor", None),
+            Cursor::NotAllowed => (c"operationNotAllowedCursor", None),
+            Cursor::ResizeLeftRight => (c"resizeLeftRightCursor", None),
+            Cursor::ResizeUpDown => (c"resizeUpDownCursor", None),
+            Cursor::ResizeNwse => (c"arrowCursor", Some(c"_windowResizeNorthWestSouthEastCursor")),
+            Cursor::ResizeNesw => (c"arrowCursor", Some(c"_windowResizeNorthEastSouthWestCursor")),
+        }
+    }
+
+    pub fn set(cursor: Cursor) -> bool {
+        unsafe {
+            let pool = objc_autoreleasePoolPush();
+            let class = objc_getClass(c"NSCursor".as_ptr());
+            let mut ns_cursor = std::ptr::null_mut();
+            if !class.is_null() {
+                let (public, private) = selectors(cursor);
+                if let Some(private) = private.filter(|&s| responds_to(class, s)) {
+                    ns_cursor = send_id(class, private);
+                }
+                if ns_cursor.is_null() {
+                    ns_cursor = send_id(class, public);
+                }
+                if !ns_cursor.is_null() {
+                    send_id(ns_cursor, c"set");
+                }
+            }
+            objc_autoreleasePoolPop(pool);
+            !ns_cursor.is_null()
+        }
+    }
+}
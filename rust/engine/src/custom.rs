@@ -0,0 +1,193 @@
+/// Texture-backed custom widgets
+///
+/// An escape hatch for content Vello can't express (embedded 3D views, map
+/// tiles): the engine owns a wgpu texture per widget, hands it to a renderer
+/// every frame before the scene is drawn, and Vello composites the result like
+/// any other image.
+
+use peniko::{Blob, ImageData};
+use std::collections::HashMap;
+use std::sync::Arc;
+use vello::peniko::{ImageAlphaType, ImageFormat};
+
+use crate::gfx::Gfx;
+
+/// Format of custom widget textures (what Vello's image atlas expects)
+pub const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// Everything a renderer gets to draw one frame
+pub struct RenderTarget<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    pub texture: &'a wgpu::Texture,
+    pub view: &'a wgpu::TextureView,
+    /// Physical pixels
+    pub width: u32,
+    pub height: u32,
+    /// Frame time passed to mcore_begin_frame
+    pub time: f64,
+}
+
+/// Draws into a custom widget's texture. Work must be submitted to the queue
+/// before returning; the engine renders the frame right after.
+pub trait TextureRenderer: Send {
+    fn render(&mut self, target: &RenderTarget<'_>);
+}
+
+struct CustomTexture {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    /// Placeholder image whose pixels are replaced by `texture` at render time
+    image: ImageData,
+    renderer: Box<dyn TextureRenderer>,
+}
+
+fn create_texture(device: &wgpu::Device, width: u32, height: u32) -> (wgpu::Texture, wgpu::TextureView, ImageData) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Custom Widget Texture"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: TEXTURE_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::STORAGE_BINDING
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    // Vello keys image overrides by blob identity, so every texture gets its own blob
+    let image = ImageData {
+        data: Blob::new(Arc::new(vec![0u8; (width as usize) * (height as usize) * 4])),
+        format: ImageFormat::Rgba8,
+        width,
+        height,
+        alpha_type: ImageAlphaType::AlphaPremultiplied,
+    };
+
+    (texture, view, image)
+}
+
+pub struct CustomTextureManager {
+    textures: HashMap<i32, CustomTexture>,
+    next_id: i32,
+}
+
+impl CustomTextureManager {
+    pub fn new() -> Self {
+        Self {
+            textures: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Create a widget texture (physical pixels) and return its ID
+    pub fn create(
+        &mut self,
+        gfx: &Gfx,
+        width: u32,
+        height: u32,
+        renderer: Box<dyn TextureRenderer>,
+    ) -> Result<i32, String> {
+        if width == 0 || height == 0 {
+            return Err(format!("Invalid custom texture size {}x{}", width, height));
+        }
+
+        let (texture, view, image) = create_texture(gfx.device(), width, height);
+        let id = self.next_id;
+        self.next_id += 1;
+        self.textures.insert(id, CustomTexture { texture, view, image, renderer });
+        Ok(id)
+    }
+
+    /// Recreate a widget's texture at a new size
+    pub fn resize(&mut self, gfx: &mut Gfx, id: i32, width: u32, height: u32) -> Result<(), String> {
+        if width == 0 || height == 0 {
+            return Err(format!("Invalid custom texture size {}x{}", width, height));
+        }
+        let Some(entry) = self.textures.get_mut(&id) else {
+            return Err(format!("Custom texture {} not found", id));
+        };
+
+        gfx.override_image(&entry.image, None);
+        let (texture, view, image) = create_texture(gfx.device(), width, height);
+        entry.texture = texture;
+        entry.view = view;
+        entry.image = image;
+        Ok(())
+    }
+
+    /// Free a widget texture, returning whether it existed
+    pub fn remove(&mut self, gfx: &mut Gfx, id: i32) -> bool {
+        match self.textures.remove(&id) {
+            Some(entry) => {
+                gfx.override_image(&entry.image, None);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Bytes held by widget textures
+    pub fn bytes(&self) -> u64 {
+        self.textures.values().map(|entry| entry.texture.width() as u64 * entry.texture.height() as u64 * 4).sum()
+    }
+
+    /// Image to draw into the scene for a widget
+    pub fn image(&self, id: i32) -> Option<&ImageData> {
+        self.textures.get(&id).map(|entry| &entry.image)
+    }
+
+    /// Let every renderer draw its texture, then point Vello at the results
+    pub fn render_all(&mut self, gfx: &mut Gfx, time: f64) {
+        for entry in self.textures.values_mut() {
+            let target = RenderTarget {
+                device: gfx.device(),
+                queue: gfx.queue(),
+                texture: &entry.texture,
+                view: &entry.view,
+                width: entry.image.width,
+                height: entry.image.height,
+                time,
+            };
+            entry.renderer.render(&target);
+        }
+        for entry in self.textures.values() {
+            gfx.override_image(&entry.image, Some(entry.texture.clone()));
+        }
+    }
+}
+
+impl Default for CustomTextureManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The native texture behind a wgpu texture (MTLTexture* on macOS), for hosts
+/// that render with their own graphics API. Null if unavailable.
+pub fn native_texture_handle(texture: &wgpu::Texture) -> *mut std::ffi::c_void {
+    #[cfg(target_os = "macos")]
+    {
+        use metal::foreign_types::ForeignType;
+        // SAFETY: the handle is only used while the texture is alive (during the render callback)
+        unsafe {
+            texture
+                .as_hal::<wgpu::hal::api::Metal>()
+                .map(|raw| raw.raw_handle().as_ptr() as *mut std::ffi::c_void)
+                .unwrap_or(std::ptr::null_mut())
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = texture;
+        std::ptr::null_mut()
+    }
+}
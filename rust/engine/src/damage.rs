@@ -0,0 +1,152 @@
+/// Skipping frames whose content didn't change
+///
+/// Off until the host turns it on (mcore_set_damage_tracking). Each frame the
+/// command buffers (mcore_render_commands, mcore_render_commands_v2) are
+/// hashed along with the frame's surface size, scale and clear color; when the
+/// hash matches the last presented frame, mcore_end_frame_present skips the
+/// render and present and returns MCORE_FRAME_SKIPPED.
+///
+/// Only command buffers can be compared. Any direct drawing call (text, clips,
+/// images, color filters...) marks the frame untracked and it is always
+/// presented, as are frames with live custom textures or particles.
+
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::CStr;
+use std::hash::Hasher;
+
+use crate::McoreDrawCommand;
+
+#[derive(Default)]
+pub struct DamageTracker {
+    enabled: bool,
+    hasher: DefaultHasher,
+    /// Something this frame drew wasn't hashed
+    untracked: bool,
+    /// Hash of the last presented frame
+    last: Option<u64>,
+}
+
+impl DamageTracker {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.last = None;
+    }
+
+    /// Present the next frame whatever it contains (resources it references
+    /// changed under the same IDs)
+    pub fn invalidate(&mut self) {
+        self.last = None;
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.hasher = DefaultHasher::new();
+        self.untracked = false;
+    }
+
+    pub fn mark_untracked(&mut self) {
+        self.untracked = true;
+    }
+
+    pub fn hash_commands(&mut self, commands: &[McoreDrawCommand]) {
+        if !self.enabled {
+            return;
+        }
+        let h = &mut self.hasher;
+        h.write_u8(1);
+        h.write_usize(commands.len());
+        for cmd in commands {
+            h.write_u8(cmd.kind);
+            for v in [cmd.x, cmd.y, cmd.width, cmd.height, cmd.radius, cmd.font_size, cmd.wrap_width, cmd.border_width] {
+                h.write_u32(v.to_bits());
+            }
+            for v in cmd.color.iter().chain(&cmd.border_color).chain(&cmd.shadow_color) {
+                h.write_u32(v.to_bits());
+            }
+            for v in [cmd.shadow_offset_x, cmd.shadow_offset_y, cmd.shadow_blur] {
+                h.write_u32(v.to_bits());
+            }
+            h.write_i32(cmd.font_id);
+            h.write(&[cmd.has_border, cmd.has_shadow, cmd.text_align, cmd.text_direction]);
+            if cmd.text_ptr.is_null() {
+                h.write_u8(0);
+            } else {
+                let text = unsafe { CStr::from_ptr(cmd.text_ptr) }.to_bytes_with_nul();
+                h.write_u8(1);
+                h.write(text);
+            }
+        }
+    }
+
+    /// A v2 command stream, hashed as raw bytes
+    pub fn hash_stream(&mut self, bytes: &[u8]) {
+        if !self.enabled {
+            return;
+        }
+        self.hasher.write_u8(2);
+        self.hasher.write_usize(bytes.len());
+        self.hasher.write(bytes);
+    }
+
+    /// Finish the frame; returns true when it matches the last presented one
+    /// and can be skipped. `inputs` covers the surface size, scale and clear
+    /// color; `volatile` forces a present (custom textures, particles).
+    pub fn finish(&mut self, inputs: &[u32], volatile: bool) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if self.untracked || volatile {
+            self.last = None;
+            return false;
+        }
+        for &v in inputs {
+            self.hasher.write_u32(v);
+        }
+        let hash = self.hasher.finish();
+        let unchanged = self.last == Some(hash);
+        self.last = Some(hash);
+        unchanged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(tracker: &mut DamageTracker, stream: &[u8], untracked: bool) -> bool {
+        tracker.begin_frame();
+        tracker.hash_stream(stream);
+        if untracked {
+            tracker.mark_untracked();
+        }
+        tracker.finish(&[800, 600], false)
+    }
+
+    #[test]
+    fn skips_only_repeated_tracked_frames() {
+        let mut tracker = DamageTracker::default();
+        assert!(!frame(&mut tracker, b"abcd", false));
+        assert!(!frame(&mut tracker, b"abcd", false), "off by default");
+
+        tracker.set_enabled(true);
+        assert!(!frame(&mut tracker, b"abcd", false));
+        assert!(frame(&mut tracker, b"abcd", false));
+        assert!(!frame(&mut tracker, b"abce", false));
+        assert!(frame(&mut tracker, b"abce", false));
+
+        // A direct draw presents, and so does the frame after it
+        assert!(!frame(&mut tracker, b"abce", true));
+        assert!(!frame(&mut tracker, b"abce", false));
+        assert!(frame(&mut tracker, b"abce", false));
+
+        tracker.invalidate();
+        assert!(!frame(&mut tracker, b"abce", false));
+
+        tracker.begin_frame();
+        tracker.hash_stream(b"abce");
+        assert!(!tracker.finish(&[800, 601], false), "resize presents");
+    }
+}
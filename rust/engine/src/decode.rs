@@ -0,0 +1,107 @@
+/// Background image decoding (mcore_image_register_async)
+///
+/// Decoding a large JPEG on the thread that draws stalls the frame it
+/// happens in. Async registration hands out the image's ID at once and
+/// decodes on a small pool of threads; until it's done, draws of the ID show
+/// the placeholder color. The finished image goes straight into the shared
+/// registry (no context lock is taken), then the host's callback runs on the
+/// decode thread.
+
+use std::ffi::c_void;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::Mutex;
+
+use crate::engine::{fire_log, LogLevel};
+use crate::image::ImageManager;
+
+/// Called with the image's ID and size, or a size of 0 x 0 if decoding failed
+pub type DecodeCallback = extern "C" fn(image_id: i32, width: u32, height: u32, user_data: *mut c_void);
+
+/// More threads than this only fight the render thread for cores
+const MAX_THREADS: usize = 4;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The host's callback and its opaque pointer
+struct Completion {
+    callback: Option<DecodeCallback>,
+    user_data: *mut c_void,
+}
+
+// SAFETY: user_data is only handed back to the host, which promised it can be
+// used from any thread
+unsafe impl Send for Completion {}
+
+impl Completion {
+    fn fire(self, image_id: i32, size: Option<(u32, u32)>) {
+        if let Some(callback) = self.callback {
+            let (width, height) = size.unwrap_or((0, 0));
+            callback(image_id, width, height, self.user_data);
+        }
+    }
+}
+
+/// Start the decode threads if they aren't running yet, reporting any that
+/// fail to `log_callback` (call without holding the engine lock)
+pub fn start(log_callback: Option<extern "C" fn(u8, *const i8)>) {
+    pool(log_callback);
+}
+
+/// Started on the first async registration and kept for the process
+fn pool(log_callback: Option<extern "C" fn(u8, *const i8)>) -> &'static Mutex<Sender<Job>> {
+    static POOL: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get().saturating_sub(1)).clamp(1, MAX_THREADS);
+        for i in 0..threads {
+            let receiver = receiver.clone();
+            let spawned = std::thread::Builder::new().name(format!("mcore-decode-{i}")).spawn(move || work(&receiver));
+            if let Err(e) = spawned {
+                fire_log(log_callback, LogLevel::Error, &format!("failed to start decode thread: {e}"));
+            }
+        }
+        Mutex::new(sender)
+    })
+}
+
+fn work(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = receiver.lock().recv();
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
+
+/// Reserve an ID for encoded image `bytes` and decode them in the background,
+/// shrunk to fit `max_dimension` if given. `images` is a shared manager, so
+/// the engine needn't stay locked: without decode threads, the image is
+/// decoded and the callback fired before this returns.
+pub fn register_async(
+    mut images: ImageManager,
+    bytes: Vec<u8>,
+    max_dimension: Option<u32>,
+    callback: Option<DecodeCallback>,
+    user_data: *mut c_void,
+) -> i32 {
+    let id = images.reserve();
+    let completion = Completion { callback, user_data };
+    let job: Job = Box::new(move || {
+        // A decoder panic fails this image rather than the pool thread
+        let decoded = std::panic::catch_unwind(|| ImageManager::load_image_bytes(&bytes, max_dimension))
+            .unwrap_or_else(|_| Err("image decoder panicked".to_string()));
+        let size = images.complete(id, decoded);
+        completion.fire(id, size);
+    });
+    // Already started by `start`
+    let sent = pool(None).lock().send(job);
+    if let Err(rejected) = sent {
+        // No decode thread could be started: decode here instead
+        (rejected.0)();
+    }
+    id
+}
@@ -0,0 +1,315 @@
+/// Engine state behind an McoreContext
+///
+/// Owns every subsystem (GPU, text, images, input, animation); lib.rs is the
+/// FFI layer that locks it and delegates to the subsystem modules. Callbacks
+/// into the host are collected while locked and fired after unlocking.
+
+use parking_lot::Mutex;
+use peniko::FontData;
+use std::sync::Arc;
+use vello::Scene;
+
+#[cfg(feature = "a11y")]
+use crate::a11y;
+#[cfg(feature = "layout")]
+use crate::layout;
+use crate::{blur, crash, custom, damage, filter, fragment, gesture, gfx, gradient, hit, image, layers, log_view, overlay, path, prefs, render_thread, replay, schedule, scroll, text, text_input, ui, video, watchdog};
+
+/// Refresh rate assumed until the host reports one
+pub const DEFAULT_REFRESH_RATE: f32 = 60.0;
+
+/// Severity passed to the host's log callback
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LogLevel {
+    Debug = 0,
+    Info = 1,
+    Warn = 2,
+    Error = 3,
+}
+
+/// Text measurement statistics for instrumentation
+#[derive(Default)]
+pub struct TextMeasurementStats {
+    pub total_measure_calls: u32,
+    pub total_offset_calls: u32,
+}
+
+impl TextMeasurementStats {
+    pub fn reset(&mut self) {
+        self.total_measure_calls = 0;
+        self.total_offset_calls = 0;
+    }
+}
+
+/// Registered font data by font ID (keeps re-registration idempotent)
+pub type FontRegistry = Arc<Mutex<Vec<(i32, FontData)>>>;
+
+/// Fonts and images, which the contexts of one McoreEngine share: IDs from
+/// one context are valid in all of them
+#[derive(Default)]
+pub struct SharedResources {
+    pub text_cx: text::TextContext,
+    pub fonts: FontRegistry,
+    pub images: image::ImageManager,
+}
+
+impl SharedResources {
+    /// Resources backed by the same fonts and images
+    pub fn shared(&self) -> Self {
+        Self {
+            text_cx: self.text_cx.shared(),
+            fonts: self.fonts.clone(),
+            images: self.images.shared(),
+        }
+    }
+}
+
+pub struct Engine {
+    pub gfx: gfx::Gfx,
+    pub scene: Scene,
+    pub time_s: f64,
+    pub text_cx: text::TextContext,
+    pub fonts: FontRegistry,
+    pub text_inputs: text_input::TextInputManager,
+    #[cfg(feature = "a11y")]
+    pub a11y: Option<a11y::AccessibilityAdapter>,
+    #[cfg(feature = "layout")]
+    pub layout: layout::LayoutTree,
+    pub images: image::ImageManager,
+    pub paths: path::PathManager,
+    pub gradients: gradient::GradientManager,
+    pub log_views: log_view::LogViewManager,
+    pub filters: filter::FilterStack,
+    pub fragments: fragment::FragmentStore,
+    pub prefs: prefs::SystemPrefs,
+    pub gestures: gesture::GestureRecognizer,
+    pub hits: hit::HitRegistry,
+    pub pointer_callback: Option<extern "C" fn(u64, u8, f32, f32)>,
+    pub scrolls: scroll::ScrollManager,
+    pub scroll_edge_callback: Option<extern "C" fn(u64, u8, f32)>,
+    pub scheduler: schedule::FrameScheduler,
+    pub redraw_callback: Option<extern "C" fn(f64)>,
+    /// Refresh rate of the display showing the surface (Hz), as reported by the host
+    pub refresh_rate: f32,
+    pub refresh_rate_callback: Option<extern "C" fn(f32)>,
+    /// Window fully covered or minimized: frames are built but not rendered
+    pub occluded: bool,
+    /// Background set with mcore_set_clear_color, used in place of the
+    /// clear color each frame passes
+    pub clear_color: Option<crate::McoreRgba>,
+    pub custom_textures: custom::CustomTextureManager,
+    /// Host video players' latest frames, drawn from custom textures
+    pub videos: video::Videos,
+    pub text_stats: TextMeasurementStats,
+    /// Command buffer recording for replay diffs (mcore_replay_record_start)
+    pub recorder: Option<replay::Recorder>,
+    /// The current frame's command buffers, for mcore_scene_dump
+    pub frame_capture: replay::FrameCapture,
+    pub frames_presented: u64,
+    /// Consecutive frames that failed to render (reset on success)
+    pub render_failures: u32,
+    pub log_callback: Option<extern "C" fn(u8, *const i8)>,
+    pub watchdog: watchdog::FrameWatchdog,
+    /// Phase timings of the last rendered frame (mcore_frame_stats)
+    pub last_timings: watchdog::FrameTimings,
+    pub overlay: overlay::DebugOverlay,
+    /// Skips presenting frames identical to the last (mcore_set_damage_tracking)
+    pub damage: damage::DamageTracker,
+    /// Host clip layers open in the scene being built
+    pub layers: layers::LayerStack,
+    /// The frame as drawn before each cut: blur layers, and color filters
+    /// pushed or popped
+    pub backdrops: Vec<blur::Backdrop>,
+    /// Between mcore_begin_frame and mcore_end_frame_present
+    pub in_frame: bool,
+    /// Builds and presents queued frames (mcore_render_thread_start)
+    pub render_thread: Option<render_thread::RenderThread>,
+    /// Immediate-mode widget state (mcore_ui_*)
+    pub ui: ui::Ui,
+}
+
+impl Engine {
+    pub fn new(gfx: gfx::Gfx) -> Self {
+        Self::with_resources(gfx, SharedResources::default())
+    }
+
+    pub fn with_resources(gfx: gfx::Gfx, resources: SharedResources) -> Self {
+        let SharedResources { text_cx, fonts, images } = resources;
+        Self {
+            gfx,
+            scene: Scene::new(),
+            time_s: 0.0,
+            text_cx,
+            fonts,
+            text_inputs: text_input::TextInputManager::new(),
+            #[cfg(feature = "a11y")]
+            a11y: None,
+            #[cfg(feature = "layout")]
+            layout: layout::LayoutTree::new(),
+            images,
+            paths: path::PathManager::new(),
+            gradients: gradient::GradientManager::new(),
+            log_views: log_view::LogViewManager::new(),
+            filters: filter::FilterStack::default(),
+            fragments: fragment::FragmentStore::new(),
+            prefs: prefs::SystemPrefs::query(),
+            gestures: gesture::GestureRecognizer::new(),
+            hits: hit::HitRegistry::new(),
+            pointer_callback: None,
+            scrolls: scroll::ScrollManager::new(),
+            scroll_edge_callback: None,
+            scheduler: schedule::FrameScheduler::new(),
+            redraw_callback: None,
+            refresh_rate: DEFAULT_REFRESH_RATE,
+            refresh_rate_callback: None,
+            occluded: false,
+            clear_color: None,
+            custom_textures: custom::CustomTextureManager::new(),
+            videos: video::Videos::default(),
+            text_stats: TextMeasurementStats::default(),
+            recorder: None,
+            frame_capture: replay::FrameCapture::default(),
+            frames_presented: 0,
+            render_failures: 0,
+            log_callback: None,
+            watchdog: watchdog::FrameWatchdog::default(),
+            last_timings: watchdog::FrameTimings::default(),
+            overlay: overlay::DebugOverlay::default(),
+            damage: damage::DamageTracker::default(),
+            layers: layers::LayerStack::default(),
+            backdrops: Vec::new(),
+            in_frame: false,
+            render_thread: None,
+            ui: ui::Ui::new(),
+        }
+    }
+
+    /// Schedule frames for engine-driven animations that are still running
+    /// (visual-only animations wait while occluded and catch up on resume)
+    pub fn schedule_animations(&mut self) {
+        if !self.occluded && (self.scrolls.is_animating() || self.gfx.particles().is_active()) {
+            self.scheduler.request_at(self.time_s);
+        }
+        if let Some(deadline) = self.gestures.next_deadline() {
+            self.scheduler.request_at(deadline);
+        }
+    }
+
+    /// Cut the frame's scene (see blur.rs): what's been drawn so far is parked
+    /// in a backdrop and drawing continues in a fresh scene, composited
+    /// through the active color filter. Open clips are closed before the cut
+    /// and reopened after it.
+    pub fn cut_scene(&mut self, blur: Option<blur::BlurRegion>) {
+        for _ in self.layers.clips() {
+            self.scene.pop_layer();
+        }
+        let scene = std::mem::replace(&mut self.scene, Scene::new());
+        for clip in self.layers.clips() {
+            clip.push_to(&mut self.scene);
+        }
+        self.backdrops.push(blur::Backdrop { scene, blur, filter: self.filters.current() });
+    }
+
+    /// State for crash bundles written from places that can't lock the engine
+    pub fn crash_snapshot(&self) -> crash::Snapshot {
+        crash::Snapshot {
+            time_s: self.time_s,
+            frames_presented: self.frames_presented,
+            render_failures: self.render_failures,
+            refresh_rate: self.refresh_rate,
+            occluded: self.occluded,
+            text_measure_calls: self.text_stats.total_measure_calls,
+            text_offset_calls: self.text_stats.total_offset_calls,
+            images: self.images.len(),
+            text_inputs: self.text_inputs.len(),
+        }
+    }
+
+    /// Drawing lands in the frame scene between begin_frame and
+    /// end_frame_present, or in a fragment being recorded. Anywhere else it
+    /// would mutate the scene that was last presented. While the render
+    /// thread runs, only it draws.
+    pub fn check_drawing(&self, call: &str) -> Result<(), String> {
+        if self.render_thread.as_ref().is_some_and(|thread| !thread.is_current()) {
+            return Err(format!("{call} called while the render thread is running; queue frames with mcore_submit_frame"));
+        }
+        if self.in_frame || self.fragments.is_recording() {
+            Ok(())
+        } else {
+            Err(format!("{call} called outside a frame (before mcore_begin_frame or after mcore_end_frame_present)"))
+        }
+    }
+
+    /// Switch to another scale factor, keeping the surface's logical size.
+    /// Cached layouts were shaped at the old scale, so they're dropped.
+    pub fn set_scale(&mut self, scale: f32) {
+        if scale == self.gfx.scale() {
+            return;
+        }
+        self.gfx.set_scale(scale);
+        self.text_cx.clear_layout_cache();
+        self.damage.invalidate();
+    }
+
+    /// A newly scheduled frame time to report to the host, with its callback
+    pub fn take_redraw(&mut self) -> Option<(f64, extern "C" fn(f64))> {
+        let callback = self.redraw_callback?;
+        self.scheduler.take_notification().map(|time| (time, callback))
+    }
+}
+
+/// Invoke the redraw callback (must be called without holding the engine lock)
+pub fn fire_redraw(redraw: Option<(f64, extern "C" fn(f64))>) {
+    if let Some((time, callback)) = redraw {
+        callback(time);
+    }
+}
+
+/// Send a message to the host's log callback, or stderr without one
+/// (must be called without holding the engine lock)
+pub fn fire_log(callback: Option<extern "C" fn(u8, *const i8)>, level: LogLevel, message: &str) {
+    match callback {
+        Some(callback) => {
+            let message = std::ffi::CString::new(message.replace('\0', "")).expect("nul bytes were removed");
+            callback(level as u8, message.as_ptr());
+        }
+        None => eprintln!("mcore [{level:?}]: {message}"),
+    }
+}
+
+/// Invoke the edge callback (must be called without holding the engine lock)
+pub fn fire_scroll_edges(events: Vec<scroll::EdgeEvent>, callback: Option<extern "C" fn(u64, u8, f32)>) {
+    if let Some(callback) = callback {
+        for event in events {
+            callback(event.id, event.edge as u8, event.velocity);
+        }
+    }
+}
+
+/// Host callbacks a frame call collects while the engine is locked, fired
+/// once it's unlocked
+#[derive(Default)]
+pub struct Deferred {
+    pub scroll_edges: Vec<scroll::EdgeEvent>,
+    pub scroll_edge_callback: Option<extern "C" fn(u64, u8, f32)>,
+    pub redraw: Option<(f64, extern "C" fn(f64))>,
+    pub log_callback: Option<extern "C" fn(u8, *const i8)>,
+    pub logs: Vec<(LogLevel, String)>,
+    /// Why a crash bundle should be written
+    pub crash: Option<String>,
+}
+
+impl Deferred {
+    /// Must be called without holding the engine lock
+    pub fn fire(self) {
+        fire_scroll_edges(self.scroll_edges, self.scroll_edge_callback);
+        fire_redraw(self.redraw);
+        for (level, message) in &self.logs {
+            fire_log(self.log_callback, *level, message);
+        }
+        if let Some(report) = self.crash.and_then(|reason| crash::write_bundle(&reason)) {
+            report.fire();
+        }
+    }
+}
@@ -0,0 +1,162 @@
+/// Color filter module
+///
+/// Vello has no per-layer color filter, so pushing or popping a filter cuts
+/// the frame's scene (Engine::cut_scene), and the content drawn while a
+/// filter is active is rendered on its own and blended over the frame
+/// through the filter's color matrix (see blur.rs).
+
+#[cfg(test)]
+use peniko::Color;
+
+/// 4x5 row-major color matrix (same layout as SVG feColorMatrix / Android ColorMatrix)
+/// Each output channel is `m[0]*r + m[1]*g + m[2]*b + m[3]*a + m[4]`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColorMatrix(pub [f32; 20]);
+
+impl ColorMatrix {
+    pub const IDENTITY: Self = Self([
+        1.0, 0.0, 0.0, 0.0, 0.0, //
+        0.0, 1.0, 0.0, 0.0, 0.0, //
+        0.0, 0.0, 1.0, 0.0, 0.0, //
+        0.0, 0.0, 0.0, 1.0, 0.0, //
+    ]);
+
+    /// Desaturate by `amount` (0.0 = unchanged, 1.0 = fully grayscale)
+    /// Uses the Rec. 709 luma weights, matching CSS `grayscale()`
+    pub fn grayscale(amount: f32) -> Self {
+        let a = 1.0 - amount.clamp(0.0, 1.0);
+        Self([
+            0.2126 + 0.7874 * a, 0.7152 - 0.7152 * a, 0.0722 - 0.0722 * a, 0.0, 0.0, //
+            0.2126 - 0.2126 * a, 0.7152 + 0.2848 * a, 0.0722 - 0.0722 * a, 0.0, 0.0, //
+            0.2126 - 0.2126 * a, 0.7152 - 0.7152 * a, 0.0722 + 0.9278 * a, 0.0, 0.0, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ])
+    }
+
+    /// Apply the matrix to a color, clamping the result to [0, 1], as the
+    /// composite pass does for each pixel
+    #[cfg(test)]
+    pub fn apply(&self, color: Color) -> Color {
+        let [r, g, b, a] = color.components;
+        let m = &self.0;
+        let row = |i: usize| (m[i] * r + m[i + 1] * g + m[i + 2] * b + m[i + 3] * a + m[i + 4]).clamp(0.0, 1.0);
+        Color::new([row(0), row(5), row(10), row(15)])
+    }
+
+    /// The matrix as the composite shader takes it: each output channel's
+    /// weights for r, g, b and a, then the four offsets
+    pub fn rows(&self) -> [[f32; 4]; 5] {
+        let m = &self.0;
+        let row = |i: usize| [m[i], m[i + 1], m[i + 2], m[i + 3]];
+        [row(0), row(5), row(10), row(15), [m[4], m[9], m[14], m[19]]]
+    }
+
+    /// Compose two filters: the result applies `self` first, then `outer`
+    pub fn then(&self, outer: &ColorMatrix) -> ColorMatrix {
+        let a = &self.0;
+        let b = &outer.0;
+        let mut out = [0.0f32; 20];
+        for row in 0..4 {
+            for col in 0..5 {
+                let mut v = 0.0;
+                for k in 0..4 {
+                    v += b[row * 5 + k] * a[k * 5 + col];
+                }
+                if col == 4 {
+                    v += b[row * 5 + 4];
+                }
+                out[row * 5 + col] = v;
+            }
+        }
+        ColorMatrix(out)
+    }
+}
+
+/// Stack of active color filters; each entry is already composed with its parents
+#[derive(Default)]
+pub struct FilterStack {
+    stack: Vec<ColorMatrix>,
+}
+
+impl FilterStack {
+    /// Push a filter nested inside the currently active ones
+    pub fn push(&mut self, matrix: ColorMatrix) {
+        let composed = match self.stack.last() {
+            Some(parent) => matrix.then(parent),
+            None => matrix,
+        };
+        self.stack.push(composed);
+    }
+
+    /// Pop the innermost filter, returning false if the stack was empty
+    pub fn pop(&mut self) -> bool {
+        self.stack.pop().is_some()
+    }
+
+    /// The effective filter for content drawn right now, if any
+    pub fn current(&self) -> Option<ColorMatrix> {
+        self.stack.last().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: Color, b: [f32; 4]) -> bool {
+        a.components.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-4)
+    }
+
+    #[test]
+    fn test_identity() {
+        let c = Color::new([0.2, 0.4, 0.6, 0.8]);
+        assert!(approx(ColorMatrix::IDENTITY.apply(c), [0.2, 0.4, 0.6, 0.8]));
+    }
+
+    #[test]
+    fn test_grayscale_full() {
+        let out = ColorMatrix::grayscale(1.0).apply(Color::new([1.0, 0.0, 0.0, 1.0]));
+        assert!(approx(out, [0.2126, 0.2126, 0.2126, 1.0]));
+    }
+
+    #[test]
+    fn test_grayscale_zero_is_identity() {
+        let c = Color::new([0.1, 0.7, 0.3, 0.5]);
+        assert!(approx(ColorMatrix::grayscale(0.0).apply(c), [0.1, 0.7, 0.3, 0.5]));
+    }
+
+    #[test]
+    fn test_rows_match_apply() {
+        let mut m = ColorMatrix::grayscale(0.5);
+        m.0[4] = 0.1;
+        m.0[18] = 0.5;
+        let rows = m.rows();
+        let c = [0.3f32, 0.6, 0.9, 0.8];
+        let dot = |row: [f32; 4]| row.iter().zip(c).map(|(w, v)| w * v).sum::<f32>();
+        let shader = [0, 1, 2, 3].map(|i| (dot(rows[i]) + rows[4][i]).clamp(0.0, 1.0));
+        assert!(approx(m.apply(Color::new(c)), shader));
+    }
+
+    #[test]
+    fn test_stack_composes() {
+        let mut stack = FilterStack::default();
+        assert!(stack.current().is_none());
+
+        // Outer filter halves alpha, inner is grayscale
+        let mut half_alpha = ColorMatrix::IDENTITY;
+        half_alpha.0[18] = 0.5;
+        stack.push(half_alpha);
+        stack.push(ColorMatrix::grayscale(1.0));
+
+        let out = stack.current().unwrap().apply(Color::new([1.0, 0.0, 0.0, 1.0]));
+        assert!(approx(out, [0.2126, 0.2126, 0.2126, 0.5]));
+
+        assert!(stack.pop());
+        assert!(stack.pop());
+        assert!(!stack.pop());
+    }
+}
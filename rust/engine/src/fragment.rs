@@ -0,0 +1,96 @@
+/// Retained scene fragments
+///
+/// A fragment is a sub-scene recorded once and replayed (appended) into later
+/// frames with a transform, so static chrome doesn't need to be re-encoded.
+
+use std::collections::HashMap;
+use vello::Scene;
+
+pub struct FragmentStore {
+    fragments: HashMap<i32, Scene>,
+    next_id: i32,
+    /// The frame scene, parked while a fragment is being recorded
+    stashed: Option<Scene>,
+}
+
+impl FragmentStore {
+    pub fn new() -> Self {
+        Self {
+            fragments: HashMap::new(),
+            next_id: 0,
+            stashed: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.stashed.is_some()
+    }
+
+    /// Start recording: drawing calls now land in a fresh scene
+    pub fn begin(&mut self, scene: &mut Scene) -> Result<(), String> {
+        if self.is_recording() {
+            return Err("Fragment recording already in progress".to_string());
+        }
+        self.stashed = Some(std::mem::replace(scene, Scene::new()));
+        Ok(())
+    }
+
+    /// Finish recording, restore the frame scene and return the fragment ID
+    pub fn end(&mut self, scene: &mut Scene) -> Result<i32, String> {
+        let Some(frame_scene) = self.stashed.take() else {
+            return Err("No fragment recording in progress".to_string());
+        };
+        let recorded = std::mem::replace(scene, frame_scene);
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.fragments.insert(id, recorded);
+        Ok(id)
+    }
+
+    pub fn get(&self, id: i32) -> Option<&Scene> {
+        self.fragments.get(&id)
+    }
+
+    /// Free a fragment, returning whether it existed
+    pub fn remove(&mut self, id: i32) -> bool {
+        self.fragments.remove(&id).is_some()
+    }
+}
+
+impl Default for FragmentStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_remove() {
+        let mut store = FragmentStore::new();
+        let mut scene = Scene::new();
+
+        store.begin(&mut scene).unwrap();
+        assert!(store.is_recording());
+        let id = store.end(&mut scene).unwrap();
+        assert!(!store.is_recording());
+
+        assert!(store.get(id).is_some());
+        assert!(store.remove(id));
+        assert!(store.get(id).is_none());
+    }
+
+    #[test]
+    fn test_unbalanced_calls() {
+        let mut store = FragmentStore::new();
+        let mut scene = Scene::new();
+
+        assert!(store.end(&mut scene).is_err());
+        store.begin(&mut scene).unwrap();
+        assert!(store.begin(&mut scene).is_err());
+        assert!(store.end(&mut scene).is_ok());
+    }
+}
@@ -0,0 +1,310 @@
+//! Fuzz harness for the C surface: entry points called with null pointers,
+//! bad sizes and randomly corrupted command streams, the way a buggy host
+//! would, asserting that every call fails with an error instead of panicking
+//! or taking the process down.
+//!
+//! The context-free parts run with the other tests. Driving a live context
+//! needs a GPU adapter, so that part only runs on request:
+//! `cargo test fuzz -- --ignored`
+
+use std::ffi::CStr;
+use std::ptr::{null, null_mut};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::engine::Engine;
+use crate::gfx::Gfx;
+use crate::*;
+
+/// Deterministic xorshift64*, so a failing case reproduces from its seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn float(&mut self, min: f32, max: f32) -> f32 {
+        min + (self.next() >> 40) as f32 / (1u64 << 24) as f32 * (max - min)
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+/// Run one call, failing the test if it panicked instead of returning an error
+fn call<R>(name: &str, f: impl FnOnce() -> R) -> R {
+    mcore_clear_error();
+    let result = f();
+    if mcore_last_error_code() == McoreErrorCode::Panic {
+        let message = unsafe { CStr::from_ptr(mcore_last_error()) }.to_string_lossy().into_owned();
+        panic!("{name}: {message}");
+    }
+    result
+}
+
+/// The call must fail with MCORE_ERROR_INVALID_ARG
+macro_rules! rejects {
+    ($call:expr) => {{
+        let _ = call(stringify!($call), || $call);
+        assert_eq!(mcore_last_error_code(), McoreErrorCode::InvalidArg, "{}", stringify!($call));
+    }};
+}
+
+fn record(stream: &mut Vec<u8>, tag: u16, payload: &[u8]) {
+    stream.extend_from_slice(&tag.to_ne_bytes());
+    stream.extend_from_slice(&0u16.to_ne_bytes());
+    stream.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+    stream.extend_from_slice(payload);
+    stream.resize(stream.len().next_multiple_of(4), 0);
+}
+
+/// A well-formed stream of random records. Payload fields are in-range
+/// floats or small integers, so the commands are drawable.
+fn random_stream(rng: &mut Rng) -> Vec<u8> {
+    let mut stream = Vec::new();
+    for _ in 0..rng.below(12) {
+        let tag = 1 + rng.below(McoreCmdTag::Video as usize + 1) as u16;
+        let mut payload: Vec<u8> = (0..rng.below(16))
+            .flat_map(|_| match rng.below(2) {
+                0 => rng.float(-500.0, 500.0).to_ne_bytes(),
+                _ => (rng.below(8) as u32).to_ne_bytes(),
+            })
+            .collect();
+        if tag == McoreCmdTag::Text as u16 {
+            let font_size = rng.float(1.0, 64.0);
+            let text = McoreCmdText { font_size, text_len: 5, ..bytemuck::Zeroable::zeroed() };
+            payload = bytemuck::bytes_of(&text).to_vec();
+            payload.extend_from_slice(b"fuzz!");
+        }
+        record(&mut stream, tag, &payload);
+    }
+    stream
+}
+
+/// Flip, drop, insert or overwrite bytes
+fn mutate(rng: &mut Rng, stream: &mut Vec<u8>) {
+    for _ in 0..1 + rng.below(4) {
+        match rng.below(4) {
+            0 if !stream.is_empty() => {
+                let i = rng.below(stream.len());
+                stream[i] ^= 1 << rng.below(8);
+            }
+            1 => stream.truncate(rng.below(stream.len() + 1)),
+            2 => {
+                let i = rng.below(stream.len() + 1);
+                let len = 1 + rng.below(16);
+                let bytes = rng.bytes(len);
+                stream.splice(i..i, bytes);
+            }
+            _ if stream.len() >= 8 => {
+                // A header's size field, pointing anywhere
+                let i = rng.below(stream.len() / 4 - 1) * 4 + 4;
+                stream[i..i + 4].copy_from_slice(&(rng.next() as u32).to_ne_bytes());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn null_pointers_are_rejected() {
+    let ctx: *mut McoreContext = null_mut();
+    let color = McoreColor { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+    let mut out = color;
+    let clear = McoreRgba { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+
+    rejects!(mcore_create(null()));
+    rejects!(mcore_engine_create(null()));
+    rejects!(mcore_engine_create_context(null_mut(), null()));
+    rejects!(mcore_render_mode(ctx));
+    rejects!(mcore_gpu_info(ctx, null_mut()));
+    rejects!(mcore_read_pixels(ctx, null_mut(), 16));
+    rejects!(mcore_resize(ctx, null()));
+    rejects!(mcore_begin_frame(ctx, 0.0));
+    rejects!(mcore_rect_rounded(ctx, null()));
+    rejects!(mcore_font_register(ctx, null()));
+    rejects!(mcore_font_load_system(ctx, null(), 400.0, 0));
+    rejects!(mcore_text_layout(ctx, null(), null_mut()));
+    rejects!(mcore_text_layout_create(ctx, null()));
+    rejects!(mcore_text_layout_metrics(null(), null_mut()));
+    rejects!(mcore_text_block_create(ctx, null(), 0, null(), 200.0));
+    rejects!(mcore_text_block_size(null(), null_mut()));
+    rejects!(mcore_text_block_paragraph_rect(null(), 0, null_mut()));
+    rejects!(mcore_text_block_hit_test(null(), 0.0, 0.0, null_mut()));
+    rejects!(mcore_text_block_draw(ctx, null(), 0.0, 0.0));
+    rejects!(mcore_code_measure(ctx, null(), null_mut()));
+    rejects!(mcore_code_draw(ctx, null(), null(), 0, 0.0, 0.0));
+    rejects!(mcore_text_window_create(null()));
+    rejects!(mcore_text_window_line_range(null(), 0, null_mut(), null_mut()));
+    rejects!(mcore_text_window_set_lines(ctx, null_mut(), 0, 10, null_mut()));
+    rejects!(mcore_text_window_hit_test(null(), 0.0, 0.0));
+    rejects!(mcore_text_window_draw(ctx, null(), 0.0, 0.0, clear));
+    rejects!(mcore_measure_text(ctx, null(), 14.0, 0.0, 0, null_mut()));
+    rejects!(mcore_text_draw(ctx, null(), 0.0, 0.0, clear));
+    rejects!(mcore_push_clip_rect(ctx, 0.0, 0.0, 1.0, 1.0));
+    rejects!(mcore_path_create(ctx, null(), 4, null(), 4));
+    rejects!(mcore_pop_clip(ctx));
+    rejects!(mcore_push_color_filter(ctx, null()));
+    rejects!(mcore_render_commands(ctx, null(), 3));
+    rejects!(mcore_render_commands_v2(ctx, null(), 64, command_stream::VERSION));
+    rejects!(mcore_gradient_create(ctx, null()));
+    rejects!(mcore_log_view_append(ctx, 0, null(), clear));
+    rejects!(mcore_log_view_clear(ctx, 0));
+    rejects!(mcore_fragment_begin(ctx));
+    rejects!(mcore_end_frame_present(ctx, clear));
+    rejects!(mcore_set_clear_color(ctx, clear));
+    rejects!(mcore_submit_frame(ctx, 0.0, null(), 64, command_stream::VERSION, clear));
+    rejects!(mcore_scene_dump(ctx, null()));
+    rejects!(mcore_set_log_callback(ctx, None));
+    rejects!(mcore_frame_stats(ctx, null_mut()));
+    rejects!(mcore_set_aa_mode(ctx, 0));
+    rejects!(mcore_get_aa_mode(ctx));
+    rejects!(mcore_set_text_rendering(ctx, null()));
+    rejects!(mcore_get_text_rendering(ctx, null_mut()));
+    rejects!(mcore_set_color_space(ctx, McoreColorSpace::DisplayP3 as u8));
+    rejects!(mcore_get_color_space(ctx));
+    rejects!(mcore_text_input_event(ctx, 1, null()));
+    rejects!(mcore_text_input_get(ctx, 1, null_mut(), 16));
+    rejects!(mcore_text_input_set(ctx, 1, null()));
+    rejects!(mcore_text_input_get_selection(ctx, 1, null_mut(), null_mut()));
+    rejects!(mcore_text_input_get_scroll(ctx, 1, null_mut(), null_mut()));
+    rejects!(mcore_ime_get_preedit(ctx, 1, null_mut(), 16, null_mut()));
+    rejects!(mcore_text_input_get_display(ctx, 1, null_mut(), 16, null_mut(), null_mut(), null_mut()));
+    rejects!(mcore_hit_region(ctx, 1, 0.0, 0.0, 1.0, 1.0, 0));
+    rejects!(mcore_scroll_get(ctx, 1, null_mut()));
+    rejects!(mcore_image_register(ctx, null()));
+    rejects!(mcore_image_draw(ctx, 0, null()));
+    rejects!(mcore_image_draw_rect(ctx, null()));
+    rejects!(mcore_image_load_file(ctx, null()));
+    rejects!(mcore_image_register_async(ctx, null(), 16, None, null_mut()));
+    rejects!(mcore_image_load_thumbnail(ctx, null(), 512));
+    rejects!(mcore_image_set_budget(ctx, 1 << 20));
+    rejects!(mcore_image_cache_stats(ctx, null_mut()));
+    rejects!(mcore_memory_pressure(ctx, 1));
+    rejects!(mcore_video_create(ctx));
+    rejects!(mcore_video_frame_submit(ctx, 0, null()));
+    rejects!(mcore_video_draw(ctx, 0, 0.0, 0.0, 10.0, 10.0, 0));
+    rejects!(mcore_clipboard_set_text(null()));
+
+    rejects!(mcore_color_parse(null(), 3, &mut out));
+    rejects!(mcore_color_parse(b"red".as_ptr(), 3, null_mut()));
+    rejects!(mcore_color_lerp(null(), &color, 0.5, &mut out));
+    rejects!(mcore_color_convert(1, 0, &color, null_mut()));
+    rejects!(mcore_color_convert(2, 0, &color, &mut out));
+    rejects!(mcore_color_from_rgba8(1, 2, 3, 4, null_mut()));
+
+    // Destroying null is a no-op, like free
+    call("mcore_destroy", || mcore_destroy(ctx));
+    call("mcore_text_layout_destroy", || mcore_text_layout_destroy(null_mut()));
+    call("mcore_text_block_destroy", || mcore_text_block_destroy(null_mut()));
+    call("mcore_text_window_destroy", || mcore_text_window_destroy(null_mut()));
+    assert_eq!(mcore_last_error_code(), McoreErrorCode::None);
+}
+
+#[test]
+fn corrupted_streams_are_rejected_whole() {
+    let mut rng = Rng(0x5eed_0001);
+    for _ in 0..5000 {
+        let mut stream = random_stream(&mut rng);
+        assert!(command_stream::parse(&stream).is_ok());
+        mutate(&mut rng, &mut stream);
+        let _ = command_stream::parse(&stream);
+
+        let len = rng.below(96);
+        let garbage = rng.bytes(len);
+        let _ = command_stream::parse(&garbage);
+    }
+}
+
+#[test]
+fn color_parse_takes_any_bytes() {
+    let mut rng = Rng(0x5eed_0002);
+    let seeds: [&[u8]; 4] = [b"oklch(70% 0.1 200)", b"#ff8800", b"rgba(1, 2, 3, 0.5)", b"rebeccapurple"];
+    let mut out = McoreColor { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+    for _ in 0..5000 {
+        let seed = rng.below(seeds.len());
+        let mut bytes = seeds[seed].to_vec();
+        mutate(&mut rng, &mut bytes);
+        let parsed = call("mcore_color_parse", || mcore_color_parse(bytes.as_ptr(), bytes.len(), &mut out));
+        assert!(parsed <= 1);
+    }
+}
+
+#[test]
+#[ignore = "needs a GPU adapter"]
+fn malformed_calls_on_a_live_context() {
+    let gfx = match pollster::block_on(Gfx::new_headless(160, 120, 2.0, Default::default())) {
+        Ok(gfx) => gfx,
+        Err(e) => {
+            eprintln!("skipping fuzz test: {e}");
+            return;
+        }
+    };
+    let ctx = Box::into_raw(Box::new(McoreContext(Arc::new(Mutex::new(Engine::new(gfx))))));
+    crate::trace::context_created(ctx as *const ());
+    let clear = McoreRgba { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+    let mut rng = Rng(0x5eed_0003);
+
+    // Out-of-order frame calls
+    call("mcore_end_frame_present", || mcore_end_frame_present(ctx, clear));
+    call("mcore_pop_clip", || mcore_pop_clip(ctx));
+    call("mcore_fragment_end", || mcore_fragment_end(ctx));
+
+    for frame in 0..500 {
+        call("mcore_begin_frame", || mcore_begin_frame(ctx, frame as f64 / 60.0));
+
+        let mut stream = random_stream(&mut rng);
+        if rng.below(2) == 0 {
+            mutate(&mut rng, &mut stream);
+        }
+        let status = call("mcore_render_commands_v2", || {
+            mcore_render_commands_v2(ctx, stream.as_ptr(), stream.len(), command_stream::VERSION)
+        });
+        if !matches!(status, McoreStatus::Ok) {
+            assert_eq!(mcore_last_error_code(), McoreErrorCode::InvalidArg);
+        }
+        let version = rng.next() as u32;
+        call("mcore_render_commands_v2", || mcore_render_commands_v2(ctx, stream.as_ptr(), stream.len(), version));
+
+        // IDs and offsets the host never got from the engine
+        let id = rng.next() as i32 % 64;
+        let offset = rng.next() as i32 % 256;
+        let transform = McoreImageTransform { x: 0.0, y: 0.0, scale: rng.float(-4.0, 4.0), rotation_deg: 0.0 };
+        call("mcore_image_draw", || mcore_image_draw(ctx, id, &transform));
+        call("mcore_image_release", || mcore_image_release(ctx, id));
+        call("mcore_fragment_draw", || mcore_fragment_draw(ctx, id, &transform));
+        call("mcore_push_clip_path", || mcore_push_clip_path(ctx, id, 0.0, 0.0));
+        call("mcore_log_view_draw", || mcore_log_view_draw(ctx, id, 0.0, 0.0, 100.0, 100.0, rng.float(-1e4, 1e4)));
+        call("mcore_gradient_destroy", || mcore_gradient_destroy(ctx, id));
+        call("mcore_text_input_set_cursor_pos", || mcore_text_input_set_cursor_pos(ctx, 1, offset, 1));
+        call("mcore_text_input_start_selection", || mcore_text_input_start_selection(ctx, 1, offset));
+        call("mcore_custom_texture_draw", || mcore_custom_texture_draw(ctx, id, 0.0, 0.0, 10.0, 10.0));
+        call("mcore_video_draw", || mcore_video_draw(ctx, id, 0.0, 0.0, 10.0, 10.0, rng.below(4) as u32));
+        call("mcore_set_aa_mode", || mcore_set_aa_mode(ctx, rng.below(256) as u8));
+        call("mcore_pop_color_filter", || mcore_pop_color_filter(ctx));
+        call("mcore_hit_pop_transform", || mcore_hit_pop_transform(ctx));
+
+        let verb_count = 1 + rng.below(8);
+        let verbs = rng.bytes(verb_count);
+        let point_count = rng.below(8);
+        let points: Vec<f32> = (0..2 * point_count).map(|_| rng.float(-100.0, 100.0)).collect();
+        let path = call("mcore_path_create", || {
+            mcore_path_create(ctx, verbs.as_ptr(), verbs.len() as i32, points.as_ptr(), (points.len() / 2) as i32)
+        });
+        call("mcore_path_destroy", || mcore_path_destroy(ctx, path));
+
+        call("mcore_end_frame_present", || mcore_end_frame_present(ctx, clear));
+    }
+
+    mcore_destroy(ctx);
+}
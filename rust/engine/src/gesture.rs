@@ -0,0 +1,271 @@
+/// Gesture recognition
+///
+/// The host feeds raw pointer events (logical pixels, seconds) and the
+/// recognizer emits tap, double-tap, long-press and pan gestures, so every host
+/// shares the same thresholds and timing.
+
+use std::collections::VecDeque;
+
+/// Movement (logical px) before a press turns into a pan
+pub const TOUCH_SLOP: f32 = 4.0;
+/// Max time between taps for a double-tap
+pub const DOUBLE_TAP_INTERVAL: f64 = 0.3;
+/// Max distance between taps for a double-tap
+pub const DOUBLE_TAP_SLOP: f32 = 10.0;
+/// Hold time for a long-press
+pub const LONG_PRESS_DURATION: f64 = 0.5;
+/// Window of recent samples used to estimate pan velocity
+const VELOCITY_WINDOW: f64 = 0.1;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PointerPhase {
+    Down,
+    Move,
+    Up,
+    Cancel,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GestureKind {
+    Tap = 0,
+    DoubleTap = 1,
+    LongPress = 2,
+    PanBegin = 3,
+    PanUpdate = 4,
+    PanEnd = 5,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Gesture {
+    pub kind: GestureKind,
+    /// Current pointer position
+    pub x: f32,
+    pub y: f32,
+    /// Translation since the press started (pans only)
+    pub dx: f32,
+    pub dy: f32,
+    /// Velocity in logical px/s (pans only)
+    pub vx: f32,
+    pub vy: f32,
+}
+
+struct Press {
+    start: (f32, f32),
+    start_time: f64,
+    samples: VecDeque<(f32, f32, f64)>,
+    panning: bool,
+    long_pressed: bool,
+}
+
+impl Press {
+    fn velocity(&self) -> (f32, f32) {
+        let (Some(first), Some(last)) = (self.samples.front(), self.samples.back()) else {
+            return (0.0, 0.0);
+        };
+        let dt = (last.2 - first.2) as f32;
+        if dt <= 0.0 {
+            return (0.0, 0.0);
+        }
+        ((last.0 - first.0) / dt, (last.1 - first.1) / dt)
+    }
+
+    fn push_sample(&mut self, x: f32, y: f32, time: f64) {
+        self.samples.push_back((x, y, time));
+        while let Some(front) = self.samples.front() {
+            if time - front.2 > VELOCITY_WINDOW && self.samples.len() > 2 {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct GestureRecognizer {
+    press: Option<Press>,
+    last_tap: Option<(f32, f32, f64)>,
+    pending: VecDeque<Gesture>,
+}
+
+impl GestureRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn emit(&mut self, kind: GestureKind, x: f32, y: f32, delta: (f32, f32), velocity: (f32, f32)) {
+        self.pending.push_back(Gesture {
+            kind,
+            x,
+            y,
+            dx: delta.0,
+            dy: delta.1,
+            vx: velocity.0,
+            vy: velocity.1,
+        });
+    }
+
+    /// Feed a raw pointer event
+    pub fn pointer_event(&mut self, phase: PointerPhase, x: f32, y: f32, time: f64) {
+        match phase {
+            PointerPhase::Down => {
+                let mut press = Press {
+                    start: (x, y),
+                    start_time: time,
+                    samples: VecDeque::new(),
+                    panning: false,
+                    long_pressed: false,
+                };
+                press.push_sample(x, y, time);
+                self.press = Some(press);
+            }
+            PointerPhase::Move => {
+                let Some(mut press) = self.press.take() else {
+                    return;
+                };
+                press.push_sample(x, y, time);
+                let delta = (x - press.start.0, y - press.start.1);
+
+                if !press.panning && !press.long_pressed && delta.0.hypot(delta.1) > TOUCH_SLOP {
+                    press.panning = true;
+                    self.emit(GestureKind::PanBegin, x, y, delta, press.velocity());
+                } else if press.panning {
+                    self.emit(GestureKind::PanUpdate, x, y, delta, press.velocity());
+                }
+                self.press = Some(press);
+            }
+            PointerPhase::Up => {
+                let Some(mut press) = self.press.take() else {
+                    return;
+                };
+                press.push_sample(x, y, time);
+                let delta = (x - press.start.0, y - press.start.1);
+
+                if press.panning {
+                    self.emit(GestureKind::PanEnd, x, y, delta, press.velocity());
+                } else if !press.long_pressed {
+                    let is_double = self.last_tap.is_some_and(|(tx, ty, tt)| {
+                        time - tt <= DOUBLE_TAP_INTERVAL && (x - tx).hypot(y - ty) <= DOUBLE_TAP_SLOP
+                    });
+                    if is_double {
+                        self.emit(GestureKind::DoubleTap, x, y, (0.0, 0.0), (0.0, 0.0));
+                        self.last_tap = None;
+                    } else {
+                        self.emit(GestureKind::Tap, x, y, (0.0, 0.0), (0.0, 0.0));
+                        self.last_tap = Some((x, y, time));
+                    }
+                }
+            }
+            PointerPhase::Cancel => {
+                if let Some(press) = self.press.take() {
+                    if press.panning {
+                        let delta = (x - press.start.0, y - press.start.1);
+                        self.emit(GestureKind::PanEnd, x, y, delta, (0.0, 0.0));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Advance time without pointer movement (fires long-press)
+    pub fn tick(&mut self, time: f64) {
+        let fire = match &self.press {
+            Some(press) => !press.panning && !press.long_pressed && time - press.start_time >= LONG_PRESS_DURATION,
+            None => false,
+        };
+        if fire {
+            let press = self.press.as_mut().unwrap();
+            press.long_pressed = true;
+            let (x, y, _) = *press.samples.back().unwrap();
+            self.emit(GestureKind::LongPress, x, y, (0.0, 0.0), (0.0, 0.0));
+        }
+    }
+
+    /// Time at which a held press will turn into a long-press, if one is pending
+    pub fn next_deadline(&self) -> Option<f64> {
+        self.press
+            .as_ref()
+            .filter(|p| !p.panning && !p.long_pressed)
+            .map(|p| p.start_time + LONG_PRESS_DURATION)
+    }
+
+    /// Pop the next recognized gesture
+    pub fn next(&mut self) -> Option<Gesture> {
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tap() {
+        let mut r = GestureRecognizer::new();
+        r.pointer_event(PointerPhase::Down, 10.0, 10.0, 0.0);
+        r.pointer_event(PointerPhase::Up, 11.0, 10.0, 0.1);
+        assert_eq!(r.next().unwrap().kind, GestureKind::Tap);
+        assert!(r.next().is_none());
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let mut r = GestureRecognizer::new();
+        r.pointer_event(PointerPhase::Down, 10.0, 10.0, 0.0);
+        r.pointer_event(PointerPhase::Up, 10.0, 10.0, 0.05);
+        r.pointer_event(PointerPhase::Down, 12.0, 10.0, 0.2);
+        r.pointer_event(PointerPhase::Up, 12.0, 10.0, 0.25);
+        assert_eq!(r.next().unwrap().kind, GestureKind::Tap);
+        assert_eq!(r.next().unwrap().kind, GestureKind::DoubleTap);
+    }
+
+    #[test]
+    fn test_slow_second_tap_is_single() {
+        let mut r = GestureRecognizer::new();
+        r.pointer_event(PointerPhase::Down, 10.0, 10.0, 0.0);
+        r.pointer_event(PointerPhase::Up, 10.0, 10.0, 0.05);
+        r.pointer_event(PointerPhase::Down, 10.0, 10.0, 1.0);
+        r.pointer_event(PointerPhase::Up, 10.0, 10.0, 1.05);
+        assert_eq!(r.next().unwrap().kind, GestureKind::Tap);
+        assert_eq!(r.next().unwrap().kind, GestureKind::Tap);
+    }
+
+    #[test]
+    fn test_long_press_suppresses_tap() {
+        let mut r = GestureRecognizer::new();
+        r.pointer_event(PointerPhase::Down, 10.0, 10.0, 0.0);
+        r.tick(0.3);
+        assert!(r.next().is_none());
+        r.tick(0.6);
+        assert_eq!(r.next().unwrap().kind, GestureKind::LongPress);
+        r.pointer_event(PointerPhase::Up, 10.0, 10.0, 0.7);
+        assert!(r.next().is_none());
+    }
+
+    #[test]
+    fn test_long_press_deadline() {
+        let mut r = GestureRecognizer::new();
+        assert_eq!(r.next_deadline(), None);
+        r.pointer_event(PointerPhase::Down, 10.0, 10.0, 1.0);
+        assert_eq!(r.next_deadline(), Some(1.0 + LONG_PRESS_DURATION));
+        r.pointer_event(PointerPhase::Up, 10.0, 10.0, 1.1);
+        assert_eq!(r.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_pan_with_velocity() {
+        let mut r = GestureRecognizer::new();
+        r.pointer_event(PointerPhase::Down, 0.0, 0.0, 0.0);
+        r.pointer_event(PointerPhase::Move, 10.0, 0.0, 0.01);
+        r.pointer_event(PointerPhase::Move, 20.0, 0.0, 0.02);
+        r.pointer_event(PointerPhase::Up, 30.0, 0.0, 0.03);
+
+        assert_eq!(r.next().unwrap().kind, GestureKind::PanBegin);
+        assert_eq!(r.next().unwrap().kind, GestureKind::PanUpdate);
+        let end = r.next().unwrap();
+        assert_eq!(end.kind, GestureKind::PanEnd);
+        assert_eq!(end.dx, 30.0);
+        assert!((end.vx - 1000.0).abs() < 1.0);
+        assert_eq!(end.vy, 0.0);
+    }
+}
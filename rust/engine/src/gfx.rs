@@ -4,8 +4,14 @@ use peniko::Color;
 use raw_window_handle::{AppKitDisplayHandle, AppKitWindowHandle, RawDisplayHandle, RawWindowHandle};
 use std::ffi::c_void;
 use std::ptr::NonNull;
+use std::sync::Arc;
 use vello::{AaConfig, AaSupport, RenderParams, Renderer, RendererOptions, Scene};
 
+use crate::blur::{Backdrop, BackdropBlur};
+use crate::color_space::{self, ColorSpace, MetalLayer};
+use crate::gpu_timer::GpuTimer;
+use crate::particles::{ParticleLayer, ParticleSystem};
+
 #[derive(Debug, thiserror::Error)]
 pub enum GfxError {
     #[error("wgpu error: {0}")]
@@ -14,6 +20,10 @@ pub enum GfxError {
     InvalidSurface,
     #[error("vello error: {0}")]
     Vello(String),
+    #[error("surface lost: {0}")]
+    SurfaceLost(String),
+    #[error("out of GPU memory")]
+    OutOfMemory,
 }
 
 #[repr(C)]
@@ -26,23 +36,193 @@ pub struct MacSurface {
     pub height_px: i32,
 }
 
-pub struct Gfx {
+/// Which GPU to render with, and how
+#[derive(Copy, Clone, Debug)]
+pub struct GpuOptions {
+    pub power: wgpu::PowerPreference,
+    /// Index into `enumerate_adapters`, or None to pick by `power`
+    pub index: Option<usize>,
+    /// Fall back to a software adapter when there's no usable GPU
+    pub software_fallback: bool,
+    /// Run Vello's pipeline stages on the CPU even on a hardware GPU
+    pub cpu_pipeline: bool,
+}
+
+impl Default for GpuOptions {
+    fn default() -> Self {
+        Self {
+            power: wgpu::PowerPreference::HighPerformance,
+            index: None,
+            software_fallback: false,
+            cpu_pipeline: false,
+        }
+    }
+}
+
+/// How frames are rasterized
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Vello's compute pipeline on a hardware GPU
+    Gpu,
+    /// Vello's pipeline stages on the CPU, fine rasterization on a hardware GPU
+    CpuPipeline,
+    /// Everything on the CPU: a software adapter (llvmpipe, WARP, SwiftShader)
+    /// with Vello's CPU pipeline
+    Software,
+}
+
+/// The adapters wgpu can see, in the order GpuOptions::index refers to
+pub fn enumerate_adapters() -> Vec<wgpu::AdapterInfo> {
+    wgpu::Instance::default().enumerate_adapters(wgpu::Backends::all()).iter().map(|a| a.get_info()).collect()
+}
+
+/// Uniforms for the blit pass (must match `BlitParams` in blit.wgsl)
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct BlitParams {
+    dither: u32,
+    decode_srgb: u32,
+    _pad: [u32; 2],
+}
+
+/// A device and Vello's pipelines. Clones share them, so contexts for several
+/// windows can render with one device (mcore_engine_create).
+#[derive(Clone)]
+pub struct SharedGpu {
     instance: wgpu::Instance,
-    surface: wgpu::Surface<'static>,
     adapter: wgpu::Adapter,
     device: wgpu::Device,
     queue: wgpu::Queue,
-    config: wgpu::SurfaceConfiguration,
+    mode: RenderMode,
+    renderer: Arc<parking_lot::Mutex<SharedRenderer>>,
+    /// Set by wgpu's device-lost callback (reason and message)
+    lost: Arc<parking_lot::Mutex<Option<String>>>,
+}
+
+struct SharedRenderer {
     renderer: Renderer,
+    /// The antialiasing methods `renderer` has pipelines for
+    aa_support: AaSupport,
+}
+
+impl SharedGpu {
+    /// Pick an adapter that can present to `surface` (any, when None) and open
+    /// a device on it
+    pub async fn new(instance: wgpu::Instance, surface: Option<&wgpu::Surface<'_>>, options: GpuOptions) -> Result<Self, GfxError> {
+        let adapter = match options.index {
+            Some(index) => {
+                let adapter = instance
+                    .enumerate_adapters(wgpu::Backends::all())
+                    .into_iter()
+                    .nth(index)
+                    .ok_or_else(|| GfxError::Wgpu(format!("no adapter at index {index}")))?;
+                if surface.is_some_and(|surface| !adapter.is_surface_supported(surface)) {
+                    let name = adapter.get_info().name;
+                    return Err(GfxError::Wgpu(format!("adapter {index} ({name}) can't present to this surface")));
+                }
+                adapter
+            }
+            None => {
+                let mut request = wgpu::RequestAdapterOptions {
+                    power_preference: options.power,
+                    compatible_surface: surface,
+                    force_fallback_adapter: false,
+                };
+                match instance.request_adapter(&request).await {
+                    Err(_) if options.software_fallback => {
+                        request.force_fallback_adapter = true;
+                        instance.request_adapter(&request).await
+                    }
+                    result => result,
+                }
+                .map_err(|e| GfxError::Wgpu(format!("{e:?}")))?
+            }
+        };
+        let mode = if adapter.get_info().device_type == wgpu::DeviceType::Cpu {
+            RenderMode::Software
+        } else if options.cpu_pipeline {
+            RenderMode::CpuPipeline
+        } else {
+            RenderMode::Gpu
+        };
+
+        // Request device with higher limits for Vello
+        let mut limits = wgpu::Limits::default();
+        limits.max_storage_buffers_per_shader_stage = 8;
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("Vello Device"),
+                    // Optional: only frame stats use it
+                    required_features: adapter.features() & wgpu::Features::TIMESTAMP_QUERY,
+                    required_limits: limits,
+                    memory_hints: wgpu::MemoryHints::default(),
+                    trace: wgpu::Trace::Off,
+                },
+            )
+            .await
+            .map_err(|e| GfxError::Wgpu(format!("{e:?}")))?;
+
+        let lost = Arc::new(parking_lot::Mutex::new(None));
+        let lost_slot = lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            *lost_slot.lock() = Some(format!("{reason:?}: {message}"));
+        });
+
+        let aa_support = AaSupport::area_only();
+        let renderer = create_renderer(&device, mode, aa_support)?;
+
+        Ok(Self {
+            instance,
+            adapter,
+            device,
+            queue,
+            mode,
+            renderer: Arc::new(parking_lot::Mutex::new(SharedRenderer { renderer, aa_support })),
+            lost,
+        })
+    }
+}
+
+pub struct Gfx {
+    /// None for headless contexts, which render into `offscreen` instead
+    surface: Option<wgpu::Surface<'static>>,
+    offscreen: Option<wgpu::Texture>,
+    /// Intermediate Rgba8Unorm texture Vello renders into, reused across
+    /// frames and recreated on resize
+    vello_target: wgpu::TextureView,
+    /// The texture behind `vello_target`, for frame captures
+    vello_texture: wgpu::Texture,
+    gpu: SharedGpu,
+    config: wgpu::SurfaceConfiguration,
     blit_pipeline: wgpu::RenderPipeline,
+    /// Blit variant that blends the scene over particles drawn beneath it
+    blit_blend_pipeline: wgpu::RenderPipeline,
     blit_bind_group_layout: wgpu::BindGroupLayout,
     sampler: wgpu::Sampler,
+    blit_params: wgpu::Buffer,
+    dither: bool,
+    /// The surface format encodes sRGB, so the blit decodes Vello's output
+    decode_srgb: bool,
+    /// None for headless contexts
+    layer: Option<MetalLayer>,
+    color_space: ColorSpace,
+    particles: ParticleSystem,
+    /// Created by the first frame with a blur layer
+    blur: Option<BackdropBlur>,
+    /// None where the adapter can't write timestamps
+    timer: Option<GpuTimer>,
+    /// Antialiasing used for this context's frames
+    aa: AaConfig,
     size: (u32, u32),
     scale: f32,
 }
 
 impl Gfx {
-    pub async fn new_macos(desc: &MacSurface) -> Result<Self, GfxError> {
+    /// Window renderer; with `shared`, it renders with that device instead of
+    /// opening one from `options`
+    pub async fn new_macos(desc: &MacSurface, options: GpuOptions, shared: Option<SharedGpu>) -> Result<Self, GfxError> {
         // SAFETY: we trust the caller to pass a valid NSView* and CAMetalLayer*.
         // raw-window-handle only needs the NSView pointer populated.
         let ns_view = NonNull::new(desc.ns_view).ok_or(GfxError::InvalidSurface)?;
@@ -51,7 +231,7 @@ impl Gfx {
 
         let disp = RawDisplayHandle::AppKit(AppKitDisplayHandle::new());
 
-        let instance = wgpu::Instance::default();
+        let instance = shared.as_ref().map(|gpu| gpu.instance.clone()).unwrap_or_default();
         // Unsafe: creating surface from raw handles is inherently unsafe.
         let surface = unsafe {
             instance
@@ -62,38 +242,38 @@ impl Gfx {
                 .map_err(|e| GfxError::Wgpu(format!("{e:?}")))?
         };
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .map_err(|e| GfxError::Wgpu(format!("{e:?}")))?;
-
-        // Request device with higher limits for Vello
-        let mut limits = wgpu::Limits::default();
-        limits.max_storage_buffers_per_shader_stage = 8;
+        let gpu = match shared {
+            Some(gpu) if !gpu.adapter.is_surface_supported(&surface) => {
+                let name = gpu.adapter.get_info().name;
+                return Err(GfxError::Wgpu(format!("shared adapter ({name}) can't present to this surface")));
+            }
+            Some(gpu) => gpu,
+            None => SharedGpu::new(instance, Some(&surface), options).await?,
+        };
+        let mut gfx = Self::new(gpu, Some(surface), desc.width_px as u32, desc.height_px as u32, desc.scale_factor);
+        // Untagged, the layer's sRGB values would go to the display as-is
+        gfx.layer = MetalLayer::new(desc.ca_metal_layer);
+        gfx.set_color_space(ColorSpace::Srgb);
+        Ok(gfx)
+    }
 
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: Some("Vello Device"),
-                    required_features: wgpu::Features::empty(),
-                    required_limits: limits,
-                    memory_hints: wgpu::MemoryHints::default(),
-                    trace: wgpu::Trace::Off,
-                },
-            )
-            .await
-            .map_err(|e| GfxError::Wgpu(format!("{e:?}")))?;
+    /// Offscreen renderer with no window; read frames back with `read_pixels`
+    pub async fn new_headless(width_px: u32, height_px: u32, scale_factor: f32, options: GpuOptions) -> Result<Self, GfxError> {
+        let gpu = SharedGpu::new(wgpu::Instance::default(), None, options).await?;
+        Ok(Self::new(gpu, None, width_px, height_px, scale_factor))
+    }
 
-        let w = desc.width_px as u32;
-        let h = desc.height_px as u32;
+    fn new(gpu: SharedGpu, surface: Option<wgpu::Surface<'static>>, w: u32, h: u32, scale_factor: f32) -> Self {
+        let device = &gpu.device;
+        let queue = &gpu.queue;
 
+        let (format, decode_srgb) = surface
+            .as_ref()
+            .and_then(|surface| color_space::pick_surface_format(&surface.get_capabilities(&gpu.adapter).formats))
+            .unwrap_or((wgpu::TextureFormat::Bgra8Unorm, false));
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: wgpu::TextureFormat::Bgra8Unorm,
+            format,
             width: w,
             height: h,
             present_mode: wgpu::PresentMode::Fifo,
@@ -101,20 +281,11 @@ impl Gfx {
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
-        surface.configure(&device, &config);
-
-        let renderer_opts = RendererOptions {
-            use_cpu: false,
-            antialiasing_support: AaSupport {
-                area: true,
-                msaa8: false,
-                msaa16: false,
-            },
-            num_init_threads: None,
-            pipeline_cache: None,
-        };
-
-        let renderer = Renderer::new(&device, renderer_opts).map_err(|e| GfxError::Vello(format!("{e:?}")))?;
+        if let Some(surface) = &surface {
+            surface.configure(device, &config);
+        }
+        let offscreen = surface.is_none().then(|| create_offscreen(device, &config));
+        let (vello_texture, vello_target) = create_vello_target(device, &config);
 
         let shader_src = include_str!("blit.wgsl");
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -142,6 +313,16 @@ impl Gfx {
                         ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                         count: None,
                     },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
                 ],
             });
 
@@ -151,34 +332,39 @@ impl Gfx {
             push_constant_ranges: &[],
         });
 
-        let blit_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Blit Pipeline"),
-            layout: Some(&blit_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: wgpu::TextureFormat::Bgra8Unorm,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+        let create_blit_pipeline = |label, blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&blit_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: config.format,
+                        blend: Some(blend),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+        let blit_pipeline = create_blit_pipeline("Blit Pipeline", wgpu::BlendState::REPLACE);
+        // Vello writes straight (non-premultiplied) alpha
+        let blit_blend_pipeline = create_blit_pipeline("Blit Blend Pipeline", wgpu::BlendState::ALPHA_BLENDING);
 
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("Blit Sampler"),
@@ -191,129 +377,545 @@ impl Gfx {
             ..Default::default()
         });
 
-        Ok(Self {
-            instance,
+        let blit_params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Blit Params"),
+            size: std::mem::size_of::<BlitParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let params = BlitParams { dither: 0, decode_srgb: decode_srgb as u32, _pad: [0; 2] };
+        queue.write_buffer(&blit_params, 0, bytemuck::bytes_of(&params));
+
+        let particles = ParticleSystem::new(device, config.format);
+        let timer = GpuTimer::new(device, queue);
+
+        Self {
             surface,
-            adapter,
-            device,
-            queue,
+            offscreen,
+            vello_target,
+            vello_texture,
+            gpu,
             config,
-            renderer,
             blit_pipeline,
+            blit_blend_pipeline,
             blit_bind_group_layout,
             sampler,
+            blit_params,
+            dither: false,
+            decode_srgb,
+            layer: None,
+            color_space: ColorSpace::Srgb,
+            particles,
+            blur: None,
+            timer,
+            aa: AaConfig::Area,
             size: (w, h),
-            scale: desc.scale_factor,
-        })
+            scale: scale_factor,
+        }
     }
 
     pub fn resize(&mut self, desc: &MacSurface) -> Result<(), GfxError> {
-        let w = desc.width_px as u32;
-        let h = desc.height_px as u32;
-        self.size = (w, h);
         self.scale = desc.scale_factor;
+        self.set_size(desc.width_px as u32, desc.height_px as u32);
+        Ok(())
+    }
+
+    /// Change the scale factor keeping the logical size: the surface is
+    /// resized to the same logical size at the new scale
+    pub fn set_scale(&mut self, scale: f32) {
+        let (w, h) = self.size;
+        let max = self.gpu.device.limits().max_texture_dimension_2d;
+        let rescale = |px: u32| ((px as f32 / self.scale * scale).round() as u32).clamp(1, max);
+        let (w, h) = (rescale(w), rescale(h));
+        self.scale = scale;
+        self.set_size(w, h);
+    }
 
+    fn set_size(&mut self, w: u32, h: u32) {
+        self.size = (w, h);
         self.config.width = w;
         self.config.height = h;
-        self.surface.configure(&self.device, &self.config);
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.gpu.device, &self.config);
+        } else {
+            self.offscreen = Some(create_offscreen(&self.gpu.device, &self.config));
+        }
+        (self.vello_texture, self.vello_target) = create_vello_target(&self.gpu.device, &self.config);
+    }
+
+    /// Why the GPU device was lost, if it has been (rendering can't recover)
+    pub fn device_lost(&self) -> Option<String> {
+        self.gpu.lost.lock().clone()
+    }
+
+    /// Adapter, backend and surface configuration, for diagnostics
+    pub fn describe(&self) -> String {
+        let info = self.gpu.adapter.get_info();
+        format!(
+            "adapter: {} ({:?}, {:?})\nmode: {:?}\ndriver: {} {}\nsurface: {}x{} @{}x {:?} {:?}{}\ndither: {}\ncolor space: {:?}",
+            info.name,
+            info.device_type,
+            info.backend,
+            self.gpu.mode,
+            info.driver,
+            info.driver_info,
+            self.config.width,
+            self.config.height,
+            self.scale,
+            self.config.format,
+            self.config.present_mode,
+            if self.surface.is_none() { " (headless)" } else { "" },
+            self.dither,
+            self.color_space,
+        )
+    }
+
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    /// Tag the window's layer with `space`; false for headless contexts and
+    /// platforms without color-managed layers, which keep the current space
+    pub fn set_color_space(&mut self, space: ColorSpace) -> bool {
+        if !self.layer.as_ref().is_some_and(|layer| layer.tag(space)) {
+            return false;
+        }
+        self.color_space = space;
+        true
+    }
+
+    pub fn aa(&self) -> AaConfig {
+        self.aa
+    }
+
+    /// Switch antialiasing method. MSAA pipelines are only built when first
+    /// asked for; if the device can't build them the method is unchanged.
+    pub fn set_aa(&mut self, aa: AaConfig) -> Result<(), GfxError> {
+        let mut shared = self.gpu.renderer.lock();
+        let supported = match aa {
+            AaConfig::Area => shared.aa_support.area,
+            AaConfig::Msaa8 => shared.aa_support.msaa8,
+            AaConfig::Msaa16 => shared.aa_support.msaa16,
+        };
+        if !supported {
+            let mut support = shared.aa_support;
+            match aa {
+                AaConfig::Area => support.area = true,
+                AaConfig::Msaa8 => support.msaa8 = true,
+                AaConfig::Msaa16 => support.msaa16 = true,
+            }
+            // Pipeline creation failures surface as validation errors
+            self.gpu.device.push_error_scope(wgpu::ErrorFilter::Validation);
+            let renderer = create_renderer(&self.gpu.device, self.gpu.mode, support);
+            let error = pollster::block_on(self.gpu.device.pop_error_scope());
+            match (renderer, error) {
+                (Ok(renderer), None) => {
+                    shared.renderer = renderer;
+                    shared.aa_support = support;
+                }
+                (Err(e), _) => return Err(e),
+                (_, Some(e)) => return Err(GfxError::Wgpu(format!("{aa:?} unsupported on this device: {e}"))),
+            }
+        }
+        self.aa = aa;
         Ok(())
     }
 
+    pub fn mode(&self) -> RenderMode {
+        self.gpu.mode
+    }
+
+    pub fn adapter(&self) -> &wgpu::Adapter {
+        &self.gpu.adapter
+    }
+
+    /// The device and pipelines, for creating more contexts that share them
+    pub fn gpu(&self) -> &SharedGpu {
+        &self.gpu
+    }
+
+    /// Surface size in physical pixels
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
     pub fn scale(&self) -> f32 {
         self.scale
     }
 
+    pub fn device(&self) -> &wgpu::Device {
+        &self.gpu.device
+    }
+
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.gpu.queue
+    }
+
+    /// GPU time of a recent frame (a frame or two behind), where supported
+    pub fn gpu_time(&self) -> Option<std::time::Duration> {
+        self.timer.as_ref().and_then(|timer| timer.last())
+    }
+
+    /// Bytes held by the engine's render targets: Vello's target, the
+    /// headless target or the surface's swapchain images
+    pub fn target_bytes(&self) -> u64 {
+        let (w, h) = self.size;
+        let frame = w as u64 * h as u64 * 4;
+        let presented = if self.surface.is_some() { self.config.desired_maximum_frame_latency as u64 + 1 } else { 1 };
+        frame * (1 + presented)
+    }
+
+    pub fn particles(&self) -> &ParticleSystem {
+        &self.particles
+    }
+
+    pub fn particles_mut(&mut self) -> &mut ParticleSystem {
+        &mut self.particles
+    }
+
+    /// Render `image` from a GPU texture instead of its pixel data (None removes the override)
+    pub fn override_image(&mut self, image: &peniko::ImageData, texture: Option<wgpu::Texture>) {
+        let texture = texture.map(|texture| wgpu::TexelCopyTextureInfoBase {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        });
+        self.gpu.renderer.lock().renderer.override_image(image, texture);
+    }
+
+    /// Enable or disable noise dithering in the blit pass.
+    /// Hides banding in large, smooth gradients on 8-bit displays.
+    pub fn set_dither(&mut self, enabled: bool) {
+        if self.dither == enabled {
+            return;
+        }
+        self.dither = enabled;
+        let params = BlitParams { dither: enabled as u32, decode_srgb: self.decode_srgb as u32, _pad: [0; 2] };
+        self.gpu.queue.write_buffer(&self.blit_params, 0, bytemuck::bytes_of(&params));
+    }
+
     pub fn render_scene(&mut self, scene: &Scene, clear: Color) -> Result<(), GfxError> {
+        self.render_layers(&[], scene, clear)
+    }
+
+    /// Render a frame cut by blur and color filter layers: each backdrop's
+    /// scene is what was drawn before its cut, `scene` what was drawn after
+    /// the last one
+    pub fn render_layers(&mut self, backdrops: &[Backdrop], scene: &Scene, clear: Color) -> Result<(), GfxError> {
         let (w, h) = self.size;
+        if let Some(timer) = &mut self.timer {
+            timer.begin(&self.gpu.device, &self.gpu.queue);
+        }
 
-        // 1) Render Vello scene to an intermediate RGBA8Unorm texture at PHYSICAL size
+        // 1) Render Vello scene to the intermediate RGBA8Unorm texture at PHYSICAL size
         // Scene is now in physical pixels (scaled by CommandBuffer)
-        let vello_size = wgpu::Extent3d {
-            width: w,
-            height: h,
-            depth_or_array_layers: 1,
-        };
-        let vello_texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Vello Target"),
-            size: vello_size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                | wgpu::TextureUsages::TEXTURE_BINDING
-                | wgpu::TextureUsages::STORAGE_BINDING,
-            view_formats: &[],
-        });
-        let vello_view = vello_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let vello_view = &self.vello_target;
+
+        // With particles beneath, Vello renders over transparency and the blit
+        // blends the scene over them; otherwise the scene is opaque
+        let particles_beneath = self.particles.has_layer(ParticleLayer::Beneath);
+        let base_color = if particles_beneath { Color::TRANSPARENT } else { clear };
 
         let params = RenderParams {
-            base_color: clear,
+            base_color,
             width: w,
             height: h,
-            antialiasing_method: AaConfig::Area,
+            antialiasing_method: self.aa,
         };
 
         // Render scene as-is (already in physical coordinates from CommandBuffer)
-        self.renderer
-            .render_to_texture(&self.device, &self.queue, scene, &vello_view, &params)
+        let first = backdrops.first().map_or(scene, |backdrop| &backdrop.scene);
+        self.gpu
+            .renderer
+            .lock()
+            .renderer
+            .render_to_texture(&self.gpu.device, &self.gpu.queue, first, vello_view, &params)
             .map_err(|e| GfxError::Vello(format!("{e:?}")))?;
 
+        // At each cut, blur the layer's backdrop if it's a blur layer, then
+        // blend what was drawn after it on top through its color filter
+        if !backdrops.is_empty() {
+            let device = &self.gpu.device;
+            let queue = &self.gpu.queue;
+            let blur = self.blur.get_or_insert_with(|| BackdropBlur::new(device));
+            blur.prepare(device, (w, h));
+            let overlay_params = RenderParams { base_color: Color::TRANSPARENT, ..params };
+            for (i, backdrop) in backdrops.iter().enumerate() {
+                if let Some(region) = &backdrop.blur {
+                    blur.blur(device, queue, vello_view, region, self.scale);
+                }
+                let above = backdrops.get(i + 1).map_or(scene, |next| &next.scene);
+                self.gpu
+                    .renderer
+                    .lock()
+                    .renderer
+                    .render_to_texture(device, queue, above, blur.overlay(), &overlay_params)
+                    .map_err(|e| GfxError::Vello(format!("{e:?}")))?;
+                blur.composite(device, queue, vello_view, backdrop.filter.as_ref());
+            }
+        }
+
         // 2) Blit from vello_texture (Rgba8Unorm) to surface (Bgra8Unorm)
-        let frame = self
-            .surface
-            .get_current_texture()
-            .map_err(|e| GfxError::Wgpu(format!("get_current_texture: {e:?}")))?;
+        let frame = match &self.surface {
+            Some(surface) => Some(
+                surface
+                    .get_current_texture()
+                    .map_err(|e| match e {
+                        wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated => GfxError::SurfaceLost(format!("{e:?}")),
+                        wgpu::SurfaceError::OutOfMemory => GfxError::OutOfMemory,
+                        e => GfxError::Wgpu(format!("get_current_texture: {e:?}")),
+                    })?,
+            ),
+            None => None,
+        };
+        let target = match (&frame, &self.offscreen) {
+            (Some(frame), _) => &frame.texture,
+            (None, Some(offscreen)) => offscreen,
+            (None, None) => return Err(GfxError::InvalidSurface),
+        };
 
-        let frame_view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let frame_view = target.create_view(&wgpu::TextureViewDescriptor::default());
 
-        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        let bind_group = self.gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Blit Bind Group"),
             layout: &self.blit_bind_group_layout,
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&vello_view),
+                    resource: wgpu::BindingResource::TextureView(vello_view),
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
                     resource: wgpu::BindingResource::Sampler(&self.sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.blit_params.as_entire_binding(),
+                },
             ],
         });
 
         let mut encoder = self
+            .gpu
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("Blit Encoder"),
             });
 
+        self.particles.simulate(&self.gpu.queue, &mut encoder, (w, h));
+
         {
+            let [r, g, b, a] = clear.components;
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Blit Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &frame_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: r as f64,
+                            g: g as f64,
+                            b: b as f64,
+                            a: a as f64,
+                        }),
                         store: wgpu::StoreOp::Store,
                     },
                     depth_slice: None,
                 })],
                 depth_stencil_attachment: None,
-                timestamp_writes: None,
+                timestamp_writes: self.timer.as_ref().and_then(|timer| timer.end_writes()),
                 occlusion_query_set: None,
             });
-            rpass.set_pipeline(&self.blit_pipeline);
+
+            if particles_beneath {
+                self.particles.draw(&mut rpass, ParticleLayer::Beneath);
+                rpass.set_pipeline(&self.blit_blend_pipeline);
+            } else {
+                rpass.set_pipeline(&self.blit_pipeline);
+            }
             rpass.set_bind_group(0, &bind_group, &[]);
             rpass.draw(0..6, 0..1);
+
+            self.particles.draw(&mut rpass, ParticleLayer::Above);
+        }
+        if let Some(timer) = &self.timer {
+            timer.resolve(&mut encoder);
         }
 
-        self.queue.submit(Some(encoder.finish()));
-        frame.present();
+        self.gpu.queue.submit(Some(encoder.finish()));
+        if let Some(timer) = &mut self.timer {
+            timer.submitted();
+        }
+        if let Some(frame) = frame {
+            frame.present();
+        }
 
         Ok(())
     }
+
+    /// Read back the last rendered frame of a headless context as tightly
+    /// packed RGBA8 rows (blocks until the GPU finishes)
+    pub fn read_pixels(&self) -> Result<Vec<u8>, GfxError> {
+        let texture = self.offscreen.as_ref().ok_or(GfxError::InvalidSurface)?;
+        self.read_texture(texture, self.size, true)
+    }
+
+    /// Read back the last rendered frame as tightly packed RGBA8 rows. Headless
+    /// contexts return exactly what was presented; windowed ones return Vello's
+    /// intermediate texture, which is the frame before particles and dithering.
+    pub fn capture_frame(&self) -> Result<Vec<u8>, GfxError> {
+        match &self.offscreen {
+            Some(texture) => self.read_texture(texture, self.size, true),
+            None => self.read_texture(&self.vello_texture, self.size, false),
+        }
+    }
+
+    /// Render a scene on its own into a transparent `width` x `height`
+    /// texture and read it back as premultiplied RGBA8 rows (blocks until the
+    /// GPU finishes)
+    pub fn render_scene_to_pixels(&mut self, scene: &Scene, width: u32, height: u32) -> Result<Vec<u8>, GfxError> {
+        let texture = self.gpu.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Snapshot Target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let params = RenderParams {
+            base_color: Color::TRANSPARENT,
+            width,
+            height,
+            antialiasing_method: self.aa,
+        };
+        self.gpu
+            .renderer
+            .lock()
+            .renderer
+            .render_to_texture(&self.gpu.device, &self.gpu.queue, scene, &view, &params)
+            .map_err(|e| GfxError::Vello(format!("{e:?}")))?;
+        self.read_texture(&texture, (width, height), false)
+    }
+
+    /// Copy a `size` texture to CPU memory, swizzling BGRA to RGBA if `bgra`
+    fn read_texture(&self, texture: &wgpu::Texture, size: (u32, u32), bgra: bool) -> Result<Vec<u8>, GfxError> {
+        let (w, h) = size;
+
+        let row_bytes = w * 4;
+        let padded_row_bytes = row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = self.gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_row_bytes * h) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .gpu
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Readback Encoder"),
+            });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: Some(h),
+                },
+            },
+            wgpu::Extent3d {
+                width: w,
+                height: h,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.gpu.queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        self.gpu.device
+            .poll(wgpu::PollType::Wait)
+            .map_err(|e| GfxError::Wgpu(format!("readback: {e:?}")))?;
+
+        // Strip row padding and swizzle BGRA -> RGBA
+        let data = slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity((row_bytes * h) as usize);
+        for row in data.chunks(padded_row_bytes as usize) {
+            let row = &row[..row_bytes as usize];
+            if !bgra {
+                pixels.extend_from_slice(row);
+                continue;
+            }
+            for px in row.chunks_exact(4) {
+                pixels.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+            }
+        }
+        drop(data);
+        buffer.unmap();
+
+        Ok(pixels)
+    }
+}
+
+/// Intermediate texture Vello renders into before the blit to the surface
+fn create_vello_target(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Vello Target"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::STORAGE_BINDING
+            | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
+
+fn create_renderer(device: &wgpu::Device, mode: RenderMode, antialiasing_support: AaSupport) -> Result<Renderer, GfxError> {
+    let options = RendererOptions {
+        // Vello's Rust stages beat its compute shaders emulated on a CPU
+        use_cpu: mode != RenderMode::Gpu,
+        antialiasing_support,
+        num_init_threads: None,
+        pipeline_cache: None,
+    };
+    Renderer::new(device, options).map_err(|e| GfxError::Vello(format!("{e:?}")))
+}
+
+/// Render target for headless contexts, matching the surface format
+fn create_offscreen(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen Target"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
 }
@@ -0,0 +1,133 @@
+/// GPU frame timing with timestamp queries
+///
+/// A timestamp is written before Vello's work is submitted and another at the
+/// end of the blit pass; the queue runs them in order, so the difference spans
+/// the frame's GPU work. Results are read back without blocking, so they
+/// arrive a frame or two late, and frames are skipped while a readback is in
+/// flight. Unavailable where the adapter lacks TIMESTAMP_QUERY.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    /// Nanoseconds per timestamp tick
+    period: f32,
+    /// A frame's timestamps are being read back
+    in_flight: bool,
+    /// Set by the map callback once the readback buffer is readable
+    mapped: Arc<AtomicBool>,
+    /// Set between `begin` and `resolve`
+    timing: bool,
+    last: Option<Duration>,
+}
+
+const SIZE: u64 = 2 * wgpu::QUERY_SIZE as u64;
+
+impl GpuTimer {
+    /// None if the device wasn't created with TIMESTAMP_QUERY
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Frame Timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Timestamps Resolve"),
+            size: SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Timestamps Readback"),
+            size: SIZE,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Some(Self {
+            query_set,
+            resolve,
+            readback,
+            period: queue.get_timestamp_period(),
+            in_flight: false,
+            mapped: Arc::new(AtomicBool::new(false)),
+            timing: false,
+            last: None,
+        })
+    }
+
+    /// GPU time of the most recent frame read back
+    pub fn last(&self) -> Option<Duration> {
+        self.last
+    }
+
+    /// Collect a finished readback, then write the frame's start timestamp
+    /// unless the previous readback is still in flight
+    pub fn begin(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let _ = device.poll(wgpu::PollType::Poll);
+        if self.in_flight && self.mapped.swap(false, Ordering::Acquire) {
+            {
+                let data = self.readback.slice(..).get_mapped_range();
+                let start = u64::from_le_bytes(data[0..8].try_into().unwrap());
+                let end = u64::from_le_bytes(data[8..16].try_into().unwrap());
+                let nanos = end.saturating_sub(start) as f64 * self.period as f64;
+                self.last = Some(Duration::from_nanos(nanos as u64));
+            }
+            self.readback.unmap();
+            self.in_flight = false;
+        }
+
+        self.timing = !self.in_flight;
+        if !self.timing {
+            return;
+        }
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Frame Start Timestamp"),
+        });
+        encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Frame Start Timestamp"),
+            timestamp_writes: Some(wgpu::ComputePassTimestampWrites {
+                query_set: &self.query_set,
+                beginning_of_pass_write_index: Some(0),
+                end_of_pass_write_index: None,
+            }),
+        });
+        queue.submit(Some(encoder.finish()));
+    }
+
+    /// Timestamp writes for the frame's last pass
+    pub fn end_writes(&self) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        self.timing.then_some(wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: None,
+            end_of_pass_write_index: Some(1),
+        })
+    }
+
+    /// Copy the timestamps out, after the frame's last pass
+    pub fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        if self.timing {
+            encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve, 0);
+            encoder.copy_buffer_to_buffer(&self.resolve, 0, &self.readback, 0, SIZE);
+        }
+    }
+
+    /// Start reading the timestamps back, after the frame is submitted
+    pub fn submitted(&mut self) {
+        if !self.timing {
+            return;
+        }
+        self.timing = false;
+        self.in_flight = true;
+        let mapped = self.mapped.clone();
+        self.readback.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            mapped.store(result.is_ok(), Ordering::Release);
+        });
+    }
+}
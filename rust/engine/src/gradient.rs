@@ -0,0 +1,146 @@
+/// Gradient brushes
+///
+/// Hosts register a gradient once (mcore_gradient_create) and reference it by
+/// ID from draw commands. Geometry is relative to the bounds of whatever shape
+/// it paints, so one gradient serves rings and fills of any size.
+
+use peniko::kurbo::{Point, Rect};
+use peniko::{Color, ColorStop, Gradient};
+use std::collections::HashMap;
+
+/// Colors are interpolated along this shape
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GradientKind {
+    /// Across the bounds at `angle_deg` (0 = left to right, 90 = top to bottom)
+    Linear,
+    /// Around the bounds' center, starting at `angle_deg` (0 = 3 o'clock, clockwise)
+    Sweep,
+}
+
+impl GradientKind {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Linear),
+            1 => Some(Self::Sweep),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradientDesc {
+    pub kind: GradientKind,
+    pub angle_deg: f32,
+    /// (offset in 0..=1, color), sorted by offset
+    pub stops: Vec<(f32, Color)>,
+}
+
+impl GradientDesc {
+    pub fn new(kind: GradientKind, angle_deg: f32, stops: &[(f32, Color)]) -> Result<Self, String> {
+        if stops.len() < 2 {
+            return Err(format!("Gradients need at least 2 stops (got {})", stops.len()));
+        }
+        if stops.iter().any(|(offset, _)| !offset.is_finite()) {
+            return Err("Gradient stop offsets must be finite".to_string());
+        }
+        let mut stops: Vec<_> = stops.iter().map(|&(offset, color)| (offset.clamp(0.0, 1.0), color)).collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self { kind, angle_deg, stops })
+    }
+
+    /// The brush for a shape with these bounds
+    pub fn brush(&self, bounds: Rect) -> Gradient {
+        let gradient = match self.kind {
+            GradientKind::Linear => {
+                let (start, end) = linear_endpoints(bounds, self.angle_deg);
+                Gradient::new_linear(start, end)
+            }
+            GradientKind::Sweep => {
+                let start = self.angle_deg.to_radians();
+                Gradient::new_sweep(bounds.center(), start, start + std::f32::consts::TAU)
+            }
+        };
+        let stops: Vec<ColorStop> = self.stops.iter().map(|&stop| ColorStop::from(stop)).collect();
+        gradient.with_stops(stops.as_slice())
+    }
+}
+
+/// Span the bounds' extent along the direction, so the first and last stops
+/// land on opposite edges (or corners)
+fn linear_endpoints(bounds: Rect, angle_deg: f32) -> (Point, Point) {
+    let (sin, cos) = (angle_deg as f64).to_radians().sin_cos();
+    let half = (bounds.width() * cos.abs() + bounds.height() * sin.abs()) / 2.0;
+    let center = bounds.center();
+    (
+        Point::new(center.x - cos * half, center.y - sin * half),
+        Point::new(center.x + cos * half, center.y + sin * half),
+    )
+}
+
+/// Registry of gradients keyed by ID
+pub struct GradientManager {
+    gradients: HashMap<i32, GradientDesc>,
+    next_id: i32,
+}
+
+impl GradientManager {
+    pub fn new() -> Self {
+        Self {
+            gradients: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Register a gradient and return its ID
+    pub fn register(&mut self, gradient: GradientDesc) -> i32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.gradients.insert(id, gradient);
+        id
+    }
+
+    pub fn get(&self, id: i32) -> Option<&GradientDesc> {
+        self.gradients.get(&id)
+    }
+
+    /// Remove a gradient, returning whether it existed
+    pub fn remove(&mut self, id: i32) -> bool {
+        self.gradients.remove(&id).is_some()
+    }
+}
+
+impl Default for GradientManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_are_validated_and_sorted() {
+        let red = Color::from_rgba8(255, 0, 0, 255);
+        let blue = Color::from_rgba8(0, 0, 255, 255);
+        assert!(GradientDesc::new(GradientKind::Linear, 0.0, &[(0.0, red)]).is_err());
+        assert!(GradientDesc::new(GradientKind::Linear, 0.0, &[(0.0, red), (f32::NAN, blue)]).is_err());
+
+        let desc = GradientDesc::new(GradientKind::Sweep, 0.0, &[(1.5, blue), (-1.0, red), (0.5, red)]).unwrap();
+        let offsets: Vec<f32> = desc.stops.iter().map(|s| s.0).collect();
+        assert_eq!(offsets, [0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn linear_spans_the_bounds() {
+        let bounds = Rect::new(10.0, 20.0, 110.0, 60.0);
+        let (start, end) = linear_endpoints(bounds, 90.0);
+        assert!((start - Point::new(60.0, 20.0)).hypot() < 1e-6);
+        assert!((end - Point::new(60.0, 60.0)).hypot() < 1e-6);
+
+        // Diagonal: the stops reach the far corners' projections
+        let (start, end) = linear_endpoints(Rect::new(0.0, 0.0, 100.0, 100.0), 45.0);
+        assert!((start.x - start.y).abs() < 1e-6);
+        assert!(((end - start).hypot() - 100.0 * std::f64::consts::SQRT_2).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,356 @@
+/// Hit regions and pointer routing
+///
+/// Hosts tag regions with IDs while drawing a frame. Regions remember the clip
+/// and transform active when they were registered, so hit testing matches what
+/// is actually visible. Pointer events are routed to the topmost region, with
+/// press capture and hover enter/leave tracking.
+
+/// Axis-aligned rectangle in logical pixels
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+}
+
+impl Rect {
+    pub fn from_xywh(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self { x0: x, y0: y, x1: x + w, y1: y + h }
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x0 && x < self.x1 && y >= self.y0 && y < self.y1
+    }
+
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        Rect {
+            x0: self.x0.max(other.x0),
+            y0: self.y0.max(other.y0),
+            x1: self.x1.min(other.x1).max(self.x0.max(other.x0)),
+            y1: self.y1.min(other.y1).max(self.y0.max(other.y0)),
+        }
+    }
+}
+
+/// Translation + uniform scale, mapping local coordinates to root coordinates
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub dx: f32,
+    pub dy: f32,
+    pub scale: f32,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self { dx: 0.0, dy: 0.0, scale: 1.0 };
+
+    /// Compose: `inner` is expressed in this transform's local space
+    pub fn then(&self, inner: &Transform) -> Transform {
+        Transform {
+            dx: self.dx + inner.dx * self.scale,
+            dy: self.dy + inner.dy * self.scale,
+            scale: self.scale * inner.scale,
+        }
+    }
+
+    pub fn apply_point(&self, x: f32, y: f32) -> (f32, f32) {
+        (x * self.scale + self.dx, y * self.scale + self.dy)
+    }
+
+    /// Map a root point back into this transform's local space
+    pub fn to_local(&self, x: f32, y: f32) -> (f32, f32) {
+        let scale = if self.scale != 0.0 { self.scale } else { 1.0 };
+        ((x - self.dx) / scale, (y - self.dy) / scale)
+    }
+
+    pub fn apply_rect(&self, r: &Rect) -> Rect {
+        Rect {
+            x0: r.x0 * self.scale + self.dx,
+            y0: r.y0 * self.scale + self.dy,
+            x1: r.x1 * self.scale + self.dx,
+            y1: r.y1 * self.scale + self.dy,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct HitRegion {
+    pub id: u64,
+    /// Bounds in root coordinates
+    pub bounds: Rect,
+    /// Clip in root coordinates active at registration
+    pub clip: Option<Rect>,
+    pub z: i32,
+    /// Transform active at registration (used for local coordinates)
+    pub transform: Transform,
+    order: usize,
+}
+
+impl HitRegion {
+    fn hit(&self, x: f32, y: f32) -> bool {
+        self.bounds.contains(x, y) && self.clip.is_none_or(|c| c.contains(x, y))
+    }
+
+    /// Map a root point into the region's local space (origin at its top-left)
+    pub fn to_local(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.bounds.x0) / self.transform.scale,
+            (y - self.bounds.y0) / self.transform.scale,
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RoutedPhase {
+    Down = 0,
+    Move = 1,
+    Up = 2,
+    Cancel = 3,
+    Enter = 4,
+    Leave = 5,
+}
+
+/// A pointer event delivered to a region
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RoutedEvent {
+    pub id: u64,
+    pub phase: RoutedPhase,
+    pub local_x: f32,
+    pub local_y: f32,
+}
+
+#[derive(Default)]
+pub struct HitRegistry {
+    regions: Vec<HitRegion>,
+    clip_stack: Vec<Rect>,
+    transform_stack: Vec<Transform>,
+    /// Every clip pushed this frame (root space) and the deepest nesting,
+    /// for the debug overlay
+    frame_clips: Vec<Rect>,
+    max_clip_depth: usize,
+    pressed: Option<u64>,
+    hovered: Option<u64>,
+}
+
+impl HitRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new frame: drop last frame's regions and stacks
+    /// (press capture and hover survive so interactions span frames)
+    pub fn begin_frame(&mut self) {
+        self.regions.clear();
+        self.clip_stack.clear();
+        self.transform_stack.clear();
+        self.frame_clips.clear();
+        self.max_clip_depth = 0;
+    }
+
+    /// The composed transform regions registered now would get
+    pub fn current_transform(&self) -> Transform {
+        self.transform_stack.last().copied().unwrap_or(Transform::IDENTITY)
+    }
+
+    /// Push a clip rect given in the current local space
+    pub fn push_clip(&mut self, rect: Rect) {
+        let root = self.current_transform().apply_rect(&rect);
+        let clip = match self.clip_stack.last() {
+            Some(parent) => parent.intersect(&root),
+            None => root,
+        };
+        self.clip_stack.push(clip);
+        self.frame_clips.push(root);
+        self.max_clip_depth = self.max_clip_depth.max(self.clip_stack.len());
+    }
+
+    /// Clips pushed so far this frame, in root space
+    pub fn frame_clips(&self) -> &[Rect] {
+        &self.frame_clips
+    }
+
+    /// Deepest clip nesting so far this frame
+    pub fn max_clip_depth(&self) -> usize {
+        self.max_clip_depth
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.clip_stack.pop();
+    }
+
+    pub fn push_transform(&mut self, transform: Transform) {
+        let composed = self.current_transform().then(&transform);
+        self.transform_stack.push(composed);
+    }
+
+    pub fn pop_transform(&mut self) {
+        self.transform_stack.pop();
+    }
+
+    /// Register a region given in the current local space
+    pub fn add_region(&mut self, id: u64, rect: Rect, z: i32) {
+        let transform = self.current_transform();
+        let order = self.regions.len();
+        self.regions.push(HitRegion {
+            id,
+            bounds: transform.apply_rect(&rect),
+            clip: self.clip_stack.last().copied(),
+            z,
+            transform,
+            order,
+        });
+    }
+
+    /// Topmost region under a root point: highest z, then latest registered
+    pub fn hit_test(&self, x: f32, y: f32) -> Option<&HitRegion> {
+        self.regions
+            .iter()
+            .filter(|r| r.hit(x, y))
+            .max_by_key(|r| (r.z, r.order))
+    }
+
+    fn region(&self, id: u64) -> Option<&HitRegion> {
+        self.regions.iter().rev().find(|r| r.id == id)
+    }
+
+    fn routed(&self, id: u64, phase: RoutedPhase, x: f32, y: f32) -> RoutedEvent {
+        let (local_x, local_y) = match self.region(id) {
+            Some(region) => region.to_local(x, y),
+            None => (x, y),
+        };
+        RoutedEvent { id, phase, local_x, local_y }
+    }
+
+    /// Route a pointer event (root logical coordinates) to regions
+    pub fn dispatch(&mut self, phase: crate::gesture::PointerPhase, x: f32, y: f32) -> Vec<RoutedEvent> {
+        use crate::gesture::PointerPhase;
+
+        let mut out = Vec::new();
+        let target = self.hit_test(x, y).map(|r| r.id);
+
+        // Hover tracking applies to every event kind
+        if target != self.hovered {
+            if let Some(old) = self.hovered {
+                out.push(self.routed(old, RoutedPhase::Leave, x, y));
+            }
+            if let Some(new) = target {
+                out.push(self.routed(new, RoutedPhase::Enter, x, y));
+            }
+            self.hovered = target;
+        }
+
+        match phase {
+            PointerPhase::Down => {
+                if let Some(id) = target {
+                    self.pressed = Some(id);
+                    out.push(self.routed(id, RoutedPhase::Down, x, y));
+                }
+            }
+            PointerPhase::Move => {
+                // Pressed region captures moves even outside its bounds
+                if let Some(id) = self.pressed.or(target) {
+                    out.push(self.routed(id, RoutedPhase::Move, x, y));
+                }
+            }
+            PointerPhase::Up => {
+                if let Some(id) = self.pressed.take() {
+                    out.push(self.routed(id, RoutedPhase::Up, x, y));
+                }
+            }
+            PointerPhase::Cancel => {
+                if let Some(id) = self.pressed.take() {
+                    out.push(self.routed(id, RoutedPhase::Cancel, x, y));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gesture::PointerPhase;
+
+    #[test]
+    fn test_z_order_and_registration_order() {
+        let mut reg = HitRegistry::new();
+        reg.add_region(1, Rect::from_xywh(0.0, 0.0, 100.0, 100.0), 0);
+        reg.add_region(2, Rect::from_xywh(0.0, 0.0, 50.0, 50.0), 0);
+        reg.add_region(3, Rect::from_xywh(0.0, 0.0, 100.0, 100.0), -1);
+
+        assert_eq!(reg.hit_test(10.0, 10.0).unwrap().id, 2);
+        assert_eq!(reg.hit_test(75.0, 75.0).unwrap().id, 1);
+        assert!(reg.hit_test(200.0, 200.0).is_none());
+    }
+
+    #[test]
+    fn test_clip_hides_region() {
+        let mut reg = HitRegistry::new();
+        reg.push_clip(Rect::from_xywh(0.0, 0.0, 50.0, 50.0));
+        reg.add_region(1, Rect::from_xywh(0.0, 0.0, 100.0, 100.0), 0);
+        reg.pop_clip();
+
+        assert!(reg.hit_test(25.0, 25.0).is_some());
+        assert!(reg.hit_test(75.0, 75.0).is_none());
+    }
+
+    #[test]
+    fn test_transform_and_local_coords() {
+        let mut reg = HitRegistry::new();
+        reg.push_transform(Transform { dx: 100.0, dy: 0.0, scale: 2.0 });
+        reg.add_region(7, Rect::from_xywh(0.0, 0.0, 10.0, 10.0), 0);
+        reg.pop_transform();
+
+        let events = reg.dispatch(PointerPhase::Down, 110.0, 4.0);
+        assert_eq!(events[0].phase, RoutedPhase::Enter);
+        let down = events[1];
+        assert_eq!(down.id, 7);
+        assert_eq!(down.phase, RoutedPhase::Down);
+        assert_eq!(down.local_x, 5.0);
+        assert_eq!(down.local_y, 2.0);
+    }
+
+    #[test]
+    fn test_clip_follows_transform() {
+        let mut reg = HitRegistry::new();
+        reg.push_transform(Transform { dx: 50.0, dy: 0.0, scale: 2.0 });
+        reg.push_clip(Rect::from_xywh(0.0, 0.0, 10.0, 10.0));
+        reg.add_region(1, Rect::from_xywh(0.0, 0.0, 20.0, 20.0), 0);
+        reg.pop_clip();
+        reg.pop_transform();
+
+        let region = reg.hit_test(60.0, 10.0).unwrap();
+        assert_eq!(region.to_local(60.0, 10.0), (5.0, 5.0));
+        assert!(reg.hit_test(80.0, 10.0).is_none(), "outside the transformed clip");
+    }
+
+    #[test]
+    fn test_transform_points_round_trip() {
+        let mut reg = HitRegistry::new();
+        reg.push_transform(Transform { dx: 10.0, dy: 20.0, scale: 2.0 });
+        reg.push_transform(Transform { dx: 5.0, dy: 0.0, scale: 1.5 });
+        let transform = reg.current_transform();
+        assert_eq!(transform, Transform { dx: 20.0, dy: 20.0, scale: 3.0 });
+
+        let root = transform.apply_point(4.0, 2.0);
+        assert_eq!(root, (32.0, 26.0));
+        assert_eq!(transform.to_local(root.0, root.1), (4.0, 2.0));
+    }
+
+    #[test]
+    fn test_press_capture_and_hover() {
+        let mut reg = HitRegistry::new();
+        reg.add_region(1, Rect::from_xywh(0.0, 0.0, 10.0, 10.0), 0);
+
+        reg.dispatch(PointerPhase::Down, 5.0, 5.0);
+        let moved = reg.dispatch(PointerPhase::Move, 50.0, 50.0);
+        assert_eq!(moved[0].phase, RoutedPhase::Leave);
+        assert_eq!(moved[1].phase, RoutedPhase::Move);
+        assert_eq!(moved[1].id, 1);
+
+        let up = reg.dispatch(PointerPhase::Up, 50.0, 50.0);
+        assert_eq!(up, vec![RoutedEvent { id: 1, phase: RoutedPhase::Up, local_x: 50.0, local_y: 50.0 }]);
+    }
+}
@@ -3,11 +3,184 @@
 /// Handles image registration, reference counting, and storage.
 /// Images are stored with Arc<Blob> for efficient sharing and GPU upload.
 
-use peniko::{Blob, ImageData};
+use parking_lot::Mutex;
+use peniko::kurbo::{Affine, Rect};
+use peniko::{Blob, Color, Extend, Fill, ImageBrush, ImageData, ImageQuality};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use vello::peniko::{ImageAlphaType, ImageFormat};
+use vello::Scene;
+
+use crate::atlas::{Atlas, Slot};
+
+#[cfg(not(feature = "image-decode"))]
+const DECODE_UNAVAILABLE: &str = "Image decoding not compiled in (enable the image-decode feature)";
+
+/// Pixel layout of host-provided data (MCORE_IMAGE_FORMAT_*)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SourceFormat {
+    Rgb8,
+    Rgba8,
+}
+
+impl SourceFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            SourceFormat::Rgb8 => 3,
+            SourceFormat::Rgba8 => 4,
+        }
+    }
+}
+
+/// How host-provided data encodes alpha (MCORE_IMAGE_ALPHA_*)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SourceAlpha {
+    /// Alpha channel (if any) is ignored
+    Opaque,
+    /// Color channels already multiplied by alpha (CGImage, most GPU APIs)
+    Premultiplied,
+    /// Color channels independent of alpha (PNG)
+    Straight,
+}
+
+/// Multiply RGBA8 color channels by alpha, in place
+pub fn premultiply(rgba: &mut [u8]) {
+    for px in rgba.chunks_exact_mut(4) {
+        let a = px[3] as u32;
+        for c in &mut px[..3] {
+            *c = ((*c as u32 * a + 127) / 255) as u8;
+        }
+    }
+}
+
+/// Divide premultiplied RGBA8 color channels by alpha, in place.
+/// Fully transparent pixels become transparent black.
+pub fn unpremultiply(rgba: &mut [u8]) {
+    for px in rgba.chunks_exact_mut(4) {
+        let a = px[3] as u32;
+        for c in &mut px[..3] {
+            *c = match a {
+                0 => 0,
+                _ => ((*c as u32 * 255 + a / 2) / a).min(255) as u8,
+            };
+        }
+    }
+}
+
+/// Convert host pixels to the straight-alpha RGBA8 every image is stored as.
+/// Vello premultiplies image samples itself, so a single stored
+/// representation keeps premultiplied sources from being multiplied twice
+/// (the dark fringes on rounded, antialiased edges).
+pub fn to_straight_rgba8(pixels: &[u8], format: SourceFormat, alpha: SourceAlpha) -> Vec<u8> {
+    let mut rgba = match format {
+        SourceFormat::Rgba8 => pixels.to_vec(),
+        SourceFormat::Rgb8 => pixels
+            .chunks_exact(3)
+            .flat_map(|px| [px[0], px[1], px[2], 255])
+            .collect(),
+    };
+    match alpha {
+        SourceAlpha::Opaque => rgba.chunks_exact_mut(4).for_each(|px| px[3] = 255),
+        SourceAlpha::Premultiplied => unpremultiply(&mut rgba),
+        SourceAlpha::Straight => {}
+    }
+    rgba
+}
+
+/// How a draw filters its image (MCORE_IMAGE_SAMPLING_*)
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Sampling {
+    #[default]
+    Linear,
+    /// Hard pixel edges, for pixel art
+    Nearest,
+    /// Linear from a pre-shrunk copy near the drawn size, so large photos
+    /// drawn small don't shimmer
+    Mipmap,
+}
+
+/// How a draw fills its rect past the image's edges (MCORE_IMAGE_EXTEND_*),
+/// per axis
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Sampler {
+    pub sampling: Sampling,
+    pub extend_x: Extend,
+    pub extend_y: Extend,
+}
+
+/// Halve straight-alpha RGBA8 pixels in each dimension (down to 1) with a
+/// box filter weighted by alpha, so transparent pixels don't darken edges
+pub fn downsample(rgba: &[u8], width: u32, height: u32) -> (Vec<u8>, u32, u32) {
+    let (w, h) = (width as usize, height as usize);
+    let (half_w, half_h) = ((w / 2).max(1), (h / 2).max(1));
+    let mut out = Vec::with_capacity(half_w * half_h * 4);
+    for y in 0..half_h {
+        for x in 0..half_w {
+            let mut sum = [0u32; 4];
+            for (sx, sy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let px = &rgba[((2 * y + sy).min(h - 1) * w + (2 * x + sx).min(w - 1)) * 4..][..4];
+                let a = px[3] as u32;
+                for c in 0..3 {
+                    sum[c] += px[c] as u32 * a;
+                }
+                sum[3] += a;
+            }
+            let a = sum[3];
+            for c in &sum[..3] {
+                out.push(if a == 0 { 0 } else { ((c + a / 2) / a) as u8 });
+            }
+            out.push(((a + 2) / 4) as u8);
+        }
+    }
+    (out, half_w as u32, half_h as u32)
+}
+
+/// The mip level to sample when a width x height image is drawn at `scale`
+/// device px per image px: the smallest copy still at least the drawn size
+pub fn mip_level(scale: f64, width: u32, height: u32) -> u32 {
+    let max_level = width.max(height).max(1).ilog2();
+    let mut level = 0;
+    let mut scale = scale;
+    while scale > 0.0 && scale <= 0.5 && level < max_level {
+        scale *= 2.0;
+        level += 1;
+    }
+    level
+}
+
+/// Why encoded `bytes` can't be decoded, if they're in a format whose cargo
+/// feature this build leaves out (image's own error doesn't say which)
+#[cfg(feature = "image-decode")]
+fn missing_feature(bytes: &[u8]) -> Option<&'static str> {
+    match image::guess_format(bytes).ok()? {
+        image::ImageFormat::WebP if !cfg!(feature = "webp") => Some("WebP decoding not compiled in (enable the webp feature)"),
+        image::ImageFormat::Avif if !cfg!(feature = "avif") => Some("AVIF decoding not compiled in (enable the avif feature)"),
+        image::ImageFormat::Tga
+        | image::ImageFormat::Qoi
+        | image::ImageFormat::Pnm
+        | image::ImageFormat::Hdr
+        | image::ImageFormat::OpenExr
+        | image::ImageFormat::Dds
+        | image::ImageFormat::Farbfeld
+            if !cfg!(feature = "image-extra") =>
+        {
+            Some("Format not compiled in (enable the image-extra feature)")
+        }
+        _ => None,
+    }
+}
+
+/// The size a width x height image is shrunk to so neither side exceeds
+/// `max_dimension`, keeping its aspect ratio; None if it already fits
+pub fn fit_within(width: u32, height: u32, max_dimension: u32) -> Option<(u32, u32)> {
+    let longest = width.max(height);
+    if max_dimension == 0 || longest <= max_dimension {
+        return None;
+    }
+    let scale = |side: u32| ((side as u64 * max_dimension as u64 + longest as u64 / 2) / longest as u64).max(1) as u32;
+    Some((scale(width), scale(height)))
+}
 
 /// Entry in the image cache with reference counting
 pub struct ImageEntry {
@@ -15,61 +188,297 @@ pub struct ImageEntry {
     pub refcount: usize,
     pub width: u32,
     pub height: u32,
+    /// Halved copies (mip levels 1, 2, ...), built as draws ask for them
+    pub mips: Vec<ImageData>,
+    /// Registry clock when the image was last drawn or retained
+    pub last_used: u64,
 }
 
-/// Image manager with reference-counted cache
-pub struct ImageManager {
+impl ImageEntry {
+    fn new(image: ImageData, refcount: usize) -> Self {
+        let (width, height) = (image.width, image.height);
+        Self { image, refcount, width, height, mips: Vec::new(), last_used: 0 }
+    }
+
+    fn mip_bytes(&self) -> u64 {
+        self.mips.iter().map(|mip| mip.data.len() as u64).sum()
+    }
+
+    fn bytes(&self) -> u64 {
+        self.image.data.len() as u64 + self.mip_bytes()
+    }
+}
+
+/// How hard the host is asking the engine to give memory back
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Pressure {
+    /// Free released images
+    Warning,
+    /// Also drop every mip level; they're rebuilt as draws need them
+    Critical,
+}
+
+/// A snapshot of the image cache (mcore_image_cache_stats)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub images: usize,
+    /// Images with no references left, kept until the budget needs the room
+    pub released: usize,
+    pub pending: usize,
+    pub bytes: u64,
+    pub released_bytes: u64,
+    pub budget: u64,
+    pub evictions: u64,
+    pub atlas_pages: usize,
+    pub atlas_bytes: u64,
+}
+
+#[derive(Default)]
+struct Registry {
     images: HashMap<i32, ImageEntry>,
+    /// IDs handed out for images still decoding (see decode.rs), with their
+    /// refcounts
+    pending: HashMap<i32, usize>,
     next_id: i32,
+    /// Bumped when a decode finishes or images are evicted, so damage
+    /// tracking redraws frames that drew them
+    generation: u64,
+    /// Decoded bytes to stay under by evicting released images, then mip
+    /// levels, least recently used first. 0 keeps no released images and
+    /// never drops mips, as before budgets existed.
+    budget: u64,
+    clock: u64,
+    evictions: u64,
+    /// Copies of small images, packed to cut per-image uploads
+    atlas: Atlas,
+}
+
+impl Registry {
+    fn bytes(&self) -> u64 {
+        self.images.values().map(ImageEntry::bytes).sum()
+    }
+
+    /// The entry for `id`, stamped as just used
+    fn touch(&mut self, id: i32) -> Option<&mut ImageEntry> {
+        self.clock += 1;
+        let clock = self.clock;
+        let entry = self.images.get_mut(&id)?;
+        entry.last_used = clock;
+        Some(entry)
+    }
+
+    /// Store a new image, packing it into the atlas if it's small
+    fn insert(&mut self, id: i32, entry: ImageEntry) {
+        let image = &entry.image;
+        if matches!((image.alpha_type, image.format), (ImageAlphaType::Alpha, ImageFormat::Rgba8)) {
+            self.atlas.insert(id, image.data.data(), image.width, image.height);
+        }
+        self.images.insert(id, entry);
+        self.touch(id);
+        self.trim();
+    }
+
+    /// IDs of the entries `pick` selects, least recently used first
+    fn by_age(&self, pick: impl Fn(&ImageEntry) -> bool) -> Vec<i32> {
+        let mut ids: Vec<(u64, i32)> = self.images.iter().filter(|&(_, entry)| pick(entry)).map(|(&id, entry)| (entry.last_used, id)).collect();
+        ids.sort_unstable();
+        ids.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Evict released images until no more than `keep` bytes are held;
+    /// returns the bytes freed
+    fn evict_released(&mut self, keep: u64) -> u64 {
+        let mut total = self.bytes();
+        let start = total;
+        for id in self.by_age(|entry| entry.refcount == 0) {
+            if total <= keep {
+                break;
+            }
+            if let Some(entry) = self.images.remove(&id) {
+                self.atlas.remove(id);
+                total -= entry.bytes();
+                self.evictions += 1;
+            }
+        }
+        start - total
+    }
+
+    /// Drop mip levels until no more than `keep` bytes are held; returns the
+    /// bytes freed
+    fn drop_mips(&mut self, keep: u64) -> u64 {
+        let mut total = self.bytes();
+        let start = total;
+        for id in self.by_age(|entry| !entry.mips.is_empty()) {
+            if total <= keep {
+                break;
+            }
+            if let Some(entry) = self.images.get_mut(&id) {
+                total -= entry.mip_bytes();
+                entry.mips = Vec::new();
+            }
+        }
+        start - total
+    }
+
+    /// Bring the cache back under budget
+    fn trim(&mut self) {
+        let freed = if self.budget == 0 {
+            self.evict_released(0)
+        } else {
+            self.evict_released(self.budget) + self.drop_mips(self.budget)
+        };
+        if freed > 0 {
+            self.generation += 1;
+        }
+    }
+}
+
+/// Image manager with reference-counted cache. Managers made with `shared`
+/// see the same images, so contexts of one McoreEngine share image IDs.
+pub struct ImageManager {
+    registry: Arc<Mutex<Registry>>,
+    /// Drawn in place of images still decoding; transparent by default
+    placeholder: Color,
 }
 
 impl ImageManager {
     /// Create a new image manager
     pub fn new() -> Self {
         Self {
-            images: HashMap::new(),
-            next_id: 0,
+            registry: Arc::default(),
+            placeholder: Color::TRANSPARENT,
         }
     }
 
-    /// Load an image from a file path (JPEG, PNG, etc.)
+    /// A manager over the same images
+    pub fn shared(&self) -> Self {
+        Self {
+            registry: self.registry.clone(),
+            placeholder: Color::TRANSPARENT,
+        }
+    }
+
+    /// Load an image from a file path (JPEG, PNG, etc.), shrunk to fit
+    /// `max_dimension` if given
     /// Returns decoded RGBA8 pixels, width, and height
-    pub fn load_image_file(path: impl AsRef<Path>) -> Result<(Vec<u8>, u32, u32), String> {
-        let img = image::open(path)
+    #[cfg(feature = "image-decode")]
+    pub fn load_image_file(path: impl AsRef<Path>, max_dimension: Option<u32>) -> Result<(Vec<u8>, u32, u32), String> {
+        let decoder = image::ImageReader::open(path)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(image::ImageError::IoError)
+            .and_then(|reader| reader.into_decoder())
             .map_err(|e| format!("Failed to load image: {}", e))?;
 
-        let rgba = img.to_rgba8();
-        let (width, height) = rgba.dimensions();
-        let pixels = rgba.into_raw();
-
-        Ok((pixels, width, height))
+        Self::decode_upright(decoder, max_dimension).map_err(|e| format!("Failed to load image: {}", e))
     }
 
-    /// Load an image from bytes (JPEG, PNG, etc.)
+    /// Load an image from bytes (JPEG, PNG, etc.), shrunk to fit
+    /// `max_dimension` if given
     /// Returns decoded RGBA8 pixels, width, and height
-    pub fn load_image_bytes(bytes: &[u8]) -> Result<(Vec<u8>, u32, u32), String> {
-        let img = image::load_from_memory(bytes)
+    #[cfg(feature = "image-decode")]
+    pub fn load_image_bytes(bytes: &[u8], max_dimension: Option<u32>) -> Result<(Vec<u8>, u32, u32), String> {
+        if let Some(missing) = missing_feature(bytes) {
+            return Err(missing.to_string());
+        }
+        let decoder = image::ImageReader::new(std::io::Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(image::ImageError::IoError)
+            .and_then(|reader| reader.into_decoder())
             .map_err(|e| format!("Failed to decode image: {}", e))?;
 
+        Self::decode_upright(decoder, max_dimension).map_err(|e| format!("Failed to decode image: {}", e))
+    }
+
+    /// Phone cameras store pixels as the sensor read them and record how to
+    /// turn them upright in EXIF; apply that so photos don't show sideways.
+    /// Unreadable EXIF leaves the pixels as stored.
+    #[cfg(feature = "image-decode")]
+    fn decode_upright(
+        mut decoder: impl image::ImageDecoder,
+        max_dimension: Option<u32>,
+    ) -> Result<(Vec<u8>, u32, u32), image::ImageError> {
+        let orientation = decoder.orientation().unwrap_or(image::metadata::Orientation::NoTransforms);
+        let img = image::DynamicImage::from_decoder(decoder)?;
+        Ok(Self::into_rgba8(img, max_dimension, orientation))
+    }
+
+    /// Shrink before rotating and converting, so a 12MP photo wanted as a
+    /// thumbnail is never held as full-size RGBA8 past the decode
+    #[cfg(feature = "image-decode")]
+    fn into_rgba8(
+        img: image::DynamicImage,
+        max_dimension: Option<u32>,
+        orientation: image::metadata::Orientation,
+    ) -> (Vec<u8>, u32, u32) {
+        let mut img = match max_dimension.and_then(|max| fit_within(img.width(), img.height(), max)) {
+            Some((width, height)) => img.thumbnail_exact(width, height),
+            None => img,
+        };
+        img.apply_orientation(orientation);
         let rgba = img.to_rgba8();
         let (width, height) = rgba.dimensions();
         let pixels = rgba.into_raw();
 
-        Ok((pixels, width, height))
+        (pixels, width, height)
     }
 
-    /// Convenience: Load and register an image from a file path
-    pub fn register_from_file(&mut self, path: impl AsRef<Path>) -> Result<i32, String> {
-        let (pixels, width, height) = Self::load_image_file(path)?;
-        self.register(&pixels, width, height, ImageFormat::Rgba8, ImageAlphaType::Alpha)
+    /// Encode RGBA8 pixels as a PNG file
+    #[cfg(feature = "image-decode")]
+    pub fn write_png(path: impl AsRef<Path>, rgba: &[u8], width: u32, height: u32) -> Result<(), String> {
+        image::save_buffer_with_format(path, rgba, width, height, image::ColorType::Rgba8, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to write PNG: {}", e))
+    }
+
+    #[cfg(not(feature = "image-decode"))]
+    pub fn write_png(_path: impl AsRef<Path>, _rgba: &[u8], _width: u32, _height: u32) -> Result<(), String> {
+        Err(DECODE_UNAVAILABLE.to_string())
+    }
+
+    #[cfg(not(feature = "image-decode"))]
+    pub fn load_image_file(_path: impl AsRef<Path>, _max_dimension: Option<u32>) -> Result<(Vec<u8>, u32, u32), String> {
+        Err(DECODE_UNAVAILABLE.to_string())
+    }
+
+    #[cfg(not(feature = "image-decode"))]
+    pub fn load_image_bytes(_bytes: &[u8], _max_dimension: Option<u32>) -> Result<(Vec<u8>, u32, u32), String> {
+        Err(DECODE_UNAVAILABLE.to_string())
+    }
+
+    /// Convenience: Load and register an image from a file path, shrunk to
+    /// fit `max_dimension` if given. Returns its ID and upright size.
+    pub fn register_from_file(&mut self, path: impl AsRef<Path>, max_dimension: Option<u32>) -> Result<(i32, u32, u32), String> {
+        let (pixels, width, height) = Self::load_image_file(path, max_dimension)?;
+        let id = self.register(&pixels, width, height, ImageFormat::Rgba8, ImageAlphaType::Alpha)?;
+        Ok((id, width, height))
     }
 
     /// Convenience: Load and register an image from bytes
     pub fn register_from_bytes(&mut self, bytes: &[u8]) -> Result<i32, String> {
-        let (pixels, width, height) = Self::load_image_bytes(bytes)?;
+        let (pixels, width, height) = Self::load_image_bytes(bytes, None)?;
         self.register(&pixels, width, height, ImageFormat::Rgba8, ImageAlphaType::Alpha)
     }
 
+    /// Register host pixel data in any supported layout and alpha encoding
+    pub fn register_pixels(
+        &mut self,
+        pixels: &[u8],
+        width: u32,
+        height: u32,
+        format: SourceFormat,
+        alpha: SourceAlpha,
+    ) -> Result<i32, String> {
+        let expected_len = (width as usize) * (height as usize) * format.bytes_per_pixel();
+        if pixels.len() != expected_len {
+            return Err(format!(
+                "Invalid pixel data length: expected {}, got {}",
+                expected_len,
+                pixels.len()
+            ));
+        }
+        let rgba = to_straight_rgba8(pixels, format, alpha);
+        self.register(&rgba, width, height, ImageFormat::Rgba8, ImageAlphaType::Alpha)
+    }
+
     /// Register a new image from raw pixel data
     /// Returns an image ID or -1 on error
     pub fn register(
@@ -109,25 +518,70 @@ impl ImageManager {
         };
 
         // Store with refcount = 1
-        let id = self.next_id;
-        self.next_id += 1;
-
-        self.images.insert(
-            id,
-            ImageEntry {
-                image,
-                refcount: 1,
-                width,
-                height,
-            },
-        );
+        let mut registry = self.registry.lock();
+        let id = registry.next_id;
+        registry.next_id += 1;
+
+        registry.insert(id, ImageEntry::new(image, 1));
 
         Ok(id)
     }
 
-    /// Increment reference count for an image
+    /// An ID for an image that's still decoding, with refcount 1; draws of
+    /// it show the placeholder until `complete`
+    pub fn reserve(&mut self) -> i32 {
+        let mut registry = self.registry.lock();
+        let id = registry.next_id;
+        registry.next_id += 1;
+        registry.pending.insert(id, 1);
+        id
+    }
+
+    /// Store a reserved image's decoded straight-alpha RGBA8 pixels, or drop
+    /// the ID if decoding failed. Returns the image's size, or None if it
+    /// failed or every reference was released while it decoded.
+    pub fn complete(&self, id: i32, decoded: Result<(Vec<u8>, u32, u32), String>) -> Option<(u32, u32)> {
+        let mut registry = self.registry.lock();
+        let refcount = registry.pending.remove(&id)?;
+        registry.generation += 1;
+        let (pixels, width, height) = decoded.ok()?;
+        let image = ImageData {
+            data: Blob::new(Arc::new(pixels)),
+            format: ImageFormat::Rgba8,
+            width,
+            height,
+            alpha_type: ImageAlphaType::Alpha,
+        };
+        registry.insert(id, ImageEntry::new(image, refcount));
+        Some((width, height))
+    }
+
+    /// Whether `id` was reserved and hasn't finished decoding
+    pub fn is_pending(&self, id: i32) -> bool {
+        self.registry.lock().pending.contains_key(&id)
+    }
+
+    /// Changes whenever a decode finishes
+    pub fn generation(&self) -> u64 {
+        self.registry.lock().generation
+    }
+
+    pub fn placeholder(&self) -> Color {
+        self.placeholder
+    }
+
+    pub fn set_placeholder(&mut self, color: Color) {
+        self.placeholder = color;
+    }
+
+    /// Increment reference count for an image. A released image that's
+    /// still cached can be retained again.
     pub fn retain(&mut self, id: i32) -> Result<(), String> {
-        if let Some(entry) = self.images.get_mut(&id) {
+        let mut registry = self.registry.lock();
+        if let Some(refcount) = registry.pending.get_mut(&id) {
+            *refcount += 1;
+            Ok(())
+        } else if let Some(entry) = registry.touch(id) {
             entry.refcount += 1;
             Ok(())
         } else {
@@ -135,47 +589,140 @@ impl ImageManager {
         }
     }
 
-    /// Decrement reference count, freeing image when count reaches 0
+    /// Decrement reference count; true when no references are left. The
+    /// image then stays cached while the budget has room for it (see
+    /// `set_budget`). An image still decoding is dropped when it finishes.
     pub fn release(&mut self, id: i32) -> Result<bool, String> {
-        if let Some(entry) = self.images.get_mut(&id) {
-            entry.refcount -= 1;
-            if entry.refcount == 0 {
-                self.images.remove(&id);
-                Ok(true) // Image was freed
-            } else {
-                Ok(false) // Image still has references
+        let mut registry = self.registry.lock();
+        if let Some(refcount) = registry.pending.get_mut(&id) {
+            *refcount -= 1;
+            if *refcount == 0 {
+                registry.pending.remove(&id);
+                return Ok(true);
             }
-        } else {
-            Err(format!("Image ID {} not found", id))
+            return Ok(false);
+        }
+        match registry.images.get_mut(&id) {
+            Some(entry) if entry.refcount == 0 => Err(format!("Image ID {} was already released", id)),
+            Some(entry) => {
+                entry.refcount -= 1;
+                let released = entry.refcount == 0;
+                if released {
+                    registry.trim();
+                }
+                Ok(released)
+            }
+            None => Err(format!("Image ID {} not found", id)),
         }
     }
 
-    /// Get an image by ID
-    pub fn get(&self, id: i32) -> Option<&ImageData> {
-        self.images.get(&id).map(|entry| &entry.image)
+    /// Bytes of decoded pixels (mip levels included) to keep; past it,
+    /// released images are evicted, then mip levels, least recently used
+    /// first. Images in use are never evicted. 0 frees released images at
+    /// once and never drops mip levels. Atlas pages aren't counted: they
+    /// never shrink (at most 32 MiB), so evicting can't bring them down.
+    pub fn set_budget(&mut self, bytes: u64) {
+        let mut registry = self.registry.lock();
+        registry.budget = bytes;
+        registry.trim();
+    }
+
+    /// Give memory back when the system runs low; returns the bytes freed
+    pub fn purge(&mut self, pressure: Pressure) -> u64 {
+        let mut registry = self.registry.lock();
+        let mut freed = registry.evict_released(0);
+        if pressure == Pressure::Critical {
+            freed += registry.drop_mips(0);
+        }
+        if freed > 0 {
+            registry.generation += 1;
+        }
+        freed
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let registry = self.registry.lock();
+        let released = registry.images.values().filter(|entry| entry.refcount == 0);
+        CacheStats {
+            images: registry.images.len(),
+            released: released.clone().count(),
+            pending: registry.pending.len(),
+            bytes: registry.bytes(),
+            released_bytes: released.map(ImageEntry::bytes).sum(),
+            budget: registry.budget,
+            evictions: registry.evictions,
+            atlas_pages: registry.atlas.pages(),
+            atlas_bytes: registry.atlas.bytes(),
+        }
+    }
+
+    /// The atlas page holding a copy of image `id`, and where in it
+    pub fn atlas_page(&self, id: i32) -> Option<(ImageData, Slot)> {
+        let mut registry = self.registry.lock();
+        registry.touch(id)?;
+        let slot = registry.atlas.slot(id)?;
+        let page = registry.atlas.page(slot.page)?;
+        Some((page, slot))
+    }
+
+    /// Get an image by ID (a cheap clone: the pixels are shared)
+    pub fn get(&self, id: i32) -> Option<ImageData> {
+        self.registry.lock().touch(id).map(|entry| entry.image.clone())
+    }
+
+    /// The image shrunk `level` times (0 is the image itself), building the
+    /// levels it needs on first use
+    pub fn get_mip(&self, id: i32, level: u32) -> Option<ImageData> {
+        let mut registry = self.registry.lock();
+        let entry = registry.touch(id)?;
+        let built = entry.mips.len() < level as usize;
+        while entry.mips.len() < level as usize {
+            let source = entry.mips.last().unwrap_or(&entry.image);
+            let alpha_type = source.alpha_type;
+            let (pixels, width, height) = downsample(source.data.data(), source.width, source.height);
+            entry.mips.push(ImageData {
+                data: Blob::new(Arc::new(pixels)),
+                format: ImageFormat::Rgba8,
+                width,
+                height,
+                alpha_type,
+            });
+        }
+        let image = match level {
+            0 => entry.image.clone(),
+            _ => entry.mips[level as usize - 1].clone(),
+        };
+        if built {
+            registry.trim();
+        }
+        Some(image)
     }
 
     /// Get image dimensions by ID
     pub fn get_dimensions(&self, id: i32) -> Option<(u32, u32)> {
-        self.images.get(&id).map(|entry| (entry.width, entry.height))
+        self.registry.lock().images.get(&id).map(|entry| (entry.width, entry.height))
     }
 
     /// Get the current reference count for an image
     #[allow(dead_code)]
     pub fn refcount(&self, id: i32) -> Option<usize> {
-        self.images.get(&id).map(|entry| entry.refcount)
+        self.registry.lock().images.get(&id).map(|entry| entry.refcount)
     }
 
     /// Get total number of images in cache
-    #[allow(dead_code)]
     pub fn len(&self) -> usize {
-        self.images.len()
+        self.registry.lock().images.len()
+    }
+
+    /// Decoded pixel bytes held by every image
+    pub fn bytes(&self) -> u64 {
+        self.registry.lock().bytes()
     }
 
     /// Check if cache is empty
     #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
-        self.images.is_empty()
+        self.registry.lock().images.is_empty()
     }
 }
 
@@ -185,6 +732,62 @@ impl Default for ImageManager {
     }
 }
 
+/// Fill `dest` with image `id`, one copy of which covers `tile` (both in the
+/// coordinates `transform` maps to device px); outside the tile the sampler's
+/// extend modes apply. Images still decoding fill `dest` with the
+/// placeholder color. False if the image doesn't exist or is empty.
+pub fn draw(scene: &mut Scene, images: &ImageManager, id: i32, transform: Affine, dest: Rect, tile: Rect, sampler: Sampler) -> bool {
+    if images.is_pending(id) {
+        let placeholder = images.placeholder();
+        if placeholder.components[3] > 0.0 {
+            scene.fill(Fill::NonZero, transform, placeholder, None, &dest);
+        }
+        return true;
+    }
+    let Some((width, height)) = images.get_dimensions(id) else {
+        return false;
+    };
+    if width == 0 || height == 0 || tile.width() <= 0.0 || tile.height() <= 0.0 {
+        return false;
+    }
+    let level = match sampler.sampling {
+        Sampling::Mipmap => {
+            let device_scale = transform.determinant().abs().sqrt();
+            let scale = device_scale * (tile.width() / width as f64).max(tile.height() / height as f64);
+            mip_level(scale, width, height)
+        }
+        _ => 0,
+    };
+    let quality = match sampler.sampling {
+        Sampling::Nearest => ImageQuality::Low,
+        Sampling::Linear | Sampling::Mipmap => ImageQuality::Medium,
+    };
+    // A page can stand in for the image where only its own pixels (and
+    // their gutter) are sampled: one copy, edges not stretched
+    let single_copy = dest.x0 >= tile.x0 && dest.y0 >= tile.y0 && dest.x1 <= tile.x1 && dest.y1 <= tile.y1;
+    if level == 0 && single_copy {
+        if let Some((page, slot)) = images.atlas_page(id) {
+            let fit = Affine::translate((tile.x0, tile.y0))
+                * Affine::scale_non_uniform(tile.width() / slot.width as f64, tile.height() / slot.height as f64)
+                * Affine::translate((-(slot.x as f64), -(slot.y as f64)));
+            let brush = ImageBrush::from(page).with_quality(quality);
+            scene.fill(Fill::NonZero, transform, &brush, Some(fit), &dest);
+            return true;
+        }
+    }
+    let Some(image) = images.get_mip(id, level) else {
+        return false;
+    };
+    let fit = Affine::translate((tile.x0, tile.y0))
+        * Affine::scale_non_uniform(tile.width() / image.width as f64, tile.height() / image.height as f64);
+    let brush = ImageBrush::from(image)
+        .with_quality(quality)
+        .with_x_extend(sampler.extend_x)
+        .with_y_extend(sampler.extend_y);
+    scene.fill(Fill::NonZero, transform, &brush, Some(fit), &dest);
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,4 +859,208 @@ mod tests {
 
         assert!(manager.get(id).is_some());
     }
+
+    #[test]
+    fn test_mip_chain() {
+        // A 3x2 image: opaque red and green columns, a transparent one
+        let pixels = [[255, 0, 0, 255], [0, 255, 0, 255], [9, 9, 9, 0]].repeat(2).concat();
+        let (half, w, h) = downsample(&pixels, 3, 2);
+        assert_eq!((w, h), (1, 1));
+        // Averages the red and green columns; odd edges are left out
+        assert_eq!(half, vec![128, 128, 0, 255]);
+
+        // Transparent pixels don't pull color toward theirs
+        let (half, _, _) = downsample(&[[200, 100, 50, 255], [0, 0, 0, 0]].repeat(2).concat(), 2, 2);
+        assert_eq!(half, vec![200, 100, 50, 128]);
+
+        assert_eq!(mip_level(1.0, 1024, 1024), 0);
+        assert_eq!(mip_level(0.5, 1024, 1024), 1);
+        assert_eq!(mip_level(0.3, 1024, 1024), 1);
+        assert_eq!(mip_level(0.01, 1024, 1024), 6);
+        assert_eq!(mip_level(0.0001, 4, 2), 2, "stops at 1px");
+
+        let mut manager = ImageManager::new();
+        let id = manager.register(&create_test_pixels(4, 4), 4, 4, ImageFormat::Rgba8, ImageAlphaType::Alpha).unwrap();
+        let mip = manager.get_mip(id, 2).unwrap();
+        assert_eq!((mip.width, mip.height), (1, 1));
+        assert_eq!(manager.bytes(), 4 * 4 * 4 + 2 * 2 * 4 + 4);
+    }
+
+    #[cfg(feature = "image-decode")]
+    #[test]
+    fn test_formats_behind_features_are_named() {
+        let webp = b"RIFF\0\0\0\0WEBPVP8L";
+        let avif = b"\0\0\0\x1cftypavif\0\0\0\0";
+        assert_eq!(missing_feature(webp).is_some(), !cfg!(feature = "webp"));
+        assert_eq!(missing_feature(avif).is_some(), !cfg!(feature = "avif"));
+        assert_eq!(missing_feature(b"\x89PNG\r\n\x1a\n\0\0\0\0"), None);
+    }
+
+    #[cfg(feature = "image-decode")]
+    #[test]
+    fn test_exif_orientation_applied_after_shrinking() {
+        use image::metadata::Orientation;
+        // 4x2, left half red, right half blue, stored as a sensor reads it
+        let mut stored = image::RgbaImage::new(4, 2);
+        for (x, _, pixel) in stored.enumerate_pixels_mut() {
+            *pixel = if x < 2 { image::Rgba([255, 0, 0, 255]) } else { image::Rgba([0, 0, 255, 255]) };
+        }
+        let stored = image::DynamicImage::ImageRgba8(stored);
+
+        // A 90 degree clockwise turn puts red on top
+        let (pixels, width, height) = ImageManager::into_rgba8(stored.clone(), None, Orientation::Rotate90);
+        assert_eq!((width, height), (2, 4));
+        assert_eq!(&pixels[..4], &[255, 0, 0, 255]);
+        assert_eq!(&pixels[pixels.len() - 4..], &[0, 0, 255, 255]);
+
+        // The longest side still fits the thumbnail size once upright
+        let (_, width, height) = ImageManager::into_rgba8(stored, Some(2), Orientation::Rotate270);
+        assert_eq!((width, height), (1, 2));
+    }
+
+    #[test]
+    fn test_fit_within() {
+        // A 12MP photo as a 512px thumbnail
+        assert_eq!(fit_within(4032, 3024, 512), Some((512, 384)));
+        assert_eq!(fit_within(3024, 4032, 512), Some((384, 512)));
+        assert_eq!(fit_within(10000, 10, 100), Some((100, 1)));
+        assert_eq!(fit_within(400, 300, 512), None, "never enlarged");
+        assert_eq!(fit_within(4032, 3024, 0), None, "0 is no limit");
+    }
+
+    #[test]
+    fn test_reserved_images() {
+        let mut manager = ImageManager::new();
+        let id = manager.reserve();
+        assert!(manager.is_pending(id));
+        assert!(manager.get(id).is_none());
+        manager.retain(id).unwrap();
+
+        let generation = manager.generation();
+        assert_eq!(manager.complete(id, Ok((create_test_pixels(2, 1), 2, 1))), Some((2, 1)));
+        assert!(!manager.is_pending(id));
+        assert_ne!(manager.generation(), generation);
+        // The retain made while decoding carries over
+        assert_eq!(manager.refcount(id), Some(2));
+        assert_eq!(manager.get_dimensions(id), Some((2, 1)));
+
+        // Failed decodes and images released while decoding free their IDs
+        let failed = manager.reserve();
+        assert_eq!(manager.complete(failed, Err("corrupt".into())), None);
+        assert!(manager.retain(failed).is_err());
+        let released = manager.reserve();
+        assert!(manager.release(released).unwrap());
+        assert_eq!(manager.complete(released, Ok((create_test_pixels(1, 1), 1, 1))), None);
+        assert!(manager.get(released).is_none());
+    }
+
+    #[test]
+    fn test_budget_evicts_released_images_oldest_first() {
+        let mut manager = ImageManager::new();
+        // 16 bytes each
+        let register = |manager: &mut ImageManager| manager.register(&create_test_pixels(2, 2), 2, 2, ImageFormat::Rgba8, ImageAlphaType::Alpha).unwrap();
+        manager.set_budget(40);
+        let [a, b, c] = [(); 3].map(|_| register(&mut manager));
+        assert_eq!(manager.stats().bytes, 48, "images in use are never evicted");
+
+        assert!(manager.release(a).unwrap());
+        assert!(manager.release(b).unwrap());
+        assert!(manager.get(a).is_none(), "oldest released image evicted first");
+        assert!(manager.get(b).is_some(), "released image kept within budget");
+        assert!(manager.release(b).is_err(), "already released");
+
+        // A cached image can be picked up again
+        manager.retain(b).unwrap();
+        assert_eq!(manager.refcount(b), Some(1));
+        manager.release(b).unwrap();
+        let stats = manager.stats();
+        assert_eq!((stats.images, stats.released, stats.released_bytes, stats.evictions), (2, 1, 16, 1));
+
+        // Mip levels go once no released images are left to evict
+        manager.get_mip(c, 1).unwrap();
+        assert_eq!(manager.bytes(), 16 + 16 + 4);
+        manager.set_budget(20);
+        assert!(manager.get(b).is_none());
+        assert_eq!(manager.bytes(), 20);
+        manager.set_budget(8);
+        assert_eq!(manager.bytes(), 16);
+
+        manager.set_budget(0);
+        manager.get_mip(c, 1).unwrap();
+        let d = register(&mut manager);
+        manager.release(d).unwrap();
+        assert!(manager.get(d).is_none(), "no budget: released images are freed at once");
+        assert_eq!(manager.purge(Pressure::Warning), 0);
+        assert_eq!(manager.purge(Pressure::Critical), 4);
+        assert_eq!(manager.bytes(), 16);
+    }
+
+    /// Straight-alpha `src` over an opaque `dst`, as the compositor blends it
+    fn composite_over(src: [u8; 4], dst: [u8; 3]) -> [u8; 3] {
+        let a = src[3] as u32;
+        std::array::from_fn(|i| ((src[i] as u32 * a + dst[i] as u32 * (255 - a) + 127) / 255) as u8)
+    }
+
+    #[test]
+    fn test_premultiply_round_trip() {
+        let straight = vec![255, 128, 0, 128, 10, 20, 30, 255, 200, 100, 50, 0];
+        let mut premul = straight.clone();
+        premultiply(&mut premul);
+        assert_eq!(premul, vec![128, 64, 0, 128, 10, 20, 30, 255, 0, 0, 0, 0]);
+
+        unpremultiply(&mut premul);
+        // Opaque pixels survive exactly, transparent ones lose their color
+        assert_eq!(&premul[4..8], &straight[4..8]);
+        assert_eq!(&premul[8..], &[0, 0, 0, 0]);
+        for (a, b) in premul[..3].iter().zip(&straight[..3]) {
+            assert!(a.abs_diff(*b) <= 1);
+        }
+    }
+
+    #[test]
+    fn test_source_conversion() {
+        let rgb = [1, 2, 3, 4, 5, 6];
+        assert_eq!(to_straight_rgba8(&rgb, SourceFormat::Rgb8, SourceAlpha::Opaque), vec![1, 2, 3, 255, 4, 5, 6, 255]);
+
+        let ignored_alpha = [9, 9, 9, 0];
+        assert_eq!(to_straight_rgba8(&ignored_alpha, SourceFormat::Rgba8, SourceAlpha::Opaque), vec![9, 9, 9, 255]);
+
+        let mut manager = ImageManager::new();
+        assert!(manager.register_pixels(&rgb, 2, 1, SourceFormat::Rgb8, SourceAlpha::Opaque).is_ok());
+        assert!(manager.register_pixels(&rgb, 2, 1, SourceFormat::Rgba8, SourceAlpha::Straight).is_err());
+    }
+
+    /// Golden values for a translucent avatar edge over white: straight and
+    /// premultiplied sources must composite identically, with no darkening
+    #[test]
+    fn test_translucent_compositing_golden() {
+        let white = [255, 255, 255];
+        let cases: [([u8; 4], [u8; 3]); 4] = [
+            ([255, 0, 0, 128], [255, 127, 127]),
+            ([0, 0, 0, 64], [191, 191, 191]),
+            ([255, 255, 255, 32], [255, 255, 255]),
+            ([40, 80, 160, 255], [40, 80, 160]),
+        ];
+        for (straight, expected) in cases {
+            let mut premul = straight.to_vec();
+            premultiply(&mut premul);
+            let from_premul = to_straight_rgba8(&premul, SourceFormat::Rgba8, SourceAlpha::Premultiplied);
+            let from_straight = to_straight_rgba8(&straight, SourceFormat::Rgba8, SourceAlpha::Straight);
+
+            assert_eq!(composite_over(straight, white), expected);
+            let from_premul: [u8; 4] = from_premul.try_into().unwrap();
+            let from_straight: [u8; 4] = from_straight.try_into().unwrap();
+            for (a, b) in composite_over(from_premul, white).iter().zip(&expected) {
+                assert!(a.abs_diff(*b) <= 1, "premultiplied {straight:?} composited to {a}, expected {b}");
+            }
+            assert_eq!(composite_over(from_straight, white), expected);
+
+            // Treating premultiplied data as straight (the old bug) darkens edges
+            if straight[3] < 255 && straight[..3] != [0, 0, 0] {
+                let wrong = composite_over(premul.try_into().unwrap(), white);
+                let luma = |px: [u8; 3]| px.iter().map(|c| *c as u32).sum::<u32>();
+                assert!(luma(wrong) < luma(expected));
+            }
+        }
+    }
 }
@@ -0,0 +1,176 @@
+/// Clip layer balancing
+///
+/// Every host clip (mcore_push_clip_*, clip commands in either command
+/// buffer) opens a Vello layer that must be closed within the same scene. A
+/// pop with nothing open, or a push still open when the frame is presented,
+/// would corrupt the rest of the encoding, so the engine tracks the open
+/// layers: pops past zero are dropped, and layers still open at
+/// mcore_end_frame_present (or mcore_fragment_end) are closed for the host.
+/// Each correction counts as a warning in mcore_frame_stats.
+///
+/// The clips themselves are kept too: when the frame's scene is cut (blur and
+/// color filter layers), the open ones are closed in the part before the cut
+/// and reopened in the part after it.
+
+use peniko::kurbo::{Affine, BezPath, Rect, RoundedRect};
+use peniko::BlendMode;
+use vello::Scene;
+
+#[derive(Clone, Debug)]
+pub enum ClipShape {
+    Rect(Rect),
+    RoundedRect(RoundedRect),
+    Path(BezPath),
+}
+
+impl From<Rect> for ClipShape {
+    fn from(rect: Rect) -> Self {
+        Self::Rect(rect)
+    }
+}
+
+impl From<RoundedRect> for ClipShape {
+    fn from(rect: RoundedRect) -> Self {
+        Self::RoundedRect(rect)
+    }
+}
+
+impl From<BezPath> for ClipShape {
+    fn from(path: BezPath) -> Self {
+        Self::Path(path)
+    }
+}
+
+/// An open layer's clip, as it was pushed
+#[derive(Clone, Debug)]
+pub struct Clip {
+    transform: Affine,
+    shape: ClipShape,
+}
+
+impl Clip {
+    /// Open the layer again in `scene`
+    pub fn push_to(&self, scene: &mut Scene) {
+        let blend = BlendMode::default();
+        match &self.shape {
+            ClipShape::Rect(rect) => scene.push_layer(blend, 1.0, self.transform, rect),
+            ClipShape::RoundedRect(rect) => scene.push_layer(blend, 1.0, self.transform, rect),
+            ClipShape::Path(path) => scene.push_layer(blend, 1.0, self.transform, path),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LayerStack {
+    /// Host layers open in the current scene, outermost first
+    open: Vec<Clip>,
+    /// The frame's layers, parked while a fragment is being recorded
+    stashed: Option<Vec<Clip>>,
+    /// Corrections made this frame
+    warnings: u32,
+}
+
+impl LayerStack {
+    pub fn begin_frame(&mut self) {
+        self.open.clear();
+        self.warnings = 0;
+    }
+
+    /// Record a layer the caller pushed with this clip
+    pub fn push(&mut self, transform: Affine, shape: impl Into<ClipShape>) {
+        self.open.push(Clip { transform, shape: shape.into() });
+    }
+
+    /// Whether there's a layer to pop; an unbalanced pop is counted and the
+    /// caller must skip it
+    pub fn pop(&mut self) -> bool {
+        if self.open.pop().is_none() {
+            self.warnings += 1;
+            return false;
+        }
+        true
+    }
+
+    /// Layers left open in the current scene, counted as warnings; the caller
+    /// pops them
+    pub fn finish(&mut self) -> u32 {
+        let open = std::mem::take(&mut self.open).len() as u32;
+        self.warnings += open;
+        open
+    }
+
+    /// A fragment recording starts with no layers open
+    pub fn begin_fragment(&mut self) {
+        self.stashed = Some(std::mem::take(&mut self.open));
+    }
+
+    /// Layers left open in the fragment (the caller pops them before
+    /// restoring the frame scene); the frame's layers are back in effect
+    pub fn end_fragment(&mut self) -> u32 {
+        let open = self.finish();
+        self.open = self.stashed.take().unwrap_or_default();
+        open
+    }
+
+    /// Host layers open in the current scene
+    pub fn depth(&self) -> u32 {
+        self.open.len() as u32
+    }
+
+    /// The open layers' clips, outermost first
+    pub fn clips(&self) -> &[Clip] {
+        &self.open
+    }
+
+    pub fn warnings(&self) -> u32 {
+        self.warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(layers: &mut LayerStack) {
+        layers.push(Affine::IDENTITY, Rect::new(0.0, 0.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn unbalanced_pops_and_leftovers_are_counted() {
+        let mut layers = LayerStack::default();
+        push(&mut layers);
+        assert!(layers.pop());
+        assert!(!layers.pop(), "nothing open");
+        push(&mut layers);
+        push(&mut layers);
+
+        layers.begin_fragment();
+        assert!(!layers.pop(), "the frame's layers aren't the fragment's to pop");
+        push(&mut layers);
+        assert_eq!(layers.end_fragment(), 1);
+
+        assert!(layers.pop());
+        assert_eq!(layers.finish(), 1);
+        assert_eq!(layers.warnings(), 4);
+
+        layers.begin_frame();
+        assert_eq!(layers.warnings(), 0);
+        assert_eq!(layers.finish(), 0);
+    }
+
+    #[test]
+    fn clips_are_kept_in_push_order() {
+        let mut layers = LayerStack::default();
+        push(&mut layers);
+        let round = RoundedRect::new(1.0, 2.0, 3.0, 4.0, 0.5);
+        layers.push(Affine::translate((5.0, 0.0)), round);
+        assert_eq!(layers.depth(), 2);
+        assert!(matches!(layers.clips()[1].shape, ClipShape::RoundedRect(r) if r == round));
+        assert_eq!(layers.clips()[1].transform, Affine::translate((5.0, 0.0)));
+
+        layers.begin_fragment();
+        assert!(layers.clips().is_empty());
+        layers.end_fragment();
+        assert_eq!(layers.depth(), 2, "the frame's clips come back");
+    }
+}
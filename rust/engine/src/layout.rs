@@ -0,0 +1,337 @@
+/// Flexbox layout (mcore_layout_*, layout feature)
+///
+/// Hosts mirror their view tree as nodes keyed by their own IDs, give each
+/// node flex properties, and read back the rects Taffy resolves, so layout
+/// math isn't rewritten in every host language. Leaves aren't measured: size
+/// text leaves with mcore_text_measure. Rects are in logical pixels, relative
+/// to the root passed to compute, which fills the space it's given.
+
+use std::collections::HashMap;
+use taffy::prelude::*;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Direction {
+    #[default]
+    Row,
+    Column,
+    RowReverse,
+    ColumnReverse,
+}
+
+/// Main-axis distribution (justify-content)
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Justify {
+    #[default]
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+/// Cross-axis alignment (align-items, align-self)
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Align {
+    #[default]
+    Stretch,
+    Start,
+    End,
+    Center,
+    Baseline,
+}
+
+/// Edge sizes in CSS order
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Edges {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+/// A node's flex properties; None sizes are auto
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FlexStyle {
+    pub direction: Direction,
+    pub wrap: bool,
+    pub justify: Justify,
+    pub align_items: Align,
+    /// None follows the parent's align_items
+    pub align_self: Option<Align>,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub min_width: Option<f32>,
+    pub min_height: Option<f32>,
+    pub max_width: Option<f32>,
+    pub max_height: Option<f32>,
+    pub grow: f32,
+    pub shrink: f32,
+    pub basis: Option<f32>,
+    pub padding: Edges,
+    pub margin: Edges,
+    /// Space between rows and between columns
+    pub row_gap: f32,
+    pub column_gap: f32,
+    /// Takes no space and gets an empty rect
+    pub hidden: bool,
+}
+
+impl Default for FlexStyle {
+    fn default() -> Self {
+        Self {
+            direction: Direction::Row,
+            wrap: false,
+            justify: Justify::Start,
+            align_items: Align::Stretch,
+            align_self: None,
+            width: None,
+            height: None,
+            min_width: None,
+            min_height: None,
+            max_width: None,
+            max_height: None,
+            grow: 0.0,
+            shrink: 1.0,
+            basis: None,
+            padding: Edges::default(),
+            margin: Edges::default(),
+            row_gap: 0.0,
+            column_gap: 0.0,
+            hidden: false,
+        }
+    }
+}
+
+fn dimension(value: Option<f32>) -> Dimension {
+    value.map_or(auto(), length)
+}
+
+fn align(value: Align) -> AlignItems {
+    match value {
+        Align::Stretch => AlignItems::Stretch,
+        Align::Start => AlignItems::FlexStart,
+        Align::End => AlignItems::FlexEnd,
+        Align::Center => AlignItems::Center,
+        Align::Baseline => AlignItems::Baseline,
+    }
+}
+
+impl FlexStyle {
+    fn to_taffy(self) -> Style {
+        Style {
+            display: if self.hidden { Display::None } else { Display::Flex },
+            flex_direction: match self.direction {
+                Direction::Row => FlexDirection::Row,
+                Direction::Column => FlexDirection::Column,
+                Direction::RowReverse => FlexDirection::RowReverse,
+                Direction::ColumnReverse => FlexDirection::ColumnReverse,
+            },
+            flex_wrap: if self.wrap { FlexWrap::Wrap } else { FlexWrap::NoWrap },
+            justify_content: Some(match self.justify {
+                Justify::Start => JustifyContent::FlexStart,
+                Justify::End => JustifyContent::FlexEnd,
+                Justify::Center => JustifyContent::Center,
+                Justify::SpaceBetween => JustifyContent::SpaceBetween,
+                Justify::SpaceAround => JustifyContent::SpaceAround,
+                Justify::SpaceEvenly => JustifyContent::SpaceEvenly,
+            }),
+            align_items: Some(align(self.align_items)),
+            align_self: self.align_self.map(align),
+            size: Size { width: dimension(self.width), height: dimension(self.height) },
+            min_size: Size { width: dimension(self.min_width), height: dimension(self.min_height) },
+            max_size: Size { width: dimension(self.max_width), height: dimension(self.max_height) },
+            flex_grow: self.grow,
+            flex_shrink: self.shrink,
+            flex_basis: dimension(self.basis),
+            padding: Rect {
+                top: length(self.padding.top),
+                right: length(self.padding.right),
+                bottom: length(self.padding.bottom),
+                left: length(self.padding.left),
+            },
+            margin: Rect {
+                top: length(self.margin.top),
+                right: length(self.margin.right),
+                bottom: length(self.margin.bottom),
+                left: length(self.margin.left),
+            },
+            gap: Size { width: length(self.column_gap), height: length(self.row_gap) },
+            ..Style::default()
+        }
+    }
+}
+
+/// A resolved node rect
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LayoutRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+pub struct LayoutTree {
+    taffy: TaffyTree,
+    nodes: HashMap<u64, NodeId>,
+    /// Rects from the last compute of each node's root
+    rects: HashMap<u64, LayoutRect>,
+}
+
+impl LayoutTree {
+    pub fn new() -> Self {
+        Self {
+            taffy: TaffyTree::new(),
+            nodes: HashMap::new(),
+            rects: HashMap::new(),
+        }
+    }
+
+    /// Create a node or replace its style
+    pub fn set_node(&mut self, id: u64, style: FlexStyle) -> Result<(), String> {
+        let style = style.to_taffy();
+        match self.nodes.get(&id) {
+            Some(&node) => self.taffy.set_style(node, style).map_err(|e| e.to_string()),
+            None => {
+                let node = self.taffy.new_leaf(style).map_err(|e| e.to_string())?;
+                self.nodes.insert(id, node);
+                Ok(())
+            }
+        }
+    }
+
+    fn node(&self, id: u64) -> Result<NodeId, String> {
+        self.nodes.get(&id).copied().ok_or_else(|| format!("Layout node {id} not found"))
+    }
+
+    /// Replace a node's children, in order. Children move out of their
+    /// previous parent.
+    pub fn set_children(&mut self, id: u64, children: &[u64]) -> Result<(), String> {
+        let parent = self.node(id)?;
+        let children = children.iter().map(|&child| self.node(child)).collect::<Result<Vec<_>, _>>()?;
+
+        // A node can't contain itself or one of its ancestors
+        let mut ancestor = Some(parent);
+        while let Some(node) = ancestor {
+            if children.contains(&node) {
+                return Err(format!("Layout node {id} can't contain itself or its ancestors"));
+            }
+            ancestor = self.taffy.parent(node);
+        }
+
+        for &child in &children {
+            if let Some(old) = self.taffy.parent(child).filter(|&old| old != parent) {
+                self.taffy.remove_child(old, child).map_err(|e| e.to_string())?;
+            }
+        }
+        self.taffy.set_children(parent, &children).map_err(|e| e.to_string())
+    }
+
+    /// Remove a node; its children are left without a parent
+    pub fn remove(&mut self, id: u64) {
+        if let Some(node) = self.nodes.remove(&id) {
+            let _ = self.taffy.remove(node);
+            self.rects.remove(&id);
+        }
+    }
+
+    /// Lay out the tree under `root` at exactly `width` x `height` and record
+    /// every node's rect relative to the root
+    pub fn compute(&mut self, root: u64, width: f32, height: f32) -> Result<(), String> {
+        let root_node = self.node(root)?;
+
+        // The root fills the space whatever its own size; its style is put
+        // back afterwards so it can still be nested later
+        let host_style = self.taffy.style(root_node).map_err(|e| e.to_string())?.clone();
+        let root_style = Style { size: Size { width: length(width), height: length(height) }, ..host_style.clone() };
+        self.taffy.set_style(root_node, root_style).map_err(|e| e.to_string())?;
+        let space = Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) };
+        let computed = self.taffy.compute_layout(root_node, space).map_err(|e| e.to_string());
+        self.taffy.set_style(root_node, host_style).map_err(|e| e.to_string())?;
+        computed?;
+
+        let ids: HashMap<NodeId, u64> = self.nodes.iter().map(|(&id, &node)| (node, id)).collect();
+        let mut stack = vec![(root_node, 0.0, 0.0)];
+        while let Some((node, parent_x, parent_y)) = stack.pop() {
+            let layout = self.taffy.layout(node).map_err(|e| e.to_string())?;
+            let (x, y) = if node == root_node {
+                (0.0, 0.0)
+            } else {
+                (parent_x + layout.location.x, parent_y + layout.location.y)
+            };
+            if let Some(&id) = ids.get(&node) {
+                self.rects.insert(id, LayoutRect { x, y, width: layout.size.width, height: layout.size.height });
+            }
+            for child in self.taffy.children(node).map_err(|e| e.to_string())? {
+                stack.push((child, x, y));
+            }
+        }
+        Ok(())
+    }
+
+    /// The node's rect from the last compute, None before one
+    pub fn rect(&self, id: u64) -> Option<LayoutRect> {
+        self.rects.get(&id).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(width: f32, height: f32) -> FlexStyle {
+        FlexStyle { width: Some(width), height: Some(height), ..FlexStyle::default() }
+    }
+
+    #[test]
+    fn test_row_with_padding_gap_and_grow() {
+        let mut tree = LayoutTree::new();
+        let padding = Edges { top: 10.0, right: 10.0, bottom: 10.0, left: 10.0 };
+        tree.set_node(1, FlexStyle { padding, column_gap: 5.0, ..FlexStyle::default() }).unwrap();
+        tree.set_node(2, fixed(50.0, 20.0)).unwrap();
+        tree.set_node(3, FlexStyle { grow: 1.0, ..FlexStyle::default() }).unwrap();
+        tree.set_children(1, &[2, 3]).unwrap();
+        tree.compute(1, 300.0, 100.0).unwrap();
+
+        assert_eq!(tree.rect(1), Some(LayoutRect { x: 0.0, y: 0.0, width: 300.0, height: 100.0 }));
+        assert_eq!(tree.rect(2), Some(LayoutRect { x: 10.0, y: 10.0, width: 50.0, height: 20.0 }));
+        // Grows into the rest of the row and stretches to the cross size
+        assert_eq!(tree.rect(3), Some(LayoutRect { x: 65.0, y: 10.0, width: 225.0, height: 80.0 }));
+    }
+
+    #[test]
+    fn test_nested_rects_are_relative_to_the_root() {
+        let mut tree = LayoutTree::new();
+        tree.set_node(1, FlexStyle { direction: Direction::Column, justify: Justify::Center, ..FlexStyle::default() }).unwrap();
+        tree.set_node(2, FlexStyle { height: Some(40.0), align_items: Align::Center, justify: Justify::End, ..FlexStyle::default() }).unwrap();
+        tree.set_node(3, fixed(20.0, 10.0)).unwrap();
+        tree.set_children(1, &[2]).unwrap();
+        tree.set_children(2, &[3]).unwrap();
+        tree.compute(1, 200.0, 100.0).unwrap();
+
+        assert_eq!(tree.rect(2), Some(LayoutRect { x: 0.0, y: 30.0, width: 200.0, height: 40.0 }));
+        assert_eq!(tree.rect(3), Some(LayoutRect { x: 180.0, y: 45.0, width: 20.0, height: 10.0 }));
+
+        // Reparenting moves the node out of its old parent
+        tree.set_children(1, &[2, 3]).unwrap();
+        tree.compute(1, 200.0, 100.0).unwrap();
+        assert_eq!(tree.rect(2).unwrap().y, 25.0);
+        assert_eq!(tree.rect(3).unwrap().y, 65.0);
+    }
+
+    #[test]
+    fn test_rejects_cycles_and_unknown_nodes() {
+        let mut tree = LayoutTree::new();
+        tree.set_node(1, FlexStyle::default()).unwrap();
+        tree.set_node(2, FlexStyle::default()).unwrap();
+        tree.set_children(1, &[2]).unwrap();
+        assert!(tree.set_children(2, &[1]).is_err());
+        assert!(tree.set_children(1, &[1]).is_err());
+        assert!(tree.set_children(1, &[9]).is_err());
+        assert!(tree.compute(9, 10.0, 10.0).is_err());
+
+        tree.remove(2);
+        tree.compute(1, 10.0, 10.0).unwrap();
+        assert_eq!(tree.rect(2), None);
+    }
+}
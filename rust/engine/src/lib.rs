@@ -1,35 +1,430 @@
 use parking_lot::Mutex;
 use peniko::{Blob, Color, FontData};
-use std::ffi::{c_void, CStr};
+use std::ffi::{c_void, CStr, CString};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use vello::Scene;
 
 // Import color types for CSS parsing and interpolation
-use peniko::color::{AlphaColor, Srgb, Oklab, DynamicColor};
+use peniko::color::{AlphaColor, DisplayP3, Srgb, Oklab, DynamicColor};
 
+use engine::{fire_redraw, fire_scroll_edges, Deferred, Engine, LogLevel, DEFAULT_REFRESH_RATE};
+
+mod engine;
 mod gfx;
 mod text;
 mod text_input;
+mod text_block;
+mod code;
+mod text_window;
+#[cfg(feature = "a11y")]
 mod a11y;
+#[cfg(feature = "layout")]
+mod layout;
+#[cfg(all(test, target_pointer_width = "64"))]
+mod abi_layout;
+#[cfg(test)]
+mod fuzz;
+#[cfg(test)]
+mod soak;
+mod cache;
 mod image;
+mod atlas;
+mod decode;
+mod path;
+mod gradient;
+mod log_view;
+mod commands;
+mod command_stream;
+mod filter;
+mod fragment;
+mod prefs;
+mod gesture;
+mod hit;
+mod scroll;
+mod schedule;
+mod custom;
+mod video;
+mod clipboard;
+mod cursor;
+mod color_space;
+mod crash;
+mod particles;
+pub mod replay;
+mod trace;
+mod watchdog;
+mod damage;
+mod gpu_timer;
+mod overlay;
+mod render_thread;
+mod layers;
+mod blur;
+mod ui;
+
+use trace::trace_call;
+
+/// What kind of failure the last error on a thread was (mcore_last_error_code)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum McoreErrorCode {
+    None = 0,
+    /// Anything not covered below; see the message
+    Other = 1,
+    /// A null pointer, out-of-range value or malformed buffer
+    InvalidArg = 2,
+    /// No object (image, path, gradient...) with the given ID
+    NotFound = 3,
+    /// The call isn't valid right now, e.g. drawing outside a frame
+    InvalidState = 4,
+    /// wgpu failed: adapter or device creation, readback, a lost device
+    Wgpu = 5,
+    /// Vello failed to render or build its pipelines
+    Vello = 6,
+    /// The window's surface is lost or out of date; resize or recreate it
+    SurfaceLost = 7,
+    OutOfMemory = 8,
+    /// A string argument wasn't valid UTF-8
+    Utf8 = 9,
+    /// Reading or writing a file failed
+    Io = 10,
+    /// A format, platform or feature this build doesn't support
+    Unsupported = 11,
+    /// The engine panicked inside the call (a bug); the call returned its
+    /// failure value
+    Panic = 12,
+    /// The host was built against an API version this engine can't serve
+    VersionMismatch = 13,
+}
+
+impl McoreErrorCode {
+    fn from_u32(code: u32) -> Option<Self> {
+        Some(match code {
+            0 => Self::None,
+            1 => Self::Other,
+            2 => Self::InvalidArg,
+            3 => Self::NotFound,
+            4 => Self::InvalidState,
+            5 => Self::Wgpu,
+            6 => Self::Vello,
+            7 => Self::SurfaceLost,
+            8 => Self::OutOfMemory,
+            9 => Self::Utf8,
+            10 => Self::Io,
+            11 => Self::Unsupported,
+            12 => Self::Panic,
+            13 => Self::VersionMismatch,
+            _ => return None,
+        })
+    }
+
+    /// Fixed description of the code (mcore_error_message)
+    fn message(self) -> &'static CStr {
+        match self {
+            Self::None => c"no error",
+            Self::Other => c"error",
+            Self::InvalidArg => c"invalid argument",
+            Self::NotFound => c"no object with that ID",
+            Self::InvalidState => c"call not valid in the current state",
+            Self::Wgpu => c"GPU (wgpu) error",
+            Self::Vello => c"renderer (Vello) error",
+            Self::SurfaceLost => c"surface lost or outdated",
+            Self::OutOfMemory => c"out of memory",
+            Self::Utf8 => c"string is not valid UTF-8",
+            Self::Io => c"file I/O error",
+            Self::Unsupported => c"not supported",
+            Self::Panic => c"internal error (the engine panicked)",
+            Self::VersionMismatch => c"host and engine were built against incompatible API versions",
+        }
+    }
+}
+
+impl gfx::GfxError {
+    fn code(&self) -> McoreErrorCode {
+        match self {
+            Self::Wgpu(_) => McoreErrorCode::Wgpu,
+            Self::InvalidSurface => McoreErrorCode::InvalidArg,
+            Self::Vello(_) => McoreErrorCode::Vello,
+            Self::SurfaceLost(_) => McoreErrorCode::SurfaceLost,
+            Self::OutOfMemory => McoreErrorCode::OutOfMemory,
+        }
+    }
+}
 
 thread_local! {
-    static LAST_ERROR: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+    static LAST_ERROR: std::cell::RefCell<Option<(McoreErrorCode, String)>> = const { std::cell::RefCell::new(None) };
+    /// Backing storage for the pointer mcore_last_error hands out
+    static LAST_ERROR_C: std::cell::RefCell<Option<CString>> = const { std::cell::RefCell::new(None) };
 }
 fn set_err(e: impl std::fmt::Display) {
-    LAST_ERROR.with(|s| *s.borrow_mut() = Some(e.to_string()));
+    set_err_code(McoreErrorCode::Other, e);
+}
+fn set_err_code(code: McoreErrorCode, e: impl std::fmt::Display) {
+    LAST_ERROR.with(|s| *s.borrow_mut() = Some((code, e.to_string())));
+}
+fn set_gfx_err(e: &gfx::GfxError) {
+    set_err_code(e.code(), e);
+}
+
+/// What an entry point returns when it fails: a null argument or a panic
+trait FailureValue {
+    fn failure() -> Self;
+}
+
+impl FailureValue for () {
+    fn failure() -> Self {}
+}
+
+impl FailureValue for McoreStatus {
+    fn failure() -> Self {
+        McoreStatus::Err
+    }
+}
+
+impl FailureValue for McoreErrorCode {
+    fn failure() -> Self {
+        McoreErrorCode::Panic
+    }
+}
+
+impl FailureValue for McoreRenderMode {
+    fn failure() -> Self {
+        McoreRenderMode::Gpu
+    }
+}
+
+impl FailureValue for McoreImageInfo {
+    fn failure() -> Self {
+        McoreImageInfo { image_id: -1, width: 0, height: 0 }
+    }
+}
+
+/// IDs and counts: -1 is the usual failure value for IDs
+impl FailureValue for i32 {
+    fn failure() -> Self {
+        -1
+    }
+}
+
+macro_rules! panic_value_zero {
+    ($($ty:ty),*) => {
+        $(impl FailureValue for $ty {
+            fn failure() -> Self {
+                0 as $ty
+            }
+        })*
+    };
+}
+panic_value_zero!(u8, u32, u64, usize, f32, f64);
+
+impl<T> FailureValue for *mut T {
+    fn failure() -> Self {
+        std::ptr::null_mut()
+    }
+}
+
+impl<T> FailureValue for *const T {
+    fn failure() -> Self {
+        std::ptr::null()
+    }
+}
+
+/// Run an entry point's body, catching panics: unwinding into C is undefined
+/// behavior, so a panic becomes an MCORE_ERROR_PANIC error and the call's
+/// failure value. The engine lock doesn't poison; state the panicking call
+/// was changing may be left half-updated.
+fn ffi_boundary<R: FailureValue>(name: &str, body: impl FnOnce() -> R) -> R {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)) {
+        Ok(value) => value,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "non-string panic payload".into());
+            set_err_code(McoreErrorCode::Panic, format!("{name} panicked: {message}"));
+            R::failure()
+        }
+    }
 }
+
+/// Fail a call given a null pointer
+fn null_arg<R: FailureValue>(call: &str, arg: &str) -> R {
+    set_err_code(McoreErrorCode::InvalidArg, format!("Null {arg} passed to {call}"));
+    R::failure()
+}
+
+/// A host array as a slice; None for a null pointer with a nonzero length
+fn host_slice<'a, T>(ptr: *const T, len: usize) -> Option<&'a [T]> {
+    if len == 0 {
+        Some(&[])
+    } else if ptr.is_null() {
+        None
+    } else {
+        Some(unsafe { std::slice::from_raw_parts(ptr, len) })
+    }
+}
+
+fn take_err() -> Option<String> {
+    LAST_ERROR.with(|s| s.borrow_mut().take()).map(|(_, msg)| msg)
+}
+/// A C string argument as UTF-8. Null and invalid UTF-8 are treated as
+/// empty and recorded as errors.
+fn utf8_arg<'a>(ptr: *const i8) -> &'a str {
+    if ptr.is_null() {
+        set_err_code(McoreErrorCode::InvalidArg, "Null string argument");
+        return "";
+    }
+    unsafe { CStr::from_ptr(ptr) }.to_str().unwrap_or_else(|e| {
+        set_err_code(McoreErrorCode::Utf8, e);
+        ""
+    })
+}
+
+/// The last error on this thread. The string stays valid until the next
+/// mcore_last_error call on the same thread; mcore_last_error_copy copies it
+/// into a buffer the caller owns instead.
 #[no_mangle]
 pub extern "C" fn mcore_last_error() -> *const i8 {
-    use std::ffi::CString;
-    LAST_ERROR.with(|s| {
-        if let Some(msg) = s.borrow().as_ref() {
-            // leak a CString for debugging simplicity (process lifetime)
-            let c = CString::new(msg.clone()).unwrap();
-            Box::leak(c.into_boxed_c_str()).as_ptr()
-        } else {
-            std::ptr::null()
+    ffi_boundary("mcore_last_error", || {
+        trace_call!("mcore_last_error");
+        LAST_ERROR.with(|s| {
+            LAST_ERROR_C.with(|c| {
+                let mut c = c.borrow_mut();
+                *c = s.borrow().as_ref().map(|(_, msg)| CString::new(msg.replace('\0', "")).expect("nul bytes were removed"));
+                c.as_ref().map_or(std::ptr::null(), |c| c.as_ptr())
+            })
+        })
+    })
+}
+
+/// Kind of the last error on this thread, MCORE_ERROR_NONE if there was none.
+/// Fallible calls set it along with the message when they fail.
+#[no_mangle]
+pub extern "C" fn mcore_last_error_code() -> McoreErrorCode {
+    ffi_boundary("mcore_last_error_code", || {
+        trace_call!("mcore_last_error_code");
+        LAST_ERROR.with(|s| s.borrow().as_ref().map_or(McoreErrorCode::None, |(code, _)| *code))
+    })
+}
+
+/// Copy the last error message on this thread into `buf` (`len` bytes,
+/// null-terminated, truncated if needed). Returns the message length without
+/// the terminator, 0 if there's no error; call with a null buf to size it.
+#[no_mangle]
+pub extern "C" fn mcore_last_error_copy(buf: *mut i8, len: usize) -> usize {
+    ffi_boundary("mcore_last_error_copy", || {
+        trace_call!("mcore_last_error_copy", buf, len);
+        LAST_ERROR.with(|s| {
+            let s = s.borrow();
+            let Some((_, msg)) = s.as_ref() else {
+                if !buf.is_null() && len > 0 {
+                    unsafe { *buf = 0 };
+                }
+                return 0;
+            };
+            if !buf.is_null() && len > 0 {
+                let n = msg.len().min(len - 1);
+                unsafe {
+                    std::ptr::copy_nonoverlapping(msg.as_ptr(), buf as *mut u8, n);
+                    *buf.add(n) = 0;
+                }
+            }
+            msg.len()
+        })
+    })
+}
+
+/// Static description of an McoreErrorCode; never null, never freed
+#[no_mangle]
+pub extern "C" fn mcore_error_message(code: u32) -> *const i8 {
+    ffi_boundary("mcore_error_message", || {
+        trace_call!("mcore_error_message", code);
+        McoreErrorCode::from_u32(code).map_or(c"unknown error code", McoreErrorCode::message).as_ptr()
+    })
+}
+
+/// Forget the last error on this thread
+#[no_mangle]
+pub extern "C" fn mcore_clear_error() {
+    ffi_boundary("mcore_clear_error", || {
+        trace_call!("mcore_clear_error");
+        LAST_ERROR.with(|s| *s.borrow_mut() = None);
+    })
+}
+
+/// Record every FFI call into a ring of the last `capacity` calls, for
+/// mcore_trace_dump (0 turns tracing off). MCORE_TRACE=<capacity> in the
+/// environment enables it at startup. While tracing, calls on a destroyed
+/// context print the history and abort instead of corrupting memory.
+#[no_mangle]
+pub extern "C" fn mcore_trace_enable(capacity: u32) {
+    ffi_boundary("mcore_trace_enable", || {
+        trace::set_capacity(capacity as usize);
+        trace_call!("mcore_trace_enable", capacity);
+    })
+}
+
+/// Copy the recorded call history (oldest first, one call per line) into
+/// buf, null-terminated and truncated to fit. Returns the full length in
+/// bytes, or -1 if tracing is off.
+#[no_mangle]
+pub extern "C" fn mcore_trace_dump(buf: *mut u8, buf_len: i32) -> i32 {
+    ffi_boundary("mcore_trace_dump", || {
+        let Some(history) = trace::dump() else {
+            return -1;
+        };
+        if !buf.is_null() && buf_len > 0 {
+            let bytes = history.as_bytes();
+            let copy_len = bytes.len().min((buf_len - 1) as usize);
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf, copy_len);
+                *buf.add(copy_len) = 0; // Null terminate
+            }
+        }
+        history.len() as i32
+    })
+}
+
+/// Optional subsystems, reported by mcore_get_capabilities as a bitmask
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreCapability {
+    /// JPEG/PNG/etc. decoding (image-decode feature)
+    ImageDecode = 1 << 0,
+    /// Screen reader support via AccessKit (a11y feature)
+    Accessibility = 1 << 1,
+    /// Flexbox layout of host node trees (layout feature)
+    Layout = 1 << 2,
+    /// WebP decoding (webp feature)
+    WebpDecode = 1 << 3,
+    /// AVIF decoding (avif feature)
+    AvifDecode = 1 << 4,
+}
+
+/// Which optional subsystems this build includes (McoreCapability bits).
+/// FFI entry points exist in every build; without the subsystem they fail
+/// with an error or do nothing.
+#[no_mangle]
+pub extern "C" fn mcore_get_capabilities() -> u32 {
+    ffi_boundary("mcore_get_capabilities", || {
+        trace_call!("mcore_get_capabilities");
+        let mut caps = 0;
+        if cfg!(feature = "image-decode") {
+            caps |= McoreCapability::ImageDecode as u32;
+        }
+        if cfg!(feature = "a11y") {
+            caps |= McoreCapability::Accessibility as u32;
+        }
+        if cfg!(feature = "layout") {
+            caps |= McoreCapability::Layout as u32;
         }
+        if cfg!(feature = "webp") {
+            caps |= McoreCapability::WebpDecode as u32;
+        }
+        if cfg!(feature = "avif") {
+            caps |= McoreCapability::AvifDecode as u32;
+        }
+        caps
     })
 }
 
@@ -57,10 +452,168 @@ pub union McoreSurfaceUnion {
     pub macos: McoreMacSurface,
 }
 
+/// Which GPU a context renders with when McoreSurfaceDesc::adapter is 0
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McorePowerPreference {
+    /// High performance (a discrete GPU where there is one)
+    Default = 0,
+    /// The integrated GPU, sparing battery for simple UIs
+    LowPower = 1,
+    HighPerformance = 2,
+}
+
 #[repr(C)]
 pub struct McoreSurfaceDesc {
+    /// MCORE_API_VERSION as the host saw it. First, so hosts built before
+    /// the field existed fail the check instead of misreading the rest.
+    pub api_version: u32,
     pub platform: McorePlatform,
     pub u: McoreSurfaceUnion,
+    pub power_preference: McorePowerPreference,
+    /// 1-based index into mcore_enumerate_adapters, or 0 to pick by
+    /// power_preference
+    pub adapter: u32,
+    /// MCORE_CREATE_* flags
+    pub flags: u32,
+}
+
+/// Fall back to a software adapter (and Vello's CPU pipeline) when no GPU
+/// adapter is usable, e.g. in VMs and CI
+pub const CREATE_SOFTWARE_FALLBACK: u32 = 1 << 0;
+/// Run Vello's pipeline stages on the CPU even with a GPU
+pub const CREATE_CPU_PIPELINE: u32 = 1 << 1;
+
+impl McoreSurfaceDesc {
+    fn gpu_options(&self) -> gfx::GpuOptions {
+        gpu_options(self.power_preference, self.adapter, self.flags)
+    }
+}
+
+/// How mcore_engine_create picks the GPU its contexts share
+#[repr(C)]
+pub struct McoreEngineDesc {
+    /// MCORE_API_VERSION as the host saw it
+    pub api_version: u32,
+    pub power_preference: McorePowerPreference,
+    /// 1-based index into mcore_enumerate_adapters, or 0 to pick by
+    /// power_preference
+    pub adapter: u32,
+    /// MCORE_CREATE_* flags
+    pub flags: u32,
+}
+
+impl McoreEngineDesc {
+    fn gpu_options(&self) -> gfx::GpuOptions {
+        gpu_options(self.power_preference, self.adapter, self.flags)
+    }
+}
+
+fn gpu_options(power_preference: McorePowerPreference, adapter: u32, flags: u32) -> gfx::GpuOptions {
+    let power = match power_preference {
+        McorePowerPreference::LowPower => wgpu::PowerPreference::LowPower,
+        McorePowerPreference::Default | McorePowerPreference::HighPerformance => wgpu::PowerPreference::HighPerformance,
+    };
+    gfx::GpuOptions {
+        power,
+        index: adapter.checked_sub(1).map(|i| i as usize),
+        ..create_flags(flags)
+    }
+}
+
+fn create_flags(flags: u32) -> gfx::GpuOptions {
+    gfx::GpuOptions {
+        software_fallback: flags & CREATE_SOFTWARE_FALLBACK != 0,
+        cpu_pipeline: flags & CREATE_CPU_PIPELINE != 0,
+        ..Default::default()
+    }
+}
+
+/// How a context rasterizes frames (mcore_render_mode)
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreRenderMode {
+    /// Vello's compute pipeline on a GPU
+    Gpu = 0,
+    /// Vello's pipeline stages on the CPU, rasterization on a GPU
+    CpuPipeline = 1,
+    /// A software adapter: everything on the CPU
+    Software = 2,
+}
+
+/// McoreAdapterInfo::device_type values
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreDeviceType {
+    Other = 0,
+    IntegratedGpu = 1,
+    DiscreteGpu = 2,
+    VirtualGpu = 3,
+    Cpu = 4,
+}
+
+/// McoreAdapterInfo::backend values
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreGpuBackend {
+    Other = 0,
+    Metal = 1,
+    Vulkan = 2,
+    Dx12 = 3,
+    Gl = 4,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreAdapterInfo {
+    /// NUL-terminated, truncated to fit
+    pub name: [u8; 128],
+    pub vendor: u32,
+    pub device: u32,
+    pub device_type: McoreDeviceType,
+    pub backend: McoreGpuBackend,
+}
+
+impl McoreAdapterInfo {
+    fn from_wgpu(info: &wgpu::AdapterInfo) -> Self {
+        let mut name = [0u8; 128];
+        let mut len = info.name.len().min(name.len() - 1);
+        while !info.name.is_char_boundary(len) {
+            len -= 1;
+        }
+        name[..len].copy_from_slice(&info.name.as_bytes()[..len]);
+        Self {
+            name,
+            vendor: info.vendor,
+            device: info.device,
+            device_type: match info.device_type {
+                wgpu::DeviceType::IntegratedGpu => McoreDeviceType::IntegratedGpu,
+                wgpu::DeviceType::DiscreteGpu => McoreDeviceType::DiscreteGpu,
+                wgpu::DeviceType::VirtualGpu => McoreDeviceType::VirtualGpu,
+                wgpu::DeviceType::Cpu => McoreDeviceType::Cpu,
+                wgpu::DeviceType::Other => McoreDeviceType::Other,
+            },
+            backend: match info.backend {
+                wgpu::Backend::Metal => McoreGpuBackend::Metal,
+                wgpu::Backend::Vulkan => McoreGpuBackend::Vulkan,
+                wgpu::Backend::Dx12 => McoreGpuBackend::Dx12,
+                wgpu::Backend::Gl => McoreGpuBackend::Gl,
+                _ => McoreGpuBackend::Other,
+            },
+        }
+    }
+}
+
+/// The adapter a context renders with and the device limits the engine got
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreGpuInfo {
+    pub adapter: McoreAdapterInfo,
+    pub max_texture_dimension_2d: u32,
+    pub max_storage_buffer_binding_size: u32,
+    pub max_buffer_size: u64,
+    /// GPU frame timings are available (McoreFrameStats::gpu_ms)
+    pub timestamp_queries: u8,
 }
 
 #[repr(C)]
@@ -98,6 +651,107 @@ pub struct McoreTextReq {
     pub wrap_width: f32,
     pub font_size_px: f32,
     pub font_id: i32,
+    /// CSS-style weight (100-900), 0 for the font's default face
+    pub weight: f32,
+    pub italic: u8,
+    /// Width ratio (0.5-2.0), 0 for normal
+    pub stretch: f32,
+    /// Extra space after each character / word, logical pixels
+    pub letter_spacing: f32,
+    pub word_spacing: f32,
+    /// Multiple of the font size, 0 for the font's default line height
+    pub line_height_multiplier: f32,
+    /// McoreTextAlign value
+    pub align: u8,
+    /// Most lines to show, 0 for no limit
+    pub max_lines: u32,
+    /// McoreTextOverflow value, applied past max_lines
+    pub overflow: u8,
+    /// McoreTextDirection value
+    pub direction: u8,
+    /// Spacing of repeating tab stops (logical px), 0 for 8 spaces
+    pub tab_width: f32,
+    /// Explicit tab stops (logical px from the line start) before the
+    /// repeating ones; may be null when tab_stop_count is 0
+    pub tab_stops: *const f32,
+    pub tab_stop_count: u32,
+}
+
+/// Horizontal alignment within the wrap width
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreTextAlign {
+    Start = 0,
+    Center = 1,
+    End = 2,
+    Justify = 3,
+}
+
+/// Paragraph direction of a text request
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreTextDirection {
+    /// From the first strong character, left to right if there is none
+    Auto = 0,
+    Ltr = 1,
+    Rtl = 2,
+}
+
+/// How text past max_lines is truncated
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreTextOverflow {
+    Clip = 0,
+    Ellipsis = 1,
+    Fade = 2,
+}
+
+/// Font parameters of a text request, with zeroed fields meaning "default"
+fn text_style(req: &McoreTextReq) -> text::TextStyle {
+    let mut style = text::TextStyle::new(req.font_size_px, req.font_id);
+    if req.weight > 0.0 {
+        style.weight = Some(req.weight.clamp(1.0, 1000.0));
+    }
+    if req.stretch > 0.0 {
+        style.stretch = req.stretch;
+    }
+    if req.line_height_multiplier > 0.0 {
+        style.line_height = Some(req.line_height_multiplier);
+    }
+    style.italic = req.italic != 0;
+    style.letter_spacing = req.letter_spacing;
+    style.word_spacing = req.word_spacing;
+    style.align = text::TextAlign::from_u8(req.align);
+    style.max_lines = (req.max_lines > 0).then_some(req.max_lines as usize);
+    style.overflow = text::TextOverflow::from_u8(req.overflow);
+    style.direction = text::TextDirection::from_u8(req.direction);
+    style.tabs = unsafe { tab_stops(req.tab_width, req.tab_stops, req.tab_stop_count) };
+    style
+}
+
+/// Tab stops from a host array, treating null/zero-length as none
+unsafe fn tab_stops(width: f32, stops: *const f32, count: u32) -> text::TabStops {
+    let stops = if stops.is_null() || count == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(stops, count as usize)
+    };
+    text::TabStops::new(width, stops)
+}
+
+/// Style applied to a byte range of a text request
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextSpan {
+    pub start: u32,
+    pub end: u32,
+    pub color: McoreRgba,
+    /// CSS-style weight (100-900), 0 to inherit
+    pub weight: f32,
+    pub italic: u8,
+    pub underline: u8,
+    /// Font for this range, -1 to inherit
+    pub font_id: i32,
 }
 
 #[repr(C)]
@@ -106,6 +760,8 @@ pub struct McoreTextMetrics {
     pub advance_w: f32,
     pub advance_h: f32,
     pub line_count: i32,
+    /// 1 if lines past max_lines were dropped
+    pub truncated: u8,
 }
 
 #[repr(C)]
@@ -115,6 +771,15 @@ pub struct McoreTextSize {
     pub height: f32,
 }
 
+/// Caret position in logical pixels; y is the top of the caret's line
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreCaretRect {
+    pub x: f32,
+    pub y: f32,
+    pub height: f32,
+}
+
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct McoreTextStats {
@@ -122,9 +787,60 @@ pub struct McoreTextStats {
     pub total_offset_calls: u32,
 }
 
+/// Translation + uniform scale from the hit transform stack
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreHitTransform {
+    pub dx: f32,
+    pub dy: f32,
+    pub scale: f32,
+}
+
+/// The region under a point (mcore_hit_test)
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct McoreHitResult {
+    pub id: u64,
+    pub z: i32,
+    /// The point in the region's local space, origin at its top-left
+    pub local_x: f32,
+    pub local_y: f32,
+}
+
+/// Shaped-layout cache counters (hits/misses/evictions since the last reset)
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextCacheStats {
+    pub entries: u32,
+    pub max_entries: u32,
+    /// Estimated bytes held by cached layouts
+    pub bytes: u64,
+    pub max_bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// What an McoreDrawCommand draws
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreDrawCmdKind {
+    RoundedRect = 0,
+    Text = 1,
+    PushClip = 2,
+    PopClip = 3,
+    StyledRect = 4,
+    /// Uses x, y, width, height, radius
+    PushClipRounded = 5,
+    /// Rounded-rect outline stroked with a gradient: x, y, width, height,
+    /// radius, border_width (centered on the edge), font_id = gradient ID
+    GradientRing = 6,
+}
+
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct McoreDrawCommand {
+    /// McoreDrawCmdKind value
     pub kind: u8,
     pub x: f32,
     pub y: f32,
@@ -149,75 +865,417 @@ pub struct McoreDrawCommand {
     pub shadow_color: [f32; 4],
     pub has_shadow: u8,
 
-    pub _padding: [u8; 2],
+    /// McoreTextAlign value (text commands)
+    pub text_align: u8,
+    /// McoreTextDirection value (text commands)
+    pub text_direction: u8,
 }
 
 // ============================================================================
-// Color Support (using color crate for proper color handling)
+// Command Stream v2 (see command_stream.rs for the format)
 // ============================================================================
 
-/// Color type - just an RGBA tuple
-/// Same layout as peniko::Color which is an array [r, g, b, a]
-#[repr(C)]
-#[derive(Copy, Clone, Debug)]
-pub struct McoreColor {
-    pub r: f32,
-    pub g: f32,
-    pub b: f32,
-    pub a: f32,
-}
+/// Changes only if the stream's framing does: tags and payload fields are
+/// only ever added
+pub const COMMAND_STREAM_VERSION: u32 = 1;
 
-impl From<McoreColor> for Color {
-    fn from(c: McoreColor) -> Self {
-        Color::new([c.r, c.g, c.b, c.a])
-    }
+/// Precedes each record's payload in a command stream
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreCmdHeader {
+    /// McoreCmdTag value
+    pub tag: u16,
+    /// Reserved, 0
+    pub flags: u16,
+    /// Payload bytes, not counting the padding to the next record
+    pub size: u32,
 }
 
-impl From<Color> for McoreColor {
-    fn from(c: Color) -> Self {
-        Self { r: c.components[0], g: c.components[1], b: c.components[2], a: c.components[3] }
-    }
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreCmdTag {
+    /// McoreCmdFill
+    FillRect = 1,
+    /// McoreCmdStroke
+    StrokeRect = 2,
+    /// McoreCmdShadow
+    Shadow = 3,
+    /// McoreCmdText, then text_len bytes of UTF-8
+    Text = 4,
+    /// McoreCmdClip
+    PushClip = 5,
+    /// No payload
+    PopClip = 6,
+    /// McoreCmdGradient
+    Gradient = 7,
+    /// McoreCmdImage
+    Image = 8,
+    /// McoreCmdTransform
+    PushTransform = 9,
+    /// No payload
+    PopTransform = 10,
+    /// McoreCmdVideo
+    Video = 11,
 }
 
-
-/// Text measurement statistics for instrumentation
-#[derive(Default)]
-struct TextMeasurementStats {
-    total_measure_calls: u32,
-    total_offset_calls: u32,
+/// Rounded-rect fill (logical px)
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct McoreCmdFill {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub radius: f32,
+    pub color: [f32; 4],
 }
 
-impl TextMeasurementStats {
-    fn reset(&mut self) {
-        self.total_measure_calls = 0;
-        self.total_offset_calls = 0;
-    }
+/// Rounded-rect outline, line_width centered on the edge
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct McoreCmdStroke {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub radius: f32,
+    pub line_width: f32,
+    pub color: [f32; 4],
 }
 
-struct Engine {
-    gfx: gfx::Gfx,
-    scene: Scene,
-    time_s: f64,
-    text_cx: text::TextContext,
-    fonts: Vec<(Vec<u8>, FontData)>,
-    text_inputs: text_input::TextInputManager,
-    a11y: Option<a11y::AccessibilityAdapter>,
-    images: image::ImageManager,
-    text_stats: TextMeasurementStats,
+/// Blurred rounded rect, for drop shadows
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct McoreCmdShadow {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub radius: f32,
+    pub blur: f32,
+    pub color: [f32; 4],
 }
 
+/// Text at (x, y); the UTF-8 is the last text_len bytes of the payload
 #[repr(C)]
-pub enum McoreStatus {
-    Ok = 0,
-    Err = 1,
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct McoreCmdText {
+    pub x: f32,
+    pub y: f32,
+    pub font_size: f32,
+    /// 0 for no wrapping
+    pub wrap_width: f32,
+    pub font_id: i32,
+    pub color: [f32; 4],
+    /// McoreTextAlign value
+    pub align: u32,
+    /// McoreTextDirection value
+    pub direction: u32,
+    pub text_len: u32,
+}
+
+/// Clip to a rounded rect until the matching PopClip
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct McoreCmdClip {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub radius: f32,
+}
+
+/// Rounded rect painted with a registered gradient: filled, or stroked
+/// line_width wide when that's above 0
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct McoreCmdGradient {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub radius: f32,
+    pub gradient_id: i32,
+    pub line_width: f32,
+}
+
+/// Registered image over a rect: stretched to it, or with a tile size drawn
+/// at that size from (x + tile_x, y + tile_y) and extended past its edges
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct McoreCmdImage {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub image_id: i32,
+    /// MCORE_IMAGE_SAMPLING_*
+    pub sampling: u32,
+    /// MCORE_IMAGE_EXTEND_*, per axis
+    pub extend_x: u32,
+    pub extend_y: u32,
+    /// Where one copy of the image goes, relative to x, y; a size of 0 is
+    /// the rect's
+    pub tile_x: f32,
+    pub tile_y: f32,
+    pub tile_width: f32,
+    pub tile_height: f32,
+}
+
+impl McoreCmdImage {
+    /// The sampler asked for; unknown values (from a newer host) fall back
+    /// to the defaults
+    fn sampler(&self) -> image::Sampler {
+        let extend = |mode| match mode {
+            1 => peniko::Extend::Repeat,
+            2 => peniko::Extend::Reflect,
+            _ => peniko::Extend::Pad,
+        };
+        image::Sampler {
+            sampling: match self.sampling {
+                1 => image::Sampling::Nearest,
+                2 => image::Sampling::Mipmap,
+                _ => image::Sampling::Linear,
+            },
+            extend_x: extend(self.extend_x),
+            extend_y: extend(self.extend_y),
+        }
+    }
+
+    /// The destination and tile rects in logical px
+    fn rects(&self) -> (peniko::kurbo::Rect, peniko::kurbo::Rect) {
+        let dest = peniko::kurbo::Rect::new(self.x as f64, self.y as f64, (self.x + self.width) as f64, (self.y + self.height) as f64);
+        let size = |tile: f32, full: f32| (if tile > 0.0 { tile } else { full }) as f64;
+        let origin = dest.origin() + peniko::kurbo::Vec2::new(self.tile_x as f64, self.tile_y as f64);
+        let tile = peniko::kurbo::Rect::from_origin_size(origin, (size(self.tile_width, self.width), size(self.tile_height, self.height)));
+        (dest, tile)
+    }
+}
+
+
+/// Affine transform applied to the following commands until the matching
+/// PopTransform, composed with any already pushed: [a, b, c, d, e, f] maps
+/// (x, y) to (a*x + c*y + e, b*x + d*y + f), translation in logical px
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct McoreCmdTransform {
+    pub matrix: [f32; 6],
+}
+
+/// A video's latest frame (mcore_video_frame_submit) fitted to a rect
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct McoreCmdVideo {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub video_id: i32,
+    /// MCORE_VIDEO_FIT_*
+    pub fit: u32,
+}
+
+impl McoreCmdVideo {
+    fn rect(&self) -> peniko::kurbo::Rect {
+        peniko::kurbo::Rect::new(self.x as f64, self.y as f64, (self.x + self.width) as f64, (self.y + self.height) as f64)
+    }
+
+    /// The fit asked for; unknown values (from a newer host) letterbox
+    fn fit_mode(&self) -> video::Fit {
+        match self.fit {
+            1 => video::Fit::Cover,
+            2 => video::Fit::Stretch,
+            _ => video::Fit::Contain,
+        }
+    }
+}
+
+// ============================================================================
+// Color Support (using color crate for proper color handling)
+// ============================================================================
+
+/// Color type - just an RGBA tuple
+/// Same layout as peniko::Color which is an array [r, g, b, a]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct McoreColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl From<McoreColor> for Color {
+    fn from(c: McoreColor) -> Self {
+        Color::new([c.r, c.g, c.b, c.a])
+    }
+}
+
+impl From<Color> for McoreColor {
+    fn from(c: Color) -> Self {
+        Self { r: c.components[0], g: c.components[1], b: c.components[2], a: c.components[3] }
+    }
+}
+
+
+#[repr(C)]
+pub enum McoreStatus {
+    Ok = 0,
+    Err = 1,
+    /// A drawing call or mcore_end_frame_present came outside
+    /// mcore_begin_frame / mcore_end_frame_present
+    NotInFrame = 2,
+    /// mcore_begin_frame came before the previous frame was presented
+    FrameInProgress = 3,
+    /// Damage tracking found nothing changed since the last presented frame;
+    /// it wasn't rendered and the previous one stays on screen
+    FrameSkipped = 4,
+}
+
+/// Drawing outside a frame would land in the last presented scene; reject it
+/// and say why (mcore_last_error). Direct drawing calls can't be compared
+/// between frames, so they also opt the frame out of damage tracking.
+fn drawing_allowed(eng: &mut Engine, call: &str) -> bool {
+    if !commands_allowed(eng, call) {
+        return false;
+    }
+    eng.damage.mark_untracked();
+    true
+}
+
+/// drawing_allowed for command buffers, which damage tracking hashes
+fn commands_allowed(eng: &Engine, call: &str) -> bool {
+    match eng.check_drawing(call) {
+        Ok(()) => true,
+        Err(e) => {
+            set_err_code(McoreErrorCode::InvalidState, e);
+            false
+        }
+    }
 }
 
 #[repr(C)]
 pub struct McoreContext(Arc<Mutex<Engine>>);
 
+/// A GPU device, Vello's pipelines, fonts and images shared by the contexts
+/// created from it (one per window), instead of each context opening its own
+#[repr(C)]
+pub struct McoreEngine {
+    gpu: gfx::SharedGpu,
+    resources: engine::SharedResources,
+}
+
+/// Version of the C API: (major << 16) | minor. Minor versions only add entry
+/// points; changing an existing struct or signature bumps the major.
+pub const API_VERSION_MAJOR: u32 = 4;
+pub const API_VERSION_MINOR: u32 = 17;
+pub const API_VERSION: u32 = (API_VERSION_MAJOR << 16) | API_VERSION_MINOR;
+
+/// The API version this engine was built with (MCORE_API_VERSION in its
+/// header)
+#[no_mangle]
+pub extern "C" fn mcore_api_version() -> u32 {
+    ffi_boundary("mcore_api_version", || {
+        trace_call!("mcore_api_version");
+        API_VERSION
+    })
+}
+
+/// Whether a host built against `host` can use this engine: the same major
+/// version, and nothing newer than what the engine has
+fn api_compatible(host: u32) -> bool {
+    host >> 16 == API_VERSION_MAJOR && host & 0xffff <= API_VERSION_MINOR
+}
+
+/// Fail a create call from a host built against another API version
+fn check_api_version(call: &str, host: u32) -> bool {
+    if !api_compatible(host) {
+        set_err_code(
+            McoreErrorCode::VersionMismatch,
+            format!(
+                "{call}: host built against API {}.{}, engine provides {API_VERSION_MAJOR}.{API_VERSION_MINOR}; rebuild the host against this engine's header",
+                host >> 16,
+                host & 0xffff
+            ),
+        );
+        return false;
+    }
+    true
+}
+
 #[no_mangle]
 pub extern "C" fn mcore_create(desc: *const McoreSurfaceDesc) -> *mut McoreContext {
-    let desc = unsafe { desc.as_ref() }.unwrap();
+    ffi_boundary("mcore_create", || {
+        trace_call!("mcore_create", desc);
+        let Some(desc) = (unsafe { desc.as_ref() }) else {
+            return null_arg("mcore_create", "desc");
+        };
+        if !check_api_version("mcore_create", desc.api_version) {
+            return std::ptr::null_mut();
+        }
+        create_context(desc, None)
+    })
+}
+
+/// Open the GPU device that contexts from mcore_engine_create_context share.
+/// Returns null on error.
+#[no_mangle]
+pub extern "C" fn mcore_engine_create(desc: *const McoreEngineDesc) -> *mut McoreEngine {
+    ffi_boundary("mcore_engine_create", || {
+        trace_call!("mcore_engine_create", desc);
+        let Some(desc) = (unsafe { desc.as_ref() }) else {
+            set_err_code(McoreErrorCode::InvalidArg, "mcore_engine_create: null desc");
+            return std::ptr::null_mut();
+        };
+        if !check_api_version("mcore_engine_create", desc.api_version) {
+            return std::ptr::null_mut();
+        }
+        match pollster::block_on(gfx::SharedGpu::new(wgpu::Instance::default(), None, desc.gpu_options())) {
+            Ok(gpu) => Box::into_raw(Box::new(McoreEngine {
+                gpu,
+                resources: engine::SharedResources::default(),
+            })),
+            Err(e) => {
+                set_gfx_err(&e);
+                std::ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Create a context for a window that renders with `engine`'s device and
+/// shares its fonts and images: font and image IDs registered through any of
+/// the engine's contexts work in all of them. The desc's power_preference,
+/// adapter and flags are ignored. Returns null on error, e.g. when the
+/// engine's GPU can't present to the window.
+#[no_mangle]
+pub extern "C" fn mcore_engine_create_context(engine: *mut McoreEngine, desc: *const McoreSurfaceDesc) -> *mut McoreContext {
+    ffi_boundary("mcore_engine_create_context", || {
+        trace_call!("mcore_engine_create_context", engine, desc);
+        let (Some(engine), Some(desc)) = (unsafe { engine.as_ref() }, unsafe { desc.as_ref() }) else {
+            set_err_code(McoreErrorCode::InvalidArg, "mcore_engine_create_context: null engine or desc");
+            return std::ptr::null_mut();
+        };
+        if !check_api_version("mcore_engine_create_context", desc.api_version) {
+            return std::ptr::null_mut();
+        }
+        create_context(desc, Some(engine))
+    })
+}
+
+/// Release the engine handle. Contexts created from it keep working and
+/// keep the shared device alive until they're destroyed.
+#[no_mangle]
+pub extern "C" fn mcore_engine_destroy(engine: *mut McoreEngine) {
+    ffi_boundary("mcore_engine_destroy", || {
+        trace_call!("mcore_engine_destroy", engine);
+        if !engine.is_null() {
+            drop(unsafe { Box::from_raw(engine) });
+        }
+    })
+}
+
+fn create_context(desc: &McoreSurfaceDesc, shared: Option<&McoreEngine>) -> *mut McoreContext {
     match desc.platform {
         McorePlatform::MacOS => {
             let mac = unsafe { desc.u.macos };
@@ -230,887 +1288,6379 @@ pub extern "C" fn mcore_create(desc: *const McoreSurfaceDesc) -> *mut McoreConte
                 height_px: mac.height_px,
             };
             // block_on in a new thread so we don't block AppKit
-            match pollster::block_on(gfx::Gfx::new_macos(&mac_surface)) {
-                Ok(engine) => {
-                    let eng = Engine {
-                        gfx: engine,
-                        scene: Scene::new(),
-                        time_s: 0.0,
-                        text_cx: text::TextContext::default(),
-                        fonts: Vec::new(),
-                        text_inputs: text_input::TextInputManager::new(),
-                        a11y: None,
-                        images: image::ImageManager::new(),
-                        text_stats: TextMeasurementStats::default(),
+            let gpu = shared.map(|engine| engine.gpu.clone());
+            match pollster::block_on(gfx::Gfx::new_macos(&mac_surface, desc.gpu_options(), gpu)) {
+                Ok(gfx) => {
+                    let eng = match shared {
+                        Some(engine) => Engine::with_resources(gfx, engine.resources.shared()),
+                        None => Engine::new(gfx),
                     };
-                    Box::into_raw(Box::new(McoreContext(Arc::new(Mutex::new(eng)))))
+                    let ctx = Box::into_raw(Box::new(McoreContext(Arc::new(Mutex::new(eng)))));
+                    trace::context_created(ctx as *const ());
+                    ctx
                 }
                 Err(e) => {
-                    set_err(e);
+                    set_gfx_err(&e);
                     std::ptr::null_mut()
                 }
             }
         }
         _ => {
-            set_err("unsupported platform");
+            set_err_code(McoreErrorCode::Unsupported, "unsupported platform");
             std::ptr::null_mut()
         }
     }
 }
 
+/// Create a context with no window: frames render into an offscreen texture
+/// of `width_px` x `height_px`, read back with mcore_read_pixels. For CI
+/// screenshot tests and server-side thumbnails. `api_version` is
+/// MCORE_API_VERSION and `flags` are MCORE_CREATE_* flags. Returns null on
+/// error.
+#[no_mangle]
+pub extern "C" fn mcore_create_headless(
+    api_version: u32,
+    width_px: u32,
+    height_px: u32,
+    scale_factor: f32,
+    flags: u32,
+) -> *mut McoreContext {
+    ffi_boundary("mcore_create_headless", || {
+        trace_call!("mcore_create_headless", api_version, width_px, height_px, scale_factor, flags);
+        if !check_api_version("mcore_create_headless", api_version) {
+            return std::ptr::null_mut();
+        }
+        if width_px == 0 || height_px == 0 || !(scale_factor > 0.0) {
+            set_err_code(McoreErrorCode::InvalidArg, format!("Invalid headless size {width_px}x{height_px} at scale {scale_factor}"));
+            return std::ptr::null_mut();
+        }
+
+        match pollster::block_on(gfx::Gfx::new_headless(width_px, height_px, scale_factor, create_flags(flags))) {
+            Ok(gfx) => {
+                let eng = Engine::new(gfx);
+                let ctx = Box::into_raw(Box::new(McoreContext(Arc::new(Mutex::new(eng)))));
+                trace::context_created(ctx as *const ());
+                ctx
+            }
+            Err(e) => {
+                set_gfx_err(&e);
+                std::ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Fill up to `capacity` entries of `out` with the GPUs available to
+/// mcore_create and return how many there are (call with capacity 0 to
+/// count). Entry i is selected with McoreSurfaceDesc::adapter = i + 1.
+#[no_mangle]
+pub extern "C" fn mcore_enumerate_adapters(out: *mut McoreAdapterInfo, capacity: u32) -> u32 {
+    ffi_boundary("mcore_enumerate_adapters", || {
+        trace_call!("mcore_enumerate_adapters", out, capacity);
+        let adapters = gfx::enumerate_adapters();
+        if !out.is_null() {
+            for (i, info) in adapters.iter().take(capacity as usize).enumerate() {
+                unsafe { out.add(i).write(McoreAdapterInfo::from_wgpu(info)) };
+            }
+        }
+        adapters.len() as u32
+    })
+}
+
+/// Whether a context renders on a GPU or fell back to the CPU
+#[no_mangle]
+pub extern "C" fn mcore_render_mode(ctx: *mut McoreContext) -> McoreRenderMode {
+    ffi_boundary("mcore_render_mode", || {
+        trace_call!("mcore_render_mode", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_render_mode", "ctx");
+        };
+        match ctx.0.lock().gfx.mode() {
+            gfx::RenderMode::Gpu => McoreRenderMode::Gpu,
+            gfx::RenderMode::CpuPipeline => McoreRenderMode::CpuPipeline,
+            gfx::RenderMode::Software => McoreRenderMode::Software,
+        }
+    })
+}
+
+/// The adapter and device limits a context renders with
+#[no_mangle]
+pub extern "C" fn mcore_gpu_info(ctx: *mut McoreContext, out: *mut McoreGpuInfo) -> McoreStatus {
+    ffi_boundary("mcore_gpu_info", || {
+        trace_call!("mcore_gpu_info", ctx, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_gpu_info", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_gpu_info", "out");
+        };
+
+        let guard = ctx.0.lock();
+        let device = guard.gfx.device();
+        let limits = device.limits();
+        *out = McoreGpuInfo {
+            adapter: McoreAdapterInfo::from_wgpu(&guard.gfx.adapter().get_info()),
+            max_texture_dimension_2d: limits.max_texture_dimension_2d,
+            max_storage_buffer_binding_size: limits.max_storage_buffer_binding_size,
+            max_buffer_size: limits.max_buffer_size,
+            timestamp_queries: device.features().contains(wgpu::Features::TIMESTAMP_QUERY) as u8,
+        };
+        McoreStatus::Ok
+    })
+}
+
+/// Copy the last presented frame of a headless context into `out` as
+/// tightly packed RGBA8 rows, top row first. `len` must be at least
+/// width_px * height_px * 4.
+#[no_mangle]
+pub extern "C" fn mcore_read_pixels(ctx: *mut McoreContext, out: *mut u8, len: usize) -> McoreStatus {
+    ffi_boundary("mcore_read_pixels", || {
+        trace_call!("mcore_read_pixels", ctx, out, len);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_read_pixels", "ctx");
+        };
+        if out.is_null() {
+            return null_arg("mcore_read_pixels", "out");
+        }
+
+        let guard = ctx.0.lock();
+        let (width, height) = guard.gfx.size();
+        let needed = width as usize * height as usize * 4;
+        if len < needed {
+            set_err_code(McoreErrorCode::InvalidArg, format!("mcore_read_pixels needs {needed} bytes for {width}x{height}, got {len}"));
+            return McoreStatus::Err;
+        }
+        match guard.gfx.read_pixels() {
+            Ok(pixels) => {
+                let out = unsafe { std::slice::from_raw_parts_mut(out, needed) };
+                out.copy_from_slice(&pixels[..needed]);
+                McoreStatus::Ok
+            }
+            Err(gfx::GfxError::InvalidSurface) => {
+                set_err_code(McoreErrorCode::InvalidState, "mcore_read_pixels only works on headless contexts (mcore_create_headless)");
+                McoreStatus::Err
+            }
+            Err(e) => {
+                set_gfx_err(&e);
+                McoreStatus::Err
+            }
+        }
+    })
+}
+
+/// Copy the last presented frame into `out` as tightly packed RGBA8 rows, top
+/// row first, for screenshots and visual regression tests. `len` must be at
+/// least width_px * height_px * 4. Windowed contexts capture the rendered
+/// scene before particles and dithering are composited.
+#[no_mangle]
+pub extern "C" fn mcore_capture_frame(ctx: *mut McoreContext, out: *mut u8, len: usize) -> McoreStatus {
+    ffi_boundary("mcore_capture_frame", || {
+        trace_call!("mcore_capture_frame", ctx, out, len);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_capture_frame", "ctx");
+        };
+        if out.is_null() {
+            return null_arg("mcore_capture_frame", "out");
+        }
+
+        let guard = ctx.0.lock();
+        let (width, height) = guard.gfx.size();
+        let needed = width as usize * height as usize * 4;
+        if len < needed {
+            set_err_code(McoreErrorCode::InvalidArg, format!("mcore_capture_frame needs {needed} bytes for {width}x{height}, got {len}"));
+            return McoreStatus::Err;
+        }
+        match guard.gfx.capture_frame() {
+            Ok(pixels) => {
+                let out = unsafe { std::slice::from_raw_parts_mut(out, needed) };
+                out.copy_from_slice(&pixels[..needed]);
+                McoreStatus::Ok
+            }
+            Err(e) => {
+                set_gfx_err(&e);
+                McoreStatus::Err
+            }
+        }
+    })
+}
+
+/// mcore_capture_frame written to a PNG file (needs the image-decode feature)
+#[no_mangle]
+pub extern "C" fn mcore_capture_frame_png(ctx: *mut McoreContext, path: *const i8) -> McoreStatus {
+    ffi_boundary("mcore_capture_frame_png", || {
+        trace_call!("mcore_capture_frame_png", ctx, path);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_capture_frame_png", "ctx");
+        };
+        if path.is_null() {
+            return null_arg("mcore_capture_frame_png", "path");
+        }
+
+        let path = unsafe { CStr::from_ptr(path) }.to_string_lossy().into_owned();
+        let (pixels, (width, height)) = {
+            let guard = ctx.0.lock();
+            match guard.gfx.capture_frame() {
+                Ok(pixels) => (pixels, guard.gfx.size()),
+                Err(e) => {
+                    set_gfx_err(&e);
+                    return McoreStatus::Err;
+                }
+            }
+        };
+        match image::ImageManager::write_png(&path, &pixels, width, height) {
+            Ok(()) => McoreStatus::Ok,
+            Err(e) => {
+                set_err_code(McoreErrorCode::Io, e);
+                McoreStatus::Err
+            }
+        }
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn mcore_destroy(ctx: *mut McoreContext) {
-    if !ctx.is_null() {
-        unsafe { drop(Box::from_raw(ctx)) }
-    }
+    ffi_boundary("mcore_destroy", || {
+        trace_call!("mcore_destroy", ctx);
+        if !ctx.is_null() {
+            trace::context_destroyed(ctx as *const ());
+            let ctx = unsafe { Box::from_raw(ctx) };
+            let render_thread = ctx.0.lock().render_thread.take();
+            if let Some(thread) = render_thread {
+                thread.stop();
+            }
+        }
+    })
 }
 
+/// New surface size and scale factor, e.g. after the window moved to a
+/// display with another scale. Drawing calls take logical pixels, so the host's
+/// coordinates stay the same; text is reshaped at the new scale on the next
+/// frame (text layout handles keep the scale they were created at).
 #[no_mangle]
 pub extern "C" fn mcore_resize(ctx: *mut McoreContext, desc: *const McoreSurfaceDesc) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let desc = unsafe { desc.as_ref() }.unwrap();
-    if let McorePlatform::MacOS = desc.platform {
-        let mac = unsafe { desc.u.macos };
-        let mac_surface = gfx::MacSurface {
-            ns_view: mac.ns_view,
-            ca_metal_layer: mac.ca_metal_layer,
-            scale_factor: mac.scale_factor,
-            width_px: mac.width_px,
-            height_px: mac.height_px,
+    ffi_boundary("mcore_resize", || {
+        trace_call!("mcore_resize", ctx, desc);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_resize", "ctx");
+        };
+        let Some(desc) = (unsafe { desc.as_ref() }) else {
+            return null_arg("mcore_resize", "desc");
+        };
+        if let McorePlatform::MacOS = desc.platform {
+            let mac = unsafe { desc.u.macos };
+            let mac_surface = gfx::MacSurface {
+                ns_view: mac.ns_view,
+                ca_metal_layer: mac.ca_metal_layer,
+                scale_factor: mac.scale_factor,
+                width_px: mac.width_px,
+                height_px: mac.height_px,
+            };
+            let mut guard = ctx.0.lock();
+            let _ = guard.gfx.resize(&mac_surface);
+        }
+    })
+}
+
+/// Move to another scale factor (the window changed displays) without a
+/// resize: the surface keeps its logical size, so its physical size changes
+/// by the ratio of the scales, and text is reshaped at the new scale. The host
+/// still updates its layer's contents scale. Not callable inside a frame.
+#[no_mangle]
+pub extern "C" fn mcore_set_scale_factor(ctx: *mut McoreContext, scale: f32) -> McoreStatus {
+    ffi_boundary("mcore_set_scale_factor", || {
+        trace_call!("mcore_set_scale_factor", ctx, scale);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_scale_factor", "ctx");
         };
+        if !(scale.is_finite() && scale > 0.0) {
+            set_err_code(McoreErrorCode::InvalidArg, format!("Invalid scale factor: {scale}"));
+            return McoreStatus::Err;
+        }
         let mut guard = ctx.0.lock();
-        let _ = guard.gfx.resize(&mac_surface);
+        if guard.in_frame {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_set_scale_factor called inside a frame");
+            return McoreStatus::FrameInProgress;
+        }
+        guard.set_scale(scale);
+        McoreStatus::Ok
+    })
+}
+
+/// The scale factor (physical px per logical px) drawing calls are mapped with
+#[no_mangle]
+pub extern "C" fn mcore_get_scale(ctx: *mut McoreContext) -> f32 {
+    ffi_boundary("mcore_get_scale", || {
+        trace_call!("mcore_get_scale", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_get_scale", "ctx");
+        };
+        ctx.0.lock().gfx.scale()
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_begin_frame(ctx: *mut McoreContext, time_seconds: f64) -> McoreStatus {
+    ffi_boundary("mcore_begin_frame", || {
+        trace_call!("mcore_begin_frame", ctx, time_seconds);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_begin_frame", "ctx");
+        };
+        let mut deferred = Deferred::default();
+        let status = begin_frame(&mut ctx.0.lock(), time_seconds, &mut deferred);
+        deferred.fire();
+        status
+    })
+}
+
+/// mcore_begin_frame on a locked engine (also driven by the render thread)
+fn begin_frame(eng: &mut Engine, time_seconds: f64, deferred: &mut Deferred) -> McoreStatus {
+    if eng.render_thread.as_ref().is_some_and(|thread| !thread.is_current()) {
+        set_err_code(McoreErrorCode::InvalidState, "mcore_begin_frame called while the render thread is running; queue frames with mcore_submit_frame");
+        return McoreStatus::Err;
+    }
+    // Resetting the scene would wipe the fragment, and its end would then
+    // swap the stale frame back in
+    if eng.fragments.is_recording() {
+        set_err_code(McoreErrorCode::InvalidState, "mcore_begin_frame called while recording a fragment; call mcore_fragment_end first");
+        return McoreStatus::Err;
     }
+    // The unpresented frame is discarded and a fresh one started
+    let status = if eng.in_frame {
+        set_err_code(McoreErrorCode::InvalidState, "mcore_begin_frame called again before mcore_end_frame_present; the previous frame was discarded");
+        McoreStatus::FrameInProgress
+    } else {
+        McoreStatus::Ok
+    };
+    eng.in_frame = true;
+    eng.time_s = time_seconds;
+    eng.watchdog.begin_frame();
+    eng.damage.begin_frame();
+    eng.overlay.frame_started(time_seconds);
+    if let Some(recorder) = eng.recorder.as_mut() {
+        recorder.begin_frame(time_seconds);
+    }
+    eng.frame_capture.begin_frame(time_seconds);
+    eng.scheduler.frame_started(time_seconds);
+    let animation_scale = eng.prefs.animation_scale();
+    eng.gfx.particles_mut().set_time(time_seconds, animation_scale);
+    eng.scene.reset();
+    eng.filters.clear();
+    eng.layers.begin_frame();
+    eng.backdrops.clear();
+    eng.ui.begin_frame();
+    eng.gestures.tick(time_seconds);
+    eng.hits.begin_frame();
+    deferred.scroll_edges = eng.scrolls.tick(time_seconds, animation_scale);
+    deferred.scroll_edge_callback = eng.scroll_edge_callback;
+    eng.schedule_animations();
+    deferred.redraw = eng.take_redraw();
+    status
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_rect_rounded(ctx: *mut McoreContext, rect: *const McoreRoundedRect) {
+    ffi_boundary("mcore_rect_rounded", || {
+        trace_call!("mcore_rect_rounded", ctx, rect);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_rect_rounded", "ctx");
+        };
+        let Some(rect) = (unsafe { rect.as_ref() }) else {
+            return null_arg("mcore_rect_rounded", "rect");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_rect_rounded") {
+            return;
+        }
+
+        let scale = guard.gfx.scale();
+        let shape = peniko::kurbo::RoundedRect::new(
+            (rect.x * scale) as f64,
+            (rect.y * scale) as f64,
+            ((rect.x + rect.w) * scale) as f64,
+            ((rect.y + rect.h) * scale) as f64,
+            (rect.radius * scale) as f64,
+        );
+
+        let color = Color::new([rect.fill.r, rect.fill.g, rect.fill.b, rect.fill.a]);
+
+        guard.scene.fill(
+            vello::peniko::Fill::NonZero,
+            peniko::kurbo::Affine::IDENTITY,
+            color,
+            None,
+            &shape,
+        );
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_font_register(ctx: *mut McoreContext, blob: *const McoreFontBlob) -> i32 {
+    ffi_boundary("mcore_font_register", || {
+        trace_call!("mcore_font_register", ctx, blob);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_font_register", "ctx");
+        };
+        let Some(blob) = (unsafe { blob.as_ref() }) else {
+            return null_arg("mcore_font_register", "blob");
+        };
+        let mut guard = ctx.0.lock();
+
+        let Some(data) = host_slice(blob.data, blob.len) else {
+            return null_arg("mcore_font_register", "blob.data");
+        };
+
+        // Re-registering the same bytes (e.g. on every window open) reuses the ID
+        // instead of growing the font collection
+        let fonts = guard.fonts.clone();
+        let mut fonts = fonts.lock();
+        if let Some((font_id, _)) = fonts.iter().find(|(_, font)| font.data.data() == data) {
+            return *font_id;
+        }
+
+        let font_blob = Blob::new(Arc::new(data.to_vec()));
+        let font_data = FontData::new(font_blob.clone(), 0);
+
+        let font_id = guard.text_cx.register_font(font_blob);
+        fonts.push((font_id, font_data));
+        // Text already on screen may now shape with the new font as a fallback
+        guard.damage.invalidate();
+
+        font_id
+    })
+}
+
+/// Give an installed font family (e.g. "SF Pro", "Helvetica Neue") a font ID
+/// without embedding its data. `weight` (100-900, 0 for regular) and `italic`
+/// pick the face used when text requests leave them unset.
+/// Returns a font ID, or -1 if the family isn't installed.
+#[no_mangle]
+pub extern "C" fn mcore_font_load_system(
+    ctx: *mut McoreContext,
+    name: *const i8,
+    weight: f32,
+    italic: u8,
+) -> i32 {
+    ffi_boundary("mcore_font_load_system", || {
+        trace_call!("mcore_font_load_system", ctx, name, weight, italic);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_font_load_system", "ctx");
+        };
+        if name.is_null() {
+            return null_arg("mcore_font_load_system", "name");
+        }
+
+        let name = utf8_arg(name);
+        let weight = if weight > 0.0 { weight.clamp(1.0, 1000.0) } else { 400.0 };
+        let mut guard = ctx.0.lock();
+
+        match guard.text_cx.load_system_font(name, weight, italic != 0) {
+            Some(font_id) => {
+                guard.damage.invalidate();
+                font_id
+            }
+            None => {
+                set_err_code(McoreErrorCode::NotFound, format!("Font family not found: {}", name));
+                -1
+            }
+        }
+    })
+}
+
+/// Number of installed font families (for font pickers)
+#[no_mangle]
+pub extern "C" fn mcore_font_system_family_count(ctx: *mut McoreContext) -> i32 {
+    ffi_boundary("mcore_font_system_family_count", || {
+        trace_call!("mcore_font_system_family_count", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_font_system_family_count", "ctx");
+            return 0;
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.text_cx.system_families().len() as i32
+    })
+}
+
+/// Copy the name of installed family `index` (sorted) into buf, null-terminated.
+/// Returns the number of bytes copied (excluding the terminator), or -1 if
+/// index is out of range.
+#[no_mangle]
+pub extern "C" fn mcore_font_system_family_name(
+    ctx: *mut McoreContext,
+    index: i32,
+    buf: *mut u8,
+    buf_len: i32,
+) -> i32 {
+    ffi_boundary("mcore_font_system_family_name", || {
+        trace_call!("mcore_font_system_family_name", ctx, index, buf, buf_len);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_font_system_family_name", "ctx");
+        };
+
+        if buf.is_null() || buf_len <= 0 {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_font_system_family_name");
+            return -1;
+        }
+
+        let mut guard = ctx.0.lock();
+        let Some(name) = usize::try_from(index).ok().and_then(|i| guard.text_cx.system_families().get(i)) else {
+            return -1;
+        };
+
+        let bytes = name.as_bytes();
+        let copy_len = bytes.len().min((buf_len - 1) as usize);
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf, copy_len);
+            *buf.add(copy_len) = 0; // Null terminate
+        }
+        copy_len as i32
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_text_layout(
+    ctx: *mut McoreContext,
+    req: *const McoreTextReq,
+    out: *mut McoreTextMetrics,
+) {
+    ffi_boundary("mcore_text_layout", || {
+        trace_call!("mcore_text_layout", ctx, req, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_layout", "ctx");
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            return null_arg("mcore_text_layout", "req");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_layout", "out");
+        };
+        let mut guard = ctx.0.lock();
+
+        let text = utf8_arg(req.utf8);
+        let scale = guard.gfx.scale();
+
+        let metrics = text::layout_text(
+            &mut guard.text_cx,
+            text,
+            &text_style(req),
+            req.wrap_width,
+            scale,
+        );
+
+        out.advance_w = metrics.width;
+        out.advance_h = metrics.height;
+        out.line_count = metrics.line_count as i32;
+        out.truncated = metrics.truncated as u8;
+    })
+}
+
+// ============================================================================
+// Text Layout Handles
+// ============================================================================
+
+/// A shaped text layout owned by the host
+/// Shape once, then measure, hit test and draw it as often as needed.
+#[repr(C)]
+pub struct McoreTextLayout {
+    shaped: text::ShapedText,
+    scale: f32,
+    /// Built from spans: draw with the span colors instead of a single color
+    styled: bool,
+}
+
+/// Convert FFI spans into text spans
+fn text_spans(spans: &[McoreTextSpan]) -> Vec<text::TextSpan> {
+    spans
+        .iter()
+        .map(|span| text::TextSpan {
+            range: span.start as usize..span.end as usize,
+            color: Color::new([span.color.r, span.color.g, span.color.b, span.color.a]),
+            weight: (span.weight > 0.0).then_some(span.weight),
+            italic: span.italic != 0,
+            underline: span.underline != 0,
+            font_id: (span.font_id >= 0).then_some(span.font_id),
+        })
+        .collect()
+}
+
+/// Slice a host span array, treating null/zero-length as empty
+unsafe fn span_slice<'a>(spans: *const McoreTextSpan, span_count: usize) -> &'a [McoreTextSpan] {
+    if spans.is_null() || span_count == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(spans, span_count)
+    }
+}
+
+/// Shape text into a layout handle. Free it with mcore_text_layout_destroy.
+/// The handle captures the current scale factor; recreate it after a DPI change.
+#[no_mangle]
+pub extern "C" fn mcore_text_layout_create(
+    ctx: *mut McoreContext,
+    req: *const McoreTextReq,
+) -> *mut McoreTextLayout {
+    ffi_boundary("mcore_text_layout_create", || {
+        trace_call!("mcore_text_layout_create", ctx, req);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_layout_create", "ctx");
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            return null_arg("mcore_text_layout_create", "req");
+        };
+
+        let text = utf8_arg(req.utf8);
+        let mut guard = ctx.0.lock();
+
+        let scale = guard.gfx.scale();
+        let layout = text::shape_text(&mut guard.text_cx, text, &text_style(req), req.wrap_width, scale);
+
+        Box::into_raw(Box::new(McoreTextLayout { shaped: layout, scale, styled: false }))
+    })
+}
+
+/// Shape rich text into a layout handle. Spans are byte ranges into req.utf8;
+/// text outside every span uses `default_color`. Span colors are baked in, so
+/// mcore_text_layout_draw ignores its color argument for these handles.
+#[no_mangle]
+pub extern "C" fn mcore_text_layout_create_styled(
+    ctx: *mut McoreContext,
+    req: *const McoreTextReq,
+    spans: *const McoreTextSpan,
+    span_count: usize,
+    default_color: McoreRgba,
+) -> *mut McoreTextLayout {
+    ffi_boundary("mcore_text_layout_create_styled", || {
+        trace_call!("mcore_text_layout_create_styled", ctx, req, spans, span_count);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_layout_create_styled", "ctx");
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            return null_arg("mcore_text_layout_create_styled", "req");
+        };
+
+        let text = utf8_arg(req.utf8);
+        let spans = unsafe { span_slice(spans, span_count) };
+        let mut guard = ctx.0.lock();
+
+        let scale = guard.gfx.scale();
+        let default_color = Color::new([default_color.r, default_color.g, default_color.b, default_color.a]);
+        let layout = text::build_styled_layout(
+            &mut guard.text_cx,
+            text,
+            &text_spans(spans),
+            &text_style(req),
+            req.wrap_width,
+            default_color,
+            scale,
+        );
+
+        Box::into_raw(Box::new(McoreTextLayout { shaped: layout, scale, styled: true }))
+    })
+}
+
+/// Free a layout handle
+#[no_mangle]
+pub extern "C" fn mcore_text_layout_destroy(layout: *mut McoreTextLayout) {
+    ffi_boundary("mcore_text_layout_destroy", || {
+        trace_call!("mcore_text_layout_destroy", layout);
+        if !layout.is_null() {
+            unsafe { drop(Box::from_raw(layout)) }
+        }
+    })
+}
+
+/// Get the logical size and line count of a layout handle
+#[no_mangle]
+pub extern "C" fn mcore_text_layout_metrics(
+    layout: *const McoreTextLayout,
+    out: *mut McoreTextMetrics,
+) {
+    ffi_boundary("mcore_text_layout_metrics", || {
+        trace_call!("mcore_text_layout_metrics", layout, out);
+        let Some(layout) = (unsafe { layout.as_ref() }) else {
+            return null_arg("mcore_text_layout_metrics", "layout");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_layout_metrics", "out");
+        };
+
+        let metrics = text::layout_metrics(&layout.shaped);
+
+        // Layout is in physical pixels, convert to logical
+        out.advance_w = metrics.width / layout.scale;
+        out.advance_h = metrics.height / layout.scale;
+        out.line_count = metrics.line_count as i32;
+        out.truncated = metrics.truncated as u8;
+    })
+}
+
+/// Map a logical point (relative to the layout origin) to a byte offset
+#[no_mangle]
+pub extern "C" fn mcore_text_layout_hit_test(
+    layout: *const McoreTextLayout,
+    x: f32,
+    y: f32,
+) -> i32 {
+    ffi_boundary("mcore_text_layout_hit_test", || {
+        trace_call!("mcore_text_layout_hit_test", layout, x, y);
+        let Some(layout) = (unsafe { layout.as_ref() }) else {
+            null_arg::<()>("mcore_text_layout_hit_test", "layout");
+            return 0;
+        };
+
+        text::hit_test_layout(&layout.shaped.layout, x * layout.scale, y * layout.scale) as i32
+    })
+}
+
+/// Caret geometry for a byte offset in a layout handle (logical, relative to the layout origin)
+#[no_mangle]
+pub extern "C" fn mcore_text_layout_caret(
+    layout: *const McoreTextLayout,
+    byte_offset: i32,
+    out: *mut McoreCaretRect,
+) {
+    ffi_boundary("mcore_text_layout_caret", || {
+        trace_call!("mcore_text_layout_caret", layout, byte_offset, out);
+        let Some(layout) = (unsafe { layout.as_ref() }) else {
+            return null_arg("mcore_text_layout_caret", "layout");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_layout_caret", "out");
+        };
+
+        let caret = text::caret_geometry(&layout.shaped.layout, byte_offset.max(0) as usize);
+
+        // Layout is in physical pixels, convert to logical
+        out.x = caret.x / layout.scale;
+        out.y = caret.y / layout.scale;
+        out.height = caret.height / layout.scale;
+    })
+}
+
+/// Selection highlight rectangles for a byte range of a layout handle
+/// (logical, relative to the layout origin). Writes up to `max_rects` and
+/// returns the total count, so a host can retry with a larger buffer.
+#[no_mangle]
+pub extern "C" fn mcore_text_layout_selection_rects(
+    layout: *const McoreTextLayout,
+    start: i32,
+    end: i32,
+    out: *mut McoreRect,
+    max_rects: i32,
+) -> i32 {
+    ffi_boundary("mcore_text_layout_selection_rects", || {
+        trace_call!("mcore_text_layout_selection_rects", layout, start, end, out, max_rects);
+        let Some(layout) = (unsafe { layout.as_ref() }) else {
+            null_arg::<()>("mcore_text_layout_selection_rects", "layout");
+            return 0;
+        };
+
+        let range = start.max(0) as usize..end.max(0) as usize;
+        let rects: Vec<_> = text::selection_geometry(&layout.shaped.layout, range)
+            .into_iter()
+            .map(|rect| rect.scale_from_origin(1.0 / layout.scale as f64))
+            .collect();
+
+        unsafe { write_rects(&rects, out, max_rects) }
+    })
+}
+
+/// Copy rects into a host buffer (up to `max_rects`), returning the total count
+unsafe fn write_rects(rects: &[peniko::kurbo::Rect], out: *mut McoreRect, max_rects: i32) -> i32 {
+    if !out.is_null() && max_rects > 0 {
+        let out = std::slice::from_raw_parts_mut(out, max_rects as usize);
+        for (slot, rect) in out.iter_mut().zip(rects) {
+            *slot = McoreRect {
+                x: rect.x0 as f32,
+                y: rect.y0 as f32,
+                width: rect.width() as f32,
+                height: rect.height() as f32,
+            };
+        }
+    }
+    rects.len() as i32
+}
+
+/// Draw a layout handle at a logical position
+#[no_mangle]
+pub extern "C" fn mcore_text_layout_draw(
+    ctx: *mut McoreContext,
+    layout: *const McoreTextLayout,
+    x: f32,
+    y: f32,
+    color: McoreRgba,
+) {
+    ffi_boundary("mcore_text_layout_draw", || {
+        trace_call!("mcore_text_layout_draw", ctx, layout, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_layout_draw", "ctx");
+        };
+        let Some(layout) = (unsafe { layout.as_ref() }) else {
+            return null_arg("mcore_text_layout_draw", "layout");
+        };
+
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_text_layout_draw") {
+            return;
+        }
+
+        if layout.styled {
+            let rendering = guard.text_cx.rendering;
+            text::draw_styled_layout(&mut guard.scene, &layout.shaped, x * layout.scale, y * layout.scale, rendering);
+            return;
+        }
+
+        let color_val = Color::new([color.r, color.g, color.b, color.a]);
+        let rendering = guard.text_cx.rendering;
+        text::draw_layout(&mut guard.scene, &layout.shaped, x * layout.scale, y * layout.scale, color_val, rendering);
+    })
+}
+
+// ============================================================================
+// Text Blocks
+// ============================================================================
+
+/// Paragraph kinds for McoreTextBlockParagraph
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreTextBlockKind {
+    Paragraph = 0,
+    /// level is 1-6
+    Heading = 1,
+    /// level is the nesting depth
+    Bullet = 2,
+    /// level is the nesting depth, number the number shown
+    Numbered = 3,
+}
+
+/// McoreTextRun flags
+pub const TEXT_RUN_BOLD: u32 = 1 << 0;
+pub const TEXT_RUN_ITALIC: u32 = 1 << 1;
+pub const TEXT_RUN_UNDERLINE: u32 = 1 << 2;
+/// Inline code: the style's code font and colors
+pub const TEXT_RUN_CODE: u32 = 1 << 3;
+/// Use the run's color instead of the style's
+pub const TEXT_RUN_COLOR: u32 = 1 << 4;
+
+/// Styling for a byte range of a paragraph
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextRun {
+    pub start: u32,
+    pub end: u32,
+    /// MCORE_TEXT_RUN_* flags
+    pub flags: u32,
+    /// Used with MCORE_TEXT_RUN_COLOR
+    pub color: McoreRgba,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextBlockParagraph {
+    pub utf8: *const i8,
+    /// McoreTextBlockKind value
+    pub kind: u32,
+    /// Heading level (1-6) or list nesting depth (0 for the top level)
+    pub level: u32,
+    /// Shown by numbered list items
+    pub number: u32,
+    pub run_count: u32,
+    /// May be null when run_count is 0
+    pub runs: *const McoreTextRun,
+}
+
+/// Styling shared by a block's paragraphs (logical px)
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextBlockStyle {
+    pub font_size_px: f32,
+    pub font_id: i32,
+    /// Font for code runs, -1 for font_id
+    pub code_font_id: i32,
+    /// Multiple of the font size, 0 for the font's default line height
+    pub line_height_multiplier: f32,
+    /// Space between paragraphs; consecutive list items sit closer
+    pub paragraph_spacing: f32,
+    /// Indent per list nesting level, markers included
+    pub list_indent: f32,
+    pub color: McoreRgba,
+    pub code_color: McoreRgba,
+    pub code_background: McoreRgba,
+}
+
+/// Laid out rich text paragraphs owned by the host
+/// Like a layout handle, it captures the scale factor at creation.
+#[repr(C)]
+pub struct McoreTextBlock {
+    block: text_block::TextBlock,
+    scale: f32,
+}
+
+fn rgba_color(color: McoreRgba) -> Color {
+    Color::new([color.r, color.g, color.b, color.a])
+}
+
+/// Convert FFI runs into block runs
+fn text_runs(runs: &[McoreTextRun]) -> Vec<text_block::Run> {
+    runs.iter()
+        .map(|run| text_block::Run {
+            range: run.start as usize..run.end as usize,
+            bold: run.flags & TEXT_RUN_BOLD != 0,
+            italic: run.flags & TEXT_RUN_ITALIC != 0,
+            underline: run.flags & TEXT_RUN_UNDERLINE != 0,
+            code: run.flags & TEXT_RUN_CODE != 0,
+            color: (run.flags & TEXT_RUN_COLOR != 0).then(|| rgba_color(run.color)),
+        })
+        .collect()
+}
+
+/// Lay out headings, paragraphs and list items stacked in one handle,
+/// wrapping at `width` (logical px, 0 for no wrapping). Free it with
+/// mcore_text_block_destroy.
+#[no_mangle]
+pub extern "C" fn mcore_text_block_create(
+    ctx: *mut McoreContext,
+    paragraphs: *const McoreTextBlockParagraph,
+    paragraph_count: usize,
+    style: *const McoreTextBlockStyle,
+    width: f32,
+) -> *mut McoreTextBlock {
+    ffi_boundary("mcore_text_block_create", || {
+        trace_call!("mcore_text_block_create", ctx, paragraphs, paragraph_count, style, width);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_block_create", "ctx");
+        };
+        let Some(paragraphs) = host_slice(paragraphs, paragraph_count) else {
+            return null_arg("mcore_text_block_create", "paragraphs");
+        };
+        let Some(style) = (unsafe { style.as_ref() }) else {
+            return null_arg("mcore_text_block_create", "style");
+        };
+        let mut guard = ctx.0.lock();
+        let scale = guard.gfx.scale();
+
+        let mut runs = Vec::with_capacity(paragraphs.len());
+        for paragraph in paragraphs {
+            let Some(paragraph_runs) = host_slice(paragraph.runs, paragraph.run_count as usize) else {
+                return null_arg("mcore_text_block_create", "runs");
+            };
+            runs.push(text_runs(paragraph_runs));
+        }
+        let paragraphs: Vec<_> = paragraphs
+            .iter()
+            .zip(runs)
+            .map(|(paragraph, runs)| text_block::Paragraph {
+                text: utf8_arg(paragraph.utf8),
+                kind: text_block::Kind::from_ffi(paragraph.kind, paragraph.level, paragraph.number),
+                runs,
+            })
+            .collect();
+
+        let mut text_style = text::TextStyle::new(style.font_size_px, style.font_id);
+        if style.line_height_multiplier > 0.0 {
+            text_style.line_height = Some(style.line_height_multiplier);
+        }
+        let block_style = text_block::BlockStyle {
+            text: text_style,
+            code_font_id: (style.code_font_id >= 0).then_some(style.code_font_id),
+            color: rgba_color(style.color),
+            code_color: rgba_color(style.code_color),
+            code_background: rgba_color(style.code_background),
+            paragraph_spacing: style.paragraph_spacing.max(0.0),
+            list_indent: style.list_indent.max(0.0),
+        };
+        let width = if width > 0.0 { width } else { text::NO_WRAP_WIDTH };
+        let block = text_block::TextBlock::new(&mut guard.text_cx, &paragraphs, &block_style, width, scale);
+
+        Box::into_raw(Box::new(McoreTextBlock { block, scale }))
+    })
+}
+
+/// Free a text block
+#[no_mangle]
+pub extern "C" fn mcore_text_block_destroy(block: *mut McoreTextBlock) {
+    ffi_boundary("mcore_text_block_destroy", || {
+        trace_call!("mcore_text_block_destroy", block);
+        if !block.is_null() {
+            unsafe { drop(Box::from_raw(block)) }
+        }
+    })
+}
+
+/// Logical size of a text block: its widest line and total height
+#[no_mangle]
+pub extern "C" fn mcore_text_block_size(block: *const McoreTextBlock, out: *mut McoreTextSize) {
+    ffi_boundary("mcore_text_block_size", || {
+        trace_call!("mcore_text_block_size", block, out);
+        let Some(block) = (unsafe { block.as_ref() }) else {
+            return null_arg("mcore_text_block_size", "block");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_block_size", "out");
+        };
+        let (width, height) = block.block.size();
+        out.width = width / block.scale;
+        out.height = height / block.scale;
+    })
+}
+
+/// Where paragraph `index` landed (logical, relative to the block origin;
+/// list items start after their marker)
+#[no_mangle]
+pub extern "C" fn mcore_text_block_paragraph_rect(
+    block: *const McoreTextBlock,
+    index: u32,
+    out: *mut McoreRect,
+) -> McoreStatus {
+    ffi_boundary("mcore_text_block_paragraph_rect", || {
+        trace_call!("mcore_text_block_paragraph_rect", block, index, out);
+        let Some(block) = (unsafe { block.as_ref() }) else {
+            return null_arg("mcore_text_block_paragraph_rect", "block");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_block_paragraph_rect", "out");
+        };
+        let Some(rect) = block.block.paragraph_rect(index as usize) else {
+            set_err_code(
+                McoreErrorCode::NotFound,
+                format!("Paragraph {} out of range (block has {})", index, block.block.paragraph_count()),
+            );
+            return McoreStatus::Err;
+        };
+        let rect = rect.scale_from_origin(1.0 / block.scale as f64);
+        *out = McoreRect { x: rect.x0 as f32, y: rect.y0 as f32, width: rect.width() as f32, height: rect.height() as f32 };
+        McoreStatus::Ok
+    })
+}
+
+/// Map a logical point (relative to the block origin) to the nearest
+/// paragraph and a byte offset in its text. Writes the paragraph index to
+/// `paragraph` (may be null) and returns the offset, or -1 for an empty block.
+#[no_mangle]
+pub extern "C" fn mcore_text_block_hit_test(
+    block: *const McoreTextBlock,
+    x: f32,
+    y: f32,
+    paragraph: *mut i32,
+) -> i32 {
+    ffi_boundary("mcore_text_block_hit_test", || {
+        trace_call!("mcore_text_block_hit_test", block, x, y, paragraph);
+        let Some(block) = (unsafe { block.as_ref() }) else {
+            return null_arg("mcore_text_block_hit_test", "block");
+        };
+        let Some((index, offset)) = block.block.hit_test(x * block.scale, y * block.scale) else {
+            return -1;
+        };
+        if let Some(paragraph) = unsafe { paragraph.as_mut() } {
+            *paragraph = index as i32;
+        }
+        offset as i32
+    })
+}
+
+/// Draw a text block at a logical position
+#[no_mangle]
+pub extern "C" fn mcore_text_block_draw(ctx: *mut McoreContext, block: *const McoreTextBlock, x: f32, y: f32) {
+    ffi_boundary("mcore_text_block_draw", || {
+        trace_call!("mcore_text_block_draw", ctx, block, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_block_draw", "ctx");
+        };
+        let Some(block) = (unsafe { block.as_ref() }) else {
+            return null_arg("mcore_text_block_draw", "block");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_text_block_draw") {
+            return;
+        }
+        let rendering = guard.text_cx.rendering;
+        block.block.draw(&mut guard.scene, x * block.scale, y * block.scale, rendering);
+    })
+}
+
+// ============================================================================
+// Code Views
+// ============================================================================
+
+/// Monospace code drawn from a buffer, shaping only the lines in the viewport
+/// (logical px)
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreCodeReq {
+    /// UTF-8, not null-terminated; may be null when len is 0
+    pub text: *const u8,
+    pub len: usize,
+    pub font_size_px: f32,
+    pub font_id: i32,
+    /// Multiple of the font size, 0 for the font's default line height
+    pub line_height_multiplier: f32,
+    /// Columns between tab stops, 0 for 8
+    pub tab_size: u32,
+    /// 1 to draw line numbers in line_number_color
+    pub line_numbers: u32,
+    /// How far the viewport is scrolled down, and its height
+    pub scroll_y: f32,
+    pub viewport_height: f32,
+    pub color: McoreRgba,
+    pub line_number_color: McoreRgba,
+}
+
+/// A syntax color for a byte range of a code buffer
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreCodeToken {
+    pub start: u32,
+    pub end: u32,
+    pub color: McoreRgba,
+}
+
+/// Logical px
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreCodeMetrics {
+    pub line_count: u32,
+    pub line_height: f32,
+    /// Where the code starts; 0 without line numbers
+    pub gutter_width: f32,
+    /// line_count * line_height, for sizing a scroll view
+    pub content_height: f32,
+}
+
+/// The buffer and style of a code request
+fn code_request<'a>(text_cx: &mut text::TextContext, req: &McoreCodeReq, scale: f32) -> Option<(&'a [u8], code::CodeStyle)> {
+    let text = host_slice(req.text, req.len)?;
+    let mut style = code::CodeStyle::new(req.font_size_px, req.font_id, rgba_color(req.color));
+    if req.line_height_multiplier > 0.0 {
+        style.text.line_height = Some(req.line_height_multiplier);
+    }
+    style.set_tab_size(text_cx, req.tab_size, scale);
+    style.line_numbers = (req.line_numbers != 0).then(|| rgba_color(req.line_number_color));
+    Some((text, style))
+}
+
+/// Line count, line height and gutter width of a code request, for sizing
+/// its scroll view
+#[no_mangle]
+pub extern "C" fn mcore_code_measure(ctx: *mut McoreContext, req: *const McoreCodeReq, out: *mut McoreCodeMetrics) {
+    ffi_boundary("mcore_code_measure", || {
+        trace_call!("mcore_code_measure", ctx, req, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_code_measure", "ctx");
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            return null_arg("mcore_code_measure", "req");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_code_measure", "out");
+        };
+        let mut guard = ctx.0.lock();
+        let scale = guard.gfx.scale();
+        let Some((text, style)) = code_request(&mut guard.text_cx, req, scale) else {
+            return null_arg("mcore_code_measure", "text");
+        };
+        let metrics = code::metrics(&mut guard.text_cx, text, &style, scale);
+        *out = McoreCodeMetrics {
+            line_count: metrics.line_count as u32,
+            line_height: metrics.line_height,
+            gutter_width: metrics.gutter_width,
+            content_height: metrics.content_height(),
+        };
+    })
+}
+
+/// Draw the lines of a code request visible in its viewport, whose top left
+/// is at logical (x, y). Tokens must be sorted by start and not overlap;
+/// text outside them uses req.color. Lines aren't clipped to the viewport's
+/// width; push a clip rect for that.
+#[no_mangle]
+pub extern "C" fn mcore_code_draw(
+    ctx: *mut McoreContext,
+    req: *const McoreCodeReq,
+    tokens: *const McoreCodeToken,
+    token_count: usize,
+    x: f32,
+    y: f32,
+) {
+    ffi_boundary("mcore_code_draw", || {
+        trace_call!("mcore_code_draw", ctx, req, tokens, token_count, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_code_draw", "ctx");
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            return null_arg("mcore_code_draw", "req");
+        };
+        let Some(tokens) = host_slice(tokens, token_count) else {
+            return null_arg("mcore_code_draw", "tokens");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_code_draw") {
+            return;
+        }
+
+        let engine = &mut *guard;
+        let scale = engine.gfx.scale();
+        let Some((text, style)) = code_request(&mut engine.text_cx, req, scale) else {
+            return null_arg("mcore_code_draw", "text");
+        };
+        let tokens: Vec<_> = tokens
+            .iter()
+            .map(|token| code::Token {
+                range: token.start as usize..token.end as usize,
+                color: rgba_color(token.color),
+            })
+            .collect();
+        code::draw(
+            &mut engine.scene,
+            &mut engine.text_cx,
+            text,
+            &tokens,
+            &style,
+            x,
+            y,
+            req.scroll_y,
+            req.viewport_height,
+            scale,
+        );
+    })
+}
+
+// ============================================================================
+// Text Windows
+// ============================================================================
+
+/// A large document shaped a range of lines at a time
+#[repr(C)]
+pub struct McoreTextWindow {
+    window: text_window::TextWindow,
+}
+
+/// Index a document's lines for windowed layout; nothing is shaped until
+/// mcore_text_window_set_lines. Lines wrap at req.wrap_width and use its
+/// style; req.max_lines is ignored. The text is copied. Free the window with
+/// mcore_text_window_destroy.
+#[no_mangle]
+pub extern "C" fn mcore_text_window_create(req: *const McoreTextReq) -> *mut McoreTextWindow {
+    ffi_boundary("mcore_text_window_create", || {
+        trace_call!("mcore_text_window_create", req);
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            return null_arg("mcore_text_window_create", "req");
+        };
+        let window = text_window::TextWindow::new(utf8_arg(req.utf8).to_string(), text_style(req), req.wrap_width);
+        Box::into_raw(Box::new(McoreTextWindow { window }))
+    })
+}
+
+/// Free a text window
+#[no_mangle]
+pub extern "C" fn mcore_text_window_destroy(window: *mut McoreTextWindow) {
+    ffi_boundary("mcore_text_window_destroy", || {
+        trace_call!("mcore_text_window_destroy", window);
+        if !window.is_null() {
+            unsafe { drop(Box::from_raw(window)) }
+        }
+    })
+}
+
+/// Hard lines in the document (one more than its line breaks)
+#[no_mangle]
+pub extern "C" fn mcore_text_window_line_count(window: *const McoreTextWindow) -> u32 {
+    ffi_boundary("mcore_text_window_line_count", || {
+        trace_call!("mcore_text_window_line_count", window);
+        let Some(window) = (unsafe { window.as_ref() }) else {
+            return null_arg("mcore_text_window_line_count", "window");
+        };
+        window.window.line_count() as u32
+    })
+}
+
+/// Line containing a byte offset of the document, for turning a byte range
+/// into lines
+#[no_mangle]
+pub extern "C" fn mcore_text_window_line_at(window: *const McoreTextWindow, byte_offset: usize) -> u32 {
+    ffi_boundary("mcore_text_window_line_at", || {
+        trace_call!("mcore_text_window_line_at", window, byte_offset);
+        let Some(window) = (unsafe { window.as_ref() }) else {
+            return null_arg("mcore_text_window_line_at", "window");
+        };
+        window.window.line_at(byte_offset) as u32
+    })
+}
+
+/// Byte range of a line, without its line break
+#[no_mangle]
+pub extern "C" fn mcore_text_window_line_range(
+    window: *const McoreTextWindow,
+    line: u32,
+    out_start: *mut usize,
+    out_end: *mut usize,
+) -> McoreStatus {
+    ffi_boundary("mcore_text_window_line_range", || {
+        trace_call!("mcore_text_window_line_range", window, line, out_start, out_end);
+        let Some(window) = (unsafe { window.as_ref() }) else {
+            return null_arg("mcore_text_window_line_range", "window");
+        };
+        let (Some(out_start), Some(out_end)) = (unsafe { out_start.as_mut() }, unsafe { out_end.as_mut() }) else {
+            return null_arg("mcore_text_window_line_range", "out");
+        };
+        let Some(range) = window.window.line_range(line as usize) else {
+            set_err_code(
+                McoreErrorCode::NotFound,
+                format!("Line {} out of range (document has {})", line, window.window.line_count()),
+            );
+            return McoreStatus::Err;
+        };
+        *out_start = range.start;
+        *out_end = range.end;
+        McoreStatus::Ok
+    })
+}
+
+/// Shape lines first..first + count (clamped to the document) for drawing
+/// and hit testing. Lines shaped for the previous range are reused, so
+/// scrolling only shapes the lines coming into view. Writes each line's
+/// logical height to `heights` (room for `count`, may be null) and returns
+/// the range's total height.
+#[no_mangle]
+pub extern "C" fn mcore_text_window_set_lines(
+    ctx: *mut McoreContext,
+    window: *mut McoreTextWindow,
+    first: u32,
+    count: u32,
+    heights: *mut f32,
+) -> f32 {
+    ffi_boundary("mcore_text_window_set_lines", || {
+        trace_call!("mcore_text_window_set_lines", ctx, window, first, count, heights);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_window_set_lines", "ctx");
+        };
+        let Some(window) = (unsafe { window.as_mut() }) else {
+            return null_arg("mcore_text_window_set_lines", "window");
+        };
+        let mut guard = ctx.0.lock();
+        let scale = guard.gfx.scale();
+        let first = first as usize;
+        let lines = window.window.set_range(&mut guard.text_cx, first..first.saturating_add(count as usize), scale);
+
+        if !heights.is_null() {
+            let out = unsafe { std::slice::from_raw_parts_mut(heights, count as usize) };
+            for (slot, height) in out.iter_mut().zip(&lines) {
+                *slot = height / scale;
+            }
+        }
+        lines.iter().sum::<f32>() / scale
+    })
+}
+
+/// Map a logical point (relative to the top of the shaped lines) to a byte
+/// offset of the document; -1 when no lines are shaped
+#[no_mangle]
+pub extern "C" fn mcore_text_window_hit_test(window: *const McoreTextWindow, x: f32, y: f32) -> i32 {
+    ffi_boundary("mcore_text_window_hit_test", || {
+        trace_call!("mcore_text_window_hit_test", window, x, y);
+        let Some(window) = (unsafe { window.as_ref() }) else {
+            return null_arg("mcore_text_window_hit_test", "window");
+        };
+        let scale = window.window.scale();
+        window.window.hit_test(x * scale, y * scale).map_or(-1, |offset| offset as i32)
+    })
+}
+
+/// Draw the shaped lines with the first one's top left at a logical position
+#[no_mangle]
+pub extern "C" fn mcore_text_window_draw(
+    ctx: *mut McoreContext,
+    window: *const McoreTextWindow,
+    x: f32,
+    y: f32,
+    color: McoreRgba,
+) {
+    ffi_boundary("mcore_text_window_draw", || {
+        trace_call!("mcore_text_window_draw", ctx, window, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_window_draw", "ctx");
+        };
+        let Some(window) = (unsafe { window.as_ref() }) else {
+            return null_arg("mcore_text_window_draw", "window");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_text_window_draw") {
+            return;
+        }
+        // Lines are shaped at the scale of the last mcore_text_window_set_lines
+        let scale = window.window.scale();
+        let rendering = guard.text_cx.rendering;
+        window.window.draw(&mut guard.scene, x * scale, y * scale, rgba_color(color), rendering);
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_measure_text(
+    ctx: *mut McoreContext,
+    text: *const i8,
+    font_size: f32,
+    max_width: f32,
+    font_id: i32,
+    out: *mut McoreTextSize,
+) {
+    ffi_boundary("mcore_measure_text", || {
+        trace_call!("mcore_measure_text", ctx, text, font_size, max_width, font_id, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_measure_text", "ctx");
+        };
+        let text = utf8_arg(text);
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_measure_text", "out");
+        };
+        let mut guard = ctx.0.lock();
+
+        // Increment instrumentation counter
+        guard.text_stats.total_measure_calls += 1;
+
+        let scale = guard.gfx.scale();
+
+        // Measure with scale for quality, returns logical measurements
+        let (width, height) = text::measure_text(
+            &mut guard.text_cx,
+            text,
+            &text::TextStyle::new(font_size, font_id),
+            max_width,
+            scale,
+        );
+
+        out.width = width;
+        out.height = height;
+    })
+}
+
+/// Same as mcore_measure_text, but single lines are measured from cached
+/// per-word advances instead of a full layout. Cheap enough for speculative
+/// measuring during layout; text that wraps or has line breaks takes the
+/// full path.
+#[no_mangle]
+pub extern "C" fn mcore_measure_text_fast(
+    ctx: *mut McoreContext,
+    text: *const i8,
+    font_size: f32,
+    max_width: f32,
+    font_id: i32,
+    out: *mut McoreTextSize,
+) {
+    ffi_boundary("mcore_measure_text_fast", || {
+        trace_call!("mcore_measure_text_fast", ctx, text, font_size, max_width, font_id, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_measure_text_fast", "ctx");
+        };
+        if text.is_null() {
+            return null_arg("mcore_measure_text_fast", "text");
+        }
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_measure_text_fast", "out");
+        };
+
+        let text = utf8_arg(text);
+        let mut guard = ctx.0.lock();
+        guard.text_stats.total_measure_calls += 1;
+        let scale = guard.gfx.scale();
+
+        let (width, height) = text::measure_text_fast(
+            &mut guard.text_cx,
+            text,
+            &text::TextStyle::new(font_size, font_id),
+            max_width,
+            scale,
+        );
+        out.width = width;
+        out.height = height;
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_measure_text_to_byte_offset(
+    ctx: *mut McoreContext,
+    text: *const i8,
+    font_size: f32,
+    byte_offset: i32,
+) -> f32 {
+    ffi_boundary("mcore_measure_text_to_byte_offset", || {
+        trace_call!("mcore_measure_text_to_byte_offset", ctx, text, font_size, byte_offset);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_measure_text_to_byte_offset", "ctx");
+        };
+        let text = utf8_arg(text);
+        let mut guard = ctx.0.lock();
+
+        // Increment instrumentation counter
+        guard.text_stats.total_offset_calls += 1;
+
+        let scale = guard.gfx.scale();
+        let byte_offset = byte_offset.max(0) as usize;
+
+        text::byte_offset_to_x(
+            &mut guard.text_cx,
+            text,
+            font_size,
+            byte_offset,
+            scale,
+        )
+    })
+}
+
+/// Caret geometry for a byte offset in text shaped from `req` (wrapping at
+/// req.wrap_width), relative to the text origin in logical pixels
+#[no_mangle]
+pub extern "C" fn mcore_text_caret(
+    ctx: *mut McoreContext,
+    req: *const McoreTextReq,
+    byte_offset: i32,
+    out: *mut McoreCaretRect,
+) {
+    ffi_boundary("mcore_text_caret", || {
+        trace_call!("mcore_text_caret", ctx, req, byte_offset, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_caret", "ctx");
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            return null_arg("mcore_text_caret", "req");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_caret", "out");
+        };
+
+        let text = utf8_arg(req.utf8);
+        let mut guard = ctx.0.lock();
+
+        guard.text_stats.total_offset_calls += 1;
+
+        let scale = guard.gfx.scale();
+        let caret = text::caret_for_offset(
+            &mut guard.text_cx,
+            text,
+            &text_style(req),
+            req.wrap_width,
+            byte_offset.max(0) as usize,
+            scale,
+        );
+
+        out.x = caret.x;
+        out.y = caret.y;
+        out.height = caret.height;
+    })
+}
+
+/// Map a logical point (relative to the text origin) to a byte offset in text
+/// shaped from `req`, wrapping at req.wrap_width
+#[no_mangle]
+pub extern "C" fn mcore_text_hit_test(
+    ctx: *mut McoreContext,
+    req: *const McoreTextReq,
+    x: f32,
+    y: f32,
+) -> i32 {
+    ffi_boundary("mcore_text_hit_test", || {
+        trace_call!("mcore_text_hit_test", ctx, req, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_text_hit_test", "ctx");
+            return 0;
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            null_arg::<()>("mcore_text_hit_test", "req");
+            return 0;
+        };
+
+        let text = utf8_arg(req.utf8);
+        let mut guard = ctx.0.lock();
+
+        guard.text_stats.total_offset_calls += 1;
+
+        let scale = guard.gfx.scale();
+        text::hit_test_text(&mut guard.text_cx, text, &text_style(req), req.wrap_width, x, y, scale) as i32
+    })
+}
+
+/// Selection highlight rectangles for the byte range start..end of text shaped
+/// from `req` (logical, relative to the text origin), one or more per line.
+/// Writes up to `max_rects` and returns the total count.
+#[no_mangle]
+pub extern "C" fn mcore_text_selection_rects(
+    ctx: *mut McoreContext,
+    req: *const McoreTextReq,
+    start: i32,
+    end: i32,
+    out: *mut McoreRect,
+    max_rects: i32,
+) -> i32 {
+    ffi_boundary("mcore_text_selection_rects", || {
+        trace_call!("mcore_text_selection_rects", ctx, req, start, end, out, max_rects);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_text_selection_rects", "ctx");
+            return 0;
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            null_arg::<()>("mcore_text_selection_rects", "req");
+            return 0;
+        };
+
+        let text = utf8_arg(req.utf8);
+        let mut guard = ctx.0.lock();
+
+        let scale = guard.gfx.scale();
+        let rects = text::selection_rects(
+            &mut guard.text_cx,
+            text,
+            &text_style(req),
+            req.wrap_width,
+            start.max(0) as usize..end.max(0) as usize,
+            scale,
+        );
+
+        unsafe { write_rects(&rects, out, max_rects) }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_get_text_stats(
+    ctx: *mut McoreContext,
+    out: *mut McoreTextStats,
+) {
+    ffi_boundary("mcore_get_text_stats", || {
+        trace_call!("mcore_get_text_stats", ctx, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_get_text_stats", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_get_text_stats", "out");
+        };
+        let guard = ctx.0.lock();
+
+        out.total_measure_calls = guard.text_stats.total_measure_calls;
+        out.total_offset_calls = guard.text_stats.total_offset_calls;
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_reset_text_stats(ctx: *mut McoreContext) {
+    ffi_boundary("mcore_reset_text_stats", || {
+        trace_call!("mcore_reset_text_stats", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_reset_text_stats", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        guard.text_stats.reset();
+        guard.text_cx.reset_layout_cache_stats();
+    })
+}
+
+/// Bound this context's shaped-layout cache. max_entries 0 restores the
+/// default count; max_bytes 0 removes the byte limit. Glyph rasterization is
+/// cached by the renderer and isn't covered by this budget.
+#[no_mangle]
+pub extern "C" fn mcore_text_cache_set_budget(
+    ctx: *mut McoreContext,
+    max_entries: u32,
+    max_bytes: u64,
+) -> McoreStatus {
+    ffi_boundary("mcore_text_cache_set_budget", || {
+        trace_call!("mcore_text_cache_set_budget", ctx, max_entries, max_bytes);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_cache_set_budget", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        let max_entries = if max_entries == 0 { text::LAYOUT_CACHE_CAPACITY } else { max_entries as usize };
+        let max_bytes = if max_bytes == 0 { usize::MAX } else { usize::try_from(max_bytes).unwrap_or(usize::MAX) };
+        guard.text_cx.set_layout_cache_budget(max_entries, max_bytes);
+        McoreStatus::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_text_cache_get_stats(
+    ctx: *mut McoreContext,
+    out: *mut McoreTextCacheStats,
+) -> McoreStatus {
+    ffi_boundary("mcore_text_cache_get_stats", || {
+        trace_call!("mcore_text_cache_get_stats", ctx, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_cache_get_stats", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_cache_get_stats", "out");
+        };
+
+        let guard = ctx.0.lock();
+        let stats = guard.text_cx.layout_cache_stats();
+        *out = McoreTextCacheStats {
+            entries: stats.entries as u32,
+            max_entries: stats.max_entries as u32,
+            bytes: stats.bytes as u64,
+            max_bytes: if stats.max_bytes == usize::MAX { 0 } else { stats.max_bytes as u64 },
+            hits: stats.hits,
+            misses: stats.misses,
+            evictions: stats.evictions,
+        };
+        McoreStatus::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_text_draw(
+    ctx: *mut McoreContext,
+    req: *const McoreTextReq,
+    x: f32,
+    y: f32,
+    color: McoreRgba,
+) {
+    ffi_boundary("mcore_text_draw", || {
+        trace_call!("mcore_text_draw", ctx, req, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_draw", "ctx");
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            return null_arg("mcore_text_draw", "req");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_text_draw") {
+            return;
+        }
+
+        let text = utf8_arg(req.utf8);
+        let scale = guard.gfx.scale();
+        let color_val = Color::new([color.r, color.g, color.b, color.a]);
+
+        // Use raw pointers to split borrows
+        let scene_ptr = &mut guard.scene as *mut Scene;
+        let text_cx_ptr = &mut guard.text_cx as *mut text::TextContext;
+
+        unsafe {
+            text::draw_text(
+                &mut *scene_ptr,
+                &mut *text_cx_ptr,
+                text,
+                x * scale,
+                y * scale,
+                &text_style(req),
+                req.wrap_width,
+                color_val,
+                scale,
+            );
+        }
+    })
+}
+
+/// Draw rich text with per-range styling in one pass (shaped every call;
+/// use mcore_text_layout_create_styled for text that doesn't change)
+#[no_mangle]
+pub extern "C" fn mcore_text_draw_spans(
+    ctx: *mut McoreContext,
+    req: *const McoreTextReq,
+    spans: *const McoreTextSpan,
+    span_count: usize,
+    x: f32,
+    y: f32,
+    default_color: McoreRgba,
+) {
+    ffi_boundary("mcore_text_draw_spans", || {
+        trace_call!("mcore_text_draw_spans", ctx, req, spans, span_count, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_draw_spans", "ctx");
+        };
+        let Some(req) = (unsafe { req.as_ref() }) else {
+            return null_arg("mcore_text_draw_spans", "req");
+        };
+        let spans = unsafe { span_slice(spans, span_count) };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_text_draw_spans") {
+            return;
+        }
+
+        let text = utf8_arg(req.utf8);
+        let scale = guard.gfx.scale();
+        let default_color = Color::new([default_color.r, default_color.g, default_color.b, default_color.a]);
+
+        let layout = text::build_styled_layout(
+            &mut guard.text_cx,
+            text,
+            &text_spans(spans),
+            &text_style(req),
+            req.wrap_width,
+            default_color,
+            scale,
+        );
+        let rendering = guard.text_cx.rendering;
+        text::draw_styled_layout(&mut guard.scene, &layout, x * scale, y * scale, rendering);
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_push_clip_rect(
+    ctx: *mut McoreContext,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+) {
+    ffi_boundary("mcore_push_clip_rect", || {
+        trace_call!("mcore_push_clip_rect", ctx, x, y, width, height);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_push_clip_rect", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_push_clip_rect") {
+            return;
+        }
+
+        // Push a clip layer with the specified rectangle
+        let scale = guard.gfx.scale();
+        let clip_rect = peniko::kurbo::Rect::new(
+            (x * scale) as f64,
+            (y * scale) as f64,
+            ((x + width) * scale) as f64,
+            ((y + height) * scale) as f64,
+        );
+        guard.scene.push_layer(vello::peniko::BlendMode::default(), 1.0, peniko::kurbo::Affine::IDENTITY, &clip_rect);
+        guard.layers.push(peniko::kurbo::Affine::IDENTITY, clip_rect);
+        guard.hits.push_clip(hit::Rect::from_xywh(x, y, width, height));
+    })
+}
+
+/// Push a clip layer shaped like a rounded rectangle
+#[no_mangle]
+pub extern "C" fn mcore_push_clip_rounded_rect(
+    ctx: *mut McoreContext,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+) {
+    ffi_boundary("mcore_push_clip_rounded_rect", || {
+        trace_call!("mcore_push_clip_rounded_rect", ctx, x, y, width, height, radius);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_push_clip_rounded_rect", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_push_clip_rounded_rect") {
+            return;
+        }
+
+        let scale = guard.gfx.scale();
+        let clip = peniko::kurbo::RoundedRect::new(
+            (x * scale) as f64,
+            (y * scale) as f64,
+            ((x + width) * scale) as f64,
+            ((y + height) * scale) as f64,
+            (radius * scale) as f64,
+        );
+        guard.scene.push_layer(vello::peniko::BlendMode::default(), 1.0, peniko::kurbo::Affine::IDENTITY, &clip);
+        guard.layers.push(peniko::kurbo::Affine::IDENTITY, clip);
+
+        // Hit testing uses the rect bounds (corners are not excluded)
+        guard.hits.push_clip(hit::Rect::from_xywh(x, y, width, height));
+    })
+}
+
+/// Register a vector path for later use (e.g. clipping)
+/// `verbs` holds `verb_count` MCORE_PATH_VERB_* bytes, `points` holds `point_count` (x, y) pairs in logical px
+/// Returns a path ID (>= 0) or -1 on error
+#[no_mangle]
+pub extern "C" fn mcore_path_create(
+    ctx: *mut McoreContext,
+    verbs: *const u8,
+    verb_count: i32,
+    points: *const f32,
+    point_count: i32,
+) -> i32 {
+    ffi_boundary("mcore_path_create", || {
+        trace_call!("mcore_path_create", ctx, verbs, verb_count, points, point_count);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_path_create", "ctx");
+        };
+
+        if verbs.is_null() || verb_count <= 0 || (points.is_null() && point_count > 0) {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_path_create");
+            return -1;
+        }
+
+        let verbs = unsafe { std::slice::from_raw_parts(verbs, verb_count as usize) };
+        let points: &[f32] = if point_count > 0 {
+            unsafe { std::slice::from_raw_parts(points, point_count as usize * 2) }
+        } else {
+            &[]
+        };
+
+        match path::build_path(verbs, points) {
+            Ok(bez) => {
+                let mut guard = ctx.0.lock();
+                guard.paths.register(bez)
+            }
+            Err(e) => {
+                set_err(e);
+                -1
+            }
+        }
+    })
+}
+
+/// Free a registered path
+#[no_mangle]
+pub extern "C" fn mcore_path_destroy(ctx: *mut McoreContext, path_id: i32) {
+    ffi_boundary("mcore_path_destroy", || {
+        trace_call!("mcore_path_destroy", ctx, path_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_path_destroy", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        if !guard.paths.remove(path_id) {
+            set_err_code(McoreErrorCode::NotFound, format!("Path ID {} not found", path_id));
+        }
+    })
+}
+
+/// Push a clip layer shaped like a registered path (offset by x, y, logical px)
+/// Returns Err (and pushes nothing) if the path ID is unknown, so the caller must not pop
+#[no_mangle]
+pub extern "C" fn mcore_push_clip_path(
+    ctx: *mut McoreContext,
+    path_id: i32,
+    x: f32,
+    y: f32,
+) -> McoreStatus {
+    ffi_boundary("mcore_push_clip_path", || {
+        trace_call!("mcore_push_clip_path", ctx, path_id, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_push_clip_path", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_push_clip_path") {
+            return McoreStatus::NotInFrame;
+        }
+
+        let Some(clip) = guard.paths.get(path_id).cloned() else {
+            set_err_code(McoreErrorCode::NotFound, format!("Path ID {} not found", path_id));
+            return McoreStatus::Err;
+        };
+
+        let scale = guard.gfx.scale();
+        let transform = peniko::kurbo::Affine::scale(scale as f64) * peniko::kurbo::Affine::translate((x as f64, y as f64));
+        guard.scene.push_layer(vello::peniko::BlendMode::default(), 1.0, transform, &clip);
+
+        // Hit testing clips to the path's bounding box
+        use peniko::kurbo::Shape;
+        let bbox = clip.bounding_box();
+        guard.layers.push(transform, clip);
+        guard.hits.push_clip(hit::Rect::from_xywh(
+            bbox.x0 as f32 + x,
+            bbox.y0 as f32 + y,
+            bbox.width() as f32,
+            bbox.height() as f32,
+        ));
+        McoreStatus::Ok
+    })
+}
+
+/// Pop the innermost clip; with none pushed this frame the pop is ignored and
+/// counted in McoreFrameStats::layer_warnings
+#[no_mangle]
+pub extern "C" fn mcore_pop_clip(ctx: *mut McoreContext) {
+    ffi_boundary("mcore_pop_clip", || {
+        trace_call!("mcore_pop_clip", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_pop_clip", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_pop_clip") {
+            return;
+        }
+        if !guard.layers.pop() {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_pop_clip called with no clip pushed");
+            return;
+        }
+        guard.scene.pop_layer();
+        guard.hits.pop_clip();
+    })
+}
+
+/// Push a backdrop blur layer: what's been drawn so far is blurred inside the
+/// rounded rect, and what's drawn until the matching mcore_pop_clip is
+/// clipped to it (frosted-glass panels). `blur_radius` is the Gaussian's
+/// standard deviation in logical px. Not allowed inside clips or fragments.
+#[no_mangle]
+pub extern "C" fn mcore_push_blur_layer(
+    ctx: *mut McoreContext,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    corner_radius: f32,
+    blur_radius: f32,
+) -> McoreStatus {
+    ffi_boundary("mcore_push_blur_layer", || {
+        trace_call!("mcore_push_blur_layer", ctx, x, y, width, height, corner_radius, blur_radius);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_push_blur_layer", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_push_blur_layer") {
+            return McoreStatus::Err;
+        }
+        let valid = |v: f32| v.is_finite() && v >= 0.0;
+        let rect_valid = x.is_finite() && y.is_finite() && valid(width) && valid(height) && valid(corner_radius);
+        if !rect_valid || !(valid(blur_radius) && blur_radius > 0.0) {
+            set_err_code(McoreErrorCode::InvalidArg, "mcore_push_blur_layer needs a finite rect and a positive blur radius");
+            return McoreStatus::Err;
+        }
+        // The frame is cut in two here, and a cut can't fall inside a layer
+        if guard.fragments.is_recording() || guard.layers.depth() > 0 {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_push_blur_layer called inside a clip or fragment recording");
+            return McoreStatus::Err;
+        }
+
+        let eng = &mut *guard;
+        eng.cut_scene(Some(blur::BlurRegion { x, y, width, height, corner_radius, radius: blur_radius }));
+
+        let scale = eng.gfx.scale();
+        let clip = peniko::kurbo::RoundedRect::new(
+            (x * scale) as f64,
+            (y * scale) as f64,
+            ((x + width) * scale) as f64,
+            ((y + height) * scale) as f64,
+            (corner_radius * scale) as f64,
+        );
+        eng.scene.push_layer(vello::peniko::BlendMode::default(), 1.0, peniko::kurbo::Affine::IDENTITY, &clip);
+        eng.layers.push(peniko::kurbo::Affine::IDENTITY, clip);
+        eng.hits.push_clip(hit::Rect::from_xywh(x, y, width, height));
+        McoreStatus::Ok
+    })
+}
+
+/// Pushing or popping a filter cuts the frame's scene, which can't happen
+/// inside a fragment recording
+fn filter_change_allowed(eng: &mut Engine, call: &str) -> bool {
+    if !drawing_allowed(eng, call) {
+        return false;
+    }
+    if eng.fragments.is_recording() {
+        set_err_code(McoreErrorCode::InvalidState, format!("{call} called inside a fragment recording"));
+        return false;
+    }
+    true
+}
+
+/// Push a color filter layer: everything drawn until the matching pop
+/// (images, fragments, videos and custom textures included) is transformed by
+/// `matrix`, a 4x5 row-major color matrix (20 floats)
+#[no_mangle]
+pub extern "C" fn mcore_push_color_filter(ctx: *mut McoreContext, matrix: *const f32) {
+    ffi_boundary("mcore_push_color_filter", || {
+        trace_call!("mcore_push_color_filter", ctx, matrix);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_push_color_filter", "ctx");
+        };
+        let Some(matrix) = host_slice(matrix, 20) else {
+            return null_arg("mcore_push_color_filter", "matrix");
+        };
+        let mut guard = ctx.0.lock();
+        if !filter_change_allowed(&mut guard, "mcore_push_color_filter") {
+            return;
+        }
+
+        let mut m = [0.0f32; 20];
+        m.copy_from_slice(matrix);
+        guard.filters.push(filter::ColorMatrix(m));
+        guard.cut_scene(None);
+    })
+}
+
+/// Push a grayscale filter layer (amount 0.0 = unchanged, 1.0 = fully desaturated)
+/// Handy for disabled-state regions
+#[no_mangle]
+pub extern "C" fn mcore_push_grayscale_filter(ctx: *mut McoreContext, amount: f32) {
+    ffi_boundary("mcore_push_grayscale_filter", || {
+        trace_call!("mcore_push_grayscale_filter", ctx, amount);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_push_grayscale_filter", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        if !filter_change_allowed(&mut guard, "mcore_push_grayscale_filter") {
+            return;
+        }
+        guard.filters.push(filter::ColorMatrix::grayscale(amount));
+        guard.cut_scene(None);
+    })
+}
+
+/// Pop the innermost color filter layer
+#[no_mangle]
+pub extern "C" fn mcore_pop_color_filter(ctx: *mut McoreContext) {
+    ffi_boundary("mcore_pop_color_filter", || {
+        trace_call!("mcore_pop_color_filter", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_pop_color_filter", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        if !filter_change_allowed(&mut guard, "mcore_pop_color_filter") {
+            return;
+        }
+        if !guard.filters.pop() {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_pop_color_filter called with no active filter");
+            return;
+        }
+        guard.cut_scene(None);
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_render_commands(
+    ctx: *mut McoreContext,
+    commands: *const McoreDrawCommand,
+    count: i32,
+) {
+    ffi_boundary("mcore_render_commands", || {
+        trace_call!("mcore_render_commands", ctx, commands, count);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_render_commands", "ctx");
+        };
+        let Some(commands) = host_slice(commands, count.max(0) as usize) else {
+            return null_arg("mcore_render_commands", "commands");
+        };
+        let mut guard = ctx.0.lock();
+        if !commands_allowed(&guard, "mcore_render_commands") {
+            return;
+        }
+
+        if let Some(recorder) = guard.recorder.as_mut() {
+            recorder.record_commands(commands);
+        }
+        guard.frame_capture.record_commands(commands);
+        guard.damage.hash_commands(commands);
+        let start = Instant::now();
+        commands::draw(&mut guard, commands);
+        guard.watchdog.record_encode(start.elapsed(), commands.len());
+    })
+}
+
+/// Draw a v2 command stream: `len` bytes of records (see McoreCmdHeader and
+/// command_stream.rs). A malformed stream is rejected before anything is drawn.
+#[no_mangle]
+pub extern "C" fn mcore_render_commands_v2(
+    ctx: *mut McoreContext,
+    data: *const u8,
+    len: usize,
+    version: u32,
+) -> McoreStatus {
+    ffi_boundary("mcore_render_commands_v2", || {
+        trace_call!("mcore_render_commands_v2", ctx, data, len, version);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_render_commands_v2", "ctx");
+        };
+        let Some(bytes) = host_slice(data, len) else {
+            return null_arg("mcore_render_commands_v2", "data");
+        };
+        if version != command_stream::VERSION {
+            set_err_code(McoreErrorCode::InvalidArg, format!(
+                "Unsupported command stream version {version} (this engine reads version {})",
+                command_stream::VERSION
+            ));
+            return McoreStatus::Err;
+        }
+
+        render_commands_v2(&mut ctx.0.lock(), bytes)
+    })
+}
+
+/// mcore_render_commands_v2 on a locked engine, for a stream of the current
+/// version (also driven by the render thread)
+fn render_commands_v2(eng: &mut Engine, bytes: &[u8]) -> McoreStatus {
+    if !commands_allowed(eng, "mcore_render_commands_v2") {
+        return McoreStatus::NotInFrame;
+    }
+    let commands = match command_stream::parse(bytes) {
+        Ok(commands) => commands,
+        Err(e) => {
+            set_err_code(McoreErrorCode::InvalidArg, format!("mcore_render_commands_v2: {e}"));
+            return McoreStatus::Err;
+        }
+    };
+
+    eng.damage.hash_stream(bytes);
+    let start = Instant::now();
+    command_stream::draw(eng, &commands);
+    eng.watchdog.record_encode(start.elapsed(), commands.len());
+    McoreStatus::Ok
+}
+
+// ============================================================================
+// Gradients
+// ============================================================================
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreGradientStop {
+    /// Position along the gradient, 0..=1
+    pub offset: f32,
+    pub color: McoreRgba,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreGradientDesc {
+    /// 0 = linear, 1 = sweep (conic)
+    pub kind: u8,
+    pub _padding: [u8; 3],
+    /// Linear: direction (0 = left to right, 90 = top to bottom).
+    /// Sweep: where offset 0 starts (0 = 3 o'clock), going clockwise.
+    pub angle_deg: f32,
+    pub stops: *const McoreGradientStop,
+    pub stop_count: u32,
+}
+
+/// Register a gradient brush, laid out relative to the bounds of the shape it
+/// paints. Draw commands reference it by ID (GradientRing: font_id).
+/// Returns a gradient ID (>= 0) or -1 on error
+#[no_mangle]
+pub extern "C" fn mcore_gradient_create(ctx: *mut McoreContext, desc: *const McoreGradientDesc) -> i32 {
+    ffi_boundary("mcore_gradient_create", || {
+        trace_call!("mcore_gradient_create", ctx, desc);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_gradient_create", "ctx");
+        };
+        let Some(desc) = (unsafe { desc.as_ref() }) else {
+            return null_arg("mcore_gradient_create", "desc");
+        };
+        if desc.stops.is_null() && desc.stop_count > 0 {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_gradient_create");
+            return -1;
+        }
+
+        let Some(kind) = gradient::GradientKind::from_u8(desc.kind) else {
+            set_err_code(McoreErrorCode::InvalidArg, format!("Unknown gradient kind: {}", desc.kind));
+            return -1;
+        };
+        let stops: Vec<(f32, Color)> = if desc.stop_count > 0 {
+            unsafe { std::slice::from_raw_parts(desc.stops, desc.stop_count as usize) }
+                .iter()
+                .map(|s| (s.offset, Color::new([s.color.r, s.color.g, s.color.b, s.color.a])))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        match gradient::GradientDesc::new(kind, desc.angle_deg, &stops) {
+            Ok(gradient) => {
+                let mut guard = ctx.0.lock();
+                guard.gradients.register(gradient)
+            }
+            Err(e) => {
+                set_err(e);
+                -1
+            }
+        }
+    })
+}
+
+/// Free a registered gradient
+#[no_mangle]
+pub extern "C" fn mcore_gradient_destroy(ctx: *mut McoreContext, gradient_id: i32) {
+    ffi_boundary("mcore_gradient_destroy", || {
+        trace_call!("mcore_gradient_destroy", ctx, gradient_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_gradient_destroy", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        if !guard.gradients.remove(gradient_id) {
+            set_err_code(McoreErrorCode::NotFound, format!("Gradient ID {} not found", gradient_id));
+        }
+        guard.damage.invalidate();
+    })
+}
+
+// ============================================================================
+// Log Views
+// ============================================================================
+
+/// Create an append-only log view. Entries wrap at `wrap_width` (logical px);
+/// beyond `max_entries` (0 = unbounded) the oldest are dropped.
+/// Returns a view ID (>= 0) or -1 on error.
+#[no_mangle]
+pub extern "C" fn mcore_log_view_create(
+    ctx: *mut McoreContext,
+    font_size: f32,
+    font_id: i32,
+    wrap_width: f32,
+    max_entries: u32,
+) -> i32 {
+    ffi_boundary("mcore_log_view_create", || {
+        trace_call!("mcore_log_view_create", ctx, font_size, font_id, wrap_width, max_entries);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_log_view_create", "ctx");
+        };
+        if !(font_size > 0.0) || !(wrap_width > 0.0) {
+            set_err_code(McoreErrorCode::InvalidArg, format!("Invalid log view size: font_size {font_size}, wrap_width {wrap_width}"));
+            return -1;
+        }
+
+        let mut guard = ctx.0.lock();
+        let scale = guard.gfx.scale();
+        let style = text::TextStyle::new(font_size, font_id);
+        guard.log_views.register(log_view::LogView::new(style, wrap_width, max_entries as usize, scale))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_log_view_destroy(ctx: *mut McoreContext, view_id: i32) {
+    ffi_boundary("mcore_log_view_destroy", || {
+        trace_call!("mcore_log_view_destroy", ctx, view_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return;
+        };
+
+        let mut guard = ctx.0.lock();
+        if !guard.log_views.remove(view_id) {
+            set_err_code(McoreErrorCode::NotFound, format!("Log view ID {} not found", view_id));
+        }
+    })
+}
+
+/// Shape and append one entry (it may span several lines)
+#[no_mangle]
+pub extern "C" fn mcore_log_view_append(
+    ctx: *mut McoreContext,
+    view_id: i32,
+    utf8: *const i8,
+    color: McoreRgba,
+) -> McoreStatus {
+    ffi_boundary("mcore_log_view_append", || {
+        trace_call!("mcore_log_view_append", ctx, view_id, utf8);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_log_view_append", "ctx");
+        };
+        if utf8.is_null() {
+            return null_arg("mcore_log_view_append", "utf8");
+        }
+
+        let text = utf8_arg(utf8);
+        let mut guard = ctx.0.lock();
+        let eng = &mut *guard;
+        let scale = eng.gfx.scale();
+        let Some(view) = eng.log_views.get_mut(view_id) else {
+            set_err_code(McoreErrorCode::NotFound, format!("Log view ID {} not found", view_id));
+            return McoreStatus::Err;
+        };
+        view.append(&mut eng.text_cx, text, Color::new([color.r, color.g, color.b, color.a]), scale);
+        McoreStatus::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_log_view_clear(ctx: *mut McoreContext, view_id: i32) {
+    ffi_boundary("mcore_log_view_clear", || {
+        trace_call!("mcore_log_view_clear", ctx, view_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_log_view_clear", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        match guard.log_views.get_mut(view_id) {
+            Some(view) => view.clear(),
+            None => set_err_code(McoreErrorCode::NotFound, format!("Log view ID {} not found", view_id)),
+        }
+    })
+}
+
+/// Change the wrap width (logical px). Reshapes every entry, so call it on
+/// resize rather than every frame.
+#[no_mangle]
+pub extern "C" fn mcore_log_view_set_width(ctx: *mut McoreContext, view_id: i32, wrap_width: f32) {
+    ffi_boundary("mcore_log_view_set_width", || {
+        trace_call!("mcore_log_view_set_width", ctx, view_id, wrap_width);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_log_view_set_width", "ctx");
+        };
+        if !(wrap_width > 0.0) {
+            set_err_code(McoreErrorCode::InvalidArg, format!("Invalid log view wrap width: {wrap_width}"));
+            return;
+        }
+
+        let mut guard = ctx.0.lock();
+        let eng = &mut *guard;
+        let scale = eng.gfx.scale();
+        match eng.log_views.get_mut(view_id) {
+            Some(view) => view.relayout(&mut eng.text_cx, wrap_width, scale),
+            None => set_err_code(McoreErrorCode::NotFound, format!("Log view ID {} not found", view_id)),
+        }
+    })
+}
+
+/// Set the tab stops entries are laid out with (see McoreTextReq), reshaping
+/// the entries already appended
+#[no_mangle]
+pub extern "C" fn mcore_log_view_set_tabs(
+    ctx: *mut McoreContext,
+    view_id: i32,
+    tab_width: f32,
+    tab_stops: *const f32,
+    tab_stop_count: u32,
+) {
+    ffi_boundary("mcore_log_view_set_tabs", || {
+        trace_call!("mcore_log_view_set_tabs", ctx, view_id, tab_width, tab_stops, tab_stop_count);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_log_view_set_tabs", "ctx");
+        };
+        let tabs = unsafe { self::tab_stops(tab_width, tab_stops, tab_stop_count) };
+
+        let mut guard = ctx.0.lock();
+        let eng = &mut *guard;
+        let scale = eng.gfx.scale();
+        match eng.log_views.get_mut(view_id) {
+            Some(view) => view.set_tabs(&mut eng.text_cx, tabs, scale),
+            None => set_err_code(McoreErrorCode::NotFound, format!("Log view ID {} not found", view_id)),
+        }
+    })
+}
+
+/// Height of all kept entries (logical px; 0 for unknown IDs)
+#[no_mangle]
+pub extern "C" fn mcore_log_view_content_height(ctx: *mut McoreContext, view_id: i32) -> f32 {
+    ffi_boundary("mcore_log_view_content_height", || {
+        trace_call!("mcore_log_view_content_height", ctx, view_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_log_view_content_height", "ctx");
+        };
+
+        let guard = ctx.0.lock();
+        guard.log_views.get(view_id).map_or(0.0, |view| view.content_height())
+    })
+}
+
+/// Draw the entries visible in a `height`-tall window at (x, y) whose
+/// content is scrolled by `scroll_y` (logical px), clipped to the window.
+/// Only the visible entries are encoded, however long the log gets.
+#[no_mangle]
+pub extern "C" fn mcore_log_view_draw(
+    ctx: *mut McoreContext,
+    view_id: i32,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    scroll_y: f32,
+) -> McoreStatus {
+    ffi_boundary("mcore_log_view_draw", || {
+        trace_call!("mcore_log_view_draw", ctx, view_id, x, y, width, height, scroll_y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_log_view_draw", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_log_view_draw") {
+            return McoreStatus::NotInFrame;
+        }
+        let eng = &mut *guard;
+        let scale = eng.gfx.scale();
+        let Some(view) = eng.log_views.get_mut(view_id) else {
+            set_err_code(McoreErrorCode::NotFound, format!("Log view ID {} not found", view_id));
+            return McoreStatus::Err;
+        };
+        // The scale factor may have changed since the entries were shaped
+        let wrap_width = view.wrap_width();
+        view.relayout(&mut eng.text_cx, wrap_width, scale);
+
+        let clip = peniko::kurbo::Rect::new(
+            (x * scale) as f64,
+            (y * scale) as f64,
+            ((x + width) * scale) as f64,
+            ((y + height) * scale) as f64,
+        );
+        let identity = peniko::kurbo::Affine::IDENTITY;
+        eng.scene.push_layer(vello::peniko::BlendMode::default(), 1.0, identity, &clip);
+        view.draw(&mut eng.scene, x, y, scroll_y, height, eng.text_cx.rendering);
+        eng.scene.pop_layer();
+        McoreStatus::Ok
+    })
+}
+
+// ============================================================================
+// Retained Scene Fragments
+// ============================================================================
+
+/// Start recording a fragment. All drawing calls until mcore_fragment_end
+/// (including mcore_render_commands) are captured instead of drawn. Frames
+/// can't begin or present until the recording ends.
+#[no_mangle]
+pub extern "C" fn mcore_fragment_begin(ctx: *mut McoreContext) -> McoreStatus {
+    ffi_boundary("mcore_fragment_begin", || {
+        trace_call!("mcore_fragment_begin", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_fragment_begin", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        let eng = &mut *guard;
+
+        match eng.fragments.begin(&mut eng.scene) {
+            Ok(()) => {
+                eng.layers.begin_fragment();
+                McoreStatus::Ok
+            }
+            Err(e) => {
+                set_err(e);
+                McoreStatus::Err
+            }
+        }
+    })
+}
+
+/// Finish recording and return the fragment ID (>= 0), or -1 if not recording
+#[no_mangle]
+pub extern "C" fn mcore_fragment_end(ctx: *mut McoreContext) -> i32 {
+    ffi_boundary("mcore_fragment_end", || {
+        trace_call!("mcore_fragment_end", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_fragment_end", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        let eng = &mut *guard;
+
+        // Close the fragment's own layers before the frame scene comes back
+        if eng.fragments.is_recording() {
+            for _ in 0..eng.layers.end_fragment() {
+                eng.scene.pop_layer();
+            }
+        }
+        match eng.fragments.end(&mut eng.scene) {
+            Ok(id) => id,
+            Err(e) => {
+                set_err(e);
+                -1
+            }
+        }
+    })
+}
+
+/// Replay a recorded fragment into the current frame with a transform
+/// The transform position is in logical pixels, like mcore_image_draw
+#[no_mangle]
+pub extern "C" fn mcore_fragment_draw(
+    ctx: *mut McoreContext,
+    fragment_id: i32,
+    transform: *const McoreImageTransform,
+) {
+    ffi_boundary("mcore_fragment_draw", || {
+        trace_call!("mcore_fragment_draw", ctx, fragment_id, transform);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_fragment_draw", "ctx");
+        };
+        let Some(transform) = (unsafe { transform.as_ref() }) else {
+            return null_arg("mcore_fragment_draw", "transform");
+        };
+
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_fragment_draw") {
+            return;
+        }
+        let eng = &mut *guard;
+
+        use peniko::kurbo::Affine;
+        let dpi_scale = eng.gfx.scale();
+        let affine = Affine::scale(transform.scale as f64)
+            .then_rotate((transform.rotation_deg as f64).to_radians())
+            .then_translate(((transform.x * dpi_scale) as f64, (transform.y * dpi_scale) as f64).into());
+
+        match eng.fragments.get(fragment_id) {
+            Some(fragment) => eng.scene.append(fragment, Some(affine)),
+            None => set_err_code(McoreErrorCode::NotFound, format!("Fragment ID {} not found", fragment_id)),
+        }
+    })
+}
+
+/// Free a recorded fragment
+#[no_mangle]
+pub extern "C" fn mcore_fragment_destroy(ctx: *mut McoreContext, fragment_id: i32) {
+    ffi_boundary("mcore_fragment_destroy", || {
+        trace_call!("mcore_fragment_destroy", ctx, fragment_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_fragment_destroy", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        if !guard.fragments.remove(fragment_id) {
+            set_err_code(McoreErrorCode::NotFound, format!("Fragment ID {} not found", fragment_id));
+        }
+    })
+}
+
+/// Render a recorded fragment once, offscreen, into a new image covering
+/// (0, 0, width, height) of the fragment (logical px, rasterized at the
+/// current scale). Expensive static content (charts, blurred backgrounds) can
+/// then be drawn as one image blit. Returns an image ID (release it with
+/// mcore_image_release) or -1 on error; snapshot again when the content changes.
+#[no_mangle]
+pub extern "C" fn mcore_fragment_to_image(ctx: *mut McoreContext, fragment_id: i32, width: f32, height: f32) -> i32 {
+    ffi_boundary("mcore_fragment_to_image", || {
+        trace_call!("mcore_fragment_to_image", ctx, fragment_id, width, height);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_fragment_to_image", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        let eng = &mut *guard;
+        let scale = eng.gfx.scale();
+        let (width_px, height_px) = ((width * scale).ceil(), (height * scale).ceil());
+        let max = eng.gfx.device().limits().max_texture_dimension_2d as f32;
+        if !(width_px >= 1.0 && height_px >= 1.0 && width_px <= max && height_px <= max) {
+            set_err_code(McoreErrorCode::InvalidArg, format!("Invalid snapshot size {width}x{height} (at most {max} physical px per side)"));
+            return -1;
+        }
+        let Some(fragment) = eng.fragments.get(fragment_id) else {
+            set_err_code(McoreErrorCode::NotFound, format!("Fragment ID {} not found", fragment_id));
+            return -1;
+        };
+
+        let (width_px, height_px) = (width_px as u32, height_px as u32);
+        let pixels = match eng.gfx.render_scene_to_pixels(fragment, width_px, height_px) {
+            Ok(pixels) => pixels,
+            Err(e) => {
+                set_gfx_err(&e);
+                return -1;
+            }
+        };
+        match eng.images.register(
+            &pixels,
+            width_px,
+            height_px,
+            vello::peniko::ImageFormat::Rgba8,
+            vello::peniko::ImageAlphaType::AlphaPremultiplied,
+        ) {
+            Ok(id) => id,
+            Err(e) => {
+                set_err(e);
+                -1
+            }
+        }
+    })
+}
+
+/// Fill the surface behind every following frame with `color`, in place of
+/// the clear color passed to mcore_end_frame_present and mcore_submit_frame,
+/// for hosts with one fixed background. Components must be finite.
+#[no_mangle]
+pub extern "C" fn mcore_set_clear_color(ctx: *mut McoreContext, color: McoreRgba) -> McoreStatus {
+    ffi_boundary("mcore_set_clear_color", || {
+        trace_call!("mcore_set_clear_color", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_clear_color", "ctx");
+        };
+        if ![color.r, color.g, color.b, color.a].iter().all(|c| c.is_finite()) {
+            set_err_code(McoreErrorCode::InvalidArg, "mcore_set_clear_color: color components must be finite");
+            return McoreStatus::Err;
+        }
+        ctx.0.lock().clear_color = Some(color);
+        McoreStatus::Ok
+    })
+}
+
+/// Render and present the frame over `clear` (or the mcore_set_clear_color
+/// color, once set), which is used exactly as given: it doesn't depend on
+/// the frame time
+#[no_mangle]
+pub extern "C" fn mcore_end_frame_present(ctx: *mut McoreContext, clear: McoreRgba) -> McoreStatus {
+    ffi_boundary("mcore_end_frame_present", || {
+        trace_call!("mcore_end_frame_present", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_end_frame_present", "ctx");
+        };
+        let mut deferred = Deferred::default();
+        let status = end_frame_present(&mut ctx.0.lock(), clear, &mut deferred);
+        deferred.fire();
+        status
+    })
+}
+
+/// mcore_end_frame_present on a locked engine (also driven by the render
+/// thread)
+fn end_frame_present(eng: &mut Engine, clear: McoreRgba, deferred: &mut Deferred) -> McoreStatus {
+    if eng.render_thread.as_ref().is_some_and(|thread| !thread.is_current()) {
+        set_err_code(McoreErrorCode::InvalidState, "mcore_end_frame_present called while the render thread is running");
+        return McoreStatus::Err;
+    }
+    // The scene holds the fragment, not the frame
+    if eng.fragments.is_recording() {
+        set_err_code(McoreErrorCode::InvalidState, "mcore_end_frame_present called while recording a fragment; call mcore_fragment_end first");
+        return McoreStatus::Err;
+    }
+    if !eng.in_frame {
+        set_err_code(McoreErrorCode::InvalidState, "mcore_end_frame_present called without mcore_begin_frame");
+        return McoreStatus::NotInFrame;
+    }
+    eng.in_frame = false;
+    // Close clips the host left open, so the overlay and the next frame
+    // aren't drawn inside them
+    for _ in 0..eng.layers.finish() {
+        eng.scene.pop_layer();
+    }
+    let clear = eng.clear_color.unwrap_or(clear);
+    let clear_color = Color::new([clear.r, clear.g, clear.b, clear.a]);
+    if eng.overlay.enabled() {
+        // Filters the host left pushed don't apply to the overlay
+        if eng.filters.current().is_some() {
+            eng.filters.clear();
+            eng.cut_scene(None);
+        }
+        overlay::draw(eng);
+        eng.damage.mark_untracked();
+    }
+
+    if let Some(recorder) = eng.recorder.as_mut() {
+        recorder.end_frame(&eng.gfx, clear_color);
+    }
+    eng.frame_capture.end_frame(clear_color);
+
+    // Nothing is visible: keep the frame's state, skip the GPU work
+    if eng.occluded {
+        eng.damage.invalidate();
+        return McoreStatus::Ok;
+    }
+
+    // Same commands as the frame on screen: leave it there
+    let (width, height) = eng.gfx.size();
+    // Frames that drew a placeholder change when its decode finishes
+    let images = eng.images.generation() as u32;
+    let inputs = [width, height, eng.gfx.scale().to_bits(), clear.r.to_bits(), clear.g.to_bits(), clear.b.to_bits(), clear.a.to_bits(), images];
+    let particles = eng.gfx.particles().is_active();
+    if eng.damage.finish(&inputs, particles) {
+        return McoreStatus::FrameSkipped;
+    }
+    let mut timings = eng.watchdog.build_timings();
+
+    // Custom widgets draw their textures before the scene samples them
+    let custom_start = Instant::now();
+    eng.custom_textures.render_all(&mut eng.gfx, eng.time_s);
+
+    // Clone the scene to avoid borrow conflict
+    let scene = eng.scene.clone();
+    let backdrops = std::mem::take(&mut eng.backdrops);
+
+    let render_start = Instant::now();
+    let result = eng.gfx.render_layers(&backdrops, &scene, clear_color);
+    timings.custom = render_start - custom_start;
+    timings.render = render_start.elapsed();
+    let slow_frame = eng.watchdog.check(eng.frames_presented, &timings);
+    eng.last_timings = timings;
+
+    let status = match &result {
+        Ok(_) => {
+            eng.frames_presented += 1;
+            eng.render_failures = 0;
+            McoreStatus::Ok
+        }
+        Err(e) => {
+            set_gfx_err(e);
+            eng.render_failures += 1;
+            McoreStatus::Err
+        }
+    };
+
+    // A lost device or a run of failed frames won't recover on its own
+    let fatal = if crash::enabled() {
+        crash::update_snapshot(eng.crash_snapshot());
+        match (eng.gfx.device_lost(), &result) {
+            (Some(reason), Err(_)) if eng.render_failures == 1 => Some(format!("GPU device lost: {reason}")),
+            (None, Err(e)) if eng.render_failures == crash::MAX_RENDER_FAILURES => {
+                Some(format!("{} consecutive frames failed to render, last error: {e}", eng.render_failures))
+            }
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    if let Some(message) = slow_frame {
+        deferred.log_callback = eng.log_callback;
+        deferred.logs.push((LogLevel::Warn, message));
+    }
+    deferred.crash = fatal;
+    status
+}
+
+/// Build, render and present frames on an engine-owned thread (see
+/// render_thread.rs). Until mcore_render_thread_stop, frames are queued with
+/// mcore_submit_frame and other threads can't begin frames or draw.
+#[no_mangle]
+pub extern "C" fn mcore_render_thread_start(ctx: *mut McoreContext) -> McoreStatus {
+    ffi_boundary("mcore_render_thread_start", || {
+        trace_call!("mcore_render_thread_start", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_render_thread_start", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        if guard.render_thread.is_some() {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_render_thread_start: the render thread is already running");
+            return McoreStatus::Err;
+        }
+        if guard.in_frame {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_render_thread_start called inside a frame");
+            return McoreStatus::FrameInProgress;
+        }
+        match render_thread::RenderThread::spawn(Arc::downgrade(&ctx.0)) {
+            Ok(thread) => {
+                guard.render_thread = Some(thread);
+                McoreStatus::Ok
+            }
+            Err(e) => {
+                set_err(format!("failed to start the render thread: {e}"));
+                McoreStatus::Err
+            }
+        }
+    })
+}
+
+/// Present the last queued frame, then end the render thread; frames are
+/// driven by the host again. Blocks until the thread exits.
+#[no_mangle]
+pub extern "C" fn mcore_render_thread_stop(ctx: *mut McoreContext) -> McoreStatus {
+    ffi_boundary("mcore_render_thread_stop", || {
+        trace_call!("mcore_render_thread_stop", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_render_thread_stop", "ctx");
+        };
+        let Some(thread) = ctx.0.lock().render_thread.take() else {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_render_thread_stop: the render thread isn't running");
+            return McoreStatus::Err;
+        };
+        thread.stop();
+        McoreStatus::Ok
+    })
+}
+
+/// Queue a frame for the render thread: `len` bytes of v2 command stream,
+/// copied before returning, drawn at `time_seconds` over `clear`. Callable
+/// from any thread. A frame still waiting when the next arrives is replaced.
+/// The stream is validated here; failures while rendering go to the log
+/// callback.
+#[no_mangle]
+pub extern "C" fn mcore_submit_frame(
+    ctx: *mut McoreContext,
+    time_seconds: f64,
+    data: *const u8,
+    len: usize,
+    version: u32,
+    clear: McoreRgba,
+) -> McoreStatus {
+    ffi_boundary("mcore_submit_frame", || {
+        trace_call!("mcore_submit_frame", ctx, time_seconds, data, len, version);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_submit_frame", "ctx");
+        };
+        let Some(bytes) = host_slice(data, len) else {
+            return null_arg("mcore_submit_frame", "data");
+        };
+        if version != command_stream::VERSION {
+            set_err_code(McoreErrorCode::InvalidArg, format!(
+                "Unsupported command stream version {version} (this engine reads version {})",
+                command_stream::VERSION
+            ));
+            return McoreStatus::Err;
+        }
+        if let Err(e) = command_stream::parse(bytes) {
+            set_err_code(McoreErrorCode::InvalidArg, format!("mcore_submit_frame: {e}"));
+            return McoreStatus::Err;
+        }
+
+        let guard = ctx.0.lock();
+        let Some(thread) = guard.render_thread.as_ref() else {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_submit_frame called without mcore_render_thread_start");
+            return McoreStatus::Err;
+        };
+        thread.submit(render_thread::QueuedFrame {
+            time_s: time_seconds,
+            stream: bytes.to_vec(),
+            clear,
+        });
+        McoreStatus::Ok
+    })
+}
+
+/// Block until every frame queued so far is presented (e.g. before reading
+/// back a frame or resizing in step with the window)
+#[no_mangle]
+pub extern "C" fn mcore_render_thread_flush(ctx: *mut McoreContext) -> McoreStatus {
+    ffi_boundary("mcore_render_thread_flush", || {
+        trace_call!("mcore_render_thread_flush", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_render_thread_flush", "ctx");
+        };
+        let done = {
+            let guard = ctx.0.lock();
+            match guard.render_thread.as_ref() {
+                Some(thread) if thread.is_current() => {
+                    set_err_code(McoreErrorCode::InvalidState, "mcore_render_thread_flush called from the render thread");
+                    return McoreStatus::Err;
+                }
+                Some(thread) => thread.flush(),
+                None => {
+                    set_err_code(McoreErrorCode::InvalidState, "mcore_render_thread_flush called without mcore_render_thread_start");
+                    return McoreStatus::Err;
+                }
+            }
+        };
+        let _ = done.recv();
+        McoreStatus::Ok
+    })
+}
+
+/// Start recording command buffers to `path` (see replay.rs). Every frame's
+/// mcore_render_commands calls and clear color are captured until
+/// mcore_replay_record_stop writes the file.
+#[no_mangle]
+pub extern "C" fn mcore_replay_record_start(ctx: *mut McoreContext, path: *const i8) -> McoreStatus {
+    ffi_boundary("mcore_replay_record_start", || {
+        trace_call!("mcore_replay_record_start", ctx, path);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_replay_record_start", "ctx");
+        };
+        if path.is_null() {
+            return null_arg("mcore_replay_record_start", "path");
+        }
+        let path = unsafe { CStr::from_ptr(path) }.to_string_lossy().into_owned();
+        let mut guard = ctx.0.lock();
+        guard.recorder = Some(replay::Recorder::new(path));
+        McoreStatus::Ok
+    })
+}
+
+/// Stop recording and write the file. Returns the number of frames written,
+/// or -1 if not recording or the write failed.
+#[no_mangle]
+pub extern "C" fn mcore_replay_record_stop(ctx: *mut McoreContext) -> i32 {
+    ffi_boundary("mcore_replay_record_stop", || {
+        trace_call!("mcore_replay_record_stop", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_replay_record_stop", "ctx");
+        };
+
+        let Some(recorder) = ctx.0.lock().recorder.take() else {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_replay_record_stop called while not recording");
+            return -1;
+        };
+
+        match recorder.finish() {
+            Ok(frames) => frames as i32,
+            Err(e) => {
+                set_err_code(McoreErrorCode::Io, format!("failed to write recording: {}", e));
+                -1
+            }
+        }
+    })
+}
+
+/// Write the current frame's command buffers (the frame being built, or the
+/// last presented one between frames) to `path` as a one-frame recording.
+/// `mcore-replay` renders it headlessly; mcore_scene_load draws it back.
+#[no_mangle]
+pub extern "C" fn mcore_scene_dump(ctx: *mut McoreContext, path: *const i8) -> McoreStatus {
+    ffi_boundary("mcore_scene_dump", || {
+        trace_call!("mcore_scene_dump", ctx, path);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scene_dump", "ctx");
+        };
+        if path.is_null() {
+            return null_arg("mcore_scene_dump", "path");
+        }
+
+        let path = unsafe { CStr::from_ptr(path) }.to_string_lossy().into_owned();
+        let recording = {
+            let guard = ctx.0.lock();
+            guard.frame_capture.to_recording(&guard.gfx)
+        };
+        match std::fs::write(&path, recording.encode()) {
+            Ok(()) => McoreStatus::Ok,
+            Err(e) => {
+                set_err_code(McoreErrorCode::Io, format!("failed to write scene dump {}: {}", path, e));
+                McoreStatus::Err
+            }
+        }
+    })
+}
+
+/// Draw the commands of a scene dump (or the first frame of a recording)
+/// into the current frame. Its clear color and surface size are not applied.
+#[no_mangle]
+pub extern "C" fn mcore_scene_load(ctx: *mut McoreContext, path: *const i8) -> McoreStatus {
+    ffi_boundary("mcore_scene_load", || {
+        trace_call!("mcore_scene_load", ctx, path);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scene_load", "ctx");
+        };
+        if path.is_null() {
+            return null_arg("mcore_scene_load", "path");
+        }
+
+        let path = unsafe { CStr::from_ptr(path) }.to_string_lossy().into_owned();
+        let recording = match replay::Recording::load(std::path::Path::new(&path)) {
+            Ok(recording) => recording,
+            Err(e) => {
+                set_err_code(McoreErrorCode::Io, format!("failed to load scene dump: {}", e));
+                return McoreStatus::Err;
+            }
+        };
+        let Some(frame) = recording.frames.first() else {
+            set_err_code(McoreErrorCode::InvalidState, format!("scene dump {} has no frames", path));
+            return McoreStatus::Err;
+        };
+
+        let mut guard = ctx.0.lock();
+        if !commands_allowed(&guard, "mcore_scene_load") {
+            return McoreStatus::NotInFrame;
+        }
+        let mut strings = Vec::new();
+        let commands: Vec<_> = frame.commands.iter().map(|cmd| cmd.to_ffi(&mut strings)).collect();
+        guard.frame_capture.record_commands(&commands);
+        guard.damage.hash_commands(&commands);
+        commands::draw(&mut guard, &commands);
+        McoreStatus::Ok
+    })
+}
+
+/// Called with (reason, bundle directory) after a crash bundle is written
+pub type McoreCrashCallback = extern "C" fn(*const i8, *const i8);
+
+/// Write a diagnostic bundle into `dir` when the engine hits an unrecoverable
+/// error (GPU device lost, repeated render failures, a panic), then call
+/// `callback` with the reason and bundle path. A null dir turns it off.
+#[no_mangle]
+pub extern "C" fn mcore_set_crash_dump(
+    ctx: *mut McoreContext,
+    dir: *const i8,
+    callback: Option<extern "C" fn(*const i8, *const i8)>,
+) -> McoreStatus {
+    ffi_boundary("mcore_set_crash_dump", || {
+        trace_call!("mcore_set_crash_dump", ctx, dir);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_crash_dump", "ctx");
+        };
+
+        let guard = ctx.0.lock();
+        if dir.is_null() {
+            crash::configure(None, None, String::new());
+            return McoreStatus::Ok;
+        }
+
+        let dir = std::path::PathBuf::from(unsafe { CStr::from_ptr(dir) }.to_string_lossy().into_owned());
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            set_err_code(McoreErrorCode::Io, format!("can't create crash dump directory {}: {e}", dir.display()));
+            return McoreStatus::Err;
+        }
+        crash::configure(Some(dir), callback, guard.gfx.describe());
+        crash::update_snapshot(guard.crash_snapshot());
+        McoreStatus::Ok
+    })
+}
+
+/// Receive engine diagnostics as (level, message): 0 debug, 1 info, 2 warn,
+/// 3 error. Without a callback they go to stderr. Runs without the engine
+/// lock held; the message is only valid during the call.
+#[no_mangle]
+pub extern "C" fn mcore_set_log_callback(
+    ctx: *mut McoreContext,
+    callback: Option<extern "C" fn(u8, *const i8)>,
+) {
+    ffi_boundary("mcore_set_log_callback", || {
+        trace_call!("mcore_set_log_callback", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_log_callback", "ctx");
+        };
+        ctx.0.lock().log_callback = callback;
+    })
+}
+
+/// Log a warning with a per-phase breakdown for every frame whose build and
+/// render take longer than `budget_ms` (see watchdog.rs). 0 turns it off.
+/// Meant for development builds of the host.
+#[no_mangle]
+pub extern "C" fn mcore_set_frame_budget(ctx: *mut McoreContext, budget_ms: f32) {
+    ffi_boundary("mcore_set_frame_budget", || {
+        trace_call!("mcore_set_frame_budget", ctx, budget_ms);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_frame_budget", "ctx");
+        };
+        let budget = (budget_ms > 0.0 && budget_ms.is_finite()).then(|| Duration::from_secs_f32(budget_ms / 1000.0));
+        ctx.0.lock().watchdog.set_budget(budget);
+    })
+}
+
+/// Draw an engine-side debug overlay over every frame: a bitmask of
+/// MCORE_DEBUG_OVERLAY_* flags (see overlay.rs), 0 turns it off
+#[no_mangle]
+pub extern "C" fn mcore_set_debug_overlay(ctx: *mut McoreContext, flags: u32) {
+    ffi_boundary("mcore_set_debug_overlay", || {
+        trace_call!("mcore_set_debug_overlay", ctx, flags);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_debug_overlay", "ctx");
+        };
+        ctx.0.lock().overlay.set_flags(flags);
+    })
+}
+
+/// Timings, scene size and memory of the last rendered frame
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct McoreFrameStats {
+    /// Frames presented so far
+    pub frame: u64,
+    /// CPU milliseconds from mcore_begin_frame to mcore_end_frame_present
+    pub build_ms: f32,
+    /// Part of build_ms the engine spent encoding command buffers
+    pub encode_ms: f32,
+    /// CPU milliseconds rendering custom widget textures
+    pub custom_ms: f32,
+    /// CPU milliseconds submitting the render and presenting
+    pub render_ms: f32,
+    /// GPU milliseconds of a recent frame (a frame or two behind), or -1
+    /// where the GPU can't time itself
+    pub gpu_ms: f32,
+    pub commands: u32,
+    pub encode_calls: u32,
+    /// Vello scene elements
+    pub paths: u32,
+    pub path_segments: u32,
+    pub clips: u32,
+    pub draw_objects: u32,
+    pub image_count: u32,
+    /// Decoded pixels of registered images and their atlas pages
+    pub image_bytes: u64,
+    /// Render targets and custom widget textures
+    pub texture_bytes: u64,
+    /// Clip pops with nothing pushed and clips left open at
+    /// mcore_end_frame_present, both corrected by the engine (layers.rs)
+    pub layer_warnings: u32,
+}
+
+/// Fill `out` with the last rendered frame's statistics. Scene counts cover
+/// the scene being built, so call it between mcore_end_frame_present and the
+/// next mcore_begin_frame to get the presented frame's.
+#[no_mangle]
+pub extern "C" fn mcore_frame_stats(ctx: *mut McoreContext, out: *mut McoreFrameStats) -> McoreStatus {
+    ffi_boundary("mcore_frame_stats", || {
+        trace_call!("mcore_frame_stats", ctx, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_frame_stats", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_frame_stats", "out");
+        };
+
+        let guard = ctx.0.lock();
+        let ms = |d: Duration| d.as_secs_f32() * 1000.0;
+        let t = &guard.last_timings;
+        let encoding = guard.scene.encoding();
+        *out = McoreFrameStats {
+            frame: guard.frames_presented,
+            build_ms: ms(t.build),
+            encode_ms: ms(t.encode),
+            custom_ms: ms(t.custom),
+            render_ms: ms(t.render),
+            gpu_ms: guard.gfx.gpu_time().map_or(-1.0, ms),
+            commands: t.commands as u32,
+            encode_calls: t.encode_calls,
+            paths: encoding.n_paths,
+            path_segments: encoding.n_path_segments,
+            clips: encoding.n_clips,
+            draw_objects: encoding.draw_tags.len() as u32,
+            image_count: guard.images.len() as u32,
+            image_bytes: guard.images.bytes() + guard.images.stats().atlas_bytes,
+            texture_bytes: guard.gfx.target_bytes() + guard.custom_textures.bytes(),
+            layer_warnings: guard.layers.warnings(),
+        };
+        McoreStatus::Ok
+    })
+}
+
+/// Enable or disable dithering in the final blit pass.
+/// Useful for dark themes with large gradients, which band visibly on 8-bit displays.
+#[no_mangle]
+pub extern "C" fn mcore_set_dither(ctx: *mut McoreContext, enabled: u8) {
+    ffi_boundary("mcore_set_dither", || {
+        trace_call!("mcore_set_dither", ctx, enabled);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_dither", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        guard.gfx.set_dither(enabled != 0);
+        guard.damage.invalidate();
+    })
+}
+
+/// Antialiasing methods (mcore_set_aa_mode)
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreAaMode {
+    /// Analytic coverage: the default, fastest, but conflates at shared edges
+    Area = 0,
+    Msaa8 = 1,
+    /// Best quality, heaviest on integrated GPUs
+    Msaa16 = 2,
+}
+
+/// Choose how frames are antialiased (McoreAaMode value). MSAA pipelines are
+/// built the first time they're chosen; if the device can't run them this
+/// returns Err and the mode is unchanged.
+#[no_mangle]
+pub extern "C" fn mcore_set_aa_mode(ctx: *mut McoreContext, mode: u8) -> McoreStatus {
+    ffi_boundary("mcore_set_aa_mode", || {
+        trace_call!("mcore_set_aa_mode", ctx, mode);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_aa_mode", "ctx");
+        };
+        let aa = match mode {
+            0 => vello::AaConfig::Area,
+            1 => vello::AaConfig::Msaa8,
+            2 => vello::AaConfig::Msaa16,
+            _ => {
+                set_err_code(McoreErrorCode::InvalidArg, format!("Unknown antialiasing mode {mode}"));
+                return McoreStatus::Err;
+            }
+        };
+
+        let mut guard = ctx.0.lock();
+        if let Err(e) = guard.gfx.set_aa(aa) {
+            set_gfx_err(&e);
+            return McoreStatus::Err;
+        }
+        guard.damage.invalidate();
+        McoreStatus::Ok
+    })
+}
+
+/// The current McoreAaMode value
+#[no_mangle]
+pub extern "C" fn mcore_get_aa_mode(ctx: *mut McoreContext) -> u8 {
+    ffi_boundary("mcore_get_aa_mode", || {
+        trace_call!("mcore_get_aa_mode", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_get_aa_mode", "ctx");
+            return McoreAaMode::Area as u8;
+        };
+        let mode = match ctx.0.lock().gfx.aa() {
+            vello::AaConfig::Area => McoreAaMode::Area,
+            vello::AaConfig::Msaa8 => McoreAaMode::Msaa8,
+            vello::AaConfig::Msaa16 => McoreAaMode::Msaa16,
+        };
+        mode as u8
+    })
+}
+
+/// Glyph fitting for a context's text
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextRendering {
+    /// 1 to hint outlines and snap baselines to whole pixels
+    pub hinting: u32,
+    /// Horizontal glyph positions per pixel: 0 unquantized, 1 whole pixels,
+    /// up to 16
+    pub subpixel_positions: u32,
+}
+
+const MAX_SUBPIXEL_POSITIONS: u32 = 16;
+
+/// Choose how this context's text is fit to the pixel grid. Hinting with
+/// whole-pixel positions makes small text crisper on low-DPI screens; the
+/// default (no hinting, unquantized positions) keeps exact shapes and
+/// spacing, which suits high-DPI screens and animated text.
+#[no_mangle]
+pub extern "C" fn mcore_set_text_rendering(ctx: *mut McoreContext, rendering: *const McoreTextRendering) -> McoreStatus {
+    ffi_boundary("mcore_set_text_rendering", || {
+        trace_call!("mcore_set_text_rendering", ctx, rendering);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_text_rendering", "ctx");
+        };
+        let Some(rendering) = (unsafe { rendering.as_ref() }) else {
+            return null_arg("mcore_set_text_rendering", "rendering");
+        };
+        if rendering.subpixel_positions > MAX_SUBPIXEL_POSITIONS {
+            set_err_code(
+                McoreErrorCode::InvalidArg,
+                format!("subpixel_positions {} is over {MAX_SUBPIXEL_POSITIONS}", rendering.subpixel_positions),
+            );
+            return McoreStatus::Err;
+        }
+
+        let mut guard = ctx.0.lock();
+        guard.text_cx.rendering = text::Rendering {
+            hinting: rendering.hinting != 0,
+            subpixel_positions: rendering.subpixel_positions as u8,
+        };
+        guard.damage.invalidate();
+        McoreStatus::Ok
+    })
+}
+
+/// The context's current text rendering options
+#[no_mangle]
+pub extern "C" fn mcore_get_text_rendering(ctx: *mut McoreContext, out: *mut McoreTextRendering) -> McoreStatus {
+    ffi_boundary("mcore_get_text_rendering", || {
+        trace_call!("mcore_get_text_rendering", ctx, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_get_text_rendering", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_get_text_rendering", "out");
+        };
+        let rendering = ctx.0.lock().text_cx.rendering;
+        *out = McoreTextRendering {
+            hinting: rendering.hinting as u32,
+            subpixel_positions: rendering.subpixel_positions as u32,
+        };
+        McoreStatus::Ok
+    })
+}
+
+/// Choose the color space (an McoreColorSpace value) the window's colors are
+/// shown in: sRGB (the default) or Display P3, where colors and images are
+/// read as P3 values. Headless contexts and platforms without color-managed
+/// windows return Err with MCORE_ERROR_UNSUPPORTED and stay in sRGB.
+#[no_mangle]
+pub extern "C" fn mcore_set_color_space(ctx: *mut McoreContext, space: u8) -> McoreStatus {
+    ffi_boundary("mcore_set_color_space", || {
+        trace_call!("mcore_set_color_space", ctx, space);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_color_space", "ctx");
+        };
+        let space = match McoreColorSpace::try_from(space) {
+            Ok(McoreColorSpace::Srgb) => color_space::ColorSpace::Srgb,
+            Ok(McoreColorSpace::DisplayP3) => color_space::ColorSpace::DisplayP3,
+            Err(e) => {
+                set_err_code(McoreErrorCode::InvalidArg, e);
+                return McoreStatus::Err;
+            }
+        };
+
+        let mut guard = ctx.0.lock();
+        if !guard.gfx.set_color_space(space) {
+            set_err_code(McoreErrorCode::Unsupported, "this context has no color-managed window to tag");
+            return McoreStatus::Err;
+        }
+        guard.damage.invalidate();
+        McoreStatus::Ok
+    })
+}
+
+/// The window's color space (sRGB for headless contexts)
+#[no_mangle]
+pub extern "C" fn mcore_get_color_space(ctx: *mut McoreContext) -> McoreColorSpace {
+    ffi_boundary("mcore_get_color_space", || {
+        trace_call!("mcore_get_color_space", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_get_color_space", "ctx");
+        };
+        match ctx.0.lock().gfx.color_space() {
+            color_space::ColorSpace::Srgb => McoreColorSpace::Srgb,
+            color_space::ColorSpace::DisplayP3 => McoreColorSpace::DisplayP3,
+        }
+    })
+}
+
+/// 1 if the main display can show Display P3 colors (macOS), 0 otherwise
+#[no_mangle]
+pub extern "C" fn mcore_display_supports_p3() -> u8 {
+    ffi_boundary("mcore_display_supports_p3", || {
+        trace_call!("mcore_display_supports_p3");
+        color_space::display_supports_p3() as u8
+    })
+}
+
+/// Skip rendering frames whose command buffers match the last presented
+/// frame: mcore_end_frame_present returns MCORE_FRAME_SKIPPED and the previous
+/// frame stays on screen (see damage.rs). Frames with direct drawing calls
+/// are always presented. Off by default.
+#[no_mangle]
+pub extern "C" fn mcore_set_damage_tracking(ctx: *mut McoreContext, enabled: u8) {
+    ffi_boundary("mcore_set_damage_tracking", || {
+        trace_call!("mcore_set_damage_tracking", ctx, enabled);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_damage_tracking", "ctx");
+        };
+        ctx.0.lock().damage.set_enabled(enabled != 0);
+    })
+}
+
+// ============================================================================
+// Text Input FFI
+// ============================================================================
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreTextEventKind {
+    InsertChar = 0,
+    Backspace = 1,
+    Delete = 2,
+    MoveCursor = 3,
+    SetCursor = 4,
+    InsertText = 5,
+    /// Delete back to the previous word start
+    BackspaceWord = 6,
+    /// Delete forward to the next word end
+    DeleteWord = 7,
+    /// Select the whole content (Cmd+A)
+    SelectAll = 8,
+    /// Copy the selection to the system clipboard and delete it (Cmd+X)
+    Cut = 9,
+    /// Copy the selection to the system clipboard (Cmd+C)
+    Copy = 10,
+    /// Replace the selection with the clipboard's text (Cmd+V)
+    Paste = 11,
+    /// Revert the last edit; a run of typing or deleting reverts as one (Cmd+Z)
+    Undo = 12,
+    /// Reapply the last undone edit (Cmd+Shift+Z)
+    Redo = 13,
+    /// Show text_ptr as the IME preedit, with the IME caret at byte
+    /// cursor_position within it (empty text ends the composition)
+    ImeSetComposition = 14,
+    /// Replace the preedit and selection with the final text_ptr
+    ImeCommit = 15,
+    /// Drop the preedit without inserting anything
+    ImeCancel = 16,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreCursorDirection {
+    Left = 0,
+    Right = 1,
+    Home = 2,
+    End = 3,
+    WordLeft = 4,
+    WordRight = 5,
+    Backward = 6,
+    Forward = 7,
+    WordBackward = 8,
+    WordForward = 9,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextEvent {
+    pub kind: McoreTextEventKind,
+    pub char_code: u32,
+    pub direction: McoreCursorDirection,
+    pub extend_selection: u8,
+    pub cursor_position: i32,
+    pub text_ptr: *const i8,
+}
+
+/// Handle a text input event for a specific widget ID
+/// Returns true if the text changed
+#[no_mangle]
+pub extern "C" fn mcore_text_input_event(
+    ctx: *mut McoreContext,
+    id: u64,
+    event: *const McoreTextEvent,
+) -> u8 {
+    ffi_boundary("mcore_text_input_event", || {
+        trace_call!("mcore_text_input_event", ctx, id, event);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_event", "ctx");
+        };
+        let Some(event) = (unsafe { event.as_ref() }) else {
+            return null_arg("mcore_text_input_event", "event");
+        };
+
+        let mut guard = ctx.0.lock();
+
+        let now = guard.time_s;
+        let state = guard.text_inputs.get_or_create(id);
+        state.touch_caret(now);
+
+        match event.kind {
+            McoreTextEventKind::InsertChar => {
+                if let Some(ch) = char::from_u32(event.char_code) {
+                    if !state.insert_char(ch) {
+                        return 0;
+                    }
+                    // Secure inputs may show the new character briefly; redraw to mask it
+                    if let Some(until) = state.reveal_last_typed(now) {
+                        guard.scheduler.request_at(until);
+                        let redraw = guard.take_redraw();
+                        drop(guard);
+                        fire_redraw(redraw);
+                    }
+                    return 1;
+                }
+            }
+            McoreTextEventKind::Backspace => {
+                state.backspace();
+                return 1;
+            }
+            McoreTextEventKind::Delete => {
+                state.delete();
+                return 1;
+            }
+            McoreTextEventKind::BackspaceWord => {
+                state.backspace_word();
+                return 1;
+            }
+            McoreTextEventKind::DeleteWord => {
+                state.delete_word();
+                return 1;
+            }
+            McoreTextEventKind::MoveCursor => {
+                let motion = match event.direction {
+                    McoreCursorDirection::Left => text_input::Motion::Left,
+                    McoreCursorDirection::Right => text_input::Motion::Right,
+                    McoreCursorDirection::Home => text_input::Motion::Home,
+                    McoreCursorDirection::End => text_input::Motion::End,
+                    McoreCursorDirection::WordLeft => text_input::Motion::WordLeft,
+                    McoreCursorDirection::WordRight => text_input::Motion::WordRight,
+                    McoreCursorDirection::Backward => text_input::Motion::Backward,
+                    McoreCursorDirection::Forward => text_input::Motion::Forward,
+                    McoreCursorDirection::WordBackward => text_input::Motion::WordBackward,
+                    McoreCursorDirection::WordForward => text_input::Motion::WordForward,
+                };
+                state.move_cursor(motion, event.extend_selection != 0);
+                return 0;  // Cursor movement doesn't change text
+            }
+            McoreTextEventKind::SetCursor => {
+                // Shift+click extends from the selection anchor (or the caret)
+                let position = event.cursor_position.max(0) as usize;
+                if event.extend_selection != 0 {
+                    state.extend_selection_to(position);
+                } else {
+                    state.set_cursor(position);
+                    state.clear_selection();
+                    state.selection_anchor = None;
+                }
+                return 0;  // Cursor movement doesn't change text
+            }
+            McoreTextEventKind::SelectAll => {
+                state.select_all();
+                return 0;
+            }
+            // Passwords never leave a secure input through the clipboard
+            McoreTextEventKind::Cut => {
+                if let Some(selected) = state.get_selection_text().filter(|_| !state.is_secure()) {
+                    if clipboard::set_text(selected) {
+                        state.backspace();
+                        return 1;
+                    }
+                }
+            }
+            McoreTextEventKind::Copy => {
+                if let Some(selected) = state.get_selection_text().filter(|_| !state.is_secure()) {
+                    clipboard::set_text(selected);
+                }
+            }
+            McoreTextEventKind::Paste => {
+                if let Some(text) = clipboard::get_text() {
+                    return state.insert_text(&text) as u8;
+                }
+            }
+            McoreTextEventKind::Undo => {
+                return state.undo() as u8;
+            }
+            McoreTextEventKind::Redo => {
+                return state.redo() as u8;
+            }
+            McoreTextEventKind::ImeSetComposition => {
+                let text = if event.text_ptr.is_null() {
+                    ""
+                } else {
+                    utf8_arg(event.text_ptr)
+                };
+                state.set_composition(text, event.cursor_position.max(0) as usize);
+                return 0;
+            }
+            McoreTextEventKind::ImeCommit => {
+                if !event.text_ptr.is_null() {
+                    let text = utf8_arg(event.text_ptr);
+                    return state.commit_composition(text) as u8;
+                }
+            }
+            McoreTextEventKind::ImeCancel => {
+                state.cancel_composition();
+                return 0;
+            }
+            McoreTextEventKind::InsertText => {
+                if !event.text_ptr.is_null() {
+                    let text = utf8_arg(event.text_ptr);
+                    return state.insert_text(text) as u8;
+                }
+            }
+        }
+
+        0
+    })
+}
+
+/// Result of mcore_text_input_event_ex
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextEventResult {
+    /// The text changed (mcore_text_input_event's return value)
+    pub changed: u8,
+    /// The scroll offset moved to follow the caret
+    pub scrolled: u8,
+    pub _padding: [u8; 2],
+    /// Offset into the content to draw at (logical px); 0 without a viewport
+    pub scroll_x: f32,
+    pub scroll_y: f32,
+}
+
+/// mcore_text_input_event, then scroll the input's viewport (see
+/// mcore_text_input_set_viewport) so the caret stays visible, and report the
+/// offset to draw at
+#[no_mangle]
+pub extern "C" fn mcore_text_input_event_ex(
+    ctx: *mut McoreContext,
+    id: u64,
+    event: *const McoreTextEvent,
+    out: *mut McoreTextEventResult,
+) -> u8 {
+    ffi_boundary("mcore_text_input_event_ex", || {
+        trace_call!("mcore_text_input_event_ex", ctx, id, event, out);
+        let changed = mcore_text_input_event(ctx, id, event);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_event_ex", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        let scrolled = text_input::follow_caret(&mut guard, id);
+        if let Some(out) = unsafe { out.as_mut() } {
+            let scroll = guard.text_inputs.get(id).map_or((0.0, 0.0), |state| state.scroll);
+            *out = McoreTextEventResult {
+                changed,
+                scrolled: scrolled as u8,
+                _padding: [0; 2],
+                scroll_x: scroll.0,
+                scroll_y: scroll.1,
+            };
+        }
+        changed
+    })
+}
+
+/// Give a text input a viewport (logical px) the engine keeps its caret
+/// visible in, laid out at font_size and wrapped at wrap_width (0 = a single
+/// line). The scroll offset follows the caret after every
+/// mcore_text_input_event_ex, and immediately here (e.g. after a resize).
+/// A width or height of 0 removes the viewport.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_set_viewport(
+    ctx: *mut McoreContext,
+    id: u64,
+    width: f32,
+    height: f32,
+    font_size: f32,
+    wrap_width: f32,
+) {
+    ffi_boundary("mcore_text_input_set_viewport", || {
+        trace_call!("mcore_text_input_set_viewport", ctx, id, width, height, font_size, wrap_width);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_set_viewport", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        let state = guard.text_inputs.get_or_create(id);
+        if width <= 0.0 || height <= 0.0 {
+            state.viewport = None;
+            state.scroll = (0.0, 0.0);
+            return;
+        }
+        state.viewport = Some(text_input::Viewport {
+            width,
+            height,
+            font_size,
+            wrap_width: (wrap_width > 0.0).then_some(wrap_width),
+        });
+        text_input::follow_caret(&mut guard, id);
+    })
+}
+
+/// How mcore_text_input_draw lays out and colors a field (logical px)
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextInputStyle {
+    /// Field box; everything is clipped to it
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub padding_x: f32,
+    pub padding_y: f32,
+    pub font_size: f32,
+    /// 0: one line, centered vertically. Otherwise wrap at this width.
+    pub wrap_width: f32,
+    pub text_color: McoreRgba,
+    pub selection_color: McoreRgba,
+    pub caret_color: McoreRgba,
+    /// Draw the selection and blinking caret
+    pub focused: u8,
+    pub _padding: [u8; 3],
+}
+
+/// Draw a text input from its engine state in one call: content (masked if
+/// secure), selection, underlined IME preedit and a blinking caret, scrolled
+/// to keep the caret visible. Also records the placement for the _root
+/// queries. The caret blinks on frame time and requests redraws for its
+/// transitions, so hosts only need to draw frames when asked.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_draw(ctx: *mut McoreContext, id: u64, style: *const McoreTextInputStyle) {
+    ffi_boundary("mcore_text_input_draw", || {
+        trace_call!("mcore_text_input_draw", ctx, id, style);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_draw", "ctx");
+        };
+        let Some(style) = (unsafe { style.as_ref() }) else {
+            return null_arg("mcore_text_input_draw", "style");
+        };
+
+        let s = *style;
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_text_input_draw") {
+            return;
+        }
+
+        text_input::draw(&mut guard, id, &s);
+        let redraw = guard.take_redraw();
+        drop(guard);
+        fire_redraw(redraw);
+    })
+}
+
+/// A text input's scroll offset (logical px; 0, 0 without a viewport)
+#[no_mangle]
+pub extern "C" fn mcore_text_input_get_scroll(ctx: *mut McoreContext, id: u64, out_x: *mut f32, out_y: *mut f32) {
+    ffi_boundary("mcore_text_input_get_scroll", || {
+        trace_call!("mcore_text_input_get_scroll", ctx, id, out_x, out_y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_get_scroll", "ctx");
+        };
+
+        let guard = ctx.0.lock();
+        let (x, y) = guard.text_inputs.get(id).map_or((0.0, 0.0), |state| state.scroll);
+        unsafe {
+            if let Some(out_x) = out_x.as_mut() {
+                *out_x = x;
+            }
+            if let Some(out_y) = out_y.as_mut() {
+                *out_y = y;
+            }
+        }
+    })
+}
+
+/// Single-line fields: keep the caret inside a field `width` wide (logical px,
+/// excluding padding) and return the horizontal offset to draw the content
+/// at (text x = field content x - offset). Sets a one-line viewport, so
+/// later events made with mcore_text_input_event_ex follow the caret too.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_scroll_x(ctx: *mut McoreContext, id: u64, width: f32, font_size: f32) -> f32 {
+    ffi_boundary("mcore_text_input_scroll_x", || {
+        trace_call!("mcore_text_input_scroll_x", ctx, id, width, font_size);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_scroll_x", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        let state = guard.text_inputs.get_or_create(id);
+        if width <= 0.0 {
+            state.viewport = None;
+            state.scroll = (0.0, 0.0);
+            return 0.0;
+        }
+        state.viewport = Some(text_input::Viewport { width, height: font_size, font_size, wrap_width: None });
+        text_input::follow_caret(&mut guard, id);
+        guard.text_inputs.get(id).map_or(0.0, |state| state.scroll.0)
+    })
+}
+
+/// Get the current text content for a widget ID
+/// Returns the number of bytes written (excluding null terminator)
+#[no_mangle]
+pub extern "C" fn mcore_text_input_get(
+    ctx: *mut McoreContext,
+    id: u64,
+    buf: *mut u8,
+    buf_len: i32,
+) -> i32 {
+    ffi_boundary("mcore_text_input_get", || {
+        trace_call!("mcore_text_input_get", ctx, id, buf, buf_len);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_text_input_get", "ctx");
+            return 0;
+        };
+
+        if buf.is_null() || buf_len <= 0 {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_text_input_get");
+            return 0;
+        }
+
+        let guard = ctx.0.lock();
+
+        if let Some(state) = guard.text_inputs.get(id) {
+            let content_bytes = state.content.as_bytes();
+            let copy_len = content_bytes.len().min((buf_len - 1) as usize);
+
+            unsafe {
+                std::ptr::copy_nonoverlapping(content_bytes.as_ptr(), buf, copy_len);
+                *buf.add(copy_len) = 0;  // Null terminate
+            }
+
+            copy_len as i32
+        } else {
+            // No state yet, return empty string
+            unsafe {
+                *buf = 0;
+            }
+            0
+        }
+    })
+}
+
+/// Get the cursor position (byte offset) for a widget ID
+#[no_mangle]
+pub extern "C" fn mcore_text_input_cursor(
+    ctx: *mut McoreContext,
+    id: u64,
+) -> i32 {
+    ffi_boundary("mcore_text_input_cursor", || {
+        trace_call!("mcore_text_input_cursor", ctx, id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_text_input_cursor", "ctx");
+            return 0;
+        };
+
+        let guard = ctx.0.lock();
+
+        guard.text_inputs
+            .get(id)
+            .map(|s| s.cursor as i32)
+            .unwrap_or(0)
+    })
+}
+
+/// Set the text content for a widget ID
+#[no_mangle]
+pub extern "C" fn mcore_text_input_set(
+    ctx: *mut McoreContext,
+    id: u64,
+    text: *const i8,
+) {
+    ffi_boundary("mcore_text_input_set", || {
+        trace_call!("mcore_text_input_set", ctx, id, text);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_set", "ctx");
+        };
+        if text.is_null() {
+            return null_arg("mcore_text_input_set", "text");
+        }
+
+        let text_str = utf8_arg(text);
+
+        let mut guard = ctx.0.lock();
+        let state = guard.text_inputs.get_or_create(id);
+        state.set_text(text_str);
+    })
+}
+
+/// Set how many undo steps a text input keeps (default 100, 0 disables undo)
+#[no_mangle]
+pub extern "C" fn mcore_text_input_set_undo_limit(
+    ctx: *mut McoreContext,
+    id: u64,
+    limit: u32,
+) {
+    ffi_boundary("mcore_text_input_set_undo_limit", || {
+        trace_call!("mcore_text_input_set_undo_limit", ctx, id, limit);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_set_undo_limit", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.text_inputs.get_or_create(id).set_undo_limit(limit as usize);
+    })
+}
+
+/// Make a text input a password field: it draws (and hit tests) as bullets
+/// while editing works on the real content, and cut/copy are disabled. With
+/// reveal_ms > 0 each typed character shows for that long before masking.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_set_secure(
+    ctx: *mut McoreContext,
+    id: u64,
+    secure: u8,
+    reveal_ms: u32,
+) {
+    ffi_boundary("mcore_text_input_set_secure", || {
+        trace_call!("mcore_text_input_set_secure", ctx, id, secure, reveal_ms);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_set_secure", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard
+            .text_inputs
+            .get_or_create(id)
+            .set_secure(secure != 0, reveal_ms as f64 / 1000.0);
+    })
+}
+
+/// Limit what typing, pasting and IME commits can put in a text input:
+/// max_bytes / max_graphemes (0 = unlimited) and a character class
+/// (MCORE_CHARS_* in mcore.h). Disallowed characters and whatever doesn't
+/// fit are dropped; see mcore_text_input_take_rejection. Content set with
+/// mcore_text_input_set is not constrained.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_set_constraints(
+    ctx: *mut McoreContext,
+    id: u64,
+    max_bytes: u32,
+    max_graphemes: u32,
+    char_class: u8,
+) {
+    ffi_boundary("mcore_text_input_set_constraints", || {
+        trace_call!("mcore_text_input_set_constraints", ctx, id, max_bytes, max_graphemes, char_class);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_set_constraints", "ctx");
+        };
+
+        let chars = match char_class {
+            1 => text_input::CharClass::Numeric,
+            2 => text_input::CharClass::Decimal,
+            3 => text_input::CharClass::Hex,
+            _ => text_input::CharClass::Any,
+        };
+        let mut guard = ctx.0.lock();
+        guard.text_inputs.get_or_create(id).set_constraints(text_input::Constraints {
+            max_bytes: (max_bytes > 0).then_some(max_bytes as usize),
+            max_graphemes: (max_graphemes > 0).then_some(max_graphemes as usize),
+            chars,
+        });
+    })
+}
+
+/// Why the latest insertion into a text input was cut short since the last
+/// call (MCORE_REJECTED_*; 0 if nothing was rejected). Hosts poll this
+/// after events to flash or shake the field.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_take_rejection(ctx: *mut McoreContext, id: u64) -> u8 {
+    ffi_boundary("mcore_text_input_take_rejection", || {
+        trace_call!("mcore_text_input_take_rejection", ctx, id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_take_rejection", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard
+            .text_inputs
+            .get_mut(id)
+            .and_then(|state| state.take_rejection())
+            .map_or(0, |rejection| rejection as u8)
+    })
+}
+
+/// What a host should configure for a focused text input
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreTextInputHints {
+    /// MCORE_KEYBOARD_*
+    pub keyboard: u8,
+    /// MCORE_AUTOCORRECT_*
+    pub autocorrect: u8,
+    /// Password field (mcore_text_input_set_secure): use secure event input
+    pub secure: u8,
+    /// Input methods should be active (off for secure, non-default keyboard
+    /// and character-constrained inputs)
+    pub ime_enabled: u8,
+}
+
+/// Keyboard type and autocorrection hints for a text input, kept with its
+/// state. Hosts read them back with mcore_text_input_get_hints on focus.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_set_hints(ctx: *mut McoreContext, id: u64, keyboard: u8, autocorrect: u8) {
+    ffi_boundary("mcore_text_input_set_hints", || {
+        trace_call!("mcore_text_input_set_hints", ctx, id, keyboard, autocorrect);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_set_hints", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.text_inputs.get_or_create(id).hints = text_input::InputHints {
+            keyboard: text_input::KeyboardType::from_u8(keyboard),
+            autocorrect: text_input::Autocorrect::from_u8(autocorrect),
+        };
+    })
+}
+
+/// Paragraph direction of a text input (McoreTextDirection): decides which
+/// way the arrow keys move and how wrapped content is aligned. Auto (the
+/// default) follows the content's first strong character.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_set_direction(ctx: *mut McoreContext, id: u64, direction: u8) {
+    ffi_boundary("mcore_text_input_set_direction", || {
+        trace_call!("mcore_text_input_set_direction", ctx, id, direction);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_set_direction", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.text_inputs.get_or_create(id).direction = text::TextDirection::from_u8(direction);
+    })
+}
+
+/// Whether a text input's content currently lays out right to left, e.g. to
+/// align a host-drawn field (0 for unknown IDs)
+#[no_mangle]
+pub extern "C" fn mcore_text_input_is_rtl(ctx: *mut McoreContext, id: u64) -> u8 {
+    ffi_boundary("mcore_text_input_is_rtl", || {
+        trace_call!("mcore_text_input_is_rtl", ctx, id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_is_rtl", "ctx");
+        };
+
+        let guard = ctx.0.lock();
+        guard.text_inputs.get(id).is_some_and(|state| state.is_rtl()) as u8
+    })
+}
+
+/// Input context configuration for a text input (defaults for unknown IDs)
+#[no_mangle]
+pub extern "C" fn mcore_text_input_get_hints(
+    ctx: *mut McoreContext,
+    id: u64,
+    out: *mut McoreTextInputHints,
+) -> McoreStatus {
+    ffi_boundary("mcore_text_input_get_hints", || {
+        trace_call!("mcore_text_input_get_hints", ctx, id, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_get_hints", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_input_get_hints", "out");
+        };
+
+        let guard = ctx.0.lock();
+        let default = text_input::TextInputState::new();
+        let state = guard.text_inputs.get(id).unwrap_or(&default);
+        *out = McoreTextInputHints {
+            keyboard: state.hints.keyboard as u8,
+            autocorrect: state.hints.autocorrect as u8,
+            secure: state.is_secure() as u8,
+            ime_enabled: state.ime_enabled() as u8,
+        };
+        McoreStatus::Ok
+    })
+}
+
+/// Get selection range for a text input widget
+/// Returns true if there is a selection, and fills out_start and out_end with the byte offsets
+#[no_mangle]
+pub extern "C" fn mcore_text_input_get_selection(
+    ctx: *mut McoreContext,
+    id: u64,
+    out_start: *mut i32,
+    out_end: *mut i32,
+) -> u8 {
+    ffi_boundary("mcore_text_input_get_selection", || {
+        trace_call!("mcore_text_input_get_selection", ctx, id, out_start, out_end);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_get_selection", "ctx");
+        };
+        if out_start.is_null() {
+            return null_arg("mcore_text_input_get_selection", "out_start");
+        }
+        if out_end.is_null() {
+            return null_arg("mcore_text_input_get_selection", "out_end");
+        }
+
+        let guard = ctx.0.lock();
+
+        if let Some(state) = guard.text_inputs.get(id) {
+            if let Some(sel) = state.get_selection() {
+                unsafe {
+                    *out_start = sel.start as i32;
+                    *out_end = sel.end as i32;
+                }
+                return 1;
+            }
+        }
+
+        0
+    })
+}
+
+/// Set cursor position and optionally start a selection
+#[no_mangle]
+pub extern "C" fn mcore_text_input_set_cursor_pos(
+    ctx: *mut McoreContext,
+    id: u64,
+    byte_offset: i32,
+    extend_selection: u8,
+) {
+    ffi_boundary("mcore_text_input_set_cursor_pos", || {
+        trace_call!("mcore_text_input_set_cursor_pos", ctx, id, byte_offset, extend_selection);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_set_cursor_pos", "ctx");
+        };
+
+        if byte_offset < 0 {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_text_input_set_cursor_pos");
+            return;
+        }
+
+        let mut guard = ctx.0.lock();
+        let state = guard.text_inputs.get_or_create(id);
+
+        if extend_selection != 0 {
+            // Extend or create selection
+            state.extend_selection_to(byte_offset as usize);
+        } else {
+            // Just move cursor, clear selection AND anchor
+            state.set_cursor(byte_offset as usize);
+            state.clear_selection();
+            state.selection_anchor = None;
+        }
+    })
+}
+
+/// Get the selected text (returns length, copies into buffer)
+#[no_mangle]
+pub extern "C" fn mcore_text_input_get_selected_text(
+    ctx: *mut McoreContext,
+    id: u64,
+    buf: *mut i8,
+    buf_len: i32,
+) -> i32 {
+    ffi_boundary("mcore_text_input_get_selected_text", || {
+        trace_call!("mcore_text_input_get_selected_text", ctx, id, buf, buf_len);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_text_input_get_selected_text", "ctx");
+            return 0;
+        };
+
+        if buf.is_null() || buf_len <= 0 {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_text_input_get_selected_text");
+            return 0;
+        }
+
+        let guard = ctx.0.lock();
+
+        if let Some(state) = guard.text_inputs.get(id) {
+            if let Some(selected) = state.get_selection_text() {
+                let bytes = selected.as_bytes();
+                let copy_len = bytes.len().min((buf_len - 1) as usize);
+                unsafe {
+                    std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, copy_len);
+                    *buf.add(copy_len) = 0; // Null terminate
+                }
+                return copy_len as i32;
+            }
+        }
+
+        0
+    })
+}
+
+/// Start a selection at a specific position (for mouse down)
+/// Sets both cursor and anchor to the same position, clearing any existing selection
+#[no_mangle]
+pub extern "C" fn mcore_text_input_start_selection(
+    ctx: *mut McoreContext,
+    id: u64,
+    byte_offset: i32,
+) {
+    ffi_boundary("mcore_text_input_start_selection", || {
+        trace_call!("mcore_text_input_start_selection", ctx, id, byte_offset);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_start_selection", "ctx");
+        };
+
+        if byte_offset < 0 {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_text_input_start_selection");
+            return;
+        }
+
+        let mut guard = ctx.0.lock();
+        let state = guard.text_inputs.get_or_create(id);
+
+        // Set cursor and anchor to the same position, clear selection
+        state.set_cursor(byte_offset as usize);
+        state.selection_anchor = Some(state.cursor);
+        state.selection = None;
+    })
+}
+
+// ========== Cursor ==========
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum McoreCursor {
+    Arrow = 0,
+    IBeam = 1,
+    PointingHand = 2,
+    Crosshair = 3,
+    OpenHand = 4,
+    ClosedHand = 5,
+    NotAllowed = 6,
+    ResizeLeftRight = 7,
+    ResizeUpDown = 8,
+    /// Diagonal, top-left to bottom-right
+    ResizeNwse = 9,
+    /// Diagonal, top-right to bottom-left
+    ResizeNesw = 10,
+}
+
+impl FailureValue for McoreCursor {
+    fn failure() -> Self {
+        McoreCursor::Arrow
+    }
+}
+
+/// Cursors cross the FFI as u8: an out-of-range enum value is UB
+impl TryFrom<u8> for McoreCursor {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, String> {
+        Ok(match value {
+            0 => McoreCursor::Arrow,
+            1 => McoreCursor::IBeam,
+            2 => McoreCursor::PointingHand,
+            3 => McoreCursor::Crosshair,
+            4 => McoreCursor::OpenHand,
+            5 => McoreCursor::ClosedHand,
+            6 => McoreCursor::NotAllowed,
+            7 => McoreCursor::ResizeLeftRight,
+            8 => McoreCursor::ResizeUpDown,
+            9 => McoreCursor::ResizeNwse,
+            10 => McoreCursor::ResizeNesw,
+            _ => return Err(format!("Unknown cursor {value}")),
+        })
+    }
+}
+
+/// Set the mouse pointer shape (an McoreCursor value; NSCursor on macOS, call
+/// on the main thread). AppKit resets it when the pointer crosses cursor
+/// rects, so set it again on mouse moves. Elsewhere the shape is only
+/// recorded for mcore_get_cursor.
+#[no_mangle]
+pub extern "C" fn mcore_set_cursor(cursor: u8) -> McoreStatus {
+    ffi_boundary("mcore_set_cursor", || {
+        trace_call!("mcore_set_cursor", cursor);
+        let cursor = match McoreCursor::try_from(cursor) {
+            Ok(cursor) => cursor,
+            Err(e) => {
+                set_err_code(McoreErrorCode::InvalidArg, e);
+                return McoreStatus::Err;
+            }
+        };
+        let cursor = match cursor {
+            McoreCursor::Arrow => cursor::Cursor::Arrow,
+            McoreCursor::IBeam => cursor::Cursor::IBeam,
+            McoreCursor::PointingHand => cursor::Cursor::PointingHand,
+            McoreCursor::Crosshair => cursor::Cursor::Crosshair,
+            McoreCursor::OpenHand => cursor::Cursor::OpenHand,
+            McoreCursor::ClosedHand => cursor::Cursor::ClosedHand,
+            McoreCursor::NotAllowed => cursor::Cursor::NotAllowed,
+            McoreCursor::ResizeLeftRight => cursor::Cursor::ResizeLeftRight,
+            McoreCursor::ResizeUpDown => cursor::Cursor::ResizeUpDown,
+            McoreCursor::ResizeNwse => cursor::Cursor::ResizeNwse,
+            McoreCursor::ResizeNesw => cursor::Cursor::ResizeNesw,
+        };
+        if cursor::set(cursor) {
+            McoreStatus::Ok
+        } else {
+            set_err("failed to set the cursor");
+            McoreStatus::Err
+        }
+    })
+}
+
+/// The shape last passed to mcore_set_cursor (Arrow before any)
+#[no_mangle]
+pub extern "C" fn mcore_get_cursor() -> McoreCursor {
+    ffi_boundary("mcore_get_cursor", || {
+        trace_call!("mcore_get_cursor");
+        match cursor::current() {
+            cursor::Cursor::Arrow => McoreCursor::Arrow,
+            cursor::Cursor::IBeam => McoreCursor::IBeam,
+            cursor::Cursor::PointingHand => McoreCursor::PointingHand,
+            cursor::Cursor::Crosshair => McoreCursor::Crosshair,
+            cursor::Cursor::OpenHand => McoreCursor::OpenHand,
+            cursor::Cursor::ClosedHand => McoreCursor::ClosedHand,
+            cursor::Cursor::NotAllowed => McoreCursor::NotAllowed,
+            cursor::Cursor::ResizeLeftRight => McoreCursor::ResizeLeftRight,
+            cursor::Cursor::ResizeUpDown => McoreCursor::ResizeUpDown,
+            cursor::Cursor::ResizeNwse => McoreCursor::ResizeNwse,
+            cursor::Cursor::ResizeNesw => McoreCursor::ResizeNesw,
+        }
+    })
+}
+
+// ========== Clipboard ==========
+
+/// Put plain text on the system clipboard
+#[no_mangle]
+pub extern "C" fn mcore_clipboard_set_text(text: *const i8) -> McoreStatus {
+    ffi_boundary("mcore_clipboard_set_text", || {
+        trace_call!("mcore_clipboard_set_text", text);
+        if text.is_null() {
+            return null_arg("mcore_clipboard_set_text", "text");
+        }
+
+        let text = unsafe { CStr::from_ptr(text) }.to_string_lossy();
+        if clipboard::set_text(&text) {
+            McoreStatus::Ok
+        } else {
+            set_err("failed to write to the clipboard");
+            McoreStatus::Err
+        }
+    })
+}
+
+/// Copy the clipboard's text into buf, null-terminated and truncated to fit.
+/// Returns the full length in bytes, or -1 if the clipboard holds no text.
+#[no_mangle]
+pub extern "C" fn mcore_clipboard_get_text(buf: *mut u8, buf_len: i32) -> i32 {
+    ffi_boundary("mcore_clipboard_get_text", || {
+        trace_call!("mcore_clipboard_get_text", buf, buf_len);
+        let Some(text) = clipboard::get_text() else {
+            return -1;
+        };
+        if !buf.is_null() && buf_len > 0 {
+            let bytes = text.as_bytes();
+            let copy_len = bytes.len().min((buf_len - 1) as usize);
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf, copy_len);
+                *buf.add(copy_len) = 0; // Null terminate
+            }
+        }
+        text.len() as i32
+    })
+}
+
+// ========== IME (Input Method Editor) Support ==========
+
+#[repr(C)]
+pub struct McoreImePreedit {
+    pub text: *const i8,
+    pub cursor_offset: i32,
+}
+
+/// Set IME preedit (composition) text for a text input
+#[no_mangle]
+pub extern "C" fn mcore_ime_set_preedit(
+    ctx: *mut McoreContext,
+    id: u64,
+    preedit: *const McoreImePreedit,
+) {
+    ffi_boundary("mcore_ime_set_preedit", || {
+        trace_call!("mcore_ime_set_preedit", ctx, id, preedit);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ime_set_preedit", "ctx");
+        };
+        if preedit.is_null() {
+            return null_arg("mcore_ime_set_preedit", "preedit");
+        }
+
+        let Some(preedit) = (unsafe { preedit.as_ref() }) else {
+            return null_arg("mcore_ime_set_preedit", "preedit");
+        };
+
+        let text = if preedit.text.is_null() {
+            ""
+        } else {
+            utf8_arg(preedit.text)
+        };
+
+        let mut guard = ctx.0.lock();
+        let state = guard.text_inputs.get_or_create(id);
+        state.set_composition(text, preedit.cursor_offset.max(0) as usize);
+    })
+}
+
+/// Commit IME text (finalize composition)
+#[no_mangle]
+pub extern "C" fn mcore_ime_commit(
+    ctx: *mut McoreContext,
+    id: u64,
+    text: *const i8,
+) {
+    ffi_boundary("mcore_ime_commit", || {
+        trace_call!("mcore_ime_commit", ctx, id, text);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ime_commit", "ctx");
+        };
+        if text.is_null() {
+            return null_arg("mcore_ime_commit", "text");
+        }
+
+        let text_str = utf8_arg(text);
+
+        let mut guard = ctx.0.lock();
+        let state = guard.text_inputs.get_or_create(id);
+        state.commit_composition(text_str);
+    })
+}
+
+/// Clear IME preedit state
+#[no_mangle]
+pub extern "C" fn mcore_ime_clear_preedit(
+    ctx: *mut McoreContext,
+    id: u64,
+) {
+    ffi_boundary("mcore_ime_clear_preedit", || {
+        trace_call!("mcore_ime_clear_preedit", ctx, id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ime_clear_preedit", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+
+        if let Some(state) = guard.text_inputs.get_mut(id) {
+            state.cancel_composition();
+        }
+    })
+}
+
+/// Get IME preedit text if any
+/// Returns 1 if there is preedit text, 0 otherwise
+#[no_mangle]
+pub extern "C" fn mcore_ime_get_preedit(
+    ctx: *mut McoreContext,
+    id: u64,
+    buf: *mut i8,
+    buf_len: i32,
+    out_cursor_offset: *mut i32,
+) -> u8 {
+    ffi_boundary("mcore_ime_get_preedit", || {
+        trace_call!("mcore_ime_get_preedit", ctx, id, buf, buf_len, out_cursor_offset);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ime_get_preedit", "ctx");
+        };
+
+        if buf.is_null() || buf_len <= 0 {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_ime_get_preedit");
+            return 0;
+        }
+
+        let guard = ctx.0.lock();
+
+        if let Some(state) = guard.text_inputs.get(id) {
+            if let Some(composition) = &state.ime_composition {
+                let bytes = composition.text.as_bytes();
+                let copy_len = bytes.len().min((buf_len - 1) as usize);
+
+                unsafe {
+                    std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, copy_len);
+                    *buf.add(copy_len) = 0; // Null terminate
+
+                    if !out_cursor_offset.is_null() {
+                        *out_cursor_offset = composition.cursor_offset as i32;
+                    }
+                }
+
+                return 1;
+            }
+        }
+
+        // No preedit text
+        if !buf.is_null() && buf_len > 0 {
+            unsafe {
+                *buf = 0; // Null terminate empty string
+            }
+        }
+
+        0
+    })
+}
+
+/// Get the text a text input should draw: its content with any preedit
+/// spliced in at the caret, as bullets for secure inputs. Writes the caret
+/// and preedit byte range within that text (preedit_start == preedit_end
+/// when not composing).
+/// Returns the number of bytes written (excluding null terminator)
+#[no_mangle]
+pub extern "C" fn mcore_text_input_get_display(
+    ctx: *mut McoreContext,
+    id: u64,
+    buf: *mut u8,
+    buf_len: i32,
+    out_caret: *mut i32,
+    out_preedit_start: *mut i32,
+    out_preedit_end: *mut i32,
+) -> i32 {
+    ffi_boundary("mcore_text_input_get_display", || {
+        trace_call!("mcore_text_input_get_display", ctx, id, buf, buf_len, out_caret, out_preedit_start, out_preedit_end);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_text_input_get_display", "ctx");
+            return 0;
+        };
+        if buf.is_null() || buf_len <= 0 {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_text_input_get_display");
+            return 0;
+        }
+
+        let guard = ctx.0.lock();
+        let display = guard.text_inputs.get(id).map(|state| state.display(guard.time_s));
+        let (text, caret, preedit) = match &display {
+            Some(d) => (d.text.as_str(), d.caret, d.preedit.clone().unwrap_or(d.caret..d.caret)),
+            None => ("", 0, 0..0),
+        };
+
+        let copy_len = text.len().min((buf_len - 1) as usize);
+        unsafe {
+            std::ptr::copy_nonoverlapping(text.as_ptr(), buf, copy_len);
+            *buf.add(copy_len) = 0;
+            if let Some(out) = out_caret.as_mut() {
+                *out = caret as i32;
+            }
+            if let Some(out) = out_preedit_start.as_mut() {
+                *out = preedit.start as i32;
+            }
+            if let Some(out) = out_preedit_end.as_mut() {
+                *out = preedit.end as i32;
+            }
+        }
+        copy_len as i32
+    })
+}
+
+/// Selection as a byte range of the text from mcore_text_input_get_display
+/// (which differs from content offsets in secure inputs and while composing).
+/// Returns 1 if there is a selection.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_get_display_selection(
+    ctx: *mut McoreContext,
+    id: u64,
+    out_start: *mut i32,
+    out_end: *mut i32,
+) -> u8 {
+    ffi_boundary("mcore_text_input_get_display_selection", || {
+        trace_call!("mcore_text_input_get_display_selection", ctx, id, out_start, out_end);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_get_display_selection", "ctx");
+        };
+        if out_start.is_null() {
+            return null_arg("mcore_text_input_get_display_selection", "out_start");
+        }
+        if out_end.is_null() {
+            return null_arg("mcore_text_input_get_display_selection", "out_end");
+        }
+
+        let guard = ctx.0.lock();
+        let selection = guard
+            .text_inputs
+            .get(id)
+            .and_then(|state| state.display(guard.time_s).selection);
+        match selection {
+            Some(range) => {
+                unsafe {
+                    *out_start = range.start as i32;
+                    *out_end = range.end as i32;
+                }
+                1
+            }
+            None => 0,
+        }
+    })
+}
+
+/// Content byte offset under logical x (relative to the text origin) in a
+/// single-line text input drawn with the default font at font_size. Hit
+/// tests what is drawn, so it works for secure inputs and during composition;
+/// pass the result to mcore_text_input_set_cursor_pos/start_selection.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_hit_test(
+    ctx: *mut McoreContext,
+    id: u64,
+    font_size: f32,
+    x: f32,
+) -> i32 {
+    ffi_boundary("mcore_text_input_hit_test", || {
+        trace_call!("mcore_text_input_hit_test", ctx, id, font_size, x);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_text_input_hit_test", "ctx");
+            return 0;
+        };
+
+        let mut guard = ctx.0.lock();
+        text_input::hit_test(&mut guard, id, font_size, x, 0.0) as i32
+    })
+}
+
+/// Caret rectangle of a single-line text input, in logical pixels relative
+/// to its text origin, following the IME caret while composing. Hosts add the
+/// text origin and convert to screen coordinates for the candidate window
+/// (NSTextInputClient firstRectForCharacterRange).
+#[no_mangle]
+pub extern "C" fn mcore_text_input_caret_rect(
+    ctx: *mut McoreContext,
+    id: u64,
+    font_size: f32,
+    out: *mut McoreCaretRect,
+) -> McoreStatus {
+    ffi_boundary("mcore_text_input_caret_rect", || {
+        trace_call!("mcore_text_input_caret_rect", ctx, id, font_size, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_caret_rect", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_input_caret_rect", "out");
+        };
+
+        let mut guard = ctx.0.lock();
+        let caret = text_input::caret(&mut guard, id, font_size);
+
+        out.x = caret.x;
+        out.y = caret.y;
+        out.height = caret.height;
+        McoreStatus::Ok
+    })
+}
+
+/// Record where a text input's text is drawn this frame: its origin in the
+/// current local space of the hit transform stack (mcore_hit_push_transform),
+/// which is captured with it. The _root queries below then work in window
+/// coordinates, so inputs inside a zoomed or panned viewport stay usable.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_set_origin(ctx: *mut McoreContext, id: u64, x: f32, y: f32) {
+    ffi_boundary("mcore_text_input_set_origin", || {
+        trace_call!("mcore_text_input_set_origin", ctx, id, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_set_origin", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        let transform = guard.hits.current_transform();
+        guard.text_inputs.get_or_create(id).placement = Some(text_input::Placement {
+            origin_x: x,
+            origin_y: y,
+            transform,
+        });
+    })
+}
+
+/// Caret of a text input in root (window) logical coordinates, scaled by the
+/// transform it was drawn under (see mcore_text_input_set_origin)
+#[no_mangle]
+pub extern "C" fn mcore_text_input_caret_rect_root(
+    ctx: *mut McoreContext,
+    id: u64,
+    font_size: f32,
+    out: *mut McoreCaretRect,
+) -> McoreStatus {
+    ffi_boundary("mcore_text_input_caret_rect_root", || {
+        trace_call!("mcore_text_input_caret_rect_root", ctx, id, font_size, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_caret_rect_root", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_text_input_caret_rect_root", "out");
+        };
+
+        let mut guard = ctx.0.lock();
+        let Some(placement) = guard.text_inputs.get(id).and_then(|state| state.placement) else {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_text_input_caret_rect_root: input has no origin (call mcore_text_input_set_origin)");
+            return McoreStatus::Err;
+        };
+        let caret = text_input::caret(&mut guard, id, font_size);
+
+        let (x, y) = placement.to_root(caret.x, caret.y);
+        out.x = x;
+        out.y = y;
+        out.height = caret.height * placement.transform.scale;
+        McoreStatus::Ok
+    })
+}
+
+/// Content byte offset under a root (window) logical point, mapped through
+/// the transform the input was drawn under. Returns -1 if the input has no
+/// origin yet.
+#[no_mangle]
+pub extern "C" fn mcore_text_input_hit_test_root(
+    ctx: *mut McoreContext,
+    id: u64,
+    font_size: f32,
+    x: f32,
+    y: f32,
+) -> i32 {
+    ffi_boundary("mcore_text_input_hit_test_root", || {
+        trace_call!("mcore_text_input_hit_test_root", ctx, id, font_size, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_text_input_hit_test_root", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        let Some(placement) = guard.text_inputs.get(id).and_then(|state| state.placement) else {
+            return -1;
+        };
+        let (local_x, local_y) = placement.from_root(x, y);
+        text_input::hit_test(&mut guard, id, font_size, local_x, local_y) as i32
+    })
+}
+
+// ============================================================================
+// Gesture Recognition FFI
+// ============================================================================
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McorePointerPhase {
+    Down = 0,
+    Move = 1,
+    Up = 2,
+    Cancel = 3,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreGesture {
+    pub kind: u8,  // 0 = Tap, 1 = DoubleTap, 2 = LongPress, 3 = PanBegin, 4 = PanUpdate, 5 = PanEnd
+    pub x: f32,
+    pub y: f32,
+    pub dx: f32,
+    pub dy: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+}
+
+/// Feed a raw pointer event (logical pixels, time in seconds) to the gesture recognizer
+/// Long-presses also fire from mcore_begin_frame when the pointer is held still
+#[no_mangle]
+pub extern "C" fn mcore_gesture_pointer_event(
+    ctx: *mut McoreContext,
+    phase: McorePointerPhase,
+    x: f32,
+    y: f32,
+    time_seconds: f64,
+) {
+    ffi_boundary("mcore_gesture_pointer_event", || {
+        trace_call!("mcore_gesture_pointer_event", ctx, x, y, time_seconds);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_gesture_pointer_event", "ctx");
+        };
+
+        let redraw = {
+            let mut guard = ctx.0.lock();
+
+            let phase = match phase {
+                McorePointerPhase::Down => gesture::PointerPhase::Down,
+                McorePointerPhase::Move => gesture::PointerPhase::Move,
+                McorePointerPhase::Up => gesture::PointerPhase::Up,
+                McorePointerPhase::Cancel => gesture::PointerPhase::Cancel,
+            };
+            guard.gestures.tick(time_seconds);
+            guard.gestures.pointer_event(phase, x, y, time_seconds);
+            guard.schedule_animations();
+            guard.take_redraw()
+        };
+
+        fire_redraw(redraw);
+    })
+}
+
+/// Pop the next recognized gesture
+/// Returns 1 and fills `out` if a gesture was pending, 0 otherwise
+#[no_mangle]
+pub extern "C" fn mcore_gesture_next(ctx: *mut McoreContext, out: *mut McoreGesture) -> u8 {
+    ffi_boundary("mcore_gesture_next", || {
+        trace_call!("mcore_gesture_next", ctx, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_gesture_next", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_gesture_next", "out");
+        };
+
+        let mut guard = ctx.0.lock();
+
+        match guard.gestures.next() {
+            Some(g) => {
+                out.kind = g.kind as u8;
+                out.x = g.x;
+                out.y = g.y;
+                out.dx = g.dx;
+                out.dy = g.dy;
+                out.velocity_x = g.vx;
+                out.velocity_y = g.vy;
+                1
+            }
+            None => 0,
+        }
+    })
+}
+
+// ============================================================================
+// Hit Regions & Pointer Routing FFI
+// ============================================================================
+
+/// Register a hit region for this frame (logical pixels, in the current hit transform)
+/// The active clip (from push_clip calls/commands) and transform are captured.
+/// Higher z wins; equal z resolves to the region registered last.
+#[no_mangle]
+pub extern "C" fn mcore_hit_region(
+    ctx: *mut McoreContext,
+    id: u64,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    z: i32,
+) {
+    ffi_boundary("mcore_hit_region", || {
+        trace_call!("mcore_hit_region", ctx, id, x, y, width, height, z);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_hit_region", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.hits.add_region(id, hit::Rect::from_xywh(x, y, width, height), z);
+    })
+}
+
+/// Push a translation + uniform scale applied to subsequently registered hit regions
+#[no_mangle]
+pub extern "C" fn mcore_hit_push_transform(ctx: *mut McoreContext, dx: f32, dy: f32, scale: f32) {
+    ffi_boundary("mcore_hit_push_transform", || {
+        trace_call!("mcore_hit_push_transform", ctx, dx, dy, scale);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_hit_push_transform", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.hits.push_transform(hit::Transform { dx, dy, scale });
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_hit_pop_transform(ctx: *mut McoreContext) {
+    ffi_boundary("mcore_hit_pop_transform", || {
+        trace_call!("mcore_hit_pop_transform", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_hit_pop_transform", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.hits.pop_transform();
+    })
+}
+
+/// The composed hit transform stack: root = local * scale + (dx, dy).
+/// Text query results (carets, selection rects, layout metrics) are local to
+/// the text origin; hosts map them with this to get window coordinates.
+#[no_mangle]
+pub extern "C" fn mcore_hit_current_transform(ctx: *mut McoreContext, out: *mut McoreHitTransform) {
+    ffi_boundary("mcore_hit_current_transform", || {
+        trace_call!("mcore_hit_current_transform", ctx, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_hit_current_transform", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_hit_current_transform", "out");
+        };
+
+        let transform = ctx.0.lock().hits.current_transform();
+        *out = McoreHitTransform { dx: transform.dx, dy: transform.dy, scale: transform.scale };
+    })
+}
+
+/// Find the topmost region under a point (window logical pixels): the one
+/// with the highest z, then the latest registered, among regions whose clip
+/// contains the point. Between frames this sees the last frame's regions.
+/// Returns 1 and fills `out` on a hit, 0 if no region is there.
+#[no_mangle]
+pub extern "C" fn mcore_hit_test(ctx: *mut McoreContext, x: f32, y: f32, out: *mut McoreHitResult) -> u8 {
+    ffi_boundary("mcore_hit_test", || {
+        trace_call!("mcore_hit_test", ctx, x, y, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_hit_test", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_hit_test", "out");
+        };
+
+        let guard = ctx.0.lock();
+        let Some(region) = guard.hits.hit_test(x, y) else {
+            return 0;
+        };
+        let (local_x, local_y) = region.to_local(x, y);
+        *out = McoreHitResult { id: region.id, z: region.z, local_x, local_y };
+        1
+    })
+}
+
+/// Set the callback receiving routed pointer events
+/// Callback signature: (region_id, phase, local_x, local_y)
+/// Phases: 0 = Down, 1 = Move, 2 = Up, 3 = Cancel, 4 = Enter, 5 = Leave
+#[no_mangle]
+pub extern "C" fn mcore_pointer_set_callback(
+    ctx: *mut McoreContext,
+    callback: extern "C" fn(u64, u8, f32, f32),
+) {
+    ffi_boundary("mcore_pointer_set_callback", || {
+        trace_call!("mcore_pointer_set_callback", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_pointer_set_callback", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.pointer_callback = Some(callback);
+    })
+}
+
+/// Route a pointer event (window logical pixels) through the hit regions
+/// The callback is invoked after the engine lock is released, so it may call back into the engine.
+#[no_mangle]
+pub extern "C" fn mcore_pointer_event(
+    ctx: *mut McoreContext,
+    phase: McorePointerPhase,
+    x: f32,
+    y: f32,
+) {
+    ffi_boundary("mcore_pointer_event", || {
+        trace_call!("mcore_pointer_event", ctx, x, y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_pointer_event", "ctx");
+        };
+
+        let (events, callback) = {
+            let mut guard = ctx.0.lock();
+            let phase = match phase {
+                McorePointerPhase::Down => gesture::PointerPhase::Down,
+                McorePointerPhase::Move => gesture::PointerPhase::Move,
+                McorePointerPhase::Up => gesture::PointerPhase::Up,
+                McorePointerPhase::Cancel => gesture::PointerPhase::Cancel,
+            };
+            (guard.hits.dispatch(phase, x, y), guard.pointer_callback)
+        };
+
+        if let Some(callback) = callback {
+            for event in events {
+                callback(event.id, event.phase as u8, event.local_x, event.local_y);
+            }
+        }
+    })
+}
+
+// ============================================================================
+// Scroll Physics FFI
+// ============================================================================
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreScrollState {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    pub animating: u8,
+}
+
+/// Set a scroll container's viewport and content sizes (logical pixels),
+/// creating it on first use
+#[no_mangle]
+pub extern "C" fn mcore_scroll_set_extent(
+    ctx: *mut McoreContext,
+    id: u64,
+    viewport_w: f32,
+    viewport_h: f32,
+    content_w: f32,
+    content_h: f32,
+) {
+    ffi_boundary("mcore_scroll_set_extent", || {
+        trace_call!("mcore_scroll_set_extent", ctx, id, viewport_w, viewport_h, content_w, content_h);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_set_extent", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.scrolls.set_extent(id, (viewport_w, viewport_h), (content_w, content_h));
+    })
+}
+
+/// Scroll immediately by a delta (mouse wheel), clamped, cancelling momentum
+#[no_mangle]
+pub extern "C" fn mcore_scroll_by(ctx: *mut McoreContext, id: u64, dx: f32, dy: f32) {
+    ffi_boundary("mcore_scroll_by", || {
+        trace_call!("mcore_scroll_by", ctx, id, dx, dy);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_by", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.scrolls.scroll_by(id, dx, dy);
+    })
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum McoreScrollPhase {
+    /// Discrete mouse wheel step
+    Wheel = 0,
+    Began = 1,
+    Changed = 2,
+    Ended = 3,
+    Cancelled = 4,
+}
+
+/// Feed a scroll event: the content delta (logical px, positive scrolls
+/// toward the end) with the gesture phase and the event time (seconds, on the
+/// mcore_begin_frame clock). Trackpad gestures drag the content with
+/// rubber-banding and fling with the engine's momentum when they end, so hosts
+/// should drop the OS's own momentum events. Edge callbacks run after the lock
+/// is released.
+#[no_mangle]
+pub extern "C" fn mcore_scroll_event(
+    ctx: *mut McoreContext,
+    id: u64,
+    dx: f32,
+    dy: f32,
+    phase: McoreScrollPhase,
+    time_seconds: f64,
+) {
+    ffi_boundary("mcore_scroll_event", || {
+        trace_call!("mcore_scroll_event", ctx, id, dx, dy, time_seconds);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_event", "ctx");
+        };
+
+        let phase = match phase {
+            McoreScrollPhase::Wheel => scroll::ScrollPhase::Wheel,
+            McoreScrollPhase::Began => scroll::ScrollPhase::Began,
+            McoreScrollPhase::Changed => scroll::ScrollPhase::Changed,
+            McoreScrollPhase::Ended => scroll::ScrollPhase::Ended,
+            McoreScrollPhase::Cancelled => scroll::ScrollPhase::Cancelled,
+        };
+        let (events, callback, redraw) = {
+            let mut guard = ctx.0.lock();
+            let animation_scale = guard.prefs.animation_scale();
+            let events = guard.scrolls.scroll_event(id, dx, dy, phase, time_seconds, animation_scale);
+            guard.schedule_animations();
+            (events, guard.scroll_edge_callback, guard.take_redraw())
+        };
+
+        fire_scroll_edges(events, callback);
+        fire_redraw(redraw);
+    })
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum McoreScrollAxis {
+    X = 0,
+    Y = 1,
+}
+
+/// Snap one axis to multiples of an item extent (0 clears snapping)
+#[no_mangle]
+pub extern "C" fn mcore_scroll_set_snap_interval(ctx: *mut McoreContext, id: u64, axis: McoreScrollAxis, interval: f32) {
+    ffi_boundary("mcore_scroll_set_snap_interval", || {
+        trace_call!("mcore_scroll_set_snap_interval", ctx, id, interval);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_set_snap_interval", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        let snap = (interval > 0.0).then_some(scroll::SnapPoints::Interval(interval));
+        guard.scrolls.set_snap(id, axis == McoreScrollAxis::Y, snap);
+    })
+}
+
+/// Snap one axis to explicit offsets (count 0 clears snapping)
+#[no_mangle]
+pub extern "C" fn mcore_scroll_set_snap_offsets(
+    ctx: *mut McoreContext,
+    id: u64,
+    axis: McoreScrollAxis,
+    offsets: *const f32,
+    count: usize,
+) {
+    ffi_boundary("mcore_scroll_set_snap_offsets", || {
+        trace_call!("mcore_scroll_set_snap_offsets", ctx, id, offsets, count);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_set_snap_offsets", "ctx");
+        };
+
+        let snap = if offsets.is_null() || count == 0 {
+            None
+        } else {
+            let offsets = unsafe { std::slice::from_raw_parts(offsets, count) };
+            Some(scroll::SnapPoints::Offsets(offsets.to_vec()))
+        };
+        let mut guard = ctx.0.lock();
+        guard.scrolls.set_snap(id, axis == McoreScrollAxis::Y, snap);
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_scroll_drag_begin(ctx: *mut McoreContext, id: u64) {
+    ffi_boundary("mcore_scroll_drag_begin", || {
+        trace_call!("mcore_scroll_drag_begin", ctx, id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_drag_begin", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.scrolls.drag_begin(id);
+    })
+}
+
+/// Update a drag with the pointer translation since the drag began
+/// (the dx/dy of a pan gesture). Edge callbacks run after the lock is released.
+#[no_mangle]
+pub extern "C" fn mcore_scroll_drag_update(ctx: *mut McoreContext, id: u64, translation_x: f32, translation_y: f32) {
+    ffi_boundary("mcore_scroll_drag_update", || {
+        trace_call!("mcore_scroll_drag_update", ctx, id, translation_x, translation_y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_drag_update", "ctx");
+        };
+
+        let (events, callback) = {
+            let mut guard = ctx.0.lock();
+            (guard.scrolls.drag_update(id, translation_x, translation_y), guard.scroll_edge_callback)
+        };
+
+        fire_scroll_edges(events, callback);
+    })
+}
+
+/// End a drag with the pointer velocity (logical px/s) to start a fling.
+/// Momentum advances in mcore_begin_frame; with reduced motion the content settles instantly.
+#[no_mangle]
+pub extern "C" fn mcore_scroll_drag_end(ctx: *mut McoreContext, id: u64, velocity_x: f32, velocity_y: f32) {
+    ffi_boundary("mcore_scroll_drag_end", || {
+        trace_call!("mcore_scroll_drag_end", ctx, id, velocity_x, velocity_y);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_drag_end", "ctx");
+        };
+
+        let redraw = {
+            let mut guard = ctx.0.lock();
+            let animation_scale = guard.prefs.animation_scale();
+            guard.scrolls.drag_end(id, velocity_x, velocity_y, animation_scale);
+            guard.schedule_animations();
+            guard.take_redraw()
+        };
+
+        fire_redraw(redraw);
+    })
+}
+
+/// Read a container's offset (may be outside the content while overscrolled).
+/// Returns 1 if the container exists, 0 otherwise.
+#[no_mangle]
+pub extern "C" fn mcore_scroll_get(ctx: *mut McoreContext, id: u64, out: *mut McoreScrollState) -> u8 {
+    ffi_boundary("mcore_scroll_get", || {
+        trace_call!("mcore_scroll_get", ctx, id, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_get", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_scroll_get", "out");
+        };
+
+        let guard = ctx.0.lock();
+
+        match guard.scrolls.get(id) {
+            Some(state) => {
+                (out.offset_x, out.offset_y) = state.offset();
+                (out.velocity_x, out.velocity_y) = state.velocity();
+                out.animating = state.is_animating() as u8;
+                1
+            }
+            None => 0,
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_scroll_remove(ctx: *mut McoreContext, id: u64) {
+    ffi_boundary("mcore_scroll_remove", || {
+        trace_call!("mcore_scroll_remove", ctx, id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_remove", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.scrolls.remove(id);
+    })
+}
+
+/// Set the callback fired when a container starts overscrolling past an edge
+#[no_mangle]
+pub extern "C" fn mcore_scroll_set_edge_callback(
+    ctx: *mut McoreContext,
+    callback: extern "C" fn(u64, u8, f32),
+) {
+    ffi_boundary("mcore_scroll_set_edge_callback", || {
+        trace_call!("mcore_scroll_set_edge_callback", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_scroll_set_edge_callback", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        guard.scroll_edge_callback = Some(callback);
+    })
+}
+
+// ============================================================================
+// Layout FFI
+// ============================================================================
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum McoreLayoutDirection {
+    Row = 0,
+    Column = 1,
+    RowReverse = 2,
+    ColumnReverse = 3,
+}
+
+/// Main-axis distribution (justify-content)
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum McoreLayoutJustify {
+    Start = 0,
+    End = 1,
+    Center = 2,
+    SpaceBetween = 3,
+    SpaceAround = 4,
+    SpaceEvenly = 5,
+}
+
+/// Cross-axis alignment (align-items, align-self)
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum McoreLayoutAlign {
+    /// Stretch for align_items; the parent's align_items for align_self
+    Auto = 0,
+    Stretch = 1,
+    Start = 2,
+    End = 3,
+    Center = 4,
+    Baseline = 5,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct McoreEdges {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+/// A layout node's flex properties (logical px). Start from
+/// mcore_layout_style_default: a zeroed style has zero width and height.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreLayoutStyle {
+    /// McoreLayoutDirection
+    pub direction: u8,
+    /// 1 wraps items onto more lines
+    pub wrap: u8,
+    /// McoreLayoutJustify
+    pub justify: u8,
+    /// McoreLayoutAlign
+    pub align_items: u8,
+    /// McoreLayoutAlign
+    pub align_self: u8,
+    /// 1 takes the node out of the layout (it gets an empty rect)
+    pub hidden: u8,
+    /// Sizes; negative (MCORE_LAYOUT_AUTO) is auto
+    pub width: f32,
+    pub height: f32,
+    pub min_width: f32,
+    pub min_height: f32,
+    pub max_width: f32,
+    pub max_height: f32,
+    pub grow: f32,
+    pub shrink: f32,
+    /// Negative is auto
+    pub basis: f32,
+    pub padding: McoreEdges,
+    pub margin: McoreEdges,
+    pub row_gap: f32,
+    pub column_gap: f32,
+}
+
+impl Default for McoreLayoutStyle {
+    fn default() -> Self {
+        Self {
+            direction: McoreLayoutDirection::Row as u8,
+            wrap: 0,
+            justify: McoreLayoutJustify::Start as u8,
+            align_items: McoreLayoutAlign::Auto as u8,
+            align_self: McoreLayoutAlign::Auto as u8,
+            hidden: 0,
+            width: -1.0,
+            height: -1.0,
+            min_width: -1.0,
+            min_height: -1.0,
+            max_width: -1.0,
+            max_height: -1.0,
+            grow: 0.0,
+            shrink: 1.0,
+            basis: -1.0,
+            padding: McoreEdges::default(),
+            margin: McoreEdges::default(),
+            row_gap: 0.0,
+            column_gap: 0.0,
+        }
+    }
+}
+
+impl FailureValue for McoreLayoutStyle {
+    fn failure() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "layout")]
+fn flex_style(style: &McoreLayoutStyle) -> Result<layout::FlexStyle, String> {
+    use layout::{Align, Direction, Edges, Justify};
+
+    let size = |value: f32| (value >= 0.0).then_some(value);
+    let edges = |e: McoreEdges| Edges { top: e.top, right: e.right, bottom: e.bottom, left: e.left };
+    let align = |code: u8, field: &str| -> Result<Option<Align>, String> {
+        Ok(match code {
+            0 => None,
+            1 => Some(Align::Stretch),
+            2 => Some(Align::Start),
+            3 => Some(Align::End),
+            4 => Some(Align::Center),
+            5 => Some(Align::Baseline),
+            _ => return Err(format!("Invalid layout {field} {code}")),
+        })
+    };
+    let valid_factor = |value: f32| value.is_finite() && value >= 0.0;
+    if !valid_factor(style.grow) || !valid_factor(style.shrink) {
+        return Err(format!("Invalid layout grow/shrink {}/{}", style.grow, style.shrink));
+    }
+
+    Ok(layout::FlexStyle {
+        direction: match style.direction {
+            0 => Direction::Row,
+            1 => Direction::Column,
+            2 => Direction::RowReverse,
+            3 => Direction::ColumnReverse,
+            code => return Err(format!("Invalid layout direction {code}")),
+        },
+        wrap: style.wrap != 0,
+        justify: match style.justify {
+            0 => Justify::Start,
+            1 => Justify::End,
+            2 => Justify::Center,
+            3 => Justify::SpaceBetween,
+            4 => Justify::SpaceAround,
+            5 => Justify::SpaceEvenly,
+            code => return Err(format!("Invalid layout justify {code}")),
+        },
+        align_items: align(style.align_items, "align_items")?.unwrap_or(Align::Stretch),
+        align_self: align(style.align_self, "align_self")?,
+        width: size(style.width),
+        height: size(style.height),
+        min_width: size(style.min_width),
+        min_height: size(style.min_height),
+        max_width: size(style.max_width),
+        max_height: size(style.max_height),
+        grow: style.grow,
+        shrink: style.shrink,
+        basis: size(style.basis),
+        padding: edges(style.padding),
+        margin: edges(style.margin),
+        row_gap: style.row_gap.max(0.0),
+        column_gap: style.column_gap.max(0.0),
+        hidden: style.hidden != 0,
+    })
+}
+
+#[cfg(not(feature = "layout"))]
+fn layout_unsupported<R: FailureValue>() -> R {
+    set_err_code(McoreErrorCode::Unsupported, "Layout support not compiled in (enable the layout feature)");
+    R::failure()
+}
+
+/// The style every field of which is at its CSS default (auto sizes, no grow,
+/// shrink 1, row direction)
+#[no_mangle]
+pub extern "C" fn mcore_layout_style_default() -> McoreLayoutStyle {
+    ffi_boundary("mcore_layout_style_default", || {
+        trace_call!("mcore_layout_style_default");
+        McoreLayoutStyle::default()
+    })
+}
+
+/// Create layout node `id` (a host-chosen ID) or replace its style
+#[no_mangle]
+pub extern "C" fn mcore_layout_node_set(ctx: *mut McoreContext, id: u64, style: *const McoreLayoutStyle) -> McoreStatus {
+    ffi_boundary("mcore_layout_node_set", || {
+        trace_call!("mcore_layout_node_set", ctx, id, style);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_layout_node_set", "ctx");
+        };
+        let Some(style) = (unsafe { style.as_ref() }) else {
+            return null_arg("mcore_layout_node_set", "style");
+        };
+
+        #[cfg(feature = "layout")]
+        {
+            let style = match flex_style(style) {
+                Ok(style) => style,
+                Err(e) => {
+                    set_err_code(McoreErrorCode::InvalidArg, e);
+                    return McoreStatus::Err;
+                }
+            };
+            match ctx.0.lock().layout.set_node(id, style) {
+                Ok(()) => McoreStatus::Ok,
+                Err(e) => {
+                    set_err(e);
+                    McoreStatus::Err
+                }
+            }
+        }
+        #[cfg(not(feature = "layout"))]
+        {
+            let _ = (ctx, id, style);
+            layout_unsupported()
+        }
+    })
 }
 
+/// Replace a node's children, in order. A child that had another parent moves.
 #[no_mangle]
-pub extern "C" fn mcore_begin_frame(ctx: *mut McoreContext, time_seconds: f64) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let mut guard = ctx.0.lock();
-    guard.time_s = time_seconds;
-    guard.scene.reset();
-}
+pub extern "C" fn mcore_layout_set_children(ctx: *mut McoreContext, id: u64, children: *const u64, count: usize) -> McoreStatus {
+    ffi_boundary("mcore_layout_set_children", || {
+        trace_call!("mcore_layout_set_children", ctx, id, children, count);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_layout_set_children", "ctx");
+        };
+        let Some(children) = host_slice(children, count) else {
+            return null_arg("mcore_layout_set_children", "children");
+        };
 
-#[no_mangle]
-pub extern "C" fn mcore_rect_rounded(ctx: *mut McoreContext, rect: *const McoreRoundedRect) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let rect = unsafe { rect.as_ref() }.unwrap();
-    let mut guard = ctx.0.lock();
-
-    let shape = peniko::kurbo::RoundedRect::new(
-        rect.x as f64,
-        rect.y as f64,
-        (rect.x + rect.w) as f64,
-        (rect.y + rect.h) as f64,
-        rect.radius as f64,
-    );
-
-    let color = Color::new([
-        rect.fill.r,
-        rect.fill.g,
-        rect.fill.b,
-        rect.fill.a,
-    ]);
-
-    guard.scene.fill(
-        vello::peniko::Fill::NonZero,
-        peniko::kurbo::Affine::IDENTITY,
-        color,
-        None,
-        &shape,
-    );
+        #[cfg(feature = "layout")]
+        match ctx.0.lock().layout.set_children(id, children) {
+            Ok(()) => McoreStatus::Ok,
+            Err(e) => {
+                set_err_code(McoreErrorCode::InvalidArg, e);
+                McoreStatus::Err
+            }
+        }
+        #[cfg(not(feature = "layout"))]
+        {
+            let _ = (ctx, id, children);
+            layout_unsupported()
+        }
+    })
 }
 
+/// Remove a node; its children stay, without a parent
 #[no_mangle]
-pub extern "C" fn mcore_font_register(ctx: *mut McoreContext, blob: *const McoreFontBlob) -> i32 {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let blob = unsafe { blob.as_ref() }.unwrap();
-    let mut guard = ctx.0.lock();
-
-    let data = unsafe { std::slice::from_raw_parts(blob.data, blob.len) };
-    let font_data_vec = data.to_vec();
-
-    let font_blob = Blob::new(Arc::new(font_data_vec.clone()));
-    let font_data = FontData::new(font_blob.clone(), 0);
-
-    guard.text_cx.font_cx.collection.register_fonts(font_blob, None);
-    guard.fonts.push((font_data_vec, font_data));
+pub extern "C" fn mcore_layout_node_remove(ctx: *mut McoreContext, id: u64) {
+    ffi_boundary("mcore_layout_node_remove", || {
+        trace_call!("mcore_layout_node_remove", ctx, id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_layout_node_remove", "ctx");
+        };
 
-    (guard.fonts.len() - 1) as i32
+        #[cfg(feature = "layout")]
+        ctx.0.lock().layout.remove(id);
+        #[cfg(not(feature = "layout"))]
+        {
+            let _ = (ctx, id);
+            layout_unsupported()
+        }
+    })
 }
 
+/// Lay out the tree under `root_id` to fill `width` x `height` (logical px)
 #[no_mangle]
-pub extern "C" fn mcore_text_layout(
-    ctx: *mut McoreContext,
-    req: *const McoreTextReq,
-    out: *mut McoreTextMetrics,
-) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let req = unsafe { req.as_ref() }.unwrap();
-    let out = unsafe { out.as_mut() }.unwrap();
-    let mut guard = ctx.0.lock();
-
-    let text = unsafe { CStr::from_ptr(req.utf8) }.to_str().unwrap_or("");
-    let scale = guard.gfx.scale();
-
-    let metrics = text::layout_text(
-        &mut guard.text_cx,
-        text,
-        req.font_size_px,
-        req.wrap_width,
-        scale,
-    );
+pub extern "C" fn mcore_layout_compute(ctx: *mut McoreContext, root_id: u64, width: f32, height: f32) -> McoreStatus {
+    ffi_boundary("mcore_layout_compute", || {
+        trace_call!("mcore_layout_compute", ctx, root_id, width, height);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_layout_compute", "ctx");
+        };
+        let valid = |value: f32| value.is_finite() && value >= 0.0;
+        if !valid(width) || !valid(height) {
+            set_err_code(McoreErrorCode::InvalidArg, format!("Invalid layout size {width}x{height}"));
+            return McoreStatus::Err;
+        }
 
-    out.advance_w = metrics.width;
-    out.advance_h = metrics.height;
-    out.line_count = metrics.line_count as i32;
+        #[cfg(feature = "layout")]
+        match ctx.0.lock().layout.compute(root_id, width, height) {
+            Ok(()) => McoreStatus::Ok,
+            Err(e) => {
+                set_err_code(McoreErrorCode::NotFound, e);
+                McoreStatus::Err
+            }
+        }
+        #[cfg(not(feature = "layout"))]
+        {
+            let _ = (ctx, root_id);
+            layout_unsupported()
+        }
+    })
 }
 
+/// Read a node's rect from the last mcore_layout_compute of its root,
+/// relative to that root. Returns 1 and fills `out` if it has one, 0 otherwise.
 #[no_mangle]
-pub extern "C" fn mcore_measure_text(
-    ctx: *mut McoreContext,
-    text: *const i8,
-    font_size: f32,
-    max_width: f32,
-    out: *mut McoreTextSize,
-) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let text = unsafe { CStr::from_ptr(text) }.to_str().unwrap_or("");
-    let out = unsafe { out.as_mut() }.unwrap();
-    let mut guard = ctx.0.lock();
+pub extern "C" fn mcore_layout_get(ctx: *mut McoreContext, id: u64, out: *mut McoreRect) -> u8 {
+    ffi_boundary("mcore_layout_get", || {
+        trace_call!("mcore_layout_get", ctx, id, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_layout_get", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_layout_get", "out");
+        };
 
-    // Increment instrumentation counter
-    guard.text_stats.total_measure_calls += 1;
+        #[cfg(feature = "layout")]
+        match ctx.0.lock().layout.rect(id) {
+            Some(rect) => {
+                *out = McoreRect { x: rect.x, y: rect.y, width: rect.width, height: rect.height };
+                1
+            }
+            None => 0,
+        }
+        #[cfg(not(feature = "layout"))]
+        {
+            let _ = (ctx, id, out);
+            layout_unsupported()
+        }
+    })
+}
 
-    let scale = guard.gfx.scale();
+// ============================================================================
+// Immediate-Mode UI FFI
+// ============================================================================
 
-    // Measure with scale for quality, returns logical measurements
-    let (width, height) = text::measure_text(
-        &mut guard.text_cx,
-        text,
-        font_size,
-        max_width,
-        scale,
-    );
+/// Colors and metrics of mcore_ui_* widgets (logical px)
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreUiTheme {
+    pub font_size: f32,
+    /// Inside buttons and text fields
+    pub padding: f32,
+    /// Between widgets
+    pub spacing: f32,
+    pub corner_radius: f32,
+    pub text: McoreRgba,
+    pub button: McoreRgba,
+    pub button_hover: McoreRgba,
+    pub button_pressed: McoreRgba,
+    pub field: McoreRgba,
+    /// Focus ring and caret; the selection is a translucent tint of it
+    pub accent: McoreRgba,
+}
 
-    out.width = width;
-    out.height = height;
+impl Default for McoreUiTheme {
+    fn default() -> Self {
+        let gray = |v: f32| McoreRgba { r: v, g: v, b: v, a: 1.0 };
+        Self {
+            font_size: 14.0,
+            padding: 6.0,
+            spacing: 8.0,
+            corner_radius: 4.0,
+            text: gray(0.1),
+            button: gray(0.88),
+            button_hover: gray(0.82),
+            button_pressed: gray(0.72),
+            field: gray(1.0),
+            accent: McoreRgba { r: 0.2, g: 0.45, b: 0.95, a: 1.0 },
+        }
+    }
 }
 
-#[no_mangle]
-pub extern "C" fn mcore_measure_text_to_byte_offset(
-    ctx: *mut McoreContext,
-    text: *const i8,
-    font_size: f32,
-    byte_offset: i32,
-) -> f32 {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let text = unsafe { CStr::from_ptr(text) }.to_str().unwrap_or("");
-    let mut guard = ctx.0.lock();
+impl FailureValue for McoreUiTheme {
+    fn failure() -> Self {
+        Self::default()
+    }
+}
 
-    // Increment instrumentation counter
-    guard.text_stats.total_offset_calls += 1;
+/// Pointer state for mcore_ui_begin (logical px)
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreUiInput {
+    pub pointer_x: f32,
+    pub pointer_y: f32,
+    /// 1 while the primary button is held
+    pub pointer_down: u8,
+    pub _padding: [u8; 3],
+}
 
-    let scale = guard.gfx.scale();
-    let byte_offset = byte_offset.max(0) as usize;
+/// Widget calls are only valid inside a frame, between mcore_ui_begin and
+/// mcore_ui_end
+fn ui_allowed(eng: &mut Engine, call: &str) -> bool {
+    if !drawing_allowed(eng, call) {
+        return false;
+    }
+    if !eng.ui.is_building() {
+        set_err_code(McoreErrorCode::InvalidState, format!("{call} called outside mcore_ui_begin / mcore_ui_end"));
+        return false;
+    }
+    true
+}
 
-    text::byte_offset_to_x(
-        &mut guard.text_cx,
-        text,
-        font_size,
-        byte_offset,
-        scale,
-    )
+/// Size of `utf8` in the theme font, wrapped at max_width
+fn ui_measure(eng: &mut Engine, utf8: &str, max_width: f32) -> (f32, f32) {
+    let style = text::TextStyle::new(eng.ui.theme.font_size, text::DEFAULT_FONT_ID);
+    let scale = eng.gfx.scale();
+    text::measure_text(&mut eng.text_cx, utf8, &style, max_width, scale)
 }
 
 #[no_mangle]
-pub extern "C" fn mcore_get_text_stats(
-    ctx: *mut McoreContext,
-    out: *mut McoreTextStats,
-) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let out = unsafe { out.as_mut() }.unwrap();
-    let guard = ctx.0.lock();
-
-    out.total_measure_calls = guard.text_stats.total_measure_calls;
-    out.total_offset_calls = guard.text_stats.total_offset_calls;
+pub extern "C" fn mcore_ui_theme_default() -> McoreUiTheme {
+    ffi_boundary("mcore_ui_theme_default", || {
+        trace_call!("mcore_ui_theme_default");
+        McoreUiTheme::default()
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn mcore_reset_text_stats(ctx: *mut McoreContext) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let mut guard = ctx.0.lock();
-    guard.text_stats.reset();
+pub extern "C" fn mcore_ui_set_theme(ctx: *mut McoreContext, theme: *const McoreUiTheme) {
+    ffi_boundary("mcore_ui_set_theme", || {
+        trace_call!("mcore_ui_set_theme", ctx, theme);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ui_set_theme", "ctx");
+        };
+        let Some(theme) = (unsafe { theme.as_ref() }) else {
+            return null_arg("mcore_ui_set_theme", "theme");
+        };
+        ctx.0.lock().ui.theme = *theme;
+    })
 }
 
+/// Start a column of widgets `width` wide at x, y, with this frame's pointer.
+/// Call inside a frame; widgets draw into it as they're called.
 #[no_mangle]
-pub extern "C" fn mcore_text_draw(
-    ctx: *mut McoreContext,
-    req: *const McoreTextReq,
-    x: f32,
-    y: f32,
-    color: McoreRgba,
-) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let req = unsafe { req.as_ref() }.unwrap();
-    let mut guard = ctx.0.lock();
-
-    let text = unsafe { CStr::from_ptr(req.utf8) }.to_str().unwrap_or("");
-    let scale = guard.gfx.scale();
-    let color_val = Color::new([color.r, color.g, color.b, color.a]);
-
-    // Use raw pointers to split borrows
-    let scene_ptr = &mut guard.scene as *mut Scene;
-    let text_cx_ptr = &mut guard.text_cx as *mut text::TextContext;
-
-    unsafe {
-        text::draw_text(
-            &mut *scene_ptr,
-            &mut *text_cx_ptr,
-            text,
-            x,
-            y,
-            req.font_size_px,
-            req.wrap_width,
-            color_val,
-            scale,
-        );
-    }
+pub extern "C" fn mcore_ui_begin(ctx: *mut McoreContext, x: f32, y: f32, width: f32, input: *const McoreUiInput) -> McoreStatus {
+    ffi_boundary("mcore_ui_begin", || {
+        trace_call!("mcore_ui_begin", ctx, x, y, width, input);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ui_begin", "ctx");
+        };
+        let Some(input) = (unsafe { input.as_ref() }) else {
+            return null_arg("mcore_ui_begin", "input");
+        };
+
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_ui_begin") {
+            return McoreStatus::NotInFrame;
+        }
+        let pointer = ui::Pointer { x: input.pointer_x, y: input.pointer_y, down: input.pointer_down != 0 };
+        if !guard.ui.begin(x, y, width, pointer) {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_ui_begin called before mcore_ui_end");
+            return McoreStatus::Err;
+        }
+        McoreStatus::Ok
+    })
 }
 
+/// A wrapped paragraph of text
 #[no_mangle]
-pub extern "C" fn mcore_push_clip_rect(
-    ctx: *mut McoreContext,
-    x: f32,
-    y: f32,
-    width: f32,
-    height: f32,
-) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let mut guard = ctx.0.lock();
+pub extern "C" fn mcore_ui_label(ctx: *mut McoreContext, text: *const i8) {
+    ffi_boundary("mcore_ui_label", || {
+        trace_call!("mcore_ui_label", ctx, text);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ui_label", "ctx");
+        };
+        let text = utf8_arg(text);
 
-    // Push a clip layer with the specified rectangle
-    let clip_rect = peniko::kurbo::Rect::new(x as f64, y as f64, (x + width) as f64, (y + height) as f64);
-    guard.scene.push_layer(vello::peniko::BlendMode::default(), 1.0, peniko::kurbo::Affine::IDENTITY, &clip_rect);
+        let mut guard = ctx.0.lock();
+        let eng = &mut *guard;
+        if !ui_allowed(eng, "mcore_ui_label") {
+            return;
+        }
+        let width = eng.ui.available_width();
+        let (_, height) = ui_measure(eng, text, width);
+        let rect = eng.ui.allocate(None, height);
+        let theme = eng.ui.theme;
+        command_stream::draw(eng, &[ui::text(&theme, rect.x0, rect.y0, width, text)]);
+    })
 }
 
+/// A push button sized to its label. Returns MCORE_UI_* response flags;
+/// MCORE_UI_CLICKED is set on the frame it's clicked.
 #[no_mangle]
-pub extern "C" fn mcore_pop_clip(ctx: *mut McoreContext) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let mut guard = ctx.0.lock();
-    guard.scene.pop_layer();
+pub extern "C" fn mcore_ui_button(ctx: *mut McoreContext, id: u64, label: *const i8) -> u32 {
+    ffi_boundary("mcore_ui_button", || {
+        trace_call!("mcore_ui_button", ctx, id, label);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ui_button", "ctx");
+        };
+        let label = utf8_arg(label);
+
+        let mut guard = ctx.0.lock();
+        let eng = &mut *guard;
+        if !ui_allowed(eng, "mcore_ui_button") {
+            return 0;
+        }
+        let theme = eng.ui.theme;
+        let (width, height) = ui_measure(eng, label, text::NO_WRAP_WIDTH);
+        let rect = eng.ui.allocate(Some(width + 2.0 * theme.padding), height + 2.0 * theme.padding);
+        let response = eng.ui.interact(id, rect, false);
+        command_stream::draw(eng, &ui::button(&theme, rect, label, response));
+        response
+    })
 }
 
+/// A single-line text field `width` wide (0 fills the column), backed by the
+/// mcore_text_input_* state of the same ID. Pressing it focuses it; send key
+/// events for mcore_ui_focused's ID with mcore_text_input_event_ex and read
+/// the text with mcore_text_input_get. Returns MCORE_UI_* response flags.
 #[no_mangle]
-pub extern "C" fn mcore_render_commands(
-    ctx: *mut McoreContext,
-    commands: *const McoreDrawCommand,
-    count: i32,
-) {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let commands = unsafe { std::slice::from_raw_parts(commands, count as usize) };
-    let mut guard = ctx.0.lock();
-
-    // Commands are in physical pixels, but text rendering needs scale for rasterization quality
-    let scale = guard.gfx.scale();
-
-    // Use raw pointers to split borrows for text rendering
-    let scene_ptr = &mut guard.scene as *mut Scene;
-    let text_cx_ptr = &mut guard.text_cx as *mut text::TextContext;
-
-    for cmd in commands {
-        match cmd.kind {
-            0 => {
-                // RoundedRect - scale from logical to physical pixels
-                let shape = peniko::kurbo::RoundedRect::new(
-                    (cmd.x * scale) as f64,
-                    (cmd.y * scale) as f64,
-                    ((cmd.x + cmd.width) * scale) as f64,
-                    ((cmd.y + cmd.height) * scale) as f64,
-                    (cmd.radius * scale) as f64,
-                );
-                let color = Color::new([cmd.color[0], cmd.color[1], cmd.color[2], cmd.color[3]]);
-                unsafe {
-                    (*scene_ptr).fill(vello::peniko::Fill::NonZero, peniko::kurbo::Affine::IDENTITY, color, None, &shape);
-                }
-            }
-            1 => {
-                // Text - scale from logical to physical pixels
-                let text = unsafe { CStr::from_ptr(cmd.text_ptr) }.to_str().unwrap_or("");
-                let color = Color::new([cmd.color[0], cmd.color[1], cmd.color[2], cmd.color[3]]);
+pub extern "C" fn mcore_ui_text_input(ctx: *mut McoreContext, id: u64, width: f32) -> u32 {
+    ffi_boundary("mcore_ui_text_input", || {
+        trace_call!("mcore_ui_text_input", ctx, id, width);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ui_text_input", "ctx");
+        };
 
-                unsafe {
-                    text::draw_text(
-                        &mut *scene_ptr,
-                        &mut *text_cx_ptr,
-                        text,
-                        cmd.x * scale,
-                        cmd.y * scale,
-                        cmd.font_size,
-                        cmd.wrap_width,
-                        color,
-                        scale,
-                    );
-                }
-            }
-            2 => {
-                // PushClip - scale from logical to physical pixels
-                let clip_rect = peniko::kurbo::Rect::new(
-                    (cmd.x * scale) as f64,
-                    (cmd.y * scale) as f64,
-                    ((cmd.x + cmd.width) * scale) as f64,
-                    ((cmd.y + cmd.height) * scale) as f64,
-                );
-                unsafe {
-                    (*scene_ptr).push_layer(vello::peniko::BlendMode::default(), 1.0, peniko::kurbo::Affine::IDENTITY, &clip_rect);
-                }
+        let (response, redraw) = {
+            let mut guard = ctx.0.lock();
+            let eng = &mut *guard;
+            if !ui_allowed(eng, "mcore_ui_text_input") {
+                return 0;
             }
-            3 => {
-                // PopClip
-                unsafe {
-                    (*scene_ptr).pop_layer();
-                }
-            }
-            4 => {
-                // StyledRect (with optional border and shadow) - scale from logical to physical pixels
-                let shape = peniko::kurbo::RoundedRect::new(
-                    (cmd.x * scale) as f64,
-                    (cmd.y * scale) as f64,
-                    ((cmd.x + cmd.width) * scale) as f64,
-                    ((cmd.y + cmd.height) * scale) as f64,
-                    (cmd.radius * scale) as f64,
-                );
-
-                unsafe {
-                    // 1. Draw shadow if present (using Vello's blurred rect)
-                    if cmd.has_shadow != 0 {
-                        let shadow_rect = peniko::kurbo::Rect::new(
-                            ((cmd.x + cmd.shadow_offset_x) * scale) as f64,
-                            ((cmd.y + cmd.shadow_offset_y) * scale) as f64,
-                            ((cmd.x + cmd.width + cmd.shadow_offset_x) * scale) as f64,
-                            ((cmd.y + cmd.height + cmd.shadow_offset_y) * scale) as f64,
-                        );
-                        let shadow_color = Color::new([
-                            cmd.shadow_color[0],
-                            cmd.shadow_color[1],
-                            cmd.shadow_color[2],
-                            cmd.shadow_color[3],
-                        ]);
-
-                        // Use draw_blurred_rounded_rect for drop shadow effect
-                        // Signature: (transform, rect, color, blur_radius, corner_radius)
-                        (*scene_ptr).draw_blurred_rounded_rect(
-                            peniko::kurbo::Affine::IDENTITY,
-                            shadow_rect,
-                            shadow_color,
-                            (cmd.shadow_blur * scale) as f64,
-                            (cmd.radius * scale) as f64,
-                        );
-                    }
+            let theme = eng.ui.theme;
+            let (_, line_height) = ui_measure(eng, "M", text::NO_WRAP_WIDTH);
+            let rect = eng.ui.allocate((width > 0.0).then_some(width), line_height + 2.0 * theme.padding);
+            let response = eng.ui.interact(id, rect, true);
+            command_stream::draw(eng, &ui::field_frame(&theme, rect, response));
+
+            let selection = McoreRgba { a: theme.accent.a * 0.3, ..theme.accent };
+            let style = McoreTextInputStyle {
+                x: rect.x0,
+                y: rect.y0,
+                width: rect.x1 - rect.x0,
+                height: rect.y1 - rect.y0,
+                padding_x: theme.padding,
+                padding_y: theme.padding,
+                font_size: theme.font_size,
+                wrap_width: 0.0,
+                text_color: theme.text,
+                selection_color: selection,
+                caret_color: theme.accent,
+                focused: (response & ui::RESPONSE_FOCUSED != 0) as u8,
+                _padding: [0; 3],
+            };
+            text_input::draw(eng, id, &style);
+            (response, eng.take_redraw())
+        };
 
-                    // 2. Draw fill
-                    let fill_color = Color::new([cmd.color[0], cmd.color[1], cmd.color[2], cmd.color[3]]);
-                    (*scene_ptr).fill(
-                        vello::peniko::Fill::NonZero,
-                        peniko::kurbo::Affine::IDENTITY,
-                        fill_color,
-                        None,
-                        &shape,
-                    );
-
-                    // 3. Draw border if present (using stroke)
-                    if cmd.has_border != 0 && cmd.border_width > 0.0 {
-                        let border_color = Color::new([
-                            cmd.border_color[0],
-                            cmd.border_color[1],
-                            cmd.border_color[2],
-                            cmd.border_color[3],
-                        ]);
-                        let stroke = peniko::kurbo::Stroke::new((cmd.border_width * scale) as f64);
-                        (*scene_ptr).stroke(
-                            &stroke,
-                            peniko::kurbo::Affine::IDENTITY,
-                            border_color,
-                            None,
-                            &shape,
-                        );
-                    }
-                }
-            }
-            _ => {}
-        }
-    }
+        fire_redraw(redraw);
+        response
+    })
 }
 
+/// Finish the column. Returns the height its widgets took (logical px).
 #[no_mangle]
-pub extern "C" fn mcore_end_frame_present(ctx: *mut McoreContext, clear: McoreRgba) -> McoreStatus {
-    let ctx = unsafe { ctx.as_mut() }.unwrap();
-    let mut guard = ctx.0.lock();
-
-    let clear_color = Color::new([clear.r, clear.g, clear.b, clear.a]);
-
-    // Clone the scene to avoid borrow conflict
-    let scene = guard.scene.clone();
+pub extern "C" fn mcore_ui_end(ctx: *mut McoreContext) -> f32 {
+    ffi_boundary("mcore_ui_end", || {
+        trace_call!("mcore_ui_end", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ui_end", "ctx");
+        };
 
-    match guard.gfx.render_scene(&scene, clear_color) {
-        Ok(_) => McoreStatus::Ok,
-        Err(e) => {
-            set_err(e);
-            McoreStatus::Err
+        let mut guard = ctx.0.lock();
+        if !guard.ui.is_building() {
+            set_err_code(McoreErrorCode::InvalidState, "mcore_ui_end called without mcore_ui_begin");
+            return 0.0;
         }
-    }
+        guard.ui.end()
+    })
+}
+
+/// The focused widget's ID, 0 if none is focused
+#[no_mangle]
+pub extern "C" fn mcore_ui_focused(ctx: *mut McoreContext) -> u64 {
+    ffi_boundary("mcore_ui_focused", || {
+        trace_call!("mcore_ui_focused", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_ui_focused", "ctx");
+        };
+        ctx.0.lock().ui.focused().unwrap_or(0)
+    })
 }
 
 // ============================================================================
-// Text Input FFI
+// Redraw Scheduling FFI
 // ============================================================================
 
-#[repr(C)]
-#[derive(Copy, Clone)]
-pub enum McoreTextEventKind {
-    InsertChar = 0,
-    Backspace = 1,
-    Delete = 2,
-    MoveCursor = 3,
-    SetCursor = 4,
-    InsertText = 5,
-}
-
-#[repr(C)]
-#[derive(Copy, Clone)]
-pub enum McoreCursorDirection {
-    Left = 0,
-    Right = 1,
-    Home = 2,
-    End = 3,
-}
+/// Set the callback told when the engine needs a frame.
+/// Callback signature: callback(time_seconds) on the mcore_begin_frame clock;
+/// a time at or before the last frame means "next vsync". It runs without the
+/// engine lock held, on whichever thread made the triggering call.
+#[no_mangle]
+pub extern "C" fn mcore_set_redraw_callback(ctx: *mut McoreContext, callback: extern "C" fn(f64)) {
+    ffi_boundary("mcore_set_redraw_callback", || {
+        trace_call!("mcore_set_redraw_callback", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_redraw_callback", "ctx");
+        };
 
-#[repr(C)]
-#[derive(Copy, Clone)]
-pub struct McoreTextEvent {
-    pub kind: McoreTextEventKind,
-    pub char_code: u32,
-    pub direction: McoreCursorDirection,
-    pub extend_selection: u8,
-    pub cursor_position: i32,
-    pub text_ptr: *const i8,
+        let mut guard = ctx.0.lock();
+        guard.redraw_callback = Some(callback);
+    })
 }
 
-/// Handle a text input event for a specific widget ID
-/// Returns true if the text changed
+/// Request a frame at (or after) an absolute time
 #[no_mangle]
-pub extern "C" fn mcore_text_input_event(
-    ctx: *mut McoreContext,
-    id: u64,
-    event: *const McoreTextEvent,
-) -> u8 {
-    let ctx = unsafe { ctx.as_mut() };
-    let event = unsafe { event.as_ref() };
-
-    if ctx.is_none() || event.is_none() {
-        return 0;
-    }
-
-    let ctx = ctx.unwrap();
-    let event = event.unwrap();
-    let mut guard = ctx.0.lock();
-
-    let state = guard.text_inputs.get_or_create(id);
+pub extern "C" fn mcore_request_frame_at(ctx: *mut McoreContext, time_seconds: f64) {
+    ffi_boundary("mcore_request_frame_at", || {
+        trace_call!("mcore_request_frame_at", ctx, time_seconds);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_request_frame_at", "ctx");
+        };
 
-    match event.kind {
-        McoreTextEventKind::InsertChar => {
-            if let Some(ch) = char::from_u32(event.char_code) {
-                state.insert_char(ch);
-                return 1;
-            }
-        }
-        McoreTextEventKind::Backspace => {
-            state.backspace();
-            return 1;
-        }
-        McoreTextEventKind::Delete => {
-            state.delete();
-            return 1;
-        }
-        McoreTextEventKind::MoveCursor => {
-            match event.direction {
-                McoreCursorDirection::Left => state.move_cursor_left(),
-                McoreCursorDirection::Right => state.move_cursor_right(),
-                McoreCursorDirection::Home => state.move_cursor_home(),
-                McoreCursorDirection::End => state.move_cursor_end(),
-            }
-            return 0;  // Cursor movement doesn't change text
-        }
-        McoreTextEventKind::SetCursor => {
-            state.set_cursor(event.cursor_position.max(0) as usize);
-            return 0;  // Cursor movement doesn't change text
-        }
-        McoreTextEventKind::InsertText => {
-            if !event.text_ptr.is_null() {
-                let text = unsafe { CStr::from_ptr(event.text_ptr) }
-                    .to_str()
-                    .unwrap_or("");
-                state.insert_text(text);
-                return 1;
-            }
-        }
-    }
+        let redraw = {
+            let mut guard = ctx.0.lock();
+            guard.scheduler.request_at(time_seconds);
+            guard.take_redraw()
+        };
 
-    0
+        fire_redraw(redraw);
+    })
 }
 
-/// Get the current text content for a widget ID
-/// Returns the number of bytes written (excluding null terminator)
+/// Request a frame as soon as possible
 #[no_mangle]
-pub extern "C" fn mcore_text_input_get(
-    ctx: *mut McoreContext,
-    id: u64,
-    buf: *mut u8,
-    buf_len: i32,
-) -> i32 {
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || buf.is_null() || buf_len <= 0 {
-        return 0;
-    }
+pub extern "C" fn mcore_request_redraw(ctx: *mut McoreContext) {
+    ffi_boundary("mcore_request_redraw", || {
+        trace_call!("mcore_request_redraw", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_request_redraw", "ctx");
+        };
 
-    let ctx = ctx.unwrap();
-    let guard = ctx.0.lock();
+        let redraw = {
+            let mut guard = ctx.0.lock();
+            let now = guard.time_s;
+            guard.scheduler.request_at(now);
+            guard.take_redraw()
+        };
 
-    if let Some(state) = guard.text_inputs.get(id) {
-        let content_bytes = state.content.as_bytes();
-        let copy_len = content_bytes.len().min((buf_len - 1) as usize);
+        fire_redraw(redraw);
+    })
+}
 
-        unsafe {
-            std::ptr::copy_nonoverlapping(content_bytes.as_ptr(), buf, copy_len);
-            *buf.add(copy_len) = 0;  // Null terminate
-        }
+/// Earliest time a frame is needed, or -1 if the engine is idle (for hosts that poll)
+#[no_mangle]
+pub extern "C" fn mcore_next_frame_time(ctx: *mut McoreContext) -> f64 {
+    ffi_boundary("mcore_next_frame_time", || {
+        trace_call!("mcore_next_frame_time", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_next_frame_time", "ctx");
+            return -1.0;
+        };
 
-        copy_len as i32
-    } else {
-        // No state yet, return empty string
-        unsafe {
-            *buf = 0;
-        }
-        0
-    }
+        let guard = ctx.0.lock();
+        guard.scheduler.deadline().unwrap_or(-1.0)
+    })
 }
 
-/// Get the cursor position (byte offset) for a widget ID
+/// Report the refresh rate of the display showing the surface (Hz), e.g. when
+/// the window moves between monitors or ProMotion switches between 120 and 60.
+/// Engine animations step at the new rate and the refresh rate callback fires
+/// if it changed.
 #[no_mangle]
-pub extern "C" fn mcore_text_input_cursor(
-    ctx: *mut McoreContext,
-    id: u64,
-) -> i32 {
-    let ctx = unsafe { ctx.as_mut() };
+pub extern "C" fn mcore_set_refresh_rate(ctx: *mut McoreContext, hz: f32) {
+    ffi_boundary("mcore_set_refresh_rate", || {
+        trace_call!("mcore_set_refresh_rate", ctx, hz);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_refresh_rate", "ctx");
+        };
+        if !(hz.is_finite() && hz > 0.0) {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_set_refresh_rate");
+            return;
+        }
 
-    if ctx.is_none() {
-        return 0;
-    }
+        let callback = {
+            let mut guard = ctx.0.lock();
+            if guard.refresh_rate == hz {
+                return;
+            }
+            guard.refresh_rate = hz;
+            guard.scrolls.set_frame_interval(1.0 / hz);
+            guard.refresh_rate_callback
+        };
+
+        if let Some(callback) = callback {
+            callback(hz);
+        }
+    })
+}
 
-    let ctx = ctx.unwrap();
-    let guard = ctx.0.lock();
+/// Refresh rate of the current display (Hz), 60 until the host reports one
+#[no_mangle]
+pub extern "C" fn mcore_get_refresh_rate(ctx: *mut McoreContext) -> f32 {
+    ffi_boundary("mcore_get_refresh_rate", || {
+        trace_call!("mcore_get_refresh_rate", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_get_refresh_rate", "ctx");
+            return DEFAULT_REFRESH_RATE;
+        };
 
-    guard.text_inputs
-        .get(id)
-        .map(|s| s.cursor as i32)
-        .unwrap_or(0)
+        let guard = ctx.0.lock();
+        guard.refresh_rate
+    })
 }
 
-/// Set the text content for a widget ID
+/// Set the callback told when the refresh rate changes, so host tickers can
+/// adapt their step size. Callback signature: callback(hz); runs without the
+/// engine lock held.
 #[no_mangle]
-pub extern "C" fn mcore_text_input_set(
-    ctx: *mut McoreContext,
-    id: u64,
-    text: *const i8,
-) {
-    let ctx = unsafe { ctx.as_mut() };
+pub extern "C" fn mcore_set_refresh_rate_callback(ctx: *mut McoreContext, callback: extern "C" fn(f32)) {
+    ffi_boundary("mcore_set_refresh_rate_callback", || {
+        trace_call!("mcore_set_refresh_rate_callback", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_refresh_rate_callback", "ctx");
+        };
 
-    if ctx.is_none() || text.is_null() {
-        return;
-    }
+        let mut guard = ctx.0.lock();
+        guard.refresh_rate_callback = Some(callback);
+    })
+}
+
+/// Tell the engine whether the window is fully occluded or minimized. While
+/// occluded, mcore_end_frame_present skips rendering and presenting (state
+/// updates and frame building still work) and animations stop requesting
+/// frames. Becoming visible requests a frame with the up-to-date state.
+#[no_mangle]
+pub extern "C" fn mcore_set_occluded(ctx: *mut McoreContext, occluded: u8) {
+    ffi_boundary("mcore_set_occluded", || {
+        trace_call!("mcore_set_occluded", ctx, occluded);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_set_occluded", "ctx");
+        };
 
-    let ctx = ctx.unwrap();
-    let text_str = unsafe { CStr::from_ptr(text) }
-        .to_str()
-        .unwrap_or("");
+        let redraw = {
+            let mut guard = ctx.0.lock();
+            let was_occluded = guard.occluded;
+            guard.occluded = occluded != 0;
+            if was_occluded && !guard.occluded {
+                let now = guard.time_s;
+                guard.scheduler.request_at(now);
+            }
+            guard.take_redraw()
+        };
 
-    let mut guard = ctx.0.lock();
-    let state = guard.text_inputs.get_or_create(id);
-    state.set_text(text_str);
+        fire_redraw(redraw);
+    })
 }
 
-/// Get selection range for a text input widget
-/// Returns true if there is a selection, and fills out_start and out_end with the byte offsets
 #[no_mangle]
-pub extern "C" fn mcore_text_input_get_selection(
-    ctx: *mut McoreContext,
-    id: u64,
-    out_start: *mut i32,
-    out_end: *mut i32,
-) -> u8 {
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || out_start.is_null() || out_end.is_null() {
-        return 0;
-    }
+pub extern "C" fn mcore_is_occluded(ctx: *mut McoreContext) -> u8 {
+    ffi_boundary("mcore_is_occluded", || {
+        trace_call!("mcore_is_occluded", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_is_occluded", "ctx");
+        };
 
-    let ctx = ctx.unwrap();
-    let guard = ctx.0.lock();
+        let guard = ctx.0.lock();
+        guard.occluded as u8
+    })
+}
 
-    if let Some(state) = guard.text_inputs.get(id) {
-        if let Some(sel) = state.get_selection() {
-            unsafe {
-                *out_start = sel.start as i32;
-                *out_end = sel.end as i32;
-            }
-            return 1;
-        }
-    }
+// ============================================================================
+// Custom Texture Widgets FFI
+// ============================================================================
 
-    0
+/// Passed to a custom texture callback each frame
+#[repr(C)]
+pub struct McoreTextureTarget {
+    /// Native texture (id<MTLTexture> on macOS), RGBA8 unorm, premultiplied alpha
+    pub native_texture: *mut c_void,
+    pub width: u32,
+    pub height: u32,
+    pub time_seconds: f64,
 }
 
-/// Set cursor position and optionally start a selection
-#[no_mangle]
-pub extern "C" fn mcore_text_input_set_cursor_pos(
-    ctx: *mut McoreContext,
-    id: u64,
-    byte_offset: i32,
-    extend_selection: u8,
-) {
-    let ctx = unsafe { ctx.as_mut() };
+type McoreTextureCallback = extern "C" fn(*mut c_void, *const McoreTextureTarget);
 
-    if ctx.is_none() || byte_offset < 0 {
-        return;
-    }
+/// Adapts a C callback to the engine's renderer trait
+struct FfiTextureRenderer {
+    callback: McoreTextureCallback,
+    user_data: *mut c_void,
+}
 
-    let ctx = ctx.unwrap();
-    let mut guard = ctx.0.lock();
-    let state = guard.text_inputs.get_or_create(id);
+// SAFETY: the host promises user_data may be used from the rendering thread
+unsafe impl Send for FfiTextureRenderer {}
 
-    if extend_selection != 0 {
-        // Extend or create selection
-        state.extend_selection_to(byte_offset as usize);
-    } else {
-        // Just move cursor, clear selection AND anchor
-        state.set_cursor(byte_offset as usize);
-        state.clear_selection();
-        state.selection_anchor = None;
+impl custom::TextureRenderer for FfiTextureRenderer {
+    fn render(&mut self, target: &custom::RenderTarget<'_>) {
+        let ffi_target = McoreTextureTarget {
+            native_texture: custom::native_texture_handle(target.texture),
+            width: target.width,
+            height: target.height,
+            time_seconds: target.time,
+        };
+        (self.callback)(self.user_data, &ffi_target);
     }
 }
 
-/// Get the selected text (returns length, copies into buffer)
+/// Create a texture-backed widget of the given physical size.
+/// `callback(user_data, target)` runs inside mcore_end_frame_present with the
+/// engine lock held: render into target->native_texture, commit your command
+/// buffer (waitUntilScheduled) and return without calling back into the engine.
+/// Returns a widget ID (>= 0) or -1 on error.
 #[no_mangle]
-pub extern "C" fn mcore_text_input_get_selected_text(
+pub extern "C" fn mcore_custom_texture_create(
     ctx: *mut McoreContext,
-    id: u64,
-    buf: *mut i8,
-    buf_len: i32,
+    width_px: u32,
+    height_px: u32,
+    callback: McoreTextureCallback,
+    user_data: *mut c_void,
 ) -> i32 {
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || buf.is_null() || buf_len <= 0 {
-        eprintln!("get_selected_text: early return (null check)");
-        return 0;
-    }
+    ffi_boundary("mcore_custom_texture_create", || {
+        trace_call!("mcore_custom_texture_create", ctx, width_px, height_px, user_data);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_custom_texture_create", "ctx");
+        };
 
-    let ctx = ctx.unwrap();
-    let guard = ctx.0.lock();
+        let mut guard = ctx.0.lock();
+        let engine = &mut *guard;
+
+        let renderer = Box::new(FfiTextureRenderer { callback, user_data });
+        match engine.custom_textures.create(&engine.gfx, width_px, height_px, renderer) {
+            Ok(id) => id,
+            Err(e) => {
+                set_err(e);
+                -1
+            }
+        }
+    })
+}
 
-    eprintln!("get_selected_text: id={}", id);
+/// Recreate a widget's texture at a new physical size
+#[no_mangle]
+pub extern "C" fn mcore_custom_texture_resize(ctx: *mut McoreContext, id: i32, width_px: u32, height_px: u32) -> McoreStatus {
+    ffi_boundary("mcore_custom_texture_resize", || {
+        trace_call!("mcore_custom_texture_resize", ctx, id, width_px, height_px);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_custom_texture_resize", "ctx");
+        };
 
-    if let Some(state) = guard.text_inputs.get(id) {
-        eprintln!("  Found state: cursor={}, anchor={:?}, selection={:?}",
-            state.cursor, state.selection_anchor, state.selection);
+        let mut guard = ctx.0.lock();
+        let engine = &mut *guard;
 
-        if let Some(selected) = state.get_selection_text() {
-            let bytes = selected.as_bytes();
-            let copy_len = bytes.len().min((buf_len - 1) as usize);
-            eprintln!("  Copying {} bytes: {:?}", copy_len, selected);
-            unsafe {
-                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, copy_len);
-                *buf.add(copy_len) = 0; // Null terminate
+        match engine.custom_textures.resize(&mut engine.gfx, id, width_px, height_px) {
+            Ok(()) => McoreStatus::Ok,
+            Err(e) => {
+                set_err(e);
+                McoreStatus::Err
             }
-            return copy_len as i32;
-        } else {
-            eprintln!("  No selection text");
         }
-    } else {
-        eprintln!("  State not found for id={}", id);
-    }
+    })
+}
 
-    0
+#[no_mangle]
+pub extern "C" fn mcore_custom_texture_destroy(ctx: *mut McoreContext, id: i32) {
+    ffi_boundary("mcore_custom_texture_destroy", || {
+        trace_call!("mcore_custom_texture_destroy", ctx, id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_custom_texture_destroy", "ctx");
+        };
+
+        let mut guard = ctx.0.lock();
+        let engine = &mut *guard;
+        engine.custom_textures.remove(&mut engine.gfx, id);
+    })
 }
 
-/// Start a selection at a specific position (for mouse down)
-/// Sets both cursor and anchor to the same position, clearing any existing selection
+/// Composite a widget's texture into a logical-pixel rectangle
 #[no_mangle]
-pub extern "C" fn mcore_text_input_start_selection(
+pub extern "C" fn mcore_custom_texture_draw(
     ctx: *mut McoreContext,
-    id: u64,
-    byte_offset: i32,
+    id: i32,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
 ) {
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || byte_offset < 0 {
-        return;
-    }
+    ffi_boundary("mcore_custom_texture_draw", || {
+        trace_call!("mcore_custom_texture_draw", ctx, id, x, y, width, height);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_custom_texture_draw", "ctx");
+        };
 
-    let ctx = ctx.unwrap();
-    let mut guard = ctx.0.lock();
-    let state = guard.text_inputs.get_or_create(id);
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_custom_texture_draw") {
+            return;
+        }
 
-    eprintln!("start_selection: id={}, byte_offset={}", id, byte_offset);
+        let Some(image) = guard.custom_textures.image(id).cloned() else {
+            return;
+        };
 
-    // Set cursor and anchor to the same position, clear selection
-    state.set_cursor(byte_offset as usize);
-    state.selection_anchor = Some(byte_offset as usize);
-    state.selection = None;
+        use peniko::kurbo::Affine;
+        let dpi_scale = guard.gfx.scale();
+        let affine = Affine::scale_non_uniform(
+            (width * dpi_scale / image.width as f32) as f64,
+            (height * dpi_scale / image.height as f32) as f64,
+        )
+        .then_translate(((x * dpi_scale) as f64, (y * dpi_scale) as f64).into());
 
-    eprintln!("  cursor={}, anchor={:?}, selection={:?}", state.cursor, state.selection_anchor, state.selection);
+        let brush = peniko::ImageBrush::from(image);
+        guard.scene.draw_image(&brush, affine);
+    })
 }
 
-// ========== IME (Input Method Editor) Support ==========
+// ============================================================================
+// Video FFI
+// ============================================================================
 
+/// A decoded video frame: pixels in memory, or an IOSurface
 #[repr(C)]
-pub struct McoreImePreedit {
-    pub text: *const i8,
-    pub cursor_offset: i32,
+#[derive(Copy, Clone)]
+pub struct McoreVideoFrame {
+    /// MCORE_VIDEO_FORMAT_*; ignored for IOSurfaces, whose own format is used
+    pub format: u32,
+    pub width: u32,
+    pub height: u32,
+    /// MCORE_VIDEO_MATRIX_*, for NV12
+    pub matrix: u32,
+    /// NV12 only: non-zero if luma and chroma span 0-255 rather than
+    /// 16-235 / 16-240; ignored for IOSurfaces
+    pub full_range: u32,
+    pub _reserved: u32,
+    /// BGRA: the pixels. NV12: the luma plane, then interleaved Cb, Cr.
+    /// Each plane holds stride * rows bytes.
+    pub planes: [*const u8; 2],
+    /// Bytes per row of each plane
+    pub strides: [u32; 2],
+    /// IOSurfaceRef (e.g. from CVPixelBufferGetIOSurface); when set, the
+    /// frame is read from it and `planes` are ignored
+    pub iosurface: *mut c_void,
 }
 
-/// Set IME preedit (composition) text for a text input
+/// Create a video to submit frames to. It draws nothing until the first
+/// frame arrives. Returns a video ID (>= 0) or -1 on error.
 #[no_mangle]
-pub extern "C" fn mcore_ime_set_preedit(
-    ctx: *mut McoreContext,
-    id: u64,
-    preedit: *const McoreImePreedit,
-) {
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || preedit.is_null() {
-        return;
-    }
-
-    let ctx = ctx.unwrap();
-    let preedit = unsafe { preedit.as_ref() }.unwrap();
-
-    let text = if preedit.text.is_null() {
-        ""
-    } else {
-        unsafe { CStr::from_ptr(preedit.text) }
-            .to_str()
-            .unwrap_or("")
-    };
-
-    let mut guard = ctx.0.lock();
-    let state = guard.text_inputs.get_or_create(id);
-
-    if text.is_empty() {
-        // Clear preedit
-        state.ime_composition = None;
-    } else {
-        // Set preedit
-        state.ime_composition = Some(crate::text_input::ImeComposition {
-            text: text.to_string(),
-            cursor_offset: preedit.cursor_offset.max(0) as usize,
-        });
-    }
+pub extern "C" fn mcore_video_create(ctx: *mut McoreContext) -> i32 {
+    ffi_boundary("mcore_video_create", || {
+        trace_call!("mcore_video_create", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_video_create", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        let engine = &mut *guard;
+        match engine.videos.create(&mut engine.custom_textures, &engine.gfx) {
+            Ok(id) => id,
+            Err(e) => {
+                set_err(e);
+                -1
+            }
+        }
+    })
 }
 
-/// Commit IME text (finalize composition)
+/// Make `frame` the one the video shows from the next rendered frame. The
+/// pixels are copied, so the host's buffer can be reused once this returns.
+/// Can be called from any thread (e.g. a display link callback).
 #[no_mangle]
-pub extern "C" fn mcore_ime_commit(
-    ctx: *mut McoreContext,
-    id: u64,
-    text: *const i8,
-) {
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || text.is_null() {
-        return;
-    }
-
-    let ctx = ctx.unwrap();
-    let text_str = unsafe { CStr::from_ptr(text) }
-        .to_str()
-        .unwrap_or("");
-
-    let mut guard = ctx.0.lock();
-    let state = guard.text_inputs.get_or_create(id);
+pub extern "C" fn mcore_video_frame_submit(ctx: *mut McoreContext, video_id: i32, frame: *const McoreVideoFrame) -> McoreStatus {
+    ffi_boundary("mcore_video_frame_submit", || {
+        trace_call!("mcore_video_frame_submit", ctx, video_id, frame);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_video_frame_submit", "ctx");
+        };
+        let Some(desc) = (unsafe { frame.as_ref() }) else {
+            return null_arg("mcore_video_frame_submit", "frame");
+        };
+        let matrix = match desc.matrix {
+            1 => video::Matrix::Bt601,
+            _ => video::Matrix::Bt709,
+        };
+        let frame = if !desc.iosurface.is_null() {
+            if !cfg!(target_os = "macos") {
+                set_err_code(McoreErrorCode::Unsupported, "IOSurface frames are only supported on macOS");
+                return McoreStatus::Err;
+            }
+            video::Frame::from_iosurface(desc.iosurface, matrix)
+        } else {
+            let format = match desc.format {
+                0 => video::PixelFormat::Bgra8,
+                1 => video::PixelFormat::Nv12,
+                other => {
+                    set_err_code(McoreErrorCode::InvalidArg, format!("Unknown video format {other}"));
+                    return McoreStatus::Err;
+                }
+            };
+            let mut planes = Vec::new();
+            for (i, &(_, rows)) in video::plane_sizes(format, desc.width, desc.height).iter().enumerate() {
+                let stride = desc.strides[i] as usize;
+                let Some(bytes) = host_slice(desc.planes[i], stride * rows) else {
+                    return null_arg("mcore_video_frame_submit", "frame->planes");
+                };
+                planes.push((bytes, stride));
+            }
+            video::Frame::from_planes(format, desc.width, desc.height, &planes, matrix, desc.full_range != 0)
+        };
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                set_err_code(McoreErrorCode::InvalidArg, e);
+                return McoreStatus::Err;
+            }
+        };
 
-    // Clear any existing preedit
-    state.ime_composition = None;
+        let mut guard = ctx.0.lock();
+        let engine = &mut *guard;
+        if let Err(e) = engine.videos.submit(&mut engine.custom_textures, &mut engine.gfx, video_id, frame) {
+            set_err_code(McoreErrorCode::NotFound, e);
+            return McoreStatus::Err;
+        }
+        guard.damage.invalidate();
+        McoreStatus::Ok
+    })
+}
 
-    // Insert the committed text
-    state.insert_text(text_str);
+/// Draw a video's latest frame into a logical-pixel rect, fitted per
+/// MCORE_VIDEO_FIT_*, like an MCORE_CMD_VIDEO record
+#[no_mangle]
+pub extern "C" fn mcore_video_draw(ctx: *mut McoreContext, video_id: i32, x: f32, y: f32, width: f32, height: f32, fit: u32) {
+    ffi_boundary("mcore_video_draw", || {
+        trace_call!("mcore_video_draw", ctx, video_id, x, y, width, height, fit);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_video_draw", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_video_draw") {
+            return;
+        }
+        let eng = &mut *guard;
+        let transform = peniko::kurbo::Affine::scale(eng.gfx.scale() as f64);
+        let cmd = McoreCmdVideo { x, y, width, height, video_id, fit };
+        video::draw(&mut eng.scene, &eng.custom_textures, &eng.videos, video_id, transform, cmd.rect(), cmd.fit_mode());
+    })
 }
 
-/// Clear IME preedit state
 #[no_mangle]
-pub extern "C" fn mcore_ime_clear_preedit(
-    ctx: *mut McoreContext,
-    id: u64,
-) {
-    let ctx = unsafe { ctx.as_mut() };
+pub extern "C" fn mcore_video_destroy(ctx: *mut McoreContext, video_id: i32) {
+    ffi_boundary("mcore_video_destroy", || {
+        trace_call!("mcore_video_destroy", ctx, video_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_video_destroy", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        let engine = &mut *guard;
+        if !engine.videos.destroy(&mut engine.custom_textures, &mut engine.gfx, video_id) {
+            set_err_code(McoreErrorCode::NotFound, format!("Video {video_id} not found"));
+        }
+    })
+}
 
-    if ctx.is_none() {
-        return;
-    }
+// ============================================================================
+// Particles FFI
+// ============================================================================
 
-    let ctx = ctx.unwrap();
-    let mut guard = ctx.0.lock();
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum McoreParticleLayer {
+    Beneath = 0,
+    Above = 1,
+}
 
-    if let Some(state) = guard.text_inputs.get_mut(id) {
-        state.ime_composition = None;
-    }
+/// Particle emitter description (logical pixels, seconds, radians)
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreParticleEmitter {
+    pub x: f32,
+    pub y: f32,
+    pub count: u32,
+    pub lifetime: f32,
+    pub speed_min: f32,
+    pub speed_max: f32,
+    /// 0 = right, PI/2 = down
+    pub direction: f32,
+    pub spread: f32,
+    /// Logical px/s^2, positive = down
+    pub gravity: f32,
+    pub size: f32,
+    /// Color ramp over each particle's life
+    pub colors: [McoreRgba; 4],
+    pub looping: u8,
+    pub layer: McoreParticleLayer,
 }
 
-/// Get IME preedit text if any
-/// Returns 1 if there is preedit text, 0 otherwise
+/// Start a particle emitter. Bursts remove themselves once every particle has
+/// died; looping emitters run until mcore_particles_stop. With reduce motion
+/// on, bursts are dropped and looping emitters wait until it's off.
+/// Returns an emitter ID (>= 0) or -1 on error.
 #[no_mangle]
-pub extern "C" fn mcore_ime_get_preedit(
-    ctx: *mut McoreContext,
-    id: u64,
-    buf: *mut i8,
-    buf_len: i32,
-    out_cursor_offset: *mut i32,
-) -> u8 {
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || buf.is_null() || buf_len <= 0 {
-        return 0;
-    }
-
-    let ctx = ctx.unwrap();
-    let guard = ctx.0.lock();
-
-    if let Some(state) = guard.text_inputs.get(id) {
-        if let Some(composition) = &state.ime_composition {
-            let bytes = composition.text.as_bytes();
-            let copy_len = bytes.len().min((buf_len - 1) as usize);
+pub extern "C" fn mcore_particles_emit(ctx: *mut McoreContext, desc: *const McoreParticleEmitter) -> i32 {
+    ffi_boundary("mcore_particles_emit", || {
+        trace_call!("mcore_particles_emit", ctx, desc);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_particles_emit", "ctx");
+        };
+        let Some(desc) = (unsafe { desc.as_ref() }) else {
+            return null_arg("mcore_particles_emit", "desc");
+        };
 
-            unsafe {
-                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, copy_len);
-                *buf.add(copy_len) = 0; // Null terminate
+        let redraw = {
+            let mut guard = ctx.0.lock();
+
+            // Simulation runs in physical pixels
+            let scale = guard.gfx.scale();
+            let config = particles::EmitterConfig {
+                x: desc.x * scale,
+                y: desc.y * scale,
+                count: desc.count,
+                lifetime: desc.lifetime,
+                speed_min: desc.speed_min * scale,
+                speed_max: desc.speed_max * scale,
+                direction: desc.direction,
+                spread: desc.spread,
+                gravity: desc.gravity * scale,
+                size: desc.size * scale,
+                colors: desc.colors.map(|c| [c.r, c.g, c.b, c.a]),
+                looping: desc.looping != 0,
+                layer: match desc.layer {
+                    McoreParticleLayer::Beneath => particles::ParticleLayer::Beneath,
+                    McoreParticleLayer::Above => particles::ParticleLayer::Above,
+                },
+            };
 
-                if !out_cursor_offset.is_null() {
-                    *out_cursor_offset = composition.cursor_offset as i32;
+            let engine = &mut *guard;
+            let device = engine.gfx.device().clone();
+            match engine.gfx.particles_mut().add_emitter(&device, config) {
+                Ok(id) => {
+                    engine.schedule_animations();
+                    Ok((id, engine.take_redraw()))
                 }
+                Err(e) => Err(e),
             }
+        };
 
-            return 1;
+        match redraw {
+            Ok((id, redraw)) => {
+                fire_redraw(redraw);
+                id
+            }
+            Err(e) => {
+                set_err(e);
+                -1
+            }
         }
-    }
+    })
+}
 
-    // No preedit text
-    if !buf.is_null() && buf_len > 0 {
-        unsafe {
-            *buf = 0; // Null terminate empty string
-        }
-    }
+/// Stop an emitter immediately
+#[no_mangle]
+pub extern "C" fn mcore_particles_stop(ctx: *mut McoreContext, id: i32) {
+    ffi_boundary("mcore_particles_stop", || {
+        trace_call!("mcore_particles_stop", ctx, id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_particles_stop", "ctx");
+        };
 
-    0
+        let mut guard = ctx.0.lock();
+        guard.gfx.particles_mut().remove_emitter(id);
+    })
 }
 
 // ============================================================================
@@ -1127,19 +7677,30 @@ pub extern "C" fn mcore_a11y_init(
     ctx: *mut McoreContext,
     ns_view: *mut c_void,
 ) {
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || ns_view.is_null() {
-        return;
-    }
+    ffi_boundary("mcore_a11y_init", || {
+        trace_call!("mcore_a11y_init", ctx, ns_view);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_a11y_init", "ctx");
+        };
+        if ns_view.is_null() {
+            return null_arg("mcore_a11y_init", "ns_view");
+        }
 
-    let ctx = ctx.unwrap();
-    let mut guard = ctx.0.lock();
+        #[cfg(feature = "a11y")]
+        {
+            let mut guard = ctx.0.lock();
 
-    // Create the accessibility adapter
-    unsafe {
-        guard.a11y = Some(a11y::AccessibilityAdapter::new(ns_view));
-    }
+            // Create the accessibility adapter
+            unsafe {
+                guard.a11y = Some(a11y::AccessibilityAdapter::new(ns_view));
+            }
+        }
+        #[cfg(not(feature = "a11y"))]
+        {
+            let _ = ctx;
+            set_err_code(McoreErrorCode::Unsupported, "Accessibility support not compiled in (enable the a11y feature)");
+        }
+    })
 }
 
 /// Represents a single accessibility node sent from Zig
@@ -1155,6 +7716,8 @@ pub struct McoreA11yNode {
     pub value: *const i8,
     pub text_selection_start: i32,
     pub text_selection_end: i32,
+    /// MCORE_A11Y_STATE_* flags
+    pub states: u32,
 }
 
 #[repr(C)]
@@ -1175,110 +7738,135 @@ pub extern "C" fn mcore_a11y_update(
     root_id: u64,
     focus_id: u64,
 ) {
-    use accesskit::{Action, NodeId, Node, Role, Rect, Tree, TreeUpdate};
-
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || nodes.is_null() || node_count <= 0 {
-        return;
-    }
-
-    let ctx = ctx.unwrap();
-    let guard = ctx.0.lock();
+    ffi_boundary("mcore_a11y_update", || {
+        trace_call!("mcore_a11y_update", ctx, nodes, node_count, root_id, focus_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_a11y_update", "ctx");
+        };
 
-    // Convert C nodes to AccessKit nodes
-    let nodes_slice = unsafe { std::slice::from_raw_parts(nodes, node_count as usize) };
+        if nodes.is_null() || node_count <= 0 {
+            set_err_code(McoreErrorCode::InvalidArg, "Invalid arguments passed to mcore_a11y_update");
+            return;
+        }
 
-    let mut ak_nodes = Vec::new();
+        let nodes_slice = unsafe { std::slice::from_raw_parts(nodes, node_count as usize) };
 
-    for c_node in nodes_slice {
-        let node_id = NodeId(c_node.id);
+        #[cfg(feature = "a11y")]
+        {
+            let tree_update = a11y::tree_update(nodes_slice, root_id, focus_id);
+            if let Some(a11y) = &ctx.0.lock().a11y {
+                a11y.update_tree(tree_update);
+            }
+        }
+        #[cfg(not(feature = "a11y"))]
+        let _ = (ctx, nodes_slice, root_id, focus_id);
+    })
+}
 
-        // Map role
-        let role = match c_node.role {
-            0 => Role::Window,
-            1 => Role::Button,
-            2 => Role::TextInput,
-            3 => Role::Label,
-            4 => Role::Group,
-            _ => Role::Unknown,
+/// Move accessibility focus to a node of the last tree (e.g. after keyboard
+/// navigation) without resending the tree
+#[no_mangle]
+pub extern "C" fn mcore_a11y_set_focus(ctx: *mut McoreContext, focus_id: u64) {
+    ffi_boundary("mcore_a11y_set_focus", || {
+        trace_call!("mcore_a11y_set_focus", ctx, focus_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_a11y_set_focus", "ctx");
         };
 
-        let mut node = Node::new(role);
-
-        // Set label
-        if !c_node.label.is_null() {
-            let label = unsafe { CStr::from_ptr(c_node.label) }
-                .to_str()
-                .unwrap_or("");
-            if !label.is_empty() {
-                node.set_label(label.to_string());
-            }
+        #[cfg(feature = "a11y")]
+        match &ctx.0.lock().a11y {
+            Some(a11y) => a11y.update_focus(accesskit::NodeId(focus_id)),
+            None => set_err_code(McoreErrorCode::InvalidState, "mcore_a11y_set_focus called before mcore_a11y_init"),
         }
-
-        // Set value (for text inputs)
-        if !c_node.value.is_null() {
-            let value = unsafe { CStr::from_ptr(c_node.value) }
-                .to_str()
-                .unwrap_or("");
-            if !value.is_empty() {
-                node.set_value(value.to_string());
-            }
+        #[cfg(not(feature = "a11y"))]
+        {
+            let _ = (ctx, focus_id);
+            set_err_code(McoreErrorCode::Unsupported, "Accessibility support not compiled in (enable the a11y feature)");
         }
+    })
+}
 
-        // Set bounds
-        node.set_bounds(Rect {
-            x0: c_node.bounds.x as f64,
-            y0: c_node.bounds.y as f64,
-            x1: (c_node.bounds.x + c_node.bounds.width) as f64,
-            y1: (c_node.bounds.y + c_node.bounds.height) as f64,
-        });
+/// Set callback for accessibility actions (focus, click, etc.)
+#[no_mangle]
+pub extern "C" fn mcore_a11y_set_action_callback(
+    callback: extern "C" fn(u64, u8),
+) {
+    ffi_boundary("mcore_a11y_set_action_callback", || {
+        trace_call!("mcore_a11y_set_action_callback");
+        #[cfg(feature = "a11y")]
+        a11y::set_action_callback(callback);
+        #[cfg(not(feature = "a11y"))]
+        let _ = callback;
+    })
+}
+
+/// System accessibility preferences
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct McoreA11yPrefs {
+    pub reduce_motion: u8,
+    pub increase_contrast: u8,
+    pub reduce_transparency: u8,
+}
 
-        // Set children
-        if !c_node.children.is_null() && c_node.children_count > 0 {
-            let children = unsafe {
-                std::slice::from_raw_parts(c_node.children, c_node.children_count as usize)
-            };
-            let child_ids: Vec<NodeId> = children.iter().map(|&id| NodeId(id)).collect();
-            node.set_children(child_ids);
-        }
+/// Re-query the OS accessibility preferences and return them
+/// Call when the app is notified of a change (e.g. on
+/// NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification)
+#[no_mangle]
+pub extern "C" fn mcore_a11y_get_preferences(ctx: *mut McoreContext, out: *mut McoreA11yPrefs) {
+    ffi_boundary("mcore_a11y_get_preferences", || {
+        trace_call!("mcore_a11y_get_preferences", ctx, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_a11y_get_preferences", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_a11y_get_preferences", "out");
+        };
 
-        // Set actions (bitfield)
-        if c_node.actions & 0x01 != 0 {  // Focus
-            node.add_action(Action::Focus);
-        }
-        if c_node.actions & 0x02 != 0 {  // Click
-            node.add_action(Action::Click);
-        }
+        let mut guard = ctx.0.lock();
 
-        // TODO: Set text selection for text inputs
-        // Text selection in AccessKit is more complex than just byte offsets
-        // It requires TextPosition with node IDs and character indices
-        // We'll implement this properly later when we have text run nodes
-        let _ = (c_node.text_selection_start, c_node.text_selection_end);
+        guard.prefs = prefs::SystemPrefs::query();
+        out.reduce_motion = guard.prefs.reduce_motion as u8;
+        out.increase_contrast = guard.prefs.increase_contrast as u8;
+        out.reduce_transparency = guard.prefs.reduce_transparency as u8;
+    })
+}
 
-        ak_nodes.push((node_id, node));
-    }
+/// Override the accessibility preferences (for testing, or platforms the engine can't query)
+#[no_mangle]
+pub extern "C" fn mcore_a11y_set_preferences(ctx: *mut McoreContext, prefs: *const McoreA11yPrefs) {
+    ffi_boundary("mcore_a11y_set_preferences", || {
+        trace_call!("mcore_a11y_set_preferences", ctx, prefs);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_a11y_set_preferences", "ctx");
+        };
+        let Some(prefs) = (unsafe { prefs.as_ref() }) else {
+            return null_arg("mcore_a11y_set_preferences", "prefs");
+        };
 
-    // Build the tree update
-    let tree_update = TreeUpdate {
-        nodes: ak_nodes,
-        tree: Some(Tree::new(NodeId(root_id))),
-        focus: NodeId(focus_id),
-    };
+        let mut guard = ctx.0.lock();
 
-    // Send to adapter
-    if let Some(a11y) = &guard.a11y {
-        a11y.update_tree(tree_update);
-    }
+        guard.prefs = prefs::SystemPrefs {
+            reduce_motion: prefs.reduce_motion != 0,
+            increase_contrast: prefs.increase_contrast != 0,
+            reduce_transparency: prefs.reduce_transparency != 0,
+        };
+    })
 }
 
-/// Set callback for accessibility actions (focus, click, etc.)
+/// Multiplier engine-driven animations apply to their motion
+/// Returns 0.0 when reduce-motion is on (animations jump to their end state), else 1.0
 #[no_mangle]
-pub extern "C" fn mcore_a11y_set_action_callback(
-    callback: extern "C" fn(u64, u8),
-) {
-    a11y::set_action_callback(callback);
+pub extern "C" fn mcore_animation_scale(ctx: *mut McoreContext) -> f32 {
+    ffi_boundary("mcore_animation_scale", || {
+        trace_call!("mcore_animation_scale", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            null_arg::<()>("mcore_animation_scale", "ctx");
+            return 1.0;
+        };
+        let guard = ctx.0.lock();
+        guard.prefs.animation_scale()
+    })
 }
 
 // ============================================================================
@@ -1294,29 +7882,35 @@ pub extern "C" fn mcore_color_parse(
     len: usize,
     out: *mut McoreColor,
 ) -> u8 {
-    let css_bytes = unsafe { std::slice::from_raw_parts(css_str, len) };
-    let css_str = match std::str::from_utf8(css_bytes) {
-        Ok(s) => s,
-        Err(_) => return 0,
-    };
+    ffi_boundary("mcore_color_parse", || {
+        trace_call!("mcore_color_parse", css_str, len, out);
+        let Some(css_bytes) = host_slice(css_str, len) else {
+            return null_arg("mcore_color_parse", "css_str");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_color_parse", "out");
+        };
+        let css_str = match std::str::from_utf8(css_bytes) {
+            Ok(s) => s,
+            Err(_) => return 0,
+        };
 
-    // Parse using color crate's CSS parser
-    let parsed: DynamicColor = match css_str.parse() {
-        Ok(c) => c,
-        Err(_) => return 0,
-    };
+        // Parse using color crate's CSS parser
+        let parsed: DynamicColor = match css_str.parse() {
+            Ok(c) => c,
+            Err(_) => return 0,
+        };
 
-    // Convert to sRGB as AlphaColor
-    let srgb: AlphaColor<Srgb> = parsed.to_alpha_color();
+        // Convert to sRGB as AlphaColor
+        let srgb: AlphaColor<Srgb> = parsed.to_alpha_color();
 
-    // Extract components
-    unsafe {
-        (*out).r = srgb.components[0];
-        (*out).g = srgb.components[1];
-        (*out).b = srgb.components[2];
-        (*out).a = srgb.components[3];
-    }
-    1
+        // Extract components
+        out.r = srgb.components[0];
+        out.g = srgb.components[1];
+        out.b = srgb.components[2];
+        out.a = srgb.components[3];
+        1
+    })
 }
 
 /// Interpolate between two colors using perceptually-correct Oklab space
@@ -1328,37 +7922,108 @@ pub extern "C" fn mcore_color_lerp(
     t: f32,
     out: *mut McoreColor,
 ) {
-    let a = unsafe { &*a };
-    let b = unsafe { &*b };
+    ffi_boundary("mcore_color_lerp", || {
+        trace_call!("mcore_color_lerp", a, b, t, out);
+        let (Some(a), Some(b), Some(out)) = (unsafe { (a.as_ref(), b.as_ref(), out.as_mut()) }) else {
+            return null_arg("mcore_color_lerp", "color");
+        };
 
-    // Create AlphaColor<Srgb> from components
-    let a_srgb = AlphaColor::<Srgb> {
-        components: [a.r, a.g, a.b, a.a],
-        cs: std::marker::PhantomData,
-    };
-    let b_srgb = AlphaColor::<Srgb> {
-        components: [b.r, b.g, b.b, b.a],
-        cs: std::marker::PhantomData,
-    };
+        // Create AlphaColor<Srgb> from components
+        let a_srgb = AlphaColor::<Srgb> {
+            components: [a.r, a.g, a.b, a.a],
+            cs: std::marker::PhantomData,
+        };
+        let b_srgb = AlphaColor::<Srgb> {
+            components: [b.r, b.g, b.b, b.a],
+            cs: std::marker::PhantomData,
+        };
+
+        // Convert to Oklab for perceptually-correct interpolation
+        let a_oklab: AlphaColor<Oklab> = a_srgb.convert();
+        let b_oklab: AlphaColor<Oklab> = b_srgb.convert();
+
+        // Lerp in Oklab space (rectangular interpolation)
+        let result = a_oklab.lerp_rect(b_oklab, t);
+
+        // Convert back to sRGB
+        let result_srgb: AlphaColor<Srgb> = result.convert();
 
-    // Convert to Oklab for perceptually-correct interpolation
-    let a_oklab: AlphaColor<Oklab> = a_srgb.convert();
-    let b_oklab: AlphaColor<Oklab> = b_srgb.convert();
+        out.r = result_srgb.components[0];
+        out.g = result_srgb.components[1];
+        out.b = result_srgb.components[2];
+        out.a = result_srgb.components[3];
+    })
+}
+
+/// Color space tag for color inputs
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum McoreColorSpace {
+    Srgb = 0,
+    DisplayP3 = 1,
+}
 
-    // Lerp in Oklab space (rectangular interpolation)
-    let result = a_oklab.lerp_rect(b_oklab, t);
+impl FailureValue for McoreColorSpace {
+    fn failure() -> Self {
+        McoreColorSpace::Srgb
+    }
+}
 
-    // Convert back to sRGB
-    let result_srgb: AlphaColor<Srgb> = result.convert();
+/// Color spaces cross the FFI as u8: an out-of-range enum value is UB
+impl TryFrom<u8> for McoreColorSpace {
+    type Error = String;
 
-    unsafe {
-        (*out).r = result_srgb.components[0];
-        (*out).g = result_srgb.components[1];
-        (*out).b = result_srgb.components[2];
-        (*out).a = result_srgb.components[3];
+    fn try_from(value: u8) -> Result<Self, String> {
+        match value {
+            0 => Ok(McoreColorSpace::Srgb),
+            1 => Ok(McoreColorSpace::DisplayP3),
+            _ => Err(format!("Unknown color space {value}")),
+        }
     }
 }
 
+/// Convert a color given in `space` to the values a window in `target`
+/// expects (see mcore_get_color_space). Colors already in `target` pass
+/// through unchanged, so wide-gamut P3 values survive in P3 windows;
+/// out-of-gamut results (e.g. saturated P3 reds in sRGB) are clamped.
+#[no_mangle]
+pub extern "C" fn mcore_color_convert(
+    space: u8,
+    target: u8,
+    input: *const McoreColor,
+    out: *mut McoreColor,
+) -> McoreStatus {
+    ffi_boundary("mcore_color_convert", || {
+        trace_call!("mcore_color_convert", space, target, input, out);
+        let (Some(input), Some(out)) = (unsafe { (input.as_ref(), out.as_mut()) }) else {
+            return null_arg("mcore_color_convert", "color");
+        };
+        let (space, target) = match (McoreColorSpace::try_from(space), McoreColorSpace::try_from(target)) {
+            (Ok(space), Ok(target)) => (space, target),
+            (Err(e), _) | (_, Err(e)) => {
+                set_err_code(McoreErrorCode::InvalidArg, e);
+                return McoreStatus::Err;
+            }
+        };
+        if space == target {
+            *out = *input;
+            return McoreStatus::Ok;
+        }
+
+        let components = [input.r, input.g, input.b, input.a];
+        let converted = match target {
+            McoreColorSpace::Srgb => AlphaColor::<DisplayP3>::new(components).convert::<Srgb>().components,
+            McoreColorSpace::DisplayP3 => AlphaColor::<Srgb>::new(components).convert::<DisplayP3>().components,
+        };
+
+        out.r = converted[0].clamp(0.0, 1.0);
+        out.g = converted[1].clamp(0.0, 1.0);
+        out.b = converted[2].clamp(0.0, 1.0);
+        out.a = converted[3].clamp(0.0, 1.0);
+        McoreStatus::Ok
+    })
+}
+
 /// Convert from RGBA8 (0-255) to McoreColor (0.0-1.0)
 #[no_mangle]
 pub extern "C" fn mcore_color_from_rgba8(
@@ -1368,12 +8033,16 @@ pub extern "C" fn mcore_color_from_rgba8(
     a: u8,
     out: *mut McoreColor,
 ) {
-    unsafe {
-        (*out).r = r as f32 / 255.0;
-        (*out).g = g as f32 / 255.0;
-        (*out).b = b as f32 / 255.0;
-        (*out).a = a as f32 / 255.0;
-    }
+    ffi_boundary("mcore_color_from_rgba8", || {
+        trace_call!("mcore_color_from_rgba8", r, g, b, a, out);
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_color_from_rgba8", "out");
+        };
+        out.r = r as f32 / 255.0;
+        out.g = g as f32 / 255.0;
+        out.b = b as f32 / 255.0;
+        out.a = a as f32 / 255.0;
+    })
 }
 
 // ============================================================================
@@ -1416,49 +8085,52 @@ pub extern "C" fn mcore_image_register(
     ctx: *mut McoreContext,
     desc: *const McoreImageDesc,
 ) -> i32 {
-    let ctx = unsafe { ctx.as_mut() };
-    let desc = unsafe { desc.as_ref() };
-
-    if ctx.is_none() || desc.is_none() {
-        set_err("Null pointer passed to mcore_image_register");
-        return -1;
-    }
+    ffi_boundary("mcore_image_register", || {
+        trace_call!("mcore_image_register", ctx, desc);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_image_register", "ctx");
+        };
+        let Some(desc) = (unsafe { desc.as_ref() }) else {
+            return null_arg("mcore_image_register", "desc");
+        };
 
-    let ctx = ctx.unwrap();
-    let desc = desc.unwrap();
-    let mut guard = ctx.0.lock();
+        let mut guard = ctx.0.lock();
 
-    // Copy pixel data from Zig memory
-    let pixels = unsafe {
-        std::slice::from_raw_parts(desc.data, desc.data_len as usize)
-    };
+        // Copy pixel data from Zig memory
+        let Some(pixels) = host_slice(desc.data, desc.data_len as usize) else {
+            return null_arg("mcore_image_register", "desc.data");
+        };
 
-    // Map format enum (only RGBA8 supported for now)
-    let format = match desc.format {
-        1 => vello::peniko::ImageFormat::Rgba8,
-        _ => {
-            set_err(format!("Unsupported image format: {} (only RGBA8 supported)", desc.format));
-            return -1;
-        }
-    };
+        // Map format enum
+        let format = match desc.format {
+            0 => crate::image::SourceFormat::Rgb8,
+            1 => crate::image::SourceFormat::Rgba8,
+            _ => {
+                set_err_code(McoreErrorCode::Unsupported, format!("Unsupported image format: {}", desc.format));
+                return -1;
+            }
+        };
 
-    // Map alpha type enum
-    let alpha_type = match desc.alpha_type {
-        2 => vello::peniko::ImageAlphaType::Alpha,
-        _ => {
-            set_err(format!("Unsupported alpha type: {} (only straight alpha supported)", desc.alpha_type));
-            return -1;
-        }
-    };
+        // Map alpha type enum
+        let alpha = match desc.alpha_type {
+            0 => crate::image::SourceAlpha::Opaque,
+            1 => crate::image::SourceAlpha::Premultiplied,
+            2 => crate::image::SourceAlpha::Straight,
+            _ => {
+                set_err_code(McoreErrorCode::Unsupported, format!("Unsupported alpha type: {}", desc.alpha_type));
+                return -1;
+            }
+        };
 
-    // Register image
-    match guard.images.register(pixels, desc.width, desc.height, format, alpha_type) {
-        Ok(id) => id,
-        Err(e) => {
-            set_err(e);
-            -1
+        // Register image (converted to straight-alpha RGBA8)
+        match guard.images.register_pixels(pixels, desc.width, desc.height, format, alpha) {
+            Ok(id) => id,
+            Err(e) => {
+                set_err(e);
+                -1
+            }
         }
-    }
+    })
 }
 
 /// Increment reference count for an image
@@ -1467,36 +8139,40 @@ pub extern "C" fn mcore_image_retain(
     ctx: *mut McoreContext,
     image_id: i32,
 ) {
-    let ctx = unsafe { ctx.as_mut() };
-    if ctx.is_none() {
-        return;
-    }
+    ffi_boundary("mcore_image_retain", || {
+        trace_call!("mcore_image_retain", ctx, image_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_image_retain", "ctx");
+        };
 
-    let ctx = ctx.unwrap();
-    let mut guard = ctx.0.lock();
+        let mut guard = ctx.0.lock();
 
-    if let Err(e) = guard.images.retain(image_id) {
-        set_err(e);
-    }
+        if let Err(e) = guard.images.retain(image_id) {
+            set_err_code(McoreErrorCode::NotFound, e);
+        }
+    })
 }
 
-/// Decrement reference count, free when 0
+/// Decrement reference count, free when 0 (or keep cached within the budget,
+/// see mcore_image_set_budget)
 #[no_mangle]
 pub extern "C" fn mcore_image_release(
     ctx: *mut McoreContext,
     image_id: i32,
 ) {
-    let ctx = unsafe { ctx.as_mut() };
-    if ctx.is_none() {
-        return;
-    }
+    ffi_boundary("mcore_image_release", || {
+        trace_call!("mcore_image_release", ctx, image_id);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_image_release", "ctx");
+        };
 
-    let ctx = ctx.unwrap();
-    let mut guard = ctx.0.lock();
+        let mut guard = ctx.0.lock();
 
-    if let Err(e) = guard.images.release(image_id) {
-        set_err(e);
-    }
+        if let Err(e) = guard.images.release(image_id) {
+            set_err_code(McoreErrorCode::NotFound, e);
+        }
+        guard.damage.invalidate();
+    })
 }
 
 /// Draw an image with transform
@@ -1506,83 +8182,109 @@ pub extern "C" fn mcore_image_draw(
     image_id: i32,
     transform: *const McoreImageTransform,
 ) {
-    let ctx = unsafe { ctx.as_mut() };
-    let transform = unsafe { transform.as_ref() };
-
-    if ctx.is_none() || transform.is_none() {
-        return;
-    }
+    ffi_boundary("mcore_image_draw", || {
+        trace_call!("mcore_image_draw", ctx, image_id, transform);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_image_draw", "ctx");
+        };
+        let Some(transform) = (unsafe { transform.as_ref() }) else {
+            return null_arg("mcore_image_draw", "transform");
+        };
 
-    let ctx = ctx.unwrap();
-    let transform = transform.unwrap();
-    let mut guard = ctx.0.lock();
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_image_draw") {
+            return;
+        }
 
-    // Look up image
-    if let Some(image_data) = guard.images.get(image_id) {
-        // Build affine transform - scale position from logical to physical pixels
-        use peniko::kurbo::Affine;
-        let dpi_scale = guard.gfx.scale();
+        // Look up image
+        if let Some((width, height)) = guard.images.get_dimensions(image_id) {
+            // Build affine transform - scale position from logical to physical pixels
+            use peniko::kurbo::{Affine, Rect};
+            let dpi_scale = guard.gfx.scale();
+
+            let affine = Affine::scale(transform.scale as f64)
+                .then_rotate((transform.rotation_deg as f64).to_radians())
+                .then_translate(((transform.x * dpi_scale) as f64, (transform.y * dpi_scale) as f64).into());
+
+            // Drawn like a one-copy image command, so small images come from
+            // the atlas
+            let rect = Rect::new(0.0, 0.0, width as f64, height as f64);
+            let eng = &mut *guard;
+            image::draw(&mut eng.scene, &eng.images, image_id, affine, rect, rect, image::Sampler::default());
+        }
+    })
+}
 
-        let affine = Affine::scale(transform.scale as f64)
-            .then_rotate((transform.rotation_deg as f64).to_radians())
-            .then_translate(((transform.x * dpi_scale) as f64, (transform.y * dpi_scale) as f64).into());
+/// Draw an image over a rect with its sampling and tiling options, like an
+/// MCORE_CMD_IMAGE record (logical px)
+#[no_mangle]
+pub extern "C" fn mcore_image_draw_rect(ctx: *mut McoreContext, cmd: *const McoreCmdImage) -> McoreStatus {
+    ffi_boundary("mcore_image_draw_rect", || {
+        trace_call!("mcore_image_draw_rect", ctx, cmd);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_image_draw_rect", "ctx");
+        };
+        let Some(cmd) = (unsafe { cmd.as_ref() }) else {
+            return null_arg("mcore_image_draw_rect", "cmd");
+        };
+        let mut guard = ctx.0.lock();
+        if !drawing_allowed(&mut guard, "mcore_image_draw_rect") {
+            return McoreStatus::Err;
+        }
 
-        // Draw to scene (create ImageBrush from ImageData)
-        let brush = peniko::ImageBrush::from(image_data.clone());
-        guard.scene.draw_image(&brush, affine);
-    }
+        let eng = &mut *guard;
+        let transform = peniko::kurbo::Affine::scale(eng.gfx.scale() as f64);
+        let (dest, tile) = cmd.rects();
+        if !image::draw(&mut eng.scene, &eng.images, cmd.image_id, transform, dest, tile, cmd.sampler()) {
+            set_err_code(McoreErrorCode::NotFound, format!("Image ID {} not found or empty", cmd.image_id));
+            return McoreStatus::Err;
+        }
+        McoreStatus::Ok
+    })
 }
 
-/// Load and register an image from a file path (JPEG, PNG, etc.)
+/// Load and register an image from a file path (JPEG, PNG, etc.), turned
+/// upright per its EXIF orientation
 /// Returns image info (id, width, height). id is -1 on error.
 #[no_mangle]
 pub extern "C" fn mcore_image_load_file(
     ctx: *mut McoreContext,
     path: *const i8,
 ) -> McoreImageInfo {
-    let ctx = unsafe { ctx.as_mut() };
-
-    if ctx.is_none() || path.is_null() {
-        set_err("Null pointer passed to mcore_image_load_file");
-        return McoreImageInfo {
-            image_id: -1,
-            width: 0,
-            height: 0,
-        };
-    }
-
-    let ctx = ctx.unwrap();
-    let path_str = unsafe { CStr::from_ptr(path) }
-        .to_str()
-        .unwrap_or("");
+    ffi_boundary("mcore_image_load_file", || {
+        trace_call!("mcore_image_load_file", ctx, path);
+        load_image_file("mcore_image_load_file", ctx, path, None)
+    })
+}
 
-    let mut guard = ctx.0.lock();
+/// mcore_image_load_file, shrunk while decoding so neither side exceeds
+/// `max_dimension` (aspect ratio kept; smaller images are left as they are).
+/// List thumbnails of large photos then cost a fraction of the memory and
+/// upload.
+#[no_mangle]
+pub extern "C" fn mcore_image_load_thumbnail(
+    ctx: *mut McoreContext,
+    path: *const i8,
+    max_dimension: u32,
+) -> McoreImageInfo {
+    ffi_boundary("mcore_image_load_thumbnail", || {
+        trace_call!("mcore_image_load_thumbnail", ctx, path, max_dimension);
+        load_image_file("mcore_image_load_thumbnail", ctx, path, Some(max_dimension))
+    })
+}
 
-    match guard.images.register_from_file(path_str) {
-        Ok(id) => {
-            // Get dimensions
-            if let Some((width, height)) = guard.images.get_dimensions(id) {
-                McoreImageInfo {
-                    image_id: id,
-                    width,
-                    height,
-                }
-            } else {
-                set_err("Failed to get image dimensions");
-                McoreImageInfo {
-                    image_id: -1,
-                    width: 0,
-                    height: 0,
-                }
-            }
-        }
+fn load_image_file(call: &str, ctx: *mut McoreContext, path: *const i8, max_dimension: Option<u32>) -> McoreImageInfo {
+    let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+        return null_arg(call, "ctx");
+    };
+    if path.is_null() {
+        return null_arg(call, "path");
+    }
+    match ctx.0.lock().images.register_from_file(utf8_arg(path), max_dimension) {
+        Ok((image_id, width, height)) => McoreImageInfo { image_id, width, height },
         Err(e) => {
             set_err(e);
-            McoreImageInfo {
-                image_id: -1,
-                width: 0,
-                height: 0,
-            }
+            McoreImageInfo::failure()
         }
     }
 }
@@ -1595,23 +8297,434 @@ pub extern "C" fn mcore_image_get_info(
     image_id: i32,
     out: *mut McoreImageInfo,
 ) -> u8 {
-    let ctx = unsafe { ctx.as_mut() };
-    let out = unsafe { out.as_mut() };
+    ffi_boundary("mcore_image_get_info", || {
+        trace_call!("mcore_image_get_info", ctx, image_id, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_image_get_info", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_image_get_info", "out");
+        };
+
+        let guard = ctx.0.lock();
+
+        if let Some((width, height)) = guard.images.get_dimensions(image_id) {
+            out.image_id = image_id;
+            out.width = width;
+            out.height = height;
+            1
+        } else {
+            0
+        }
+    })
+}
+
+/// Register encoded image bytes (JPEG, PNG, ...) and decode them on a
+/// background thread. Returns the image's ID (-1 on error) at once; until
+/// decoding finishes, draws of it fill their rect with the placeholder color.
+/// `callback` (optional) then runs on the decode thread with the ID and size,
+/// or a size of 0 x 0 if decoding failed, in which case the ID is freed.
+/// `data` can be freed after this returns.
+#[no_mangle]
+pub extern "C" fn mcore_image_register_async(
+    ctx: *mut McoreContext,
+    data: *const u8,
+    len: usize,
+    callback: Option<extern "C" fn(i32, u32, u32, *mut c_void)>,
+    user_data: *mut c_void,
+) -> i32 {
+    ffi_boundary("mcore_image_register_async", || {
+        trace_call!("mcore_image_register_async", ctx, data, len, user_data);
+        register_async("mcore_image_register_async", ctx, data, len, None, callback, user_data)
+    })
+}
+
+/// mcore_image_register_async, shrunk while decoding so neither side exceeds
+/// `max_dimension` (see mcore_image_load_thumbnail)
+#[no_mangle]
+pub extern "C" fn mcore_image_register_thumbnail_async(
+    ctx: *mut McoreContext,
+    data: *const u8,
+    len: usize,
+    max_dimension: u32,
+    callback: Option<extern "C" fn(i32, u32, u32, *mut c_void)>,
+    user_data: *mut c_void,
+) -> i32 {
+    ffi_boundary("mcore_image_register_thumbnail_async", || {
+        trace_call!("mcore_image_register_thumbnail_async", ctx, data, len, max_dimension, user_data);
+        register_async("mcore_image_register_thumbnail_async", ctx, data, len, Some(max_dimension), callback, user_data)
+    })
+}
 
-    if ctx.is_none() || out.is_none() {
-        return 0;
+fn register_async(
+    call: &str,
+    ctx: *mut McoreContext,
+    data: *const u8,
+    len: usize,
+    max_dimension: Option<u32>,
+    callback: Option<decode::DecodeCallback>,
+    user_data: *mut c_void,
+) -> i32 {
+    let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+        return null_arg(call, "ctx");
+    };
+    let Some(bytes) = host_slice(data, len) else {
+        return null_arg(call, "data");
+    };
+    if !cfg!(feature = "image-decode") {
+        set_err_code(McoreErrorCode::Unsupported, "Image decoding not compiled in (enable the image-decode feature)");
+        return -1;
     }
+    let (images, log_callback) = {
+        let guard = ctx.0.lock();
+        (guard.images.shared(), guard.log_callback)
+    };
+    // Unlocked: starting threads can log, and the fallback decodes inline
+    decode::start(log_callback);
+    decode::register_async(images, bytes.to_vec(), max_dimension, callback, user_data)
+}
 
-    let ctx = ctx.unwrap();
-    let out = out.unwrap();
-    let guard = ctx.0.lock();
+/// Color drawn in place of images still decoding (transparent by default)
+#[no_mangle]
+pub extern "C" fn mcore_image_set_placeholder(ctx: *mut McoreContext, color: McoreRgba) {
+    ffi_boundary("mcore_image_set_placeholder", || {
+        trace_call!("mcore_image_set_placeholder", ctx);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_image_set_placeholder", "ctx");
+        };
+        let mut guard = ctx.0.lock();
+        guard.images.set_placeholder(Color::new([color.r, color.g, color.b, color.a]));
+        guard.damage.invalidate();
+    })
+}
 
-    if let Some((width, height)) = guard.images.get_dimensions(image_id) {
-        out.image_id = image_id;
-        out.width = width;
-        out.height = height;
-        1
-    } else {
-        0
+/// Occupancy of the image cache, shared by the contexts of an engine
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct McoreImageCacheStats {
+    /// Decoded pixels held, mip levels included
+    pub bytes: u64,
+    /// Part of bytes held by released images kept for reuse
+    pub released_bytes: u64,
+    pub budget_bytes: u64,
+    /// Released images freed so far to stay within the budget
+    pub evictions: u64,
+    pub image_count: u32,
+    pub released_count: u32,
+    /// Images still decoding
+    pub pending_count: u32,
+    /// Shared textures small images are packed into, up to 4MB each and 8
+    /// in all. Pages are kept once allocated and aren't counted in bytes or
+    /// against the budget (they are in mcore_frame_stats image_bytes).
+    pub atlas_pages: u32,
+}
+
+/// Cap the decoded pixels the image cache holds. Released images stay cached
+/// (and can be retained again) until the budget needs their room; then they
+/// are evicted least recently drawn first, followed by mip levels. Images in
+/// use are never evicted. The default, 0, frees released images at once.
+/// Atlas pages aren't counted: they never shrink, so they take up to 32 MiB
+/// on top of the budget (see atlas_pages).
+#[no_mangle]
+pub extern "C" fn mcore_image_set_budget(ctx: *mut McoreContext, bytes: u64) {
+    ffi_boundary("mcore_image_set_budget", || {
+        trace_call!("mcore_image_set_budget", ctx, bytes);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_image_set_budget", "ctx");
+        };
+        ctx.0.lock().images.set_budget(bytes);
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn mcore_image_cache_stats(ctx: *mut McoreContext, out: *mut McoreImageCacheStats) -> McoreStatus {
+    ffi_boundary("mcore_image_cache_stats", || {
+        trace_call!("mcore_image_cache_stats", ctx, out);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_image_cache_stats", "ctx");
+        };
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return null_arg("mcore_image_cache_stats", "out");
+        };
+        let stats = ctx.0.lock().images.stats();
+        *out = McoreImageCacheStats {
+            bytes: stats.bytes,
+            released_bytes: stats.released_bytes,
+            budget_bytes: stats.budget,
+            evictions: stats.evictions,
+            image_count: stats.images as u32,
+            released_count: stats.released as u32,
+            pending_count: stats.pending as u32,
+            atlas_pages: stats.atlas_pages as u32,
+        };
+        McoreStatus::Ok
+    })
+}
+
+/// Give memory back when the system runs low: MCORE_MEMORY_PRESSURE_WARNING
+/// frees released images, MCORE_MEMORY_PRESSURE_CRITICAL also drops every
+/// mip level. Call it from the platform's memory warning (e.g. a
+/// DISPATCH_SOURCE_TYPE_MEMORYPRESSURE source). Returns the bytes freed.
+#[no_mangle]
+pub extern "C" fn mcore_memory_pressure(ctx: *mut McoreContext, level: u32) -> u64 {
+    ffi_boundary("mcore_memory_pressure", || {
+        trace_call!("mcore_memory_pressure", ctx, level);
+        let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+            return null_arg("mcore_memory_pressure", "ctx");
+        };
+        let pressure = match level {
+            1 => image::Pressure::Warning,
+            2 => image::Pressure::Critical,
+            _ => {
+                set_err_code(McoreErrorCode::InvalidArg, format!("Unknown memory pressure level {level}"));
+                return 0;
+            }
+        };
+        let mut guard = ctx.0.lock();
+        let freed = guard.images.purge(pressure);
+        if freed > 0 {
+            guard.damage.invalidate();
+        }
+        freed
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> (McoreErrorCode, String) {
+        let message = unsafe { CStr::from_ptr(mcore_last_error()) }.to_string_lossy().into_owned();
+        (mcore_last_error_code(), message)
+    }
+
+    #[test]
+    fn panics_become_errors_with_failure_values() {
+        mcore_clear_error();
+        assert_eq!(ffi_boundary("test_id", || -> i32 { panic!("boom") }), -1);
+        assert_eq!(last_error(), (McoreErrorCode::Panic, "test_id panicked: boom".to_string()));
+
+        let status = ffi_boundary("test_status", || -> McoreStatus { panic!("{} {}", "formatted", 42) });
+        assert!(matches!(status, McoreStatus::Err));
+        assert_eq!(last_error().1, "test_status panicked: formatted 42");
+
+        assert!(ffi_boundary("test_ptr", || -> *mut McoreContext { panic!() }).is_null());
+        ffi_boundary::<()>("test_unit", || std::panic::panic_any(7u32));
+        assert_eq!(last_error().1, "test_unit panicked: non-string panic payload");
+
+        // Bodies that don't panic are untouched
+        mcore_clear_error();
+        assert_eq!(ffi_boundary("test_ok", || 5u32), 5);
+        assert_eq!(mcore_last_error_code(), McoreErrorCode::None);
+    }
+
+    #[test]
+    fn null_arguments_are_invalid_args() {
+        let status = mcore_begin_frame(std::ptr::null_mut(), 0.0);
+        assert!(matches!(status, McoreStatus::Err));
+        assert_eq!(last_error(), (McoreErrorCode::InvalidArg, "Null ctx passed to mcore_begin_frame".to_string()));
+
+        assert_eq!(mcore_font_register(std::ptr::null_mut(), std::ptr::null()), -1);
+        assert_eq!(mcore_last_error_code(), McoreErrorCode::InvalidArg);
+
+        // A null array is fine when it's empty
+        assert_eq!(host_slice::<u8>(std::ptr::null(), 0), Some(&[][..]));
+        assert_eq!(host_slice::<u8>(std::ptr::null(), 4), None);
+    }
+
+    #[test]
+    fn create_calls_check_the_api_version() {
+        assert_eq!(mcore_api_version(), API_VERSION);
+        assert!(api_compatible(API_VERSION));
+        assert!(!api_compatible(API_VERSION + 1), "host expects a newer minor version");
+        assert!(!api_compatible((API_VERSION_MAJOR + 1) << 16));
+        // A host from before the field existed passes its platform there
+        assert!(!api_compatible(McorePlatform::MacOS as u32));
+
+        // Checked before any GPU work
+        assert!(mcore_create_headless(0, 64, 64, 1.0, 0).is_null());
+        assert_eq!(mcore_last_error_code(), McoreErrorCode::VersionMismatch);
+        assert!(last_error().1.starts_with("mcore_create_headless: host built against API 0.0"));
+
+        let desc = McoreEngineDesc {
+            api_version: API_VERSION + 1,
+            power_preference: McorePowerPreference::Default,
+            adapter: 0,
+            flags: 0,
+        };
+        assert!(mcore_engine_create(&desc).is_null());
+        assert_eq!(mcore_last_error_code(), McoreErrorCode::VersionMismatch);
+    }
+
+    #[test]
+    fn unknown_cursors_are_invalid_args() {
+        assert!(matches!(mcore_set_cursor(11), McoreStatus::Err));
+        assert_eq!(last_error(), (McoreErrorCode::InvalidArg, "Unknown cursor 11".to_string()));
+        assert!(matches!(McoreCursor::try_from(McoreCursor::ResizeNesw as u8), Ok(McoreCursor::ResizeNesw)));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn unknown_color_spaces_are_invalid_args() {
+        let ctx = mcore_create_headless(API_VERSION, 64, 64, 1.0, 0);
+        if ctx.is_null() {
+            eprintln!("skipping color space test: {}", last_error().1);
+            return;
+        }
+        assert!(matches!(mcore_set_color_space(ctx, 2), McoreStatus::Err));
+        assert_eq!(last_error(), (McoreErrorCode::InvalidArg, "Unknown color space 2".to_string()));
+        // Headless: a known space is unsupported rather than invalid
+        assert!(matches!(mcore_set_color_space(ctx, McoreColorSpace::DisplayP3 as u8), McoreStatus::Err));
+        assert_eq!(mcore_last_error_code(), McoreErrorCode::Unsupported);
+        assert_eq!(mcore_get_color_space(ctx), McoreColorSpace::Srgb);
+        mcore_destroy(ctx);
+    }
+
+    #[test]
+    fn color_convert_keeps_p3_in_p3_windows() {
+        let p3_red = McoreColor { r: 1.0, g: 0.0, b: 0.0, a: 1.0 };
+        let mut out = McoreColor { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+        let (srgb, p3) = (McoreColorSpace::Srgb as u8, McoreColorSpace::DisplayP3 as u8);
+
+        assert!(matches!(mcore_color_convert(p3, p3, &p3_red, &mut out), McoreStatus::Ok));
+        assert_eq!([out.r, out.g, out.b, out.a], [1.0, 0.0, 0.0, 1.0]);
+
+        // Past the sRGB gamut: clamped
+        assert!(matches!(mcore_color_convert(p3, srgb, &p3_red, &mut out), McoreStatus::Ok));
+        assert_eq!([out.r, out.g, out.a], [1.0, 0.0, 1.0]);
+
+        // sRGB fits inside P3, so its red is a less saturated P3 red
+        let srgb_red = McoreColor { r: 1.0, g: 0.0, b: 0.0, a: 1.0 };
+        assert!(matches!(mcore_color_convert(srgb, p3, &srgb_red, &mut out), McoreStatus::Ok));
+        assert!(out.r < 1.0 && out.g > 0.0, "{out:?}");
+
+        assert!(matches!(mcore_color_convert(2, p3, &p3_red, &mut out), McoreStatus::Err));
+        assert_eq!(last_error(), (McoreErrorCode::InvalidArg, "Unknown color space 2".to_string()));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn frames_wait_for_fragment_recordings() {
+        let ctx = mcore_create_headless(API_VERSION, 64, 64, 1.0, 0);
+        if ctx.is_null() {
+            eprintln!("skipping fragment test: {}", last_error().1);
+            return;
+        }
+        let clear = McoreRgba { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+        let rect = McoreRoundedRect { x: 0.0, y: 0.0, w: 10.0, h: 10.0, radius: 0.0, fill: clear };
+
+        // Recording, then a frame begins: refused, and the recording survives
+        assert!(matches!(mcore_fragment_begin(ctx), McoreStatus::Ok));
+        mcore_rect_rounded(ctx, &rect);
+        assert!(matches!(mcore_begin_frame(ctx, 0.0), McoreStatus::Err));
+        assert_eq!(mcore_last_error_code(), McoreErrorCode::InvalidState);
+        let fragment = mcore_fragment_end(ctx);
+        assert!(fragment >= 0);
+        assert!(matches!(mcore_begin_frame(ctx, 0.0), McoreStatus::Ok));
+
+        // In a frame, recording, then present: refused until the recording ends
+        assert!(matches!(mcore_fragment_begin(ctx), McoreStatus::Ok));
+        assert!(matches!(mcore_end_frame_present(ctx, clear), McoreStatus::Err));
+        assert_eq!(mcore_last_error_code(), McoreErrorCode::InvalidState);
+        assert!(mcore_fragment_end(ctx) >= 0);
+        {
+            let guard = unsafe { &*ctx }.0.lock();
+            assert!(guard.in_frame, "the frame stays open for the present");
+            assert!(!guard.fragments.is_recording());
+        }
+        assert!(!matches!(mcore_end_frame_present(ctx, clear), McoreStatus::Err));
+
+        mcore_destroy(ctx);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn color_filters_apply_to_images() {
+        let ctx = mcore_create_headless(API_VERSION, 16, 16, 1.0, 0);
+        if ctx.is_null() {
+            eprintln!("skipping color filter test: {}", last_error().1);
+            return;
+        }
+        let red: Vec<u8> = [255, 0, 0, 255].repeat(8 * 8);
+        let desc = McoreImageDesc { data: red.as_ptr(), data_len: red.len() as u32, width: 8, height: 8, format: 1, alpha_type: 0 };
+        let image = mcore_image_register(ctx, &desc);
+        assert!(image >= 0);
+
+        let clear = McoreRgba { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+        let at_origin = McoreImageTransform { x: 0.0, y: 0.0, scale: 1.0, rotation_deg: 0.0 };
+        // The image's center pixel, as presented
+        let draw = |grayscale: bool| {
+            assert!(matches!(mcore_begin_frame(ctx, 0.0), McoreStatus::Ok));
+            if grayscale {
+                mcore_push_grayscale_filter(ctx, 1.0);
+            }
+            mcore_image_draw(ctx, image, &at_origin);
+            if grayscale {
+                mcore_pop_color_filter(ctx);
+            }
+            assert!(matches!(mcore_end_frame_present(ctx, clear), McoreStatus::Ok));
+            let pixels = unsafe { &*ctx }.0.lock().gfx.read_pixels().expect("headless readback");
+            let i = (4 * 16 + 4) * 4;
+            [pixels[i], pixels[i + 1], pixels[i + 2]].map(i32::from)
+        };
+
+        let [r, g, _] = draw(false);
+        assert!(r > 240 && g < 16, "unfiltered image is red, got {r}, {g}");
+        let [r, g, b] = draw(true);
+        assert!(r < 128, "filtered image is desaturated, got {r}");
+        assert!((r - g).abs() <= 2 && (g - b).abs() <= 2, "filtered image is gray, got {r}, {g}, {b}");
+
+        mcore_image_release(ctx, image);
+        mcore_destroy(ctx);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn set_clear_color_overrides_the_frame_clear() {
+        let ctx = mcore_create_headless(API_VERSION, 16, 16, 1.0, 0);
+        if ctx.is_null() {
+            eprintln!("skipping clear color test: {}", last_error().1);
+            return;
+        }
+        let red = McoreRgba { r: 1.0, g: 0.0, b: 0.0, a: 1.0 };
+        let blue = McoreRgba { r: 0.0, g: 0.0, b: 1.0, a: 1.0 };
+        let present = |time: f64| {
+            assert!(matches!(mcore_begin_frame(ctx, time), McoreStatus::Ok));
+            assert!(matches!(mcore_end_frame_present(ctx, blue), McoreStatus::Ok));
+            let pixels = unsafe { &*ctx }.0.lock().gfx.read_pixels().expect("headless readback");
+            [pixels[0], pixels[1], pixels[2]]
+        };
+
+        assert_eq!(present(0.0), [0, 0, 255]);
+        assert!(matches!(mcore_set_clear_color(ctx, red), McoreStatus::Ok));
+        // The same at any frame time
+        assert_eq!(present(1.0), [255, 0, 0]);
+        assert_eq!(present(2.5), [255, 0, 0]);
+
+        let nan = McoreRgba { r: f32::NAN, ..red };
+        assert!(matches!(mcore_set_clear_color(ctx, nan), McoreStatus::Err));
+        assert_eq!(mcore_last_error_code(), McoreErrorCode::InvalidArg);
+        mcore_destroy(ctx);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn render_thread_frames_pass_the_trace_check() {
+        let ctx = mcore_create_headless(API_VERSION, 16, 16, 1.0, 0);
+        if ctx.is_null() {
+            eprintln!("skipping render thread test: {}", last_error().1);
+            return;
+        }
+        // Tracing aborts on calls with a context it doesn't know
+        mcore_trace_enable(64);
+        let clear = McoreRgba { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+        assert!(matches!(mcore_render_thread_start(ctx), McoreStatus::Ok));
+        assert!(matches!(mcore_submit_frame(ctx, 1.0, std::ptr::null(), 0, command_stream::VERSION, clear), McoreStatus::Ok));
+        assert!(matches!(mcore_render_thread_flush(ctx), McoreStatus::Ok));
+        assert_eq!(unsafe { &*ctx }.0.lock().time_s, 1.0, "the frame was built");
+        // Queued frames are drained while destroying, too
+        assert!(matches!(mcore_submit_frame(ctx, 2.0, std::ptr::null(), 0, command_stream::VERSION, clear), McoreStatus::Ok));
+        mcore_destroy(ctx);
+        mcore_trace_enable(0);
     }
 }
@@ -0,0 +1,236 @@
+/// Append-only text views (chat transcripts, build output, consoles)
+///
+/// Laying out a growing transcript as one text gets quadratic: every append
+/// reshapes everything before it. Here each appended entry is shaped once, on
+/// append, and keeps its height in a running stack of offsets; drawing finds
+/// the visible entries by binary search and only touches those. Everything is
+/// reshaped only when the wrap width, scale factor or tab stops change.
+
+use peniko::Color;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use vello::Scene;
+
+use crate::text::{self, ShapedText, TabStops, TextContext, TextStyle};
+
+struct Entry {
+    text: String,
+    color: Color,
+    shaped: ShapedText,
+}
+
+/// Running vertical positions of a list of entries. Tops are kept relative
+/// to the first entry ever pushed, so dropping old entries doesn't shift
+/// the rest.
+#[derive(Default)]
+struct Stack {
+    tops: VecDeque<f64>,
+    bottom: f64,
+}
+
+impl Stack {
+    fn push(&mut self, height: f32) {
+        self.tops.push_back(self.bottom);
+        self.bottom += height as f64;
+    }
+
+    fn pop_front(&mut self) {
+        self.tops.pop_front();
+    }
+
+    fn base(&self) -> f64 {
+        self.tops.front().copied().unwrap_or(self.bottom)
+    }
+
+    /// Top of entry `i`, relative to the first entry kept
+    fn top(&self, i: usize) -> f32 {
+        (self.tops[i] - self.base()) as f32
+    }
+
+    fn height(&self) -> f32 {
+        (self.bottom - self.base()) as f32
+    }
+
+    /// Entries overlapping `y0..y1` (relative to the first entry kept)
+    fn visible(&self, y0: f32, y1: f32) -> Range<usize> {
+        let base = self.base();
+        let (y0, y1) = (base + y0 as f64, base + y1 as f64);
+        // The last entry starting at or above y0 contains it
+        let start = self.tops.partition_point(|&top| top <= y0).saturating_sub(1);
+        let end = self.tops.partition_point(|&top| top < y1);
+        start..end.max(start)
+    }
+}
+
+pub struct LogView {
+    style: TextStyle,
+    /// Logical px
+    wrap_width: f32,
+    /// Scale factor the entries were shaped at
+    scale: f32,
+    /// Oldest entries are dropped beyond this (0 keeps everything)
+    max_entries: usize,
+    entries: VecDeque<Entry>,
+    stack: Stack,
+}
+
+impl LogView {
+    pub fn new(style: TextStyle, wrap_width: f32, max_entries: usize, scale: f32) -> Self {
+        Self {
+            style,
+            wrap_width,
+            scale,
+            max_entries,
+            entries: VecDeque::new(),
+            stack: Stack::default(),
+        }
+    }
+
+    /// Logical px
+    pub fn wrap_width(&self) -> f32 {
+        self.wrap_width
+    }
+
+    /// Total height of the kept entries (logical px)
+    pub fn content_height(&self) -> f32 {
+        self.stack.height()
+    }
+
+    fn shape(&self, text_cx: &mut TextContext, text: &str) -> (ShapedText, f32) {
+        let shaped = text::shape_text(text_cx, text, &self.style, self.wrap_width, self.scale);
+        let height = text::layout_metrics(&shaped).height / self.scale;
+        (shaped, height)
+    }
+
+    /// Shape and add an entry below the others
+    pub fn append(&mut self, text_cx: &mut TextContext, text: &str, color: Color, scale: f32) {
+        self.relayout(text_cx, self.wrap_width, scale);
+        let (shaped, height) = self.shape(text_cx, text);
+        self.entries.push_back(Entry { text: text.to_string(), color, shaped });
+        self.stack.push(height);
+
+        while self.max_entries > 0 && self.entries.len() > self.max_entries {
+            self.entries.pop_front();
+            self.stack.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.stack = Stack::default();
+    }
+
+    /// Reshape every entry if the wrap width or scale changed
+    pub fn relayout(&mut self, text_cx: &mut TextContext, wrap_width: f32, scale: f32) {
+        if wrap_width == self.wrap_width && scale == self.scale {
+            return;
+        }
+        self.wrap_width = wrap_width;
+        self.scale = scale;
+        self.reshape_all(text_cx);
+    }
+
+    /// Lay out tabs at new stops, reshaping every entry
+    pub fn set_tabs(&mut self, text_cx: &mut TextContext, tabs: TabStops, scale: f32) {
+        self.style.tabs = tabs;
+        self.scale = scale;
+        self.reshape_all(text_cx);
+    }
+
+    fn reshape_all(&mut self, text_cx: &mut TextContext) {
+        let mut stack = Stack::default();
+        let mut entries = std::mem::take(&mut self.entries);
+        for entry in &mut entries {
+            let (shaped, height) = self.shape(text_cx, &entry.text);
+            entry.shaped = shaped;
+            stack.push(height);
+        }
+        self.entries = entries;
+        self.stack = stack;
+    }
+
+    /// Draw the entries visible through a `height`-tall window scrolled to
+    /// `scroll_y`, with the content's top at logical (x, y - scroll_y)
+    pub fn draw(
+        &self,
+        scene: &mut Scene,
+        x: f32,
+        y: f32,
+        scroll_y: f32,
+        height: f32,
+        rendering: text::Rendering,
+    ) {
+        for i in self.stack.visible(scroll_y, scroll_y + height) {
+            let entry = &self.entries[i];
+            let top = y - scroll_y + self.stack.top(i);
+            text::draw_layout(scene, &entry.shaped, x * self.scale, top * self.scale, entry.color, rendering);
+        }
+    }
+}
+
+/// Registry of log views keyed by ID
+pub struct LogViewManager {
+    views: HashMap<i32, LogView>,
+    next_id: i32,
+}
+
+impl LogViewManager {
+    pub fn new() -> Self {
+        Self {
+            views: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Register a view and return its ID
+    pub fn register(&mut self, view: LogView) -> i32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.views.insert(id, view);
+        id
+    }
+
+    pub fn get(&self, id: i32) -> Option<&LogView> {
+        self.views.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: i32) -> Option<&mut LogView> {
+        self.views.get_mut(&id)
+    }
+
+    /// Remove a view, returning whether it existed
+    pub fn remove(&mut self, id: i32) -> bool {
+        self.views.remove(&id).is_some()
+    }
+}
+
+impl Default for LogViewManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible_range_uses_running_offsets() {
+        let mut stack = Stack::default();
+        for height in [20.0, 40.0, 20.0, 20.0] {
+            stack.push(height);
+        }
+        assert_eq!(stack.height(), 100.0);
+        assert_eq!(stack.visible(0.0, 10.0), 0..1);
+        assert_eq!(stack.visible(30.0, 65.0), 1..3);
+        assert_eq!(stack.visible(60.0, 60.0), 2..2);
+        assert_eq!(stack.visible(95.0, 200.0), 3..4);
+        assert_eq!(stack.visible(500.0, 600.0), 3..3);
+
+        // Dropping the oldest entry keeps the others' offsets relative to it
+        stack.pop_front();
+        assert_eq!(stack.height(), 80.0);
+        assert_eq!(stack.top(1), 40.0);
+        assert_eq!(stack.visible(0.0, 10.0), 0..1);
+    }
+}
@@ -0,0 +1,177 @@
+/// Engine-drawn debug overlay (mcore_set_debug_overlay)
+///
+/// Drawn over the host's content at the end of each frame, from data the
+/// engine already tracks: frame intervals, command counts, the hit registry's
+/// clips and the text layout cache counters. Each flag turns on one part; the
+/// text parts share a panel in the top left corner. Frames with the overlay
+/// on are never skipped by damage tracking, since the graph moves every frame.
+
+use peniko::kurbo::{Affine, Rect, RoundedRect, Stroke};
+use peniko::{Color, Fill};
+use std::collections::VecDeque;
+
+use crate::engine::Engine;
+use crate::text;
+
+pub const FPS_GRAPH: u32 = 1 << 0;
+pub const COMMAND_COUNT: u32 = 1 << 1;
+pub const CLIP_DEPTH: u32 = 1 << 2;
+pub const TEXT_CACHE: u32 = 1 << 3;
+pub const CLIP_OUTLINES: u32 = 1 << 4;
+
+/// Frames shown in the graph
+const HISTORY: usize = 120;
+/// Logical px
+const MARGIN: f32 = 8.0;
+const PADDING: f32 = 6.0;
+const PANEL_WIDTH: f32 = 2.0 * PADDING + HISTORY as f32;
+const GRAPH_HEIGHT: f32 = 40.0;
+const FONT_SIZE: f32 = 11.0;
+const LINE_HEIGHT: f32 = 14.0;
+
+#[derive(Default)]
+pub struct DebugOverlay {
+    flags: u32,
+    /// Seconds between recent frames, oldest first
+    intervals: VecDeque<f32>,
+    last_time: Option<f64>,
+    /// Layout cache (hits, misses) when the last frame was drawn
+    cache_counts: (u64, u64),
+}
+
+impl DebugOverlay {
+    pub fn set_flags(&mut self, flags: u32) {
+        self.flags = flags;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.flags != 0
+    }
+
+    pub fn frame_started(&mut self, time_s: f64) {
+        if let Some(last) = self.last_time {
+            if self.intervals.len() == HISTORY {
+                self.intervals.pop_front();
+            }
+            self.intervals.push_back((time_s - last).max(0.0) as f32);
+        }
+        self.last_time = Some(time_s);
+    }
+
+    /// Average over the graph's frames
+    fn fps(&self) -> Option<f32> {
+        let total: f32 = self.intervals.iter().sum();
+        (total > 0.0).then(|| self.intervals.len() as f32 / total)
+    }
+
+    /// Hit rate of the layout cache since the last call, given its running
+    /// counters (which the host may reset)
+    fn cache_hit_rate(&mut self, hits: u64, misses: u64) -> Option<f32> {
+        let (last_hits, last_misses) = self.cache_counts;
+        self.cache_counts = (hits, misses);
+        let (hits, misses) = (hits.saturating_sub(last_hits), misses.saturating_sub(last_misses));
+        (hits + misses > 0).then(|| hits as f32 / (hits + misses) as f32)
+    }
+}
+
+/// Draw the overlay into the frame's scene
+pub fn draw(eng: &mut Engine) {
+    let flags = eng.overlay.flags;
+    let scale = eng.gfx.scale();
+    let px = |v: f32| (v * scale) as f64;
+
+    if flags & CLIP_OUTLINES != 0 {
+        let stroke = Stroke::new(scale.max(1.0) as f64);
+        let magenta = Color::from_rgba8(255, 0, 255, 200);
+        for clip in eng.hits.frame_clips() {
+            let rect = Rect::new(px(clip.x0), px(clip.y0), px(clip.x1), px(clip.y1));
+            eng.scene.stroke(&stroke, Affine::IDENTITY, magenta, None, &rect);
+        }
+    }
+
+    let mut lines = Vec::new();
+    if flags & FPS_GRAPH != 0 {
+        lines.push(match eng.overlay.fps() {
+            Some(fps) => format!("{fps:.0} fps ({:.1} ms)", 1000.0 / fps),
+            None => "-- fps".to_string(),
+        });
+    }
+    if flags & COMMAND_COUNT != 0 {
+        let timings = eng.watchdog.build_timings();
+        lines.push(format!("{} commands in {} calls", timings.commands, timings.encode_calls));
+    }
+    if flags & CLIP_DEPTH != 0 {
+        let mut line = format!("clip depth {} ({} clips)", eng.hits.max_clip_depth(), eng.hits.frame_clips().len());
+        if eng.layers.warnings() > 0 {
+            line += &format!(", {} unbalanced", eng.layers.warnings());
+        }
+        lines.push(line);
+    }
+    if flags & TEXT_CACHE != 0 {
+        let stats = eng.text_cx.layout_cache_stats();
+        lines.push(match eng.overlay.cache_hit_rate(stats.hits, stats.misses) {
+            Some(rate) => format!("text cache {:.0}% hits", rate * 100.0),
+            None => "text cache idle".to_string(),
+        });
+    }
+    if lines.is_empty() {
+        return;
+    }
+    let graph = flags & FPS_GRAPH != 0;
+
+    let height = 2.0 * PADDING + lines.len() as f32 * LINE_HEIGHT + if graph { GRAPH_HEIGHT + PADDING } else { 0.0 };
+    let panel = RoundedRect::new(px(MARGIN), px(MARGIN), px(MARGIN + PANEL_WIDTH), px(MARGIN + height), px(4.0));
+    eng.scene.fill(Fill::NonZero, Affine::IDENTITY, Color::from_rgba8(0, 0, 0, 180), None, &panel);
+
+    let (x, mut y) = (MARGIN + PADDING, MARGIN + PADDING);
+    if graph {
+        // Bars are frame intervals, scaled so two refresh periods fill the graph
+        let budget = 1.0 / eng.refresh_rate.max(1.0);
+        let bottom = y + GRAPH_HEIGHT;
+        for (i, &interval) in eng.overlay.intervals.iter().enumerate() {
+            let height = (interval / (2.0 * budget)).min(1.0) * GRAPH_HEIGHT;
+            let left = x + (HISTORY - eng.overlay.intervals.len() + i) as f32;
+            let bar = Rect::new(px(left), px(bottom - height), px(left + 1.0), px(bottom));
+            let color = if interval > budget * 1.2 {
+                Color::from_rgba8(240, 80, 80, 255)
+            } else {
+                Color::from_rgba8(90, 220, 120, 255)
+            };
+            eng.scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &bar);
+        }
+        let target = Rect::new(px(x), px(bottom - GRAPH_HEIGHT / 2.0), px(x + HISTORY as f32), px(bottom - GRAPH_HEIGHT / 2.0) + 1.0);
+        eng.scene.fill(Fill::NonZero, Affine::IDENTITY, Color::from_rgba8(255, 255, 255, 90), None, &target);
+        y = bottom + PADDING;
+    }
+
+    // Shaped uncached, so the overlay doesn't skew the cache stats it shows
+    let style = text::TextStyle::new(FONT_SIZE, -1);
+    for line in lines {
+        let shaped = text::shape_text(&mut eng.text_cx, &line, &style, PANEL_WIDTH, scale);
+        text::draw_layout(&mut eng.scene, &shaped, x * scale, y * scale, Color::WHITE, eng.text_cx.rendering);
+        y += LINE_HEIGHT;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_recent_intervals_and_cache_deltas() {
+        let mut overlay = DebugOverlay::default();
+        assert_eq!(overlay.fps(), None);
+        for i in 0..=HISTORY + 10 {
+            overlay.frame_started(i as f64 * 0.02);
+        }
+        assert_eq!(overlay.intervals.len(), HISTORY);
+        assert!((overlay.fps().unwrap() - 50.0).abs() < 0.01);
+
+        assert_eq!(overlay.cache_hit_rate(0, 0), None);
+        assert_eq!(overlay.cache_hit_rate(9, 1), Some(0.9));
+        assert_eq!(overlay.cache_hit_rate(10, 4), Some(0.25));
+        // Counters reset by the host
+        assert_eq!(overlay.cache_hit_rate(2, 2), None);
+        assert_eq!(overlay.cache_hit_rate(4, 2), Some(1.0));
+    }
+}
@@ -0,0 +1,420 @@
+/// GPU particle layer
+///
+/// Emitters simulate their particles in a compute shader and draw them as
+/// instanced soft sprites straight onto the surface, beneath or above the
+/// Vello layer. Meant for confetti and ambient effects that would be far too
+/// costly as per-frame vector commands. Positions are physical pixels.
+///
+/// With reduce motion on, nothing is emitted: bursts are dropped, looping
+/// emitters are held (not simulated or drawn) until it's turned off again,
+/// and no frames are requested for them.
+
+use std::collections::HashMap;
+
+/// Upper bound on particles per emitter
+pub const MAX_PARTICLES: u32 = 65_536;
+/// Longest simulation step, so a stalled frame doesn't fling particles away
+const MAX_DT: f32 = 0.1;
+const WORKGROUP_SIZE: u32 = 64;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParticleLayer {
+    /// Drawn under the Vello scene (visible through transparent areas)
+    Beneath,
+    /// Drawn over the Vello scene
+    Above,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct EmitterConfig {
+    pub x: f32,
+    pub y: f32,
+    pub count: u32,
+    /// Seconds each particle lives
+    pub lifetime: f32,
+    pub speed_min: f32,
+    pub speed_max: f32,
+    /// Radians, 0 = +x, y down
+    pub direction: f32,
+    /// Radians around `direction`
+    pub spread: f32,
+    /// px/s^2, positive = down
+    pub gravity: f32,
+    pub size: f32,
+    /// Color ramp over each particle's life (straight alpha)
+    pub colors: [[f32; 4]; 4],
+    /// Respawn particles forever instead of a single burst
+    pub looping: bool,
+    pub layer: ParticleLayer,
+}
+
+/// GPU layout of a particle (must match `Particle` in particles.wgsl)
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct GpuParticle {
+    pos: [f32; 2],
+    vel: [f32; 2],
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    seed: f32,
+}
+
+/// Must match `Emitter` in particles.wgsl
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct EmitterUniforms {
+    origin: [f32; 2],
+    speed: [f32; 2],
+    direction: f32,
+    spread: f32,
+    gravity: f32,
+    lifetime: f32,
+    size: f32,
+    dt: f32,
+    time: f32,
+    looping: u32,
+    viewport: [f32; 2],
+    count: u32,
+    _pad: u32,
+    colors: [[f32; 4]; 4],
+}
+
+struct Emitter {
+    config: EmitterConfig,
+    uniforms: wgpu::Buffer,
+    sim_bind_group: wgpu::BindGroup,
+    draw_bind_group: wgpu::BindGroup,
+    /// Seconds since the emitter started
+    elapsed: f32,
+}
+
+impl Emitter {
+    /// Bursts are done once their longest-lived particle has died
+    fn is_finished(&self) -> bool {
+        !self.config.looping && self.elapsed > self.config.lifetime
+    }
+}
+
+/// Initial particles: all unborn. Bursts are born on the first step; looping
+/// emitters stagger births across one lifetime so emission is continuous.
+fn initial_particles(config: &EmitterConfig) -> Vec<GpuParticle> {
+    (0..config.count)
+        .map(|i| {
+            let delay = if config.looping {
+                config.lifetime * i as f32 / config.count as f32
+            } else {
+                0.0
+            };
+            GpuParticle {
+                pos: [config.x, config.y],
+                vel: [0.0, 0.0],
+                age: -delay - f32::EPSILON,
+                lifetime: 0.0,
+                size: 0.0,
+                seed: 0.0,
+            }
+        })
+        .collect()
+}
+
+pub struct ParticleSystem {
+    sim_layout: wgpu::BindGroupLayout,
+    draw_layout: wgpu::BindGroupLayout,
+    sim_pipeline: wgpu::ComputePipeline,
+    draw_pipeline: wgpu::RenderPipeline,
+    emitters: HashMap<i32, Emitter>,
+    next_id: i32,
+    time: f64,
+    last_step: Option<f64>,
+    /// Reduce motion is on
+    held: bool,
+}
+
+impl ParticleSystem {
+    pub fn new(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Particle Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("particles.wgsl").into()),
+        });
+
+        let uniform_entry = |visibility| wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let storage_entry = |visibility, read_only| wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+
+        let sim_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Particle Sim Bind Group Layout"),
+            entries: &[
+                uniform_entry(wgpu::ShaderStages::COMPUTE),
+                storage_entry(wgpu::ShaderStages::COMPUTE, false),
+            ],
+        });
+        let draw_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Particle Draw Bind Group Layout"),
+            entries: &[
+                uniform_entry(wgpu::ShaderStages::VERTEX),
+                storage_entry(wgpu::ShaderStages::VERTEX, true),
+            ],
+        });
+
+        let sim_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Sim Pipeline Layout"),
+            bind_group_layouts: &[&sim_layout],
+            push_constant_ranges: &[],
+        });
+        let sim_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Particle Sim Pipeline"),
+            layout: Some(&sim_pipeline_layout),
+            module: &shader,
+            entry_point: Some("simulate"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let draw_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Draw Pipeline Layout"),
+            bind_group_layouts: &[&draw_layout],
+            push_constant_ranges: &[],
+        });
+        let draw_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Particle Draw Pipeline"),
+            layout: Some(&draw_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            sim_layout,
+            draw_layout,
+            sim_pipeline,
+            draw_pipeline,
+            emitters: HashMap::new(),
+            next_id: 0,
+            time: 0.0,
+            last_step: None,
+            held: false,
+        }
+    }
+
+    /// Start an emitter and return its ID
+    pub fn add_emitter(&mut self, device: &wgpu::Device, config: EmitterConfig) -> Result<i32, String> {
+        if config.count == 0 || config.count > MAX_PARTICLES {
+            return Err(format!("Particle count must be 1..={}, got {}", MAX_PARTICLES, config.count));
+        }
+        if config.lifetime <= 0.0 {
+            return Err("Particle lifetime must be positive".to_string());
+        }
+
+        use wgpu::util::DeviceExt;
+        let particles = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Particles"),
+            contents: bytemuck::cast_slice(&initial_particles(&config)),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Emitter"),
+            size: std::mem::size_of::<EmitterUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = |layout: &wgpu::BindGroupLayout, label| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(label),
+                layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: uniforms.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: particles.as_entire_binding(),
+                    },
+                ],
+            })
+        };
+        let sim_bind_group = bind_group(&self.sim_layout, "Particle Sim Bind Group");
+        let draw_bind_group = bind_group(&self.draw_layout, "Particle Draw Bind Group");
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.emitters.insert(
+            id,
+            Emitter {
+                config,
+                uniforms,
+                sim_bind_group,
+                draw_bind_group,
+                elapsed: 0.0,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Stop an emitter immediately, returning whether it existed
+    pub fn remove_emitter(&mut self, id: i32) -> bool {
+        self.emitters.remove(&id).is_some()
+    }
+
+    /// Set the frame clock (seconds, same as mcore_begin_frame) and the
+    /// system animation scale; at 0 emission stops (see the module docs)
+    pub fn set_time(&mut self, time: f64, animation_scale: f32) {
+        self.time = time;
+        self.held = animation_scale == 0.0;
+        if self.held {
+            self.emitters.retain(|_, emitter| emitter.config.looping);
+            // Looping emitters pick up where they were, not a step ahead
+            self.last_step = None;
+        }
+    }
+
+    /// Whether any emitter still has live particles (the host should keep drawing)
+    pub fn is_active(&self) -> bool {
+        !self.held && !self.emitters.is_empty()
+    }
+
+    pub fn has_layer(&self, layer: ParticleLayer) -> bool {
+        !self.held && self.emitters.values().any(|e| e.config.layer == layer)
+    }
+
+    /// Advance every emitter to the current time on the GPU and drop finished bursts
+    pub fn simulate(&mut self, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder, viewport: (u32, u32)) {
+        if self.held {
+            return;
+        }
+        let dt = match self.last_step {
+            Some(last) => ((self.time - last) as f32).clamp(0.0, MAX_DT),
+            None => 0.0,
+        };
+        self.last_step = Some(self.time);
+
+        self.emitters.retain(|_, emitter| !emitter.is_finished());
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Particle Sim Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.sim_pipeline);
+
+        for emitter in self.emitters.values_mut() {
+            emitter.elapsed += dt;
+            let c = &emitter.config;
+            let uniforms = EmitterUniforms {
+                origin: [c.x, c.y],
+                speed: [c.speed_min, c.speed_max],
+                direction: c.direction,
+                spread: c.spread,
+                gravity: c.gravity,
+                lifetime: c.lifetime,
+                size: c.size,
+                dt,
+                time: self.time as f32,
+                looping: c.looping as u32,
+                viewport: [viewport.0 as f32, viewport.1 as f32],
+                count: c.count,
+                _pad: 0,
+                colors: c.colors,
+            };
+            queue.write_buffer(&emitter.uniforms, 0, bytemuck::bytes_of(&uniforms));
+
+            pass.set_bind_group(0, &emitter.sim_bind_group, &[]);
+            pass.dispatch_workgroups(c.count.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+    }
+
+    /// Draw one layer's emitters into an existing render pass
+    pub fn draw(&self, pass: &mut wgpu::RenderPass<'_>, layer: ParticleLayer) {
+        if self.held {
+            return;
+        }
+        pass.set_pipeline(&self.draw_pipeline);
+        for emitter in self.emitters.values().filter(|e| e.config.layer == layer) {
+            pass.set_bind_group(0, &emitter.draw_bind_group, &[]);
+            pass.draw(0..6, 0..emitter.config.count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(looping: bool) -> EmitterConfig {
+        EmitterConfig {
+            x: 10.0,
+            y: 20.0,
+            count: 4,
+            lifetime: 2.0,
+            speed_min: 0.0,
+            speed_max: 0.0,
+            direction: 0.0,
+            spread: 0.0,
+            gravity: 0.0,
+            size: 1.0,
+            colors: [[1.0; 4]; 4],
+            looping,
+            layer: ParticleLayer::Above,
+        }
+    }
+
+    #[test]
+    fn test_uniform_layout_matches_shader() {
+        // WGSL: 64 bytes of scalars/vec2s, then array<vec4<f32>, 4>
+        assert_eq!(std::mem::size_of::<EmitterUniforms>(), 128);
+        assert_eq!(std::mem::size_of::<GpuParticle>(), 32);
+    }
+
+    #[test]
+    fn test_burst_particles_all_born_on_first_step() {
+        let particles = initial_particles(&config(false));
+        assert!(particles.iter().all(|p| p.age < 0.0 && p.age > -1e-3));
+    }
+
+    #[test]
+    fn test_looping_particles_are_staggered() {
+        let particles = initial_particles(&config(true));
+        let ages: Vec<f32> = particles.iter().map(|p| p.age).collect();
+        assert!(ages.windows(2).all(|w| w[1] < w[0]));
+        assert!(ages[3] > -2.0);
+    }
+}
@@ -0,0 +1,134 @@
+// GPU particle simulation + rendering
+// One dispatch and one instanced draw per emitter; positions are physical pixels.
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    // Negative while waiting to be born
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    seed: f32,
+}
+
+// Must match `EmitterUniforms` in particles.rs
+struct Emitter {
+    origin: vec2<f32>,
+    speed: vec2<f32>,   // min, max
+    direction: f32,     // radians, 0 = +x, y down
+    spread: f32,        // radians around direction
+    gravity: f32,       // px/s^2, positive = down
+    lifetime: f32,
+    size: f32,
+    dt: f32,
+    time: f32,
+    looping: u32,
+    viewport: vec2<f32>,
+    count: u32,
+    _pad: u32,
+    colors: array<vec4<f32>, 4>,  // color ramp over each particle's life
+}
+
+fn hash(n: f32) -> f32 {
+    return fract(sin(n) * 43758.5453);
+}
+
+fn spawn(i: u32, age: f32) -> Particle {
+    let s = f32(i) * 12.9898 + emitter_u.time * 78.233;
+    let angle = emitter_u.direction + (hash(s) - 0.5) * emitter_u.spread;
+    let speed = mix(emitter_u.speed.x, emitter_u.speed.y, hash(s + 1.0));
+
+    var p: Particle;
+    p.pos = emitter_u.origin;
+    p.vel = vec2<f32>(cos(angle), sin(angle)) * speed;
+    p.age = age;
+    p.lifetime = emitter_u.lifetime * mix(0.75, 1.0, hash(s + 2.0));
+    p.size = emitter_u.size * mix(0.5, 1.0, hash(s + 3.0));
+    p.seed = hash(s + 4.0);
+    return p;
+}
+
+@group(0) @binding(0) var<uniform> emitter_u: Emitter;
+
+// ----------------------------------------------------------------------------
+// Simulation
+// ----------------------------------------------------------------------------
+
+@group(0) @binding(1) var<storage, read_write> sim_particles: array<Particle>;
+
+@compute @workgroup_size(64)
+fn simulate(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let i = gid.x;
+    if (i >= emitter_u.count) {
+        return;
+    }
+
+    var p = sim_particles[i];
+    let was_unborn = p.age < 0.0;
+    p.age += emitter_u.dt;
+
+    if (was_unborn && p.age >= 0.0) {
+        p = spawn(i, p.age);
+    } else if (p.age >= p.lifetime && p.lifetime > 0.0 && emitter_u.looping != 0u) {
+        p = spawn(i, p.age - p.lifetime);
+    } else if (p.age >= 0.0) {
+        p.vel.y += emitter_u.gravity * emitter_u.dt;
+        p.pos += p.vel * emitter_u.dt;
+    }
+
+    sim_particles[i] = p;
+}
+
+// ----------------------------------------------------------------------------
+// Rendering
+// ----------------------------------------------------------------------------
+
+@group(0) @binding(1) var<storage, read> draw_particles: array<Particle>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vi: u32, @builtin(instance_index) ii: u32) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(-1.0, 1.0),
+        vec2<f32>(-1.0, 1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    let corner = corners[vi];
+    let p = draw_particles[ii];
+
+    let world = p.pos + corner * p.size * 0.5;
+    let ndc = vec2<f32>(
+        world.x / emitter_u.viewport.x * 2.0 - 1.0,
+        1.0 - world.y / emitter_u.viewport.y * 2.0,
+    );
+
+    // Sample the 4-stop ramp at this particle's normalized age
+    let t = clamp(p.age / max(p.lifetime, 1e-6), 0.0, 1.0) * 3.0;
+    let idx = min(u32(floor(t)), 2u);
+    var color = mix(emitter_u.colors[idx], emitter_u.colors[idx + 1u], t - f32(idx));
+    if (p.age < 0.0 || p.age >= p.lifetime) {
+        color = vec4<f32>(0.0);
+    }
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(ndc, 0.0, 1.0);
+    out.uv = corner;
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Soft round sprite
+    let d = length(in.uv);
+    let alpha = in.color.a * (1.0 - smoothstep(0.8, 1.0, d));
+    return vec4<f32>(in.color.rgb, alpha);
+}
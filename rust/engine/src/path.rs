@@ -0,0 +1,134 @@
+/// Path management module
+///
+/// Stores host-built vector paths by ID so they can be reused for clipping
+/// (and later filling) without re-sending the geometry every frame.
+
+use peniko::kurbo::{BezPath, Point};
+use std::collections::HashMap;
+
+/// Path verbs as sent over the FFI (one byte per verb)
+pub const VERB_MOVE_TO: u8 = 0;
+pub const VERB_LINE_TO: u8 = 1;
+pub const VERB_QUAD_TO: u8 = 2;
+pub const VERB_CUBIC_TO: u8 = 3;
+pub const VERB_CLOSE: u8 = 4;
+
+/// Build a BezPath from a verb list and a flat list of (x, y) point pairs.
+/// Each verb consumes 1 (move/line), 2 (quad), 3 (cubic) or 0 (close) points.
+pub fn build_path(verbs: &[u8], points: &[f32]) -> Result<BezPath, String> {
+    let mut path = BezPath::new();
+    let mut i = 0;
+
+    let mut take = |n: usize| -> Result<Vec<Point>, String> {
+        if i + n * 2 > points.len() {
+            return Err(format!("Path point data exhausted at index {}", i / 2));
+        }
+        let pts = (0..n)
+            .map(|k| Point::new(points[i + k * 2] as f64, points[i + k * 2 + 1] as f64))
+            .collect();
+        i += n * 2;
+        Ok(pts)
+    };
+
+    for &verb in verbs {
+        match verb {
+            VERB_MOVE_TO => {
+                let p = take(1)?;
+                path.move_to(p[0]);
+            }
+            VERB_LINE_TO => {
+                let p = take(1)?;
+                path.line_to(p[0]);
+            }
+            VERB_QUAD_TO => {
+                let p = take(2)?;
+                path.quad_to(p[0], p[1]);
+            }
+            VERB_CUBIC_TO => {
+                let p = take(3)?;
+                path.curve_to(p[0], p[1], p[2]);
+            }
+            VERB_CLOSE => path.close_path(),
+            _ => return Err(format!("Unknown path verb: {}", verb)),
+        }
+    }
+
+    Ok(path)
+}
+
+/// Registry of paths keyed by ID
+pub struct PathManager {
+    paths: HashMap<i32, BezPath>,
+    next_id: i32,
+}
+
+impl PathManager {
+    pub fn new() -> Self {
+        Self {
+            paths: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Register a path and return its ID
+    pub fn register(&mut self, path: BezPath) -> i32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.paths.insert(id, path);
+        id
+    }
+
+    pub fn get(&self, id: i32) -> Option<&BezPath> {
+        self.paths.get(&id)
+    }
+
+    /// Remove a path, returning whether it existed
+    pub fn remove(&mut self, id: i32) -> bool {
+        self.paths.remove(&id).is_some()
+    }
+}
+
+impl Default for PathManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use peniko::kurbo::Shape;
+
+    #[test]
+    fn test_build_triangle() {
+        let verbs = [VERB_MOVE_TO, VERB_LINE_TO, VERB_LINE_TO, VERB_CLOSE];
+        let points = [0.0, 0.0, 10.0, 0.0, 0.0, 10.0];
+        let path = build_path(&verbs, &points).unwrap();
+        assert_eq!(path.elements().len(), 4);
+        let bbox = path.bounding_box();
+        assert_eq!(bbox.width(), 10.0);
+        assert_eq!(bbox.height(), 10.0);
+    }
+
+    #[test]
+    fn test_build_missing_points() {
+        let verbs = [VERB_MOVE_TO, VERB_CUBIC_TO];
+        let points = [0.0, 0.0, 1.0, 1.0];
+        assert!(build_path(&verbs, &points).is_err());
+    }
+
+    #[test]
+    fn test_unknown_verb() {
+        assert!(build_path(&[42], &[]).is_err());
+    }
+
+    #[test]
+    fn test_register_remove() {
+        let mut manager = PathManager::new();
+        let id = manager.register(BezPath::new());
+        assert!(manager.get(id).is_some());
+        assert!(manager.remove(id));
+        assert!(manager.get(id).is_none());
+        assert!(!manager.remove(id));
+    }
+}
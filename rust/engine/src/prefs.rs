@@ -0,0 +1,98 @@
+/// System accessibility preferences
+///
+/// Queried from NSWorkspace on macOS. Engine-driven animations consult
+/// `SystemPrefs::animation_scale` so they settle instantly when the user has
+/// asked for reduced motion.
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SystemPrefs {
+    pub reduce_motion: bool,
+    pub increase_contrast: bool,
+    pub reduce_transparency: bool,
+}
+
+impl SystemPrefs {
+    /// Read the current preferences from the OS (all off on other platforms)
+    pub fn query() -> Self {
+        #[cfg(target_os = "macos")]
+        {
+            macos::query()
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            Self::default()
+        }
+    }
+
+    /// Multiplier for engine animation durations/velocities.
+    /// 0.0 means "jump straight to the end state".
+    pub fn animation_scale(&self) -> f32 {
+        if self.reduce_motion {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::SystemPrefs;
+    use std::ffi::{c_char, c_void};
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {}
+
+    unsafe fn send_id(receiver: *mut c_void, selector: &str) -> *mut c_void {
+        let sel = sel_registerName(selector.as_ptr() as *const c_char);
+        let f: unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        f(receiver, sel)
+    }
+
+    unsafe fn send_bool(receiver: *mut c_void, selector: &str) -> bool {
+        let sel = sel_registerName(selector.as_ptr() as *const c_char);
+        // BOOL is a single byte on both x86_64 and arm64
+        let f: unsafe extern "C" fn(*mut c_void, *mut c_void) -> u8 =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        f(receiver, sel) != 0
+    }
+
+    pub fn query() -> SystemPrefs {
+        unsafe {
+            let class = objc_getClass(c"NSWorkspace".as_ptr());
+            if class.is_null() {
+                return SystemPrefs::default();
+            }
+            let workspace = send_id(class, "sharedWorkspace\0");
+            if workspace.is_null() {
+                return SystemPrefs::default();
+            }
+            SystemPrefs {
+                reduce_motion: send_bool(workspace, "accessibilityDisplayShouldReduceMotion\0"),
+                increase_contrast: send_bool(workspace, "accessibilityDisplayShouldIncreaseContrast\0"),
+                reduce_transparency: send_bool(workspace, "accessibilityDisplayShouldReduceTransparency\0"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_animation_scale() {
+        let mut prefs = SystemPrefs::default();
+        assert_eq!(prefs.animation_scale(), 1.0);
+        prefs.reduce_motion = true;
+        assert_eq!(prefs.animation_scale(), 0.0);
+    }
+}
@@ -0,0 +1,158 @@
+/// Engine-owned render thread (mcore_render_thread_start)
+///
+/// Threading model: every FFI call locks its context, so calls from different
+/// threads serialize, but a frame (begin_frame, drawing, end_frame_present)
+/// must come from one thread at a time. Without a render thread that's the
+/// host's thread, which then blocks in presenting. With one, hosts queue whole
+/// frames (a v2 command stream with its time and clear color) from any thread
+/// with mcore_submit_frame, and the render thread builds, renders and presents
+/// them. Frames started or drawn from other threads are then rejected.
+///
+/// Only the newest queued frame matters: frames queued while the thread is
+/// busy replace each other, and the last one is rendered.
+
+use parking_lot::Mutex;
+use std::sync::mpsc;
+use std::sync::Weak;
+use std::thread::{self, JoinHandle};
+
+use crate::engine::{fire_log, Deferred, Engine, LogLevel};
+use crate::{McoreRgba, McoreStatus};
+
+pub struct QueuedFrame {
+    pub time_s: f64,
+    /// A v2 command stream, already validated
+    pub stream: Vec<u8>,
+    pub clear: McoreRgba,
+}
+
+enum Message {
+    Frame(QueuedFrame),
+    /// Answered once the frames queued before it are presented
+    Flush(mpsc::Sender<()>),
+}
+
+pub struct RenderThread {
+    sender: mpsc::Sender<Message>,
+    handle: JoinHandle<()>,
+}
+
+impl RenderThread {
+    /// The thread holds the engine weakly, so dropping the context still
+    /// frees it
+    pub fn spawn(engine: Weak<Mutex<Engine>>) -> std::io::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::Builder::new()
+            .name("mcore-render".into())
+            .spawn(move || run(receiver, |frame| render(&engine, frame)))?;
+        Ok(Self { sender, handle })
+    }
+
+    pub fn is_current(&self) -> bool {
+        self.handle.thread().id() == thread::current().id()
+    }
+
+    pub fn submit(&self, frame: QueuedFrame) {
+        let _ = self.sender.send(Message::Frame(frame));
+    }
+
+    /// A receiver that's answered once every frame queued so far is presented
+    pub fn flush(&self) -> mpsc::Receiver<()> {
+        let (done, receiver) = mpsc::channel();
+        let _ = self.sender.send(Message::Flush(done));
+        receiver
+    }
+
+    /// Present the last queued frame and end the thread. Call without the
+    /// engine locked.
+    pub fn stop(self) {
+        let current = self.is_current();
+        let Self { sender, handle } = self;
+        drop(sender);
+        if !current {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Render the newest frame whenever the queue runs dry, until every sender
+/// is gone
+fn run(receiver: mpsc::Receiver<Message>, mut render: impl FnMut(QueuedFrame)) {
+    let mut pending = None;
+    loop {
+        // Block only when there's nothing left to render
+        let message = match pending {
+            None => receiver.recv().ok(),
+            Some(_) => receiver.try_recv().ok(),
+        };
+        match message {
+            Some(Message::Frame(frame)) => pending = Some(frame),
+            Some(Message::Flush(done)) => {
+                if let Some(frame) = pending.take() {
+                    render(frame);
+                }
+                let _ = done.send(());
+            }
+            None => match pending.take() {
+                Some(frame) => render(frame),
+                None => break,
+            },
+        }
+    }
+}
+
+/// Build and present one frame with the engine locked throughout, through
+/// the same code as the host's frame calls. Failures go to the log callback
+/// since there's no caller to return them to.
+fn render(engine: &Weak<Mutex<Engine>>, frame: QueuedFrame) {
+    let Some(engine) = engine.upgrade() else {
+        return;
+    };
+    let mut deferred = Deferred::default();
+    // A panic fails the frame rather than ending the thread
+    let status = crate::ffi_boundary("render thread", || {
+        let mut eng = engine.lock();
+        let status = crate::begin_frame(&mut eng, frame.time_s, &mut deferred);
+        let mut failed = !matches!(status, McoreStatus::Ok | McoreStatus::FrameInProgress);
+        failed |= !matches!(crate::render_commands_v2(&mut eng, &frame.stream), McoreStatus::Ok);
+        let status = crate::end_frame_present(&mut eng, frame.clear, &mut deferred);
+        failed |= !matches!(status, McoreStatus::Ok | McoreStatus::FrameSkipped);
+        if failed { McoreStatus::Err } else { McoreStatus::Ok }
+    });
+    deferred.fire();
+
+    if !matches!(status, McoreStatus::Ok) {
+        let message = crate::take_err().unwrap_or_else(|| "render thread frame failed".into());
+        let log_callback = engine.lock().log_callback;
+        fire_log(log_callback, LogLevel::Error, &format!("render thread: {message}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(time_s: f64) -> QueuedFrame {
+        QueuedFrame {
+            time_s,
+            stream: Vec::new(),
+            clear: McoreRgba { r: 0.0, g: 0.0, b: 0.0, a: 1.0 },
+        }
+    }
+
+    #[test]
+    fn renders_newest_frame_and_answers_flushes() {
+        let (sender, receiver) = mpsc::channel();
+        let (done, flushed) = mpsc::channel();
+        sender.send(Message::Frame(frame(1.0))).unwrap();
+        sender.send(Message::Frame(frame(2.0))).unwrap();
+        sender.send(Message::Flush(done)).unwrap();
+        sender.send(Message::Frame(frame(3.0))).unwrap();
+        drop(sender);
+
+        let mut rendered = Vec::new();
+        run(receiver, |frame| rendered.push(frame.time_s));
+        assert_eq!(rendered, [2.0, 3.0], "stale frames replaced, last one rendered on stop");
+        assert!(flushed.try_recv().is_ok());
+    }
+}
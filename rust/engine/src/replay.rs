@@ -0,0 +1,649 @@
+//! Command buffer recording and replay for pixel-diff testing
+//!
+//! A recording captures every frame's mcore_render_commands buffers plus the
+//! clear color and surface size. Replaying it renders the same frames on a
+//! headless context, so two engine builds can be compared pixel for pixel
+//! (see the `mcore-replay` binary and `cargo xtask replay-diff`).
+//!
+//! Only command buffers are recorded: direct draw calls (mcore_text_draw,
+//! images, paths) and registered font blobs are not, so text that used a
+//! registered font replays with the system font.
+//!
+//! Scene dumps (mcore_scene_dump) are one-frame recordings in the same format,
+//! taken from the FrameCapture every engine keeps of its current frame, so a
+//! rendering bug can be attached to a report and replayed without the host.
+
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use peniko::Color;
+
+use crate::engine::Engine;
+use crate::gfx::{Gfx, GpuOptions, MacSurface};
+use crate::McoreDrawCommand;
+
+const MAGIC: &[u8; 4] = b"MCRP";
+/// Version 2 added text_direction
+const VERSION: u32 = 2;
+/// Marks a command without text
+const NO_TEXT: u32 = u32::MAX;
+
+/// An owned copy of an McoreDrawCommand
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedCommand {
+    pub kind: u8,
+    pub rect: [f32; 5], // x, y, width, height, radius
+    pub color: [f32; 4],
+    pub text: Option<String>,
+    pub font_size: f32,
+    pub wrap_width: f32,
+    pub font_id: i32,
+    pub border_width: f32,
+    pub border_color: [f32; 4],
+    pub has_border: u8,
+    pub shadow: [f32; 3], // offset x, offset y, blur
+    pub shadow_color: [f32; 4],
+    pub has_shadow: u8,
+    pub text_align: u8,
+    pub text_direction: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedFrame {
+    pub time_s: f64,
+    pub width_px: u32,
+    pub height_px: u32,
+    pub scale: f32,
+    pub clear: [f32; 4],
+    pub commands: Vec<RecordedCommand>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// Collects frames while recording is on (Engine::recorder)
+pub(crate) struct Recorder {
+    path: PathBuf,
+    recording: Recording,
+    pending: Vec<RecordedCommand>,
+    time_s: f64,
+}
+
+impl Recorder {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            recording: Recording::default(),
+            pending: Vec::new(),
+            time_s: 0.0,
+        }
+    }
+
+    pub fn begin_frame(&mut self, time_s: f64) {
+        self.pending.clear();
+        self.time_s = time_s;
+    }
+
+    pub fn record_commands(&mut self, commands: &[McoreDrawCommand]) {
+        self.pending.extend(commands.iter().map(RecordedCommand::from_ffi));
+    }
+
+    pub fn end_frame(&mut self, gfx: &Gfx, clear: Color) {
+        let (width_px, height_px) = gfx.size();
+        self.recording.frames.push(RecordedFrame {
+            time_s: self.time_s,
+            width_px,
+            height_px,
+            scale: gfx.scale(),
+            clear: clear.components,
+            commands: std::mem::take(&mut self.pending),
+        });
+    }
+
+    /// Write the recording to its file
+    pub fn finish(self) -> io::Result<usize> {
+        fs::write(&self.path, self.recording.encode())?;
+        Ok(self.recording.frames.len())
+    }
+}
+
+/// The current frame's command buffers (Engine::frame_capture), kept for
+/// mcore_scene_dump. Texts share one buffer and every buffer is reused from
+/// frame to frame, so keeping it costs a copy per command, not an allocation.
+/// Holds the frame being built, or the last presented one between frames.
+#[derive(Default)]
+pub(crate) struct FrameCapture {
+    time_s: f64,
+    clear: [f32; 4],
+    /// Commands with their text left out, and where it is in `text`
+    commands: Vec<(RecordedCommand, Option<Range<usize>>)>,
+    text: String,
+}
+
+impl FrameCapture {
+    pub fn begin_frame(&mut self, time_s: f64) {
+        self.time_s = time_s;
+        self.commands.clear();
+        self.text.clear();
+    }
+
+    pub fn record_commands(&mut self, commands: &[McoreDrawCommand]) {
+        for cmd in commands {
+            let range = command_text(cmd).map(|text| {
+                let start = self.text.len();
+                self.text.push_str(&text);
+                start..self.text.len()
+            });
+            self.commands.push((RecordedCommand::from_ffi_with_text(cmd, None), range));
+        }
+    }
+
+    pub fn end_frame(&mut self, clear: Color) {
+        self.clear = clear.components;
+    }
+
+    /// The frame so far as a one-frame recording. The clear color is the last
+    /// presented frame's until this one is presented.
+    pub fn to_recording(&self, gfx: &Gfx) -> Recording {
+        let (width_px, height_px) = gfx.size();
+        let commands = self
+            .commands
+            .iter()
+            .map(|(cmd, range)| RecordedCommand {
+                text: range.clone().map(|range| self.text[range].to_string()),
+                ..cmd.clone()
+            })
+            .collect();
+        Recording {
+            frames: vec![RecordedFrame {
+                time_s: self.time_s,
+                width_px,
+                height_px,
+                scale: gfx.scale(),
+                clear: self.clear,
+                commands,
+            }],
+        }
+    }
+}
+
+fn command_text(cmd: &McoreDrawCommand) -> Option<std::borrow::Cow<'_, str>> {
+    (!cmd.text_ptr.is_null()).then(|| unsafe { std::ffi::CStr::from_ptr(cmd.text_ptr) }.to_string_lossy())
+}
+
+impl RecordedCommand {
+    fn from_ffi(cmd: &McoreDrawCommand) -> Self {
+        Self::from_ffi_with_text(cmd, command_text(cmd).map(|text| text.into_owned()))
+    }
+
+    fn from_ffi_with_text(cmd: &McoreDrawCommand, text: Option<String>) -> Self {
+        Self {
+            kind: cmd.kind,
+            rect: [cmd.x, cmd.y, cmd.width, cmd.height, cmd.radius],
+            color: cmd.color,
+            text,
+            font_size: cmd.font_size,
+            wrap_width: cmd.wrap_width,
+            font_id: cmd.font_id,
+            border_width: cmd.border_width,
+            border_color: cmd.border_color,
+            has_border: cmd.has_border,
+            shadow: [cmd.shadow_offset_x, cmd.shadow_offset_y, cmd.shadow_blur],
+            shadow_color: cmd.shadow_color,
+            has_shadow: cmd.has_shadow,
+            text_align: cmd.text_align,
+            text_direction: cmd.text_direction,
+        }
+    }
+
+    /// FFI form; text pointers borrow from `strings`, which must outlive the result
+    pub(crate) fn to_ffi(&self, strings: &mut Vec<CString>) -> McoreDrawCommand {
+        let text_ptr = match &self.text {
+            Some(text) => {
+                let text = CString::new(text.replace('\0', "")).expect("nul bytes were removed");
+                let ptr = text.as_ptr();
+                strings.push(text);
+                ptr
+            }
+            None => std::ptr::null(),
+        };
+        let [x, y, width, height, radius] = self.rect;
+        let [shadow_offset_x, shadow_offset_y, shadow_blur] = self.shadow;
+        McoreDrawCommand {
+            kind: self.kind,
+            x,
+            y,
+            width,
+            height,
+            radius,
+            color: self.color,
+            text_ptr,
+            font_size: self.font_size,
+            wrap_width: self.wrap_width,
+            font_id: self.font_id,
+            border_width: self.border_width,
+            border_color: self.border_color,
+            has_border: self.has_border,
+            shadow_offset_x,
+            shadow_offset_y,
+            shadow_blur,
+            shadow_color: self.shadow_color,
+            has_shadow: self.has_shadow,
+            text_align: self.text_align,
+            text_direction: self.text_direction,
+        }
+    }
+}
+
+// ============================================================================
+// File format: little-endian, MAGIC + VERSION, then frames until end of file
+// ============================================================================
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::decode(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut w = Writer(Vec::new());
+        w.0.extend_from_slice(MAGIC);
+        w.u32(VERSION);
+        for frame in &self.frames {
+            w.f64(frame.time_s);
+            w.u32(frame.width_px);
+            w.u32(frame.height_px);
+            w.f32(frame.scale);
+            w.f32s(&frame.clear);
+            w.u32(frame.commands.len() as u32);
+            for cmd in &frame.commands {
+                w.0.push(cmd.kind);
+                w.f32s(&cmd.rect);
+                w.f32s(&cmd.color);
+                match &cmd.text {
+                    Some(text) => {
+                        w.u32(text.len() as u32);
+                        w.0.extend_from_slice(text.as_bytes());
+                    }
+                    None => w.u32(NO_TEXT),
+                }
+                w.f32(cmd.font_size);
+                w.f32(cmd.wrap_width);
+                w.u32(cmd.font_id as u32);
+                w.f32(cmd.border_width);
+                w.f32s(&cmd.border_color);
+                w.0.push(cmd.has_border);
+                w.f32s(&cmd.shadow);
+                w.f32s(&cmd.shadow_color);
+                w.0.push(cmd.has_shadow);
+                w.0.push(cmd.text_align);
+                w.0.push(cmd.text_direction);
+            }
+        }
+        w.0
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut r = Reader { bytes, pos: 0 };
+        if r.take(4)? != MAGIC {
+            return Err("not an mcore recording".into());
+        }
+        let version = r.u32()?;
+        if !(1..=VERSION).contains(&version) {
+            return Err(format!("unsupported recording version {}", version));
+        }
+
+        let mut frames = Vec::new();
+        while r.pos < bytes.len() {
+            let time_s = r.f64()?;
+            let width_px = r.u32()?;
+            let height_px = r.u32()?;
+            let scale = r.f32()?;
+            let clear = r.f32s()?;
+            let count = r.u32()?;
+            let mut commands = Vec::with_capacity(count.min(4096) as usize);
+            for _ in 0..count {
+                let kind = r.u8()?;
+                let rect = r.f32s()?;
+                let color = r.f32s()?;
+                let text = match r.u32()? {
+                    NO_TEXT => None,
+                    len => Some(String::from_utf8_lossy(r.take(len as usize)?).into_owned()),
+                };
+                commands.push(RecordedCommand {
+                    kind,
+                    rect,
+                    color,
+                    text,
+                    font_size: r.f32()?,
+                    wrap_width: r.f32()?,
+                    font_id: r.u32()? as i32,
+                    border_width: r.f32()?,
+                    border_color: r.f32s()?,
+                    has_border: r.u8()?,
+                    shadow: r.f32s()?,
+                    shadow_color: r.f32s()?,
+                    has_shadow: r.u8()?,
+                    text_align: r.u8()?,
+                    text_direction: if version >= 2 { r.u8()? } else { 0 },
+                });
+            }
+            frames.push(RecordedFrame { time_s, width_px, height_px, scale, clear, commands });
+        }
+
+        Ok(Self { frames })
+    }
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u32(&mut self, v: u32) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn f32(&mut self, v: f32) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn f64(&mut self, v: f64) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn f32s(&mut self, v: &[f32]) {
+        for &x in v {
+            self.f32(x);
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len());
+        let end = end.ok_or("truncated recording")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f64(&mut self) -> Result<f64, String> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f32s<const N: usize>(&mut self) -> Result<[f32; N], String> {
+        let mut out = [0.0; N];
+        for x in &mut out {
+            *x = self.f32()?;
+        }
+        Ok(out)
+    }
+}
+
+// ============================================================================
+// Replay
+// ============================================================================
+
+/// An RGBA8 frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Render every frame of a recording on a headless context
+pub fn render(recording: &Recording) -> Result<Vec<Frame>, String> {
+    let Some(first) = recording.frames.first() else {
+        return Ok(Vec::new());
+    };
+
+    // CI machines often have no GPU
+    let options = GpuOptions { software_fallback: true, ..Default::default() };
+    let gfx = pollster::block_on(Gfx::new_headless(first.width_px, first.height_px, first.scale, options))
+        .map_err(|e| e.to_string())?;
+    let mut engine = Engine::new(gfx);
+    let mut frames = Vec::with_capacity(recording.frames.len());
+
+    for frame in &recording.frames {
+        if engine.gfx.size() != (frame.width_px, frame.height_px) || engine.gfx.scale() != frame.scale {
+            let surface = MacSurface {
+                ns_view: std::ptr::null_mut(),
+                ca_metal_layer: std::ptr::null_mut(),
+                scale_factor: frame.scale,
+                width_px: frame.width_px as i32,
+                height_px: frame.height_px as i32,
+            };
+            engine.gfx.resize(&surface).map_err(|e| e.to_string())?;
+        }
+
+        engine.time_s = frame.time_s;
+        engine.scene.reset();
+        engine.hits.begin_frame();
+
+        let mut strings = Vec::new();
+        let commands: Vec<_> = frame.commands.iter().map(|cmd| cmd.to_ffi(&mut strings)).collect();
+        crate::commands::draw(&mut engine, &commands);
+
+        let scene = engine.scene.clone();
+        engine
+            .gfx
+            .render_scene(&scene, Color::new(frame.clear))
+            .map_err(|e| e.to_string())?;
+        frames.push(Frame {
+            width: frame.width_px,
+            height: frame.height_px,
+            pixels: engine.gfx.read_pixels().map_err(|e| e.to_string())?,
+        });
+    }
+
+    Ok(frames)
+}
+
+// ============================================================================
+// Diffing
+// ============================================================================
+
+pub struct FrameDiff {
+    /// Pixels whose largest channel difference exceeds the tolerance
+    pub mismatched: usize,
+    pub max_delta: u8,
+    /// Baseline dimmed to grey with mismatched pixels in red
+    pub image: Frame,
+}
+
+/// Compare two frames; a size mismatch counts every pixel as different
+pub fn diff(a: &Frame, b: &Frame, tolerance: u8) -> FrameDiff {
+    if (a.width, a.height) != (b.width, b.height) {
+        return FrameDiff {
+            mismatched: (a.width * a.height).max(b.width * b.height) as usize,
+            max_delta: u8::MAX,
+            image: a.clone(),
+        };
+    }
+
+    let mut mismatched = 0;
+    let mut max_delta = 0;
+    let mut pixels = Vec::with_capacity(a.pixels.len());
+
+    for (pa, pb) in a.pixels.chunks_exact(4).zip(b.pixels.chunks_exact(4)) {
+        let delta = pa.iter().zip(pb).map(|(x, y)| x.abs_diff(*y)).max().unwrap_or(0);
+        max_delta = max_delta.max(delta);
+        if delta > tolerance {
+            mismatched += 1;
+            pixels.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let luma = ((pa[0] as u32 * 3 + pa[1] as u32 * 6 + pa[2] as u32) / 10) as u8;
+            let dimmed = 128 + luma / 2;
+            pixels.extend_from_slice(&[dimmed, dimmed, dimmed, 255]);
+        }
+    }
+
+    FrameDiff {
+        mismatched,
+        max_delta,
+        image: Frame { width: a.width, height: a.height, pixels },
+    }
+}
+
+// ============================================================================
+// Frame files: PAM (P7, RGB_ALPHA), readable without an image codec
+// ============================================================================
+
+pub fn write_pam(path: &Path, frame: &Frame) -> io::Result<()> {
+    let mut bytes = format!(
+        "P7\nWIDTH {}\nHEIGHT {}\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n",
+        frame.width, frame.height
+    )
+    .into_bytes();
+    bytes.extend_from_slice(&frame.pixels);
+    fs::write(path, bytes)
+}
+
+pub fn read_pam(path: &Path) -> Result<Frame, String> {
+    let bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse_pam(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn parse_pam(bytes: &[u8]) -> Result<Frame, String> {
+    const END: &[u8] = b"ENDHDR\n";
+    let header_end = bytes
+        .windows(END.len())
+        .position(|w| w == END)
+        .ok_or("missing PAM header")?;
+    let header = std::str::from_utf8(&bytes[..header_end]).map_err(|_| "bad PAM header")?;
+    if !header.starts_with("P7\n") {
+        return Err("not a PAM file".into());
+    }
+
+    let field = |name: &str| -> Result<u32, String> {
+        header
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.trim().parse().ok())
+            .ok_or_else(|| format!("missing {}", name))
+    };
+    let (width, height) = (field("WIDTH")?, field("HEIGHT")?);
+    if field("DEPTH")? != 4 || field("MAXVAL")? != 255 {
+        return Err("only 8-bit RGBA PAM files are supported".into());
+    }
+
+    let pixels = bytes[header_end + END.len()..].to_vec();
+    if pixels.len() != (width * height * 4) as usize {
+        return Err("PAM pixel data doesn't match its size".into());
+    }
+    Ok(Frame { width, height, pixels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(text: Option<&str>) -> RecordedCommand {
+        RecordedCommand {
+            kind: 1,
+            rect: [1.0, 2.0, 3.0, 4.0, 5.0],
+            color: [0.1, 0.2, 0.3, 1.0],
+            text: text.map(String::from),
+            font_size: 14.0,
+            wrap_width: 200.0,
+            font_id: -1,
+            border_width: 1.0,
+            border_color: [0.0; 4],
+            has_border: 1,
+            shadow: [0.0, 2.0, 4.0],
+            shadow_color: [0.0, 0.0, 0.0, 0.5],
+            has_shadow: 0,
+            text_align: 2,
+            text_direction: 2,
+        }
+    }
+
+    #[test]
+    fn test_recording_round_trip() {
+        let recording = Recording {
+            frames: vec![RecordedFrame {
+                time_s: 1.5,
+                width_px: 800,
+                height_px: 600,
+                scale: 2.0,
+                clear: [1.0, 1.0, 1.0, 1.0],
+                commands: vec![command(Some("héllo")), command(None)],
+            }],
+        };
+        assert_eq!(Recording::decode(&recording.encode()).unwrap(), recording);
+    }
+
+    #[test]
+    fn test_frame_capture_reuses_text_buffer() {
+        let mut capture = FrameCapture::default();
+        let mut strings = Vec::new();
+        let commands: Vec<_> = [Some("one"), None, Some("two")]
+            .into_iter()
+            .map(|text| command(text).to_ffi(&mut strings))
+            .collect();
+
+        capture.begin_frame(1.0);
+        capture.record_commands(&commands);
+        assert_eq!(capture.text, "onetwo");
+        let texts: Vec<_> = capture.commands.iter().map(|(cmd, range)| (cmd.text.clone(), range.clone())).collect();
+        assert_eq!(texts, [(None, Some(0..3)), (None, None), (None, Some(3..6))]);
+
+        capture.begin_frame(2.0);
+        capture.record_commands(&commands[1..]);
+        assert_eq!(capture.text, "two");
+        assert_eq!(capture.commands.len(), 2);
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_input() {
+        assert!(Recording::decode(b"nope").is_err());
+
+        let mut bytes = Recording { frames: vec![] }.encode();
+        bytes.extend_from_slice(&[0, 0, 0]);
+        assert!(Recording::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_diff_counts_pixels_past_tolerance() {
+        let a = Frame { width: 2, height: 1, pixels: vec![10, 10, 10, 255, 0, 0, 0, 255] };
+        let b = Frame { width: 2, height: 1, pixels: vec![12, 10, 10, 255, 0, 90, 0, 255] };
+
+        let result = diff(&a, &b, 2);
+        assert_eq!(result.mismatched, 1);
+        assert_eq!(result.max_delta, 90);
+        assert_eq!(&result.image.pixels[4..], &[255, 0, 0, 255]);
+
+        assert_eq!(diff(&a, &b, 90).mismatched, 0);
+    }
+
+    #[test]
+    fn test_pam_round_trip() {
+        let frame = Frame { width: 2, height: 2, pixels: (0..16).collect() };
+        let path = std::env::temp_dir().join(format!("mcore-replay-test-{}.pam", std::process::id()));
+        write_pam(&path, &frame).unwrap();
+        let read = read_pam(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(read, frame);
+    }
+}
@@ -0,0 +1,93 @@
+/// Redraw scheduling
+///
+/// Engine-internal animations (scroll momentum, long-press timers) and the host
+/// request frames at absolute times on the `mcore_begin_frame` clock. The host
+/// is only notified when the earliest deadline moves earlier than what it was
+/// last told, so it can sleep between frames instead of polling every vsync.
+
+#[derive(Default)]
+pub struct FrameScheduler {
+    /// Earliest requested frame time
+    deadline: Option<f64>,
+    /// Last deadline reported to the host
+    notified: Option<f64>,
+}
+
+impl FrameScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask for a frame at or after `time`. Times in the past mean "next vsync".
+    pub fn request_at(&mut self, time: f64) {
+        self.deadline = Some(self.deadline.map_or(time, |d| d.min(time)));
+    }
+
+    /// A frame started at `time`: every deadline up to it is satisfied
+    pub fn frame_started(&mut self, time: f64) {
+        if self.deadline.is_some_and(|d| d <= time) {
+            self.deadline = None;
+        }
+        if self.notified.is_some_and(|n| n <= time) {
+            self.notified = None;
+        }
+    }
+
+    pub fn deadline(&self) -> Option<f64> {
+        self.deadline
+    }
+
+    /// The deadline to report, if the host hasn't already been told about it
+    /// (or an earlier one)
+    pub fn take_notification(&mut self) -> Option<f64> {
+        let deadline = self.deadline?;
+        if self.notified.is_some_and(|n| n <= deadline) {
+            return None;
+        }
+        self.notified = Some(deadline);
+        Some(deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_earliest_request_wins() {
+        let mut s = FrameScheduler::new();
+        s.request_at(2.0);
+        s.request_at(1.0);
+        s.request_at(3.0);
+        assert_eq!(s.deadline(), Some(1.0));
+    }
+
+    #[test]
+    fn test_notifies_only_when_deadline_moves_earlier() {
+        let mut s = FrameScheduler::new();
+        s.request_at(2.0);
+        assert_eq!(s.take_notification(), Some(2.0));
+        assert_eq!(s.take_notification(), None);
+
+        // Later request doesn't change the deadline
+        s.request_at(3.0);
+        assert_eq!(s.take_notification(), None);
+
+        s.request_at(1.5);
+        assert_eq!(s.take_notification(), Some(1.5));
+    }
+
+    #[test]
+    fn test_frame_satisfies_deadline() {
+        let mut s = FrameScheduler::new();
+        s.request_at(1.0);
+        assert_eq!(s.take_notification(), Some(1.0));
+
+        s.frame_started(1.0);
+        assert_eq!(s.deadline(), None);
+
+        // The next request is reported again
+        s.request_at(1.0);
+        assert_eq!(s.take_notification(), Some(1.0));
+    }
+}
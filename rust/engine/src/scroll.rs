@@ -0,0 +1,637 @@
+/// Scroll physics
+///
+/// Engine-side momentum and rubber-band overscroll for scroll containers. The
+/// host reports drags (translation since the press, as produced by the gesture
+/// recognizer) and the final pointer velocity; the engine decelerates flings,
+/// resists dragging past the edges and springs back, reporting each time an
+/// edge is hit so hosts can layer pull-to-refresh or edge glows on top.
+/// Containers can also snap to item boundaries, with flings retargeted to land
+/// exactly on the nearest snap point. Trackpad scrolls (phased wheel events)
+/// drive the same drag and fling, so the engine supplies the momentum instead
+/// of the OS. All values are logical pixels and seconds.
+
+use std::collections::HashMap;
+
+/// Velocity multiplier per millisecond while flinging (UIScrollView "normal")
+pub const DECELERATION_RATE: f32 = 0.998;
+/// Rubber-band resistance; lower is stiffer
+pub const RUBBER_BAND_COEFFICIENT: f32 = 0.55;
+/// Stiffness of the spring that returns overscrolled content to the edge
+const SPRING_STIFFNESS: f32 = 170.0;
+/// Below this speed (px/s) a fling stops
+const MIN_VELOCITY: f32 = 5.0;
+/// Within this distance (px) of its target a settling spring snaps
+const SETTLE_DISTANCE: f32 = 0.5;
+/// Longest step integrated at once, so a stalled frame doesn't explode the spring
+const MAX_STEP: f32 = 1.0 / 30.0;
+/// A trackpad gesture that ends this long (s) after its last delta was held
+/// still, so it doesn't fling
+const FLING_WINDOW: f64 = 0.1;
+
+/// Phase of a scroll event
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScrollPhase {
+    /// A discrete mouse wheel step
+    Wheel,
+    /// Fingers touched the trackpad
+    Began,
+    Changed,
+    /// Fingers lifted: fling with the gesture's velocity
+    Ended,
+    /// The gesture was interrupted: settle without a fling
+    Cancelled,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Edge {
+    Top = 0,
+    Bottom = 1,
+    Left = 2,
+    Right = 3,
+}
+
+/// An edge was hit (content started overscrolling past it)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EdgeEvent {
+    pub id: u64,
+    pub edge: Edge,
+    /// Content velocity when the edge was hit (px/s, 0 while dragging)
+    pub velocity: f32,
+}
+
+/// UIScrollView-style rubber band: resistance grows with distance, never
+/// exceeding `dimension`
+pub fn rubber_band(overscroll: f32, dimension: f32) -> f32 {
+    if dimension <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - 1.0 / (overscroll * RUBBER_BAND_COEFFICIENT / dimension + 1.0)) * dimension
+}
+
+/// Resting positions for one axis
+#[derive(Clone, Debug, PartialEq)]
+pub enum SnapPoints {
+    /// Every multiple of an item extent (plus the end of the content)
+    Interval(f32),
+    /// Explicit offsets
+    Offsets(Vec<f32>),
+}
+
+impl SnapPoints {
+    /// Snap point closest to `offset`, within [0, max]
+    pub fn nearest(&self, offset: f32, max: f32) -> f32 {
+        let candidates: Vec<f32> = match self {
+            SnapPoints::Interval(interval) if *interval > 0.0 => {
+                vec![(offset / interval).round() * interval, max]
+            }
+            SnapPoints::Interval(_) => vec![offset],
+            SnapPoints::Offsets(offsets) if !offsets.is_empty() => offsets.clone(),
+            SnapPoints::Offsets(_) => vec![offset],
+        };
+        candidates
+            .into_iter()
+            .map(|c| c.clamp(0.0, max))
+            .min_by(|a, b| (a - offset).abs().total_cmp(&(b - offset).abs()))
+            .unwrap_or(0.0)
+    }
+}
+
+/// Exponential decay constant of a fling (per second)
+fn decay_constant() -> f32 {
+    -1000.0 * DECELERATION_RATE.ln()
+}
+
+#[derive(Clone, Debug, Default)]
+struct Axis {
+    offset: f32,
+    velocity: f32,
+    viewport: f32,
+    content: f32,
+    drag_origin: f32,
+    edge: Option<Edge>,
+    snap: Option<SnapPoints>,
+    /// Where the current fling will come to rest when snapping
+    snap_target: Option<f32>,
+}
+
+impl Axis {
+    fn max(&self) -> f32 {
+        (self.content - self.viewport).max(0.0)
+    }
+
+    /// Nearest in-bounds offset
+    fn target(&self) -> f32 {
+        self.offset.clamp(0.0, self.max())
+    }
+
+    fn is_overscrolled(&self) -> bool {
+        self.offset < 0.0 || self.offset > self.max()
+    }
+
+    fn is_animating(&self) -> bool {
+        self.velocity != 0.0 || self.is_overscrolled()
+    }
+
+    fn drag_to(&mut self, translation: f32) {
+        let raw = self.drag_origin - translation;
+        let max = self.max();
+        self.offset = if raw < 0.0 {
+            -rubber_band(-raw, self.viewport)
+        } else if raw > max {
+            max + rubber_band(raw - max, self.viewport)
+        } else {
+            raw
+        };
+        self.velocity = 0.0;
+    }
+
+    fn step(&mut self, dt: f32) {
+        if self.is_overscrolled() {
+            // Critically damped spring toward the edge
+            let target = self.target();
+            let before = self.offset - target;
+            let damping = 2.0 * SPRING_STIFFNESS.sqrt();
+            let accel = -SPRING_STIFFNESS * before - damping * self.velocity;
+            self.velocity += accel * dt;
+            self.offset += self.velocity * dt;
+
+            // Snap when close, or when a large step carried us past the edge
+            let after = self.offset - target;
+            let crossed = before.signum() != after.signum();
+            if crossed || (after.abs() < SETTLE_DISTANCE && self.velocity.abs() < MIN_VELOCITY) {
+                self.offset = target;
+                self.velocity = 0.0;
+            }
+        } else if self.velocity != 0.0 {
+            self.velocity *= DECELERATION_RATE.powf(dt * 1000.0);
+            self.offset += self.velocity * dt;
+
+            if self.velocity.abs() < MIN_VELOCITY {
+                self.velocity = 0.0;
+                if let Some(target) = self.snap_target.take() {
+                    self.offset = target;
+                }
+            }
+        }
+    }
+
+    /// Start a fling. With snapping, the velocity is adjusted so the natural
+    /// deceleration comes to rest exactly on the snap point nearest to where
+    /// the fling would have ended.
+    fn fling(&mut self, velocity: f32) {
+        self.velocity = velocity;
+        self.snap_target = None;
+
+        if self.is_overscrolled() {
+            return;
+        }
+        if let Some(snap) = &self.snap {
+            let projected = self.offset + velocity / decay_constant();
+            let target = snap.nearest(projected, self.max());
+            self.snap_target = Some(target);
+            self.velocity = (target - self.offset) * decay_constant();
+            if self.velocity.abs() < MIN_VELOCITY {
+                self.offset = target;
+                self.velocity = 0.0;
+                self.snap_target = None;
+            }
+        }
+    }
+
+    /// Stop all motion and rest in bounds (on the pending snap point, if any)
+    fn settle(&mut self) {
+        self.offset = self.snap_target.take().unwrap_or_else(|| self.target());
+        self.velocity = 0.0;
+    }
+
+    /// Track which edge (if any) is overscrolled; returns a newly hit edge
+    fn update_edge(&mut self, low: Edge, high: Edge) -> Option<Edge> {
+        let edge = if self.offset < 0.0 {
+            Some(low)
+        } else if self.offset > self.max() {
+            Some(high)
+        } else {
+            None
+        };
+        let hit = edge.filter(|e| self.edge != Some(*e));
+        self.edge = edge;
+        hit
+    }
+}
+
+/// A trackpad gesture in progress: deltas add up to a drag translation
+#[derive(Clone, Debug, Default)]
+struct TrackpadGesture {
+    translation: (f32, f32),
+    /// Content velocity (px/s), smoothed over recent deltas
+    velocity: (f32, f32),
+    last_time: Option<f64>,
+}
+
+impl TrackpadGesture {
+    fn add(&mut self, dx: f32, dy: f32, time: f64) {
+        self.translation.0 -= dx;
+        self.translation.1 -= dy;
+        if let Some(dt) = self.last_time.map(|last| (time - last) as f32).filter(|dt| *dt > 0.0) {
+            let blend = |v: f32, d: f32| 0.5 * v + 0.5 * d / dt;
+            self.velocity = (blend(self.velocity.0, dx), blend(self.velocity.1, dy));
+        }
+        self.last_time = Some(time);
+    }
+
+    /// Velocity to fling with when the fingers lift at `time`
+    fn release_velocity(&self, time: f64) -> (f32, f32) {
+        match self.last_time {
+            Some(last) if time - last <= FLING_WINDOW => self.velocity,
+            _ => (0.0, 0.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ScrollState {
+    x: Axis,
+    y: Axis,
+    dragging: bool,
+    trackpad: Option<TrackpadGesture>,
+}
+
+impl ScrollState {
+    /// Current content offset; may lie outside [0, content - viewport] while overscrolled
+    pub fn offset(&self) -> (f32, f32) {
+        (self.x.offset, self.y.offset)
+    }
+
+    pub fn velocity(&self) -> (f32, f32) {
+        (self.x.velocity, self.y.velocity)
+    }
+
+    /// Whether the host needs to keep producing frames
+    pub fn is_animating(&self) -> bool {
+        !self.dragging && (self.x.is_animating() || self.y.is_animating())
+    }
+
+    fn edges(&mut self, id: u64) -> Vec<EdgeEvent> {
+        let mut out = Vec::new();
+        if let Some(edge) = self.x.update_edge(Edge::Left, Edge::Right) {
+            out.push(EdgeEvent { id, edge, velocity: self.x.velocity });
+        }
+        if let Some(edge) = self.y.update_edge(Edge::Top, Edge::Bottom) {
+            out.push(EdgeEvent { id, edge, velocity: self.y.velocity });
+        }
+        out
+    }
+}
+
+#[derive(Default)]
+pub struct ScrollManager {
+    states: HashMap<u64, ScrollState>,
+    last_time: Option<f64>,
+    /// Display frame interval (s); physics integrates in steps of at most one frame
+    frame_interval: Option<f32>,
+}
+
+impl ScrollManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match integration steps to the display refresh rate (1 / Hz)
+    pub fn set_frame_interval(&mut self, interval: f32) {
+        self.frame_interval = (interval > 0.0).then_some(interval);
+    }
+
+    /// Whether any container is flinging, settling or springing back
+    pub fn is_animating(&self) -> bool {
+        self.states.values().any(|s| s.is_animating())
+    }
+
+    pub fn get(&self, id: u64) -> Option<&ScrollState> {
+        self.states.get(&id)
+    }
+
+    pub fn remove(&mut self, id: u64) -> bool {
+        self.states.remove(&id).is_some()
+    }
+
+    /// Set viewport and content sizes, creating the container if needed
+    pub fn set_extent(&mut self, id: u64, viewport: (f32, f32), content: (f32, f32)) {
+        let state = self.states.entry(id).or_default();
+        let resting = !state.dragging && !state.is_animating();
+        state.x.viewport = viewport.0;
+        state.y.viewport = viewport.1;
+        state.x.content = content.0;
+        state.y.content = content.1;
+
+        // Content shrank underneath a resting offset: snap back in bounds
+        if resting {
+            state.x.settle();
+            state.y.settle();
+        }
+    }
+
+    /// Wheel / programmatic scroll: moves immediately, clamped, cancels momentum
+    pub fn scroll_by(&mut self, id: u64, dx: f32, dy: f32) {
+        if let Some(state) = self.states.get_mut(&id) {
+            state.x.offset += dx;
+            state.y.offset += dy;
+            state.x.snap_target = None;
+            state.y.snap_target = None;
+            state.x.settle();
+            state.y.settle();
+        }
+    }
+
+    /// Set (or clear) the snap points of one axis
+    pub fn set_snap(&mut self, id: u64, vertical: bool, snap: Option<SnapPoints>) {
+        if let Some(state) = self.states.get_mut(&id) {
+            let axis = if vertical { &mut state.y } else { &mut state.x };
+            axis.snap = snap;
+            axis.snap_target = None;
+        }
+    }
+
+    /// Start a drag: catches any fling in progress
+    pub fn drag_begin(&mut self, id: u64) {
+        if let Some(state) = self.states.get_mut(&id) {
+            state.dragging = true;
+            state.trackpad = None;
+            for axis in [&mut state.x, &mut state.y] {
+                axis.velocity = 0.0;
+                axis.snap_target = None;
+                axis.drag_origin = axis.offset;
+            }
+        }
+    }
+
+    /// Update a drag with the pointer translation since drag_begin
+    pub fn drag_update(&mut self, id: u64, translation_x: f32, translation_y: f32) -> Vec<EdgeEvent> {
+        let Some(state) = self.states.get_mut(&id) else {
+            return Vec::new();
+        };
+        if !state.dragging {
+            return Vec::new();
+        }
+        state.x.drag_to(translation_x);
+        state.y.drag_to(translation_y);
+        state.edges(id)
+    }
+
+    /// Release a drag with the pointer velocity (px/s); content flings the
+    /// opposite way. With `animation_scale` 0 the content settles instantly.
+    pub fn drag_end(&mut self, id: u64, pointer_vx: f32, pointer_vy: f32, animation_scale: f32) {
+        if let Some(state) = self.states.get_mut(&id) {
+            state.dragging = false;
+            state.x.fling(-pointer_vx);
+            state.y.fling(-pointer_vy);
+            if animation_scale == 0.0 {
+                state.x.settle();
+                state.y.settle();
+            }
+        }
+    }
+
+    /// A scroll event: content delta (dx, dy) at `time`. Wheel steps scroll
+    /// immediately; a trackpad gesture drags the content (rubber-banding past
+    /// the edges) and flings when it ends.
+    pub fn scroll_event(&mut self, id: u64, dx: f32, dy: f32, phase: ScrollPhase, time: f64, animation_scale: f32) -> Vec<EdgeEvent> {
+        let Some(state) = self.states.get(&id) else {
+            return Vec::new();
+        };
+        let in_gesture = state.trackpad.is_some();
+        match phase {
+            ScrollPhase::Wheel => {
+                self.scroll_by(id, dx, dy);
+                Vec::new()
+            }
+            ScrollPhase::Began | ScrollPhase::Changed => {
+                // A gesture whose start the host missed begins with its first delta
+                if phase == ScrollPhase::Began || !in_gesture {
+                    self.drag_begin(id);
+                }
+                let state = self.states.get_mut(&id).expect("checked above");
+                let gesture = state.trackpad.get_or_insert_with(TrackpadGesture::default);
+                gesture.add(dx, dy, time);
+                let (tx, ty) = gesture.translation;
+                self.drag_update(id, tx, ty)
+            }
+            ScrollPhase::Ended | ScrollPhase::Cancelled => {
+                let state = self.states.get_mut(&id).expect("checked above");
+                let Some(gesture) = state.trackpad.take() else {
+                    return Vec::new();
+                };
+                let (vx, vy) = match phase {
+                    ScrollPhase::Ended => gesture.release_velocity(time),
+                    _ => (0.0, 0.0),
+                };
+                // drag_end takes the pointer's velocity, which is opposite the content's
+                self.drag_end(id, -vx, -vy, animation_scale);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Advance all containers to `time` (seconds)
+    pub fn tick(&mut self, time: f64, animation_scale: f32) -> Vec<EdgeEvent> {
+        let dt = match self.last_time {
+            Some(last) => (time - last).max(0.0) as f32,
+            None => 0.0,
+        };
+        self.last_time = Some(time);
+
+        let max_step = self.frame_interval.map_or(MAX_STEP, |i| i.min(MAX_STEP));
+        let mut events = Vec::new();
+        for (id, state) in self.states.iter_mut() {
+            if state.dragging {
+                continue;
+            }
+            if animation_scale == 0.0 {
+                state.x.settle();
+                state.y.settle();
+            } else {
+                let mut remaining = dt;
+                while remaining > 0.0 && state.is_animating() {
+                    let step = remaining.min(max_step);
+                    state.x.step(step);
+                    state.y.step(step);
+                    remaining -= step;
+                }
+            }
+            events.extend(state.edges(*id));
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> ScrollManager {
+        let mut m = ScrollManager::new();
+        m.set_extent(1, (100.0, 100.0), (100.0, 1000.0));
+        m.tick(0.0, 1.0);
+        m
+    }
+
+    fn run(m: &mut ScrollManager, from: f64, seconds: f64) -> Vec<EdgeEvent> {
+        let mut events = Vec::new();
+        let mut t = from;
+        while t < from + seconds {
+            t += 1.0 / 60.0;
+            events.extend(m.tick(t, 1.0));
+        }
+        events
+    }
+
+    #[test]
+    fn test_rubber_band_resists() {
+        let d = rubber_band(100.0, 100.0);
+        assert!(d > 0.0 && d < 100.0);
+        assert!(rubber_band(10_000.0, 100.0) < 100.0);
+    }
+
+    #[test]
+    fn test_fling_decelerates_and_stops() {
+        let mut m = manager();
+        m.drag_begin(1);
+        m.drag_update(1, 0.0, -50.0);
+        m.drag_end(1, 0.0, -1000.0, 1.0);
+
+        run(&mut m, 0.0, 5.0);
+        let state = m.get(1).unwrap();
+        assert!(!state.is_animating());
+        assert!(state.offset().1 > 50.0 && state.offset().1 <= 900.0);
+    }
+
+    #[test]
+    fn test_overscroll_reports_edge_and_springs_back() {
+        let mut m = manager();
+        m.drag_begin(1);
+        let events = m.drag_update(1, 0.0, 80.0);
+        assert_eq!(events, vec![EdgeEvent { id: 1, edge: Edge::Top, velocity: 0.0 }]);
+        assert!(m.get(1).unwrap().offset().1 > -80.0);
+
+        // Staying overscrolled doesn't repeat the event
+        assert!(m.drag_update(1, 0.0, 90.0).is_empty());
+
+        m.drag_end(1, 0.0, 0.0, 1.0);
+        run(&mut m, 0.0, 2.0);
+        assert_eq!(m.get(1).unwrap().offset().1, 0.0);
+    }
+
+    #[test]
+    fn test_fling_into_edge() {
+        let mut m = manager();
+        m.drag_begin(1);
+        m.drag_end(1, 0.0, -20_000.0, 1.0);
+
+        let events = run(&mut m, 0.0, 3.0);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].edge, Edge::Bottom);
+        assert!(events[0].velocity > 0.0);
+        assert_eq!(m.get(1).unwrap().offset().1, 900.0);
+    }
+
+    #[test]
+    fn test_snap_nearest() {
+        let interval = SnapPoints::Interval(100.0);
+        assert_eq!(interval.nearest(140.0, 900.0), 100.0);
+        assert_eq!(interval.nearest(160.0, 900.0), 200.0);
+        assert_eq!(interval.nearest(890.0, 850.0), 850.0);
+
+        let offsets = SnapPoints::Offsets(vec![0.0, 300.0, 320.0]);
+        assert_eq!(offsets.nearest(250.0, 900.0), 300.0);
+        assert_eq!(offsets.nearest(-50.0, 900.0), 0.0);
+    }
+
+    #[test]
+    fn test_fling_lands_on_snap_point() {
+        let mut m = manager();
+        m.set_snap(1, true, Some(SnapPoints::Interval(100.0)));
+        m.drag_begin(1);
+        m.drag_update(1, 0.0, -30.0);
+        m.drag_end(1, 0.0, -400.0, 1.0);
+
+        run(&mut m, 0.0, 5.0);
+        let state = m.get(1).unwrap();
+        assert!(!state.is_animating());
+        assert_eq!(state.offset().1, 200.0);
+    }
+
+    #[test]
+    fn test_slow_release_settles_to_nearest() {
+        let mut m = manager();
+        m.set_snap(1, true, Some(SnapPoints::Interval(100.0)));
+        m.drag_begin(1);
+        m.drag_update(1, 0.0, -130.0);
+        m.drag_end(1, 0.0, 0.0, 1.0);
+
+        run(&mut m, 0.0, 5.0);
+        assert_eq!(m.get(1).unwrap().offset().1, 100.0);
+    }
+
+    #[test]
+    fn test_fling_settles_at_high_refresh_rate() {
+        let mut m = manager();
+        m.set_frame_interval(1.0 / 120.0);
+        m.drag_begin(1);
+        m.drag_end(1, 0.0, -20_000.0, 1.0);
+
+        let mut t = 0.0;
+        while t < 3.0 {
+            t += 1.0 / 120.0;
+            m.tick(t, 1.0);
+        }
+        let state = m.get(1).unwrap();
+        assert!(!state.is_animating());
+        assert_eq!(state.offset().1, 900.0);
+    }
+
+    #[test]
+    fn test_trackpad_gesture_drags_then_flings() {
+        let mut m = manager();
+        let mut t = 0.0;
+        m.scroll_event(1, 0.0, 0.0, ScrollPhase::Began, t, 1.0);
+        for _ in 0..10 {
+            t += 1.0 / 60.0;
+            m.scroll_event(1, 0.0, 10.0, ScrollPhase::Changed, t, 1.0);
+        }
+        assert_eq!(m.get(1).unwrap().offset().1, 100.0);
+
+        m.scroll_event(1, 0.0, 0.0, ScrollPhase::Ended, t, 1.0);
+        assert!(m.get(1).unwrap().velocity().1 > 500.0, "flings with the gesture's speed");
+        run(&mut m, t, 5.0);
+        assert!(m.get(1).unwrap().offset().1 > 150.0);
+
+        // Held still before lifting: no fling
+        m.scroll_event(1, 0.0, 10.0, ScrollPhase::Changed, 10.0, 1.0);
+        m.scroll_event(1, 0.0, 0.0, ScrollPhase::Ended, 10.5, 1.0);
+        assert!(!m.get(1).unwrap().is_animating());
+    }
+
+    #[test]
+    fn test_trackpad_rubber_bands_past_the_edge() {
+        let mut m = manager();
+        m.scroll_event(1, 0.0, 0.0, ScrollPhase::Began, 0.0, 1.0);
+        let events = m.scroll_event(1, 0.0, -80.0, ScrollPhase::Changed, 0.016, 1.0);
+        assert_eq!(events[0].edge, Edge::Top);
+        let offset = m.get(1).unwrap().offset().1;
+        assert!(offset < 0.0 && offset > -80.0);
+
+        m.scroll_event(1, 0.0, 0.0, ScrollPhase::Cancelled, 0.5, 1.0);
+        run(&mut m, 0.5, 2.0);
+        assert_eq!(m.get(1).unwrap().offset().1, 0.0);
+    }
+
+    #[test]
+    fn test_reduced_motion_settles_instantly() {
+        let mut m = manager();
+        m.drag_begin(1);
+        m.drag_update(1, 0.0, 50.0);
+        m.drag_end(1, 0.0, 500.0, 0.0);
+        let state = m.get(1).unwrap();
+        assert_eq!(state.offset(), (0.0, 0.0));
+        assert!(!state.is_animating());
+    }
+}
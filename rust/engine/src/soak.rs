@@ -0,0 +1,195 @@
+//! Long-session soak test: thousands of headless frames with font, image and
+//! text-editing churn, driven through the FFI the way a host would, asserting
+//! that live heap memory stops growing once caches are warm.
+//!
+//! Needs a GPU adapter and takes a while, so it only runs on request:
+//! `cargo test --release soak -- --ignored --nocapture`
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ffi::CString;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::engine::Engine;
+use crate::gfx::Gfx;
+use crate::*;
+
+/// Frames before measuring, so caches (layouts, glyphs, pipelines) fill up
+const WARMUP_FRAMES: u32 = 500;
+const SOAK_FRAMES: u32 = 5000;
+/// Live-heap growth tolerated after warmup
+const GROWTH_BUDGET_BYTES: isize = 1 << 20;
+
+/// Counts live bytes per thread, so parallel tests don't skew the numbers
+struct CountingAlloc;
+
+thread_local! {
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+fn track(delta: isize) {
+    let _ = LIVE_BYTES.try_with(|live| live.set(live.get() + delta));
+}
+
+fn live_bytes() -> isize {
+    LIVE_BYTES.with(Cell::get)
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        track(layout.size() as isize);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        track(-(layout.size() as isize));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        track(new_size as isize - layout.size() as isize);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn text_event(kind: McoreTextEventKind, text: Option<&CString>) -> McoreTextEvent {
+    McoreTextEvent {
+        kind,
+        char_code: 0,
+        direction: McoreCursorDirection::Left,
+        extend_selection: 0,
+        cursor_position: 0,
+        text_ptr: text.map_or(std::ptr::null(), |t| t.as_ptr()),
+    }
+}
+
+fn draw_command(kind: u8, y: f32, text: Option<&CString>) -> McoreDrawCommand {
+    McoreDrawCommand {
+        kind,
+        x: 8.0,
+        y,
+        width: 200.0,
+        height: 24.0,
+        radius: 4.0,
+        color: [0.2, 0.4, 0.8, 1.0],
+        text_ptr: text.map_or(std::ptr::null(), |t| t.as_ptr()),
+        font_size: 14.0,
+        wrap_width: 200.0,
+        font_id: 0,
+        border_width: 0.0,
+        border_color: [0.0; 4],
+        has_border: 0,
+        shadow_offset_x: 0.0,
+        shadow_offset_y: 2.0,
+        shadow_blur: 4.0,
+        shadow_color: [0.0, 0.0, 0.0, 0.3],
+        has_shadow: (y > 50.0) as u8,
+        text_align: 0,
+        text_direction: 0,
+    }
+}
+
+/// One host frame: edit a field, churn fonts and images, draw, present
+fn run_frame(ctx: *mut McoreContext, frame: u32, system_font: Option<&CString>) {
+    mcore_begin_frame(ctx, frame as f64 / 60.0);
+
+    // Typing and deleting keeps the field's content cycling through new strings
+    let typed = CString::new(format!("frame {frame} ")).unwrap();
+    mcore_text_input_event(ctx, 1, &text_event(McoreTextEventKind::InsertText, Some(&typed)));
+    if frame % 3 == 0 {
+        mcore_text_input_event(ctx, 1, &text_event(McoreTextEventKind::BackspaceWord, None));
+    }
+    if frame % 50 == 0 {
+        let empty = CString::new("").unwrap();
+        mcore_text_input_set(ctx, 1, empty.as_ptr());
+    }
+    let mut content = [0u8; 1024];
+    let len = mcore_text_input_get(ctx, 1, content.as_mut_ptr(), content.len() as i32).clamp(0, 1023);
+    let content = CString::new(&content[..len as usize]).unwrap_or_default();
+
+    // Hosts commonly reload fonts when windows open
+    if let Some(name) = system_font.filter(|_| frame % 10 == 0) {
+        mcore_font_load_system(ctx, name.as_ptr(), 400.0, 0);
+    }
+
+    // Images decoded and released every few frames (thumbnails scrolling by)
+    let image = (frame % 4 == 0).then(|| {
+        let side = 32 + frame % 64;
+        let pixels = vec![(frame % 255) as u8; (side * side * 4) as usize];
+        let desc = McoreImageDesc {
+            data: pixels.as_ptr(),
+            data_len: pixels.len() as u32,
+            width: side,
+            height: side,
+            format: 1,
+            alpha_type: 2,
+        };
+        mcore_image_register(ctx, &desc)
+    });
+
+    let label = CString::new(format!("Frame {frame}: {}", frame.wrapping_mul(2654435761))).unwrap();
+    let commands = [
+        draw_command(0, 8.0, None),
+        draw_command(1, 40.0, Some(&label)),
+        draw_command(0, 72.0, None),
+        draw_command(1, 104.0, Some(&content)),
+    ];
+    mcore_render_commands(ctx, commands.as_ptr(), commands.len() as i32);
+
+    if let Some(image_id) = image.filter(|id| *id >= 0) {
+        let transform = McoreImageTransform { x: 16.0, y: 140.0, scale: 1.0, rotation_deg: frame as f32 };
+        mcore_image_draw(ctx, image_id, &transform);
+        mcore_image_release(ctx, image_id);
+    }
+
+    // Errors are reported through mcore_last_error every frame in a bad session
+    let pixel = [0u8; 4];
+    let bad = McoreImageDesc { data: pixel.as_ptr(), data_len: 4, width: 1, height: 1, format: 0, alpha_type: 2 };
+    assert_eq!(mcore_image_register(ctx, &bad), -1);
+    assert!(!mcore_last_error().is_null());
+    assert_eq!(mcore_last_error_code(), McoreErrorCode::Unsupported);
+
+    mcore_end_frame_present(ctx, McoreRgba { r: 1.0, g: 1.0, b: 1.0, a: 1.0 });
+}
+
+#[test]
+#[ignore = "needs a GPU adapter; long-running"]
+fn soak_memory_stays_bounded() {
+    let gfx = match pollster::block_on(Gfx::new_headless(320, 240, 2.0, Default::default())) {
+        Ok(gfx) => gfx,
+        Err(e) => {
+            eprintln!("skipping soak test: {e}");
+            return;
+        }
+    };
+    let ctx = Box::into_raw(Box::new(McoreContext(Arc::new(Mutex::new(Engine::new(gfx))))));
+    crate::trace::context_created(ctx as *const ());
+
+    let mut name = [0u8; 256];
+    let system_font = (mcore_font_system_family_count(ctx) > 0)
+        .then(|| mcore_font_system_family_name(ctx, 0, name.as_mut_ptr(), name.len() as i32))
+        .filter(|len| *len > 0)
+        .and_then(|len| CString::new(&name[..len as usize]).ok());
+
+    for frame in 0..WARMUP_FRAMES {
+        run_frame(ctx, frame, system_font.as_ref());
+    }
+    let baseline = live_bytes();
+
+    for frame in WARMUP_FRAMES..WARMUP_FRAMES + SOAK_FRAMES {
+        run_frame(ctx, frame, system_font.as_ref());
+    }
+    let growth = live_bytes() - baseline;
+    eprintln!("live heap growth over {SOAK_FRAMES} frames: {growth} bytes");
+
+    mcore_destroy(ctx);
+    assert!(
+        growth < GROWTH_BUDGET_BYTES,
+        "live heap grew by {growth} bytes over {SOAK_FRAMES} frames"
+    );
+}
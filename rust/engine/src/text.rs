@@ -1,47 +1,783 @@
 // Text module - handles Parley text layout and measurement
 
-use parley::layout::{Alignment, AlignmentOptions, Cursor, Layout, PositionedLayoutItem};
-use parley::style::{FontStack, StyleProperty};
-use parley::{FontContext, LayoutContext};
-use peniko::{kurbo, Brush, Color};
+use parley::layout::{Affinity, Alignment, AlignmentOptions, Cursor, Layout, PositionedLayoutItem, Selection};
+use parley::style::{
+    FontFamily, FontSettings, FontStack, FontStyle, FontWeight, FontWidth, GenericFamily, LineHeight, StyleProperty,
+};
+use parking_lot::Mutex;
+use parley::fontique::{Collection, CollectionOptions, SourceCache};
+use parley::{FontContext, LayoutContext, RangedBuilder};
+use peniko::{kurbo, BlendMode, Blob, Brush, Color, Compose, Gradient, Mix};
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use vello::Scene;
 
+use crate::cache::{CacheStats, LruCache};
+
+/// Font ID meaning "no registered font, use the default stack"
+pub const DEFAULT_FONT_ID: i32 = -1;
+
+/// Number of shaped layouts kept per context, unless the host sets a budget
+pub const LAYOUT_CACHE_CAPACITY: usize = 256;
+
+/// Wrap widths at least this large (logical px) mean "don't wrap": hosts pass
+/// it for single-line text, so it's never used to align lines within
+pub const NO_WRAP_WIDTH: f32 = 100000.0;
+
+/// Word advances kept for measure_text_fast before the table is reset
+const ADVANCE_CACHE_CAPACITY: usize = 4096;
+
+/// Explicit tab stops a style can hold
+pub const MAX_TAB_STOPS: usize = 16;
+
+/// Tabs advance to multiples of this many spaces unless the style sets a width
+const DEFAULT_TAB_SPACES: f32 = 8.0;
+
+/// Appended to the last visible line by TextOverflow::Ellipsis
+const ELLIPSIS: char = '…';
+
+/// Length of the TextOverflow::Fade gradient, in line heights
+const FADE_LINE_HEIGHTS: f32 = 2.0;
+
+/// Font features turned off for TextStyle::ligatures = false
+const NO_LIGATURES: &str = "\"liga\" 0, \"clig\" 0, \"dlig\" 0, \"calt\" 0";
+
+/// Horizontal alignment of lines within the wrap width
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+    Justify,
+}
+
+impl TextAlign {
+    /// Map an FFI value (unknown values fall back to Start)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => TextAlign::Center,
+            2 => TextAlign::End,
+            3 => TextAlign::Justify,
+            _ => TextAlign::Start,
+        }
+    }
+
+    /// Start and End resolve against the paragraph direction, so overriding
+    /// it (TextDirection::Rtl on Latin text) also flips the alignment
+    fn to_parley(self, rtl: bool) -> Alignment {
+        match (self, rtl) {
+            (TextAlign::Start, false) | (TextAlign::End, true) => Alignment::Left,
+            (TextAlign::Start, true) | (TextAlign::End, false) => Alignment::Right,
+            (TextAlign::Center, _) => Alignment::Center,
+            (TextAlign::Justify, _) => Alignment::Justify,
+        }
+    }
+}
+
+/// Where tab characters advance to, in logical px from the start of the line:
+/// the explicit stops in order, then every `width` after the last one.
+/// Stops are measured from the left edge, so they suit left-to-right text.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TabStops {
+    /// Sorted, positive; unused slots are 0
+    stops: [f32; MAX_TAB_STOPS],
+    /// Spacing of the repeating stops, 0 for DEFAULT_TAB_SPACES spaces
+    pub width: f32,
+}
+
+impl TabStops {
+    /// Explicit stops are sorted; non-positive or non-finite ones, and any
+    /// beyond MAX_TAB_STOPS, are dropped
+    pub fn new(width: f32, stops: &[f32]) -> Self {
+        let mut sorted: Vec<f32> = stops.iter().copied().filter(|s| s.is_finite() && *s > 0.0).collect();
+        sorted.sort_by(f32::total_cmp);
+        sorted.dedup();
+        let mut tabs = Self {
+            stops: [0.0; MAX_TAB_STOPS],
+            width: if width.is_finite() { width.max(0.0) } else { 0.0 },
+        };
+        for (slot, stop) in tabs.stops.iter_mut().zip(sorted) {
+            *slot = stop;
+        }
+        tabs
+    }
+
+    pub fn stops(&self) -> &[f32] {
+        let count = self.stops.iter().take_while(|&&s| s > 0.0).count();
+        &self.stops[..count]
+    }
+
+    /// The first stop past `x`, using `default_width` if the stops have no width
+    fn next_stop(&self, x: f32, default_width: f32) -> f32 {
+        // A tab always moves forward, even if it starts right on a stop
+        const MIN_ADVANCE: f32 = 0.5;
+        if let Some(&stop) = self.stops().iter().find(|&&s| s > x + MIN_ADVANCE) {
+            return stop;
+        }
+        let width = if self.width > 0.0 { self.width } else { default_width };
+        if width <= 0.0 {
+            return x;
+        }
+        let last = self.stops().last().copied().unwrap_or(0.0);
+        let n = ((x + MIN_ADVANCE - last) / width).floor().max(0.0) + 1.0;
+        last + n * width
+    }
+
+    fn key(&self) -> (u32, [u32; MAX_TAB_STOPS]) {
+        (self.width.to_bits(), self.stops.map(f32::to_bits))
+    }
+}
+
+/// Base (paragraph) direction of a text request
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// From the first strong character (Unicode bidi rules P2/P3), LTR if none
+    #[default]
+    Auto,
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    /// Map an FFI value (unknown values fall back to Auto)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => TextDirection::Ltr,
+            2 => TextDirection::Rtl,
+            _ => TextDirection::Auto,
+        }
+    }
+
+    /// Whether `text` lays out right to left under this setting
+    pub fn is_rtl(self, text: &str) -> bool {
+        match self {
+            TextDirection::Auto => detect_direction(text) == TextDirection::Rtl,
+            TextDirection::Ltr => false,
+            TextDirection::Rtl => true,
+        }
+    }
+}
+
+/// Direction of the first strong character in `text` (Ltr if there is none)
+pub fn detect_direction(text: &str) -> TextDirection {
+    text.chars()
+        .find_map(strong_direction)
+        .unwrap_or(TextDirection::Ltr)
+}
+
+/// Bidi class of a character as far as base direction detection goes: Some
+/// for strong left-to-right or right-to-left characters, None for neutral
+/// and weak ones (digits, punctuation, marks)
+fn strong_direction(ch: char) -> Option<TextDirection> {
+    match ch as u32 {
+        0x200E => Some(TextDirection::Ltr),
+        0x200F | 0x061C => Some(TextDirection::Rtl),
+        // Arabic-Indic digits are weak even inside the Arabic block
+        0x0660..=0x0669 | 0x06F0..=0x06F9 => None,
+        // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic and their
+        // presentation forms, plus the supplementary RTL blocks
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF => {
+            (!is_mark(ch)).then_some(TextDirection::Rtl)
+        }
+        _ if ch.is_alphabetic() => Some(TextDirection::Ltr),
+        _ => None,
+    }
+}
+
+/// Hebrew points and Arabic harakat are nonspacing marks (weak)
+fn is_mark(ch: char) -> bool {
+    matches!(ch as u32, 0x0591..=0x05C7 | 0x0610..=0x061A | 0x064B..=0x065F | 0x0670 | 0x06D6..=0x06ED)
+}
+
+/// What happens to lines beyond `TextStyle::max_lines`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextOverflow {
+    /// Drop the extra lines
+    #[default]
+    Clip,
+    /// Drop the extra lines and end the last visible one with "…"
+    Ellipsis,
+    /// Drop the extra lines and fade out the end of the last visible one
+    Fade,
+}
+
+impl TextOverflow {
+    /// Map an FFI value (unknown values fall back to Clip)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => TextOverflow::Ellipsis,
+            2 => TextOverflow::Fade,
+            _ => TextOverflow::Clip,
+        }
+    }
+}
+
+/// Font parameters shared by every text request
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextStyle {
+    pub font_size: f32,
+    pub font_id: i32,
+    /// CSS-style weight (100-900), None for the font's default face
+    pub weight: Option<f32>,
+    /// Italic, or upright unless the font defaults to an italic face
+    pub italic: bool,
+    /// Width as a ratio of normal (0.5 ultra-condensed to 2.0 ultra-expanded)
+    pub stretch: f32,
+    /// Extra space after each character, logical pixels
+    pub letter_spacing: f32,
+    /// Extra space after each word, logical pixels
+    pub word_spacing: f32,
+    /// Line height as a multiple of the font size, None for the font's own metrics
+    pub line_height: Option<f32>,
+    pub align: TextAlign,
+    pub direction: TextDirection,
+    /// Most lines to show, None for no limit
+    pub max_lines: Option<usize>,
+    pub overflow: TextOverflow,
+    pub tabs: TabStops,
+    /// Ligatures and contextual alternates; off for code, where they merge
+    /// characters that should keep a column each (`->`, `!=`)
+    pub ligatures: bool,
+}
+
+impl TextStyle {
+    /// Regular upright text at a size
+    pub fn new(font_size: f32, font_id: i32) -> Self {
+        Self {
+            font_size,
+            font_id,
+            weight: None,
+            italic: false,
+            stretch: 1.0,
+            letter_spacing: 0.0,
+            word_spacing: 0.0,
+            line_height: None,
+            align: TextAlign::Start,
+            direction: TextDirection::Auto,
+            max_lines: None,
+            overflow: TextOverflow::Clip,
+            tabs: TabStops::default(),
+            ligatures: true,
+        }
+    }
+}
+
+/// How glyphs are fit to the pixel grid when drawn
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rendering {
+    /// Hint outlines and snap baselines to whole pixels: crisper small text
+    /// on low-DPI screens, at the cost of exact glyph shapes
+    pub hinting: bool,
+    /// Horizontal glyph positions per pixel: 0 leaves them unquantized,
+    /// 1 snaps to whole pixels, 4 to quarter pixels
+    pub subpixel_positions: u8,
+}
+
+impl Rendering {
+    /// Move a glyph origin (layout px) onto the pixel grid under `transform`.
+    /// Only transforms that keep glyphs upright and unskewed can line up with
+    /// pixels; glyphs under any other are left where they are.
+    fn snap(self, transform: kurbo::Affine, x: f32, y: f32) -> (f32, f32) {
+        let [a, b, c, d, e, f] = transform.as_coeffs();
+        if b != 0.0 || c != 0.0 || a <= 0.0 || d <= 0.0 {
+            return (x, y);
+        }
+        let quantize = |value: f32, scale: f64, offset: f64, steps: f64| {
+            (((value as f64 * scale + offset) * steps).round() / steps - offset) / scale
+        };
+        let x = match self.subpixel_positions {
+            0 => x,
+            steps => quantize(x, a, e, steps as f64) as f32,
+        };
+        let y = if self.hinting { quantize(y, d, f, 1.0) as f32 } else { y };
+        (x, y)
+    }
+}
+
+/// Cache key for a shaped + line-broken layout
+/// Floats are keyed by their bit patterns; the text itself is stored alongside
+/// the cached layout and compared on lookup, so hash collisions are harmless.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+struct LayoutKey {
+    text_hash: u64,
+    font_size: u32,
+    font_id: i32,
+    weight: Option<u32>,
+    italic: bool,
+    stretch: u32,
+    letter_spacing: u32,
+    word_spacing: u32,
+    line_height: Option<u32>,
+    align: TextAlign,
+    direction: TextDirection,
+    max_lines: Option<usize>,
+    overflow: TextOverflow,
+    tabs: (u32, [u32; MAX_TAB_STOPS]),
+    ligatures: bool,
+    max_advance: u32,
+    scale: u32,
+}
+
+impl LayoutKey {
+    fn new(text: &str, style: &TextStyle, max_advance: f32, scale: f32) -> Self {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        Self {
+            text_hash: hasher.finish(),
+            font_size: style.font_size.to_bits(),
+            font_id: style.font_id,
+            weight: style.weight.map(f32::to_bits),
+            italic: style.italic,
+            stretch: style.stretch.to_bits(),
+            letter_spacing: style.letter_spacing.to_bits(),
+            word_spacing: style.word_spacing.to_bits(),
+            line_height: style.line_height.map(f32::to_bits),
+            align: style.align,
+            direction: style.direction,
+            max_lines: style.max_lines,
+            overflow: style.overflow,
+            tabs: style.tabs.key(),
+            ligatures: style.ligatures,
+            max_advance: max_advance.to_bits(),
+            scale: scale.to_bits(),
+        }
+    }
+}
+
+/// A shaped layout, after applying the style's line limit
+pub struct ShapedText {
+    pub layout: Layout<Brush>,
+    /// Lines beyond `max_lines` were dropped
+    pub truncated: bool,
+    /// Fade out the end of the last line when drawing
+    fade: bool,
+}
+
+/// A font ID's family and the face it defaults to
+#[derive(Clone, PartialEq)]
+struct RegisteredFont {
+    family: Option<String>,
+    /// Used when a request doesn't specify a weight
+    weight: f32,
+    /// Used unless a request asks for italic
+    italic: bool,
+}
+
+/// Registered fonts, indexed by font ID, shared by the text contexts of one
+/// McoreEngine
+#[derive(Default)]
+struct FontTable {
+    fonts: Vec<RegisteredFont>,
+    /// Bumped when font data is registered, so every context drops layouts
+    /// shaped before it
+    generation: u64,
+}
+
 pub struct TextContext {
     pub font_cx: FontContext,
     pub layout_cx: LayoutContext<Brush>,
+    layout_cache: LruCache<LayoutKey, (String, ShapedText)>,
+    /// (word, advance, line height) in physical px, for measure_text_fast
+    advances: HashMap<LayoutKey, (String, f32, f32)>,
+    fonts: Arc<Mutex<FontTable>>,
+    /// FontTable::generation the caches were built at
+    font_generation: u64,
+    /// Sorted installed family names, collected on first use
+    system_families: Option<Vec<String>>,
+    /// Glyph fitting for layouts drawn in this context
+    pub rendering: Rendering,
 }
 
 impl Default for TextContext {
     fn default() -> Self {
+        // Shared collections let `shared` contexts see fonts registered here
+        let font_cx = FontContext {
+            collection: Collection::new(CollectionOptions { shared: true, ..Default::default() }),
+            source_cache: SourceCache::new_shared(),
+        };
+        Self::with_fonts(font_cx, Arc::default())
+    }
+}
+
+impl TextContext {
+    fn with_fonts(font_cx: FontContext, fonts: Arc<Mutex<FontTable>>) -> Self {
+        let font_generation = fonts.lock().generation;
         Self {
-            font_cx: FontContext::default(),
+            font_cx,
             layout_cx: LayoutContext::new(),
+            layout_cache: LruCache::new(LAYOUT_CACHE_CAPACITY),
+            advances: HashMap::new(),
+            fonts,
+            font_generation,
+            system_families: None,
+            rendering: Rendering::default(),
+        }
+    }
+
+    /// A context with its own caches over the same fonts and font IDs
+    pub fn shared(&self) -> Self {
+        let font_cx = FontContext {
+            collection: self.font_cx.collection.clone(),
+            source_cache: self.font_cx.source_cache.clone(),
+        };
+        Self::with_fonts(font_cx, self.fonts.clone())
+    }
+
+    /// Drop cached layouts if a context sharing the fonts registered one
+    fn sync_fonts(&mut self) {
+        let generation = self.fonts.lock().generation;
+        if generation != self.font_generation {
+            self.font_generation = generation;
+            self.clear_layout_cache();
+        }
+    }
+
+    /// Drop all cached layouts (e.g. after registering fonts)
+    pub fn clear_layout_cache(&mut self) {
+        self.layout_cache.clear();
+        self.advances.clear();
+    }
+
+    /// Bound the layout cache by entry count and estimated bytes
+    pub fn set_layout_cache_budget(&mut self, max_entries: usize, max_bytes: usize) {
+        // Callers use a layout right after caching it, so keep at least one
+        self.layout_cache.set_limits(max_entries.max(1), max_bytes);
+    }
+
+    pub fn layout_cache_stats(&self) -> CacheStats {
+        self.layout_cache.stats()
+    }
+
+    pub fn reset_layout_cache_stats(&mut self) {
+        self.layout_cache.reset_counters();
+    }
+
+    /// Register font data and return its font ID (IDs count up from 0)
+    pub fn register_font(&mut self, blob: Blob<u8>) -> i32 {
+        let families = self.font_cx.collection.register_fonts(blob, None);
+        let family = families
+            .first()
+            .and_then(|(id, _)| self.font_cx.collection.family_name(*id))
+            .map(str::to_string);
+        let mut table = self.fonts.lock();
+        table.fonts.push(RegisteredFont {
+            family,
+            weight: 400.0,
+            italic: false,
+        });
+        // New fonts can change fallback results for already-shaped text
+        table.generation += 1;
+        let id = (table.fonts.len() - 1) as i32;
+        drop(table);
+
+        self.sync_fonts();
+        id
+    }
+
+    /// Give an installed font family a font ID, without embedding its data.
+    /// `weight` and `italic` pick the face used when requests don't specify
+    /// one. Returns None if no installed family has that name.
+    pub fn load_system_font(&mut self, name: &str, weight: f32, italic: bool) -> Option<i32> {
+        let family = self.font_cx.collection.family_by_name(name)?;
+        let style = if italic { FontStyle::Italic } else { FontStyle::Normal };
+        family.match_index(FontWidth::NORMAL, style, FontWeight::new(weight), true)?;
+
+        let font = RegisteredFont {
+            family: Some(family.name().to_string()),
+            weight,
+            italic,
+        };
+        // Loading the same face again reuses its ID
+        let mut table = self.fonts.lock();
+        if let Some(existing) = table.fonts.iter().position(|f| *f == font) {
+            return Some(existing as i32);
+        }
+        table.fonts.push(font);
+        Some((table.fonts.len() - 1) as i32)
+    }
+
+    /// Names of the installed font families, sorted
+    pub fn system_families(&mut self) -> &[String] {
+        let collection = &mut self.font_cx.collection;
+        self.system_families.get_or_insert_with(|| {
+            let mut names: Vec<String> = collection.family_names().map(str::to_string).collect();
+            names.sort();
+            names.dedup();
+            names
+        })
+    }
+
+    fn registered(&self, font_id: i32) -> Option<RegisteredFont> {
+        let table = self.fonts.lock();
+        usize::try_from(font_id).ok().and_then(|i| table.fonts.get(i).cloned())
+    }
+
+    /// Default (weight, italic) of a font ID; regular upright for unknown IDs
+    fn default_face(&self, font_id: i32) -> (f32, bool) {
+        self.registered(font_id).map_or((400.0, false), |f| (f.weight, f.italic))
+    }
+
+    /// Font stack for a font ID: the registered family with system-ui as
+    /// fallback, or just system-ui for DEFAULT_FONT_ID / unknown IDs
+    fn font_stack(&self, font_id: i32) -> FontStack<'static> {
+        let family = self.registered(font_id).and_then(|f| f.family);
+
+        match family {
+            Some(name) => FontStack::List(
+                vec![
+                    FontFamily::Named(name.into()),
+                    FontFamily::Generic(GenericFamily::SystemUi),
+                ]
+                .into(),
+            ),
+            None => FontStack::Source("system-ui".into()),
         }
     }
 }
 
+/// Push the request-wide style as builder defaults; `face` is the font's
+/// default (weight, italic)
+fn push_style_defaults(
+    builder: &mut RangedBuilder<'_, Brush>,
+    style: &TextStyle,
+    font_stack: FontStack<'static>,
+    face: (f32, bool),
+) {
+    builder.push_default(StyleProperty::FontSize(style.font_size));
+    builder.push_default(StyleProperty::FontStack(font_stack));
+    builder.push_default(StyleProperty::FontWeight(FontWeight::new(style.weight.unwrap_or(face.0))));
+    builder.push_default(StyleProperty::FontWidth(FontWidth::from_ratio(style.stretch)));
+    if style.italic || face.1 {
+        builder.push_default(StyleProperty::FontStyle(FontStyle::Italic));
+    }
+    // Spacing is scaled to physical pixels by the builder along with the font size
+    if style.letter_spacing != 0.0 {
+        builder.push_default(StyleProperty::LetterSpacing(style.letter_spacing));
+    }
+    if style.word_spacing != 0.0 {
+        builder.push_default(StyleProperty::WordSpacing(style.word_spacing));
+    }
+    if let Some(line_height) = style.line_height {
+        builder.push_default(StyleProperty::LineHeight(LineHeight::FontSizeRelative(line_height)));
+    }
+    if !style.ligatures {
+        builder.push_default(StyleProperty::FontFeatures(FontSettings::Source(Cow::Borrowed(NO_LIGATURES))));
+    }
+}
+
+/// Shape and line-break text. `max_advance` is in physical pixels.
+fn build_layout(
+    text_cx: &mut TextContext,
+    text: &str,
+    style: &TextStyle,
+    max_advance: f32,
+    scale: f32,
+) -> ShapedText {
+    let tab_width = default_tab_width(text_cx, text, style, scale);
+    let font_stack = text_cx.font_stack(style.font_id);
+    let face = text_cx.default_face(style.font_id);
+    shape_truncated(text, style, max_advance, scale, tab_width, |text, tabs| {
+        let mut layout: Layout<Brush> = {
+            let mut builder = text_cx
+                .layout_cx
+                .ranged_builder(&mut text_cx.font_cx, text, scale, true);
+            push_style_defaults(&mut builder, style, font_stack.clone(), face);
+            push_tab_spacing(&mut builder, tabs);
+            builder.build(text)
+        };
+        layout.break_all_lines(Some(max_advance));
+        layout
+    })
+}
+
+/// Shape text with `shape` (build + line-break, with extra letter spacing on
+/// the given tabs), then apply the style's tab stops, line limit and
+/// alignment. Truncating reshapes a prefix of the text, so byte offsets into
+/// the result still match the original up to the cut.
+fn shape_truncated(
+    text: &str,
+    style: &TextStyle,
+    max_advance: f32,
+    scale: f32,
+    tab_width: f32,
+    mut shape_spaced: impl FnMut(&str, &[(usize, f32)]) -> Layout<Brush>,
+) -> ShapedText {
+    let mut shape = |text: &str| {
+        let mut layout = shape_spaced(text, &[]);
+        if !text.contains('\t') {
+            return layout;
+        }
+        let tabs = tab_spacing(&mut layout, text, style, tab_width, scale);
+        shape_spaced(text, &tabs)
+    };
+    let mut layout = shape(text);
+    let mut truncated = false;
+
+    if let Some(max_lines) = style.max_lines.map(|n| n.max(1)) {
+        if layout.len() > max_lines {
+            truncated = true;
+            let last_line = layout.get(max_lines - 1).expect("line within layout").text_range();
+            layout = match style.overflow {
+                TextOverflow::Clip | TextOverflow::Fade => shape(text[..last_line.end].trim_end()),
+                TextOverflow::Ellipsis => ellipsize(text, last_line, max_lines, &mut shape),
+            };
+        }
+    }
+
+    let rtl = style.direction.is_rtl(text);
+    align_layout(&mut layout, style.align, rtl, max_advance);
+    ShapedText {
+        layout,
+        truncated,
+        fade: truncated && style.overflow == TextOverflow::Fade,
+    }
+}
+
+/// Width of the repeating tab stops for text without its own (logical px):
+/// DEFAULT_TAB_SPACES spaces in the style's font
+fn default_tab_width(text_cx: &mut TextContext, text: &str, style: &TextStyle, scale: f32) -> f32 {
+    if !text.contains('\t') || style.tabs.width > 0.0 {
+        return 0.0;
+    }
+    space_width(text_cx, style, scale) * DEFAULT_TAB_SPACES
+}
+
+/// Advance of a space in the style's font (logical px), e.g. a column of
+/// monospace text
+pub fn space_width(text_cx: &mut TextContext, style: &TextStyle, scale: f32) -> f32 {
+    let (space, _) = word_advance(text_cx, " ", style, scale);
+    space / scale
+}
+
+/// Extra letter spacing (logical px) for each tab in `layout`, a first
+/// shaping of `text`, so each one ends on the next tab stop. Widening a tab
+/// moves the rest of its line, so later tabs on the line are measured from
+/// where they'll end up.
+fn tab_spacing(layout: &mut Layout<Brush>, text: &str, style: &TextStyle, tab_width: f32, scale: f32) -> Vec<(usize, f32)> {
+    // Stops are measured from the start of each line
+    layout.align(None, Alignment::Left, AlignmentOptions::default());
+
+    let mut spacing = Vec::new();
+    let (mut line_top, mut shift) = (f64::NAN, 0.0f32);
+    for (i, _) in text.match_indices('\t') {
+        let start = Cursor::from_byte_index(layout, i, Affinity::Downstream).geometry(layout, 0.0);
+        let end = Cursor::from_byte_index(layout, i + 1, Affinity::Upstream).geometry(layout, 0.0);
+        if start.y0 != line_top {
+            line_top = start.y0;
+            shift = 0.0;
+        }
+        let advance = (end.x0 - start.x0).abs() as f32;
+        let x = start.x0 as f32 + shift;
+        let target = style.tabs.next_stop(x / scale, tab_width) * scale - x;
+        // The style's own letter spacing is replaced on the tab, not added to
+        let glyph = advance - style.letter_spacing * scale;
+        spacing.push((i, (target - glyph) / scale));
+        shift += target - advance;
+    }
+    spacing
+}
+
+fn push_tab_spacing(builder: &mut RangedBuilder<'_, Brush>, tabs: &[(usize, f32)]) {
+    for &(i, spacing) in tabs {
+        builder.push(StyleProperty::LetterSpacing(spacing), i..i + 1);
+    }
+}
+
+/// Shape the longest prefix of `text` that ends inside `last_line` and still
+/// fits in `max_lines` with an ellipsis appended
+fn ellipsize(
+    text: &str,
+    last_line: std::ops::Range<usize>,
+    max_lines: usize,
+    shape: &mut impl FnMut(&str) -> Layout<Brush>,
+) -> Layout<Brush> {
+    let candidate = |end: usize| format!("{}{}", text[..end].trim_end(), ELLIPSIS);
+    let cuts: Vec<usize> = text[last_line.clone()]
+        .char_indices()
+        .map(|(i, _)| last_line.start + i)
+        .chain(std::iter::once(last_line.end))
+        .collect();
+
+    // Binary search for the last cut that fits (the first is kept even if it doesn't)
+    let (mut lo, mut hi) = (0, cuts.len() - 1);
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        if shape(&candidate(cuts[mid])).len() <= max_lines {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    shape(&candidate(cuts[lo]))
+}
+
+/// Align lines within the wrap width (physical pixels), or within the widest
+/// line when there is no real wrap width (0, or NO_WRAP_WIDTH and up), so
+/// "no wrap" widths don't push right-aligned and RTL text off screen
+fn align_layout(layout: &mut Layout<Brush>, align: TextAlign, rtl: bool, max_advance: f32) {
+    let container = (max_advance > 0.0 && max_advance < NO_WRAP_WIDTH).then_some(max_advance);
+    layout.align(container, align.to_parley(rtl), AlignmentOptions::default());
+}
+
+/// Get a shaped layout from the cache, building it on a miss.
+/// `max_advance` is in physical pixels.
+fn cached_layout<'a>(
+    text_cx: &'a mut TextContext,
+    text: &str,
+    style: &TextStyle,
+    max_advance: f32,
+    scale: f32,
+) -> &'a ShapedText {
+    text_cx.sync_fonts();
+    let key = LayoutKey::new(text, style, max_advance, scale);
+
+    let hit = matches!(text_cx.layout_cache.get(&key), Some((cached, _)) if cached == text);
+    if !hit {
+        let layout = build_layout(text_cx, text, style, max_advance, scale);
+        let bytes = approx_layout_bytes(text, &layout);
+        text_cx.layout_cache.insert_sized(key, (text.to_string(), layout), bytes);
+    }
+
+    &text_cx.layout_cache.peek(&key).expect("layout was just inserted").1
+}
+
+/// Rough heap footprint of a cached layout, for the cache's byte budget.
+/// Parley keeps per-character cluster data, per-glyph positions and a line table.
+fn approx_layout_bytes(text: &str, shaped: &ShapedText) -> usize {
+    const PER_TEXT_BYTE: usize = 24;
+    const PER_GLYPH: usize = 24;
+    const PER_LINE: usize = 128;
+
+    let glyphs: usize = shaped
+        .layout
+        .lines()
+        .flat_map(|line| line.items())
+        .map(|item| match item {
+            PositionedLayoutItem::GlyphRun(run) => run.glyphs().count(),
+            _ => 0,
+        })
+        .sum();
+    // The cache also stores a copy of the text as the key check
+    std::mem::size_of::<ShapedText>()
+        + text.len() * (1 + PER_TEXT_BYTE)
+        + glyphs * PER_GLYPH
+        + shaped.layout.len() * PER_LINE
+}
+
 /// Measure text and return width and height
 pub fn measure_text(
     text_cx: &mut TextContext,
     text: &str,
-    font_size: f32,
+    style: &TextStyle,
     max_width: f32,
     scale: f32,
 ) -> (f32, f32) {
-    let mut layout: Layout<Brush> = {
-        let mut builder = text_cx
-            .layout_cx
-            .ranged_builder(&mut text_cx.font_cx, text, scale, true);
-        builder.push_default(StyleProperty::FontSize(font_size));
-        builder.push_default(StyleProperty::FontStack(FontStack::Source(
-            "system-ui".into(),
-        )));
-        builder.build(text)
-    };
-
     // Parley expects physical pixel coordinates, so scale max_width
-    layout.break_all_lines(Some(max_width * scale));
-    layout.align(None, Alignment::Start, AlignmentOptions::default());
+    let layout = &cached_layout(text_cx, text, style, max_width * scale, scale).layout;
 
     let width = layout.width();
 
@@ -56,6 +792,83 @@ pub fn measure_text(
     (width / scale, total_height / scale)
 }
 
+/// Measure one line of text by summing cached per-word advances, without
+/// building (or caching) a layout for the whole string. For hosts that
+/// measure many speculative strings during layout. Falls back to
+/// measure_text when the text has line breaks or would wrap at `max_width`.
+pub fn measure_text_fast(
+    text_cx: &mut TextContext,
+    text: &str,
+    style: &TextStyle,
+    max_width: f32,
+    scale: f32,
+) -> (f32, f32) {
+    // Tabs' widths depend on where they fall in the line
+    if text.trim_end().is_empty() || text.contains(['\t', '\n', '\r', '\u{2028}', '\u{2029}']) {
+        return measure_text(text_cx, text, style, max_width, scale);
+    }
+
+    // Alignment and line limits don't change a single line's advance
+    let line_style = TextStyle {
+        align: TextAlign::Start,
+        max_lines: None,
+        overflow: TextOverflow::Clip,
+        ..*style
+    };
+    let (mut width, mut height) = (0.0f32, 0.0f32);
+    let mut words = word_segments(text).peekable();
+    while let Some(word) = words.next() {
+        // Like Layout::width, leave out the line's trailing whitespace
+        let word = if words.peek().is_none() { word.trim_end() } else { word };
+        let (advance, line_height) = word_advance(text_cx, word, &line_style, scale);
+        width += advance;
+        height = height.max(line_height);
+    }
+
+    let (width, height) = (width / scale, height / scale);
+    if width > max_width {
+        return measure_text(text_cx, text, style, max_width, scale);
+    }
+    (width, height)
+}
+
+/// Words with their trailing whitespace ("  ab  cd" -> "  ", "ab  ", "cd")
+fn word_segments(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let end = rest[word_end..]
+            .find(|c: char| !c.is_whitespace())
+            .map_or(rest.len(), |i| word_end + i);
+        let (word, tail) = rest.split_at(end);
+        rest = tail;
+        Some(word)
+    })
+}
+
+/// A word's (advance, line height) in physical px, shaped on first use
+fn word_advance(text_cx: &mut TextContext, word: &str, style: &TextStyle, scale: f32) -> (f32, f32) {
+    text_cx.sync_fonts();
+    let key = LayoutKey::new(word, style, 0.0, scale);
+    if let Some((cached, advance, line_height)) = text_cx.advances.get(&key) {
+        if cached == word {
+            return (*advance, *line_height);
+        }
+    }
+
+    let layout = build_layout(text_cx, word, style, f32::MAX, scale).layout;
+    let advance = layout.full_width();
+    let line_height = layout.lines().map(|line| line.metrics().line_height).fold(0.0, f32::max);
+    if text_cx.advances.len() >= ADVANCE_CACHE_CAPACITY {
+        text_cx.advances.clear();
+    }
+    text_cx.advances.insert(key, (word.to_string(), advance, line_height));
+    (advance, line_height)
+}
+
 /// Measure text width up to a specific byte offset (kept for API compatibility)
 pub fn measure_text_to_byte_offset(
     text_cx: &mut TextContext,
@@ -67,7 +880,7 @@ pub fn measure_text_to_byte_offset(
     byte_offset_to_x(text_cx, text, font_size, byte_offset, scale)
 }
 
-/// Measure text and get a hit position (x coordinate) for a byte offset
+/// Caret x position (logical pixels) for a byte offset in single-line text
 pub fn byte_offset_to_x(
     text_cx: &mut TextContext,
     text: &str,
@@ -75,72 +888,85 @@ pub fn byte_offset_to_x(
     byte_offset: usize,
     scale: f32,
 ) -> f32 {
-    let byte_offset = byte_offset.min(text.len());
-
     // Use a very large max_width to prevent wrapping in single-line inputs
-    // Scale to physical pixels for Parley
-    let max_width_no_wrap = 100000.0 * scale;
+    let shaped = cached_layout(text_cx, text, &TextStyle::new(font_size, DEFAULT_FONT_ID), NO_WRAP_WIDTH, scale);
 
-    // Measure cursor position by adding a marker character after the cursor position
-    // This prevents trailing space collapse issues
-    if byte_offset == 0 {
-        return 0.0;
-    }
+    // Layout returns physical pixels, convert to logical
+    caret_geometry(&shaped.layout, byte_offset).x / scale
+}
 
-    if byte_offset >= text.len() {
-        // Cursor at end - use marker to handle trailing spaces
-        let text_with_marker = format!("{}|", text);
-        let mut marked_layout: Layout<Brush> = {
-            let mut builder = text_cx.layout_cx.ranged_builder(&mut text_cx.font_cx, &text_with_marker, scale, true);
-            builder.push_default(StyleProperty::FontSize(font_size));
-            builder.push_default(StyleProperty::FontStack(FontStack::Source("system-ui".into())));
-            builder.build(&text_with_marker)
-        };
-        marked_layout.break_all_lines(Some(max_width_no_wrap));
-        marked_layout.align(None, Alignment::Start, AlignmentOptions::default());
-
-        // Measure marker
-        let mut marker_layout: Layout<Brush> = {
-            let mut builder = text_cx.layout_cx.ranged_builder(&mut text_cx.font_cx, "|", scale, true);
-            builder.push_default(StyleProperty::FontSize(font_size));
-            builder.push_default(StyleProperty::FontStack(FontStack::Source("system-ui".into())));
-            builder.build("|")
-        };
-        marker_layout.break_all_lines(Some(max_width_no_wrap));
-        marker_layout.align(None, Alignment::Start, AlignmentOptions::default());
+/// Caret position and extent, in the layout's coordinate space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaretRect {
+    pub x: f32,
+    /// Top of the line containing the caret
+    pub y: f32,
+    pub height: f32,
+}
 
-        // Layout returns physical pixels, convert to logical
-        return (marked_layout.width() - marker_layout.width()) / scale;
+/// Caret geometry for a byte offset in a shaped (possibly wrapped) layout.
+/// Follows Parley's cluster geometry, so it is correct for ligatures, kerning
+/// and RTL runs. Offsets inside a cluster snap to its start; at a soft wrap
+/// the caret sits at the start of the next line.
+pub fn caret_geometry(layout: &Layout<Brush>, byte_offset: usize) -> CaretRect {
+    let rect = Cursor::from_byte_index(layout, byte_offset, Affinity::Downstream).geometry(layout, 0.0);
+
+    CaretRect {
+        x: rect.x0 as f32,
+        y: rect.y0 as f32,
+        height: (rect.y1 - rect.y0) as f32,
     }
+}
 
-    // Get the substring up to the cursor and add a visible marker
-    let text_up_to_cursor = &text[..byte_offset];
-    let text_with_marker = format!("{}|", text_up_to_cursor);
+/// Caret geometry for text shaped with `style`; `wrap_width` and the result are logical
+pub fn caret_for_offset(
+    text_cx: &mut TextContext,
+    text: &str,
+    style: &TextStyle,
+    wrap_width: f32,
+    byte_offset: usize,
+    scale: f32,
+) -> CaretRect {
+    let shaped = cached_layout(text_cx, text, style, wrap_width * scale, scale);
+    let caret = caret_geometry(&shaped.layout, byte_offset);
 
-    // Measure with the marker
-    let mut marked_layout: Layout<Brush> = {
-        let mut builder = text_cx.layout_cx.ranged_builder(&mut text_cx.font_cx, &text_with_marker, scale, true);
-        builder.push_default(StyleProperty::FontSize(font_size));
-        builder.push_default(StyleProperty::FontStack(FontStack::Source("system-ui".into())));
-        builder.build(&text_with_marker)
-    };
+    // Layout returns physical pixels, convert to logical
+    CaretRect {
+        x: caret.x / scale,
+        y: caret.y / scale,
+        height: caret.height / scale,
+    }
+}
 
-    marked_layout.break_all_lines(Some(max_width_no_wrap));
-    marked_layout.align(None, Alignment::Start, AlignmentOptions::default());
+/// Rectangles covering a byte range of a shaped layout, one or more per line,
+/// in the layout's coordinate space. Empty for a collapsed range.
+pub fn selection_geometry(layout: &Layout<Brush>, range: std::ops::Range<usize>) -> Vec<kurbo::Rect> {
+    let anchor = Cursor::from_byte_index(layout, range.start, Affinity::Downstream);
+    let focus = Cursor::from_byte_index(layout, range.end, Affinity::Upstream);
 
-    // Now measure just the marker character to subtract its width
-    let mut marker_layout: Layout<Brush> = {
-        let mut builder = text_cx.layout_cx.ranged_builder(&mut text_cx.font_cx, "|", scale, true);
-        builder.push_default(StyleProperty::FontSize(font_size));
-        builder.push_default(StyleProperty::FontStack(FontStack::Source("system-ui".into())));
-        builder.build("|")
-    };
+    Selection::new(anchor, focus)
+        .geometry(layout)
+        .into_iter()
+        .map(|(rect, _line)| kurbo::Rect::new(rect.x0, rect.y0, rect.x1, rect.y1))
+        .collect()
+}
 
-    marker_layout.break_all_lines(Some(max_width_no_wrap));
-    marker_layout.align(None, Alignment::Start, AlignmentOptions::default());
+/// Selection rectangles for text shaped with `style`; `wrap_width` and the result are logical
+pub fn selection_rects(
+    text_cx: &mut TextContext,
+    text: &str,
+    style: &TextStyle,
+    wrap_width: f32,
+    range: std::ops::Range<usize>,
+    scale: f32,
+) -> Vec<kurbo::Rect> {
+    let shaped = cached_layout(text_cx, text, style, wrap_width * scale, scale);
 
     // Layout returns physical pixels, convert to logical
-    (marked_layout.width() - marker_layout.width()) / scale
+    selection_geometry(&shaped.layout, range)
+        .into_iter()
+        .map(|rect| rect.scale_from_origin(1.0 / scale as f64))
+        .collect()
 }
 
 /// Hit test text at an x coordinate and return the byte offset
@@ -151,26 +977,30 @@ pub fn x_to_byte_offset(
     x: f32,
     scale: f32,
 ) -> usize {
-    let mut layout: Layout<Brush> = {
-        let mut builder = text_cx
-            .layout_cx
-            .ranged_builder(&mut text_cx.font_cx, text, scale, true);
-        builder.push_default(StyleProperty::FontSize(font_size));
-        builder.push_default(StyleProperty::FontStack(FontStack::Source(
-            "system-ui".into(),
-        )));
-        builder.build(text)
-    };
-
     // Use a very large max_width to prevent wrapping
-    layout.break_all_lines(Some(100000.0));
-    layout.align(None, Alignment::Start, AlignmentOptions::default());
+    let shaped = cached_layout(text_cx, text, &TextStyle::new(font_size, DEFAULT_FONT_ID), NO_WRAP_WIDTH, scale);
 
     // Hit test at point
-    let cursor = Cursor::from_point(&layout, x, 0.0);
+    let cursor = Cursor::from_point(&shaped.layout, x, 0.0);
     cursor.index()
 }
 
+/// Hit test text shaped with `style` at a logical point (relative to the text
+/// origin) and return the byte offset. Points above or below the text clamp
+/// to the first or last line; `wrap_width` is logical.
+pub fn hit_test_text(
+    text_cx: &mut TextContext,
+    text: &str,
+    style: &TextStyle,
+    wrap_width: f32,
+    x: f32,
+    y: f32,
+    scale: f32,
+) -> usize {
+    let shaped = cached_layout(text_cx, text, style, wrap_width * scale, scale);
+    hit_test_layout(&shaped.layout, x * scale, y * scale)
+}
+
 /// Draw text into a Vello scene
 pub fn draw_text(
     scene: &mut Scene,
@@ -178,28 +1008,91 @@ pub fn draw_text(
     text: &str,
     x: f32,
     y: f32,
-    font_size: f32,
+    style: &TextStyle,
     wrap_width: f32,
     color: Color,
     scale: f32,
 ) {
-    let mut layout: Layout<Brush> = {
-        let mut builder = text_cx
-            .layout_cx
-            .ranged_builder(&mut text_cx.font_cx, text, scale, true);
-        builder.push_default(StyleProperty::FontSize(font_size));
-        builder.push_default(StyleProperty::FontStack(FontStack::Source(
-            "system-ui".into(),
-        )));
-        builder.build(text)
+    // Parley expects physical pixel coordinates, so scale wrap_width
+    let rendering = text_cx.rendering;
+    let layout = cached_layout(text_cx, text, style, wrap_width * scale, scale);
+    draw_layout(scene, layout, x, y, color, rendering);
+}
+
+/// Draw text with `transform` mapping its layout (physical px, origin at the
+/// top left) into the scene
+pub fn draw_text_transformed(
+    scene: &mut Scene,
+    text_cx: &mut TextContext,
+    text: &str,
+    transform: kurbo::Affine,
+    style: &TextStyle,
+    wrap_width: f32,
+    color: Color,
+    scale: f32,
+) {
+    let rendering = text_cx.rendering;
+    let layout = cached_layout(text_cx, text, style, wrap_width * scale, scale);
+    draw_shaped(scene, layout, transform, Some(&Brush::Solid(color)), rendering);
+}
+
+/// Draw an already-shaped layout into a Vello scene at physical position (x, y)
+pub fn draw_layout(scene: &mut Scene, shaped: &ShapedText, x: f32, y: f32, color: Color, rendering: Rendering) {
+    draw_shaped(scene, shaped, translate(x, y), Some(&Brush::Solid(color)), rendering);
+}
+
+/// Draw a layout using the brushes and decorations stored in its styles
+/// (for layouts built with `build_styled_layout`)
+pub fn draw_styled_layout(scene: &mut Scene, shaped: &ShapedText, x: f32, y: f32, rendering: Rendering) {
+    draw_shaped(scene, shaped, translate(x, y), None, rendering);
+}
+
+fn translate(x: f32, y: f32) -> kurbo::Affine {
+    kurbo::Affine::translate((x as f64, y as f64))
+}
+
+/// Draw glyph runs, fading out the end of the last line for TextOverflow::Fade
+fn draw_shaped(
+    scene: &mut Scene,
+    shaped: &ShapedText,
+    transform: kurbo::Affine,
+    brush_override: Option<&Brush>,
+    rendering: Rendering,
+) {
+    let layout = &shaped.layout;
+    let last_line = layout.len().checked_sub(1).and_then(|i| layout.get(i));
+    let Some(last_line) = last_line.filter(|_| shaped.fade) else {
+        draw_glyph_runs(scene, layout, transform, brush_override, rendering);
+        return;
     };
 
-    // Parley expects physical pixel coordinates, so scale wrap_width
-    layout.break_all_lines(Some(wrap_width * scale));
-    layout.align(None, Alignment::Start, AlignmentOptions::default());
+    let metrics = last_line.metrics();
 
-    let brush = Brush::Solid(color);
+    // Isolate the text so the fade only erases glyphs, not the backdrop
+    // (padded so overhanging glyphs aren't clipped)
+    let pad = metrics.line_height as f64;
+    let bounds = kurbo::Rect::new(0.0, 0.0, layout.full_width() as f64, layout.height() as f64).inflate(pad, pad);
+    scene.push_layer(BlendMode::default(), 1.0, transform, &bounds);
+    draw_glyph_runs(scene, layout, transform, brush_override, rendering);
 
+    let line_end = (metrics.offset + metrics.advance - metrics.trailing_whitespace) as f64;
+    let fade_start = (line_end - (metrics.line_height * FADE_LINE_HEIGHTS) as f64).max(metrics.offset as f64);
+    let fade = kurbo::Rect::new(fade_start, metrics.block_min_coord as f64, line_end + pad, metrics.block_max_coord as f64);
+    let gradient = Gradient::new_linear((fade_start, 0.0), (line_end, 0.0)).with_stops([Color::TRANSPARENT, Color::BLACK]);
+    scene.push_layer(BlendMode::new(Mix::Normal, Compose::DestOut), 1.0, transform, &fade);
+    scene.fill(vello::peniko::Fill::NonZero, transform, &gradient, None, &fade);
+    scene.pop_layer();
+    scene.pop_layer();
+}
+
+/// Render glyph runs; `brush_override` replaces per-run brushes when set
+fn draw_glyph_runs(
+    scene: &mut Scene,
+    layout: &Layout<Brush>,
+    transform: kurbo::Affine,
+    brush_override: Option<&Brush>,
+    rendering: Rendering,
+) {
     // Render glyphs using the same pattern as original code
     for line in layout.lines() {
         for item in line.items() {
@@ -213,19 +1106,20 @@ pub fn draw_text(
             let font = run.font();
             let font_size = run.font_size();
             let coords = run.normalized_coords();
+            let style = glyph_run.style();
+            let brush = brush_override.unwrap_or(&style.brush);
 
             scene
                 .draw_glyphs(font)
-                .brush(&brush)
-                .hint(false)
-                .transform(kurbo::Affine::translate((x as f64, y as f64)))
+                .brush(brush)
+                .hint(rendering.hinting)
+                .transform(transform)
                 .font_size(font_size)
                 .normalized_coords(coords)
                 .draw(
                     vello::peniko::Fill::NonZero,
                     glyph_run.glyphs().map(|glyph| {
-                        let gx = glyph_x + glyph.x;
-                        let gy = glyph_y - glyph.y;
+                        let (gx, gy) = rendering.snap(transform, glyph_x + glyph.x, glyph_y - glyph.y);
                         glyph_x += glyph.advance;
                         vello::Glyph {
                             id: glyph.id,
@@ -234,38 +1128,138 @@ pub fn draw_text(
                         }
                     }),
                 );
+
+            // Underline / strikethrough decorations
+            let metrics = run.metrics();
+            let x0 = glyph_run.offset() as f64;
+            let x1 = x0 + glyph_run.advance() as f64;
+            let decorations = [
+                (&style.underline, metrics.underline_offset, metrics.underline_size),
+                (&style.strikethrough, metrics.strikethrough_offset, metrics.strikethrough_size),
+            ];
+            for (decoration, default_offset, default_size) in decorations {
+                let Some(decoration) = decoration else {
+                    continue;
+                };
+                let offset = decoration.offset.unwrap_or(default_offset);
+                let size = decoration.size.unwrap_or(default_size);
+                let y0 = (glyph_y - offset) as f64;
+                let rect = kurbo::Rect::new(x0, y0, x1, y0 + size as f64);
+                let brush = brush_override.unwrap_or(&decoration.brush);
+                scene.fill(vello::peniko::Fill::NonZero, transform, brush, None, &rect);
+            }
         }
     }
 }
 
+/// Per-range style for rich text
+pub struct TextSpan {
+    pub range: std::ops::Range<usize>,
+    pub color: Color,
+    /// CSS-style weight (100-900), None to inherit
+    pub weight: Option<f32>,
+    pub italic: bool,
+    pub underline: bool,
+    /// Registered font ID, None to inherit
+    pub font_id: Option<i32>,
+}
+
+/// Shape text with per-range styles into a single layout
+/// Ranges are byte ranges; out-of-bounds or non-char-boundary ranges are clamped
+pub fn build_styled_layout(
+    text_cx: &mut TextContext,
+    text: &str,
+    spans: &[TextSpan],
+    style: &TextStyle,
+    wrap_width: f32,
+    default_color: Color,
+    scale: f32,
+) -> ShapedText {
+    let default_stack = text_cx.font_stack(style.font_id);
+    let face = text_cx.default_face(style.font_id);
+    let span_stacks: Vec<Option<FontStack<'static>>> = spans
+        .iter()
+        .map(|span| span.font_id.map(|id| text_cx.font_stack(id)))
+        .collect();
+
+    let tab_width = default_tab_width(text_cx, text, style, scale);
+
+    // Parley expects physical pixel coordinates, so scale wrap_width
+    let max_advance = wrap_width * scale;
+    shape_truncated(text, style, max_advance, scale, tab_width, |text, tabs| {
+        let mut builder = text_cx
+            .layout_cx
+            .ranged_builder(&mut text_cx.font_cx, text, scale, true);
+        push_style_defaults(&mut builder, style, default_stack.clone(), face);
+        builder.push_default(StyleProperty::Brush(Brush::Solid(default_color)));
+
+        for (span, stack) in spans.iter().zip(&span_stacks) {
+            let start = floor_char_boundary(text, span.range.start);
+            let end = floor_char_boundary(text, span.range.end);
+            if start >= end {
+                continue;
+            }
+            let range = start..end;
+            builder.push(StyleProperty::Brush(Brush::Solid(span.color)), range.clone());
+            if let Some(weight) = span.weight {
+                builder.push(StyleProperty::FontWeight(FontWeight::new(weight)), range.clone());
+            }
+            if span.italic {
+                builder.push(StyleProperty::FontStyle(FontStyle::Italic), range.clone());
+            }
+            if span.underline {
+                builder.push(StyleProperty::Underline(true), range.clone());
+            }
+            if let Some(stack) = stack {
+                builder.push(StyleProperty::FontStack(stack.clone()), range.clone());
+            }
+        }
+        push_tab_spacing(&mut builder, tabs);
+
+        let mut layout = builder.build(text);
+        layout.break_all_lines(Some(max_advance));
+        layout
+    })
+}
+
+/// Clamp a byte index to the text and move it back to a char boundary
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut i = index.min(text.len());
+    while i > 0 && !text.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
 /// Layout text and return full metrics (width, height, line count)
 pub struct TextMetrics {
     pub width: f32,
     pub height: f32,
     pub line_count: usize,
+    /// Lines beyond `max_lines` were dropped
+    pub truncated: bool,
 }
 
+/// `wrap_width` and the metrics are in logical pixels
 pub fn layout_text(
     text_cx: &mut TextContext,
     text: &str,
-    font_size: f32,
+    style: &TextStyle,
     wrap_width: f32,
     scale: f32,
 ) -> TextMetrics {
-    let mut layout: Layout<Brush> = {
-        let mut builder = text_cx
-            .layout_cx
-            .ranged_builder(&mut text_cx.font_cx, text, scale, true);
-        builder.push_default(StyleProperty::FontSize(font_size));
-        builder.push_default(StyleProperty::FontStack(FontStack::Source(
-            "system-ui".into(),
-        )));
-        builder.build(text)
-    };
-
-    layout.break_all_lines(Some(wrap_width));
-    layout.align(None, Alignment::Start, AlignmentOptions::default());
+    let layout = cached_layout(text_cx, text, style, wrap_width * scale, scale);
+    let metrics = layout_metrics(layout);
+    TextMetrics {
+        width: metrics.width / scale,
+        height: metrics.height / scale,
+        ..metrics
+    }
+}
 
+/// Metrics of an already-shaped layout (in the layout's physical pixels)
+pub fn layout_metrics(shaped: &ShapedText) -> TextMetrics {
+    let layout = &shaped.layout;
     let width = layout.width();
 
     // Calculate proper height using line metrics (includes line spacing)
@@ -279,5 +1273,127 @@ pub fn layout_text(
         width,
         height: total_height,
         line_count: layout.len(),
+        truncated: shaped.truncated,
+    }
+}
+
+/// Shape text into a standalone layout owned by the caller (bypasses the cache)
+/// `wrap_width` is in logical pixels
+pub fn shape_text(
+    text_cx: &mut TextContext,
+    text: &str,
+    style: &TextStyle,
+    wrap_width: f32,
+    scale: f32,
+) -> ShapedText {
+    build_layout(text_cx, text, style, wrap_width * scale, scale)
+}
+
+/// Hit test a shaped layout at a physical point and return the byte offset
+pub fn hit_test_layout(layout: &Layout<Brush>, x: f32, y: f32) -> usize {
+    Cursor::from_point(layout, x, y).index()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_segments_keep_trailing_whitespace() {
+        let words: Vec<&str> = word_segments("  ab  cd\te ").collect();
+        assert_eq!(words, ["  ", "ab  ", "cd\t", "e "]);
+        assert_eq!(word_segments("word").collect::<Vec<_>>(), ["word"]);
+        assert_eq!(word_segments("").count(), 0);
+    }
+
+    #[test]
+    fn test_detect_direction() {
+        assert_eq!(detect_direction("hello"), TextDirection::Ltr);
+        assert_eq!(detect_direction("שלום world"), TextDirection::Rtl);
+        assert_eq!(detect_direction("123 - مرحبا"), TextDirection::Rtl);
+        // Arabic-Indic digits and harakat are weak; the Latin letter decides
+        assert_eq!(detect_direction("\u{0661}\u{064B} abc"), TextDirection::Ltr);
+        assert_eq!(detect_direction("\u{200F}abc"), TextDirection::Rtl);
+        assert_eq!(detect_direction("12 + 3"), TextDirection::Ltr);
+
+        assert!(TextDirection::Auto.is_rtl("עברית"));
+        assert!(!TextDirection::Ltr.is_rtl("עברית"));
+        assert!(TextDirection::Rtl.is_rtl("latin"));
+    }
+
+    #[test]
+    fn test_ffi_enums_fall_back_to_defaults() {
+        assert_eq!(TextAlign::from_u8(1), TextAlign::Center);
+        assert_eq!(TextAlign::from_u8(3), TextAlign::Justify);
+        assert_eq!(TextAlign::from_u8(42), TextAlign::Start);
+
+        assert_eq!(TextOverflow::from_u8(1), TextOverflow::Ellipsis);
+        assert_eq!(TextOverflow::from_u8(2), TextOverflow::Fade);
+        assert_eq!(TextOverflow::from_u8(42), TextOverflow::Clip);
+
+        assert_eq!(TextDirection::from_u8(2), TextDirection::Rtl);
+        assert_eq!(TextDirection::from_u8(42), TextDirection::Auto);
+    }
+
+    #[test]
+    fn test_floor_char_boundary() {
+        let text = "a\u{e9}b"; // é is 2 bytes
+        assert_eq!(floor_char_boundary(text, 0), 0);
+        assert_eq!(floor_char_boundary(text, 2), 1);
+        assert_eq!(floor_char_boundary(text, 3), 3);
+        assert_eq!(floor_char_boundary(text, 100), text.len());
+    }
+
+    #[test]
+    fn test_tab_stops_then_repeating_width() {
+        let tabs = TabStops::new(0.0, &[120.0, f32::NAN, 40.0, -5.0]);
+        assert_eq!(tabs.stops(), [40.0, 120.0]);
+        assert_eq!(tabs.next_stop(0.0, 50.0), 40.0);
+        // A tab on a stop still moves to the next one
+        assert_eq!(tabs.next_stop(40.0, 50.0), 120.0);
+        assert_eq!(tabs.next_stop(130.0, 50.0), 170.0);
+
+        let tabs = TabStops::new(32.0, &[]);
+        assert_eq!(tabs.next_stop(0.0, 50.0), 32.0);
+        assert_eq!(tabs.next_stop(70.0, 50.0), 96.0);
+        assert_eq!(TabStops::new(-1.0, &[]).width, 0.0);
+    }
+
+    #[test]
+    fn test_rendering_snaps_to_pixel_grid() {
+        let at = kurbo::Affine::translate((10.3, 20.6));
+        assert_eq!(Rendering::default().snap(at, 1.4, 2.2), (1.4, 2.2));
+
+        let whole = Rendering { hinting: true, subpixel_positions: 1 };
+        let (x, y) = whole.snap(at, 1.4, 2.2);
+        assert!((x - 1.7).abs() < 1e-4 && (y - 2.4).abs() < 1e-4, "lands on 12, 23: {x}, {y}");
+
+        // Quarter pixels, through a 2x scale
+        let quarter = Rendering { hinting: false, subpixel_positions: 4 };
+        let (x, y) = quarter.snap(kurbo::Affine::scale(2.0), 1.1, 2.2);
+        assert!((x - 1.125).abs() < 1e-4 && y == 2.2, "{x}, {y}");
+
+        // Rotated glyphs can't line up with pixels
+        assert_eq!(whole.snap(kurbo::Affine::rotate(0.5), 1.4, 2.2), (1.4, 2.2));
+    }
+
+    #[test]
+    fn test_layout_key_covers_style() {
+        let style = TextStyle::new(14.0, DEFAULT_FONT_ID);
+        let key = LayoutKey::new("hello", &style, 100.0, 2.0);
+        assert!(key == LayoutKey::new("hello", &style, 100.0, 2.0));
+
+        let clamped = TextStyle {
+            max_lines: Some(1),
+            overflow: TextOverflow::Ellipsis,
+            ..style
+        };
+        assert!(key != LayoutKey::new("hello", &clamped, 100.0, 2.0));
+        let tabbed = TextStyle { tabs: TabStops::new(24.0, &[]), ..style };
+        assert!(key != LayoutKey::new("hello", &tabbed, 100.0, 2.0));
+        let code = TextStyle { ligatures: false, ..style };
+        assert!(key != LayoutKey::new("hello", &code, 100.0, 2.0));
+        assert!(key != LayoutKey::new("hello", &style, 100.0, 1.0));
+        assert!(key != LayoutKey::new("hello!", &style, 100.0, 2.0));
     }
 }
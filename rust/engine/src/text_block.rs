@@ -0,0 +1,290 @@
+/// Rich text blocks: headings, paragraphs and list items laid out as a unit
+/// (chat messages, rendered markdown)
+///
+/// A markdown view would otherwise measure and draw every paragraph of a
+/// message itself, stacking them, placing list markers and filling code span
+/// backgrounds by hand. A block takes the whole list, shapes each paragraph
+/// once with its runs' styles and stacks them, so the host measures and
+/// draws a message with one call apiece.
+
+use std::ops::Range;
+
+use peniko::{kurbo, Color};
+use vello::Scene;
+
+use crate::text::{self, ShapedText, TextContext, TextSpan, TextStyle};
+
+/// Heading sizes as multiples of the body size, h1 to h6 (as in CSS)
+const HEADING_SCALE: [f32; 6] = [2.0, 1.5, 1.17, 1.0, 0.83, 0.67];
+const BOLD_WEIGHT: f32 = 700.0;
+/// Deeper list items are indented like this one
+const MAX_DEPTH: u32 = 8;
+/// Bullets by nesting depth, repeating
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+/// Space between a list marker and its item, in ems
+const MARKER_GAP: f32 = 0.5;
+/// Code span backgrounds reach this far past the text, in ems
+const CODE_PAD: f64 = 0.2;
+const CODE_RADIUS: f64 = 0.25;
+/// Consecutive list items are this much of the paragraph spacing apart
+const LIST_SPACING: f32 = 0.25;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Paragraph,
+    /// Level 1-6
+    Heading(u8),
+    /// List item at a nesting depth (0 for the top level)
+    Bullet(u32),
+    Numbered { depth: u32, number: u32 },
+}
+
+impl Kind {
+    /// From McoreTextBlockKind values; unknown kinds are paragraphs
+    pub fn from_ffi(kind: u32, level: u32, number: u32) -> Self {
+        match kind {
+            1 => Self::Heading(level.clamp(1, 6) as u8),
+            2 => Self::Bullet(level.min(MAX_DEPTH)),
+            3 => Self::Numbered { depth: level.min(MAX_DEPTH), number },
+            _ => Self::Paragraph,
+        }
+    }
+
+    fn font_scale(self) -> f32 {
+        match self {
+            Self::Heading(level) => HEADING_SCALE[level as usize - 1],
+            _ => 1.0,
+        }
+    }
+
+    fn is_list_item(self) -> bool {
+        matches!(self, Self::Bullet(_) | Self::Numbered { .. })
+    }
+
+    /// How far the text starts from the block's left edge; list items leave
+    /// room for their marker
+    fn indent(self, list_indent: f32) -> f32 {
+        match self {
+            Self::Bullet(depth) | Self::Numbered { depth, .. } => list_indent * (depth + 1) as f32,
+            _ => 0.0,
+        }
+    }
+
+    fn marker(self) -> Option<String> {
+        match self {
+            Self::Bullet(depth) => Some(BULLETS[depth as usize % BULLETS.len()].to_string()),
+            Self::Numbered { number, .. } => Some(format!("{number}.")),
+            _ => None,
+        }
+    }
+}
+
+/// Space between a paragraph and the one after it
+fn gap(above: Kind, below: Kind, spacing: f32) -> f32 {
+    if above.is_list_item() && below.is_list_item() {
+        spacing * LIST_SPACING
+    } else {
+        spacing
+    }
+}
+
+/// Styling for a byte range of a paragraph
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    pub range: Range<usize>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// Inline code: the code font and color over a background
+    pub code: bool,
+    /// None for the block's color (or its code color)
+    pub color: Option<Color>,
+}
+
+pub struct Paragraph<'a> {
+    pub text: &'a str,
+    pub kind: Kind,
+    pub runs: Vec<Run>,
+}
+
+/// Styling shared by a block's paragraphs (logical px)
+#[derive(Clone, Debug)]
+pub struct BlockStyle {
+    /// Body text; headings scale its font size
+    pub text: TextStyle,
+    /// Font for code runs, None for the body font
+    pub code_font_id: Option<i32>,
+    pub color: Color,
+    pub code_color: Color,
+    pub code_background: Color,
+    pub paragraph_spacing: f32,
+    /// Indent per list nesting level, markers included
+    pub list_indent: f32,
+}
+
+struct Placed {
+    shaped: ShapedText,
+    /// List marker, drawn in the block color
+    marker: Option<(ShapedText, kurbo::Point)>,
+    /// Code span backgrounds, relative to the paragraph
+    code: Vec<kurbo::RoundedRect>,
+    x: f32,
+    y: f32,
+    height: f32,
+}
+
+/// Laid out paragraphs, in physical pixels
+pub struct TextBlock {
+    paragraphs: Vec<Placed>,
+    width: f32,
+    height: f32,
+    color: Color,
+    code_background: Color,
+}
+
+impl TextBlock {
+    /// Shape and stack `paragraphs` to fit `width` (logical px)
+    pub fn new(text_cx: &mut TextContext, paragraphs: &[Paragraph], style: &BlockStyle, width: f32, scale: f32) -> Self {
+        let mut placed = Vec::with_capacity(paragraphs.len());
+        let (mut y, mut right) = (0.0f32, 0.0f32);
+        let mut above = None;
+        for paragraph in paragraphs {
+            if let Some(above) = above {
+                y += gap(above, paragraph.kind, style.paragraph_spacing) * scale;
+            }
+            above = Some(paragraph.kind);
+
+            let mut para_style = style.text;
+            para_style.font_size *= paragraph.kind.font_scale();
+            if let Kind::Heading(_) = paragraph.kind {
+                para_style.weight = Some(BOLD_WEIGHT);
+            }
+            let indent = paragraph.kind.indent(style.list_indent);
+            let spans = text_spans(&paragraph.runs, style);
+            let wrap_width = (width - indent).max(para_style.font_size);
+            let shaped = text::build_styled_layout(text_cx, paragraph.text, &spans, &para_style, wrap_width, style.color, scale);
+
+            let em = (para_style.font_size * scale) as f64;
+            let code = paragraph
+                .runs
+                .iter()
+                .filter(|run| run.code)
+                .flat_map(|run| text::selection_geometry(&shaped.layout, run.range.clone()))
+                .map(|rect| rect.inflate(em * CODE_PAD, 0.0).to_rounded_rect(em * CODE_RADIUS))
+                .collect();
+
+            // Right-aligned in the indent, on the item's first baseline
+            let marker = paragraph.kind.marker().map(|marker| {
+                let marker = text::shape_text(text_cx, &marker, &para_style, text::NO_WRAP_WIDTH, scale);
+                let left = (indent - MARKER_GAP * para_style.font_size) * scale - marker.layout.width();
+                let top = first_baseline(&shaped) - first_baseline(&marker);
+                (marker, kurbo::Point::new(left.max(0.0) as f64, top as f64))
+            });
+
+            let x = indent * scale;
+            let height = text::layout_metrics(&shaped).height;
+            right = right.max(x + shaped.layout.width());
+            placed.push(Placed { shaped, marker, code, x, y, height });
+            y += height;
+        }
+
+        Self {
+            paragraphs: placed,
+            width: right,
+            height: y,
+            color: style.color,
+            code_background: style.code_background,
+        }
+    }
+
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    pub fn paragraph_count(&self) -> usize {
+        self.paragraphs.len()
+    }
+
+    /// Bounds of paragraph `index`'s text relative to the block origin
+    pub fn paragraph_rect(&self, index: usize) -> Option<kurbo::Rect> {
+        let placed = self.paragraphs.get(index)?;
+        let (x, y) = (placed.x as f64, placed.y as f64);
+        Some(kurbo::Rect::new(x, y, x + placed.shaped.layout.width() as f64, y + placed.height as f64))
+    }
+
+    /// Paragraph index and byte offset nearest a point relative to the block
+    /// origin; None for an empty block
+    pub fn hit_test(&self, x: f32, y: f32) -> Option<(usize, usize)> {
+        // The paragraph ending below y, or the last one; gaps go to the one below
+        let index = self
+            .paragraphs
+            .partition_point(|placed| placed.y + placed.height <= y)
+            .min(self.paragraphs.len().checked_sub(1)?);
+        let placed = &self.paragraphs[index];
+        Some((index, text::hit_test_layout(&placed.shaped.layout, x - placed.x, y - placed.y)))
+    }
+
+    /// Draw at a physical position
+    pub fn draw(&self, scene: &mut Scene, x: f32, y: f32, rendering: text::Rendering) {
+        for placed in &self.paragraphs {
+            let (left, top) = (x + placed.x, y + placed.y);
+            let transform = kurbo::Affine::translate((left as f64, top as f64));
+            for rect in &placed.code {
+                scene.fill(vello::peniko::Fill::NonZero, transform, self.code_background, None, rect);
+            }
+            if let Some((marker, at)) = &placed.marker {
+                text::draw_layout(scene, marker, x + at.x as f32, top + at.y as f32, self.color, rendering);
+            }
+            text::draw_styled_layout(scene, &placed.shaped, left, top, rendering);
+        }
+    }
+}
+
+fn text_spans(runs: &[Run], style: &BlockStyle) -> Vec<TextSpan> {
+    runs.iter()
+        .map(|run| TextSpan {
+            range: run.range.clone(),
+            color: run.color.unwrap_or(if run.code { style.code_color } else { style.color }),
+            weight: run.bold.then_some(BOLD_WEIGHT),
+            italic: run.italic,
+            underline: run.underline,
+            font_id: if run.code { style.code_font_id } else { None },
+        })
+        .collect()
+}
+
+fn first_baseline(shaped: &ShapedText) -> f32 {
+    shaped.layout.lines().next().map_or(0.0, |line| line.metrics().baseline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kinds_from_ffi() {
+        assert_eq!(Kind::from_ffi(0, 3, 0), Kind::Paragraph);
+        assert_eq!(Kind::from_ffi(1, 0, 0), Kind::Heading(1));
+        assert_eq!(Kind::from_ffi(1, 9, 0), Kind::Heading(6));
+        assert_eq!(Kind::from_ffi(2, 100, 0), Kind::Bullet(MAX_DEPTH));
+        assert_eq!(Kind::from_ffi(3, 1, 12), Kind::Numbered { depth: 1, number: 12 });
+        assert_eq!(Kind::from_ffi(42, 1, 1), Kind::Paragraph);
+
+        assert_eq!(Kind::Heading(2).font_scale(), 1.5);
+        assert_eq!(Kind::Bullet(1).marker().as_deref(), Some("◦"));
+        assert_eq!(Kind::Bullet(3).marker().as_deref(), Some("•"));
+        assert_eq!(Kind::Numbered { depth: 0, number: 12 }.marker().as_deref(), Some("12."));
+        assert_eq!(Kind::Heading(1).marker(), None);
+    }
+
+    #[test]
+    fn test_list_items_indent_and_sit_closer() {
+        assert_eq!(Kind::Paragraph.indent(20.0), 0.0);
+        assert_eq!(Kind::Bullet(0).indent(20.0), 20.0);
+        assert_eq!(Kind::Numbered { depth: 2, number: 1 }.indent(20.0), 60.0);
+
+        assert_eq!(gap(Kind::Paragraph, Kind::Bullet(0), 12.0), 12.0);
+        assert_eq!(gap(Kind::Bullet(0), Kind::Bullet(1), 12.0), 3.0);
+        assert_eq!(gap(Kind::Numbered { depth: 0, number: 1 }, Kind::Heading(2), 12.0), 12.0);
+    }
+}
@@ -1,5 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::ops::Range;
+use peniko::Color;
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+use crate::engine::Engine;
+use crate::hit::Transform;
+use crate::text::{self, CaretRect, TextDirection};
+use crate::{McoreRgba, McoreTextInputStyle};
 
 /// IME composition (preedit) state
 #[derive(Default, Clone)]
@@ -8,6 +15,274 @@ pub struct ImeComposition {
     pub cursor_offset: usize,  // Cursor position within preedit text
 }
 
+/// Where a text input's text was last drawn: its origin in the local space of
+/// the hit transform stack, and that transform (mapping local to root)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Placement {
+    pub origin_x: f32,
+    pub origin_y: f32,
+    pub transform: Transform,
+}
+
+impl Placement {
+    /// Root point for a point relative to the text origin
+    pub fn to_root(&self, x: f32, y: f32) -> (f32, f32) {
+        self.transform.apply_point(self.origin_x + x, self.origin_y + y)
+    }
+
+    /// Point relative to the text origin for a root point
+    pub fn from_root(&self, x: f32, y: f32) -> (f32, f32) {
+        let (local_x, local_y) = self.transform.to_local(x, y);
+        (local_x - self.origin_x, local_y - self.origin_y)
+    }
+}
+
+/// Characters a constrained input accepts
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CharClass {
+    #[default]
+    Any,
+    /// 0-9
+    Numeric,
+    /// 0-9, one '.', and a leading '-'
+    Decimal,
+    /// 0-9, a-f, A-F
+    Hex,
+}
+
+/// Why (part of) an insertion was dropped
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    /// It would have gone over the maximum length
+    Length = 1,
+    /// It contained characters outside the input's class
+    Character = 2,
+}
+
+/// Limits enforced on typed, pasted and committed text. Content set by the
+/// host (set_text) is taken as is.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Constraints {
+    pub max_bytes: Option<usize>,
+    pub max_graphemes: Option<usize>,
+    pub chars: CharClass,
+}
+
+/// Virtual keyboard a host should show for an input
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum KeyboardType {
+    #[default]
+    Default,
+    Email,
+    Number,
+    Decimal,
+    Url,
+}
+
+impl KeyboardType {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Email,
+            2 => Self::Number,
+            3 => Self::Decimal,
+            4 => Self::Url,
+            _ => Self::Default,
+        }
+    }
+}
+
+/// Autocorrection: the platform's choice, or forced on or off
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Autocorrect {
+    #[default]
+    Default,
+    On,
+    Off,
+}
+
+impl Autocorrect {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::On,
+            2 => Self::Off,
+            _ => Self::Default,
+        }
+    }
+}
+
+/// How a host should configure its text input system (NSTextInputContext,
+/// UIKit keyboards) while an input has focus
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputHints {
+    pub keyboard: KeyboardType,
+    pub autocorrect: Autocorrect,
+}
+
+/// Caret blink cycle in seconds: shown for the first half, hidden for the second
+pub const CARET_BLINK_PERIOD: f64 = 1.0;
+
+/// Room kept past the caret when scrolling horizontally to follow it
+pub const CARET_FOLLOW_MARGIN: f32 = 20.0;
+
+/// Visible area of an editor, for caret-follow scrolling (logical px)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+    pub width: f32,
+    pub height: f32,
+    pub font_size: f32,
+    /// Multi-line editors wrap at this width; None lays the content out on one line
+    pub wrap_width: Option<f32>,
+}
+
+impl Viewport {
+    /// The scroll offset closest to `scroll` that shows the whole caret,
+    /// kept within the content's (width, height). Single-line viewports only
+    /// scroll horizontally (hosts center the line vertically).
+    pub fn follow(&self, scroll: (f32, f32), caret: CaretRect, content: (f32, f32)) -> (f32, f32) {
+        let margin = CARET_FOLLOW_MARGIN.min(self.width / 2.0);
+        let (mut x, mut y) = scroll;
+
+        if caret.x - x > self.width - margin {
+            x = caret.x - self.width + margin;
+        }
+        if caret.x < x {
+            x = caret.x;
+        }
+        if caret.y + caret.height - y > self.height {
+            y = caret.y + caret.height - self.height;
+        }
+        if caret.y < y {
+            y = caret.y;
+        }
+
+        // Shrinking content (deleting at the end) scrolls back
+        let max_x = (content.0.max(caret.x) + margin - self.width).max(0.0);
+        let max_y = match self.wrap_width {
+            Some(_) => (content.1.max(caret.y + caret.height) - self.height).max(0.0),
+            None => 0.0,
+        };
+        (x.clamp(0.0, max_x), y.clamp(0.0, max_y))
+    }
+}
+
+/// Drawn for each grapheme of a secure input
+const MASK: &str = "\u{2022}";
+
+/// Password mode: content is drawn as bullets
+#[derive(Clone, Debug, Default)]
+struct Secure {
+    /// How long a typed character stays visible, in seconds (0 never shows it)
+    reveal_for: f64,
+    /// Start of the last typed grapheme, and when it gets masked again
+    revealed: Option<(usize, f64)>,
+}
+
+/// What a text input shows: its content with any IME preedit spliced in
+/// where the composition will land (replacing the selection), masked for
+/// secure inputs. Offsets are bytes in `text`, not in the content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayText {
+    pub text: String,
+    pub caret: usize,
+    /// Drawn underlined
+    pub preedit: Option<Range<usize>>,
+    pub selection: Option<Range<usize>>,
+    /// (content offset, display offset) at each shown content grapheme boundary
+    boundaries: Vec<(usize, usize)>,
+}
+
+impl DisplayText {
+    /// Content byte offset for a display offset, e.g. from hit testing the
+    /// drawn text (offsets inside the preedit map to where it will land)
+    pub fn to_content(&self, display: usize) -> usize {
+        match self.boundaries.binary_search_by_key(&display, |&(_, d)| d) {
+            Ok(i) => self.boundaries[i].0,
+            Err(0) => 0,
+            Err(i) => self.boundaries[i - 1].0,
+        }
+    }
+
+    fn to_display(&self, content: usize) -> usize {
+        match self.boundaries.binary_search_by_key(&content, |&(c, _)| c) {
+            Ok(i) => self.boundaries[i].1,
+            Err(0) => 0,
+            Err(i) => self.boundaries[i - 1].1,
+        }
+    }
+}
+
+/// Cursor motions, which can move the caret or extend the selection
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Motion {
+    /// Arrow keys move visually: in a right-to-left paragraph Left moves
+    /// toward the end of the text
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    /// Logical: toward the start / end of the text whatever its direction
+    Backward,
+    Forward,
+    WordBackward,
+    WordForward,
+    /// Start / end of the text (the right / left edge of an RTL paragraph)
+    Home,
+    End,
+}
+
+impl Motion {
+    /// The logical motion an arrow key makes in a paragraph of this direction.
+    /// Runs embedded in the other direction move with the paragraph.
+    fn resolve(self, rtl: bool) -> Motion {
+        match (self, rtl) {
+            (Motion::Left, false) | (Motion::Right, true) => Motion::Backward,
+            (Motion::Right, false) | (Motion::Left, true) => Motion::Forward,
+            (Motion::WordLeft, false) | (Motion::WordRight, true) => Motion::WordBackward,
+            (Motion::WordRight, false) | (Motion::WordLeft, true) => Motion::WordForward,
+            (motion, _) => motion,
+        }
+    }
+}
+
+/// Undo steps kept per text input unless the host sets a limit
+pub const DEFAULT_UNDO_LIMIT: usize = 100;
+
+/// Content and caret as they were before an edit
+struct Snapshot {
+    content: String,
+    cursor: usize,
+    selection: Option<Range<usize>>,
+}
+
+/// Edits that coalesce with the one before them into a single undo step
+#[derive(Copy, Clone, PartialEq)]
+enum EditKind {
+    Typing,
+    Deleting,
+    /// Paste, word deletion, IME commits: always their own step
+    Other,
+}
+
+/// Undo/redo stacks for one text input
+struct History {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+    limit: usize,
+    /// The typing/deleting run in progress, and where its caret ended up
+    run: Option<(EditKind, usize)>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit: DEFAULT_UNDO_LIMIT,
+            run: None,
+        }
+    }
+}
+
 /// State for a single text input widget
 #[derive(Default)]
 pub struct TextInputState {
@@ -16,6 +291,23 @@ pub struct TextInputState {
     pub selection: Option<Range<usize>>,
     pub selection_anchor: Option<usize>,  // Where the selection started (for drag selection)
     pub ime_composition: Option<ImeComposition>,  // Active IME composition
+    history: History,
+    secure: Option<Secure>,
+    /// Set while drawing (mcore_text_input_set_origin), for root-space queries
+    pub placement: Option<Placement>,
+    constraints: Constraints,
+    /// Latest rejection not yet reported to the host
+    rejection: Option<Rejection>,
+    pub hints: InputHints,
+    /// Paragraph direction, for arrow keys and alignment
+    pub direction: TextDirection,
+    /// Set by the host (mcore_text_input_set_viewport) to have the engine
+    /// scroll to keep the caret visible
+    pub viewport: Option<Viewport>,
+    /// Offset of the viewport into the laid-out content (logical px)
+    pub scroll: (f32, f32),
+    /// When the caret last moved; blinking restarts from here
+    caret_epoch: f64,
 }
 
 impl TextInputState {
@@ -23,58 +315,299 @@ impl TextInputState {
         Self::default()
     }
 
-    pub fn insert_char(&mut self, ch: char) {
-        // Delete selection if present
-        if let Some(sel) = &self.selection {
-            self.content.drain(sel.clone());
-            self.cursor = sel.start;
-            self.selection = None;
+    /// Remove the selected text, leaving the caret where it was.
+    /// Returns false if there was no selection.
+    fn delete_selection(&mut self) -> bool {
+        // Any edit ends the selection gesture, so Shift+arrow starts afresh
+        self.selection_anchor = None;
+        let Some(sel) = self.selection.take() else {
+            return false;
+        };
+        self.content.drain(sel.clone());
+        self.cursor = sel.start;
+        true
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            content: self.content.clone(),
+            cursor: self.cursor,
+            selection: self.selection.clone(),
         }
+    }
 
-        // Insert character at cursor
-        self.content.insert(self.cursor, ch);
-        self.cursor += ch.len_utf8();
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.content = snapshot.content;
+        self.cursor = snapshot.cursor;
+        self.selection = snapshot.selection;
+        self.selection_anchor = None;
+        self.history.run = None;
     }
 
-    pub fn backspace(&mut self) {
-        if let Some(sel) = &self.selection {
-            // Delete selection
-            self.content.drain(sel.clone());
-            self.cursor = sel.start;
-            self.selection = None;
-        } else if self.cursor > 0 {
-            // Find previous grapheme boundary (simplified: just use char boundary)
-            let prev = previous_char_boundary(&self.content, self.cursor);
-            self.content.drain(prev..self.cursor);
-            self.cursor = prev;
+    /// Apply an edit, recording an undo step unless it continues the current
+    /// typing or deleting run (same kind, caret where the run left it)
+    fn edit(&mut self, kind: EditKind, apply: impl FnOnce(&mut Self)) {
+        if let Some(secure) = self.secure.as_mut() {
+            secure.revealed = None;
         }
+        let before = self.snapshot();
+        let continues_run = kind != EditKind::Other
+            && self.selection.is_none()
+            && self.history.run == Some((kind, self.cursor));
+
+        apply(self);
+        if self.content == before.content {
+            return; // e.g. backspace at the start
+        }
+
+        if !continues_run {
+            if self.history.undo.len() == self.history.limit {
+                self.history.undo.pop_front();
+            }
+            if self.history.limit > 0 {
+                self.history.undo.push_back(before);
+            }
+        }
+        self.history.redo.clear();
+        self.history.run = (kind != EditKind::Other).then_some((kind, self.cursor));
     }
 
-    pub fn delete(&mut self) {
-        if let Some(sel) = &self.selection {
-            // Delete selection
-            self.content.drain(sel.clone());
-            self.cursor = sel.start;
-            self.selection = None;
-        } else if self.cursor < self.content.len() {
-            // Find next grapheme boundary (simplified: just use char boundary)
-            let next = next_char_boundary(&self.content, self.cursor);
-            self.content.drain(self.cursor..next);
+    /// Revert the last undo step (Cmd+Z). Returns false if there is none.
+    pub fn undo(&mut self) -> bool {
+        let Some(previous) = self.history.undo.pop_back() else {
+            return false;
+        };
+        self.history.redo.push(self.snapshot());
+        self.restore(previous);
+        true
+    }
+
+    /// Reapply the last undone step (Cmd+Shift+Z). Returns false if there is none.
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.history.redo.pop() else {
+            return false;
+        };
+        self.history.undo.push_back(self.snapshot());
+        self.restore(next);
+        true
+    }
+
+    /// Keep at most `limit` undo steps (0 disables undo)
+    pub fn set_undo_limit(&mut self, limit: usize) {
+        self.history.limit = limit;
+        while self.history.undo.len() > limit {
+            self.history.undo.pop_front();
+        }
+    }
+
+    pub fn set_constraints(&mut self, constraints: Constraints) {
+        self.constraints = constraints;
+    }
+
+    /// The latest rejected insertion since the last call, if any
+    pub fn take_rejection(&mut self) -> Option<Rejection> {
+        self.rejection.take()
+    }
+
+    /// The part of `text` the constraints let in, replacing the selection at
+    /// the caret. Anything dropped is recorded as a rejection.
+    fn admit(&mut self, text: &str) -> String {
+        let c = self.constraints;
+        if c == Constraints::default() {
+            return text.to_string();
+        }
+
+        let at = self.selection.as_ref().map_or(self.cursor, |sel| sel.start);
+        let removed = self.selection.as_ref().map_or(0..0, Clone::clone);
+        let kept = || self.content[..removed.start].chars().chain(self.content[removed.end..].chars());
+
+        let mut has_point = kept().any(|ch| ch == '.');
+        let has_sign = kept().next() == Some('-');
+        let mut admitted = String::with_capacity(text.len());
+        let mut rejection = None;
+        for ch in text.chars() {
+            let ok = match c.chars {
+                CharClass::Any => true,
+                CharClass::Numeric => ch.is_ascii_digit(),
+                CharClass::Hex => ch.is_ascii_hexdigit(),
+                CharClass::Decimal => match ch {
+                    // Nothing goes in front of the sign
+                    _ if at == 0 && has_sign => false,
+                    '.' => !std::mem::replace(&mut has_point, true),
+                    '-' => at == 0 && admitted.is_empty() && !has_sign,
+                    _ => ch.is_ascii_digit(),
+                },
+            };
+            if ok {
+                admitted.push(ch);
+            } else {
+                rejection = Some(Rejection::Character);
+            }
+        }
+
+        // Truncate to whole graphemes that fit in what's left
+        let kept_bytes = self.content.len() - removed.len();
+        let mut bytes_left = c.max_bytes.map_or(usize::MAX, |max| max.saturating_sub(kept_bytes));
+        let mut graphemes_left = c.max_graphemes.map_or(usize::MAX, |max| {
+            let kept_graphemes = self.content[..removed.start].graphemes(true).count()
+                + self.content[removed.end..].graphemes(true).count();
+            max.saturating_sub(kept_graphemes)
+        });
+        let mut fits = 0;
+        for grapheme in admitted.graphemes(true) {
+            if grapheme.len() > bytes_left || graphemes_left == 0 {
+                rejection = Some(Rejection::Length);
+                break;
+            }
+            bytes_left -= grapheme.len();
+            graphemes_left -= 1;
+            fits += grapheme.len();
+        }
+        admitted.truncate(fits);
+
+        if rejection.is_some() {
+            self.rejection = rejection;
+        }
+        admitted
+    }
+
+    /// Returns false if the constraints rejected the character
+    pub fn insert_char(&mut self, ch: char) -> bool {
+        let text = self.admit(ch.encode_utf8(&mut [0; 4]));
+        if text.is_empty() {
+            return false;
         }
+        self.edit(EditKind::Typing, |s| {
+            s.delete_selection();
+
+            // Insert character at cursor
+            s.content.insert_str(s.cursor, &text);
+            s.cursor += text.len();
+        });
+        true
+    }
+
+    pub fn backspace(&mut self) {
+        self.edit(EditKind::Deleting, |s| {
+            if !s.delete_selection() && s.cursor > 0 {
+                let prev = previous_grapheme_boundary(&s.content, s.cursor);
+                s.content.drain(prev..s.cursor);
+                s.cursor = prev;
+            }
+        });
+    }
+
+    pub fn delete(&mut self) {
+        self.edit(EditKind::Deleting, |s| {
+            if !s.delete_selection() && s.cursor < s.content.len() {
+                let next = next_grapheme_boundary(&s.content, s.cursor);
+                s.content.drain(s.cursor..next);
+            }
+        });
     }
 
     pub fn move_cursor_left(&mut self) {
         if self.cursor > 0 {
-            self.cursor = previous_char_boundary(&self.content, self.cursor);
+            self.cursor = previous_grapheme_boundary(&self.content, self.cursor);
         }
     }
 
     pub fn move_cursor_right(&mut self) {
         if self.cursor < self.content.len() {
-            self.cursor = next_char_boundary(&self.content, self.cursor);
+            self.cursor = next_grapheme_boundary(&self.content, self.cursor);
+        }
+    }
+
+    /// Move to the start of the previous word (Option+Left on macOS)
+    pub fn move_cursor_word_left(&mut self) {
+        self.cursor = previous_word_boundary(&self.content, self.cursor);
+    }
+
+    /// Move to the end of the next word (Option+Right on macOS)
+    pub fn move_cursor_word_right(&mut self) {
+        self.cursor = next_word_boundary(&self.content, self.cursor);
+    }
+
+    /// Delete back to the start of the previous word (Option+Backspace on macOS)
+    pub fn backspace_word(&mut self) {
+        self.edit(EditKind::Other, |s| {
+            if !s.delete_selection() {
+                let start = previous_word_boundary(&s.content, s.cursor);
+                s.content.drain(start..s.cursor);
+                s.cursor = start;
+            }
+        });
+    }
+
+    /// Delete forward to the end of the next word (Option+Delete on macOS)
+    pub fn delete_word(&mut self) {
+        self.edit(EditKind::Other, |s| {
+            if !s.delete_selection() {
+                let end = next_word_boundary(&s.content, s.cursor);
+                s.content.drain(s.cursor..end);
+            }
+        });
+    }
+
+    /// Move the caret, or with `extend` (Shift held) move the selection's
+    /// focus while its anchor stays put. Without `extend`, Left/Right
+    /// collapse an existing selection to its start/end instead of moving.
+    pub fn move_cursor(&mut self, motion: Motion, extend: bool) {
+        let motion = motion.resolve(self.is_rtl());
+        if extend {
+            let anchor = self.anchor();
+            self.apply_motion(motion);
+            self.select_from(anchor, self.cursor);
+            return;
+        }
+
+        let selection = self.selection.take();
+        self.selection_anchor = None;
+        match (motion, selection) {
+            (Motion::Backward, Some(sel)) => self.cursor = sel.start,
+            (Motion::Forward, Some(sel)) => self.cursor = sel.end,
+            _ => self.apply_motion(motion),
         }
     }
 
+    /// Apply a resolved (logical) motion
+    fn apply_motion(&mut self, motion: Motion) {
+        match motion {
+            Motion::Backward => self.move_cursor_left(),
+            Motion::Forward => self.move_cursor_right(),
+            Motion::WordBackward => self.move_cursor_word_left(),
+            Motion::WordForward => self.move_cursor_word_right(),
+            Motion::Home => self.move_cursor_home(),
+            Motion::End => self.move_cursor_end(),
+            Motion::Left | Motion::Right | Motion::WordLeft | Motion::WordRight => {
+                unreachable!("arrow motions are resolved before being applied")
+            }
+        }
+    }
+
+    /// Whether the content is laid out right to left
+    pub fn is_rtl(&self) -> bool {
+        self.direction.is_rtl(&self.content)
+    }
+
+    /// Where an extended selection grows from: the existing anchor while a
+    /// selection or drag is active, otherwise the caret
+    fn anchor(&self) -> usize {
+        self.selection_anchor.unwrap_or(self.cursor)
+    }
+
+    /// Select from `anchor` to `focus`, leaving the caret at `focus`
+    fn select_from(&mut self, anchor: usize, focus: usize) {
+        self.selection = (anchor != focus).then(|| anchor.min(focus)..anchor.max(focus));
+        self.selection_anchor = Some(anchor);
+        self.cursor = focus;
+    }
+
+    /// Select the whole content, caret at the end (Cmd+A)
+    pub fn select_all(&mut self) {
+        self.select_from(0, self.content.len());
+    }
+
     pub fn move_cursor_home(&mut self) {
         self.cursor = 0;
     }
@@ -84,30 +617,34 @@ impl TextInputState {
     }
 
     pub fn set_cursor(&mut self, position: usize) {
-        // Clamp to valid range and ensure on char boundary
-        self.cursor = position.min(self.content.len());
-        while !self.content.is_char_boundary(self.cursor) && self.cursor > 0 {
-            self.cursor -= 1;
-        }
+        // Clamp to valid range and ensure on grapheme boundary
+        self.cursor = ensure_grapheme_boundary(&self.content, position);
     }
 
-    pub fn insert_text(&mut self, text: &str) {
-        // Delete selection if present
-        if let Some(sel) = &self.selection {
-            self.content.drain(sel.clone());
-            self.cursor = sel.start;
-            self.selection = None;
+    /// Returns false if the constraints rejected all of `text`. Rejected
+    /// parts are dropped and the rest inserted.
+    pub fn insert_text(&mut self, text: &str) -> bool {
+        let text = self.admit(text);
+        if text.is_empty() {
+            return false;
         }
+        self.edit(EditKind::Other, |s| {
+            s.delete_selection();
 
-        // Insert text at cursor
-        self.content.insert_str(self.cursor, text);
-        self.cursor += text.len();
+            // Insert text at cursor
+            s.content.insert_str(s.cursor, &text);
+            s.cursor += text.len();
+        });
+        true
     }
 
+    /// Replace the content from the host; clears undo history
     pub fn set_text(&mut self, text: &str) {
         self.content = text.to_string();
         self.cursor = self.content.len();
         self.selection = None;
+        self.selection_anchor = None;
+        self.history = History { limit: self.history.limit, ..History::default() };
     }
 
     /// Start a selection at the current cursor position
@@ -115,39 +652,18 @@ impl TextInputState {
         self.selection = Some(self.cursor..self.cursor);
     }
 
-    /// Extend selection to a specific byte position
+    /// Extend selection to a specific byte position (drag or Shift+click),
+    /// anchored where the selection started or at the caret
     pub fn extend_selection_to(&mut self, position: usize) {
-        let pos = position.min(self.content.len());
-        let pos = ensure_char_boundary(&self.content, pos);
-
-        // Get or set the anchor point (where selection started)
-        let anchor = self.selection_anchor.unwrap_or(self.cursor);
-
-        eprintln!("extend_selection_to: pos={}, anchor={}, cursor={}", pos, anchor, self.cursor);
-
-        // Create selection from anchor to current position
-        let start = anchor.min(pos);
-        let end = anchor.max(pos);
-
-        eprintln!("  selection range: {}..{}", start, end);
-
-        if start < end {
-            self.selection = Some(start..end);
-            eprintln!("  SET selection to {:?}", self.selection);
-        } else {
-            self.selection = None;
-            eprintln!("  CLEARED selection (start >= end)");
-        }
-
-        self.cursor = pos;
-        self.selection_anchor = Some(anchor);
+        let pos = ensure_grapheme_boundary(&self.content, position);
+        self.select_from(self.anchor(), pos);
     }
 
     /// Set selection to a specific range
     pub fn set_selection(&mut self, start: usize, end: usize, cursor: usize) {
-        let start = ensure_char_boundary(&self.content, start.min(self.content.len()));
-        let end = ensure_char_boundary(&self.content, end.min(self.content.len()));
-        let cursor = ensure_char_boundary(&self.content, cursor.min(self.content.len()));
+        let start = ensure_grapheme_boundary(&self.content, start.min(self.content.len()));
+        let end = ensure_grapheme_boundary(&self.content, end.min(self.content.len()));
+        let cursor = ensure_grapheme_boundary(&self.content, cursor.min(self.content.len()));
 
         if start < end {
             self.selection = Some(start..end);
@@ -171,39 +687,174 @@ impl TextInputState {
     pub fn get_selection(&self) -> Option<Range<usize>> {
         self.selection.clone()
     }
-}
 
-/// Find the previous character boundary
-fn previous_char_boundary(text: &str, cursor: usize) -> usize {
-    let mut offset = cursor;
-    while offset > 0 {
-        offset -= 1;
-        if text.is_char_boundary(offset) {
-            break;
+    /// Show `text` as the in-progress IME composition (empty ends it)
+    pub fn set_composition(&mut self, text: &str, cursor_offset: usize) {
+        if text.is_empty() {
+            self.ime_composition = None;
+            return;
+        }
+        let mut cursor_offset = cursor_offset.min(text.len());
+        while !text.is_char_boundary(cursor_offset) {
+            cursor_offset -= 1;
         }
+        self.ime_composition = Some(ImeComposition { text: text.to_string(), cursor_offset });
+    }
+
+    /// Replace the composition (and selection) with the IME's final text
+    pub fn commit_composition(&mut self, text: &str) -> bool {
+        self.ime_composition = None;
+        !text.is_empty() && self.insert_text(text)
+    }
+
+    /// Drop the composition without inserting anything
+    pub fn cancel_composition(&mut self) {
+        self.ime_composition = None;
+    }
+
+    /// Draw the content as bullets (password fields). With `reveal_for` > 0
+    /// each typed character shows for that many seconds first.
+    pub fn set_secure(&mut self, secure: bool, reveal_for: f64) {
+        // Hosts may call this every frame, so keep a pending reveal
+        let revealed = self.secure.take().and_then(|s| s.revealed);
+        self.secure = secure.then(|| Secure { reveal_for: reveal_for.max(0.0), revealed });
+    }
+
+    pub fn is_secure(&self) -> bool {
+        self.secure.is_some()
+    }
+
+    /// Keep the caret solid for a moment after typing or moving it
+    pub fn touch_caret(&mut self, now: f64) {
+        self.caret_epoch = now;
+    }
+
+    /// Whether the blinking caret shows at `now`, and when that next changes
+    pub fn caret_blink(&self, now: f64) -> (bool, f64) {
+        let half = CARET_BLINK_PERIOD / 2.0;
+        let phase = (now - self.caret_epoch).max(0.0) % CARET_BLINK_PERIOD;
+        (phase < half, now + half - phase % half)
     }
-    offset
-}
 
-/// Find the next character boundary
-fn next_char_boundary(text: &str, cursor: usize) -> usize {
-    let mut offset = cursor;
-    while offset < text.len() {
-        offset += 1;
-        if text.is_char_boundary(offset) {
-            break;
+    /// Whether input methods (CJK composition, dictation, emoji) should be
+    /// active: not for passwords, and not for fields that only take ASCII
+    /// digits, addresses or hex
+    pub fn ime_enabled(&self) -> bool {
+        !self.is_secure() && self.hints.keyboard == KeyboardType::Default && self.constraints.chars == CharClass::Any
+    }
+
+    /// Show the grapheme just typed until `now` + the reveal time. Returns
+    /// when it gets masked again, for scheduling a redraw.
+    pub fn reveal_last_typed(&mut self, now: f64) -> Option<f64> {
+        let start = previous_grapheme_boundary(&self.content, self.cursor);
+        let secure = self.secure.as_mut().filter(|s| s.reveal_for > 0.0)?;
+        let until = now + secure.reveal_for;
+        secure.revealed = Some((start, until));
+        Some(until)
+    }
+
+    /// The revealed grapheme's start, while it's still the one before the caret
+    fn revealed(&self, now: f64) -> Option<usize> {
+        let (start, until) = self.secure.as_ref()?.revealed?;
+        let current = now < until
+            && self.selection.is_none()
+            && start < self.cursor
+            && previous_grapheme_boundary(&self.content, self.cursor) == start;
+        current.then_some(start)
+    }
+
+    /// Append `range` of `source` to `out`, masked if secure except for the
+    /// grapheme at `revealed`, recording content/display boundaries
+    fn push_shown(&self, out: &mut DisplayText, source: &str, range: Range<usize>, revealed: Option<usize>) {
+        for (i, grapheme) in source[range.clone()].grapheme_indices(true) {
+            let offset = range.start + i;
+            out.boundaries.push((offset, out.text.len()));
+            let masked = self.secure.is_some() && revealed != Some(offset);
+            out.text.push_str(if masked { MASK } else { grapheme });
         }
+        out.boundaries.push((range.end, out.text.len()));
     }
-    offset
+
+    /// The text to draw at engine time `now`, with caret, preedit and selection
+    pub fn display(&self, now: f64) -> DisplayText {
+        let revealed = self.revealed(now);
+        let mut out = DisplayText {
+            text: String::with_capacity(self.content.len()),
+            caret: 0,
+            preedit: None,
+            selection: None,
+            boundaries: Vec::new(),
+        };
+
+        let Some(composition) = &self.ime_composition else {
+            self.push_shown(&mut out, &self.content, 0..self.content.len(), revealed);
+            out.caret = out.to_display(self.cursor);
+            out.selection = self.selection.as_ref().map(|s| out.to_display(s.start)..out.to_display(s.end));
+            return out;
+        };
+
+        let replaced = self.selection.clone().unwrap_or(self.cursor..self.cursor);
+        self.push_shown(&mut out, &self.content, 0..replaced.start, revealed);
+
+        // The preedit isn't content, so it gets no boundaries of its own
+        let preedit_start = out.text.len();
+        let mut preedit = DisplayText { text: String::new(), caret: 0, preedit: None, selection: None, boundaries: Vec::new() };
+        self.push_shown(&mut preedit, &composition.text, 0..composition.text.len(), None);
+        out.caret = preedit_start + preedit.to_display(composition.cursor_offset);
+        out.text.push_str(&preedit.text);
+        out.preedit = Some(preedit_start..out.text.len());
+
+        self.push_shown(&mut out, &self.content, replaced.end..self.content.len(), revealed);
+        out
+    }
+}
+
+/// Find the previous extended grapheme cluster boundary, so emoji ZWJ
+/// sequences and combining marks are never split
+fn previous_grapheme_boundary(text: &str, cursor: usize) -> usize {
+    let mut graphemes = GraphemeCursor::new(cursor, text.len(), true);
+    graphemes.prev_boundary(text, 0).ok().flatten().unwrap_or(0)
+}
+
+/// Find the next extended grapheme cluster boundary
+fn next_grapheme_boundary(text: &str, cursor: usize) -> usize {
+    let mut graphemes = GraphemeCursor::new(cursor, text.len(), true);
+    graphemes.next_boundary(text, 0).ok().flatten().unwrap_or(text.len())
+}
+
+/// A word segment (as opposed to whitespace or punctuation between words)
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+/// Start of the word before the cursor, skipping whitespace and punctuation
+fn previous_word_boundary(text: &str, cursor: usize) -> usize {
+    text.split_word_bound_indices()
+        .take_while(|(start, _)| *start < cursor)
+        .filter(|(_, segment)| is_word(segment))
+        .last()
+        .map_or(0, |(start, _)| start)
+}
+
+/// End of the word after the cursor, skipping whitespace and punctuation
+fn next_word_boundary(text: &str, cursor: usize) -> usize {
+    text.split_word_bound_indices()
+        .find(|(start, segment)| start + segment.len() > cursor && is_word(segment))
+        .map_or(text.len(), |(start, segment)| start + segment.len())
 }
 
-/// Ensure a position is on a character boundary, moving backward if necessary
-fn ensure_char_boundary(text: &str, position: usize) -> usize {
+/// Ensure a position is on a grapheme boundary, moving backward if necessary
+fn ensure_grapheme_boundary(text: &str, position: usize) -> usize {
     let mut pos = position.min(text.len());
     while pos > 0 && !text.is_char_boundary(pos) {
         pos -= 1;
     }
-    pos
+    let mut graphemes = GraphemeCursor::new(pos, text.len(), true);
+    if graphemes.is_boundary(text, 0).unwrap_or(true) {
+        pos
+    } else {
+        previous_grapheme_boundary(text, pos)
+    }
 }
 
 /// Manager for all text input states
@@ -226,11 +877,183 @@ impl TextInputManager {
         self.states.get(&id)
     }
 
+    /// Number of text inputs with state
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
     pub fn get_mut(&mut self, id: u64) -> Option<&mut TextInputState> {
         self.states.get_mut(&id)
     }
 }
 
+/// Draw input `id` (mcore_text_input_draw); the caret blink's frame request
+/// is left for the caller to take
+pub fn draw(eng: &mut Engine, id: u64, s: &McoreTextInputStyle) {
+    // The content area is the viewport the caret is kept in
+    let viewport = Viewport {
+        width: (s.width - 2.0 * s.padding_x).max(1.0),
+        height: (s.height - 2.0 * s.padding_y).max(1.0),
+        font_size: s.font_size,
+        wrap_width: (s.wrap_width > 0.0).then_some(s.wrap_width),
+    };
+    eng.text_inputs.get_or_create(id).viewport = Some(viewport);
+    follow_caret(eng, id);
+
+    let now = eng.time_s;
+    let scale = eng.gfx.scale();
+    let color = |c: McoreRgba| Color::new([c.r, c.g, c.b, c.a]);
+    let transform = eng.hits.current_transform();
+    let reduce_motion = eng.prefs.reduce_motion;
+
+    let state = eng.text_inputs.get_or_create(id);
+    let display = state.display(now);
+    let scroll = state.scroll;
+    let (blink_on, blink_change) = state.caret_blink(now);
+    let rtl = state.is_rtl();
+
+    let text_style = text::TextStyle {
+        direction: state.direction,
+        ..text::TextStyle::new(s.font_size, text::DEFAULT_FONT_ID)
+    };
+    let wrap = viewport.wrap_width.unwrap_or(text::NO_WRAP_WIDTH);
+    let (content_width, content_height) = text::measure_text(&mut eng.text_cx, &display.text, &text_style, wrap, scale);
+    // A right-to-left line that fits hugs the field's right edge
+    let align_x = if rtl && viewport.wrap_width.is_none() {
+        (viewport.width - content_width).max(0.0)
+    } else {
+        0.0
+    };
+    let origin_x = s.x + s.padding_x + align_x - scroll.0;
+    let origin_y = if viewport.wrap_width.is_some() {
+        s.y + s.padding_y - scroll.1
+    } else {
+        s.y + (s.height - content_height) / 2.0
+    };
+    state.placement = Some(Placement { origin_x, origin_y, transform });
+
+    let rect = |x: f32, y: f32, w: f32, h: f32| {
+        peniko::kurbo::Rect::new(
+            (x * scale) as f64,
+            (y * scale) as f64,
+            ((x + w) * scale) as f64,
+            ((y + h) * scale) as f64,
+        )
+    };
+    let identity = peniko::kurbo::Affine::IDENTITY;
+    let fill = vello::peniko::Fill::NonZero;
+
+    eng.scene.push_layer(vello::peniko::BlendMode::default(), 1.0, identity, &rect(s.x, s.y, s.width, s.height));
+
+    // Selection (a composition replaces it, so it's hidden while composing)
+    let focused = s.focused != 0;
+    if let Some(selection) = display.selection.clone().filter(|_| focused && display.preedit.is_none()) {
+        let selection_color = color(s.selection_color);
+        for r in text::selection_rects(&mut eng.text_cx, &display.text, &text_style, wrap, selection, scale) {
+            let r = rect(origin_x + r.x0 as f32, origin_y + r.y0 as f32, r.width() as f32, r.height() as f32);
+            eng.scene.fill(fill, identity, selection_color, None, &r);
+        }
+    }
+
+    let text_color = color(s.text_color);
+    if !display.text.is_empty() {
+        text::draw_text(
+            &mut eng.scene,
+            &mut eng.text_cx,
+            &display.text,
+            origin_x * scale,
+            origin_y * scale,
+            &text_style,
+            wrap,
+            text_color,
+            scale,
+        );
+    }
+
+    // Preedit underline along the bottom of each of its line boxes
+    if let Some(preedit) = display.preedit.clone() {
+        for r in text::selection_rects(&mut eng.text_cx, &display.text, &text_style, wrap, preedit, scale) {
+            let underline = rect(origin_x + r.x0 as f32, origin_y + r.y1 as f32 - 2.0, r.width() as f32, 1.0);
+            eng.scene.fill(fill, identity, text_color, None, &underline);
+        }
+    }
+
+    if focused && (blink_on || reduce_motion) {
+        let caret = text::caret_for_offset(&mut eng.text_cx, &display.text, &text_style, wrap, display.caret, scale);
+        let caret_rect = rect(origin_x + caret.x, origin_y + caret.y, 1.0, caret.height);
+        eng.scene.fill(fill, identity, color(s.caret_color), None, &caret_rect);
+    }
+    if focused && !reduce_motion {
+        eng.scheduler.request_at(blink_change);
+    }
+
+    eng.scene.pop_layer();
+}
+
+/// Scroll a text input's viewport to show its caret; returns whether it moved
+pub fn follow_caret(eng: &mut Engine, id: u64) -> bool {
+    let Some(state) = eng.text_inputs.get(id) else {
+        return false;
+    };
+    let Some(viewport) = state.viewport else {
+        return false;
+    };
+    let display = state.display(eng.time_s);
+    let scroll = state.scroll;
+
+    let style = text::TextStyle {
+        direction: state.direction,
+        ..text::TextStyle::new(viewport.font_size, text::DEFAULT_FONT_ID)
+    };
+    let wrap = viewport.wrap_width.unwrap_or(text::NO_WRAP_WIDTH);
+    let scale = eng.gfx.scale();
+    eng.text_stats.total_offset_calls += 1;
+    let caret = text::caret_for_offset(&mut eng.text_cx, &display.text, &style, wrap, display.caret, scale);
+    let content = text::measure_text(&mut eng.text_cx, &display.text, &style, wrap, scale);
+
+    let followed = viewport.follow(scroll, caret, content);
+    let state = eng.text_inputs.get_or_create(id);
+    state.scroll = followed;
+    followed != scroll
+}
+
+/// Content offset under a point relative to a single-line input's text origin
+pub fn hit_test(eng: &mut Engine, id: u64, font_size: f32, x: f32, y: f32) -> usize {
+    let Some(display) = eng.text_inputs.get(id).map(|state| state.display(eng.time_s)) else {
+        return 0;
+    };
+
+    eng.text_stats.total_offset_calls += 1;
+    let scale = eng.gfx.scale();
+    let offset = text::hit_test_text(
+        &mut eng.text_cx,
+        &display.text,
+        &text::TextStyle::new(font_size, text::DEFAULT_FONT_ID),
+        text::NO_WRAP_WIDTH,
+        x,
+        y,
+        scale,
+    );
+    display.to_content(offset)
+}
+
+/// Caret of a single-line input relative to its text origin (logical)
+pub fn caret(eng: &mut Engine, id: u64, font_size: f32) -> CaretRect {
+    let display = eng.text_inputs.get(id).map(|state| state.display(eng.time_s));
+    let (text, caret) = display.as_ref().map_or(("", 0), |d| (d.text.as_str(), d.caret));
+
+    eng.text_stats.total_offset_calls += 1;
+    let scale = eng.gfx.scale();
+    text::caret_for_offset(
+        &mut eng.text_cx,
+        text,
+        &text::TextStyle::new(font_size, text::DEFAULT_FONT_ID),
+        text::NO_WRAP_WIDTH,
+        caret,
+        scale,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,6 +1088,331 @@ mod tests {
         assert_eq!(state.cursor, 4);
     }
 
+    #[test]
+    fn test_extend_selection_with_motion() {
+        let mut state = TextInputState::new();
+        state.insert_text("hello world");
+        state.move_cursor(Motion::WordLeft, true);
+        assert_eq!(state.get_selection(), Some(6..11));
+        state.move_cursor(Motion::Left, true);
+        assert_eq!(state.get_selection(), Some(5..11));
+        assert_eq!(state.cursor, 5);
+
+        // Extending back past the anchor flips the selection around it
+        state.move_cursor(Motion::End, true);
+        assert_eq!(state.get_selection(), None);
+        state.move_cursor(Motion::Home, true);
+        assert_eq!(state.get_selection(), Some(0..11));
+
+        // Plain Right collapses to the selection end
+        state.move_cursor(Motion::Right, false);
+        assert_eq!(state.get_selection(), None);
+        assert_eq!(state.cursor, 11);
+    }
+
+    #[test]
+    fn test_arrows_follow_paragraph_direction() {
+        let mut state = TextInputState::new();
+        state.insert_text("שלום עולם");
+        let end = state.content.len();
+
+        // Left moves toward the end of right-to-left text
+        state.move_cursor(Motion::Home, false);
+        state.move_cursor(Motion::Left, false);
+        assert_eq!(state.cursor, "ש".len());
+        state.move_cursor(Motion::WordLeft, false);
+        assert_eq!(state.cursor, "שלום".len());
+        state.move_cursor(Motion::Backward, false);
+        assert_eq!(state.cursor, "שלו".len());
+
+        // Collapsing a selection with Right goes to its logical start
+        state.move_cursor(Motion::End, true);
+        state.move_cursor(Motion::Right, false);
+        assert_eq!(state.cursor, "שלו".len());
+        assert_eq!(state.get_selection(), None);
+        state.move_cursor(Motion::Forward, true);
+        assert_eq!(state.get_selection(), Some("שלו".len().."שלום".len()));
+
+        // Overriding the direction restores left-to-right arrows
+        state.direction = TextDirection::Ltr;
+        state.set_cursor(end);
+        state.clear_selection();
+        state.selection_anchor = None;
+        state.move_cursor(Motion::Left, false);
+        assert_eq!(state.cursor, end - "ם".len());
+    }
+
+    #[test]
+    fn test_select_all_and_shift_click() {
+        let mut state = TextInputState::new();
+        state.insert_text("hello world");
+        state.select_all();
+        assert_eq!(state.get_selection_text(), Some("hello world"));
+
+        // Click at 2, then Shift+click at 8 and back at 0: anchor stays at 2
+        state.move_cursor(Motion::Home, false);
+        state.set_cursor(2);
+        state.extend_selection_to(8);
+        assert_eq!(state.get_selection(), Some(2..8));
+        state.extend_selection_to(0);
+        assert_eq!(state.get_selection(), Some(0..2));
+
+        state.backspace();
+        assert_eq!(state.content, "llo world");
+        assert_eq!(state.cursor, 0);
+    }
+
+    #[test]
+    fn test_undo_coalesces_typing() {
+        let mut state = TextInputState::new();
+        for ch in "hello".chars() {
+            state.insert_char(ch);
+        }
+        state.insert_text(" world");
+        state.backspace();
+        state.backspace();
+
+        assert!(state.undo());
+        assert_eq!(state.content, "hello world");
+        assert!(state.undo());
+        assert_eq!(state.content, "hello");
+        assert!(state.undo());
+        assert_eq!(state.content, "");
+        assert!(!state.undo());
+
+        assert!(state.redo());
+        assert!(state.redo());
+        assert_eq!(state.content, "hello world");
+        assert_eq!(state.cursor, 11);
+
+        // A new edit drops the redo stack
+        state.insert_char('!');
+        assert!(!state.redo());
+    }
+
+    #[test]
+    fn test_undo_breaks_runs_on_cursor_moves() {
+        let mut state = TextInputState::new();
+        state.insert_char('a');
+        state.insert_char('b');
+        state.move_cursor(Motion::Home, false);
+        state.insert_char('c');
+        assert_eq!(state.content, "cab");
+
+        assert!(state.undo());
+        assert_eq!(state.content, "ab");
+        assert_eq!(state.cursor, 0);
+
+        // Backspace at the start changes nothing and records nothing
+        state.backspace();
+        assert!(state.undo());
+        assert_eq!(state.content, "");
+    }
+
+    #[test]
+    fn test_undo_limit() {
+        let mut state = TextInputState::new();
+        state.set_undo_limit(2);
+        for text in ["a", "b", "c"] {
+            state.insert_text(text);
+        }
+        assert!(state.undo());
+        assert!(state.undo());
+        assert!(!state.undo());
+        assert_eq!(state.content, "a");
+
+        state.set_undo_limit(0);
+        state.insert_text("x");
+        assert!(!state.undo());
+    }
+
+    #[test]
+    fn test_ime_composition() {
+        let mut state = TextInputState::new();
+        state.insert_text("ab");
+        state.move_cursor(Motion::Left, false);
+
+        // "にほ" with the IME caret after the first character
+        state.set_composition("にほ", 3);
+        let display = state.display(0.0);
+        assert_eq!(display.to_content(4), 1);
+        assert_eq!(display.text, "aにほb");
+        assert_eq!(display.preedit, Some(1..7));
+        assert_eq!(display.caret, 4);
+        assert_eq!(state.content, "ab");
+
+        // Offsets inside a character snap back to its start
+        state.set_composition("日本", 4);
+        assert_eq!(state.display(0.0).caret, 4);
+
+        state.commit_composition("日本");
+        assert_eq!(state.content, "a日本b");
+        assert_eq!(state.cursor, 7);
+        assert_eq!(state.display(0.0).preedit, None);
+
+        // A composition replaces the selection, and cancelling leaves it alone
+        state.select_all();
+        state.set_composition("x", 1);
+        assert_eq!(state.display(0.0).text, "x");
+        state.cancel_composition();
+        assert_eq!(state.display(0.0).text, "a日本b");
+    }
+
+    #[test]
+    fn test_secure_display() {
+        let mut state = TextInputState::new();
+        state.set_secure(true, 1.0);
+        state.insert_text("pä");
+        let display = state.display(0.0);
+        assert_eq!(display.text, "\u{2022}\u{2022}");
+        assert_eq!(display.caret, 6);
+        assert_eq!(display.to_content(3), 1);
+        assert_eq!(display.to_content(6), 3);
+
+        // The last typed character shows until the reveal time passes
+        state.insert_char('x');
+        assert_eq!(state.reveal_last_typed(10.0), Some(11.0));
+        assert_eq!(state.display(10.5).text, "\u{2022}\u{2022}x");
+        assert_eq!(state.display(11.0).text, "\u{2022}\u{2022}\u{2022}");
+
+        // Moving the caret masks it straight away
+        state.move_cursor(Motion::Left, false);
+        assert_eq!(state.display(10.5).text, "\u{2022}\u{2022}\u{2022}");
+
+        state.select_all();
+        assert_eq!(state.display(0.0).selection, Some(0..9));
+        assert_eq!(state.content, "päx");
+    }
+
+    #[test]
+    fn test_constraints() {
+        let mut state = TextInputState::new();
+        state.set_constraints(Constraints { chars: CharClass::Decimal, ..Default::default() });
+        for ch in "-1a.5.-".chars() {
+            state.insert_char(ch);
+        }
+        assert_eq!(state.content, "-1.5");
+        assert_eq!(state.take_rejection(), Some(Rejection::Character));
+        assert_eq!(state.take_rejection(), None);
+        state.set_cursor(0);
+        assert!(!state.insert_char('2'), "nothing before the sign");
+
+        // Pastes keep the characters that fit, by whole graphemes
+        let mut state = TextInputState::new();
+        state.set_constraints(Constraints { max_graphemes: Some(4), ..Default::default() });
+        assert!(state.insert_text("ab"));
+        assert!(state.insert_text("ce\u{301}fg"));
+        assert_eq!(state.content, "abce\u{301}");
+        assert_eq!(state.take_rejection(), Some(Rejection::Length));
+        assert!(!state.insert_char('x'));
+
+        // Replacing a selection frees its length
+        state.select_all();
+        assert!(state.insert_text("0123"));
+        assert_eq!(state.content, "0123");
+
+        let mut state = TextInputState::new();
+        state.set_constraints(Constraints { max_bytes: Some(4), chars: CharClass::Hex, ..Default::default() });
+        assert!(state.insert_text("0xBEEF"));
+        assert_eq!(state.content, "0BEE");
+        assert_eq!(state.take_rejection(), Some(Rejection::Length));
+
+        // The host's own text isn't constrained
+        state.set_text("not hex");
+        assert_eq!(state.content, "not hex");
+    }
+
+    #[test]
+    fn test_ime_enabled() {
+        let mut state = TextInputState::new();
+        assert!(state.ime_enabled());
+        state.hints.keyboard = KeyboardType::Email;
+        assert!(!state.ime_enabled());
+
+        state.hints.keyboard = KeyboardType::Default;
+        state.set_constraints(Constraints { chars: CharClass::Numeric, ..Default::default() });
+        assert!(!state.ime_enabled());
+
+        state.set_constraints(Constraints::default());
+        state.set_secure(true, 0.0);
+        assert!(!state.ime_enabled());
+    }
+
+    #[test]
+    fn test_viewport_follows_caret() {
+        let viewport = Viewport { width: 100.0, height: 40.0, font_size: 16.0, wrap_width: Some(100.0) };
+        let caret = |x, y| CaretRect { x, y, height: 20.0 };
+        let content = (100.0, 200.0);
+
+        // Already visible: nothing moves
+        assert_eq!(viewport.follow((0.0, 0.0), caret(10.0, 20.0), content), (0.0, 0.0));
+        // Below the viewport: scroll just far enough to show the caret's line
+        assert_eq!(viewport.follow((0.0, 0.0), caret(10.0, 100.0), content), (0.0, 80.0));
+        // Above it
+        assert_eq!(viewport.follow((0.0, 80.0), caret(10.0, 40.0), content), (0.0, 40.0));
+        // Content shrank below the offset
+        assert_eq!(viewport.follow((0.0, 80.0), caret(0.0, 0.0), (0.0, 20.0)), (0.0, 0.0));
+
+        // Single line: keep the margin past the caret while typing at the end
+        let line = Viewport { width: 100.0, height: 20.0, font_size: 16.0, wrap_width: None };
+        assert_eq!(line.follow((0.0, 0.0), caret(150.0, 0.0), (150.0, 20.0)), (70.0, 0.0));
+        assert_eq!(line.follow((70.0, 0.0), caret(30.0, 0.0), (150.0, 20.0)), (30.0, 0.0));
+        assert_eq!(line.follow((70.0, 0.0), caret(60.0, 0.0), (60.0, 20.0)), (0.0, 0.0));
+        // A field shorter than the line still never scrolls vertically
+        let short = Viewport { height: 12.0, ..line };
+        assert_eq!(short.follow((0.0, 0.0), caret(150.0, 0.0), (150.0, 20.0)), (70.0, 0.0));
+    }
+
+    #[test]
+    fn test_caret_blink() {
+        let mut state = TextInputState::new();
+        state.touch_caret(10.0);
+        assert_eq!(state.caret_blink(10.0), (true, 10.5));
+        assert_eq!(state.caret_blink(10.75), (false, 11.0));
+        assert_eq!(state.caret_blink(11.25), (true, 11.5));
+
+        // Typing restarts the cycle with the caret shown
+        state.touch_caret(10.75);
+        assert_eq!(state.caret_blink(10.75), (true, 11.25));
+    }
+
+    #[test]
+    fn test_word_movement() {
+        let mut state = TextInputState::new();
+        state.insert_text("hello, big world");
+        state.move_cursor_word_left();
+        assert_eq!(state.cursor, 11);
+        state.move_cursor_word_left();
+        assert_eq!(state.cursor, 7);
+        state.move_cursor_word_left();
+        assert_eq!(state.cursor, 0);
+        state.move_cursor_word_left();
+        assert_eq!(state.cursor, 0);
+
+        state.move_cursor_word_right();
+        assert_eq!(state.cursor, 5);
+        state.move_cursor_word_right();
+        assert_eq!(state.cursor, 10);
+        state.move_cursor_word_right();
+        state.move_cursor_word_right();
+        assert_eq!(state.cursor, 16);
+    }
+
+    #[test]
+    fn test_word_deletion() {
+        let mut state = TextInputState::new();
+        state.insert_text("one two  three");
+        state.backspace_word();
+        assert_eq!(state.content, "one two  ");
+        state.backspace_word();
+        assert_eq!(state.content, "one ");
+
+        state.move_cursor_home();
+        state.delete_word();
+        assert_eq!(state.content, " ");
+        assert_eq!(state.cursor, 0);
+    }
+
     #[test]
     fn test_utf8_handling() {
         let mut state = TextInputState::new();
@@ -276,4 +1424,30 @@ mod tests {
         assert_eq!(state.content, "日");
         assert_eq!(state.cursor, 3);
     }
+
+    #[test]
+    fn test_grapheme_clusters() {
+        // Family emoji (ZWJ sequence) and e + combining acute accent
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let accented = "e\u{301}";
+        let mut state = TextInputState::new();
+        state.insert_text(family);
+        state.insert_text(accented);
+
+        state.move_cursor_left();
+        assert_eq!(state.cursor, family.len());
+        state.move_cursor_left();
+        assert_eq!(state.cursor, 0);
+        state.move_cursor_right();
+        assert_eq!(state.cursor, family.len());
+
+        state.delete();
+        assert_eq!(state.content, family);
+        state.backspace();
+        assert_eq!(state.content, "");
+
+        state.set_text(family);
+        state.set_cursor(4);
+        assert_eq!(state.cursor, 0);
+    }
 }
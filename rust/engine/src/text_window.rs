@@ -0,0 +1,149 @@
+/// Windowed layout of large documents (log files, transcripts loaded whole)
+///
+/// A text layout shapes its whole string, which stops being interactive
+/// somewhere in the megabytes. A window splits the document at its hard line
+/// breaks once, up front, and shapes lines only when the host asks for a
+/// range of them. Lines already shaped for the previous range are kept, so
+/// scrolling shapes just the lines coming into view. Line indices count hard
+/// lines of the document, so they stay put as the window moves.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use peniko::Color;
+use vello::Scene;
+
+use crate::text::{self, ShapedText, TextContext, TextStyle};
+
+pub struct TextWindow {
+    text: String,
+    /// Where each line starts
+    starts: Vec<usize>,
+    style: TextStyle,
+    /// Logical px
+    wrap_width: f32,
+    /// Scale factor the shaped lines were built at
+    scale: f32,
+    range: Range<usize>,
+    /// Lines in `range` by index, with their heights (physical px)
+    shaped: HashMap<usize, (ShapedText, f32)>,
+}
+
+impl TextWindow {
+    /// Index `text`'s lines; nothing is shaped until `set_range`
+    pub fn new(text: String, style: TextStyle, wrap_width: f32) -> Self {
+        let starts = std::iter::once(0).chain(text.match_indices('\n').map(|(i, _)| i + 1)).collect();
+        // Each line is its own paragraph, so a line limit would apply per line
+        let style = TextStyle { max_lines: None, ..style };
+        Self { text, starts, style, wrap_width, scale: 0.0, range: 0..0, shaped: HashMap::new() }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Byte range of line `line`, without its line break
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.starts.get(line)?;
+        let end = self.starts.get(line + 1).map_or(self.text.len(), |next| next - 1);
+        let end = if self.text[start..end].ends_with('\r') { end - 1 } else { end };
+        Some(start..end)
+    }
+
+    /// Line containing byte `offset` (the last line past the end)
+    pub fn line_at(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    /// Shape lines `range` (clamped to the document), dropping the lines
+    /// shaped for any other range. Returns the lines' heights (physical px).
+    pub fn set_range(&mut self, text_cx: &mut TextContext, range: Range<usize>, scale: f32) -> Vec<f32> {
+        let end = range.end.min(self.line_count());
+        let range = range.start.min(end)..end;
+        if scale != self.scale {
+            self.scale = scale;
+            self.shaped.clear();
+        }
+        self.shaped.retain(|line, _| range.contains(line));
+        for line in range.clone() {
+            if self.shaped.contains_key(&line) {
+                continue;
+            }
+            let source = &self.text[self.line_range(line).expect("line in document")];
+            let shaped = text::shape_text(text_cx, source, &self.style, self.wrap_width, scale);
+            let height = text::layout_metrics(&shaped).height;
+            self.shaped.insert(line, (shaped, height));
+        }
+        self.range = range;
+        self.heights().collect()
+    }
+
+    /// Scale factor the shaped lines were built at (0 before any)
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    fn heights(&self) -> impl Iterator<Item = f32> + '_ {
+        self.range.clone().map(|line| self.shaped[&line].1)
+    }
+
+    /// Lines in the range with their tops, relative to the first one
+    fn placed(&self) -> impl Iterator<Item = (usize, &ShapedText, f32)> + '_ {
+        let mut top = 0.0;
+        self.range.clone().map(move |line| {
+            let (shaped, height) = &self.shaped[&line];
+            let placed = (line, shaped, top);
+            top += height;
+            placed
+        })
+    }
+
+    /// Document byte offset nearest a point relative to the top of the range
+    /// (physical px); None when no lines are shaped
+    pub fn hit_test(&self, x: f32, y: f32) -> Option<usize> {
+        let mut last = None;
+        for (line, shaped, top) in self.placed() {
+            if top > y && last.is_some() {
+                break;
+            }
+            last = Some((line, shaped, top));
+        }
+        let (line, shaped, top) = last?;
+        let start = self.starts[line];
+        Some(start + text::hit_test_layout(&shaped.layout, x, y - top))
+    }
+
+    /// Draw the range with its first line's top left at physical (x, y)
+    pub fn draw(&self, scene: &mut Scene, x: f32, y: f32, color: Color, rendering: text::Rendering) {
+        for (_, shaped, top) in self.placed() {
+            text::draw_layout(scene, shaped, x, y + top, color, rendering);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_indexed_up_front() {
+        let style = TextStyle::new(14.0, 0);
+        let window = TextWindow::new("first\r\nsecond\n\nlast".to_string(), style, 200.0);
+        assert_eq!(window.line_count(), 4);
+        assert_eq!(window.line_range(0), Some(0..5));
+        assert_eq!(window.line_range(1), Some(7..13));
+        assert_eq!(window.line_range(2), Some(14..14));
+        assert_eq!(window.line_range(3), Some(15..19));
+        assert_eq!(window.line_range(4), None);
+
+        assert_eq!(window.line_at(0), 0);
+        assert_eq!(window.line_at(6), 0, "the line break belongs to its line");
+        assert_eq!(window.line_at(7), 1);
+        assert_eq!(window.line_at(14), 2);
+        assert_eq!(window.line_at(1000), 3);
+
+        let empty = TextWindow::new(String::new(), style, 200.0);
+        assert_eq!(empty.line_count(), 1);
+        assert_eq!(empty.line_range(0), Some(0..0));
+    }
+}
@@ -0,0 +1,240 @@
+/// Opt-in tracing of FFI calls
+///
+/// When enabled (mcore_trace_enable, or MCORE_TRACE=<capacity> in the
+/// environment), every FFI entry point records its name and scalar/pointer
+/// arguments into a fixed-size ring buffer. Hosts dump it with
+/// mcore_trace_dump after an error; API misuse that would otherwise corrupt
+/// memory (calls on a destroyed context, double destroy) prints the history
+/// to stderr and aborts.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+
+/// Ring size used by MCORE_TRACE when it isn't a number
+pub const DEFAULT_CAPACITY: usize = 256;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ENV_CHECKED: Once = Once::new();
+static RING: Mutex<Option<TraceRing>> = Mutex::new(None);
+/// Contexts created and not yet destroyed (tracked even while tracing is off,
+/// so enabling it mid-session still catches use-after-destroy)
+static LIVE_CONTEXTS: Mutex<Option<HashSet<usize>>> = Mutex::new(None);
+
+/// A traced argument value
+pub enum Value {
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+    Ptr(usize),
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Int(v) => write!(f, "{v}"),
+            Value::Uint(v) => write!(f, "{v}"),
+            Value::Float(v) => write!(f, "{v}"),
+            Value::Ptr(0) => write!(f, "null"),
+            Value::Ptr(v) => write!(f, "{v:#x}"),
+        }
+    }
+}
+
+/// Arguments the tracer can record (scalars and pointers; structs passed by
+/// value and callbacks are left out)
+pub trait TraceArg {
+    fn trace_value(&self) -> Value;
+}
+
+macro_rules! impl_trace_arg {
+    ($variant:ident as $wide:ty: $($ty:ty),*) => {
+        $(impl TraceArg for $ty {
+            fn trace_value(&self) -> Value {
+                Value::$variant(*self as $wide)
+            }
+        })*
+    };
+}
+
+impl_trace_arg!(Int as i64: i8, i16, i32, i64, isize);
+impl_trace_arg!(Uint as u64: u8, u16, u32, u64, usize);
+impl_trace_arg!(Float as f64: f32, f64);
+
+impl TraceArg for bool {
+    fn trace_value(&self) -> Value {
+        Value::Uint(*self as u64)
+    }
+}
+
+impl<T: ?Sized> TraceArg for *const T {
+    fn trace_value(&self) -> Value {
+        Value::Ptr(self.cast::<()>() as usize)
+    }
+}
+
+impl<T: ?Sized> TraceArg for *mut T {
+    fn trace_value(&self) -> Value {
+        Value::Ptr(self.cast::<()>() as usize)
+    }
+}
+
+/// Record an FFI call: `trace_call!("mcore_resize", ctx, desc)`
+macro_rules! trace_call {
+    ($name:literal $(, $arg:ident)*) => {
+        if $crate::trace::enabled() {
+            $crate::trace::record(
+                $name,
+                &[$((stringify!($arg), $crate::trace::TraceArg::trace_value(&$arg))),*],
+            );
+        }
+    };
+}
+pub(crate) use trace_call;
+
+struct Call {
+    seq: u64,
+    elapsed_s: f64,
+    thread: String,
+    name: &'static str,
+    args: String,
+}
+
+struct TraceRing {
+    calls: VecDeque<Call>,
+    capacity: usize,
+    next_seq: u64,
+    start: Instant,
+}
+
+impl TraceRing {
+    fn new(capacity: usize) -> Self {
+        Self {
+            calls: VecDeque::with_capacity(capacity),
+            capacity,
+            next_seq: 0,
+            start: Instant::now(),
+        }
+    }
+
+    fn push(&mut self, name: &'static str, args: String) {
+        if self.calls.len() == self.capacity {
+            self.calls.pop_front();
+        }
+        let thread = std::thread::current();
+        self.calls.push_back(Call {
+            seq: self.next_seq,
+            elapsed_s: self.start.elapsed().as_secs_f64(),
+            thread: thread.name().map_or_else(|| format!("{:?}", thread.id()), str::to_string),
+            name,
+            args,
+        });
+        self.next_seq += 1;
+    }
+
+    fn dump(&self) -> String {
+        let mut out = String::new();
+        let dropped = self.next_seq - self.calls.len() as u64;
+        let _ = writeln!(out, "mcore FFI trace: last {} calls ({dropped} older dropped)", self.calls.len());
+        for call in &self.calls {
+            let _ = writeln!(
+                out,
+                "#{:<6} {:>10.4}s [{}] {}({})",
+                call.seq, call.elapsed_s, call.thread, call.name, call.args
+            );
+        }
+        out
+    }
+}
+
+/// Whether calls are being recorded (checks MCORE_TRACE on first use)
+pub fn enabled() -> bool {
+    ENV_CHECKED.call_once(|| {
+        if let Ok(value) = std::env::var("MCORE_TRACE") {
+            set_capacity(value.parse().unwrap_or(DEFAULT_CAPACITY));
+        }
+    });
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Start recording into a ring of `capacity` calls (0 stops and clears it)
+pub fn set_capacity(capacity: usize) {
+    let mut ring = RING.lock();
+    *ring = (capacity > 0).then(|| TraceRing::new(capacity));
+    ENABLED.store(capacity > 0, Ordering::Relaxed);
+}
+
+pub fn record(name: &'static str, args: &[(&str, Value)]) {
+    let mut formatted = String::new();
+    for (i, (arg, value)) in args.iter().enumerate() {
+        let sep = if i == 0 { "" } else { ", " };
+        let _ = write!(formatted, "{sep}{arg}={value}");
+    }
+
+    let dead_context = args.iter().find_map(|(arg, value)| match value {
+        Value::Ptr(addr) if *arg == "ctx" && *addr != 0 && !is_live(*addr) => Some(*addr),
+        _ => None,
+    });
+
+    let mut ring = RING.lock();
+    if let Some(ring) = ring.as_mut() {
+        ring.push(name, formatted);
+    }
+
+    if let Some(addr) = dead_context {
+        let history = ring.as_ref().map(TraceRing::dump).unwrap_or_default();
+        drop(ring);
+        eprintln!("mcore: {name} called with destroyed or unknown context {addr:#x}\n{history}");
+        std::process::abort();
+    }
+}
+
+/// The recorded history as text, oldest call first
+pub fn dump() -> Option<String> {
+    RING.lock().as_ref().map(TraceRing::dump)
+}
+
+pub fn context_created(ctx: *const ()) {
+    LIVE_CONTEXTS.lock().get_or_insert_with(HashSet::new).insert(ctx as usize);
+}
+
+pub fn context_destroyed(ctx: *const ()) {
+    if let Some(live) = LIVE_CONTEXTS.lock().as_mut() {
+        live.remove(&(ctx as usize));
+    }
+}
+
+fn is_live(ctx: usize) -> bool {
+    LIVE_CONTEXTS.lock().as_ref().is_some_and(|live| live.contains(&ctx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_keeps_most_recent_calls() {
+        let mut ring = TraceRing::new(2);
+        ring.push("mcore_begin_frame", "ctx=0x10, time_seconds=1".into());
+        ring.push("mcore_render_commands", "ctx=0x10, commands=0x20, count=3".into());
+        ring.push("mcore_end_frame_present", "ctx=0x10".into());
+
+        let dump = ring.dump();
+        assert!(dump.contains("last 2 calls (1 older dropped)"));
+        assert!(!dump.contains("mcore_begin_frame"));
+        assert!(dump.contains("mcore_render_commands(ctx=0x10, commands=0x20, count=3)"));
+        assert!(dump.contains("#2"));
+    }
+
+    #[test]
+    fn values_format_for_humans() {
+        assert_eq!(std::ptr::null::<u8>().trace_value().to_string(), "null");
+        assert_eq!((0x1000 as *mut u8).trace_value().to_string(), "0x1000");
+        assert_eq!((-3i32).trace_value().to_string(), "-3");
+        assert_eq!(1.5f32.trace_value().to_string(), "1.5");
+    }
+}
@@ -0,0 +1,300 @@
+/// Immediate-mode widgets (mcore_ui_*)
+///
+/// For small tools that don't want a host toolkit. Between mcore_ui_begin and
+/// mcore_ui_end each widget call stacks itself below the previous one, tests
+/// itself against this frame's pointer, draws itself as draw commands and
+/// returns what happened to it. Widgets are named by host IDs that stay the
+/// same across frames; press and focus are remembered by ID.
+
+use crate::command_stream::Command;
+use crate::hit::Rect;
+use crate::{McoreCmdFill, McoreCmdStroke, McoreCmdText, McoreRgba, McoreUiTheme};
+
+/// Response flags returned by widget calls
+pub const RESPONSE_HOVERED: u32 = 1 << 0;
+/// The pointer went down on the widget and is still down
+pub const RESPONSE_PRESSED: u32 = 1 << 1;
+/// Pressed and released over the widget this frame
+pub const RESPONSE_CLICKED: u32 = 1 << 2;
+pub const RESPONSE_FOCUSED: u32 = 1 << 3;
+
+/// Pointer state for a frame (logical px)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Pointer {
+    pub x: f32,
+    pub y: f32,
+    pub down: bool,
+}
+
+pub struct Ui {
+    pub theme: McoreUiTheme,
+    /// Between begin and end
+    building: bool,
+    x: f32,
+    width: f32,
+    top: f32,
+    /// Where the next widget goes
+    cursor_y: f32,
+    pointer: Pointer,
+    was_down: bool,
+    just_pressed: bool,
+    just_released: bool,
+    /// The widget the current press started on
+    pressed: Option<u64>,
+    focused: Option<u64>,
+    /// Whether a widget took this frame's press, and whether the focused
+    /// widget was drawn; focus is dropped otherwise
+    press_taken: bool,
+    focus_seen: bool,
+}
+
+impl Ui {
+    pub fn new() -> Self {
+        Self {
+            theme: McoreUiTheme::default(),
+            building: false,
+            x: 0.0,
+            width: 0.0,
+            top: 0.0,
+            cursor_y: 0.0,
+            pointer: Pointer::default(),
+            was_down: false,
+            just_pressed: false,
+            just_released: false,
+            pressed: None,
+            focused: None,
+            press_taken: false,
+            focus_seen: false,
+        }
+    }
+
+    /// A frame presented without mcore_ui_end doesn't block the next one's
+    /// widgets
+    pub fn begin_frame(&mut self) {
+        self.building = false;
+    }
+
+    /// Start laying out widgets in a column `width` wide at x, y. False if
+    /// the previous begin wasn't ended.
+    pub fn begin(&mut self, x: f32, y: f32, width: f32, pointer: Pointer) -> bool {
+        if self.building {
+            return false;
+        }
+        self.building = true;
+        self.x = x;
+        self.width = width.max(0.0);
+        self.top = y;
+        self.cursor_y = y;
+        self.just_pressed = pointer.down && !self.was_down;
+        self.just_released = !pointer.down && self.was_down;
+        self.pointer = pointer;
+        self.press_taken = false;
+        self.focus_seen = false;
+        true
+    }
+
+    pub fn is_building(&self) -> bool {
+        self.building
+    }
+
+    pub fn available_width(&self) -> f32 {
+        self.width
+    }
+
+    /// The next widget's rect: `width` wide (None fills the column) and
+    /// `height` tall
+    pub fn allocate(&mut self, width: Option<f32>, height: f32) -> Rect {
+        let width = width.unwrap_or(self.width).min(self.width);
+        let rect = Rect::from_xywh(self.x, self.cursor_y, width, height);
+        self.cursor_y += height + self.theme.spacing;
+        rect
+    }
+
+    /// Route this frame's pointer to a widget; a press on a focusable widget
+    /// focuses it, on any other widget it clears focus
+    pub fn interact(&mut self, id: u64, rect: Rect, focusable: bool) -> u32 {
+        let over = rect.contains(self.pointer.x, self.pointer.y);
+        if self.just_pressed && over && !self.press_taken {
+            self.pressed = Some(id);
+            self.press_taken = true;
+            self.focused = focusable.then_some(id);
+        }
+
+        let held = self.pressed == Some(id);
+        let mut response = 0;
+        // While a press is held only its widget shows hover
+        if over && (held || self.pressed.is_none() || !self.pointer.down) {
+            response |= RESPONSE_HOVERED;
+        }
+        if held && self.pointer.down {
+            response |= RESPONSE_PRESSED;
+        }
+        if held && self.just_released && over {
+            response |= RESPONSE_CLICKED;
+        }
+        if self.focused == Some(id) {
+            self.focus_seen = true;
+            response |= RESPONSE_FOCUSED;
+        }
+        response
+    }
+
+    /// Finish the frame's widgets and return the height they took
+    pub fn end(&mut self) -> f32 {
+        if (self.just_pressed && !self.press_taken) || !self.focus_seen {
+            self.focused = None;
+        }
+        if !self.pointer.down {
+            self.pressed = None;
+        }
+        self.was_down = self.pointer.down;
+        self.building = false;
+        (self.cursor_y - self.top - self.theme.spacing).max(0.0)
+    }
+
+    pub fn focused(&self) -> Option<u64> {
+        self.focused
+    }
+}
+
+fn rgba(c: McoreRgba) -> [f32; 4] {
+    [c.r, c.g, c.b, c.a]
+}
+
+/// A line of theme text at x, y, wrapped at wrap_width (0 for none)
+pub fn text<'a>(theme: &McoreUiTheme, x: f32, y: f32, wrap_width: f32, utf8: &'a str) -> Command<'a> {
+    Command::Text(
+        McoreCmdText {
+            x,
+            y,
+            font_size: theme.font_size,
+            wrap_width,
+            font_id: crate::text::DEFAULT_FONT_ID,
+            color: rgba(theme.text),
+            align: 0,
+            direction: 0,
+            text_len: utf8.len() as u32,
+        },
+        utf8,
+    )
+}
+
+/// A button's background, shaded by its response, and its label
+pub fn button<'a>(theme: &McoreUiTheme, rect: Rect, label: &'a str, response: u32) -> [Command<'a>; 2] {
+    let background = if response & RESPONSE_PRESSED != 0 {
+        theme.button_pressed
+    } else if response & RESPONSE_HOVERED != 0 {
+        theme.button_hover
+    } else {
+        theme.button
+    };
+    [
+        Command::Fill(McoreCmdFill {
+            x: rect.x0,
+            y: rect.y0,
+            width: rect.x1 - rect.x0,
+            height: rect.y1 - rect.y0,
+            radius: theme.corner_radius,
+            color: rgba(background),
+        }),
+        text(theme, rect.x0 + theme.padding, rect.y0 + theme.padding, 0.0, label),
+    ]
+}
+
+/// A text field's background, ringed in the accent color while focused
+pub fn field_frame(theme: &McoreUiTheme, rect: Rect, response: u32) -> Vec<Command<'static>> {
+    let (width, height) = (rect.x1 - rect.x0, rect.y1 - rect.y0);
+    let mut commands = vec![Command::Fill(McoreCmdFill {
+        x: rect.x0,
+        y: rect.y0,
+        width,
+        height,
+        radius: theme.corner_radius,
+        color: rgba(theme.field),
+    })];
+    if response & RESPONSE_FOCUSED != 0 {
+        commands.push(Command::Stroke(McoreCmdStroke {
+            x: rect.x0,
+            y: rect.y0,
+            width,
+            height,
+            radius: theme.corner_radius,
+            line_width: 1.5,
+            color: rgba(theme.accent),
+        }));
+    }
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(ui: &mut Ui, x: f32, y: f32, down: bool, widgets: &[(u64, bool)]) -> Vec<u32> {
+        assert!(ui.begin(0.0, 0.0, 200.0, Pointer { x, y, down }));
+        let responses = widgets
+            .iter()
+            .map(|&(id, focusable)| {
+                let rect = ui.allocate(Some(100.0), 20.0);
+                ui.interact(id, rect, focusable)
+            })
+            .collect();
+        ui.end();
+        responses
+    }
+
+    #[test]
+    fn test_click_needs_press_and_release_on_the_widget() {
+        let mut ui = Ui::new();
+        ui.theme.spacing = 10.0;
+        let widgets = [(1, false), (2, false)];
+
+        // The second widget starts at y = 30
+        assert_eq!(frame(&mut ui, 10.0, 35.0, false, &widgets), [0, RESPONSE_HOVERED]);
+        assert_eq!(frame(&mut ui, 10.0, 35.0, true, &widgets), [0, RESPONSE_HOVERED | RESPONSE_PRESSED]);
+        assert_eq!(frame(&mut ui, 10.0, 35.0, false, &widgets), [0, RESPONSE_HOVERED | RESPONSE_CLICKED]);
+
+        // Dragging off before releasing doesn't click, and the other widget
+        // isn't hovered while the press is held
+        frame(&mut ui, 10.0, 35.0, true, &widgets);
+        assert_eq!(frame(&mut ui, 10.0, 5.0, true, &widgets), [0, RESPONSE_PRESSED]);
+        assert_eq!(frame(&mut ui, 10.0, 5.0, false, &widgets), [RESPONSE_HOVERED, 0]);
+    }
+
+    #[test]
+    fn test_focus_follows_presses() {
+        let mut ui = Ui::new();
+        ui.theme.spacing = 0.0;
+        let widgets = [(1, true), (2, false)];
+
+        frame(&mut ui, 10.0, 5.0, true, &widgets);
+        assert_eq!(ui.focused(), Some(1));
+        assert_eq!(frame(&mut ui, 150.0, 5.0, false, &widgets), [RESPONSE_FOCUSED, 0]);
+
+        // Pressing a button takes focus away, as does pressing empty space
+        frame(&mut ui, 10.0, 25.0, true, &widgets);
+        assert_eq!(ui.focused(), None);
+        frame(&mut ui, 10.0, 25.0, false, &widgets);
+        frame(&mut ui, 10.0, 5.0, true, &widgets);
+        frame(&mut ui, 10.0, 5.0, false, &widgets);
+        frame(&mut ui, 150.0, 5.0, true, &widgets);
+        assert_eq!(ui.focused(), None);
+
+        // A focused widget that stops being drawn loses focus
+        frame(&mut ui, 10.0, 5.0, false, &widgets);
+        frame(&mut ui, 10.0, 5.0, true, &widgets);
+        frame(&mut ui, 10.0, 5.0, false, &[]);
+        assert_eq!(ui.focused(), None);
+    }
+
+    #[test]
+    fn test_widgets_stack_down_the_column() {
+        let mut ui = Ui::new();
+        ui.theme.spacing = 4.0;
+        assert!(ui.begin(10.0, 20.0, 200.0, Pointer::default()));
+        assert!(!ui.begin(0.0, 0.0, 0.0, Pointer::default()), "already building");
+        assert_eq!(ui.allocate(None, 30.0), Rect::from_xywh(10.0, 20.0, 200.0, 30.0));
+        assert_eq!(ui.allocate(Some(500.0), 10.0), Rect::from_xywh(10.0, 54.0, 200.0, 10.0));
+        assert_eq!(ui.end(), 44.0);
+    }
+}
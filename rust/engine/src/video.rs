@@ -0,0 +1,593 @@
+/// Video frames in the scene (mcore_video_frame_submit)
+///
+/// A host player (AVPlayerItemVideoOutput, a camera session) hands over each
+/// decoded frame as BGRA or NV12 pixels, or as the IOSurface behind a
+/// CVPixelBuffer. A video is a custom widget texture (custom.rs) whose
+/// renderer uploads the latest frame's planes and converts them to RGB on
+/// the GPU with the frame's Y'CbCr matrix and range; draws composite that
+/// texture like any other image, fitted to their rect.
+///
+/// Frames are copied at submit, so the host can recycle its buffer at once.
+/// Only the latest frame is kept: one submitted faster than frames are
+/// presented replaces the one before it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use peniko::kurbo::{Affine, Rect};
+use peniko::{Fill, ImageBrush};
+use vello::Scene;
+
+use crate::custom::{self, CustomTextureManager, RenderTarget, TextureRenderer};
+use crate::gfx::Gfx;
+
+/// How a frame's pixels are laid out (MCORE_VIDEO_FORMAT_*)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// One plane, 4 bytes per pixel
+    Bgra8,
+    /// A luma plane, then a half-size plane of interleaved Cb, Cr bytes
+    Nv12,
+}
+
+/// The Y'CbCr to R'G'B' matrix of an NV12 frame (MCORE_VIDEO_MATRIX_*)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Matrix {
+    /// HD video
+    Bt709,
+    /// SD video and most JPEG-derived content
+    Bt601,
+}
+
+/// How a frame is fitted to a draw's rect (MCORE_VIDEO_FIT_*)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fit {
+    /// Whole frame, letterboxed to keep its aspect ratio
+    Contain,
+    /// Rect covered, the frame cropped to keep its aspect ratio
+    Cover,
+    /// Frame stretched to the rect
+    Stretch,
+}
+
+/// A decoded frame, rows tightly packed
+pub struct Frame {
+    pub format: PixelFormat,
+    pub width: u32,
+    pub height: u32,
+    pub planes: Vec<Vec<u8>>,
+    pub matrix: Matrix,
+    /// Luma and chroma use 0-255 rather than 16-235 / 16-240
+    pub full_range: bool,
+}
+
+/// Bytes per row and rows of each plane of a width x height frame
+pub fn plane_sizes(format: PixelFormat, width: u32, height: u32) -> Vec<(usize, usize)> {
+    let (w, h) = (width as usize, height as usize);
+    match format {
+        PixelFormat::Bgra8 => vec![(w * 4, h)],
+        PixelFormat::Nv12 => vec![(w, h), (w.div_ceil(2) * 2, h.div_ceil(2))],
+    }
+}
+
+impl Frame {
+    /// Copy planes given as (bytes, bytes per row), dropping row padding
+    pub fn from_planes(
+        format: PixelFormat,
+        width: u32,
+        height: u32,
+        planes: &[(&[u8], usize)],
+        matrix: Matrix,
+        full_range: bool,
+    ) -> Result<Self, String> {
+        if width == 0 || height == 0 {
+            return Err(format!("Invalid video frame size {}x{}", width, height));
+        }
+        let sizes = plane_sizes(format, width, height);
+        if planes.len() < sizes.len() {
+            return Err(format!("{:?} frames need {} planes", format, sizes.len()));
+        }
+        let mut packed = Vec::with_capacity(sizes.len());
+        for (i, (&(row_bytes, rows), &(bytes, stride))) in sizes.iter().zip(planes).enumerate() {
+            if stride < row_bytes {
+                return Err(format!("Plane {} stride {} is shorter than its {} byte rows", i, stride, row_bytes));
+            }
+            let needed = stride * (rows - 1) + row_bytes;
+            if bytes.len() < needed {
+                return Err(format!("Plane {} has {} bytes, expected at least {}", i, bytes.len(), needed));
+            }
+            let mut plane = Vec::with_capacity(row_bytes * rows);
+            for row in 0..rows {
+                plane.extend_from_slice(&bytes[row * stride..][..row_bytes]);
+            }
+            packed.push(plane);
+        }
+        Ok(Self { format, width, height, planes: packed, matrix, full_range })
+    }
+
+    /// Copy the frame out of an IOSurface (a CVPixelBuffer's backing); its
+    /// pixel format decides the layout and range
+    pub fn from_iosurface(surface: *mut std::ffi::c_void, matrix: Matrix) -> Result<Self, String> {
+        #[cfg(target_os = "macos")]
+        {
+            macos::copy_iosurface(surface, matrix)
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = (surface, matrix);
+            Err("IOSurface frames are only supported on macOS".to_string())
+        }
+    }
+}
+
+/// Rows turning (Y, Cb, Cr, 1), sampled as 0-1, into R'G'B'
+pub fn yuv_to_rgb(matrix: Matrix, full_range: bool) -> [[f32; 4]; 3] {
+    let (kr, kb) = match matrix {
+        Matrix::Bt709 => (0.2126, 0.0722),
+        Matrix::Bt601 => (0.299, 0.114),
+    };
+    let kg = 1.0 - kr - kb;
+    let (y_scale, y_offset, c_scale) = match full_range {
+        true => (1.0, 0.0, 1.0),
+        false => (255.0 / 219.0, 16.0 / 255.0, 255.0 / 224.0),
+    };
+    let c_offset = 128.0 / 255.0;
+    // Color difference weights on Cb and Cr per output channel
+    let weights = [
+        [0.0, 2.0 * (1.0 - kr)],
+        [-2.0 * kb * (1.0 - kb) / kg, -2.0 * kr * (1.0 - kr) / kg],
+        [2.0 * (1.0 - kb), 0.0],
+    ];
+    weights.map(|[cb, cr]: [f32; 2]| {
+        let (cb, cr) = (cb * c_scale, cr * c_scale);
+        [y_scale, cb, cr, -y_scale * y_offset - (cb + cr) * c_offset]
+    })
+}
+
+/// Where a width x height frame goes in `dest`
+pub fn fit_rect(dest: Rect, width: u32, height: u32, fit: Fit) -> Rect {
+    let (w, h) = (width as f64, height as f64);
+    let scale = match fit {
+        Fit::Stretch => return dest,
+        Fit::Contain => (dest.width() / w).min(dest.height() / h),
+        Fit::Cover => (dest.width() / w).max(dest.height() / h),
+    };
+    Rect::from_center_size(dest.center(), (w * scale, h * scale))
+}
+
+/// Uniforms (must match `VideoParams` in video.wgsl)
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct VideoParams {
+    rows: [[f32; 4]; 3],
+}
+
+/// Plane textures for one frame format and size
+struct Planes {
+    format: PixelFormat,
+    size: (u32, u32),
+    textures: [wgpu::Texture; 2],
+}
+
+/// Pipelines, created when a video's first frame is rendered
+struct Converter {
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    nv12_pipeline: wgpu::RenderPipeline,
+    bgra_pipeline: wgpu::RenderPipeline,
+    params: wgpu::Buffer,
+    planes: Option<Planes>,
+}
+
+impl Converter {
+    fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Video Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("video.wgsl").into()),
+        });
+
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Video Bind Group Layout"),
+            entries: &[
+                texture_entry(0),
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Video Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label, entry_point| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(entry_point),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: custom::TEXTURE_FORMAT,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Video Chroma Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Video Params"),
+            size: std::mem::size_of::<VideoParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            bind_group_layout,
+            sampler,
+            nv12_pipeline: create_pipeline("Video NV12 Pipeline", "fs_nv12"),
+            bgra_pipeline: create_pipeline("Video BGRA Pipeline", "fs_bgra"),
+            params,
+            planes: None,
+        }
+    }
+
+    /// Plane textures for `frame`, reused while its format and size don't change
+    fn planes(&mut self, device: &wgpu::Device, frame: &Frame) -> &Planes {
+        let size = (frame.width, frame.height);
+        if self.planes.as_ref().is_some_and(|planes| planes.format == frame.format && planes.size == size) {
+            return self.planes.as_ref().expect("checked above");
+        }
+        let create = |label, format, (width, height): (u32, u32)| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            })
+        };
+        let textures = match frame.format {
+            PixelFormat::Bgra8 => [
+                create("Video BGRA Plane", wgpu::TextureFormat::Bgra8Unorm, size),
+                create("Video Unused Plane", wgpu::TextureFormat::Rg8Unorm, (1, 1)),
+            ],
+            PixelFormat::Nv12 => [
+                create("Video Luma Plane", wgpu::TextureFormat::R8Unorm, size),
+                create("Video Chroma Plane", wgpu::TextureFormat::Rg8Unorm, (size.0.div_ceil(2), size.1.div_ceil(2))),
+            ],
+        };
+        self.planes.insert(Planes { format: frame.format, size, textures })
+    }
+
+    /// Convert `frame` into the target texture
+    fn render(&mut self, target: &RenderTarget<'_>, frame: &Frame) {
+        let (device, queue) = (target.device, target.queue);
+        let planes = self.planes(device, frame);
+        for (texture, pixels) in planes.textures.iter().zip(&frame.planes) {
+            let extent = texture.size();
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                pixels,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(pixels.len() as u32 / extent.height),
+                    rows_per_image: None,
+                },
+                extent,
+            );
+        }
+        let views = planes.textures.each_ref().map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        let params = VideoParams { rows: yuv_to_rgb(frame.matrix, frame.full_range) };
+        queue.write_buffer(&self.params, 0, bytemuck::bytes_of(&params));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Video Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&views[0]) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&views[1]) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                wgpu::BindGroupEntry { binding: 3, resource: self.params.as_entire_binding() },
+            ],
+        });
+        let pipeline = match frame.format {
+            PixelFormat::Bgra8 => &self.bgra_pipeline,
+            PixelFormat::Nv12 => &self.nv12_pipeline,
+        };
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Video Encoder") });
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Video Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            rpass.set_pipeline(pipeline);
+            rpass.set_bind_group(0, &bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+}
+
+/// The latest frame, handed from mcore_video_frame_submit to the renderer
+type Latest = Arc<Mutex<Option<Frame>>>;
+
+/// A video's custom texture renderer: converts a new frame if there is one,
+/// otherwise leaves the last one in the texture
+struct VideoRenderer {
+    latest: Latest,
+    converter: Option<Converter>,
+}
+
+impl TextureRenderer for VideoRenderer {
+    fn render(&mut self, target: &RenderTarget<'_>) {
+        let Some(frame) = self.latest.lock().take() else {
+            return;
+        };
+        if (frame.width, frame.height) != (target.width, target.height) {
+            return;
+        }
+        self.converter.get_or_insert_with(|| Converter::new(target.device)).render(target, &frame);
+    }
+}
+
+struct Stream {
+    latest: Latest,
+    /// Size of the last submitted frame; None before the first
+    size: Option<(u32, u32)>,
+}
+
+/// Videos by ID (the ID of each one's custom texture)
+#[derive(Default)]
+pub struct Videos {
+    streams: HashMap<i32, Stream>,
+}
+
+impl Videos {
+    pub fn create(&mut self, textures: &mut CustomTextureManager, gfx: &Gfx) -> Result<i32, String> {
+        let latest = Latest::default();
+        // Sized to the first frame when it arrives
+        let renderer = Box::new(VideoRenderer { latest: latest.clone(), converter: None });
+        let id = textures.create(gfx, 1, 1, renderer)?;
+        self.streams.insert(id, Stream { latest, size: None });
+        Ok(id)
+    }
+
+    /// Make `frame` the one video `id` shows from the next rendered frame
+    pub fn submit(&mut self, textures: &mut CustomTextureManager, gfx: &mut Gfx, id: i32, frame: Frame) -> Result<(), String> {
+        let stream = self.streams.get_mut(&id).ok_or_else(|| format!("Video {} not found", id))?;
+        let size = (frame.width, frame.height);
+        if stream.size != Some(size) {
+            textures.resize(gfx, id, size.0, size.1)?;
+            stream.size = Some(size);
+        }
+        *stream.latest.lock() = Some(frame);
+        Ok(())
+    }
+
+    pub fn destroy(&mut self, textures: &mut CustomTextureManager, gfx: &mut Gfx, id: i32) -> bool {
+        self.streams.remove(&id).is_some() && textures.remove(gfx, id)
+    }
+
+    /// Size of the last submitted frame
+    pub fn frame_size(&self, id: i32) -> Option<(u32, u32)> {
+        self.streams.get(&id)?.size
+    }
+}
+
+/// Fill `dest` (in the coordinates `transform` maps to device px) with video
+/// `id`'s latest frame, fitted per `fit`. False if there's no such video or
+/// it has no frame yet.
+pub fn draw(scene: &mut Scene, textures: &CustomTextureManager, videos: &Videos, id: i32, transform: Affine, dest: Rect, fit: Fit) -> bool {
+    let (Some((width, height)), Some(image)) = (videos.frame_size(id), textures.image(id)) else {
+        return false;
+    };
+    let placed = fit_rect(dest, width, height, fit);
+    let shape = dest.intersect(placed);
+    if shape.is_zero_area() {
+        return true;
+    }
+    let brush_transform = Affine::translate((placed.x0, placed.y0))
+        * Affine::scale_non_uniform(placed.width() / image.width as f64, placed.height() / image.height as f64);
+    scene.fill(Fill::NonZero, transform, &ImageBrush::from(image.clone()), Some(brush_transform), &shape);
+    true
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::{Frame, Matrix, PixelFormat};
+    use std::ffi::c_void;
+
+    #[link(name = "IOSurface", kind = "framework")]
+    extern "C" {
+        fn IOSurfaceLock(buffer: *mut c_void, options: u32, seed: *mut u32) -> i32;
+        fn IOSurfaceUnlock(buffer: *mut c_void, options: u32, seed: *mut u32) -> i32;
+        fn IOSurfaceGetPixelFormat(buffer: *mut c_void) -> u32;
+        fn IOSurfaceGetWidth(buffer: *mut c_void) -> usize;
+        fn IOSurfaceGetHeight(buffer: *mut c_void) -> usize;
+        fn IOSurfaceGetPlaneCount(buffer: *mut c_void) -> usize;
+        fn IOSurfaceGetBaseAddress(buffer: *mut c_void) -> *mut c_void;
+        fn IOSurfaceGetBytesPerRow(buffer: *mut c_void) -> usize;
+        fn IOSurfaceGetAllocSize(buffer: *mut c_void) -> usize;
+        fn IOSurfaceGetBaseAddressOfPlane(buffer: *mut c_void, plane: usize) -> *mut c_void;
+        fn IOSurfaceGetBytesPerRowOfPlane(buffer: *mut c_void, plane: usize) -> usize;
+        fn IOSurfaceGetHeightOfPlane(buffer: *mut c_void, plane: usize) -> usize;
+    }
+
+    /// kIOSurfaceLockReadOnly
+    const LOCK_READ_ONLY: u32 = 1;
+    /// kCVPixelFormatType_32BGRA ('BGRA')
+    const FORMAT_BGRA: u32 = u32::from_be_bytes(*b"BGRA");
+    /// kCVPixelFormatType_420YpCbCr8BiPlanarVideoRange ('420v')
+    const FORMAT_NV12_VIDEO: u32 = u32::from_be_bytes(*b"420v");
+    /// kCVPixelFormatType_420YpCbCr8BiPlanarFullRange ('420f')
+    const FORMAT_NV12_FULL: u32 = u32::from_be_bytes(*b"420f");
+
+    /// Plane `i`'s bytes and bytes per row; the surface must be locked
+    unsafe fn plane<'a>(surface: *mut c_void, i: usize) -> (&'a [u8], usize) {
+        if IOSurfaceGetPlaneCount(surface) == 0 {
+            let base = IOSurfaceGetBaseAddress(surface) as *const u8;
+            (std::slice::from_raw_parts(base, IOSurfaceGetAllocSize(surface)), IOSurfaceGetBytesPerRow(surface))
+        } else {
+            let base = IOSurfaceGetBaseAddressOfPlane(surface, i) as *const u8;
+            let stride = IOSurfaceGetBytesPerRowOfPlane(surface, i);
+            (std::slice::from_raw_parts(base, stride * IOSurfaceGetHeightOfPlane(surface, i)), stride)
+        }
+    }
+
+    pub fn copy_iosurface(surface: *mut c_void, matrix: Matrix) -> Result<Frame, String> {
+        unsafe {
+            let (format, full_range) = match IOSurfaceGetPixelFormat(surface) {
+                FORMAT_BGRA => (PixelFormat::Bgra8, true),
+                FORMAT_NV12_VIDEO => (PixelFormat::Nv12, false),
+                FORMAT_NV12_FULL => (PixelFormat::Nv12, true),
+                other => {
+                    let name = String::from_utf8_lossy(&other.to_be_bytes()).into_owned();
+                    return Err(format!("Unsupported IOSurface pixel format '{}' (use BGRA, 420v or 420f)", name));
+                }
+            };
+            let width = IOSurfaceGetWidth(surface) as u32;
+            let height = IOSurfaceGetHeight(surface) as u32;
+            if IOSurfaceLock(surface, LOCK_READ_ONLY, std::ptr::null_mut()) != 0 {
+                return Err("Failed to lock IOSurface".to_string());
+            }
+            let plane = |i| plane(surface, i);
+            let frame = match format {
+                PixelFormat::Bgra8 => Frame::from_planes(format, width, height, &[plane(0)], matrix, full_range),
+                PixelFormat::Nv12 => Frame::from_planes(format, width, height, &[plane(0), plane(1)], matrix, full_range),
+            };
+            IOSurfaceUnlock(surface, LOCK_READ_ONLY, std::ptr::null_mut());
+            frame
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(rows: [[f32; 4]; 3], y: u8, cb: u8, cr: u8) -> [f32; 3] {
+        let yuv = [y as f32 / 255.0, cb as f32 / 255.0, cr as f32 / 255.0, 1.0];
+        rows.map(|row| row.iter().zip(yuv).map(|(a, b)| a * b).sum())
+    }
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 0.01, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_yuv_to_rgb() {
+        let video_709 = yuv_to_rgb(Matrix::Bt709, false);
+        assert_close(convert(video_709, 16, 128, 128), [0.0; 3]);
+        assert_close(convert(video_709, 235, 128, 128), [1.0; 3]);
+        // BT.709 red, green and blue in video range
+        assert_close(convert(video_709, 63, 102, 240), [1.0, 0.0, 0.0]);
+        assert_close(convert(video_709, 173, 42, 26), [0.0, 1.0, 0.0]);
+        assert_close(convert(video_709, 32, 240, 118), [0.0, 0.0, 1.0]);
+
+        let full_601 = yuv_to_rgb(Matrix::Bt601, true);
+        assert_close(convert(full_601, 255, 128, 128), [1.0; 3]);
+        // JPEG's red
+        assert_close(convert(full_601, 76, 85, 255), [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_planes_are_repacked() {
+        // 3x2 NV12 with 4-byte rows: chroma rounds up to 2 pairs x 1 row
+        let luma = [1, 2, 3, 0, 4, 5, 6, 0];
+        let chroma = [7, 8, 9, 10];
+        let frame = Frame::from_planes(PixelFormat::Nv12, 3, 2, &[(&luma, 4), (&chroma, 4)], Matrix::Bt709, false).unwrap();
+        assert_eq!(frame.planes, vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9, 10]]);
+
+        // The last row needs no padding after it
+        assert!(Frame::from_planes(PixelFormat::Bgra8, 1, 2, &[(&[0; 12], 8)], Matrix::Bt709, false).is_ok());
+        assert!(Frame::from_planes(PixelFormat::Bgra8, 1, 2, &[(&[0; 11], 8)], Matrix::Bt709, false).is_err());
+        assert!(Frame::from_planes(PixelFormat::Bgra8, 2, 1, &[(&[0; 8], 4)], Matrix::Bt709, false).is_err(), "stride too short");
+        assert!(Frame::from_planes(PixelFormat::Nv12, 2, 2, &[(&[0; 4], 2)], Matrix::Bt709, false).is_err(), "missing plane");
+    }
+
+    #[test]
+    fn test_fit_rect() {
+        let dest = Rect::new(0.0, 0.0, 200.0, 100.0);
+        assert_eq!(fit_rect(dest, 1920, 1080, Fit::Stretch), dest);
+        // 16:9 in a 2:1 rect: pillarboxed, or cropped top and bottom
+        let contain = fit_rect(dest, 1600, 900, Fit::Contain);
+        assert_eq!((contain.width().round(), contain.height()), (178.0, 100.0));
+        assert_eq!(contain.center(), dest.center());
+        let cover = fit_rect(dest, 1600, 900, Fit::Cover);
+        assert_eq!((cover.width(), cover.height()), (200.0, 112.5));
+        assert_eq!(cover.y0, -6.25);
+    }
+}
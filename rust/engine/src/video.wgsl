@@ -0,0 +1,49 @@
+// Video frame conversion (see video.rs)
+
+// NV12: luma (R8). BGRA: the frame (sampled as RGBA).
+@group(0) @binding(0)
+var plane0: texture_2d<f32>;
+// NV12: half-size interleaved chroma, Cb and Cr (RG8). BGRA: unused.
+@group(0) @binding(1)
+var plane1: texture_2d<f32>;
+@group(0) @binding(2)
+var plane_sampler: sampler;
+
+// Rows turning (Y, Cb, Cr, 1) into R'G'B'
+struct VideoParams {
+    r: vec4<f32>,
+    g: vec4<f32>,
+    b: vec4<f32>,
+}
+
+@group(0) @binding(3)
+var<uniform> params: VideoParams;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let x = f32(vertex_index & 1u);
+    let y = f32((vertex_index >> 1u) & 1u);
+    out.position = vec4<f32>(x * 4.0 - 1.0, 1.0 - y * 4.0, 0.0, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_nv12(in: VertexOutput) -> @location(0) vec4<f32> {
+    let luma = textureLoad(plane0, vec2<i32>(in.position.xy), 0).r;
+    // Chroma is filtered up from half size
+    let uv = in.position.xy / vec2<f32>(textureDimensions(plane0));
+    let chroma = textureSampleLevel(plane1, plane_sampler, uv, 0.0).rg;
+    let yuv = vec4<f32>(luma, chroma, 1.0);
+    let rgb = vec3<f32>(dot(params.r, yuv), dot(params.g, yuv), dot(params.b, yuv));
+    return vec4<f32>(clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0)), 1.0);
+}
+
+@fragment
+fn fs_bgra(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureLoad(plane0, vec2<i32>(in.position.xy), 0);
+}
@@ -0,0 +1,150 @@
+/// Frame-time watchdog
+///
+/// Every frame is timed by phase; the last presented frame's timings are
+/// reported by mcore_frame_stats. Warnings are a development aid, off until
+/// the host sets a budget (mcore_set_frame_budget). Frames whose scene build
+/// plus render run over it are reported through the log callback with a
+/// per-phase breakdown, so pathological host command patterns (thousands of
+/// tiny draw calls, reshaping text every frame) get flagged while they're
+/// being written rather than found in a profiler later.
+///
+/// Phases:
+///   build   mcore_begin_frame until mcore_end_frame_present: host-side work
+///           plus the engine encoding its commands (broken out as "encode")
+///   custom  custom widget textures rendered before the scene
+///   render  Vello rasterization, blit and present
+
+use std::time::{Duration, Instant};
+
+#[derive(Default)]
+pub struct FrameWatchdog {
+    budget: Option<Duration>,
+    frame_start: Option<Instant>,
+    encode: Duration,
+    encode_calls: u32,
+    commands: usize,
+}
+
+/// Where one frame's time went
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FrameTimings {
+    pub build: Duration,
+    pub encode: Duration,
+    pub encode_calls: u32,
+    pub commands: usize,
+    pub custom: Duration,
+    pub render: Duration,
+}
+
+impl FrameTimings {
+    pub fn total(&self) -> Duration {
+        self.build + self.custom + self.render
+    }
+}
+
+impl FrameWatchdog {
+    /// Report frames slower than `budget` (None turns the watchdog off)
+    pub fn set_budget(&mut self, budget: Option<Duration>) {
+        self.budget = budget;
+        self.frame_start = None;
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.frame_start = Some(Instant::now());
+        self.encode = Duration::ZERO;
+        self.encode_calls = 0;
+        self.commands = 0;
+    }
+
+    /// Time the engine spent drawing one command buffer
+    pub fn record_encode(&mut self, elapsed: Duration, commands: usize) {
+        self.encode += elapsed;
+        self.encode_calls += 1;
+        self.commands += commands;
+    }
+
+    /// Build-phase timings of the frame so far
+    pub fn build_timings(&self) -> FrameTimings {
+        FrameTimings {
+            build: self.frame_start.map(|start| start.elapsed()).unwrap_or_default(),
+            encode: self.encode,
+            encode_calls: self.encode_calls,
+            commands: self.commands,
+            ..Default::default()
+        }
+    }
+
+    /// The warning to log if a finished frame ran over budget
+    pub fn check(&self, frame: u64, timings: &FrameTimings) -> Option<String> {
+        let budget = self.budget?;
+        (timings.total() > budget).then(|| format_report(frame, budget, timings))
+    }
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn format_report(frame: u64, budget: Duration, t: &FrameTimings) -> String {
+    format!(
+        "frame {frame} took {:.1} ms (budget {:.1} ms): build {:.1} ms (encode {:.1} ms, {} commands in {} calls), custom {:.1} ms, render {:.1} ms",
+        ms(t.total()),
+        ms(budget),
+        ms(t.build),
+        ms(t.encode),
+        t.commands,
+        t.encode_calls,
+        ms(t.custom),
+        ms(t.render),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_only_frames_over_budget() {
+        let mut watchdog = FrameWatchdog::default();
+        let slow = FrameTimings {
+            build: Duration::from_millis(30),
+            encode: Duration::from_millis(25),
+            encode_calls: 3,
+            commands: 4000,
+            custom: Duration::ZERO,
+            render: Duration::from_millis(8),
+        };
+        assert!(watchdog.check(1, &slow).is_none(), "off without a budget");
+
+        watchdog.set_budget(Some(Duration::from_millis(16)));
+        let report = watchdog.check(7, &slow).unwrap();
+        assert!(report.starts_with("frame 7 took 38.0 ms (budget 16.0 ms)"));
+        assert!(report.contains("encode 25.0 ms, 4000 commands in 3 calls"));
+        assert!(report.ends_with("render 8.0 ms"));
+
+        let fast = FrameTimings { build: Duration::from_millis(5), render: Duration::from_millis(4), ..slow };
+        assert!(watchdog.check(8, &fast).is_none());
+    }
+
+    #[test]
+    fn counters_reset_each_frame() {
+        let mut watchdog = FrameWatchdog::default();
+        watchdog.set_budget(Some(Duration::from_millis(16)));
+        watchdog.begin_frame();
+        watchdog.record_encode(Duration::from_millis(2), 10);
+        watchdog.record_encode(Duration::from_millis(3), 5);
+        let timings = watchdog.build_timings();
+        assert_eq!((timings.encode_calls, timings.commands), (2, 15));
+        assert_eq!(timings.encode, Duration::from_millis(5));
+
+        watchdog.begin_frame();
+        assert_eq!(watchdog.build_timings().commands, 0);
+
+        // Timings are kept for mcore_frame_stats without a budget
+        watchdog.set_budget(None);
+        watchdog.begin_frame();
+        watchdog.record_encode(Duration::from_millis(2), 10);
+        assert_eq!(watchdog.build_timings().commands, 10);
+        assert!(watchdog.check(1, &watchdog.build_timings()).is_none());
+    }
+}
@@ -0,0 +1,1111 @@
+//! `cargo xtask bindings [--check]`
+//!
+//! Reads the `#[repr(C)]` types, `pub const` integers and `extern "C"`
+//! functions in rust/engine/src/lib.rs and writes three files from them:
+//!
+//! - src/renderer/mcore.zig: extern declarations and struct mirrors for the Zig host
+//! - bindings/zello.h: the same declarations for C, ObjC and Swift hosts
+//! - rust/engine/src/abi_layout.rs: the engine-side layout tests
+//!
+//! All three assert the same sizes, alignments and field offsets (computed
+//! here with C layout rules for 64-bit targets): `cargo test` checks the
+//! engine, `zig build test-abi` the Zig mirror, and zello.h static-asserts
+//! them wherever it's compiled. `--check` fails instead of writing if any
+//! file is out of date.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bindings target 64-bit hosts only
+const POINTER_SIZE: usize = 8;
+
+const GENERATED_NOTE: &str = "Generated by `cargo xtask bindings` from rust/engine/src/lib.rs. Do not edit.";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Ty {
+    Prim(String),
+    /// `c_void`, only behind a pointer
+    Void,
+    Ptr { mutable: bool, pointee: Box<Ty> },
+    Array(Box<Ty>, usize),
+    Fn { params: Vec<Ty>, ret: Option<Box<Ty>>, nullable: bool },
+    Named(String),
+}
+
+#[derive(Debug)]
+struct Field {
+    name: String,
+    ty: Ty,
+    docs: Vec<String>,
+    comment: Option<String>,
+}
+
+#[derive(Debug)]
+enum ItemKind {
+    Struct(Vec<Field>),
+    Union(Vec<Field>),
+    Enum(Vec<(String, i64)>),
+    /// Handle types whose contents are private to the engine
+    Opaque,
+}
+
+#[derive(Debug)]
+struct Item {
+    name: String,
+    docs: Vec<String>,
+    kind: ItemKind,
+}
+
+#[derive(Debug)]
+struct Func {
+    name: String,
+    docs: Vec<String>,
+    params: Vec<(String, Ty)>,
+    ret: Option<Ty>,
+}
+
+/// Hosts see these with an `MCORE_` prefix
+#[derive(Debug)]
+struct Const {
+    name: String,
+    docs: Vec<String>,
+    ty: Ty,
+    value: i64,
+    /// The Rust expression, if it's more than a literal
+    expr: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Abi {
+    items: Vec<Item>,
+    aliases: Vec<(String, Ty)>,
+    consts: Vec<Const>,
+    funcs: Vec<Func>,
+}
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut check = false;
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            other => return Err(format!("unknown option {}", other)),
+        }
+    }
+
+    let root = repo_root();
+    let source = fs::read_to_string(root.join("rust/engine/src/lib.rs")).map_err(|e| e.to_string())?;
+    let abi = parse(&source)?;
+
+    let outputs = [
+        (root.join("src/renderer/mcore.zig"), zig_source(&abi)?),
+        (root.join("bindings/zello.h"), c_header(&abi)?),
+        (root.join("rust/engine/src/abi_layout.rs"), rust_layout_tests(&abi)?),
+    ];
+
+    for (path, contents) in &outputs {
+        if check {
+            let current = fs::read_to_string(path).unwrap_or_default();
+            if &current != contents {
+                return Err(format!("{} is out of date; run `cargo xtask bindings`", path.display()));
+            }
+        } else {
+            fs::write(path, contents).map_err(|e| e.to_string())?;
+            eprintln!("Wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// Line-based scan of lib.rs. Relies on rustfmt-style layout: one field or
+/// variant per line, closing braces on their own line.
+fn parse(source: &str) -> Result<Abi, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut abi = Abi::default();
+    let mut docs = Vec::new();
+    let mut repr_c = false;
+    let mut i = 0;
+
+    while i < lines.len() {
+        // Indented lines are inside impls and fns, so `type` there is an
+        // associated type, not an alias
+        let top_level = !lines[i].starts_with(char::is_whitespace);
+        let line = lines[i].trim();
+        i += 1;
+
+        if let Some(doc) = line.strip_prefix("///") {
+            docs.push(doc.to_string());
+            continue;
+        }
+        if line == "#[repr(C)]" {
+            repr_c = true;
+            continue;
+        }
+        if line.starts_with("#[") {
+            continue;
+        }
+
+        if repr_c {
+            if let Some((keyword, rest)) = ["pub struct ", "pub union ", "pub enum "]
+                .iter()
+                .find_map(|k| line.strip_prefix(k).map(|rest| (*k, rest)))
+            {
+                let name: String = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+                let kind = if !rest.trim_end().ends_with('{') {
+                    // Tuple or unit struct
+                    ItemKind::Opaque
+                } else {
+                    let (body, next) = block_body(&lines, i)?;
+                    i = next;
+                    match keyword {
+                        "pub enum " => ItemKind::Enum(parse_variants(&body)?),
+                        _ => match parse_fields(&body)? {
+                            Some(fields) if keyword == "pub union " => ItemKind::Union(fields),
+                            Some(fields) => ItemKind::Struct(fields),
+                            None => ItemKind::Opaque,
+                        },
+                    }
+                };
+                abi.items.push(Item { name, docs: std::mem::take(&mut docs), kind });
+            }
+        } else if let Some(rest) = line.strip_prefix("type ").or_else(|| line.strip_prefix("pub type ")).filter(|_| top_level) {
+            if let Some((name, ty)) = rest.trim_end_matches(';').split_once('=') {
+                abi.aliases.push((name.trim().to_string(), parse_type(ty)?));
+            }
+        } else if let Some(rest) = line.strip_prefix("pub const ").filter(|_| top_level) {
+            let mut konst = parse_const(rest, &abi.consts)?;
+            konst.docs = std::mem::take(&mut docs);
+            abi.consts.push(konst);
+        } else if line.starts_with("pub extern \"C\" fn ") {
+            // Signatures may span several lines; they end at the body's brace
+            let mut signature = line.to_string();
+            while !signature.trim_end().ends_with('{') {
+                let next = lines.get(i).ok_or("unterminated function signature")?;
+                signature.push(' ');
+                signature.push_str(next.trim());
+                i += 1;
+            }
+            let mut func = parse_signature(&signature)?;
+            func.docs = std::mem::take(&mut docs);
+            abi.funcs.push(func);
+        }
+
+        docs.clear();
+        repr_c = false;
+    }
+
+    Ok(abi)
+}
+
+/// Lines between an item's opening line and its closing `}`
+fn block_body<'a>(lines: &[&'a str], start: usize) -> Result<(Vec<&'a str>, usize), String> {
+    let mut body = Vec::new();
+    for (offset, line) in lines[start..].iter().enumerate() {
+        if line.trim() == "}" {
+            return Ok((body, start + offset + 1));
+        }
+        body.push(line.trim());
+    }
+    Err("unterminated item body".into())
+}
+
+/// Split `code // comment` into its parts
+fn split_comment(line: &str) -> (&str, Option<String>) {
+    match line.split_once("//") {
+        Some((code, comment)) => (code.trim(), Some(comment.trim().to_string())),
+        None => (line.trim(), None),
+    }
+}
+
+/// Returns None if any field is private (the type is opaque to hosts)
+fn parse_fields(body: &[&str]) -> Result<Option<Vec<Field>>, String> {
+    let mut fields = Vec::new();
+    let mut docs = Vec::new();
+
+    for line in body {
+        if let Some(doc) = line.strip_prefix("///") {
+            docs.push(doc.to_string());
+            continue;
+        }
+        if line.is_empty() || line.starts_with("//") || line.starts_with("#[") {
+            continue;
+        }
+
+        let Some(field) = line.strip_prefix("pub ") else {
+            return Ok(None);
+        };
+        let (code, comment) = split_comment(field);
+        let (name, ty) = code
+            .trim_end_matches(',')
+            .split_once(':')
+            .ok_or_else(|| format!("can't parse field `{}`", line))?;
+        fields.push(Field {
+            name: name.trim().to_string(),
+            ty: parse_type(ty)?,
+            docs: std::mem::take(&mut docs),
+            comment,
+        });
+    }
+
+    Ok(Some(fields))
+}
+
+fn parse_variants(body: &[&str]) -> Result<Vec<(String, i64)>, String> {
+    let mut variants = Vec::new();
+    let mut next = 0;
+
+    for line in body {
+        let (code, _) = split_comment(line);
+        if code.is_empty() || code.starts_with("#[") {
+            continue;
+        }
+
+        let code = code.trim_end_matches(',');
+        let (name, value) = match code.split_once('=') {
+            Some((name, value)) => {
+                let value = parse_discriminant(value).ok_or_else(|| format!("can't parse variant `{}`", line))?;
+                (name.trim(), value)
+            }
+            None => (code, next),
+        };
+        variants.push((name.to_string(), value));
+        next = value + 1;
+    }
+
+    Ok(variants)
+}
+
+/// `3` or a flag like `1 << 2`
+fn parse_discriminant(text: &str) -> Option<i64> {
+    match text.split_once("<<") {
+        Some((base, shift)) => Some(base.trim().parse::<i64>().ok()? << shift.trim().parse::<u32>().ok()?),
+        None => text.trim().parse().ok(),
+    }
+}
+
+/// `NAME: u32 = (OTHER << 16) | 4;`, where OTHER is an earlier constant
+fn parse_const(text: &str, earlier: &[Const]) -> Result<Const, String> {
+    let parse_err = || format!("can't parse constant `{}`", text);
+    let (name, rest) = text.split_once(':').ok_or_else(parse_err)?;
+    let (ty, expr) = rest.trim_end_matches(';').split_once('=').ok_or_else(parse_err)?;
+    let ty = parse_type(ty)?;
+    if !matches!(&ty, Ty::Prim(prim) if prim != "f32" && prim != "f64" && prim != "bool") {
+        return Err(format!("constant `{}` isn't an integer", name.trim()));
+    }
+    let expr = expr.trim();
+    Ok(Const {
+        name: name.trim().to_string(),
+        docs: Vec::new(),
+        ty,
+        value: eval_const(expr, earlier).ok_or_else(parse_err)?,
+        expr: expr.parse::<i64>().is_err().then(|| expr.to_string()),
+    })
+}
+
+/// Integer literals and earlier constants joined by `|` and `<<`
+fn eval_const(text: &str, earlier: &[Const]) -> Option<i64> {
+    let text = text.trim();
+    if text.starts_with('(') && matching_paren(text, 0) == Some(text.len() - 1) {
+        return eval_const(&text[1..text.len() - 1], earlier);
+    }
+    for op in ["|", "<<"] {
+        if let Some(at) = top_level_op(text, op) {
+            let (lhs, rhs) = (eval_const(&text[..at], earlier)?, eval_const(&text[at + op.len()..], earlier)?);
+            return Some(if op == "|" { lhs | rhs } else { lhs << rhs });
+        }
+    }
+    match text.replace('_', "").parse() {
+        Ok(value) => Some(value),
+        Err(_) => earlier.iter().find(|konst| konst.name == text).map(|konst| konst.value),
+    }
+}
+
+/// The last `op` outside parentheses, so operators associate to the left
+fn top_level_op(text: &str, op: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut found = None;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if depth == 0 && text[i..].starts_with(op) => found = Some(i),
+            _ => {}
+        }
+    }
+    found
+}
+
+fn parse_signature(signature: &str) -> Result<Func, String> {
+    let rest = signature.trim_start_matches("pub extern \"C\" fn ").trim();
+    let open = rest.find('(').ok_or("missing parameter list")?;
+    let close = matching_paren(rest, open).ok_or("unbalanced parameter list")?;
+
+    let mut params = Vec::new();
+    for param in split_top_level(&rest[open + 1..close]) {
+        let (name, ty) = param.split_once(':').ok_or_else(|| format!("can't parse parameter `{}`", param))?;
+        params.push((name.trim().to_string(), parse_type(ty)?));
+    }
+
+    let tail = rest[close + 1..].trim().trim_end_matches('{').trim();
+    let ret = match tail.strip_prefix("->") {
+        Some(ty) => Some(parse_type(ty)?),
+        None => None,
+    };
+
+    Ok(Func {
+        name: rest[..open].trim().to_string(),
+        docs: Vec::new(),
+        params,
+        ret,
+    })
+}
+
+fn parse_type(text: &str) -> Result<Ty, String> {
+    let text = text.trim();
+
+    if let Some(pointee) = text.strip_prefix("*const ") {
+        return Ok(Ty::Ptr { mutable: false, pointee: Box::new(parse_type(pointee)?) });
+    }
+    if let Some(pointee) = text.strip_prefix("*mut ") {
+        return Ok(Ty::Ptr { mutable: true, pointee: Box::new(parse_type(pointee)?) });
+    }
+    if let Some(inner) = text.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        return match parse_type(inner)? {
+            Ty::Fn { params, ret, .. } => Ok(Ty::Fn { params, ret, nullable: true }),
+            _ => Err(format!("only function pointers can be optional: `{}`", text)),
+        };
+    }
+    if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        let (elem, len) = inner.rsplit_once(';').ok_or_else(|| format!("can't parse array `{}`", text))?;
+        let len = len.trim().parse().map_err(|_| format!("can't parse array length `{}`", text))?;
+        return Ok(Ty::Array(Box::new(parse_type(elem)?), len));
+    }
+    if let Some(rest) = text.strip_prefix("extern \"C\" fn") {
+        let rest = rest.trim();
+        let close = matching_paren(rest, 0).ok_or_else(|| format!("can't parse function type `{}`", text))?;
+        let params = split_top_level(&rest[1..close])
+            .into_iter()
+            .map(parse_type)
+            .collect::<Result<_, _>>()?;
+        let ret = match rest[close + 1..].trim().strip_prefix("->") {
+            Some(ty) => Some(Box::new(parse_type(ty)?)),
+            None => None,
+        };
+        return Ok(Ty::Fn { params, ret, nullable: false });
+    }
+
+    match text {
+        "c_void" | "std::ffi::c_void" => Ok(Ty::Void),
+        "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "f32" | "f64" | "usize" | "isize" | "bool" => {
+            Ok(Ty::Prim(text.to_string()))
+        }
+        _ if !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_') => Ok(Ty::Named(text.to_string())),
+        _ => Err(format!("unsupported FFI type `{}`", text)),
+    }
+}
+
+/// Index of the `)` matching the `(` at `open`
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices().skip(open) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split on commas outside brackets (`->` doesn't close a bracket)
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    let mut prev = ' ';
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' | '<' => depth += 1,
+            ')' | ']' => depth -= 1,
+            '>' if prev != '-' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    parts.push(text[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+// ============================================================================
+// Layout
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+struct Layout {
+    size: usize,
+    align: usize,
+    /// Field offsets (structs only)
+    offsets: Vec<usize>,
+}
+
+struct Layouts<'a> {
+    abi: &'a Abi,
+    items: HashMap<&'a str, &'a Item>,
+}
+
+impl<'a> Layouts<'a> {
+    fn new(abi: &'a Abi) -> Self {
+        let items = abi.items.iter().map(|item| (item.name.as_str(), item)).collect();
+        Self { abi, items }
+    }
+
+    fn item(&self, item: &Item) -> Result<Option<Layout>, String> {
+        match &item.kind {
+            ItemKind::Opaque => Ok(None),
+            // repr(C) enums are C ints
+            ItemKind::Enum(_) => Ok(Some(Layout { size: 4, align: 4, offsets: Vec::new() })),
+            ItemKind::Struct(fields) => {
+                let mut offset = 0;
+                let mut align = 1;
+                let mut offsets = Vec::new();
+                for field in fields {
+                    let (field_size, field_align) = self.size_align(&field.ty)?;
+                    offset = round_up(offset, field_align);
+                    offsets.push(offset);
+                    offset += field_size;
+                    align = align.max(field_align);
+                }
+                Ok(Some(Layout { size: round_up(offset, align), align, offsets }))
+            }
+            ItemKind::Union(fields) => {
+                let mut size = 0;
+                let mut align = 1;
+                for field in fields {
+                    let (field_size, field_align) = self.size_align(&field.ty)?;
+                    size = size.max(field_size);
+                    align = align.max(field_align);
+                }
+                Ok(Some(Layout { size: round_up(size, align), align, offsets: vec![0; fields.len()] }))
+            }
+        }
+    }
+
+    fn size_align(&self, ty: &Ty) -> Result<(usize, usize), String> {
+        match ty {
+            Ty::Prim(name) => {
+                let size = match name.as_str() {
+                    "u8" | "i8" | "bool" => 1,
+                    "u16" | "i16" => 2,
+                    "u32" | "i32" | "f32" => 4,
+                    _ => 8,
+                };
+                Ok((size, size))
+            }
+            Ty::Ptr { .. } | Ty::Fn { .. } => Ok((POINTER_SIZE, POINTER_SIZE)),
+            Ty::Array(elem, len) => {
+                let (size, align) = self.size_align(elem)?;
+                Ok((size * len, align))
+            }
+            Ty::Named(name) => {
+                if let Some((_, ty)) = self.abi.aliases.iter().find(|(alias, _)| alias == name) {
+                    return self.size_align(ty);
+                }
+                let item = self.items.get(name.as_str()).ok_or_else(|| format!("unknown type `{}`", name))?;
+                let layout = self.item(item)?.ok_or_else(|| format!("opaque type `{}` used by value", name))?;
+                Ok((layout.size, layout.align))
+            }
+            Ty::Void => Err("c_void used by value".into()),
+        }
+    }
+}
+
+fn round_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+// ============================================================================
+// Zig output
+// ============================================================================
+
+const ZIG_RESERVED: &[&str] = &[
+    "addrspace", "align", "allowzero", "and", "anyframe", "anytype", "asm", "break", "callconv", "catch", "comptime",
+    "const", "continue", "defer", "else", "enum", "errdefer", "error", "export", "extern", "fn", "for", "if", "inline",
+    "linksection", "noalias", "noinline", "nosuspend", "opaque", "or", "orelse", "packed", "pub", "resume", "return",
+    "struct", "suspend", "switch", "test", "threadlocal", "try", "type", "union", "unreachable", "var", "volatile",
+    "while",
+];
+
+fn zig_ident(name: &str) -> String {
+    if ZIG_RESERVED.contains(&name) {
+        format!("@\"{}\"", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// `MacOS` -> `mac_os`, `InsertChar` -> `insert_char`
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev: Option<char> = None;
+    for c in name.chars() {
+        if c.is_uppercase() {
+            if prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit()) {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+        prev = Some(c);
+    }
+    out
+}
+
+fn is_opaque(abi: &Abi, name: &str) -> bool {
+    abi.items.iter().any(|item| item.name == name && matches!(item.kind, ItemKind::Opaque))
+}
+
+fn zig_type(abi: &Abi, ty: &Ty) -> String {
+    match ty {
+        Ty::Prim(name) => match name.as_str() {
+            "isize" => "isize".into(),
+            other => other.into(),
+        },
+        Ty::Void => "anyopaque".into(),
+        Ty::Ptr { mutable, pointee } => {
+            let constness = if *mutable { "" } else { "const " };
+            match pointee.as_ref() {
+                Ty::Void => format!("?*{}anyopaque", constness),
+                Ty::Named(name) if is_opaque(abi, name) => format!("?*{}{}", constness, name),
+                // C strings and byte buffers
+                Ty::Prim(name) if name == "i8" => format!("[*c]{}u8", constness),
+                pointee => format!("[*c]{}{}", constness, zig_type(abi, pointee)),
+            }
+        }
+        Ty::Array(elem, len) => format!("[{}]{}", len, zig_type(abi, elem)),
+        Ty::Fn { params, ret, nullable } => {
+            let params: Vec<String> = params.iter().map(|p| zig_type(abi, p)).collect();
+            let ret = ret.as_ref().map_or("void".to_string(), |r| zig_type(abi, r));
+            format!("{}*const fn ({}) callconv(.c) {}", if *nullable { "?" } else { "" }, params.join(", "), ret)
+        }
+        Ty::Named(name) => name.clone(),
+    }
+}
+
+fn push_docs(out: &mut String, docs: &[String], indent: &str) {
+    for doc in docs {
+        out.push_str(&format!("{}///{}\n", indent, doc));
+    }
+}
+
+fn zig_source(abi: &Abi) -> Result<String, String> {
+    let layouts = Layouts::new(abi);
+    let mut out = format!("//! {}\n//! Mirrors the engine ABI for 64-bit targets; layout tests: `zig build test-abi`.\n\nconst std = @import(\"std\");\n", GENERATED_NOTE);
+
+    // Undocumented constants continue the group above them
+    for (i, konst) in abi.consts.iter().enumerate() {
+        if i == 0 || !konst.docs.is_empty() {
+            out.push('\n');
+        }
+        push_docs(&mut out, &konst.docs, "");
+        out.push_str(&format!("pub const MCORE_{}: {} = {}\n", konst.name, zig_type(abi, &konst.ty), const_value(konst, ";")));
+    }
+
+    for item in &abi.items {
+        out.push('\n');
+        push_docs(&mut out, &item.docs, "");
+        match &item.kind {
+            ItemKind::Opaque => out.push_str(&format!("pub const {} = opaque {{}};\n", item.name)),
+            ItemKind::Enum(variants) => {
+                out.push_str(&format!("pub const {} = enum(c_int) {{\n", item.name));
+                for (name, value) in variants {
+                    out.push_str(&format!("    {} = {},\n", zig_ident(&snake_case(name)), value));
+                }
+                out.push_str("};\n");
+            }
+            ItemKind::Struct(fields) | ItemKind::Union(fields) => {
+                let keyword = if matches!(item.kind, ItemKind::Union(_)) { "union" } else { "struct" };
+                out.push_str(&format!("pub const {} = extern {} {{\n", item.name, keyword));
+                for field in fields {
+                    push_docs(&mut out, &field.docs, "    ");
+                    out.push_str(&format!("    {}: {},", zig_ident(&field.name), zig_type(abi, &field.ty)));
+                    if let Some(comment) = &field.comment {
+                        out.push_str(&format!(" // {}", comment));
+                    }
+                    out.push('\n');
+                }
+                out.push_str("};\n");
+            }
+        }
+    }
+
+    for (name, ty) in &abi.aliases {
+        out.push_str(&format!("\npub const {} = {};\n", name, zig_type(abi, ty)));
+    }
+
+    out.push_str("\n// Functions\n");
+    for func in &abi.funcs {
+        out.push('\n');
+        push_docs(&mut out, &func.docs, "");
+        let params: Vec<String> = func
+            .params
+            .iter()
+            .map(|(name, ty)| format!("{}: {}", zig_ident(name), zig_type(abi, ty)))
+            .collect();
+        let ret = func.ret.as_ref().map_or("void".to_string(), |r| zig_type(abi, r));
+        out.push_str(&format!("pub extern fn {}({}) {};\n", func.name, params.join(", "), ret));
+    }
+
+    out.push_str("\n// Layout tests (same numbers as rust/engine/src/abi_layout.rs)\n");
+    for item in &abi.items {
+        let Some(layout) = layouts.item(item)? else {
+            continue;
+        };
+        out.push_str(&format!("\ntest \"{} layout\" {{\n", item.name));
+        out.push_str(&format!("    try std.testing.expectEqual({}, @sizeOf({}));\n", layout.size, item.name));
+        out.push_str(&format!("    try std.testing.expectEqual({}, @alignOf({}));\n", layout.align, item.name));
+        if let ItemKind::Struct(fields) = &item.kind {
+            for (field, offset) in fields.iter().zip(&layout.offsets) {
+                out.push_str(&format!(
+                    "    try std.testing.expectEqual({}, @offsetOf({}, \"{}\"));\n",
+                    offset, item.name, field.name
+                ));
+            }
+        }
+        out.push_str("}\n");
+    }
+
+    Ok(out)
+}
+
+// ============================================================================
+// C output
+// ============================================================================
+
+const C_RESERVED: &[&str] = &[
+    "auto", "bool", "break", "case", "char", "class", "const", "continue", "default", "delete", "do", "double", "else",
+    "enum", "extern", "float", "for", "goto", "if", "in", "inline", "int", "long", "new", "operator", "private",
+    "public", "register", "restrict", "return", "short", "signed", "sizeof", "static", "struct", "switch", "template",
+    "this", "typedef", "union", "unsigned", "void", "volatile", "while",
+];
+
+fn c_ident(name: &str) -> String {
+    if C_RESERVED.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Types that don't wrap the declared name
+fn c_type(ty: &Ty) -> Result<String, String> {
+    Ok(match ty {
+        Ty::Prim(name) => match name.as_str() {
+            "u8" => "uint8_t",
+            "i8" => "int8_t",
+            "u16" => "uint16_t",
+            "i16" => "int16_t",
+            "u32" => "uint32_t",
+            "i32" => "int32_t",
+            "u64" => "uint64_t",
+            "i64" => "int64_t",
+            "f32" => "float",
+            "f64" => "double",
+            "usize" => "size_t",
+            "isize" => "ptrdiff_t",
+            _ => "bool",
+        }
+        .to_string(),
+        Ty::Void => "void".into(),
+        Ty::Named(name) => name.clone(),
+        Ty::Ptr { mutable, pointee } => {
+            let constness = if *mutable { "" } else { "const " };
+            match pointee.as_ref() {
+                // C strings and byte buffers
+                Ty::Prim(name) if name == "i8" => format!("{}char*", constness),
+                pointee => format!("{}{}*", constness, c_type(pointee)?),
+            }
+        }
+        Ty::Array(..) | Ty::Fn { .. } => return Err(format!("unsupported C type {:?}", ty)),
+    })
+}
+
+/// A declaration of `name` (empty for a parameter type); arrays and function
+/// pointers wrap the name
+fn c_decl(ty: &Ty, name: &str) -> Result<String, String> {
+    match ty {
+        Ty::Array(elem, len) => c_decl(elem, &format!("{}[{}]", name, len)),
+        Ty::Fn { params, ret, .. } => {
+            let params = params.iter().map(|p| c_decl(p, "")).collect::<Result<Vec<_>, _>>()?;
+            let params = if params.is_empty() { "void".to_string() } else { params.join(", ") };
+            let ret = match ret {
+                Some(ret) => c_type(ret)?,
+                None => "void".into(),
+            };
+            Ok(format!("{} (*{})({})", ret, name, params))
+        }
+        ty if name.is_empty() => c_type(ty),
+        ty => Ok(format!("{} {}", c_type(ty)?, name)),
+    }
+}
+
+/// A literal as written, otherwise the computed value, with the expression
+/// in a trailing comment (`0x40010; // (API_VERSION_MAJOR << 16) | ...`)
+fn const_value(konst: &Const, terminator: &str) -> String {
+    match &konst.expr {
+        Some(expr) => format!("{:#x}{} // {}", konst.value, terminator, expr),
+        None => format!("{}{}", konst.value, terminator),
+    }
+}
+
+fn push_c_docs(out: &mut String, docs: &[String], indent: &str) {
+    for doc in docs {
+        out.push_str(&format!("{}//{}\n", indent, doc));
+    }
+}
+
+/// Named types a struct or union holds by value, which C needs defined first
+fn by_value_deps<'a>(ty: &'a Ty, deps: &mut Vec<&'a str>) {
+    match ty {
+        Ty::Named(name) => deps.push(name),
+        Ty::Array(elem, _) => by_value_deps(elem, deps),
+        _ => {}
+    }
+}
+
+/// Structs and unions ordered so each comes after the types it holds by value
+fn c_definition_order(abi: &Abi) -> Result<Vec<&Item>, String> {
+    let records: Vec<&Item> = abi
+        .items
+        .iter()
+        .filter(|item| matches!(item.kind, ItemKind::Struct(_) | ItemKind::Union(_)))
+        .collect();
+    let mut ordered: Vec<&Item> = Vec::new();
+    while ordered.len() < records.len() {
+        let before = ordered.len();
+        for item in &records {
+            if ordered.iter().any(|done| done.name == item.name) {
+                continue;
+            }
+            let (ItemKind::Struct(fields) | ItemKind::Union(fields)) = &item.kind else {
+                unreachable!();
+            };
+            let mut deps = Vec::new();
+            for field in fields {
+                by_value_deps(&field.ty, &mut deps);
+            }
+            let ready = deps.iter().all(|dep| {
+                !records.iter().any(|r| r.name == *dep) || ordered.iter().any(|done| done.name == *dep)
+            });
+            if ready {
+                ordered.push(item);
+            }
+        }
+        if ordered.len() == before {
+            return Err("structs contain each other by value".into());
+        }
+    }
+    Ok(ordered)
+}
+
+fn c_header(abi: &Abi) -> Result<String, String> {
+    let layouts = Layouts::new(abi);
+    let mut out = format!(
+        "// {}\n// C declarations of the engine ABI for 64-bit hosts. The static asserts at the\n// end check the layouts the generator computed wherever this is compiled.\n\n#ifndef ZELLO_H\n#define ZELLO_H\n\n#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n\n#ifdef __cplusplus\nextern \"C\" {{\n#endif\n",
+        GENERATED_NOTE
+    );
+
+    for (i, konst) in abi.consts.iter().enumerate() {
+        if i == 0 || !konst.docs.is_empty() {
+            out.push('\n');
+        }
+        push_c_docs(&mut out, &konst.docs, "");
+        out.push_str(&format!("#define MCORE_{} {}\n", konst.name, const_value(konst, "")));
+    }
+
+    // Enums first: they can't be forward declared
+    for item in &abi.items {
+        let ItemKind::Enum(variants) = &item.kind else {
+            continue;
+        };
+        out.push('\n');
+        push_c_docs(&mut out, &item.docs, "");
+        out.push_str(&format!("typedef enum {} {{\n", item.name));
+        for (name, value) in variants {
+            out.push_str(&format!("  {}_{} = {},\n", item.name, name, value));
+        }
+        out.push_str(&format!("}} {};\n", item.name));
+    }
+
+    // Every struct, union and handle by name, so pointers can refer to any of them
+    out.push('\n');
+    for item in &abi.items {
+        let keyword = match item.kind {
+            ItemKind::Enum(_) => continue,
+            ItemKind::Union(_) => "union",
+            _ => "struct",
+        };
+        if matches!(item.kind, ItemKind::Opaque) {
+            push_c_docs(&mut out, &item.docs, "");
+        }
+        out.push_str(&format!("typedef {} {} {};\n", keyword, item.name, item.name));
+    }
+
+    for (name, ty) in &abi.aliases {
+        out.push_str(&format!("\ntypedef {};\n", c_decl(ty, name)?));
+    }
+
+    for item in c_definition_order(abi)? {
+        let (ItemKind::Struct(fields) | ItemKind::Union(fields)) = &item.kind else {
+            continue;
+        };
+        let keyword = if matches!(item.kind, ItemKind::Union(_)) { "union" } else { "struct" };
+        out.push('\n');
+        push_c_docs(&mut out, &item.docs, "");
+        out.push_str(&format!("{} {} {{\n", keyword, item.name));
+        for field in fields {
+            push_c_docs(&mut out, &field.docs, "  ");
+            out.push_str(&format!("  {};", c_decl(&field.ty, &c_ident(&field.name))?));
+            if let Some(comment) = &field.comment {
+                out.push_str(&format!(" // {}", comment));
+            }
+            out.push('\n');
+        }
+        out.push_str("};\n");
+    }
+
+    out.push_str("\n// Functions\n");
+    for func in &abi.funcs {
+        out.push('\n');
+        push_c_docs(&mut out, &func.docs, "");
+        let params = func
+            .params
+            .iter()
+            .map(|(name, ty)| c_decl(ty, &c_ident(name)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let params = if params.is_empty() { "void".to_string() } else { params.join(", ") };
+        let ret = match &func.ret {
+            Some(ret) => c_type(ret)?,
+            None => "void".into(),
+        };
+        out.push_str(&format!("{} {}({});\n", ret, func.name, params));
+    }
+
+    out.push_str("\n#ifdef __cplusplus\n}  // extern \"C\"\n#define ZELLO_STATIC_ASSERT static_assert\n#define ZELLO_ALIGNOF alignof\n#else\n#define ZELLO_STATIC_ASSERT _Static_assert\n#define ZELLO_ALIGNOF _Alignof\n#endif\n");
+    out.push_str("\n// Layout checks (same numbers as rust/engine/src/abi_layout.rs)\n");
+    out.push_str("ZELLO_STATIC_ASSERT(sizeof(void*) == 8, \"zello.h describes the ABI of 64-bit targets\");\n");
+    for item in &abi.items {
+        let Some(layout) = layouts.item(item)? else {
+            continue;
+        };
+        out.push_str(&format!("ZELLO_STATIC_ASSERT(sizeof({0}) == {1}, \"{0} size\");\n", item.name, layout.size));
+        out.push_str(&format!("ZELLO_STATIC_ASSERT(ZELLO_ALIGNOF({0}) == {1}, \"{0} alignment\");\n", item.name, layout.align));
+        if let ItemKind::Struct(fields) = &item.kind {
+            for (field, offset) in fields.iter().zip(&layout.offsets) {
+                out.push_str(&format!(
+                    "ZELLO_STATIC_ASSERT(offsetof({0}, {1}) == {2}, \"{0}.{1} offset\");\n",
+                    item.name,
+                    c_ident(&field.name),
+                    offset
+                ));
+            }
+        }
+    }
+    out.push_str("#undef ZELLO_STATIC_ASSERT\n#undef ZELLO_ALIGNOF\n\n#endif  // ZELLO_H\n");
+
+    Ok(out)
+}
+
+// ============================================================================
+// Rust output
+// ============================================================================
+
+fn rust_layout_tests(abi: &Abi) -> Result<String, String> {
+    let layouts = Layouts::new(abi);
+    let mut out = format!(
+        "//! {}\n//! Engine side of the ABI layout tests; src/renderer/mcore.zig asserts the same numbers.\n\nuse std::mem::{{align_of, offset_of, size_of}};\n\nuse crate::*;\n",
+        GENERATED_NOTE
+    );
+
+    if !abi.consts.is_empty() {
+        out.push_str("\n#[test]\nfn constant_values() {\n");
+        for konst in &abi.consts {
+            out.push_str(&format!("    assert_eq!({}, {:#x});\n", konst.name, konst.value));
+        }
+        out.push_str("}\n");
+    }
+
+    for item in &abi.items {
+        let Some(layout) = layouts.item(item)? else {
+            continue;
+        };
+        out.push_str(&format!("\n#[test]\nfn {}_layout() {{\n", snake_case(&item.name)));
+        out.push_str(&format!("    assert_eq!(size_of::<{}>(), {});\n", item.name, layout.size));
+        out.push_str(&format!("    assert_eq!(align_of::<{}>(), {});\n", item.name, layout.align));
+        if let ItemKind::Struct(fields) = &item.kind {
+            for (field, offset) in fields.iter().zip(&layout.offsets) {
+                out.push_str(&format!("    assert_eq!(offset_of!({}, {}), {});\n", item.name, field.name, offset));
+            }
+        }
+        out.push_str("}\n");
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!(
+            parse_type("*const i8").unwrap(),
+            Ty::Ptr { mutable: false, pointee: Box::new(Ty::Prim("i8".into())) }
+        );
+        assert_eq!(parse_type("[f32; 4]").unwrap(), Ty::Array(Box::new(Ty::Prim("f32".into())), 4));
+        assert_eq!(
+            parse_type("extern \"C\" fn(u64, u8) -> f32").unwrap(),
+            Ty::Fn {
+                params: vec![Ty::Prim("u64".into()), Ty::Prim("u8".into())],
+                ret: Some(Box::new(Ty::Prim("f32".into()))),
+                nullable: false,
+            }
+        );
+        assert!(parse_type("Vec<u8>").is_err());
+    }
+
+    #[test]
+    fn test_split_ignores_arrows() {
+        assert_eq!(
+            split_top_level("a: extern \"C\" fn(u8) -> u8, b: *mut c_void"),
+            vec!["a: extern \"C\" fn(u8) -> u8", "b: *mut c_void"]
+        );
+    }
+
+    #[test]
+    fn test_layout_and_output() {
+        let source = r#"
+#[repr(C)]
+pub enum Kind {
+    /// The first kind
+    First = 1,
+    SecondThing,
+    Flag = 1 << 3,
+}
+
+/// A handle
+#[repr(C)]
+pub struct Handle(Arc<Inner>);
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Event {
+    pub kind: Kind,
+    pub flag: u8,
+    /// Text to insert
+    pub text: *const i8, // may be null
+    pub color: [f32; 4],
+}
+
+/// Send an event
+#[no_mangle]
+pub extern "C" fn send(
+    handle: *mut Handle,
+    event: *const Event,
+) -> u8 {
+    0
+}
+"#;
+        let abi = parse(source).unwrap();
+        assert_eq!(abi.items.len(), 3);
+        assert!(matches!(abi.items[1].kind, ItemKind::Opaque));
+
+        let layout = Layouts::new(&abi).item(&abi.items[2]).unwrap().unwrap();
+        assert_eq!(layout.offsets, vec![0, 4, 8, 16]);
+        assert_eq!((layout.size, layout.align), (32, 8));
+
+        let zig = zig_source(&abi).unwrap();
+        assert!(zig.contains("    second_thing = 2,\n    flag = 8,\n"));
+        assert!(zig.contains("    text: [*c]const u8, // may be null\n"));
+        assert!(zig.contains("/// Send an event\npub extern fn send(handle: ?*Handle, event: [*c]const Event) u8;\n"));
+        assert!(zig.contains("@offsetOf(Event, \"color\")"));
+
+        let c = c_header(&abi).unwrap();
+        assert!(c.contains("typedef enum Kind {\n  Kind_First = 1,\n  Kind_SecondThing = 2,\n  Kind_Flag = 8,\n} Kind;\n"));
+        assert!(c.contains("// A handle\ntypedef struct Handle Handle;\n"));
+        assert!(c.contains("  const char* text; // may be null\n  float color[4];\n"));
+        assert!(c.contains("// Send an event\nuint8_t send(Handle* handle, const Event* event);\n"));
+        assert!(c.contains("ZELLO_STATIC_ASSERT(offsetof(Event, color) == 16, \"Event.color offset\");\n"));
+
+        let rust = rust_layout_tests(&abi).unwrap();
+        assert!(rust.contains("    assert_eq!(offset_of!(Event, color), 16);\n"));
+    }
+
+    #[test]
+    fn test_constants() {
+        let source = r#"
+/// Minor versions only add
+pub const VERSION_MINOR: u32 = 2;
+pub const VERSION: u32 = (3 << 16) | VERSION_MINOR;
+const PRIVATE: u32 = 1;
+
+impl TryFrom<u8> for Kind {
+    type Error = String;
+}
+"#;
+        let abi = parse(source).unwrap();
+        assert_eq!(abi.consts.iter().map(|konst| konst.value).collect::<Vec<_>>(), [2, 0x30002]);
+        assert!(abi.aliases.is_empty(), "associated types aren't aliases");
+
+        let c = c_header(&abi).unwrap();
+        assert!(c.contains("// Minor versions only add\n#define MCORE_VERSION_MINOR 2\n"));
+        assert!(c.contains("#define MCORE_VERSION 0x30002 // (3 << 16) | VERSION_MINOR\n"));
+        assert!(zig_source(&abi).unwrap().contains("pub const MCORE_VERSION: u32 = 0x30002; // (3 << 16) | VERSION_MINOR\n"));
+        assert!(parse("pub const SCALE: f32 = 1.0;").is_err());
+    }
+
+    #[test]
+    fn test_c_declarations() {
+        let callback = parse_type("Option<extern \"C\" fn(u64, *const i8)>").unwrap();
+        assert_eq!(c_decl(&callback, "callback").unwrap(), "void (*callback)(uint64_t, const char*)");
+        assert_eq!(c_decl(&parse_type("[[f32; 2]; 3]").unwrap(), "points").unwrap(), "float points[3][2]");
+        assert_eq!(c_decl(&parse_type("*mut c_void").unwrap(), "").unwrap(), "void*");
+
+        // Structs held by value are defined before their holders
+        let source = r#"
+#[repr(C)]
+pub struct Outer {
+    pub inner: [Inner; 2],
+    pub next: *const Outer,
+}
+
+#[repr(C)]
+pub struct Inner {
+    pub value: u32,
+}
+"#;
+        let abi = parse(source).unwrap();
+        let order: Vec<&str> = c_definition_order(&abi).unwrap().iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(order, ["Inner", "Outer"]);
+    }
+}
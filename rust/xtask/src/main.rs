@@ -0,0 +1,254 @@
+//! Engine build tasks
+//!
+//! `cargo xtask xcframework [--debug] [--no-ios]` builds the engine staticlib for
+//! every Apple target, merges the slices with lipo and packages them with the
+//! generated bindings/zello.h and a module map into
+//! target/xcframework/Mcore.xcframework, so Swift (`import Mcore`) and other
+//! hosts can consume a release directly.
+//!
+//! `cargo xtask bindings [--check]` regenerates the Zig ABI mirror and the
+//! generated C header, bindings/zello.h (see bindings.rs). `zig-bindings` is
+//! the old name.
+//!
+//! `cargo xtask replay-diff <recording> [--baseline <rev>] [--tolerance N]`
+//! renders a command buffer recording with the engine at `rev` (default HEAD)
+//! and with the working tree, then diffs every frame. Use it to check that a
+//! renderer refactor doesn't change pixels.
+
+mod bindings;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+const LIB_NAME: &str = "libmasonry_core_capi.a";
+const FRAMEWORK_NAME: &str = "Mcore.xcframework";
+
+const MACOS_TARGETS: &[&str] = &["aarch64-apple-darwin", "x86_64-apple-darwin"];
+const IOS_TARGETS: &[&str] = &["aarch64-apple-ios"];
+const IOS_SIM_TARGETS: &[&str] = &["aarch64-apple-ios-sim", "x86_64-apple-ios"];
+
+/// Features for iOS slices: the a11y feature wraps the AppKit adapter, which
+/// doesn't build for iOS
+const IOS_FEATURES: &str = "image-decode,image-extra,webp";
+
+/// One library inside the XCFramework (all slices of one platform variant)
+struct Platform {
+    name: &'static str,
+    targets: &'static [&'static str],
+    features: Option<&'static str>,
+    /// Platform UI framework the module map links
+    ui_framework: &'static str,
+}
+
+const PLATFORMS: &[Platform] = &[
+    Platform {
+        name: "macos",
+        targets: MACOS_TARGETS,
+        features: None,
+        ui_framework: "AppKit",
+    },
+    Platform {
+        name: "ios",
+        targets: IOS_TARGETS,
+        features: Some(IOS_FEATURES),
+        ui_framework: "UIKit",
+    },
+    Platform {
+        name: "ios-simulator",
+        targets: IOS_SIM_TARGETS,
+        features: Some(IOS_FEATURES),
+        ui_framework: "UIKit",
+    },
+];
+
+struct Options {
+    release: bool,
+    ios: bool,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("xcframework") => parse_options(&args[1..]).and_then(|opts| xcframework(&opts)),
+        Some("bindings" | "zig-bindings") => bindings::run(&args[1..]),
+        Some("replay-diff") => replay_diff(&args[1..]),
+        _ => {
+            eprintln!("usage: cargo xtask xcframework [--debug] [--no-ios]");
+            eprintln!("       cargo xtask bindings [--check]");
+            eprintln!("       cargo xtask replay-diff <recording> [--baseline <rev>] [--tolerance N]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("xtask: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut opts = Options { release: true, ios: true };
+    for arg in args {
+        match arg.as_str() {
+            "--debug" => opts.release = false,
+            "--no-ios" => opts.ios = false,
+            other => return Err(format!("unknown option {}", other)),
+        }
+    }
+    Ok(opts)
+}
+
+/// rust/engine (the crate being packaged)
+fn engine_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../engine")
+}
+
+/// bindings/ at the repo root
+fn bindings_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../bindings")
+}
+
+fn run(cmd: &mut Command) -> Result<(), String> {
+    eprintln!("+ {:?}", cmd);
+    let status = cmd.status().map_err(|e| format!("failed to run {:?}: {}", cmd.get_program(), e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{:?} exited with {}", cmd.get_program(), status))
+    }
+}
+
+fn xcframework(opts: &Options) -> Result<(), String> {
+    let engine = engine_dir();
+    let profile = if opts.release { "release" } else { "debug" };
+    let out_dir = engine.join("target/xcframework");
+    let staging = out_dir.join("staging");
+
+    // Never package a header that disagrees with the library
+    bindings::run(&["--check".to_string()])?;
+
+    let _ = fs::remove_dir_all(&out_dir);
+    fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+
+    let mut create = Command::new("xcodebuild");
+    create.arg("-create-xcframework");
+
+    for platform in PLATFORMS.iter().filter(|p| opts.ios || p.name == "macos") {
+        // Build every slice
+        for target in platform.targets {
+            let mut cargo = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
+            cargo.current_dir(&engine).args(["build", "--lib", "--target", target]);
+            if opts.release {
+                cargo.arg("--release");
+            }
+            if let Some(features) = platform.features {
+                cargo.args(["--no-default-features", "--features", features]);
+            }
+            run(&mut cargo)?;
+        }
+
+        // Merge the slices into one fat library
+        let dir = staging.join(platform.name);
+        let headers = dir.join("Headers");
+        fs::create_dir_all(&headers).map_err(|e| e.to_string())?;
+
+        let fat_lib = dir.join(LIB_NAME);
+        let mut lipo = Command::new("lipo");
+        lipo.arg("-create").arg("-output").arg(&fat_lib);
+        for target in platform.targets {
+            lipo.arg(engine.join("target").join(target).join(profile).join(LIB_NAME));
+        }
+        run(&mut lipo)?;
+
+        // Header + module map
+        fs::copy(bindings_dir().join("zello.h"), headers.join("zello.h")).map_err(|e| e.to_string())?;
+        fs::write(headers.join("module.modulemap"), module_map(platform.ui_framework)).map_err(|e| e.to_string())?;
+
+        create.arg("-library").arg(&fat_lib).arg("-headers").arg(&headers);
+    }
+
+    let framework = out_dir.join(FRAMEWORK_NAME);
+    create.arg("-output").arg(&framework);
+    run(&mut create)?;
+
+    fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    eprintln!("Wrote {}", framework.display());
+    Ok(())
+}
+
+fn replay_diff(args: &[String]) -> Result<(), String> {
+    let mut recording = None;
+    let mut baseline = "HEAD".to_string();
+    let mut tolerance = "0".to_string();
+
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--baseline" => baseline = rest.next().ok_or("--baseline needs a git revision")?.clone(),
+            "--tolerance" => tolerance = rest.next().ok_or("--tolerance needs a value")?.clone(),
+            path if recording.is_none() => recording = Some(PathBuf::from(path)),
+            other => return Err(format!("unexpected argument {}", other)),
+        }
+    }
+    let recording = recording.ok_or("replay-diff needs a recording (see mcore_replay_record_start)")?;
+    let recording = fs::canonicalize(&recording).map_err(|e| format!("{}: {}", recording.display(), e))?;
+
+    let engine = engine_dir();
+    let out_dir = engine.join("target/replay-diff");
+    let worktree = out_dir.join("baseline-src");
+    let _ = fs::remove_dir_all(&out_dir);
+    fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+
+    // Check out the baseline next to the working tree
+    run(Command::new("git")
+        .current_dir(&engine)
+        .args(["worktree", "add", "--detach"])
+        .arg(&worktree)
+        .arg(&baseline))?;
+
+    let render = |engine_dir: &Path, frames: &str| {
+        run(Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+            .current_dir(engine_dir)
+            .args(["run", "--release", "--quiet", "--bin", "mcore-replay", "--", "render"])
+            .arg(&recording)
+            .arg(out_dir.join(frames)))
+    };
+    let rendered = render(&worktree.join("rust/engine"), "baseline").and_then(|()| render(&engine, "current"));
+
+    let _ = run(Command::new("git")
+        .current_dir(&engine)
+        .args(["worktree", "remove", "--force"])
+        .arg(&worktree));
+    rendered?;
+
+    run(Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .current_dir(&engine)
+        .args(["run", "--release", "--quiet", "--bin", "mcore-replay", "--", "diff"])
+        .arg(out_dir.join("baseline"))
+        .arg(out_dir.join("current"))
+        .args(["--tolerance", &tolerance, "--out"])
+        .arg(out_dir.join("diff")))
+    .map_err(|_| format!("frames differ from {}; see {}", baseline, out_dir.join("diff").display()))
+}
+
+/// Clang module so Swift can `import Mcore`; links the system frameworks the
+/// engine needs so hosts don't have to list them
+fn module_map(ui_framework: &str) -> String {
+    format!(
+        r#"module Mcore {{
+    header "zello.h"
+    link "masonry_core_capi"
+    link framework "{}"
+    link framework "QuartzCore"
+    link framework "Metal"
+    export *
+}}
+"#,
+        ui_framework
+    )
+}
@@ -1,6 +1,7 @@
 const std = @import("std");
 const UI = @import("../ui/ui.zig").UI;
 const a11y_mod = @import("../ui/a11y.zig");
+const text_input_widget = @import("../ui/widgets/text_input.zig");
 const c_api = @import("../renderer/c_api.zig");
 const c = c_api.c;
 
@@ -10,17 +11,20 @@ extern fn mv_get_ns_view() ?*anyopaque;
 extern fn mv_get_metal_layer() ?*anyopaque;
 extern fn mv_set_frame_callback(cb: *const fn (t: f64) callconv(.c) void) void;
 extern fn mv_set_resize_callback(cb: *const fn (w: c_int, h: c_int, scale: f32) callconv(.c) void) void;
-extern fn mv_set_key_callback(cb: *const fn (key: c_int, char_code: c_uint, shift: bool, cmd: bool) callconv(.c) void) void;
+extern fn mv_set_key_callback(cb: *const fn (key: c_int, char_code: c_uint, shift: bool, cmd: bool, option: bool) callconv(.c) void) void;
 extern fn mv_set_mouse_callback(cb: *const fn (event_type: c_int, x: f32, y: f32) callconv(.c) void) void;
 extern fn mv_set_scroll_callback(cb: *const fn (delta_x: f32, delta_y: f32) callconv(.c) void) void;
 extern fn mv_set_ime_commit_callback(cb: *const fn (text: [*:0]const u8) callconv(.c) void) void;
 extern fn mv_set_ime_preedit_callback(cb: *const fn (text: [*:0]const u8, cursor_offset: c_int) callconv(.c) void) void;
 extern fn mv_set_ime_cursor_rect_callback(cb: *const fn () callconv(.c) ImeRect) void;
+extern fn mv_set_refresh_rate_callback(cb: *const fn (hz: f32) callconv(.c) void) void;
+extern fn mv_get_refresh_rate() f32;
+extern fn mv_set_occlusion_callback(cb: *const fn (occluded: bool) callconv(.c) void) void;
 extern fn mv_app_run() void;
-extern fn mv_clipboard_set_text(text: [*:0]const u8) void;
-extern fn mv_clipboard_get_text(buffer: [*]u8, buffer_len: c_int) c_int;
 extern fn mv_app_quit() void;
+extern fn mv_shift_held() bool;
 extern fn mv_trigger_initial_resize() void;
+extern fn mv_set_text_input_mode(ime_enabled: bool, secure: bool) void;
 
 const ImeRect = extern struct { x: f32, y: f32, w: f32, h: f32 };
 
@@ -40,6 +44,9 @@ var g_ime_cursor_x: f32 = 10;
 var g_ime_cursor_y: f32 = 10;
 var g_ime_cursor_h: f32 = 20;
 
+// Last text input mode sent to the view (ime_enabled, secure)
+var g_input_mode: [2]bool = .{ true, false };
+
 pub const App = struct {
     ui: *UI,
     ctx: *c.mcore_context_t,
@@ -72,6 +79,7 @@ pub fn init(
 
     // Create surface description
     g_desc = .{
+        .api_version = c.MCORE_API_VERSION,
         .platform = c.MCORE_PLATFORM_MACOS,
         .u = .{
             .macos = .{
@@ -82,6 +90,10 @@ pub fn init(
                 .height_px = @as(i32, @intCast(height)) * 2,
             },
         },
+        // A simple UI doesn't need to wake a discrete GPU
+        .power_preference = c.MCORE_POWER_LOW_POWER,
+        .adapter = 0,
+        .flags = c.MCORE_CREATE_SOFTWARE_FALLBACK,
     };
 
     // Create rendering context
@@ -108,8 +120,13 @@ pub fn init(
     mv_set_ime_commit_callback(on_ime_commit);
     mv_set_ime_preedit_callback(on_ime_preedit);
     mv_set_ime_cursor_rect_callback(on_ime_cursor_rect);
+    mv_set_refresh_rate_callback(on_refresh_rate);
+    mv_set_occlusion_callback(on_occlusion);
     mv_set_frame_callback(on_frame);
 
+    // Engine animations step at the display's rate, not an assumed 60Hz
+    c.mcore_set_refresh_rate(g_ctx, mv_get_refresh_rate());
+
     // Trigger initial resize to get actual window size
     mv_trigger_initial_resize();
 
@@ -130,8 +147,38 @@ pub fn run(_: App) void {
 // ============================================================================
 
 fn on_frame(t: f64) callconv(.c) void {
-    c.mcore_begin_frame(g_ctx, t);
+    if (c.mcore_begin_frame(g_ctx, t) != c.MCORE_OK) {
+        const err = c.mcore_last_error();
+        if (err != null) std.debug.print("mcore error: {s}\n", .{std.mem.span(err)});
+    }
     g_frame_fn(g_ui, t);
+    syncTextInputMode();
+}
+
+/// Configure the view's text input for the focused field's hints (IME off for
+/// passwords and numeric fields, secure event input for passwords)
+fn syncTextInputMode() void {
+    var mode: [2]bool = .{ true, false };
+    if (g_ui.focus.focused_id) |fid| {
+        if (g_ui.state.text_inputs.contains(fid)) {
+            var hints: c.mcore_text_input_hints_t = undefined;
+            if (c.mcore_text_input_get_hints(g_ctx, fid, &hints) == c.MCORE_OK) {
+                mode = .{ hints.ime_enabled != 0, hints.secure != 0 };
+            }
+        }
+    }
+    if (mode[0] != g_input_mode[0] or mode[1] != g_input_mode[1]) {
+        mv_set_text_input_mode(mode[0], mode[1]);
+        g_input_mode = mode;
+    }
+}
+
+fn on_refresh_rate(hz: f32) callconv(.c) void {
+    c.mcore_set_refresh_rate(g_ctx, hz);
+}
+
+fn on_occlusion(occluded: bool) callconv(.c) void {
+    c.mcore_set_occluded(g_ctx, @intFromBool(occluded));
 }
 
 fn on_resize(w: c_int, h: c_int, scale: f32) callconv(.c) void {
@@ -146,7 +193,7 @@ fn on_resize(w: c_int, h: c_int, scale: f32) callconv(.c) void {
     c.mcore_resize(g_ctx, &g_desc);
 }
 
-fn on_key(key: c_int, char_code: c_uint, shift: bool, cmd: bool) callconv(.c) void {
+fn on_key(key: c_int, char_code: c_uint, shift: bool, cmd: bool, option: bool) callconv(.c) void {
     // Handle Cmd+Q to quit
     if (cmd and char_code == 'q') {
         mv_app_quit();
@@ -160,71 +207,36 @@ fn on_key(key: c_int, char_code: c_uint, shift: bool, cmd: bool) callconv(.c) vo
     }
 
     // Forward to UI
-    g_ui.handleKey(key, char_code, shift, cmd);
+    g_ui.handleKey(key, char_code, shift, cmd, option);
 }
 
+/// Cmd+A/X/C/V/Z on the focused text input (the engine talks to the pasteboard)
 fn handleClipboardOps(char_code: c_uint) void {
-    var clipboard_buf: [4096]u8 = undefined;
-
-    if (char_code == 'a') {
-        // Select All
-        if (g_ui.focus.focused_id) |fid| {
-            const len = c.mcore_text_input_get(g_ctx, fid, &clipboard_buf, 4096);
-            if (len > 0) {
-                c.mcore_text_input_set_cursor_pos(g_ctx, fid, 0, 0);
-                c.mcore_text_input_set_cursor_pos(g_ctx, fid, len, 1);
-            }
-        }
-    } else if (char_code == 'c') {
-        // Copy
-        if (g_ui.focus.focused_id) |fid| {
-            const len = c.mcore_text_input_get_selected_text(g_ctx, fid, &clipboard_buf, 4096);
-            if (len > 0) {
-                clipboard_buf[@intCast(len)] = 0;
-                mv_clipboard_set_text(@ptrCast(&clipboard_buf));
-            }
-        }
-    } else if (char_code == 'x') {
-        // Cut
-        if (g_ui.focus.focused_id) |fid| {
-            const len = c.mcore_text_input_get_selected_text(g_ctx, fid, &clipboard_buf, 4096);
-            if (len > 0) {
-                clipboard_buf[@intCast(len)] = 0;
-                mv_clipboard_set_text(@ptrCast(&clipboard_buf));
-                var event = c.mcore_text_event_t{
-                    .kind = c.TEXT_EVENT_BACKSPACE,
-                    .char_code = 0,
-                    .direction = c.CURSOR_LEFT,
-                    .extend_selection = 0,
-                    .cursor_position = 0,
-                    .text_ptr = null,
-                };
-                _ = c.mcore_text_input_event(g_ctx, fid, &event);
-            }
-        }
-    } else if (char_code == 'v') {
-        // Paste
-        const len = mv_clipboard_get_text(&clipboard_buf, 4096);
-        if (len > 0) {
-            clipboard_buf[@intCast(len)] = 0;
-            var event = c.mcore_text_event_t{
-                .kind = c.TEXT_EVENT_INSERT_TEXT,
-                .char_code = 0,
-                .direction = c.CURSOR_LEFT,
-                .extend_selection = 0,
-                .cursor_position = 0,
-                .text_ptr = @ptrCast(&clipboard_buf),
-            };
-            if (g_ui.focus.focused_id) |fid| {
-                _ = c.mcore_text_input_event(g_ctx, fid, &event);
-            }
-        }
-    }
+    const fid = g_ui.focus.focused_id orelse return;
+    const kind: c.mcore_text_event_kind_t = switch (char_code) {
+        'a' => c.TEXT_EVENT_SELECT_ALL,
+        'x' => c.TEXT_EVENT_CUT,
+        'c' => c.TEXT_EVENT_COPY,
+        'v' => c.TEXT_EVENT_PASTE,
+        'z' => c.TEXT_EVENT_UNDO,
+        // charactersIgnoringModifiers keeps Shift
+        'Z' => c.TEXT_EVENT_REDO,
+        else => return,
+    };
+    var event = c.mcore_text_event_t{
+        .kind = kind,
+        .char_code = 0,
+        .direction = c.CURSOR_LEFT,
+        .extend_selection = 0,
+        .cursor_position = 0,
+        .text_ptr = null,
+    };
+    _ = c.mcore_text_input_event(g_ctx, fid, &event);
 }
 
 fn on_mouse(event_type: c_int, x: f32, y: f32) callconv(.c) void {
     if (event_type == MOUSE_DOWN) {
-        g_ui.handleMouseDown(x, y);
+        g_ui.handleMouseDown(x, y, mv_shift_held());
     } else if (event_type == MOUSE_UP) {
         g_ui.handleMouseUp(x, y);
     } else if (event_type == MOUSE_MOVED) {
@@ -237,32 +249,40 @@ fn on_scroll(delta_x: f32, delta_y: f32) callconv(.c) void {
 }
 
 fn on_ime_commit(text: [*:0]const u8) callconv(.c) void {
-    const focused_id = g_ui.focus.focused_id orelse return;
-    c.mcore_ime_commit(g_ctx, focused_id, text);
+    sendImeEvent(c.TEXT_EVENT_IME_COMMIT, text, 0);
 }
 
 fn on_ime_preedit(text: [*:0]const u8, cursor_offset: c_int) callconv(.c) void {
+    // An empty preedit means the IME ended the composition without committing
+    if (text[0] == 0) {
+        sendImeEvent(c.TEXT_EVENT_IME_CANCEL, null, 0);
+    } else {
+        sendImeEvent(c.TEXT_EVENT_IME_SET_COMPOSITION, text, cursor_offset);
+    }
+}
+
+fn sendImeEvent(kind: c.mcore_text_event_kind_t, text: ?[*:0]const u8, cursor_offset: c_int) void {
     const focused_id = g_ui.focus.focused_id orelse return;
-    const preedit = c.mcore_ime_preedit_t{
-        .text = text,
-        .cursor_offset = cursor_offset,
+    var event = c.mcore_text_event_t{
+        .kind = kind,
+        .char_code = 0,
+        .direction = c.CURSOR_LEFT,
+        .extend_selection = 0,
+        .cursor_position = cursor_offset,
+        .text_ptr = text,
     };
-    c.mcore_ime_set_preedit(g_ctx, focused_id, &preedit);
+    _ = c.mcore_text_input_event(g_ctx, focused_id, &event);
 }
 
 fn on_ime_cursor_rect() callconv(.c) ImeRect {
-    // Update cursor position from focused text input
+    // Place the candidate window at the (IME) caret of the focused text input,
+    // in window coordinates under whatever transform the input was drawn with
     if (g_ui.focus.focused_id) |fid| {
-        if (g_ui.state.text_inputs.get(fid)) |ti| {
-            var text_buf: [256]u8 = undefined;
-            const cursor_pos = c.mcore_text_input_cursor(g_ctx, fid);
-            const text_len = c.mcore_text_input_get(g_ctx, fid, &text_buf, 256);
-            const text_ptr: [*:0]const u8 = if (text_len > 0) @ptrCast(text_buf[0..@intCast(text_len)].ptr) else "";
-            const cursor_offset_x = c.mcore_measure_text_to_byte_offset(g_ctx, text_ptr, 16, cursor_pos);
-
-            g_ime_cursor_x = ti.x + 10 + cursor_offset_x - ti.scroll_offset;
-            g_ime_cursor_y = ti.y;
-            g_ime_cursor_h = ti.height;
+        var caret: c.mcore_caret_rect_t = undefined;
+        if (c.mcore_text_input_caret_rect_root(g_ctx, fid, 16, &caret) == c.MCORE_OK) {
+            g_ime_cursor_x = caret.x;
+            g_ime_cursor_y = caret.y;
+            g_ime_cursor_h = caret.height;
         }
     }
 
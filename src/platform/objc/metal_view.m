@@ -1,16 +1,19 @@
 #import <AppKit/AppKit.h>
 #import <QuartzCore/CAMetalLayer.h>
 #import <QuartzCore/CVDisplayLink.h>
+#import <Carbon/Carbon.h>  // EnableSecureEventInput
 
 typedef void (*mv_frame_cb_t)(double t);
 typedef void (*mv_resize_cb_t)(int w, int h, float scale);
-typedef void (*mv_key_cb_t)(int key, unsigned int char_code, bool shift, bool cmd);
+typedef void (*mv_key_cb_t)(int key, unsigned int char_code, bool shift, bool cmd, bool option);
 typedef void (*mv_mouse_cb_t)(int event_type, float x, float y);
 typedef void (*mv_scroll_cb_t)(float delta_x, float delta_y);
 typedef void (*mv_ime_commit_cb_t)(const char* text);
 typedef void (*mv_ime_preedit_cb_t)(const char* text, int cursor_offset);
 typedef struct { float x, y, w, h; } mv_ime_rect_t;
 typedef mv_ime_rect_t (*mv_ime_cursor_rect_cb_t)(void);
+typedef void (*mv_refresh_rate_cb_t)(float hz);
+typedef void (*mv_occlusion_cb_t)(bool occluded);
 
 static mv_frame_cb_t g_frame_cb = 0;
 static mv_resize_cb_t g_resize_cb = 0;
@@ -20,6 +23,12 @@ static mv_scroll_cb_t g_scroll_cb = 0;
 static mv_ime_commit_cb_t g_ime_commit_cb = 0;
 static mv_ime_preedit_cb_t g_ime_preedit_cb = 0;
 static mv_ime_cursor_rect_cb_t g_ime_cursor_rect_cb = 0;
+static mv_refresh_rate_cb_t g_refresh_rate_cb = 0;
+static mv_occlusion_cb_t g_occlusion_cb = 0;
+static double g_refresh_rate = 60.0;
+// Focused text input's configuration (mv_set_text_input_mode)
+static bool g_ime_enabled = true;
+static bool g_secure_input = false;
 
 @interface MVMetalView : NSView <NSTextInputClient>
 @property(nonatomic, copy) NSString *markedText;
@@ -63,8 +72,9 @@ static mv_ime_cursor_rect_cb_t g_ime_cursor_rect_cb = 0;
     }
 }
 - (void)keyDown:(NSEvent *)event {
-    // Only use NSTextInputClient if IME callbacks are set up
-    if (g_ime_commit_cb && g_ime_preedit_cb) {
+    // Only use NSTextInputClient if IME callbacks are set up and the focused
+    // input wants input methods
+    if (g_ime_commit_cb && g_ime_preedit_cb && g_ime_enabled) {
         // Reset flag before interpretKeyEvents
         self.handledByIME = NO;
 
@@ -75,6 +85,7 @@ static mv_ime_cursor_rect_cb_t g_ime_cursor_rect_cb = 0;
         if (!self.handledByIME && g_key_cb) {
             bool shift = (event.modifierFlags & NSEventModifierFlagShift) != 0;
             bool cmd = (event.modifierFlags & NSEventModifierFlagCommand) != 0;
+            bool option = (event.modifierFlags & NSEventModifierFlagOption) != 0;
             unsigned int char_code = 0;
 
             // Get the character if it's a printable character
@@ -87,13 +98,14 @@ static mv_ime_cursor_rect_cb_t g_ime_cursor_rect_cb = 0;
                 }
             }
 
-            g_key_cb(event.keyCode, char_code, shift, cmd);
+            g_key_cb(event.keyCode, char_code, shift, cmd, option);
         }
     } else {
         // Fallback to original behavior if IME not set up
         if (g_key_cb) {
             bool shift = (event.modifierFlags & NSEventModifierFlagShift) != 0;
             bool cmd = (event.modifierFlags & NSEventModifierFlagCommand) != 0;
+            bool option = (event.modifierFlags & NSEventModifierFlagOption) != 0;
             unsigned int char_code = 0;
 
             // Get the character if it's a printable character
@@ -106,7 +118,7 @@ static mv_ime_cursor_rect_cb_t g_ime_cursor_rect_cb = 0;
                 }
             }
 
-            g_key_cb(event.keyCode, char_code, shift, cmd);
+            g_key_cb(event.keyCode, char_code, shift, cmd, option);
         }
     }
 }
@@ -190,10 +202,13 @@ static mv_ime_cursor_rect_cb_t g_ime_cursor_rect_cb = 0;
             // Update marked range
             self.markedRange = NSMakeRange(0, text.length);
 
-            // Notify Zig of preedit text
+            // Notify Zig of preedit text; the engine takes the IME caret as
+            // a UTF-8 byte offset, selectedRange is in UTF-16 units
             const char* utf8 = [text UTF8String];
             if (utf8) {
-                g_ime_preedit_cb(utf8, (int)selectedRange.location);
+                NSUInteger caret = MIN(selectedRange.location, text.length);
+                NSUInteger caret_bytes = [[text substringToIndex:caret] lengthOfBytesUsingEncoding:NSUTF8StringEncoding];
+                g_ime_preedit_cb(utf8, (int)caret_bytes);
             }
         }
 
@@ -236,17 +251,11 @@ static mv_ime_cursor_rect_cb_t g_ime_cursor_rect_cb = 0;
     if (g_ime_cursor_rect_cb) {
         mv_ime_rect_t rect = g_ime_cursor_rect_cb();
 
-        // TODO: Fix coordinate conversion for emoji picker positioning
-        // Currently the picker appears far from the cursor. Need to properly
-        // convert from view pixel coordinates to screen coordinates, accounting
-        // for window position, backing scale factor, and macOS coordinate system
-        // (origin at bottom-left).
-
-        // Convert from window coordinates to screen coordinates
-        NSRect windowRect = NSMakeRect(rect.x, rect.y, rect.w, rect.h);
-        NSRect screenRect = [self.window convertRectToScreen:windowRect];
-
-        return screenRect;
+        // The rect is in logical view coordinates (flipped, origin top-left):
+        // go through the window to screen space, which is bottom-left origin
+        NSRect viewRect = NSMakeRect(rect.x, rect.y, rect.w, rect.h);
+        NSRect windowRect = [self convertRect:viewRect toView:nil];
+        return [self.window convertRectToScreen:windowRect];
     }
 
     // Fallback to bottom-left corner
@@ -332,6 +341,37 @@ static mv_ime_cursor_rect_cb_t g_ime_cursor_rect_cb = 0;
 
 static MVApp *GApp;
 
+static double mv_screen_refresh_rate(NSScreen *screen) {
+    if (@available(macOS 12.0, *)) {
+        NSInteger fps = screen.maximumFramesPerSecond;
+        if (fps > 0) return (double)fps;
+    }
+    return 60.0;
+}
+
+// Frame timer at the display's refresh rate - use common modes to keep firing during resize
+static void mv_start_frame_timer(double hz) {
+    [GApp.timer invalidate];
+    GApp.timer = [NSTimer timerWithTimeInterval:(1.0/hz)
+                                         repeats:YES
+                                           block:^(__unused NSTimer *t) {
+        static double t0 = 0;
+        double now = CFAbsoluteTimeGetCurrent();
+        if (t0 == 0) t0 = now;
+        if (g_frame_cb) g_frame_cb(now - t0);
+    }];
+    [[NSRunLoop currentRunLoop] addTimer:GApp.timer forMode:NSRunLoopCommonModes];
+}
+
+// Window moved to another screen or the display mode changed
+static void mv_update_refresh_rate(void) {
+    double hz = mv_screen_refresh_rate(GApp.window.screen);
+    if (hz == g_refresh_rate) return;
+    g_refresh_rate = hz;
+    mv_start_frame_timer(hz);
+    if (g_refresh_rate_cb) g_refresh_rate_cb((float)hz);
+}
+
 void* mv_app_init(int width, int height, const char* ctitle) {
     @autoreleasepool {
         if (!NSApp) {
@@ -359,16 +399,26 @@ void* mv_app_init(int width, int height, const char* ctitle) {
         [GApp.window makeKeyAndOrderFront:nil];
         [NSApp activateIgnoringOtherApps:YES];
 
-        // 60 fps timer - use common modes to keep firing during resize
-        GApp.timer = [NSTimer timerWithTimeInterval:(1.0/60.0)
-                                             repeats:YES
-                                               block:^(__unused NSTimer *t) {
-            static double t0 = 0;
-            double now = CFAbsoluteTimeGetCurrent();
-            if (t0 == 0) t0 = now;
-            if (g_frame_cb) g_frame_cb(now - t0);
+        g_refresh_rate = mv_screen_refresh_rate(GApp.window.screen);
+        mv_start_frame_timer(g_refresh_rate);
+
+        NSNotificationCenter *center = [NSNotificationCenter defaultCenter];
+        [center addObserverForName:NSWindowDidChangeScreenNotification
+                            object:GApp.window
+                             queue:nil
+                        usingBlock:^(__unused NSNotification *n) { mv_update_refresh_rate(); }];
+        [center addObserverForName:NSApplicationDidChangeScreenParametersNotification
+                            object:nil
+                             queue:nil
+                        usingBlock:^(__unused NSNotification *n) { mv_update_refresh_rate(); }];
+        // Covers miniaturizing, other windows on top and hidden spaces
+        [center addObserverForName:NSWindowDidChangeOcclusionStateNotification
+                            object:GApp.window
+                             queue:nil
+                        usingBlock:^(__unused NSNotification *n) {
+            bool occluded = (GApp.window.occlusionState & NSWindowOcclusionStateVisible) == 0;
+            if (g_occlusion_cb) g_occlusion_cb(occluded);
         }];
-        [[NSRunLoop currentRunLoop] addTimer:GApp.timer forMode:NSRunLoopCommonModes];
         return (__bridge void*)GApp;
     }
 }
@@ -413,45 +463,46 @@ void mv_set_ime_cursor_rect_callback(mv_ime_cursor_rect_cb_t cb) {
     g_ime_cursor_rect_cb = cb;
 }
 
-void mv_app_run(void) {
-    [NSApp run];
-}
+// Configure text input for the focused field: input methods on or off (an
+// in-progress composition is dropped when they turn off), and secure event
+// input for password fields so other processes can't observe keystrokes
+void mv_set_text_input_mode(bool ime_enabled, bool secure) {
+    if (!ime_enabled && g_ime_enabled && GApp && GApp.view) {
+        [GApp.view.inputContext discardMarkedText];
+        if ([GApp.view hasMarkedText]) {
+            [GApp.view unmarkText];
+        }
+    }
+    g_ime_enabled = ime_enabled;
 
-// Clipboard functions
-void mv_clipboard_set_text(const char* text) {
-    @autoreleasepool {
-        if (!text) return;
-        NSString *str = [NSString stringWithUTF8String:text];
-        NSPasteboard *pasteboard = [NSPasteboard generalPasteboard];
-        [pasteboard clearContents];
-        [pasteboard setString:str forType:NSPasteboardTypeString];
+    if (secure != g_secure_input) {
+        if (secure) {
+            EnableSecureEventInput();
+        } else {
+            DisableSecureEventInput();
+        }
+        g_secure_input = secure;
     }
 }
 
-int mv_clipboard_get_text(char* buffer, int buffer_len) {
-    @autoreleasepool {
-        if (!buffer || buffer_len <= 0) return 0;
+void mv_set_refresh_rate_callback(mv_refresh_rate_cb_t cb) {
+    g_refresh_rate_cb = cb;
+}
 
-        NSPasteboard *pasteboard = [NSPasteboard generalPasteboard];
-        NSString *str = [pasteboard stringForType:NSPasteboardTypeString];
+void mv_set_occlusion_callback(mv_occlusion_cb_t cb) {
+    g_occlusion_cb = cb;
+}
 
-        if (!str) {
-            buffer[0] = '\0';
-            return 0;
-        }
+float mv_get_refresh_rate(void) {
+    return (float)g_refresh_rate;
+}
 
-        const char* utf8 = [str UTF8String];
-        if (!utf8) {
-            buffer[0] = '\0';
-            return 0;
-        }
+void mv_app_run(void) {
+    [NSApp run];
+}
 
-        int len = (int)strlen(utf8);
-        int copy_len = (len < buffer_len - 1) ? len : (buffer_len - 1);
-        memcpy(buffer, utf8, copy_len);
-        buffer[copy_len] = '\0';
-        return copy_len;
-    }
+bool mv_shift_held(void) {
+    return ([NSEvent modifierFlags] & NSEventModifierFlagShift) != 0;
 }
 
 void mv_app_quit(void) {
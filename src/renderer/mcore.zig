@@ -0,0 +1,2795 @@
+//! Generated by `cargo xtask bindings` from rust/engine/src/lib.rs. Do not edit.
+//! Mirrors the engine ABI for 64-bit targets; layout tests: `zig build test-abi`.
+
+const std = @import("std");
+
+/// Fall back to a software adapter (and Vello's CPU pipeline) when no GPU
+/// adapter is usable, e.g. in VMs and CI
+pub const MCORE_CREATE_SOFTWARE_FALLBACK: u32 = 0x1; // 1 << 0
+
+/// Run Vello's pipeline stages on the CPU even with a GPU
+pub const MCORE_CREATE_CPU_PIPELINE: u32 = 0x2; // 1 << 1
+
+/// Changes only if the stream's framing does: tags and payload fields are
+/// only ever added
+pub const MCORE_COMMAND_STREAM_VERSION: u32 = 1;
+
+/// Version of the C API: (major << 16) | minor. Minor versions only add entry
+/// points; changing an existing struct or signature bumps the major.
+pub const MCORE_API_VERSION_MAJOR: u32 = 4;
+pub const MCORE_API_VERSION_MINOR: u32 = 17;
+pub const MCORE_API_VERSION: u32 = 0x40011; // (API_VERSION_MAJOR << 16) | API_VERSION_MINOR
+
+/// McoreTextRun flags
+pub const MCORE_TEXT_RUN_BOLD: u32 = 0x1; // 1 << 0
+pub const MCORE_TEXT_RUN_ITALIC: u32 = 0x2; // 1 << 1
+pub const MCORE_TEXT_RUN_UNDERLINE: u32 = 0x4; // 1 << 2
+
+/// Inline code: the style's code font and colors
+pub const MCORE_TEXT_RUN_CODE: u32 = 0x8; // 1 << 3
+
+/// Use the run's color instead of the style's
+pub const MCORE_TEXT_RUN_COLOR: u32 = 0x10; // 1 << 4
+
+/// What kind of failure the last error on a thread was (mcore_last_error_code)
+pub const McoreErrorCode = enum(c_int) {
+    none = 0,
+    other = 1,
+    invalid_arg = 2,
+    not_found = 3,
+    invalid_state = 4,
+    wgpu = 5,
+    vello = 6,
+    surface_lost = 7,
+    out_of_memory = 8,
+    utf8 = 9,
+    io = 10,
+    unsupported = 11,
+    panic = 12,
+    version_mismatch = 13,
+};
+
+/// Optional subsystems, reported by mcore_get_capabilities as a bitmask
+pub const McoreCapability = enum(c_int) {
+    image_decode = 1,
+    accessibility = 2,
+    layout = 4,
+    webp_decode = 8,
+    avif_decode = 16,
+};
+
+pub const McorePlatform = enum(c_int) {
+    mac_os = 1,
+    windows = 2,
+    x11 = 3,
+    wayland = 4,
+};
+
+pub const McoreMacSurface = extern struct {
+    ns_view: ?*anyopaque, // NSView*
+    ca_metal_layer: ?*anyopaque, // CAMetalLayer*
+    scale_factor: f32,
+    width_px: i32,
+    height_px: i32,
+};
+
+pub const McoreSurfaceUnion = extern union {
+    macos: McoreMacSurface,
+};
+
+/// Which GPU a context renders with when McoreSurfaceDesc::adapter is 0
+pub const McorePowerPreference = enum(c_int) {
+    default = 0,
+    low_power = 1,
+    high_performance = 2,
+};
+
+pub const McoreSurfaceDesc = extern struct {
+    /// MCORE_API_VERSION as the host saw it. First, so hosts built before
+    /// the field existed fail the check instead of misreading the rest.
+    api_version: u32,
+    platform: McorePlatform,
+    u: McoreSurfaceUnion,
+    power_preference: McorePowerPreference,
+    /// 1-based index into mcore_enumerate_adapters, or 0 to pick by
+    /// power_preference
+    adapter: u32,
+    /// MCORE_CREATE_* flags
+    flags: u32,
+};
+
+/// How mcore_engine_create picks the GPU its contexts share
+pub const McoreEngineDesc = extern struct {
+    /// MCORE_API_VERSION as the host saw it
+    api_version: u32,
+    power_preference: McorePowerPreference,
+    /// 1-based index into mcore_enumerate_adapters, or 0 to pick by
+    /// power_preference
+    adapter: u32,
+    /// MCORE_CREATE_* flags
+    flags: u32,
+};
+
+/// How a context rasterizes frames (mcore_render_mode)
+pub const McoreRenderMode = enum(c_int) {
+    gpu = 0,
+    cpu_pipeline = 1,
+    software = 2,
+};
+
+/// McoreAdapterInfo::device_type values
+pub const McoreDeviceType = enum(c_int) {
+    other = 0,
+    integrated_gpu = 1,
+    discrete_gpu = 2,
+    virtual_gpu = 3,
+    cpu = 4,
+};
+
+/// McoreAdapterInfo::backend values
+pub const McoreGpuBackend = enum(c_int) {
+    other = 0,
+    metal = 1,
+    vulkan = 2,
+    dx12 = 3,
+    gl = 4,
+};
+
+pub const McoreAdapterInfo = extern struct {
+    /// NUL-terminated, truncated to fit
+    name: [128]u8,
+    vendor: u32,
+    device: u32,
+    device_type: McoreDeviceType,
+    backend: McoreGpuBackend,
+};
+
+/// The adapter a context renders with and the device limits the engine got
+pub const McoreGpuInfo = extern struct {
+    adapter: McoreAdapterInfo,
+    max_texture_dimension_2d: u32,
+    max_storage_buffer_binding_size: u32,
+    max_buffer_size: u64,
+    /// GPU frame timings are available (McoreFrameStats::gpu_ms)
+    timestamp_queries: u8,
+};
+
+pub const McoreRgba = extern struct {
+    r: f32,
+    g: f32,
+    b: f32,
+    a: f32,
+};
+
+pub const McoreRoundedRect = extern struct {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    radius: f32,
+    fill: McoreRgba,
+};
+
+pub const McoreFontBlob = extern struct {
+    data: [*c]const u8,
+    len: usize,
+    name: [*c]const u8,
+};
+
+pub const McoreTextReq = extern struct {
+    utf8: [*c]const u8,
+    wrap_width: f32,
+    font_size_px: f32,
+    font_id: i32,
+    /// CSS-style weight (100-900), 0 for the font's default face
+    weight: f32,
+    italic: u8,
+    /// Width ratio (0.5-2.0), 0 for normal
+    stretch: f32,
+    /// Extra space after each character / word, logical pixels
+    letter_spacing: f32,
+    word_spacing: f32,
+    /// Multiple of the font size, 0 for the font's default line height
+    line_height_multiplier: f32,
+    /// McoreTextAlign value
+    @"align": u8,
+    /// Most lines to show, 0 for no limit
+    max_lines: u32,
+    /// McoreTextOverflow value, applied past max_lines
+    overflow: u8,
+    /// McoreTextDirection value
+    direction: u8,
+    /// Spacing of repeating tab stops (logical px), 0 for 8 spaces
+    tab_width: f32,
+    /// Explicit tab stops (logical px from the line start) before the
+    /// repeating ones; may be null when tab_stop_count is 0
+    tab_stops: [*c]const f32,
+    tab_stop_count: u32,
+};
+
+/// Horizontal alignment within the wrap width
+pub const McoreTextAlign = enum(c_int) {
+    start = 0,
+    center = 1,
+    end = 2,
+    justify = 3,
+};
+
+/// Paragraph direction of a text request
+pub const McoreTextDirection = enum(c_int) {
+    auto = 0,
+    ltr = 1,
+    rtl = 2,
+};
+
+/// How text past max_lines is truncated
+pub const McoreTextOverflow = enum(c_int) {
+    clip = 0,
+    ellipsis = 1,
+    fade = 2,
+};
+
+/// Style applied to a byte range of a text request
+pub const McoreTextSpan = extern struct {
+    start: u32,
+    end: u32,
+    color: McoreRgba,
+    /// CSS-style weight (100-900), 0 to inherit
+    weight: f32,
+    italic: u8,
+    underline: u8,
+    /// Font for this range, -1 to inherit
+    font_id: i32,
+};
+
+pub const McoreTextMetrics = extern struct {
+    advance_w: f32,
+    advance_h: f32,
+    line_count: i32,
+    /// 1 if lines past max_lines were dropped
+    truncated: u8,
+};
+
+pub const McoreTextSize = extern struct {
+    width: f32,
+    height: f32,
+};
+
+/// Caret position in logical pixels; y is the top of the caret's line
+pub const McoreCaretRect = extern struct {
+    x: f32,
+    y: f32,
+    height: f32,
+};
+
+pub const McoreTextStats = extern struct {
+    total_measure_calls: u32,
+    total_offset_calls: u32,
+};
+
+/// Translation + uniform scale from the hit transform stack
+pub const McoreHitTransform = extern struct {
+    dx: f32,
+    dy: f32,
+    scale: f32,
+};
+
+/// The region under a point (mcore_hit_test)
+pub const McoreHitResult = extern struct {
+    id: u64,
+    z: i32,
+    /// The point in the region's local space, origin at its top-left
+    local_x: f32,
+    local_y: f32,
+};
+
+/// Shaped-layout cache counters (hits/misses/evictions since the last reset)
+pub const McoreTextCacheStats = extern struct {
+    entries: u32,
+    max_entries: u32,
+    /// Estimated bytes held by cached layouts
+    bytes: u64,
+    max_bytes: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+};
+
+/// What an McoreDrawCommand draws
+pub const McoreDrawCmdKind = enum(c_int) {
+    rounded_rect = 0,
+    text = 1,
+    push_clip = 2,
+    pop_clip = 3,
+    styled_rect = 4,
+    push_clip_rounded = 5,
+    gradient_ring = 6,
+};
+
+pub const McoreDrawCommand = extern struct {
+    /// McoreDrawCmdKind value
+    kind: u8,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+    color: [4]f32,
+    text_ptr: [*c]const u8,
+    font_size: f32,
+    wrap_width: f32,
+    font_id: i32,
+    border_width: f32,
+    border_color: [4]f32,
+    has_border: u8,
+    shadow_offset_x: f32,
+    shadow_offset_y: f32,
+    shadow_blur: f32,
+    shadow_color: [4]f32,
+    has_shadow: u8,
+    /// McoreTextAlign value (text commands)
+    text_align: u8,
+    /// McoreTextDirection value (text commands)
+    text_direction: u8,
+};
+
+/// Precedes each record's payload in a command stream
+pub const McoreCmdHeader = extern struct {
+    /// McoreCmdTag value
+    tag: u16,
+    /// Reserved, 0
+    flags: u16,
+    /// Payload bytes, not counting the padding to the next record
+    size: u32,
+};
+
+pub const McoreCmdTag = enum(c_int) {
+    fill_rect = 1,
+    stroke_rect = 2,
+    shadow = 3,
+    text = 4,
+    push_clip = 5,
+    pop_clip = 6,
+    gradient = 7,
+    image = 8,
+    push_transform = 9,
+    pop_transform = 10,
+    video = 11,
+};
+
+/// Rounded-rect fill (logical px)
+pub const McoreCmdFill = extern struct {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+    color: [4]f32,
+};
+
+/// Rounded-rect outline, line_width centered on the edge
+pub const McoreCmdStroke = extern struct {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+    line_width: f32,
+    color: [4]f32,
+};
+
+/// Blurred rounded rect, for drop shadows
+pub const McoreCmdShadow = extern struct {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+    blur: f32,
+    color: [4]f32,
+};
+
+/// Text at (x, y); the UTF-8 is the last text_len bytes of the payload
+pub const McoreCmdText = extern struct {
+    x: f32,
+    y: f32,
+    font_size: f32,
+    /// 0 for no wrapping
+    wrap_width: f32,
+    font_id: i32,
+    color: [4]f32,
+    /// McoreTextAlign value
+    @"align": u32,
+    /// McoreTextDirection value
+    direction: u32,
+    text_len: u32,
+};
+
+/// Clip to a rounded rect until the matching PopClip
+pub const McoreCmdClip = extern struct {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+};
+
+/// Rounded rect painted with a registered gradient: filled, or stroked
+/// line_width wide when that's above 0
+pub const McoreCmdGradient = extern struct {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+    gradient_id: i32,
+    line_width: f32,
+};
+
+/// Registered image over a rect: stretched to it, or with a tile size drawn
+/// at that size from (x + tile_x, y + tile_y) and extended past its edges
+pub const McoreCmdImage = extern struct {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    image_id: i32,
+    /// MCORE_IMAGE_SAMPLING_*
+    sampling: u32,
+    /// MCORE_IMAGE_EXTEND_*, per axis
+    extend_x: u32,
+    extend_y: u32,
+    /// Where one copy of the image goes, relative to x, y; a size of 0 is
+    /// the rect's
+    tile_x: f32,
+    tile_y: f32,
+    tile_width: f32,
+    tile_height: f32,
+};
+
+/// Affine transform applied to the following commands until the matching
+/// PopTransform, composed with any already pushed: [a, b, c, d, e, f] maps
+/// (x, y) to (a*x + c*y + e, b*x + d*y + f), translation in logical px
+pub const McoreCmdTransform = extern struct {
+    matrix: [6]f32,
+};
+
+/// A video's latest frame (mcore_video_frame_submit) fitted to a rect
+pub const McoreCmdVideo = extern struct {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    video_id: i32,
+    /// MCORE_VIDEO_FIT_*
+    fit: u32,
+};
+
+/// Color type - just an RGBA tuple
+/// Same layout as peniko::Color which is an array [r, g, b, a]
+pub const McoreColor = extern struct {
+    r: f32,
+    g: f32,
+    b: f32,
+    a: f32,
+};
+
+pub const McoreStatus = enum(c_int) {
+    ok = 0,
+    err = 1,
+    not_in_frame = 2,
+    frame_in_progress = 3,
+    frame_skipped = 4,
+};
+
+pub const McoreContext = opaque {};
+
+/// A GPU device, Vello's pipelines, fonts and images shared by the contexts
+/// created from it (one per window), instead of each context opening its own
+pub const McoreEngine = opaque {};
+
+/// A shaped text layout owned by the host
+/// Shape once, then measure, hit test and draw it as often as needed.
+pub const McoreTextLayout = opaque {};
+
+/// Paragraph kinds for McoreTextBlockParagraph
+pub const McoreTextBlockKind = enum(c_int) {
+    paragraph = 0,
+    heading = 1,
+    bullet = 2,
+    numbered = 3,
+};
+
+/// Styling for a byte range of a paragraph
+pub const McoreTextRun = extern struct {
+    start: u32,
+    end: u32,
+    /// MCORE_TEXT_RUN_* flags
+    flags: u32,
+    /// Used with MCORE_TEXT_RUN_COLOR
+    color: McoreRgba,
+};
+
+pub const McoreTextBlockParagraph = extern struct {
+    utf8: [*c]const u8,
+    /// McoreTextBlockKind value
+    kind: u32,
+    /// Heading level (1-6) or list nesting depth (0 for the top level)
+    level: u32,
+    /// Shown by numbered list items
+    number: u32,
+    run_count: u32,
+    /// May be null when run_count is 0
+    runs: [*c]const McoreTextRun,
+};
+
+/// Styling shared by a block's paragraphs (logical px)
+pub const McoreTextBlockStyle = extern struct {
+    font_size_px: f32,
+    font_id: i32,
+    /// Font for code runs, -1 for font_id
+    code_font_id: i32,
+    /// Multiple of the font size, 0 for the font's default line height
+    line_height_multiplier: f32,
+    /// Space between paragraphs; consecutive list items sit closer
+    paragraph_spacing: f32,
+    /// Indent per list nesting level, markers included
+    list_indent: f32,
+    color: McoreRgba,
+    code_color: McoreRgba,
+    code_background: McoreRgba,
+};
+
+/// Laid out rich text paragraphs owned by the host
+/// Like a layout handle, it captures the scale factor at creation.
+pub const McoreTextBlock = opaque {};
+
+/// Monospace code drawn from a buffer, shaping only the lines in the viewport
+/// (logical px)
+pub const McoreCodeReq = extern struct {
+    /// UTF-8, not null-terminated; may be null when len is 0
+    text: [*c]const u8,
+    len: usize,
+    font_size_px: f32,
+    font_id: i32,
+    /// Multiple of the font size, 0 for the font's default line height
+    line_height_multiplier: f32,
+    /// Columns between tab stops, 0 for 8
+    tab_size: u32,
+    /// 1 to draw line numbers in line_number_color
+    line_numbers: u32,
+    /// How far the viewport is scrolled down, and its height
+    scroll_y: f32,
+    viewport_height: f32,
+    color: McoreRgba,
+    line_number_color: McoreRgba,
+};
+
+/// A syntax color for a byte range of a code buffer
+pub const McoreCodeToken = extern struct {
+    start: u32,
+    end: u32,
+    color: McoreRgba,
+};
+
+/// Logical px
+pub const McoreCodeMetrics = extern struct {
+    line_count: u32,
+    line_height: f32,
+    /// Where the code starts; 0 without line numbers
+    gutter_width: f32,
+    /// line_count * line_height, for sizing a scroll view
+    content_height: f32,
+};
+
+/// A large document shaped a range of lines at a time
+pub const McoreTextWindow = opaque {};
+
+pub const McoreGradientStop = extern struct {
+    /// Position along the gradient, 0..=1
+    offset: f32,
+    color: McoreRgba,
+};
+
+pub const McoreGradientDesc = extern struct {
+    /// 0 = linear, 1 = sweep (conic)
+    kind: u8,
+    _padding: [3]u8,
+    /// Linear: direction (0 = left to right, 90 = top to bottom).
+    /// Sweep: where offset 0 starts (0 = 3 o'clock), going clockwise.
+    angle_deg: f32,
+    stops: [*c]const McoreGradientStop,
+    stop_count: u32,
+};
+
+/// Timings, scene size and memory of the last rendered frame
+pub const McoreFrameStats = extern struct {
+    /// Frames presented so far
+    frame: u64,
+    /// CPU milliseconds from mcore_begin_frame to mcore_end_frame_present
+    build_ms: f32,
+    /// Part of build_ms the engine spent encoding command buffers
+    encode_ms: f32,
+    /// CPU milliseconds rendering custom widget textures
+    custom_ms: f32,
+    /// CPU milliseconds submitting the render and presenting
+    render_ms: f32,
+    /// GPU milliseconds of a recent frame (a frame or two behind), or -1
+    /// where the GPU can't time itself
+    gpu_ms: f32,
+    commands: u32,
+    encode_calls: u32,
+    /// Vello scene elements
+    paths: u32,
+    path_segments: u32,
+    clips: u32,
+    draw_objects: u32,
+    image_count: u32,
+    /// Decoded pixels of registered images and their atlas pages
+    image_bytes: u64,
+    /// Render targets and custom widget textures
+    texture_bytes: u64,
+    /// Clip pops with nothing pushed and clips left open at
+    /// mcore_end_frame_present, both corrected by the engine (layers.rs)
+    layer_warnings: u32,
+};
+
+/// Antialiasing methods (mcore_set_aa_mode)
+pub const McoreAaMode = enum(c_int) {
+    area = 0,
+    msaa8 = 1,
+    msaa16 = 2,
+};
+
+/// Glyph fitting for a context's text
+pub const McoreTextRendering = extern struct {
+    /// 1 to hint outlines and snap baselines to whole pixels
+    hinting: u32,
+    /// Horizontal glyph positions per pixel: 0 unquantized, 1 whole pixels,
+    /// up to 16
+    subpixel_positions: u32,
+};
+
+pub const McoreTextEventKind = enum(c_int) {
+    insert_char = 0,
+    backspace = 1,
+    delete = 2,
+    move_cursor = 3,
+    set_cursor = 4,
+    insert_text = 5,
+    backspace_word = 6,
+    delete_word = 7,
+    select_all = 8,
+    cut = 9,
+    copy = 10,
+    paste = 11,
+    undo = 12,
+    redo = 13,
+    ime_set_composition = 14,
+    ime_commit = 15,
+    ime_cancel = 16,
+};
+
+pub const McoreCursorDirection = enum(c_int) {
+    left = 0,
+    right = 1,
+    home = 2,
+    end = 3,
+    word_left = 4,
+    word_right = 5,
+    backward = 6,
+    forward = 7,
+    word_backward = 8,
+    word_forward = 9,
+};
+
+pub const McoreTextEvent = extern struct {
+    kind: McoreTextEventKind,
+    char_code: u32,
+    direction: McoreCursorDirection,
+    extend_selection: u8,
+    cursor_position: i32,
+    text_ptr: [*c]const u8,
+};
+
+/// Result of mcore_text_input_event_ex
+pub const McoreTextEventResult = extern struct {
+    /// The text changed (mcore_text_input_event's return value)
+    changed: u8,
+    /// The scroll offset moved to follow the caret
+    scrolled: u8,
+    _padding: [2]u8,
+    /// Offset into the content to draw at (logical px); 0 without a viewport
+    scroll_x: f32,
+    scroll_y: f32,
+};
+
+/// How mcore_text_input_draw lays out and colors a field (logical px)
+pub const McoreTextInputStyle = extern struct {
+    /// Field box; everything is clipped to it
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    padding_x: f32,
+    padding_y: f32,
+    font_size: f32,
+    /// 0: one line, centered vertically. Otherwise wrap at this width.
+    wrap_width: f32,
+    text_color: McoreRgba,
+    selection_color: McoreRgba,
+    caret_color: McoreRgba,
+    /// Draw the selection and blinking caret
+    focused: u8,
+    _padding: [3]u8,
+};
+
+/// What a host should configure for a focused text input
+pub const McoreTextInputHints = extern struct {
+    /// MCORE_KEYBOARD_*
+    keyboard: u8,
+    /// MCORE_AUTOCORRECT_*
+    autocorrect: u8,
+    /// Password field (mcore_text_input_set_secure): use secure event input
+    secure: u8,
+    /// Input methods should be active (off for secure, non-default keyboard
+    /// and character-constrained inputs)
+    ime_enabled: u8,
+};
+
+pub const McoreCursor = enum(c_int) {
+    arrow = 0,
+    ibeam = 1,
+    pointing_hand = 2,
+    crosshair = 3,
+    open_hand = 4,
+    closed_hand = 5,
+    not_allowed = 6,
+    resize_left_right = 7,
+    resize_up_down = 8,
+    resize_nwse = 9,
+    resize_nesw = 10,
+};
+
+pub const McoreImePreedit = extern struct {
+    text: [*c]const u8,
+    cursor_offset: i32,
+};
+
+pub const McorePointerPhase = enum(c_int) {
+    down = 0,
+    move = 1,
+    up = 2,
+    cancel = 3,
+};
+
+pub const McoreGesture = extern struct {
+    kind: u8, // 0 = Tap, 1 = DoubleTap, 2 = LongPress, 3 = PanBegin, 4 = PanUpdate, 5 = PanEnd
+    x: f32,
+    y: f32,
+    dx: f32,
+    dy: f32,
+    velocity_x: f32,
+    velocity_y: f32,
+};
+
+pub const McoreScrollState = extern struct {
+    offset_x: f32,
+    offset_y: f32,
+    velocity_x: f32,
+    velocity_y: f32,
+    animating: u8,
+};
+
+pub const McoreScrollPhase = enum(c_int) {
+    wheel = 0,
+    began = 1,
+    changed = 2,
+    ended = 3,
+    cancelled = 4,
+};
+
+pub const McoreScrollAxis = enum(c_int) {
+    x = 0,
+    y = 1,
+};
+
+pub const McoreLayoutDirection = enum(c_int) {
+    row = 0,
+    column = 1,
+    row_reverse = 2,
+    column_reverse = 3,
+};
+
+/// Main-axis distribution (justify-content)
+pub const McoreLayoutJustify = enum(c_int) {
+    start = 0,
+    end = 1,
+    center = 2,
+    space_between = 3,
+    space_around = 4,
+    space_evenly = 5,
+};
+
+/// Cross-axis alignment (align-items, align-self)
+pub const McoreLayoutAlign = enum(c_int) {
+    auto = 0,
+    stretch = 1,
+    start = 2,
+    end = 3,
+    center = 4,
+    baseline = 5,
+};
+
+pub const McoreEdges = extern struct {
+    top: f32,
+    right: f32,
+    bottom: f32,
+    left: f32,
+};
+
+/// A layout node's flex properties (logical px). Start from
+/// mcore_layout_style_default: a zeroed style has zero width and height.
+pub const McoreLayoutStyle = extern struct {
+    /// McoreLayoutDirection
+    direction: u8,
+    /// 1 wraps items onto more lines
+    wrap: u8,
+    /// McoreLayoutJustify
+    justify: u8,
+    /// McoreLayoutAlign
+    align_items: u8,
+    /// McoreLayoutAlign
+    align_self: u8,
+    /// 1 takes the node out of the layout (it gets an empty rect)
+    hidden: u8,
+    /// Sizes; negative (MCORE_LAYOUT_AUTO) is auto
+    width: f32,
+    height: f32,
+    min_width: f32,
+    min_height: f32,
+    max_width: f32,
+    max_height: f32,
+    grow: f32,
+    shrink: f32,
+    /// Negative is auto
+    basis: f32,
+    padding: McoreEdges,
+    margin: McoreEdges,
+    row_gap: f32,
+    column_gap: f32,
+};
+
+/// Colors and metrics of mcore_ui_* widgets (logical px)
+pub const McoreUiTheme = extern struct {
+    font_size: f32,
+    /// Inside buttons and text fields
+    padding: f32,
+    /// Between widgets
+    spacing: f32,
+    corner_radius: f32,
+    text: McoreRgba,
+    button: McoreRgba,
+    button_hover: McoreRgba,
+    button_pressed: McoreRgba,
+    field: McoreRgba,
+    /// Focus ring and caret; the selection is a translucent tint of it
+    accent: McoreRgba,
+};
+
+/// Pointer state for mcore_ui_begin (logical px)
+pub const McoreUiInput = extern struct {
+    pointer_x: f32,
+    pointer_y: f32,
+    /// 1 while the primary button is held
+    pointer_down: u8,
+    _padding: [3]u8,
+};
+
+/// Passed to a custom texture callback each frame
+pub const McoreTextureTarget = extern struct {
+    /// Native texture (id<MTLTexture> on macOS), RGBA8 unorm, premultiplied alpha
+    native_texture: ?*anyopaque,
+    width: u32,
+    height: u32,
+    time_seconds: f64,
+};
+
+/// A decoded video frame: pixels in memory, or an IOSurface
+pub const McoreVideoFrame = extern struct {
+    /// MCORE_VIDEO_FORMAT_*; ignored for IOSurfaces, whose own format is used
+    format: u32,
+    width: u32,
+    height: u32,
+    /// MCORE_VIDEO_MATRIX_*, for NV12
+    matrix: u32,
+    /// NV12 only: non-zero if luma and chroma span 0-255 rather than
+    /// 16-235 / 16-240; ignored for IOSurfaces
+    full_range: u32,
+    _reserved: u32,
+    /// BGRA: the pixels. NV12: the luma plane, then interleaved Cb, Cr.
+    /// Each plane holds stride * rows bytes.
+    planes: [2][*c]const u8,
+    /// Bytes per row of each plane
+    strides: [2]u32,
+    /// IOSurfaceRef (e.g. from CVPixelBufferGetIOSurface); when set, the
+    /// frame is read from it and `planes` are ignored
+    iosurface: ?*anyopaque,
+};
+
+pub const McoreParticleLayer = enum(c_int) {
+    beneath = 0,
+    above = 1,
+};
+
+/// Particle emitter description (logical pixels, seconds, radians)
+pub const McoreParticleEmitter = extern struct {
+    x: f32,
+    y: f32,
+    count: u32,
+    lifetime: f32,
+    speed_min: f32,
+    speed_max: f32,
+    /// 0 = right, PI/2 = down
+    direction: f32,
+    spread: f32,
+    /// Logical px/s^2, positive = down
+    gravity: f32,
+    size: f32,
+    /// Color ramp over each particle's life
+    colors: [4]McoreRgba,
+    looping: u8,
+    layer: McoreParticleLayer,
+};
+
+/// Represents a single accessibility node sent from Zig
+pub const McoreA11yNode = extern struct {
+    id: u64,
+    role: u8, // Maps to accesskit::Role
+    label: [*c]const u8,
+    bounds: McoreRect,
+    actions: u32, // Bitfield of supported actions
+    children: [*c]const u64,
+    children_count: i32,
+    value: [*c]const u8,
+    text_selection_start: i32,
+    text_selection_end: i32,
+    /// MCORE_A11Y_STATE_* flags
+    states: u32,
+};
+
+pub const McoreRect = extern struct {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+};
+
+/// System accessibility preferences
+pub const McoreA11yPrefs = extern struct {
+    reduce_motion: u8,
+    increase_contrast: u8,
+    reduce_transparency: u8,
+};
+
+/// Color space tag for color inputs
+pub const McoreColorSpace = enum(c_int) {
+    srgb = 0,
+    display_p3 = 1,
+};
+
+pub const McoreImageDesc = extern struct {
+    data: [*c]const u8,
+    data_len: u32,
+    width: u32,
+    height: u32,
+    format: u8,
+    alpha_type: u8,
+};
+
+pub const McoreImageTransform = extern struct {
+    x: f32,
+    y: f32,
+    scale: f32,
+    rotation_deg: f32,
+};
+
+pub const McoreImageInfo = extern struct {
+    image_id: i32,
+    width: u32,
+    height: u32,
+};
+
+/// Occupancy of the image cache, shared by the contexts of an engine
+pub const McoreImageCacheStats = extern struct {
+    /// Decoded pixels held, mip levels included
+    bytes: u64,
+    /// Part of bytes held by released images kept for reuse
+    released_bytes: u64,
+    budget_bytes: u64,
+    /// Released images freed so far to stay within the budget
+    evictions: u64,
+    image_count: u32,
+    released_count: u32,
+    /// Images still decoding
+    pending_count: u32,
+    /// Shared textures small images are packed into, up to 4MB each and 8
+    /// in all. Pages are kept once allocated and aren't counted in bytes or
+    /// against the budget (they are in mcore_frame_stats image_bytes).
+    atlas_pages: u32,
+};
+
+pub const McoreCrashCallback = *const fn ([*c]const u8, [*c]const u8) callconv(.c) void;
+
+pub const McoreTextureCallback = *const fn (?*anyopaque, [*c]const McoreTextureTarget) callconv(.c) void;
+
+// Functions
+
+/// The last error on this thread. The string stays valid until the next
+/// mcore_last_error call on the same thread; mcore_last_error_copy copies it
+/// into a buffer the caller owns instead.
+pub extern fn mcore_last_error() [*c]const u8;
+
+/// Kind of the last error on this thread, MCORE_ERROR_NONE if there was none.
+/// Fallible calls set it along with the message when they fail.
+pub extern fn mcore_last_error_code() McoreErrorCode;
+
+/// Copy the last error message on this thread into `buf` (`len` bytes,
+/// null-terminated, truncated if needed). Returns the message length without
+/// the terminator, 0 if there's no error; call with a null buf to size it.
+pub extern fn mcore_last_error_copy(buf: [*c]u8, len: usize) usize;
+
+/// Static description of an McoreErrorCode; never null, never freed
+pub extern fn mcore_error_message(code: u32) [*c]const u8;
+
+/// Forget the last error on this thread
+pub extern fn mcore_clear_error() void;
+
+/// Record every FFI call into a ring of the last `capacity` calls, for
+/// mcore_trace_dump (0 turns tracing off). MCORE_TRACE=<capacity> in the
+/// environment enables it at startup. While tracing, calls on a destroyed
+/// context print the history and abort instead of corrupting memory.
+pub extern fn mcore_trace_enable(capacity: u32) void;
+
+/// Copy the recorded call history (oldest first, one call per line) into
+/// buf, null-terminated and truncated to fit. Returns the full length in
+/// bytes, or -1 if tracing is off.
+pub extern fn mcore_trace_dump(buf: [*c]u8, buf_len: i32) i32;
+
+/// Which optional subsystems this build includes (McoreCapability bits).
+/// FFI entry points exist in every build; without the subsystem they fail
+/// with an error or do nothing.
+pub extern fn mcore_get_capabilities() u32;
+
+/// The API version this engine was built with (MCORE_API_VERSION in its
+/// header)
+pub extern fn mcore_api_version() u32;
+
+pub extern fn mcore_create(desc: [*c]const McoreSurfaceDesc) ?*McoreContext;
+
+/// Open the GPU device that contexts from mcore_engine_create_context share.
+/// Returns null on error.
+pub extern fn mcore_engine_create(desc: [*c]const McoreEngineDesc) ?*McoreEngine;
+
+/// Create a context for a window that renders with `engine`'s device and
+/// shares its fonts and images: font and image IDs registered through any of
+/// the engine's contexts work in all of them. The desc's power_preference,
+/// adapter and flags are ignored. Returns null on error, e.g. when the
+/// engine's GPU can't present to the window.
+pub extern fn mcore_engine_create_context(engine: ?*McoreEngine, desc: [*c]const McoreSurfaceDesc) ?*McoreContext;
+
+/// Release the engine handle. Contexts created from it keep working and
+/// keep the shared device alive until they're destroyed.
+pub extern fn mcore_engine_destroy(engine: ?*McoreEngine) void;
+
+/// Create a context with no window: frames render into an offscreen texture
+/// of `width_px` x `height_px`, read back with mcore_read_pixels. For CI
+/// screenshot tests and server-side thumbnails. `api_version` is
+/// MCORE_API_VERSION and `flags` are MCORE_CREATE_* flags. Returns null on
+/// error.
+pub extern fn mcore_create_headless(api_version: u32, width_px: u32, height_px: u32, scale_factor: f32, flags: u32) ?*McoreContext;
+
+/// Fill up to `capacity` entries of `out` with the GPUs available to
+/// mcore_create and return how many there are (call with capacity 0 to
+/// count). Entry i is selected with McoreSurfaceDesc::adapter = i + 1.
+pub extern fn mcore_enumerate_adapters(out: [*c]McoreAdapterInfo, capacity: u32) u32;
+
+/// Whether a context renders on a GPU or fell back to the CPU
+pub extern fn mcore_render_mode(ctx: ?*McoreContext) McoreRenderMode;
+
+/// The adapter and device limits a context renders with
+pub extern fn mcore_gpu_info(ctx: ?*McoreContext, out: [*c]McoreGpuInfo) McoreStatus;
+
+/// Copy the last presented frame of a headless context into `out` as
+/// tightly packed RGBA8 rows, top row first. `len` must be at least
+/// width_px * height_px * 4.
+pub extern fn mcore_read_pixels(ctx: ?*McoreContext, out: [*c]u8, len: usize) McoreStatus;
+
+/// Copy the last presented frame into `out` as tightly packed RGBA8 rows, top
+/// row first, for screenshots and visual regression tests. `len` must be at
+/// least width_px * height_px * 4. Windowed contexts capture the rendered
+/// scene before particles and dithering are composited.
+pub extern fn mcore_capture_frame(ctx: ?*McoreContext, out: [*c]u8, len: usize) McoreStatus;
+
+/// mcore_capture_frame written to a PNG file (needs the image-decode feature)
+pub extern fn mcore_capture_frame_png(ctx: ?*McoreContext, path: [*c]const u8) McoreStatus;
+
+pub extern fn mcore_destroy(ctx: ?*McoreContext) void;
+
+/// New surface size and scale factor, e.g. after the window moved to a
+/// display with another scale. Drawing calls take logical pixels, so the host's
+/// coordinates stay the same; text is reshaped at the new scale on the next
+/// frame (text layout handles keep the scale they were created at).
+pub extern fn mcore_resize(ctx: ?*McoreContext, desc: [*c]const McoreSurfaceDesc) void;
+
+/// Move to another scale factor (the window changed displays) without a
+/// resize: the surface keeps its logical size, so its physical size changes
+/// by the ratio of the scales, and text is reshaped at the new scale. The host
+/// still updates its layer's contents scale. Not callable inside a frame.
+pub extern fn mcore_set_scale_factor(ctx: ?*McoreContext, scale: f32) McoreStatus;
+
+/// The scale factor (physical px per logical px) drawing calls are mapped with
+pub extern fn mcore_get_scale(ctx: ?*McoreContext) f32;
+
+pub extern fn mcore_begin_frame(ctx: ?*McoreContext, time_seconds: f64) McoreStatus;
+
+pub extern fn mcore_rect_rounded(ctx: ?*McoreContext, rect: [*c]const McoreRoundedRect) void;
+
+pub extern fn mcore_font_register(ctx: ?*McoreContext, blob: [*c]const McoreFontBlob) i32;
+
+/// Give an installed font family (e.g. "SF Pro", "Helvetica Neue") a font ID
+/// without embedding its data. `weight` (100-900, 0 for regular) and `italic`
+/// pick the face used when text requests leave them unset.
+/// Returns a font ID, or -1 if the family isn't installed.
+pub extern fn mcore_font_load_system(ctx: ?*McoreContext, name: [*c]const u8, weight: f32, italic: u8) i32;
+
+/// Number of installed font families (for font pickers)
+pub extern fn mcore_font_system_family_count(ctx: ?*McoreContext) i32;
+
+/// Copy the name of installed family `index` (sorted) into buf, null-terminated.
+/// Returns the number of bytes copied (excluding the terminator), or -1 if
+/// index is out of range.
+pub extern fn mcore_font_system_family_name(ctx: ?*McoreContext, index: i32, buf: [*c]u8, buf_len: i32) i32;
+
+pub extern fn mcore_text_layout(ctx: ?*McoreContext, req: [*c]const McoreTextReq, out: [*c]McoreTextMetrics) void;
+
+/// Shape text into a layout handle. Free it with mcore_text_layout_destroy.
+/// The handle captures the current scale factor; recreate it after a DPI change.
+pub extern fn mcore_text_layout_create(ctx: ?*McoreContext, req: [*c]const McoreTextReq) ?*McoreTextLayout;
+
+/// Shape rich text into a layout handle. Spans are byte ranges into req.utf8;
+/// text outside every span uses `default_color`. Span colors are baked in, so
+/// mcore_text_layout_draw ignores its color argument for these handles.
+pub extern fn mcore_text_layout_create_styled(ctx: ?*McoreContext, req: [*c]const McoreTextReq, spans: [*c]const McoreTextSpan, span_count: usize, default_color: McoreRgba) ?*McoreTextLayout;
+
+/// Free a layout handle
+pub extern fn mcore_text_layout_destroy(layout: ?*McoreTextLayout) void;
+
+/// Get the logical size and line count of a layout handle
+pub extern fn mcore_text_layout_metrics(layout: ?*const McoreTextLayout, out: [*c]McoreTextMetrics) void;
+
+/// Map a logical point (relative to the layout origin) to a byte offset
+pub extern fn mcore_text_layout_hit_test(layout: ?*const McoreTextLayout, x: f32, y: f32) i32;
+
+/// Caret geometry for a byte offset in a layout handle (logical, relative to the layout origin)
+pub extern fn mcore_text_layout_caret(layout: ?*const McoreTextLayout, byte_offset: i32, out: [*c]McoreCaretRect) void;
+
+/// Selection highlight rectangles for a byte range of a layout handle
+/// (logical, relative to the layout origin). Writes up to `max_rects` and
+/// returns the total count, so a host can retry with a larger buffer.
+pub extern fn mcore_text_layout_selection_rects(layout: ?*const McoreTextLayout, start: i32, end: i32, out: [*c]McoreRect, max_rects: i32) i32;
+
+/// Draw a layout handle at a logical position
+pub extern fn mcore_text_layout_draw(ctx: ?*McoreContext, layout: ?*const McoreTextLayout, x: f32, y: f32, color: McoreRgba) void;
+
+/// Lay out headings, paragraphs and list items stacked in one handle,
+/// wrapping at `width` (logical px, 0 for no wrapping). Free it with
+/// mcore_text_block_destroy.
+pub extern fn mcore_text_block_create(ctx: ?*McoreContext, paragraphs: [*c]const McoreTextBlockParagraph, paragraph_count: usize, style: [*c]const McoreTextBlockStyle, width: f32) ?*McoreTextBlock;
+
+/// Free a text block
+pub extern fn mcore_text_block_destroy(block: ?*McoreTextBlock) void;
+
+/// Logical size of a text block: its widest line and total height
+pub extern fn mcore_text_block_size(block: ?*const McoreTextBlock, out: [*c]McoreTextSize) void;
+
+/// Where paragraph `index` landed (logical, relative to the block origin;
+/// list items start after their marker)
+pub extern fn mcore_text_block_paragraph_rect(block: ?*const McoreTextBlock, index: u32, out: [*c]McoreRect) McoreStatus;
+
+/// Map a logical point (relative to the block origin) to the nearest
+/// paragraph and a byte offset in its text. Writes the paragraph index to
+/// `paragraph` (may be null) and returns the offset, or -1 for an empty block.
+pub extern fn mcore_text_block_hit_test(block: ?*const McoreTextBlock, x: f32, y: f32, paragraph: [*c]i32) i32;
+
+/// Draw a text block at a logical position
+pub extern fn mcore_text_block_draw(ctx: ?*McoreContext, block: ?*const McoreTextBlock, x: f32, y: f32) void;
+
+/// Line count, line height and gutter width of a code request, for sizing
+/// its scroll view
+pub extern fn mcore_code_measure(ctx: ?*McoreContext, req: [*c]const McoreCodeReq, out: [*c]McoreCodeMetrics) void;
+
+/// Draw the lines of a code request visible in its viewport, whose top left
+/// is at logical (x, y). Tokens must be sorted by start and not overlap;
+/// text outside them uses req.color. Lines aren't clipped to the viewport's
+/// width; push a clip rect for that.
+pub extern fn mcore_code_draw(ctx: ?*McoreContext, req: [*c]const McoreCodeReq, tokens: [*c]const McoreCodeToken, token_count: usize, x: f32, y: f32) void;
+
+/// Index a document's lines for windowed layout; nothing is shaped until
+/// mcore_text_window_set_lines. Lines wrap at req.wrap_width and use its
+/// style; req.max_lines is ignored. The text is copied. Free the window with
+/// mcore_text_window_destroy.
+pub extern fn mcore_text_window_create(req: [*c]const McoreTextReq) ?*McoreTextWindow;
+
+/// Free a text window
+pub extern fn mcore_text_window_destroy(window: ?*McoreTextWindow) void;
+
+/// Hard lines in the document (one more than its line breaks)
+pub extern fn mcore_text_window_line_count(window: ?*const McoreTextWindow) u32;
+
+/// Line containing a byte offset of the document, for turning a byte range
+/// into lines
+pub extern fn mcore_text_window_line_at(window: ?*const McoreTextWindow, byte_offset: usize) u32;
+
+/// Byte range of a line, without its line break
+pub extern fn mcore_text_window_line_range(window: ?*const McoreTextWindow, line: u32, out_start: [*c]usize, out_end: [*c]usize) McoreStatus;
+
+/// Shape lines first..first + count (clamped to the document) for drawing
+/// and hit testing. Lines shaped for the previous range are reused, so
+/// scrolling only shapes the lines coming into view. Writes each line's
+/// logical height to `heights` (room for `count`, may be null) and returns
+/// the range's total height.
+pub extern fn mcore_text_window_set_lines(ctx: ?*McoreContext, window: ?*McoreTextWindow, first: u32, count: u32, heights: [*c]f32) f32;
+
+/// Map a logical point (relative to the top of the shaped lines) to a byte
+/// offset of the document; -1 when no lines are shaped
+pub extern fn mcore_text_window_hit_test(window: ?*const McoreTextWindow, x: f32, y: f32) i32;
+
+/// Draw the shaped lines with the first one's top left at a logical position
+pub extern fn mcore_text_window_draw(ctx: ?*McoreContext, window: ?*const McoreTextWindow, x: f32, y: f32, color: McoreRgba) void;
+
+pub extern fn mcore_measure_text(ctx: ?*McoreContext, text: [*c]const u8, font_size: f32, max_width: f32, font_id: i32, out: [*c]McoreTextSize) void;
+
+/// Same as mcore_measure_text, but single lines are measured from cached
+/// per-word advances instead of a full layout. Cheap enough for speculative
+/// measuring during layout; text that wraps or has line breaks takes the
+/// full path.
+pub extern fn mcore_measure_text_fast(ctx: ?*McoreContext, text: [*c]const u8, font_size: f32, max_width: f32, font_id: i32, out: [*c]McoreTextSize) void;
+
+pub extern fn mcore_measure_text_to_byte_offset(ctx: ?*McoreContext, text: [*c]const u8, font_size: f32, byte_offset: i32) f32;
+
+/// Caret geometry for a byte offset in text shaped from `req` (wrapping at
+/// req.wrap_width), relative to the text origin in logical pixels
+pub extern fn mcore_text_caret(ctx: ?*McoreContext, req: [*c]const McoreTextReq, byte_offset: i32, out: [*c]McoreCaretRect) void;
+
+/// Map a logical point (relative to the text origin) to a byte offset in text
+/// shaped from `req`, wrapping at req.wrap_width
+pub extern fn mcore_text_hit_test(ctx: ?*McoreContext, req: [*c]const McoreTextReq, x: f32, y: f32) i32;
+
+/// Selection highlight rectangles for the byte range start..end of text shaped
+/// from `req` (logical, relative to the text origin), one or more per line.
+/// Writes up to `max_rects` and returns the total count.
+pub extern fn mcore_text_selection_rects(ctx: ?*McoreContext, req: [*c]const McoreTextReq, start: i32, end: i32, out: [*c]McoreRect, max_rects: i32) i32;
+
+pub extern fn mcore_get_text_stats(ctx: ?*McoreContext, out: [*c]McoreTextStats) void;
+
+pub extern fn mcore_reset_text_stats(ctx: ?*McoreContext) void;
+
+/// Bound this context's shaped-layout cache. max_entries 0 restores the
+/// default count; max_bytes 0 removes the byte limit. Glyph rasterization is
+/// cached by the renderer and isn't covered by this budget.
+pub extern fn mcore_text_cache_set_budget(ctx: ?*McoreContext, max_entries: u32, max_bytes: u64) McoreStatus;
+
+pub extern fn mcore_text_cache_get_stats(ctx: ?*McoreContext, out: [*c]McoreTextCacheStats) McoreStatus;
+
+pub extern fn mcore_text_draw(ctx: ?*McoreContext, req: [*c]const McoreTextReq, x: f32, y: f32, color: McoreRgba) void;
+
+/// Draw rich text with per-range styling in one pass (shaped every call;
+/// use mcore_text_layout_create_styled for text that doesn't change)
+pub extern fn mcore_text_draw_spans(ctx: ?*McoreContext, req: [*c]const McoreTextReq, spans: [*c]const McoreTextSpan, span_count: usize, x: f32, y: f32, default_color: McoreRgba) void;
+
+pub extern fn mcore_push_clip_rect(ctx: ?*McoreContext, x: f32, y: f32, width: f32, height: f32) void;
+
+/// Push a clip layer shaped like a rounded rectangle
+pub extern fn mcore_push_clip_rounded_rect(ctx: ?*McoreContext, x: f32, y: f32, width: f32, height: f32, radius: f32) void;
+
+/// Register a vector path for later use (e.g. clipping)
+/// `verbs` holds `verb_count` MCORE_PATH_VERB_* bytes, `points` holds `point_count` (x, y) pairs in logical px
+/// Returns a path ID (>= 0) or -1 on error
+pub extern fn mcore_path_create(ctx: ?*McoreContext, verbs: [*c]const u8, verb_count: i32, points: [*c]const f32, point_count: i32) i32;
+
+/// Free a registered path
+pub extern fn mcore_path_destroy(ctx: ?*McoreContext, path_id: i32) void;
+
+/// Push a clip layer shaped like a registered path (offset by x, y, logical px)
+/// Returns Err (and pushes nothing) if the path ID is unknown, so the caller must not pop
+pub extern fn mcore_push_clip_path(ctx: ?*McoreContext, path_id: i32, x: f32, y: f32) McoreStatus;
+
+/// Pop the innermost clip; with none pushed this frame the pop is ignored and
+/// counted in McoreFrameStats::layer_warnings
+pub extern fn mcore_pop_clip(ctx: ?*McoreContext) void;
+
+/// Push a backdrop blur layer: what's been drawn so far is blurred inside the
+/// rounded rect, and what's drawn until the matching mcore_pop_clip is
+/// clipped to it (frosted-glass panels). `blur_radius` is the Gaussian's
+/// standard deviation in logical px. Not allowed inside clips or fragments.
+pub extern fn mcore_push_blur_layer(ctx: ?*McoreContext, x: f32, y: f32, width: f32, height: f32, corner_radius: f32, blur_radius: f32) McoreStatus;
+
+/// Push a color filter layer: everything drawn until the matching pop
+/// (images, fragments, videos and custom textures included) is transformed by
+/// `matrix`, a 4x5 row-major color matrix (20 floats)
+pub extern fn mcore_push_color_filter(ctx: ?*McoreContext, matrix: [*c]const f32) void;
+
+/// Push a grayscale filter layer (amount 0.0 = unchanged, 1.0 = fully desaturated)
+/// Handy for disabled-state regions
+pub extern fn mcore_push_grayscale_filter(ctx: ?*McoreContext, amount: f32) void;
+
+/// Pop the innermost color filter layer
+pub extern fn mcore_pop_color_filter(ctx: ?*McoreContext) void;
+
+pub extern fn mcore_render_commands(ctx: ?*McoreContext, commands: [*c]const McoreDrawCommand, count: i32) void;
+
+/// Draw a v2 command stream: `len` bytes of records (see McoreCmdHeader and
+/// command_stream.rs). A malformed stream is rejected before anything is drawn.
+pub extern fn mcore_render_commands_v2(ctx: ?*McoreContext, data: [*c]const u8, len: usize, version: u32) McoreStatus;
+
+/// Register a gradient brush, laid out relative to the bounds of the shape it
+/// paints. Draw commands reference it by ID (GradientRing: font_id).
+/// Returns a gradient ID (>= 0) or -1 on error
+pub extern fn mcore_gradient_create(ctx: ?*McoreContext, desc: [*c]const McoreGradientDesc) i32;
+
+/// Free a registered gradient
+pub extern fn mcore_gradient_destroy(ctx: ?*McoreContext, gradient_id: i32) void;
+
+/// Create an append-only log view. Entries wrap at `wrap_width` (logical px);
+/// beyond `max_entries` (0 = unbounded) the oldest are dropped.
+/// Returns a view ID (>= 0) or -1 on error.
+pub extern fn mcore_log_view_create(ctx: ?*McoreContext, font_size: f32, font_id: i32, wrap_width: f32, max_entries: u32) i32;
+
+pub extern fn mcore_log_view_destroy(ctx: ?*McoreContext, view_id: i32) void;
+
+/// Shape and append one entry (it may span several lines)
+pub extern fn mcore_log_view_append(ctx: ?*McoreContext, view_id: i32, utf8: [*c]const u8, color: McoreRgba) McoreStatus;
+
+pub extern fn mcore_log_view_clear(ctx: ?*McoreContext, view_id: i32) void;
+
+/// Change the wrap width (logical px). Reshapes every entry, so call it on
+/// resize rather than every frame.
+pub extern fn mcore_log_view_set_width(ctx: ?*McoreContext, view_id: i32, wrap_width: f32) void;
+
+/// Set the tab stops entries are laid out with (see McoreTextReq), reshaping
+/// the entries already appended
+pub extern fn mcore_log_view_set_tabs(ctx: ?*McoreContext, view_id: i32, tab_width: f32, tab_stops: [*c]const f32, tab_stop_count: u32) void;
+
+/// Height of all kept entries (logical px; 0 for unknown IDs)
+pub extern fn mcore_log_view_content_height(ctx: ?*McoreContext, view_id: i32) f32;
+
+/// Draw the entries visible in a `height`-tall window at (x, y) whose
+/// content is scrolled by `scroll_y` (logical px), clipped to the window.
+/// Only the visible entries are encoded, however long the log gets.
+pub extern fn mcore_log_view_draw(ctx: ?*McoreContext, view_id: i32, x: f32, y: f32, width: f32, height: f32, scroll_y: f32) McoreStatus;
+
+/// Start recording a fragment. All drawing calls until mcore_fragment_end
+/// (including mcore_render_commands) are captured instead of drawn. Frames
+/// can't begin or present until the recording ends.
+pub extern fn mcore_fragment_begin(ctx: ?*McoreContext) McoreStatus;
+
+/// Finish recording and return the fragment ID (>= 0), or -1 if not recording
+pub extern fn mcore_fragment_end(ctx: ?*McoreContext) i32;
+
+/// Replay a recorded fragment into the current frame with a transform
+/// The transform position is in logical pixels, like mcore_image_draw
+pub extern fn mcore_fragment_draw(ctx: ?*McoreContext, fragment_id: i32, transform: [*c]const McoreImageTransform) void;
+
+/// Free a recorded fragment
+pub extern fn mcore_fragment_destroy(ctx: ?*McoreContext, fragment_id: i32) void;
+
+/// Render a recorded fragment once, offscreen, into a new image covering
+/// (0, 0, width, height) of the fragment (logical px, rasterized at the
+/// current scale). Expensive static content (charts, blurred backgrounds) can
+/// then be drawn as one image blit. Returns an image ID (release it with
+/// mcore_image_release) or -1 on error; snapshot again when the content changes.
+pub extern fn mcore_fragment_to_image(ctx: ?*McoreContext, fragment_id: i32, width: f32, height: f32) i32;
+
+/// Fill the surface behind every following frame with `color`, in place of
+/// the clear color passed to mcore_end_frame_present and mcore_submit_frame,
+/// for hosts with one fixed background. Components must be finite.
+pub extern fn mcore_set_clear_color(ctx: ?*McoreContext, color: McoreRgba) McoreStatus;
+
+/// Render and present the frame over `clear` (or the mcore_set_clear_color
+/// color, once set), which is used exactly as given: it doesn't depend on
+/// the frame time
+pub extern fn mcore_end_frame_present(ctx: ?*McoreContext, clear: McoreRgba) McoreStatus;
+
+/// Build, render and present frames on an engine-owned thread (see
+/// render_thread.rs). Until mcore_render_thread_stop, frames are queued with
+/// mcore_submit_frame and other threads can't begin frames or draw.
+pub extern fn mcore_render_thread_start(ctx: ?*McoreContext) McoreStatus;
+
+/// Present the last queued frame, then end the render thread; frames are
+/// driven by the host again. Blocks until the thread exits.
+pub extern fn mcore_render_thread_stop(ctx: ?*McoreContext) McoreStatus;
+
+/// Queue a frame for the render thread: `len` bytes of v2 command stream,
+/// copied before returning, drawn at `time_seconds` over `clear`. Callable
+/// from any thread. A frame still waiting when the next arrives is replaced.
+/// The stream is validated here; failures while rendering go to the log
+/// callback.
+pub extern fn mcore_submit_frame(ctx: ?*McoreContext, time_seconds: f64, data: [*c]const u8, len: usize, version: u32, clear: McoreRgba) McoreStatus;
+
+/// Block until every frame queued so far is presented (e.g. before reading
+/// back a frame or resizing in step with the window)
+pub extern fn mcore_render_thread_flush(ctx: ?*McoreContext) McoreStatus;
+
+/// Start recording command buffers to `path` (see replay.rs). Every frame's
+/// mcore_render_commands calls and clear color are captured until
+/// mcore_replay_record_stop writes the file.
+pub extern fn mcore_replay_record_start(ctx: ?*McoreContext, path: [*c]const u8) McoreStatus;
+
+/// Stop recording and write the file. Returns the number of frames written,
+/// or -1 if not recording or the write failed.
+pub extern fn mcore_replay_record_stop(ctx: ?*McoreContext) i32;
+
+/// Write the current frame's command buffers (the frame being built, or the
+/// last presented one between frames) to `path` as a one-frame recording.
+/// `mcore-replay` renders it headlessly; mcore_scene_load draws it back.
+pub extern fn mcore_scene_dump(ctx: ?*McoreContext, path: [*c]const u8) McoreStatus;
+
+/// Draw the commands of a scene dump (or the first frame of a recording)
+/// into the current frame. Its clear color and surface size are not applied.
+pub extern fn mcore_scene_load(ctx: ?*McoreContext, path: [*c]const u8) McoreStatus;
+
+/// Write a diagnostic bundle into `dir` when the engine hits an unrecoverable
+/// error (GPU device lost, repeated render failures, a panic), then call
+/// `callback` with the reason and bundle path. A null dir turns it off.
+pub extern fn mcore_set_crash_dump(ctx: ?*McoreContext, dir: [*c]const u8, callback: ?*const fn ([*c]const u8, [*c]const u8) callconv(.c) void) McoreStatus;
+
+/// Receive engine diagnostics as (level, message): 0 debug, 1 info, 2 warn,
+/// 3 error. Without a callback they go to stderr. Runs without the engine
+/// lock held; the message is only valid during the call.
+pub extern fn mcore_set_log_callback(ctx: ?*McoreContext, callback: ?*const fn (u8, [*c]const u8) callconv(.c) void) void;
+
+/// Log a warning with a per-phase breakdown for every frame whose build and
+/// render take longer than `budget_ms` (see watchdog.rs). 0 turns it off.
+/// Meant for development builds of the host.
+pub extern fn mcore_set_frame_budget(ctx: ?*McoreContext, budget_ms: f32) void;
+
+/// Draw an engine-side debug overlay over every frame: a bitmask of
+/// MCORE_DEBUG_OVERLAY_* flags (see overlay.rs), 0 turns it off
+pub extern fn mcore_set_debug_overlay(ctx: ?*McoreContext, flags: u32) void;
+
+/// Fill `out` with the last rendered frame's statistics. Scene counts cover
+/// the scene being built, so call it between mcore_end_frame_present and the
+/// next mcore_begin_frame to get the presented frame's.
+pub extern fn mcore_frame_stats(ctx: ?*McoreContext, out: [*c]McoreFrameStats) McoreStatus;
+
+/// Enable or disable dithering in the final blit pass.
+/// Useful for dark themes with large gradients, which band visibly on 8-bit displays.
+pub extern fn mcore_set_dither(ctx: ?*McoreContext, enabled: u8) void;
+
+/// Choose how frames are antialiased (McoreAaMode value). MSAA pipelines are
+/// built the first time they're chosen; if the device can't run them this
+/// returns Err and the mode is unchanged.
+pub extern fn mcore_set_aa_mode(ctx: ?*McoreContext, mode: u8) McoreStatus;
+
+/// The current McoreAaMode value
+pub extern fn mcore_get_aa_mode(ctx: ?*McoreContext) u8;
+
+/// Choose how this context's text is fit to the pixel grid. Hinting with
+/// whole-pixel positions makes small text crisper on low-DPI screens; the
+/// default (no hinting, unquantized positions) keeps exact shapes and
+/// spacing, which suits high-DPI screens and animated text.
+pub extern fn mcore_set_text_rendering(ctx: ?*McoreContext, rendering: [*c]const McoreTextRendering) McoreStatus;
+
+/// The context's current text rendering options
+pub extern fn mcore_get_text_rendering(ctx: ?*McoreContext, out: [*c]McoreTextRendering) McoreStatus;
+
+/// Choose the color space (an McoreColorSpace value) the window's colors are
+/// shown in: sRGB (the default) or Display P3, where colors and images are
+/// read as P3 values. Headless contexts and platforms without color-managed
+/// windows return Err with MCORE_ERROR_UNSUPPORTED and stay in sRGB.
+pub extern fn mcore_set_color_space(ctx: ?*McoreContext, space: u8) McoreStatus;
+
+/// The window's color space (sRGB for headless contexts)
+pub extern fn mcore_get_color_space(ctx: ?*McoreContext) McoreColorSpace;
+
+/// 1 if the main display can show Display P3 colors (macOS), 0 otherwise
+pub extern fn mcore_display_supports_p3() u8;
+
+/// Skip rendering frames whose command buffers match the last presented
+/// frame: mcore_end_frame_present returns MCORE_FRAME_SKIPPED and the previous
+/// frame stays on screen (see damage.rs). Frames with direct drawing calls
+/// are always presented. Off by default.
+pub extern fn mcore_set_damage_tracking(ctx: ?*McoreContext, enabled: u8) void;
+
+/// Handle a text input event for a specific widget ID
+/// Returns true if the text changed
+pub extern fn mcore_text_input_event(ctx: ?*McoreContext, id: u64, event: [*c]const McoreTextEvent) u8;
+
+/// mcore_text_input_event, then scroll the input's viewport (see
+/// mcore_text_input_set_viewport) so the caret stays visible, and report the
+/// offset to draw at
+pub extern fn mcore_text_input_event_ex(ctx: ?*McoreContext, id: u64, event: [*c]const McoreTextEvent, out: [*c]McoreTextEventResult) u8;
+
+/// Give a text input a viewport (logical px) the engine keeps its caret
+/// visible in, laid out at font_size and wrapped at wrap_width (0 = a single
+/// line). The scroll offset follows the caret after every
+/// mcore_text_input_event_ex, and immediately here (e.g. after a resize).
+/// A width or height of 0 removes the viewport.
+pub extern fn mcore_text_input_set_viewport(ctx: ?*McoreContext, id: u64, width: f32, height: f32, font_size: f32, wrap_width: f32) void;
+
+/// Draw a text input from its engine state in one call: content (masked if
+/// secure), selection, underlined IME preedit and a blinking caret, scrolled
+/// to keep the caret visible. Also records the placement for the _root
+/// queries. The caret blinks on frame time and requests redraws for its
+/// transitions, so hosts only need to draw frames when asked.
+pub extern fn mcore_text_input_draw(ctx: ?*McoreContext, id: u64, style: [*c]const McoreTextInputStyle) void;
+
+/// A text input's scroll offset (logical px; 0, 0 without a viewport)
+pub extern fn mcore_text_input_get_scroll(ctx: ?*McoreContext, id: u64, out_x: [*c]f32, out_y: [*c]f32) void;
+
+/// Single-line fields: keep the caret inside a field `width` wide (logical px,
+/// excluding padding) and return the horizontal offset to draw the content
+/// at (text x = field content x - offset). Sets a one-line viewport, so
+/// later events made with mcore_text_input_event_ex follow the caret too.
+pub extern fn mcore_text_input_scroll_x(ctx: ?*McoreContext, id: u64, width: f32, font_size: f32) f32;
+
+/// Get the current text content for a widget ID
+/// Returns the number of bytes written (excluding null terminator)
+pub extern fn mcore_text_input_get(ctx: ?*McoreContext, id: u64, buf: [*c]u8, buf_len: i32) i32;
+
+/// Get the cursor position (byte offset) for a widget ID
+pub extern fn mcore_text_input_cursor(ctx: ?*McoreContext, id: u64) i32;
+
+/// Set the text content for a widget ID
+pub extern fn mcore_text_input_set(ctx: ?*McoreContext, id: u64, text: [*c]const u8) void;
+
+/// Set how many undo steps a text input keeps (default 100, 0 disables undo)
+pub extern fn mcore_text_input_set_undo_limit(ctx: ?*McoreContext, id: u64, limit: u32) void;
+
+/// Make a text input a password field: it draws (and hit tests) as bullets
+/// while editing works on the real content, and cut/copy are disabled. With
+/// reveal_ms > 0 each typed character shows for that long before masking.
+pub extern fn mcore_text_input_set_secure(ctx: ?*McoreContext, id: u64, secure: u8, reveal_ms: u32) void;
+
+/// Limit what typing, pasting and IME commits can put in a text input:
+/// max_bytes / max_graphemes (0 = unlimited) and a character class
+/// (MCORE_CHARS_* in mcore.h). Disallowed characters and whatever doesn't
+/// fit are dropped; see mcore_text_input_take_rejection. Content set with
+/// mcore_text_input_set is not constrained.
+pub extern fn mcore_text_input_set_constraints(ctx: ?*McoreContext, id: u64, max_bytes: u32, max_graphemes: u32, char_class: u8) void;
+
+/// Why the latest insertion into a text input was cut short since the last
+/// call (MCORE_REJECTED_*; 0 if nothing was rejected). Hosts poll this
+/// after events to flash or shake the field.
+pub extern fn mcore_text_input_take_rejection(ctx: ?*McoreContext, id: u64) u8;
+
+/// Keyboard type and autocorrection hints for a text input, kept with its
+/// state. Hosts read them back with mcore_text_input_get_hints on focus.
+pub extern fn mcore_text_input_set_hints(ctx: ?*McoreContext, id: u64, keyboard: u8, autocorrect: u8) void;
+
+/// Paragraph direction of a text input (McoreTextDirection): decides which
+/// way the arrow keys move and how wrapped content is aligned. Auto (the
+/// default) follows the content's first strong character.
+pub extern fn mcore_text_input_set_direction(ctx: ?*McoreContext, id: u64, direction: u8) void;
+
+/// Whether a text input's content currently lays out right to left, e.g. to
+/// align a host-drawn field (0 for unknown IDs)
+pub extern fn mcore_text_input_is_rtl(ctx: ?*McoreContext, id: u64) u8;
+
+/// Input context configuration for a text input (defaults for unknown IDs)
+pub extern fn mcore_text_input_get_hints(ctx: ?*McoreContext, id: u64, out: [*c]McoreTextInputHints) McoreStatus;
+
+/// Get selection range for a text input widget
+/// Returns true if there is a selection, and fills out_start and out_end with the byte offsets
+pub extern fn mcore_text_input_get_selection(ctx: ?*McoreContext, id: u64, out_start: [*c]i32, out_end: [*c]i32) u8;
+
+/// Set cursor position and optionally start a selection
+pub extern fn mcore_text_input_set_cursor_pos(ctx: ?*McoreContext, id: u64, byte_offset: i32, extend_selection: u8) void;
+
+/// Get the selected text (returns length, copies into buffer)
+pub extern fn mcore_text_input_get_selected_text(ctx: ?*McoreContext, id: u64, buf: [*c]u8, buf_len: i32) i32;
+
+/// Start a selection at a specific position (for mouse down)
+/// Sets both cursor and anchor to the same position, clearing any existing selection
+pub extern fn mcore_text_input_start_selection(ctx: ?*McoreContext, id: u64, byte_offset: i32) void;
+
+/// Set the mouse pointer shape (an McoreCursor value; NSCursor on macOS, call
+/// on the main thread). AppKit resets it when the pointer crosses cursor
+/// rects, so set it again on mouse moves. Elsewhere the shape is only
+/// recorded for mcore_get_cursor.
+pub extern fn mcore_set_cursor(cursor: u8) McoreStatus;
+
+/// The shape last passed to mcore_set_cursor (Arrow before any)
+pub extern fn mcore_get_cursor() McoreCursor;
+
+/// Put plain text on the system clipboard
+pub extern fn mcore_clipboard_set_text(text: [*c]const u8) McoreStatus;
+
+/// Copy the clipboard's text into buf, null-terminated and truncated to fit.
+/// Returns the full length in bytes, or -1 if the clipboard holds no text.
+pub extern fn mcore_clipboard_get_text(buf: [*c]u8, buf_len: i32) i32;
+
+/// Set IME preedit (composition) text for a text input
+pub extern fn mcore_ime_set_preedit(ctx: ?*McoreContext, id: u64, preedit: [*c]const McoreImePreedit) void;
+
+/// Commit IME text (finalize composition)
+pub extern fn mcore_ime_commit(ctx: ?*McoreContext, id: u64, text: [*c]const u8) void;
+
+/// Clear IME preedit state
+pub extern fn mcore_ime_clear_preedit(ctx: ?*McoreContext, id: u64) void;
+
+/// Get IME preedit text if any
+/// Returns 1 if there is preedit text, 0 otherwise
+pub extern fn mcore_ime_get_preedit(ctx: ?*McoreContext, id: u64, buf: [*c]u8, buf_len: i32, out_cursor_offset: [*c]i32) u8;
+
+/// Get the text a text input should draw: its content with any preedit
+/// spliced in at the caret, as bullets for secure inputs. Writes the caret
+/// and preedit byte range within that text (preedit_start == preedit_end
+/// when not composing).
+/// Returns the number of bytes written (excluding null terminator)
+pub extern fn mcore_text_input_get_display(ctx: ?*McoreContext, id: u64, buf: [*c]u8, buf_len: i32, out_caret: [*c]i32, out_preedit_start: [*c]i32, out_preedit_end: [*c]i32) i32;
+
+/// Selection as a byte range of the text from mcore_text_input_get_display
+/// (which differs from content offsets in secure inputs and while composing).
+/// Returns 1 if there is a selection.
+pub extern fn mcore_text_input_get_display_selection(ctx: ?*McoreContext, id: u64, out_start: [*c]i32, out_end: [*c]i32) u8;
+
+/// Content byte offset under logical x (relative to the text origin) in a
+/// single-line text input drawn with the default font at font_size. Hit
+/// tests what is drawn, so it works for secure inputs and during composition;
+/// pass the result to mcore_text_input_set_cursor_pos/start_selection.
+pub extern fn mcore_text_input_hit_test(ctx: ?*McoreContext, id: u64, font_size: f32, x: f32) i32;
+
+/// Caret rectangle of a single-line text input, in logical pixels relative
+/// to its text origin, following the IME caret while composing. Hosts add the
+/// text origin and convert to screen coordinates for the candidate window
+/// (NSTextInputClient firstRectForCharacterRange).
+pub extern fn mcore_text_input_caret_rect(ctx: ?*McoreContext, id: u64, font_size: f32, out: [*c]McoreCaretRect) McoreStatus;
+
+/// Record where a text input's text is drawn this frame: its origin in the
+/// current local space of the hit transform stack (mcore_hit_push_transform),
+/// which is captured with it. The _root queries below then work in window
+/// coordinates, so inputs inside a zoomed or panned viewport stay usable.
+pub extern fn mcore_text_input_set_origin(ctx: ?*McoreContext, id: u64, x: f32, y: f32) void;
+
+/// Caret of a text input in root (window) logical coordinates, scaled by the
+/// transform it was drawn under (see mcore_text_input_set_origin)
+pub extern fn mcore_text_input_caret_rect_root(ctx: ?*McoreContext, id: u64, font_size: f32, out: [*c]McoreCaretRect) McoreStatus;
+
+/// Content byte offset under a root (window) logical point, mapped through
+/// the transform the input was drawn under. Returns -1 if the input has no
+/// origin yet.
+pub extern fn mcore_text_input_hit_test_root(ctx: ?*McoreContext, id: u64, font_size: f32, x: f32, y: f32) i32;
+
+/// Feed a raw pointer event (logical pixels, time in seconds) to the gesture recognizer
+/// Long-presses also fire from mcore_begin_frame when the pointer is held still
+pub extern fn mcore_gesture_pointer_event(ctx: ?*McoreContext, phase: McorePointerPhase, x: f32, y: f32, time_seconds: f64) void;
+
+/// Pop the next recognized gesture
+/// Returns 1 and fills `out` if a gesture was pending, 0 otherwise
+pub extern fn mcore_gesture_next(ctx: ?*McoreContext, out: [*c]McoreGesture) u8;
+
+/// Register a hit region for this frame (logical pixels, in the current hit transform)
+/// The active clip (from push_clip calls/commands) and transform are captured.
+/// Higher z wins; equal z resolves to the region registered last.
+pub extern fn mcore_hit_region(ctx: ?*McoreContext, id: u64, x: f32, y: f32, width: f32, height: f32, z: i32) void;
+
+/// Push a translation + uniform scale applied to subsequently registered hit regions
+pub extern fn mcore_hit_push_transform(ctx: ?*McoreContext, dx: f32, dy: f32, scale: f32) void;
+
+pub extern fn mcore_hit_pop_transform(ctx: ?*McoreContext) void;
+
+/// The composed hit transform stack: root = local * scale + (dx, dy).
+/// Text query results (carets, selection rects, layout metrics) are local to
+/// the text origin; hosts map them with this to get window coordinates.
+pub extern fn mcore_hit_current_transform(ctx: ?*McoreContext, out: [*c]McoreHitTransform) void;
+
+/// Find the topmost region under a point (window logical pixels): the one
+/// with the highest z, then the latest registered, among regions whose clip
+/// contains the point. Between frames this sees the last frame's regions.
+/// Returns 1 and fills `out` on a hit, 0 if no region is there.
+pub extern fn mcore_hit_test(ctx: ?*McoreContext, x: f32, y: f32, out: [*c]McoreHitResult) u8;
+
+/// Set the callback receiving routed pointer events
+/// Callback signature: (region_id, phase, local_x, local_y)
+/// Phases: 0 = Down, 1 = Move, 2 = Up, 3 = Cancel, 4 = Enter, 5 = Leave
+pub extern fn mcore_pointer_set_callback(ctx: ?*McoreContext, callback: *const fn (u64, u8, f32, f32) callconv(.c) void) void;
+
+/// Route a pointer event (window logical pixels) through the hit regions
+/// The callback is invoked after the engine lock is released, so it may call back into the engine.
+pub extern fn mcore_pointer_event(ctx: ?*McoreContext, phase: McorePointerPhase, x: f32, y: f32) void;
+
+/// Set a scroll container's viewport and content sizes (logical pixels),
+/// creating it on first use
+pub extern fn mcore_scroll_set_extent(ctx: ?*McoreContext, id: u64, viewport_w: f32, viewport_h: f32, content_w: f32, content_h: f32) void;
+
+/// Scroll immediately by a delta (mouse wheel), clamped, cancelling momentum
+pub extern fn mcore_scroll_by(ctx: ?*McoreContext, id: u64, dx: f32, dy: f32) void;
+
+/// Feed a scroll event: the content delta (logical px, positive scrolls
+/// toward the end) with the gesture phase and the event time (seconds, on the
+/// mcore_begin_frame clock). Trackpad gestures drag the content with
+/// rubber-banding and fling with the engine's momentum when they end, so hosts
+/// should drop the OS's own momentum events. Edge callbacks run after the lock
+/// is released.
+pub extern fn mcore_scroll_event(ctx: ?*McoreContext, id: u64, dx: f32, dy: f32, phase: McoreScrollPhase, time_seconds: f64) void;
+
+/// Snap one axis to multiples of an item extent (0 clears snapping)
+pub extern fn mcore_scroll_set_snap_interval(ctx: ?*McoreContext, id: u64, axis: McoreScrollAxis, interval: f32) void;
+
+/// Snap one axis to explicit offsets (count 0 clears snapping)
+pub extern fn mcore_scroll_set_snap_offsets(ctx: ?*McoreContext, id: u64, axis: McoreScrollAxis, offsets: [*c]const f32, count: usize) void;
+
+pub extern fn mcore_scroll_drag_begin(ctx: ?*McoreContext, id: u64) void;
+
+/// Update a drag with the pointer translation since the drag began
+/// (the dx/dy of a pan gesture). Edge callbacks run after the lock is released.
+pub extern fn mcore_scroll_drag_update(ctx: ?*McoreContext, id: u64, translation_x: f32, translation_y: f32) void;
+
+/// End a drag with the pointer velocity (logical px/s) to start a fling.
+/// Momentum advances in mcore_begin_frame; with reduced motion the content settles instantly.
+pub extern fn mcore_scroll_drag_end(ctx: ?*McoreContext, id: u64, velocity_x: f32, velocity_y: f32) void;
+
+/// Read a container's offset (may be outside the content while overscrolled).
+/// Returns 1 if the container exists, 0 otherwise.
+pub extern fn mcore_scroll_get(ctx: ?*McoreContext, id: u64, out: [*c]McoreScrollState) u8;
+
+pub extern fn mcore_scroll_remove(ctx: ?*McoreContext, id: u64) void;
+
+/// Set the callback fired when a container starts overscrolling past an edge
+pub extern fn mcore_scroll_set_edge_callback(ctx: ?*McoreContext, callback: *const fn (u64, u8, f32) callconv(.c) void) void;
+
+/// The style every field of which is at its CSS default (auto sizes, no grow,
+/// shrink 1, row direction)
+pub extern fn mcore_layout_style_default() McoreLayoutStyle;
+
+/// Create layout node `id` (a host-chosen ID) or replace its style
+pub extern fn mcore_layout_node_set(ctx: ?*McoreContext, id: u64, style: [*c]const McoreLayoutStyle) McoreStatus;
+
+/// Replace a node's children, in order. A child that had another parent moves.
+pub extern fn mcore_layout_set_children(ctx: ?*McoreContext, id: u64, children: [*c]const u64, count: usize) McoreStatus;
+
+/// Remove a node; its children stay, without a parent
+pub extern fn mcore_layout_node_remove(ctx: ?*McoreContext, id: u64) void;
+
+/// Lay out the tree under `root_id` to fill `width` x `height` (logical px)
+pub extern fn mcore_layout_compute(ctx: ?*McoreContext, root_id: u64, width: f32, height: f32) McoreStatus;
+
+/// Read a node's rect from the last mcore_layout_compute of its root,
+/// relative to that root. Returns 1 and fills `out` if it has one, 0 otherwise.
+pub extern fn mcore_layout_get(ctx: ?*McoreContext, id: u64, out: [*c]McoreRect) u8;
+
+pub extern fn mcore_ui_theme_default() McoreUiTheme;
+
+pub extern fn mcore_ui_set_theme(ctx: ?*McoreContext, theme: [*c]const McoreUiTheme) void;
+
+/// Start a column of widgets `width` wide at x, y, with this frame's pointer.
+/// Call inside a frame; widgets draw into it as they're called.
+pub extern fn mcore_ui_begin(ctx: ?*McoreContext, x: f32, y: f32, width: f32, input: [*c]const McoreUiInput) McoreStatus;
+
+/// A wrapped paragraph of text
+pub extern fn mcore_ui_label(ctx: ?*McoreContext, text: [*c]const u8) void;
+
+/// A push button sized to its label. Returns MCORE_UI_* response flags;
+/// MCORE_UI_CLICKED is set on the frame it's clicked.
+pub extern fn mcore_ui_button(ctx: ?*McoreContext, id: u64, label: [*c]const u8) u32;
+
+/// A single-line text field `width` wide (0 fills the column), backed by the
+/// mcore_text_input_* state of the same ID. Pressing it focuses it; send key
+/// events for mcore_ui_focused's ID with mcore_text_input_event_ex and read
+/// the text with mcore_text_input_get. Returns MCORE_UI_* response flags.
+pub extern fn mcore_ui_text_input(ctx: ?*McoreContext, id: u64, width: f32) u32;
+
+/// Finish the column. Returns the height its widgets took (logical px).
+pub extern fn mcore_ui_end(ctx: ?*McoreContext) f32;
+
+/// The focused widget's ID, 0 if none is focused
+pub extern fn mcore_ui_focused(ctx: ?*McoreContext) u64;
+
+/// Set the callback told when the engine needs a frame.
+/// Callback signature: callback(time_seconds) on the mcore_begin_frame clock;
+/// a time at or before the last frame means "next vsync". It runs without the
+/// engine lock held, on whichever thread made the triggering call.
+pub extern fn mcore_set_redraw_callback(ctx: ?*McoreContext, callback: *const fn (f64) callconv(.c) void) void;
+
+/// Request a frame at (or after) an absolute time
+pub extern fn mcore_request_frame_at(ctx: ?*McoreContext, time_seconds: f64) void;
+
+/// Request a frame as soon as possible
+pub extern fn mcore_request_redraw(ctx: ?*McoreContext) void;
+
+/// Earliest time a frame is needed, or -1 if the engine is idle (for hosts that poll)
+pub extern fn mcore_next_frame_time(ctx: ?*McoreContext) f64;
+
+/// Report the refresh rate of the display showing the surface (Hz), e.g. when
+/// the window moves between monitors or ProMotion switches between 120 and 60.
+/// Engine animations step at the new rate and the refresh rate callback fires
+/// if it changed.
+pub extern fn mcore_set_refresh_rate(ctx: ?*McoreContext, hz: f32) void;
+
+/// Refresh rate of the current display (Hz), 60 until the host reports one
+pub extern fn mcore_get_refresh_rate(ctx: ?*McoreContext) f32;
+
+/// Set the callback told when the refresh rate changes, so host tickers can
+/// adapt their step size. Callback signature: callback(hz); runs without the
+/// engine lock held.
+pub extern fn mcore_set_refresh_rate_callback(ctx: ?*McoreContext, callback: *const fn (f32) callconv(.c) void) void;
+
+/// Tell the engine whether the window is fully occluded or minimized. While
+/// occluded, mcore_end_frame_present skips rendering and presenting (state
+/// updates and frame building still work) and animations stop requesting
+/// frames. Becoming visible requests a frame with the up-to-date state.
+pub extern fn mcore_set_occluded(ctx: ?*McoreContext, occluded: u8) void;
+
+pub extern fn mcore_is_occluded(ctx: ?*McoreContext) u8;
+
+/// Create a texture-backed widget of the given physical size.
+/// `callback(user_data, target)` runs inside mcore_end_frame_present with the
+/// engine lock held: render into target->native_texture, commit your command
+/// buffer (waitUntilScheduled) and return without calling back into the engine.
+/// Returns a widget ID (>= 0) or -1 on error.
+pub extern fn mcore_custom_texture_create(ctx: ?*McoreContext, width_px: u32, height_px: u32, callback: McoreTextureCallback, user_data: ?*anyopaque) i32;
+
+/// Recreate a widget's texture at a new physical size
+pub extern fn mcore_custom_texture_resize(ctx: ?*McoreContext, id: i32, width_px: u32, height_px: u32) McoreStatus;
+
+pub extern fn mcore_custom_texture_destroy(ctx: ?*McoreContext, id: i32) void;
+
+/// Composite a widget's texture into a logical-pixel rectangle
+pub extern fn mcore_custom_texture_draw(ctx: ?*McoreContext, id: i32, x: f32, y: f32, width: f32, height: f32) void;
+
+/// Create a video to submit frames to. It draws nothing until the first
+/// frame arrives. Returns a video ID (>= 0) or -1 on error.
+pub extern fn mcore_video_create(ctx: ?*McoreContext) i32;
+
+/// Make `frame` the one the video shows from the next rendered frame. The
+/// pixels are copied, so the host's buffer can be reused once this returns.
+/// Can be called from any thread (e.g. a display link callback).
+pub extern fn mcore_video_frame_submit(ctx: ?*McoreContext, video_id: i32, frame: [*c]const McoreVideoFrame) McoreStatus;
+
+/// Draw a video's latest frame into a logical-pixel rect, fitted per
+/// MCORE_VIDEO_FIT_*, like an MCORE_CMD_VIDEO record
+pub extern fn mcore_video_draw(ctx: ?*McoreContext, video_id: i32, x: f32, y: f32, width: f32, height: f32, fit: u32) void;
+
+pub extern fn mcore_video_destroy(ctx: ?*McoreContext, video_id: i32) void;
+
+/// Start a particle emitter. Bursts remove themselves once every particle has
+/// died; looping emitters run until mcore_particles_stop. With reduce motion
+/// on, bursts are dropped and looping emitters wait until it's off.
+/// Returns an emitter ID (>= 0) or -1 on error.
+pub extern fn mcore_particles_emit(ctx: ?*McoreContext, desc: [*c]const McoreParticleEmitter) i32;
+
+/// Stop an emitter immediately
+pub extern fn mcore_particles_stop(ctx: ?*McoreContext, id: i32) void;
+
+/// Initialize the accessibility adapter for a given NSView
+/// This should be called after creating the window but before showing it
+///
+/// # Safety
+/// ns_view must be a valid pointer to an NSView instance
+pub extern fn mcore_a11y_init(ctx: ?*McoreContext, ns_view: ?*anyopaque) void;
+
+/// Update the accessibility tree
+/// Zig builds an array of nodes and sends them all at once
+pub extern fn mcore_a11y_update(ctx: ?*McoreContext, nodes: [*c]const McoreA11yNode, node_count: i32, root_id: u64, focus_id: u64) void;
+
+/// Move accessibility focus to a node of the last tree (e.g. after keyboard
+/// navigation) without resending the tree
+pub extern fn mcore_a11y_set_focus(ctx: ?*McoreContext, focus_id: u64) void;
+
+/// Set callback for accessibility actions (focus, click, etc.)
+pub extern fn mcore_a11y_set_action_callback(callback: *const fn (u64, u8) callconv(.c) void) void;
+
+/// Re-query the OS accessibility preferences and return them
+/// Call when the app is notified of a change (e.g. on
+/// NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification)
+pub extern fn mcore_a11y_get_preferences(ctx: ?*McoreContext, out: [*c]McoreA11yPrefs) void;
+
+/// Override the accessibility preferences (for testing, or platforms the engine can't query)
+pub extern fn mcore_a11y_set_preferences(ctx: ?*McoreContext, prefs: [*c]const McoreA11yPrefs) void;
+
+/// Multiplier engine-driven animations apply to their motion
+/// Returns 0.0 when reduce-motion is on (animations jump to their end state), else 1.0
+pub extern fn mcore_animation_scale(ctx: ?*McoreContext) f32;
+
+/// Parse a CSS color string into McoreColor
+/// Supports: oklch(), rgb(), rgba(), hex (#rrggbb), named colors, hsl(), lab(), lch(), etc.
+/// Returns 1 on success, 0 on parse error
+pub extern fn mcore_color_parse(css_str: [*c]const u8, len: usize, out: [*c]McoreColor) u8;
+
+/// Interpolate between two colors using perceptually-correct Oklab space
+/// This produces much better results than naive RGB interpolation
+pub extern fn mcore_color_lerp(a: [*c]const McoreColor, b: [*c]const McoreColor, t: f32, out: [*c]McoreColor) void;
+
+/// Convert a color given in `space` to the values a window in `target`
+/// expects (see mcore_get_color_space). Colors already in `target` pass
+/// through unchanged, so wide-gamut P3 values survive in P3 windows;
+/// out-of-gamut results (e.g. saturated P3 reds in sRGB) are clamped.
+pub extern fn mcore_color_convert(space: u8, target: u8, input: [*c]const McoreColor, out: [*c]McoreColor) McoreStatus;
+
+/// Convert from RGBA8 (0-255) to McoreColor (0.0-1.0)
+pub extern fn mcore_color_from_rgba8(r: u8, g: u8, b: u8, a: u8, out: [*c]McoreColor) void;
+
+/// Register an image and copy pixel data to Rust
+/// Returns an image ID (>= 0) or -1 on error
+/// The `data` pointer can be freed after this function returns
+pub extern fn mcore_image_register(ctx: ?*McoreContext, desc: [*c]const McoreImageDesc) i32;
+
+/// Increment reference count for an image
+pub extern fn mcore_image_retain(ctx: ?*McoreContext, image_id: i32) void;
+
+/// Decrement reference count, free when 0 (or keep cached within the budget,
+/// see mcore_image_set_budget)
+pub extern fn mcore_image_release(ctx: ?*McoreContext, image_id: i32) void;
+
+/// Draw an image with transform
+pub extern fn mcore_image_draw(ctx: ?*McoreContext, image_id: i32, transform: [*c]const McoreImageTransform) void;
+
+/// Draw an image over a rect with its sampling and tiling options, like an
+/// MCORE_CMD_IMAGE record (logical px)
+pub extern fn mcore_image_draw_rect(ctx: ?*McoreContext, cmd: [*c]const McoreCmdImage) McoreStatus;
+
+/// Load and register an image from a file path (JPEG, PNG, etc.), turned
+/// upright per its EXIF orientation
+/// Returns image info (id, width, height). id is -1 on error.
+pub extern fn mcore_image_load_file(ctx: ?*McoreContext, path: [*c]const u8) McoreImageInfo;
+
+/// mcore_image_load_file, shrunk while decoding so neither side exceeds
+/// `max_dimension` (aspect ratio kept; smaller images are left as they are).
+/// List thumbnails of large photos then cost a fraction of the memory and
+/// upload.
+pub extern fn mcore_image_load_thumbnail(ctx: ?*McoreContext, path: [*c]const u8, max_dimension: u32) McoreImageInfo;
+
+/// Get image dimensions by ID
+/// Returns 1 on success, 0 if image not found
+pub extern fn mcore_image_get_info(ctx: ?*McoreContext, image_id: i32, out: [*c]McoreImageInfo) u8;
+
+/// Register encoded image bytes (JPEG, PNG, ...) and decode them on a
+/// background thread. Returns the image's ID (-1 on error) at once; until
+/// decoding finishes, draws of it fill their rect with the placeholder color.
+/// `callback` (optional) then runs on the decode thread with the ID and size,
+/// or a size of 0 x 0 if decoding failed, in which case the ID is freed.
+/// `data` can be freed after this returns.
+pub extern fn mcore_image_register_async(ctx: ?*McoreContext, data: [*c]const u8, len: usize, callback: ?*const fn (i32, u32, u32, ?*anyopaque) callconv(.c) void, user_data: ?*anyopaque) i32;
+
+/// mcore_image_register_async, shrunk while decoding so neither side exceeds
+/// `max_dimension` (see mcore_image_load_thumbnail)
+pub extern fn mcore_image_register_thumbnail_async(ctx: ?*McoreContext, data: [*c]const u8, len: usize, max_dimension: u32, callback: ?*const fn (i32, u32, u32, ?*anyopaque) callconv(.c) void, user_data: ?*anyopaque) i32;
+
+/// Color drawn in place of images still decoding (transparent by default)
+pub extern fn mcore_image_set_placeholder(ctx: ?*McoreContext, color: McoreRgba) void;
+
+/// Cap the decoded pixels the image cache holds. Released images stay cached
+/// (and can be retained again) until the budget needs their room; then they
+/// are evicted least recently drawn first, followed by mip levels. Images in
+/// use are never evicted. The default, 0, frees released images at once.
+/// Atlas pages aren't counted: they never shrink, so they take up to 32 MiB
+/// on top of the budget (see atlas_pages).
+pub extern fn mcore_image_set_budget(ctx: ?*McoreContext, bytes: u64) void;
+
+pub extern fn mcore_image_cache_stats(ctx: ?*McoreContext, out: [*c]McoreImageCacheStats) McoreStatus;
+
+/// Give memory back when the system runs low: MCORE_MEMORY_PRESSURE_WARNING
+/// frees released images, MCORE_MEMORY_PRESSURE_CRITICAL also drops every
+/// mip level. Call it from the platform's memory warning (e.g. a
+/// DISPATCH_SOURCE_TYPE_MEMORYPRESSURE source). Returns the bytes freed.
+pub extern fn mcore_memory_pressure(ctx: ?*McoreContext, level: u32) u64;
+
+// Layout tests (same numbers as rust/engine/src/abi_layout.rs)
+
+test "McoreErrorCode layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreErrorCode));
+    try std.testing.expectEqual(4, @alignOf(McoreErrorCode));
+}
+
+test "McoreCapability layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreCapability));
+    try std.testing.expectEqual(4, @alignOf(McoreCapability));
+}
+
+test "McorePlatform layout" {
+    try std.testing.expectEqual(4, @sizeOf(McorePlatform));
+    try std.testing.expectEqual(4, @alignOf(McorePlatform));
+}
+
+test "McoreMacSurface layout" {
+    try std.testing.expectEqual(32, @sizeOf(McoreMacSurface));
+    try std.testing.expectEqual(8, @alignOf(McoreMacSurface));
+    try std.testing.expectEqual(0, @offsetOf(McoreMacSurface, "ns_view"));
+    try std.testing.expectEqual(8, @offsetOf(McoreMacSurface, "ca_metal_layer"));
+    try std.testing.expectEqual(16, @offsetOf(McoreMacSurface, "scale_factor"));
+    try std.testing.expectEqual(20, @offsetOf(McoreMacSurface, "width_px"));
+    try std.testing.expectEqual(24, @offsetOf(McoreMacSurface, "height_px"));
+}
+
+test "McoreSurfaceUnion layout" {
+    try std.testing.expectEqual(32, @sizeOf(McoreSurfaceUnion));
+    try std.testing.expectEqual(8, @alignOf(McoreSurfaceUnion));
+}
+
+test "McorePowerPreference layout" {
+    try std.testing.expectEqual(4, @sizeOf(McorePowerPreference));
+    try std.testing.expectEqual(4, @alignOf(McorePowerPreference));
+}
+
+test "McoreSurfaceDesc layout" {
+    try std.testing.expectEqual(56, @sizeOf(McoreSurfaceDesc));
+    try std.testing.expectEqual(8, @alignOf(McoreSurfaceDesc));
+    try std.testing.expectEqual(0, @offsetOf(McoreSurfaceDesc, "api_version"));
+    try std.testing.expectEqual(4, @offsetOf(McoreSurfaceDesc, "platform"));
+    try std.testing.expectEqual(8, @offsetOf(McoreSurfaceDesc, "u"));
+    try std.testing.expectEqual(40, @offsetOf(McoreSurfaceDesc, "power_preference"));
+    try std.testing.expectEqual(44, @offsetOf(McoreSurfaceDesc, "adapter"));
+    try std.testing.expectEqual(48, @offsetOf(McoreSurfaceDesc, "flags"));
+}
+
+test "McoreEngineDesc layout" {
+    try std.testing.expectEqual(16, @sizeOf(McoreEngineDesc));
+    try std.testing.expectEqual(4, @alignOf(McoreEngineDesc));
+    try std.testing.expectEqual(0, @offsetOf(McoreEngineDesc, "api_version"));
+    try std.testing.expectEqual(4, @offsetOf(McoreEngineDesc, "power_preference"));
+    try std.testing.expectEqual(8, @offsetOf(McoreEngineDesc, "adapter"));
+    try std.testing.expectEqual(12, @offsetOf(McoreEngineDesc, "flags"));
+}
+
+test "McoreRenderMode layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreRenderMode));
+    try std.testing.expectEqual(4, @alignOf(McoreRenderMode));
+}
+
+test "McoreDeviceType layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreDeviceType));
+    try std.testing.expectEqual(4, @alignOf(McoreDeviceType));
+}
+
+test "McoreGpuBackend layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreGpuBackend));
+    try std.testing.expectEqual(4, @alignOf(McoreGpuBackend));
+}
+
+test "McoreAdapterInfo layout" {
+    try std.testing.expectEqual(144, @sizeOf(McoreAdapterInfo));
+    try std.testing.expectEqual(4, @alignOf(McoreAdapterInfo));
+    try std.testing.expectEqual(0, @offsetOf(McoreAdapterInfo, "name"));
+    try std.testing.expectEqual(128, @offsetOf(McoreAdapterInfo, "vendor"));
+    try std.testing.expectEqual(132, @offsetOf(McoreAdapterInfo, "device"));
+    try std.testing.expectEqual(136, @offsetOf(McoreAdapterInfo, "device_type"));
+    try std.testing.expectEqual(140, @offsetOf(McoreAdapterInfo, "backend"));
+}
+
+test "McoreGpuInfo layout" {
+    try std.testing.expectEqual(168, @sizeOf(McoreGpuInfo));
+    try std.testing.expectEqual(8, @alignOf(McoreGpuInfo));
+    try std.testing.expectEqual(0, @offsetOf(McoreGpuInfo, "adapter"));
+    try std.testing.expectEqual(144, @offsetOf(McoreGpuInfo, "max_texture_dimension_2d"));
+    try std.testing.expectEqual(148, @offsetOf(McoreGpuInfo, "max_storage_buffer_binding_size"));
+    try std.testing.expectEqual(152, @offsetOf(McoreGpuInfo, "max_buffer_size"));
+    try std.testing.expectEqual(160, @offsetOf(McoreGpuInfo, "timestamp_queries"));
+}
+
+test "McoreRgba layout" {
+    try std.testing.expectEqual(16, @sizeOf(McoreRgba));
+    try std.testing.expectEqual(4, @alignOf(McoreRgba));
+    try std.testing.expectEqual(0, @offsetOf(McoreRgba, "r"));
+    try std.testing.expectEqual(4, @offsetOf(McoreRgba, "g"));
+    try std.testing.expectEqual(8, @offsetOf(McoreRgba, "b"));
+    try std.testing.expectEqual(12, @offsetOf(McoreRgba, "a"));
+}
+
+test "McoreRoundedRect layout" {
+    try std.testing.expectEqual(36, @sizeOf(McoreRoundedRect));
+    try std.testing.expectEqual(4, @alignOf(McoreRoundedRect));
+    try std.testing.expectEqual(0, @offsetOf(McoreRoundedRect, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreRoundedRect, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreRoundedRect, "w"));
+    try std.testing.expectEqual(12, @offsetOf(McoreRoundedRect, "h"));
+    try std.testing.expectEqual(16, @offsetOf(McoreRoundedRect, "radius"));
+    try std.testing.expectEqual(20, @offsetOf(McoreRoundedRect, "fill"));
+}
+
+test "McoreFontBlob layout" {
+    try std.testing.expectEqual(24, @sizeOf(McoreFontBlob));
+    try std.testing.expectEqual(8, @alignOf(McoreFontBlob));
+    try std.testing.expectEqual(0, @offsetOf(McoreFontBlob, "data"));
+    try std.testing.expectEqual(8, @offsetOf(McoreFontBlob, "len"));
+    try std.testing.expectEqual(16, @offsetOf(McoreFontBlob, "name"));
+}
+
+test "McoreTextReq layout" {
+    try std.testing.expectEqual(80, @sizeOf(McoreTextReq));
+    try std.testing.expectEqual(8, @alignOf(McoreTextReq));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextReq, "utf8"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextReq, "wrap_width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreTextReq, "font_size_px"));
+    try std.testing.expectEqual(16, @offsetOf(McoreTextReq, "font_id"));
+    try std.testing.expectEqual(20, @offsetOf(McoreTextReq, "weight"));
+    try std.testing.expectEqual(24, @offsetOf(McoreTextReq, "italic"));
+    try std.testing.expectEqual(28, @offsetOf(McoreTextReq, "stretch"));
+    try std.testing.expectEqual(32, @offsetOf(McoreTextReq, "letter_spacing"));
+    try std.testing.expectEqual(36, @offsetOf(McoreTextReq, "word_spacing"));
+    try std.testing.expectEqual(40, @offsetOf(McoreTextReq, "line_height_multiplier"));
+    try std.testing.expectEqual(44, @offsetOf(McoreTextReq, "align"));
+    try std.testing.expectEqual(48, @offsetOf(McoreTextReq, "max_lines"));
+    try std.testing.expectEqual(52, @offsetOf(McoreTextReq, "overflow"));
+    try std.testing.expectEqual(53, @offsetOf(McoreTextReq, "direction"));
+    try std.testing.expectEqual(56, @offsetOf(McoreTextReq, "tab_width"));
+    try std.testing.expectEqual(64, @offsetOf(McoreTextReq, "tab_stops"));
+    try std.testing.expectEqual(72, @offsetOf(McoreTextReq, "tab_stop_count"));
+}
+
+test "McoreTextAlign layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreTextAlign));
+    try std.testing.expectEqual(4, @alignOf(McoreTextAlign));
+}
+
+test "McoreTextDirection layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreTextDirection));
+    try std.testing.expectEqual(4, @alignOf(McoreTextDirection));
+}
+
+test "McoreTextOverflow layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreTextOverflow));
+    try std.testing.expectEqual(4, @alignOf(McoreTextOverflow));
+}
+
+test "McoreTextSpan layout" {
+    try std.testing.expectEqual(36, @sizeOf(McoreTextSpan));
+    try std.testing.expectEqual(4, @alignOf(McoreTextSpan));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextSpan, "start"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextSpan, "end"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextSpan, "color"));
+    try std.testing.expectEqual(24, @offsetOf(McoreTextSpan, "weight"));
+    try std.testing.expectEqual(28, @offsetOf(McoreTextSpan, "italic"));
+    try std.testing.expectEqual(29, @offsetOf(McoreTextSpan, "underline"));
+    try std.testing.expectEqual(32, @offsetOf(McoreTextSpan, "font_id"));
+}
+
+test "McoreTextMetrics layout" {
+    try std.testing.expectEqual(16, @sizeOf(McoreTextMetrics));
+    try std.testing.expectEqual(4, @alignOf(McoreTextMetrics));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextMetrics, "advance_w"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextMetrics, "advance_h"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextMetrics, "line_count"));
+    try std.testing.expectEqual(12, @offsetOf(McoreTextMetrics, "truncated"));
+}
+
+test "McoreTextSize layout" {
+    try std.testing.expectEqual(8, @sizeOf(McoreTextSize));
+    try std.testing.expectEqual(4, @alignOf(McoreTextSize));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextSize, "width"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextSize, "height"));
+}
+
+test "McoreCaretRect layout" {
+    try std.testing.expectEqual(12, @sizeOf(McoreCaretRect));
+    try std.testing.expectEqual(4, @alignOf(McoreCaretRect));
+    try std.testing.expectEqual(0, @offsetOf(McoreCaretRect, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCaretRect, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCaretRect, "height"));
+}
+
+test "McoreTextStats layout" {
+    try std.testing.expectEqual(8, @sizeOf(McoreTextStats));
+    try std.testing.expectEqual(4, @alignOf(McoreTextStats));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextStats, "total_measure_calls"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextStats, "total_offset_calls"));
+}
+
+test "McoreHitTransform layout" {
+    try std.testing.expectEqual(12, @sizeOf(McoreHitTransform));
+    try std.testing.expectEqual(4, @alignOf(McoreHitTransform));
+    try std.testing.expectEqual(0, @offsetOf(McoreHitTransform, "dx"));
+    try std.testing.expectEqual(4, @offsetOf(McoreHitTransform, "dy"));
+    try std.testing.expectEqual(8, @offsetOf(McoreHitTransform, "scale"));
+}
+
+test "McoreHitResult layout" {
+    try std.testing.expectEqual(24, @sizeOf(McoreHitResult));
+    try std.testing.expectEqual(8, @alignOf(McoreHitResult));
+    try std.testing.expectEqual(0, @offsetOf(McoreHitResult, "id"));
+    try std.testing.expectEqual(8, @offsetOf(McoreHitResult, "z"));
+    try std.testing.expectEqual(12, @offsetOf(McoreHitResult, "local_x"));
+    try std.testing.expectEqual(16, @offsetOf(McoreHitResult, "local_y"));
+}
+
+test "McoreTextCacheStats layout" {
+    try std.testing.expectEqual(48, @sizeOf(McoreTextCacheStats));
+    try std.testing.expectEqual(8, @alignOf(McoreTextCacheStats));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextCacheStats, "entries"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextCacheStats, "max_entries"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextCacheStats, "bytes"));
+    try std.testing.expectEqual(16, @offsetOf(McoreTextCacheStats, "max_bytes"));
+    try std.testing.expectEqual(24, @offsetOf(McoreTextCacheStats, "hits"));
+    try std.testing.expectEqual(32, @offsetOf(McoreTextCacheStats, "misses"));
+    try std.testing.expectEqual(40, @offsetOf(McoreTextCacheStats, "evictions"));
+}
+
+test "McoreDrawCmdKind layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreDrawCmdKind));
+    try std.testing.expectEqual(4, @alignOf(McoreDrawCmdKind));
+}
+
+test "McoreDrawCommand layout" {
+    try std.testing.expectEqual(120, @sizeOf(McoreDrawCommand));
+    try std.testing.expectEqual(8, @alignOf(McoreDrawCommand));
+    try std.testing.expectEqual(0, @offsetOf(McoreDrawCommand, "kind"));
+    try std.testing.expectEqual(4, @offsetOf(McoreDrawCommand, "x"));
+    try std.testing.expectEqual(8, @offsetOf(McoreDrawCommand, "y"));
+    try std.testing.expectEqual(12, @offsetOf(McoreDrawCommand, "width"));
+    try std.testing.expectEqual(16, @offsetOf(McoreDrawCommand, "height"));
+    try std.testing.expectEqual(20, @offsetOf(McoreDrawCommand, "radius"));
+    try std.testing.expectEqual(24, @offsetOf(McoreDrawCommand, "color"));
+    try std.testing.expectEqual(40, @offsetOf(McoreDrawCommand, "text_ptr"));
+    try std.testing.expectEqual(48, @offsetOf(McoreDrawCommand, "font_size"));
+    try std.testing.expectEqual(52, @offsetOf(McoreDrawCommand, "wrap_width"));
+    try std.testing.expectEqual(56, @offsetOf(McoreDrawCommand, "font_id"));
+    try std.testing.expectEqual(60, @offsetOf(McoreDrawCommand, "border_width"));
+    try std.testing.expectEqual(64, @offsetOf(McoreDrawCommand, "border_color"));
+    try std.testing.expectEqual(80, @offsetOf(McoreDrawCommand, "has_border"));
+    try std.testing.expectEqual(84, @offsetOf(McoreDrawCommand, "shadow_offset_x"));
+    try std.testing.expectEqual(88, @offsetOf(McoreDrawCommand, "shadow_offset_y"));
+    try std.testing.expectEqual(92, @offsetOf(McoreDrawCommand, "shadow_blur"));
+    try std.testing.expectEqual(96, @offsetOf(McoreDrawCommand, "shadow_color"));
+    try std.testing.expectEqual(112, @offsetOf(McoreDrawCommand, "has_shadow"));
+    try std.testing.expectEqual(113, @offsetOf(McoreDrawCommand, "text_align"));
+    try std.testing.expectEqual(114, @offsetOf(McoreDrawCommand, "text_direction"));
+}
+
+test "McoreCmdHeader layout" {
+    try std.testing.expectEqual(8, @sizeOf(McoreCmdHeader));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdHeader));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdHeader, "tag"));
+    try std.testing.expectEqual(2, @offsetOf(McoreCmdHeader, "flags"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCmdHeader, "size"));
+}
+
+test "McoreCmdTag layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreCmdTag));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdTag));
+}
+
+test "McoreCmdFill layout" {
+    try std.testing.expectEqual(36, @sizeOf(McoreCmdFill));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdFill));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdFill, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCmdFill, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCmdFill, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreCmdFill, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreCmdFill, "radius"));
+    try std.testing.expectEqual(20, @offsetOf(McoreCmdFill, "color"));
+}
+
+test "McoreCmdStroke layout" {
+    try std.testing.expectEqual(40, @sizeOf(McoreCmdStroke));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdStroke));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdStroke, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCmdStroke, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCmdStroke, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreCmdStroke, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreCmdStroke, "radius"));
+    try std.testing.expectEqual(20, @offsetOf(McoreCmdStroke, "line_width"));
+    try std.testing.expectEqual(24, @offsetOf(McoreCmdStroke, "color"));
+}
+
+test "McoreCmdShadow layout" {
+    try std.testing.expectEqual(40, @sizeOf(McoreCmdShadow));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdShadow));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdShadow, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCmdShadow, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCmdShadow, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreCmdShadow, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreCmdShadow, "radius"));
+    try std.testing.expectEqual(20, @offsetOf(McoreCmdShadow, "blur"));
+    try std.testing.expectEqual(24, @offsetOf(McoreCmdShadow, "color"));
+}
+
+test "McoreCmdText layout" {
+    try std.testing.expectEqual(48, @sizeOf(McoreCmdText));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdText));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdText, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCmdText, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCmdText, "font_size"));
+    try std.testing.expectEqual(12, @offsetOf(McoreCmdText, "wrap_width"));
+    try std.testing.expectEqual(16, @offsetOf(McoreCmdText, "font_id"));
+    try std.testing.expectEqual(20, @offsetOf(McoreCmdText, "color"));
+    try std.testing.expectEqual(36, @offsetOf(McoreCmdText, "align"));
+    try std.testing.expectEqual(40, @offsetOf(McoreCmdText, "direction"));
+    try std.testing.expectEqual(44, @offsetOf(McoreCmdText, "text_len"));
+}
+
+test "McoreCmdClip layout" {
+    try std.testing.expectEqual(20, @sizeOf(McoreCmdClip));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdClip));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdClip, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCmdClip, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCmdClip, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreCmdClip, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreCmdClip, "radius"));
+}
+
+test "McoreCmdGradient layout" {
+    try std.testing.expectEqual(28, @sizeOf(McoreCmdGradient));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdGradient));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdGradient, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCmdGradient, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCmdGradient, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreCmdGradient, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreCmdGradient, "radius"));
+    try std.testing.expectEqual(20, @offsetOf(McoreCmdGradient, "gradient_id"));
+    try std.testing.expectEqual(24, @offsetOf(McoreCmdGradient, "line_width"));
+}
+
+test "McoreCmdImage layout" {
+    try std.testing.expectEqual(48, @sizeOf(McoreCmdImage));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdImage));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdImage, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCmdImage, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCmdImage, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreCmdImage, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreCmdImage, "image_id"));
+    try std.testing.expectEqual(20, @offsetOf(McoreCmdImage, "sampling"));
+    try std.testing.expectEqual(24, @offsetOf(McoreCmdImage, "extend_x"));
+    try std.testing.expectEqual(28, @offsetOf(McoreCmdImage, "extend_y"));
+    try std.testing.expectEqual(32, @offsetOf(McoreCmdImage, "tile_x"));
+    try std.testing.expectEqual(36, @offsetOf(McoreCmdImage, "tile_y"));
+    try std.testing.expectEqual(40, @offsetOf(McoreCmdImage, "tile_width"));
+    try std.testing.expectEqual(44, @offsetOf(McoreCmdImage, "tile_height"));
+}
+
+test "McoreCmdTransform layout" {
+    try std.testing.expectEqual(24, @sizeOf(McoreCmdTransform));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdTransform));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdTransform, "matrix"));
+}
+
+test "McoreCmdVideo layout" {
+    try std.testing.expectEqual(24, @sizeOf(McoreCmdVideo));
+    try std.testing.expectEqual(4, @alignOf(McoreCmdVideo));
+    try std.testing.expectEqual(0, @offsetOf(McoreCmdVideo, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCmdVideo, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCmdVideo, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreCmdVideo, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreCmdVideo, "video_id"));
+    try std.testing.expectEqual(20, @offsetOf(McoreCmdVideo, "fit"));
+}
+
+test "McoreColor layout" {
+    try std.testing.expectEqual(16, @sizeOf(McoreColor));
+    try std.testing.expectEqual(4, @alignOf(McoreColor));
+    try std.testing.expectEqual(0, @offsetOf(McoreColor, "r"));
+    try std.testing.expectEqual(4, @offsetOf(McoreColor, "g"));
+    try std.testing.expectEqual(8, @offsetOf(McoreColor, "b"));
+    try std.testing.expectEqual(12, @offsetOf(McoreColor, "a"));
+}
+
+test "McoreStatus layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreStatus));
+    try std.testing.expectEqual(4, @alignOf(McoreStatus));
+}
+
+test "McoreTextBlockKind layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreTextBlockKind));
+    try std.testing.expectEqual(4, @alignOf(McoreTextBlockKind));
+}
+
+test "McoreTextRun layout" {
+    try std.testing.expectEqual(28, @sizeOf(McoreTextRun));
+    try std.testing.expectEqual(4, @alignOf(McoreTextRun));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextRun, "start"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextRun, "end"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextRun, "flags"));
+    try std.testing.expectEqual(12, @offsetOf(McoreTextRun, "color"));
+}
+
+test "McoreTextBlockParagraph layout" {
+    try std.testing.expectEqual(32, @sizeOf(McoreTextBlockParagraph));
+    try std.testing.expectEqual(8, @alignOf(McoreTextBlockParagraph));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextBlockParagraph, "utf8"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextBlockParagraph, "kind"));
+    try std.testing.expectEqual(12, @offsetOf(McoreTextBlockParagraph, "level"));
+    try std.testing.expectEqual(16, @offsetOf(McoreTextBlockParagraph, "number"));
+    try std.testing.expectEqual(20, @offsetOf(McoreTextBlockParagraph, "run_count"));
+    try std.testing.expectEqual(24, @offsetOf(McoreTextBlockParagraph, "runs"));
+}
+
+test "McoreTextBlockStyle layout" {
+    try std.testing.expectEqual(72, @sizeOf(McoreTextBlockStyle));
+    try std.testing.expectEqual(4, @alignOf(McoreTextBlockStyle));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextBlockStyle, "font_size_px"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextBlockStyle, "font_id"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextBlockStyle, "code_font_id"));
+    try std.testing.expectEqual(12, @offsetOf(McoreTextBlockStyle, "line_height_multiplier"));
+    try std.testing.expectEqual(16, @offsetOf(McoreTextBlockStyle, "paragraph_spacing"));
+    try std.testing.expectEqual(20, @offsetOf(McoreTextBlockStyle, "list_indent"));
+    try std.testing.expectEqual(24, @offsetOf(McoreTextBlockStyle, "color"));
+    try std.testing.expectEqual(40, @offsetOf(McoreTextBlockStyle, "code_color"));
+    try std.testing.expectEqual(56, @offsetOf(McoreTextBlockStyle, "code_background"));
+}
+
+test "McoreCodeReq layout" {
+    try std.testing.expectEqual(80, @sizeOf(McoreCodeReq));
+    try std.testing.expectEqual(8, @alignOf(McoreCodeReq));
+    try std.testing.expectEqual(0, @offsetOf(McoreCodeReq, "text"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCodeReq, "len"));
+    try std.testing.expectEqual(16, @offsetOf(McoreCodeReq, "font_size_px"));
+    try std.testing.expectEqual(20, @offsetOf(McoreCodeReq, "font_id"));
+    try std.testing.expectEqual(24, @offsetOf(McoreCodeReq, "line_height_multiplier"));
+    try std.testing.expectEqual(28, @offsetOf(McoreCodeReq, "tab_size"));
+    try std.testing.expectEqual(32, @offsetOf(McoreCodeReq, "line_numbers"));
+    try std.testing.expectEqual(36, @offsetOf(McoreCodeReq, "scroll_y"));
+    try std.testing.expectEqual(40, @offsetOf(McoreCodeReq, "viewport_height"));
+    try std.testing.expectEqual(44, @offsetOf(McoreCodeReq, "color"));
+    try std.testing.expectEqual(60, @offsetOf(McoreCodeReq, "line_number_color"));
+}
+
+test "McoreCodeToken layout" {
+    try std.testing.expectEqual(24, @sizeOf(McoreCodeToken));
+    try std.testing.expectEqual(4, @alignOf(McoreCodeToken));
+    try std.testing.expectEqual(0, @offsetOf(McoreCodeToken, "start"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCodeToken, "end"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCodeToken, "color"));
+}
+
+test "McoreCodeMetrics layout" {
+    try std.testing.expectEqual(16, @sizeOf(McoreCodeMetrics));
+    try std.testing.expectEqual(4, @alignOf(McoreCodeMetrics));
+    try std.testing.expectEqual(0, @offsetOf(McoreCodeMetrics, "line_count"));
+    try std.testing.expectEqual(4, @offsetOf(McoreCodeMetrics, "line_height"));
+    try std.testing.expectEqual(8, @offsetOf(McoreCodeMetrics, "gutter_width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreCodeMetrics, "content_height"));
+}
+
+test "McoreGradientStop layout" {
+    try std.testing.expectEqual(20, @sizeOf(McoreGradientStop));
+    try std.testing.expectEqual(4, @alignOf(McoreGradientStop));
+    try std.testing.expectEqual(0, @offsetOf(McoreGradientStop, "offset"));
+    try std.testing.expectEqual(4, @offsetOf(McoreGradientStop, "color"));
+}
+
+test "McoreGradientDesc layout" {
+    try std.testing.expectEqual(24, @sizeOf(McoreGradientDesc));
+    try std.testing.expectEqual(8, @alignOf(McoreGradientDesc));
+    try std.testing.expectEqual(0, @offsetOf(McoreGradientDesc, "kind"));
+    try std.testing.expectEqual(1, @offsetOf(McoreGradientDesc, "_padding"));
+    try std.testing.expectEqual(4, @offsetOf(McoreGradientDesc, "angle_deg"));
+    try std.testing.expectEqual(8, @offsetOf(McoreGradientDesc, "stops"));
+    try std.testing.expectEqual(16, @offsetOf(McoreGradientDesc, "stop_count"));
+}
+
+test "McoreFrameStats layout" {
+    try std.testing.expectEqual(80, @sizeOf(McoreFrameStats));
+    try std.testing.expectEqual(8, @alignOf(McoreFrameStats));
+    try std.testing.expectEqual(0, @offsetOf(McoreFrameStats, "frame"));
+    try std.testing.expectEqual(8, @offsetOf(McoreFrameStats, "build_ms"));
+    try std.testing.expectEqual(12, @offsetOf(McoreFrameStats, "encode_ms"));
+    try std.testing.expectEqual(16, @offsetOf(McoreFrameStats, "custom_ms"));
+    try std.testing.expectEqual(20, @offsetOf(McoreFrameStats, "render_ms"));
+    try std.testing.expectEqual(24, @offsetOf(McoreFrameStats, "gpu_ms"));
+    try std.testing.expectEqual(28, @offsetOf(McoreFrameStats, "commands"));
+    try std.testing.expectEqual(32, @offsetOf(McoreFrameStats, "encode_calls"));
+    try std.testing.expectEqual(36, @offsetOf(McoreFrameStats, "paths"));
+    try std.testing.expectEqual(40, @offsetOf(McoreFrameStats, "path_segments"));
+    try std.testing.expectEqual(44, @offsetOf(McoreFrameStats, "clips"));
+    try std.testing.expectEqual(48, @offsetOf(McoreFrameStats, "draw_objects"));
+    try std.testing.expectEqual(52, @offsetOf(McoreFrameStats, "image_count"));
+    try std.testing.expectEqual(56, @offsetOf(McoreFrameStats, "image_bytes"));
+    try std.testing.expectEqual(64, @offsetOf(McoreFrameStats, "texture_bytes"));
+    try std.testing.expectEqual(72, @offsetOf(McoreFrameStats, "layer_warnings"));
+}
+
+test "McoreAaMode layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreAaMode));
+    try std.testing.expectEqual(4, @alignOf(McoreAaMode));
+}
+
+test "McoreTextRendering layout" {
+    try std.testing.expectEqual(8, @sizeOf(McoreTextRendering));
+    try std.testing.expectEqual(4, @alignOf(McoreTextRendering));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextRendering, "hinting"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextRendering, "subpixel_positions"));
+}
+
+test "McoreTextEventKind layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreTextEventKind));
+    try std.testing.expectEqual(4, @alignOf(McoreTextEventKind));
+}
+
+test "McoreCursorDirection layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreCursorDirection));
+    try std.testing.expectEqual(4, @alignOf(McoreCursorDirection));
+}
+
+test "McoreTextEvent layout" {
+    try std.testing.expectEqual(32, @sizeOf(McoreTextEvent));
+    try std.testing.expectEqual(8, @alignOf(McoreTextEvent));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextEvent, "kind"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextEvent, "char_code"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextEvent, "direction"));
+    try std.testing.expectEqual(12, @offsetOf(McoreTextEvent, "extend_selection"));
+    try std.testing.expectEqual(16, @offsetOf(McoreTextEvent, "cursor_position"));
+    try std.testing.expectEqual(24, @offsetOf(McoreTextEvent, "text_ptr"));
+}
+
+test "McoreTextEventResult layout" {
+    try std.testing.expectEqual(12, @sizeOf(McoreTextEventResult));
+    try std.testing.expectEqual(4, @alignOf(McoreTextEventResult));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextEventResult, "changed"));
+    try std.testing.expectEqual(1, @offsetOf(McoreTextEventResult, "scrolled"));
+    try std.testing.expectEqual(2, @offsetOf(McoreTextEventResult, "_padding"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextEventResult, "scroll_x"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextEventResult, "scroll_y"));
+}
+
+test "McoreTextInputStyle layout" {
+    try std.testing.expectEqual(84, @sizeOf(McoreTextInputStyle));
+    try std.testing.expectEqual(4, @alignOf(McoreTextInputStyle));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextInputStyle, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreTextInputStyle, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextInputStyle, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreTextInputStyle, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreTextInputStyle, "padding_x"));
+    try std.testing.expectEqual(20, @offsetOf(McoreTextInputStyle, "padding_y"));
+    try std.testing.expectEqual(24, @offsetOf(McoreTextInputStyle, "font_size"));
+    try std.testing.expectEqual(28, @offsetOf(McoreTextInputStyle, "wrap_width"));
+    try std.testing.expectEqual(32, @offsetOf(McoreTextInputStyle, "text_color"));
+    try std.testing.expectEqual(48, @offsetOf(McoreTextInputStyle, "selection_color"));
+    try std.testing.expectEqual(64, @offsetOf(McoreTextInputStyle, "caret_color"));
+    try std.testing.expectEqual(80, @offsetOf(McoreTextInputStyle, "focused"));
+    try std.testing.expectEqual(81, @offsetOf(McoreTextInputStyle, "_padding"));
+}
+
+test "McoreTextInputHints layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreTextInputHints));
+    try std.testing.expectEqual(1, @alignOf(McoreTextInputHints));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextInputHints, "keyboard"));
+    try std.testing.expectEqual(1, @offsetOf(McoreTextInputHints, "autocorrect"));
+    try std.testing.expectEqual(2, @offsetOf(McoreTextInputHints, "secure"));
+    try std.testing.expectEqual(3, @offsetOf(McoreTextInputHints, "ime_enabled"));
+}
+
+test "McoreCursor layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreCursor));
+    try std.testing.expectEqual(4, @alignOf(McoreCursor));
+}
+
+test "McoreImePreedit layout" {
+    try std.testing.expectEqual(16, @sizeOf(McoreImePreedit));
+    try std.testing.expectEqual(8, @alignOf(McoreImePreedit));
+    try std.testing.expectEqual(0, @offsetOf(McoreImePreedit, "text"));
+    try std.testing.expectEqual(8, @offsetOf(McoreImePreedit, "cursor_offset"));
+}
+
+test "McorePointerPhase layout" {
+    try std.testing.expectEqual(4, @sizeOf(McorePointerPhase));
+    try std.testing.expectEqual(4, @alignOf(McorePointerPhase));
+}
+
+test "McoreGesture layout" {
+    try std.testing.expectEqual(28, @sizeOf(McoreGesture));
+    try std.testing.expectEqual(4, @alignOf(McoreGesture));
+    try std.testing.expectEqual(0, @offsetOf(McoreGesture, "kind"));
+    try std.testing.expectEqual(4, @offsetOf(McoreGesture, "x"));
+    try std.testing.expectEqual(8, @offsetOf(McoreGesture, "y"));
+    try std.testing.expectEqual(12, @offsetOf(McoreGesture, "dx"));
+    try std.testing.expectEqual(16, @offsetOf(McoreGesture, "dy"));
+    try std.testing.expectEqual(20, @offsetOf(McoreGesture, "velocity_x"));
+    try std.testing.expectEqual(24, @offsetOf(McoreGesture, "velocity_y"));
+}
+
+test "McoreScrollState layout" {
+    try std.testing.expectEqual(20, @sizeOf(McoreScrollState));
+    try std.testing.expectEqual(4, @alignOf(McoreScrollState));
+    try std.testing.expectEqual(0, @offsetOf(McoreScrollState, "offset_x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreScrollState, "offset_y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreScrollState, "velocity_x"));
+    try std.testing.expectEqual(12, @offsetOf(McoreScrollState, "velocity_y"));
+    try std.testing.expectEqual(16, @offsetOf(McoreScrollState, "animating"));
+}
+
+test "McoreScrollPhase layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreScrollPhase));
+    try std.testing.expectEqual(4, @alignOf(McoreScrollPhase));
+}
+
+test "McoreScrollAxis layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreScrollAxis));
+    try std.testing.expectEqual(4, @alignOf(McoreScrollAxis));
+}
+
+test "McoreLayoutDirection layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreLayoutDirection));
+    try std.testing.expectEqual(4, @alignOf(McoreLayoutDirection));
+}
+
+test "McoreLayoutJustify layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreLayoutJustify));
+    try std.testing.expectEqual(4, @alignOf(McoreLayoutJustify));
+}
+
+test "McoreLayoutAlign layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreLayoutAlign));
+    try std.testing.expectEqual(4, @alignOf(McoreLayoutAlign));
+}
+
+test "McoreEdges layout" {
+    try std.testing.expectEqual(16, @sizeOf(McoreEdges));
+    try std.testing.expectEqual(4, @alignOf(McoreEdges));
+    try std.testing.expectEqual(0, @offsetOf(McoreEdges, "top"));
+    try std.testing.expectEqual(4, @offsetOf(McoreEdges, "right"));
+    try std.testing.expectEqual(8, @offsetOf(McoreEdges, "bottom"));
+    try std.testing.expectEqual(12, @offsetOf(McoreEdges, "left"));
+}
+
+test "McoreLayoutStyle layout" {
+    try std.testing.expectEqual(84, @sizeOf(McoreLayoutStyle));
+    try std.testing.expectEqual(4, @alignOf(McoreLayoutStyle));
+    try std.testing.expectEqual(0, @offsetOf(McoreLayoutStyle, "direction"));
+    try std.testing.expectEqual(1, @offsetOf(McoreLayoutStyle, "wrap"));
+    try std.testing.expectEqual(2, @offsetOf(McoreLayoutStyle, "justify"));
+    try std.testing.expectEqual(3, @offsetOf(McoreLayoutStyle, "align_items"));
+    try std.testing.expectEqual(4, @offsetOf(McoreLayoutStyle, "align_self"));
+    try std.testing.expectEqual(5, @offsetOf(McoreLayoutStyle, "hidden"));
+    try std.testing.expectEqual(8, @offsetOf(McoreLayoutStyle, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreLayoutStyle, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreLayoutStyle, "min_width"));
+    try std.testing.expectEqual(20, @offsetOf(McoreLayoutStyle, "min_height"));
+    try std.testing.expectEqual(24, @offsetOf(McoreLayoutStyle, "max_width"));
+    try std.testing.expectEqual(28, @offsetOf(McoreLayoutStyle, "max_height"));
+    try std.testing.expectEqual(32, @offsetOf(McoreLayoutStyle, "grow"));
+    try std.testing.expectEqual(36, @offsetOf(McoreLayoutStyle, "shrink"));
+    try std.testing.expectEqual(40, @offsetOf(McoreLayoutStyle, "basis"));
+    try std.testing.expectEqual(44, @offsetOf(McoreLayoutStyle, "padding"));
+    try std.testing.expectEqual(60, @offsetOf(McoreLayoutStyle, "margin"));
+    try std.testing.expectEqual(76, @offsetOf(McoreLayoutStyle, "row_gap"));
+    try std.testing.expectEqual(80, @offsetOf(McoreLayoutStyle, "column_gap"));
+}
+
+test "McoreUiTheme layout" {
+    try std.testing.expectEqual(112, @sizeOf(McoreUiTheme));
+    try std.testing.expectEqual(4, @alignOf(McoreUiTheme));
+    try std.testing.expectEqual(0, @offsetOf(McoreUiTheme, "font_size"));
+    try std.testing.expectEqual(4, @offsetOf(McoreUiTheme, "padding"));
+    try std.testing.expectEqual(8, @offsetOf(McoreUiTheme, "spacing"));
+    try std.testing.expectEqual(12, @offsetOf(McoreUiTheme, "corner_radius"));
+    try std.testing.expectEqual(16, @offsetOf(McoreUiTheme, "text"));
+    try std.testing.expectEqual(32, @offsetOf(McoreUiTheme, "button"));
+    try std.testing.expectEqual(48, @offsetOf(McoreUiTheme, "button_hover"));
+    try std.testing.expectEqual(64, @offsetOf(McoreUiTheme, "button_pressed"));
+    try std.testing.expectEqual(80, @offsetOf(McoreUiTheme, "field"));
+    try std.testing.expectEqual(96, @offsetOf(McoreUiTheme, "accent"));
+}
+
+test "McoreUiInput layout" {
+    try std.testing.expectEqual(12, @sizeOf(McoreUiInput));
+    try std.testing.expectEqual(4, @alignOf(McoreUiInput));
+    try std.testing.expectEqual(0, @offsetOf(McoreUiInput, "pointer_x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreUiInput, "pointer_y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreUiInput, "pointer_down"));
+    try std.testing.expectEqual(9, @offsetOf(McoreUiInput, "_padding"));
+}
+
+test "McoreTextureTarget layout" {
+    try std.testing.expectEqual(24, @sizeOf(McoreTextureTarget));
+    try std.testing.expectEqual(8, @alignOf(McoreTextureTarget));
+    try std.testing.expectEqual(0, @offsetOf(McoreTextureTarget, "native_texture"));
+    try std.testing.expectEqual(8, @offsetOf(McoreTextureTarget, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreTextureTarget, "height"));
+    try std.testing.expectEqual(16, @offsetOf(McoreTextureTarget, "time_seconds"));
+}
+
+test "McoreVideoFrame layout" {
+    try std.testing.expectEqual(56, @sizeOf(McoreVideoFrame));
+    try std.testing.expectEqual(8, @alignOf(McoreVideoFrame));
+    try std.testing.expectEqual(0, @offsetOf(McoreVideoFrame, "format"));
+    try std.testing.expectEqual(4, @offsetOf(McoreVideoFrame, "width"));
+    try std.testing.expectEqual(8, @offsetOf(McoreVideoFrame, "height"));
+    try std.testing.expectEqual(12, @offsetOf(McoreVideoFrame, "matrix"));
+    try std.testing.expectEqual(16, @offsetOf(McoreVideoFrame, "full_range"));
+    try std.testing.expectEqual(20, @offsetOf(McoreVideoFrame, "_reserved"));
+    try std.testing.expectEqual(24, @offsetOf(McoreVideoFrame, "planes"));
+    try std.testing.expectEqual(40, @offsetOf(McoreVideoFrame, "strides"));
+    try std.testing.expectEqual(48, @offsetOf(McoreVideoFrame, "iosurface"));
+}
+
+test "McoreParticleLayer layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreParticleLayer));
+    try std.testing.expectEqual(4, @alignOf(McoreParticleLayer));
+}
+
+test "McoreParticleEmitter layout" {
+    try std.testing.expectEqual(112, @sizeOf(McoreParticleEmitter));
+    try std.testing.expectEqual(4, @alignOf(McoreParticleEmitter));
+    try std.testing.expectEqual(0, @offsetOf(McoreParticleEmitter, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreParticleEmitter, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreParticleEmitter, "count"));
+    try std.testing.expectEqual(12, @offsetOf(McoreParticleEmitter, "lifetime"));
+    try std.testing.expectEqual(16, @offsetOf(McoreParticleEmitter, "speed_min"));
+    try std.testing.expectEqual(20, @offsetOf(McoreParticleEmitter, "speed_max"));
+    try std.testing.expectEqual(24, @offsetOf(McoreParticleEmitter, "direction"));
+    try std.testing.expectEqual(28, @offsetOf(McoreParticleEmitter, "spread"));
+    try std.testing.expectEqual(32, @offsetOf(McoreParticleEmitter, "gravity"));
+    try std.testing.expectEqual(36, @offsetOf(McoreParticleEmitter, "size"));
+    try std.testing.expectEqual(40, @offsetOf(McoreParticleEmitter, "colors"));
+    try std.testing.expectEqual(104, @offsetOf(McoreParticleEmitter, "looping"));
+    try std.testing.expectEqual(108, @offsetOf(McoreParticleEmitter, "layer"));
+}
+
+test "McoreA11yNode layout" {
+    try std.testing.expectEqual(88, @sizeOf(McoreA11yNode));
+    try std.testing.expectEqual(8, @alignOf(McoreA11yNode));
+    try std.testing.expectEqual(0, @offsetOf(McoreA11yNode, "id"));
+    try std.testing.expectEqual(8, @offsetOf(McoreA11yNode, "role"));
+    try std.testing.expectEqual(16, @offsetOf(McoreA11yNode, "label"));
+    try std.testing.expectEqual(24, @offsetOf(McoreA11yNode, "bounds"));
+    try std.testing.expectEqual(40, @offsetOf(McoreA11yNode, "actions"));
+    try std.testing.expectEqual(48, @offsetOf(McoreA11yNode, "children"));
+    try std.testing.expectEqual(56, @offsetOf(McoreA11yNode, "children_count"));
+    try std.testing.expectEqual(64, @offsetOf(McoreA11yNode, "value"));
+    try std.testing.expectEqual(72, @offsetOf(McoreA11yNode, "text_selection_start"));
+    try std.testing.expectEqual(76, @offsetOf(McoreA11yNode, "text_selection_end"));
+    try std.testing.expectEqual(80, @offsetOf(McoreA11yNode, "states"));
+}
+
+test "McoreRect layout" {
+    try std.testing.expectEqual(16, @sizeOf(McoreRect));
+    try std.testing.expectEqual(4, @alignOf(McoreRect));
+    try std.testing.expectEqual(0, @offsetOf(McoreRect, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreRect, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreRect, "width"));
+    try std.testing.expectEqual(12, @offsetOf(McoreRect, "height"));
+}
+
+test "McoreA11yPrefs layout" {
+    try std.testing.expectEqual(3, @sizeOf(McoreA11yPrefs));
+    try std.testing.expectEqual(1, @alignOf(McoreA11yPrefs));
+    try std.testing.expectEqual(0, @offsetOf(McoreA11yPrefs, "reduce_motion"));
+    try std.testing.expectEqual(1, @offsetOf(McoreA11yPrefs, "increase_contrast"));
+    try std.testing.expectEqual(2, @offsetOf(McoreA11yPrefs, "reduce_transparency"));
+}
+
+test "McoreColorSpace layout" {
+    try std.testing.expectEqual(4, @sizeOf(McoreColorSpace));
+    try std.testing.expectEqual(4, @alignOf(McoreColorSpace));
+}
+
+test "McoreImageDesc layout" {
+    try std.testing.expectEqual(24, @sizeOf(McoreImageDesc));
+    try std.testing.expectEqual(8, @alignOf(McoreImageDesc));
+    try std.testing.expectEqual(0, @offsetOf(McoreImageDesc, "data"));
+    try std.testing.expectEqual(8, @offsetOf(McoreImageDesc, "data_len"));
+    try std.testing.expectEqual(12, @offsetOf(McoreImageDesc, "width"));
+    try std.testing.expectEqual(16, @offsetOf(McoreImageDesc, "height"));
+    try std.testing.expectEqual(20, @offsetOf(McoreImageDesc, "format"));
+    try std.testing.expectEqual(21, @offsetOf(McoreImageDesc, "alpha_type"));
+}
+
+test "McoreImageTransform layout" {
+    try std.testing.expectEqual(16, @sizeOf(McoreImageTransform));
+    try std.testing.expectEqual(4, @alignOf(McoreImageTransform));
+    try std.testing.expectEqual(0, @offsetOf(McoreImageTransform, "x"));
+    try std.testing.expectEqual(4, @offsetOf(McoreImageTransform, "y"));
+    try std.testing.expectEqual(8, @offsetOf(McoreImageTransform, "scale"));
+    try std.testing.expectEqual(12, @offsetOf(McoreImageTransform, "rotation_deg"));
+}
+
+test "McoreImageInfo layout" {
+    try std.testing.expectEqual(12, @sizeOf(McoreImageInfo));
+    try std.testing.expectEqual(4, @alignOf(McoreImageInfo));
+    try std.testing.expectEqual(0, @offsetOf(McoreImageInfo, "image_id"));
+    try std.testing.expectEqual(4, @offsetOf(McoreImageInfo, "width"));
+    try std.testing.expectEqual(8, @offsetOf(McoreImageInfo, "height"));
+}
+
+test "McoreImageCacheStats layout" {
+    try std.testing.expectEqual(48, @sizeOf(McoreImageCacheStats));
+    try std.testing.expectEqual(8, @alignOf(McoreImageCacheStats));
+    try std.testing.expectEqual(0, @offsetOf(McoreImageCacheStats, "bytes"));
+    try std.testing.expectEqual(8, @offsetOf(McoreImageCacheStats, "released_bytes"));
+    try std.testing.expectEqual(16, @offsetOf(McoreImageCacheStats, "budget_bytes"));
+    try std.testing.expectEqual(24, @offsetOf(McoreImageCacheStats, "evictions"));
+    try std.testing.expectEqual(32, @offsetOf(McoreImageCacheStats, "image_count"));
+    try std.testing.expectEqual(36, @offsetOf(McoreImageCacheStats, "released_count"));
+    try std.testing.expectEqual(40, @offsetOf(McoreImageCacheStats, "pending_count"));
+    try std.testing.expectEqual(44, @offsetOf(McoreImageCacheStats, "atlas_pages"));
+}
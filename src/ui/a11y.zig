@@ -13,6 +13,14 @@ pub const Role = enum(u8) {
     Label = 3,
     Group = 4,
     Image = 5,
+    CheckBox = 6,
+    Switch = 7,
+    Slider = 8,
+    Link = 9,
+    Heading = 10,
+    List = 11,
+    ListItem = 12,
+    ScrollView = 13,
 };
 
 /// Accessibility actions (bitfield)
@@ -21,6 +29,17 @@ pub const Actions = struct {
     pub const Click: u32 = 0x02;
 };
 
+/// Accessibility node states (bitfield)
+pub const States = struct {
+    pub const Disabled: u32 = c.MCORE_A11Y_STATE_DISABLED;
+    pub const Checked: u32 = c.MCORE_A11Y_STATE_CHECKED;
+    pub const Mixed: u32 = c.MCORE_A11Y_STATE_MIXED;
+    pub const Selected: u32 = c.MCORE_A11Y_STATE_SELECTED;
+    pub const Expanded: u32 = c.MCORE_A11Y_STATE_EXPANDED;
+    pub const Collapsed: u32 = c.MCORE_A11Y_STATE_COLLAPSED;
+    pub const Hidden: u32 = c.MCORE_A11Y_STATE_HIDDEN;
+};
+
 /// Rectangle in screen coordinates
 pub const Rect = struct {
     x: f32,
@@ -40,6 +59,7 @@ pub const Node = struct {
     value: ?[]const u8 = null,
     text_selection_start: i32 = -1,
     text_selection_end: i32 = -1,
+    states: u32 = 0,
     allocator: std.mem.Allocator,
 
     pub fn init(allocator: std.mem.Allocator, id: u64, role: Role, bounds: Rect) Node {
@@ -73,6 +93,10 @@ pub const Node = struct {
         self.actions |= action;
     }
 
+    pub fn addState(self: *Node, state: u32) void {
+        self.states |= state;
+    }
+
     pub fn addChild(self: *Node, child_id: u64) !void {
         try self.children.append(self.allocator, child_id);
     }
@@ -132,6 +156,7 @@ pub const TreeBuilder = struct {
                 .value = if (node.value) |val| val.ptr else null,
                 .text_selection_start = node.text_selection_start,
                 .text_selection_end = node.text_selection_end,
+                .states = node.states,
             };
         }
 
@@ -150,3 +175,8 @@ pub const TreeBuilder = struct {
 pub fn init(ctx: ?*c.mcore_context_t, ns_view: *anyopaque) void {
     c.mcore_a11y_init(ctx, ns_view);
 }
+
+/// Move screen reader focus without resending the tree
+pub fn setFocus(ctx: ?*c.mcore_context_t, focus_id: u64) void {
+    c.mcore_a11y_set_focus(ctx, focus_id);
+}
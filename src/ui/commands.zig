@@ -8,6 +8,8 @@ pub const DrawCommandKind = enum(u8) {
     PushClip = 2,
     PopClip = 3,
     StyledRect = 4,  // New: rect with border and/or shadow
+    PushClipRounded = 5,  // Clip to a rounded rect (uses radius)
+    GradientRing = 6,  // Rounded-rect outline stroked with a gradient (font_id = gradient ID)
 };
 
 /// Command buffer entry - must match C layout for FFI
@@ -36,8 +38,23 @@ pub const DrawCommand = extern struct {
     shadow_color: [4]f32,
     has_shadow: u8,  // 0 = no shadow, 1 = has shadow
 
-    // Padding to maintain alignment
-    _padding: [2]u8 = undefined,
+    text_align: TextAlign = .Start, // For text commands
+    text_direction: TextDirection = .Auto, // For text commands
+};
+
+/// Horizontal alignment of text lines within wrap_width
+pub const TextAlign = enum(u8) {
+    Start = 0,
+    Center = 1,
+    End = 2,
+    Justify = 3,
+};
+
+/// Paragraph direction of text commands (Auto: from the first strong character)
+pub const TextDirection = enum(u8) {
+    Auto = 0,
+    Ltr = 1,
+    Rtl = 2,
 };
 
 pub const CommandBuffer = struct {
@@ -91,6 +108,11 @@ pub const CommandBuffer = struct {
     /// IMPORTANT: The text pointer must remain valid until the frame is rendered
     /// (typically satisfied by string literals or data with matching lifetime)
     pub fn text(self: *CommandBuffer, str: [*:0]const u8, x: f32, y: f32, font_size: f32, wrap_width: f32, col: Color) !void {
+        return self.textAligned(str, x, y, font_size, wrap_width, .Start, col);
+    }
+
+    /// Add a text command aligned within wrap_width
+    pub fn textAligned(self: *CommandBuffer, str: [*:0]const u8, x: f32, y: f32, font_size: f32, wrap_width: f32, alignment: TextAlign, col: Color) !void {
         if (self.count >= self.commands.len) return error.BufferFull;
 
         self.commands[self.count] = .{
@@ -113,6 +135,7 @@ pub const CommandBuffer = struct {
             .shadow_blur = 0,
             .shadow_color = .{ 0, 0, 0, 0 },
             .has_shadow = 0,
+            .text_align = alignment,
         };
         self.count += 1;
     }
@@ -188,6 +211,63 @@ pub const CommandBuffer = struct {
         self.count += 1;
     }
 
+    pub fn pushClipRounded(self: *CommandBuffer, x: f32, y: f32, w: f32, h: f32, radius: f32) !void {
+        if (self.count >= self.commands.len) return error.BufferFull;
+
+        self.commands[self.count] = .{
+            .kind = .PushClipRounded,
+            .x = x,
+            .y = y,
+            .width = w,
+            .height = h,
+            .radius = radius,
+            .color = [4]f32{ 0, 0, 0, 0 },
+            .text_ptr = null,
+            .font_size = 0,
+            .wrap_width = 0,
+            .font_id = 0,
+            .border_width = 0,
+            .border_color = .{ 0, 0, 0, 0 },
+            .has_border = 0,
+            .shadow_offset_x = 0,
+            .shadow_offset_y = 0,
+            .shadow_blur = 0,
+            .shadow_color = .{ 0, 0, 0, 0 },
+            .has_shadow = 0,
+        };
+        self.count += 1;
+    }
+
+    /// Stroke a rounded rect's outline with a gradient registered through
+    /// mcore_gradient_create (focus glows, gradient borders). The stroke is
+    /// centered on the rect's edge.
+    pub fn gradientRing(self: *CommandBuffer, x: f32, y: f32, w: f32, h: f32, radius: f32, width: f32, gradient_id: i32) !void {
+        if (self.count >= self.commands.len) return error.BufferFull;
+
+        self.commands[self.count] = .{
+            .kind = .GradientRing,
+            .x = x,
+            .y = y,
+            .width = w,
+            .height = h,
+            .radius = radius,
+            .color = [4]f32{ 0, 0, 0, 0 },
+            .text_ptr = null,
+            .font_size = 0,
+            .wrap_width = 0,
+            .font_id = gradient_id,
+            .border_width = width,
+            .border_color = .{ 0, 0, 0, 0 },
+            .has_border = 0,
+            .shadow_offset_x = 0,
+            .shadow_offset_y = 0,
+            .shadow_blur = 0,
+            .shadow_color = .{ 0, 0, 0, 0 },
+            .has_shadow = 0,
+        };
+        self.count += 1;
+    }
+
     pub fn popClip(self: *CommandBuffer) !void {
         if (self.count >= self.commands.len) return error.BufferFull;
 
@@ -88,6 +88,17 @@ pub const WidgetContext = struct {
         return left;
     }
 
+    /// Find byte offset in wrapped text at a 2D point (for multi-line cursor positioning)
+    pub fn findByteOffsetAt(self: *const WidgetContext, text: [*:0]const u8, font_size: f32, wrap_width: f32, x: f32, y: f32) usize {
+        var req = std.mem.zeroes(c.mcore_text_req_t);
+        req.utf8 = text;
+        req.wrap_width = wrap_width;
+        req.font_size_px = font_size;
+        req.font_id = -1;
+
+        return @intCast(c.mcore_text_hit_test(self.ctx, &req, x, y));
+    }
+
     // ========================================================================
     // Drawing Commands
     // ========================================================================
@@ -74,6 +74,7 @@ pub const TextInputState = struct {
     buffer: [256]u8 = undefined,
     width: f32,
     height: f32,
+    /// Horizontal caret-follow offset, as computed by the engine this frame
     scroll_offset: f32 = 0,
     x: f32 = 0,
     y: f32 = 0,
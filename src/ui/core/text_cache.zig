@@ -113,7 +113,8 @@ pub const TextCache = struct {
         self.stats.misses += 1;
 
         var size: c.mcore_text_size_t = undefined;
-        c.mcore_measure_text(ctx, text.ptr, font_size, max_width, &size);
+        // Font 0 matches the font_id the draw commands use
+        c.mcore_measure_text(ctx, text.ptr, font_size, max_width, 0, &size);
 
         const value = CacheValue{
             .width = size.width,
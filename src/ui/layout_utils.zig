@@ -13,7 +13,7 @@ pub const ChildMeasurement = struct {
 /// Measure text using the FFI text measurement API
 pub fn measureText(ctx: *c.mcore_context_t, text: []const u8, font_size: f32, max_width: f32) layout_mod.Size {
     var size: c.mcore_text_size_t = undefined;
-    c.mcore_measure_text(ctx, text.ptr, font_size, max_width, &size);
+    c.mcore_measure_text(ctx, text.ptr, font_size, max_width, 0, &size);
     return .{ .width = size.width, .height = size.height };
 }
 
@@ -129,7 +129,7 @@ pub const UI = struct {
         // Present
         const clear = c.mcore_rgba_t{ .r = clear_color.r, .g = clear_color.g, .b = clear_color.b, .a = clear_color.a };
         const st = c.mcore_end_frame_present(self.ctx, clear);
-        if (st != c.MCORE_OK) {
+        if (st != c.MCORE_OK and st != c.MCORE_FRAME_SKIPPED) {
             const err = c.mcore_last_error();
             if (err != null) std.debug.print("mcore error: {s}\n", .{std.mem.span(err)});
         }
@@ -214,7 +214,7 @@ pub const UI = struct {
     // Input Handling
     // ============================================================================
 
-    pub fn handleMouseDown(self: *UI, x: f32, y: f32) void {
+    pub fn handleMouseDown(self: *UI, x: f32, y: f32, shift: bool) void {
         self.input.mouse_x = x;
         self.input.mouse_y = y;
         self.input.mouse_down = true;
@@ -226,13 +226,13 @@ pub const UI = struct {
             if (clickable.kind == .TextInput and clickable.bounds.contains(x, y)) {
                 // Handle text input mouse down
                 if (self.state.text_inputs.getPtr(clickable.id)) |ti| {
-                    const local_x = x - (clickable.bounds.x + text_input_widget.PADDING_X) + ti.scroll_offset;
-                    const len = c.mcore_text_input_get(self.ctx, clickable.id, @constCast(&ti.buffer), 256);
-                    const text = ti.buffer[0..@intCast(len)];
-                    const text_ptr: [*:0]const u8 = if (text.len > 0) @ptrCast(text.ptr) else "";
-                    var widget_ctx = self.createWidgetContext();
-                    const byte_offset = widget_ctx.findByteOffsetAtX(text_ptr, 16, local_x);
-                    c.mcore_text_input_start_selection(self.ctx, clickable.id, @intCast(byte_offset));
+                    const byte_offset = textInputHit(self.ctx, clickable, ti, x, y);
+                    if (shift) {
+                        // Shift+click extends the selection from its anchor (or the caret)
+                        c.mcore_text_input_set_cursor_pos(self.ctx, clickable.id, byte_offset, 1);
+                    } else {
+                        c.mcore_text_input_start_selection(self.ctx, clickable.id, byte_offset);
+                    }
                 }
                 self.focus.setFocus(clickable.id);
                 return;
@@ -265,13 +265,8 @@ pub const UI = struct {
                 for (self.frame_exchange.prev.clickables.items) |clickable| {
                     if (clickable.kind == .TextInput and clickable.id == fid and clickable.bounds.contains(x, y)) {
                         if (self.state.text_inputs.getPtr(clickable.id)) |ti| {
-                            const local_x = x - (clickable.bounds.x + text_input_widget.PADDING_X) + ti.scroll_offset;
-                            const len = c.mcore_text_input_get(self.ctx, clickable.id, @constCast(&ti.buffer), 256);
-                            const text = ti.buffer[0..@intCast(len)];
-                            const text_ptr: [*:0]const u8 = if (text.len > 0) @ptrCast(text.ptr) else "";
-                            var widget_ctx = self.createWidgetContext();
-                            const byte_offset = widget_ctx.findByteOffsetAtX(text_ptr, 16, local_x);
-                            c.mcore_text_input_set_cursor_pos(self.ctx, clickable.id, @intCast(byte_offset), 1);
+                            const byte_offset = textInputHit(self.ctx, clickable, ti, x, y);
+                            c.mcore_text_input_set_cursor_pos(self.ctx, clickable.id, byte_offset, 1);
                         }
                         return;
                     }
@@ -305,7 +300,7 @@ pub const UI = struct {
         }
     }
 
-    pub fn handleKey(self: *UI, key: c_int, char_code: u32, shift: bool, cmd: bool) void {
+    pub fn handleKey(self: *UI, key: c_int, char_code: u32, shift: bool, cmd: bool, option: bool) void {
         const KEY_TAB = 48;
 
         // Handle Tab navigation
@@ -322,7 +317,7 @@ pub const UI = struct {
         if (self.focus.focused_id) |fid| {
             if (self.state.text_inputs.getPtr(fid)) |_| {
                 var widget_ctx = self.createWidgetContext();
-                _ = text_input_widget.handleKey(&widget_ctx, fid, key, char_code, shift, cmd);
+                _ = text_input_widget.handleKey(&widget_ctx, fid, key, char_code, shift, cmd, option);
             }
         }
     }
@@ -1203,6 +1198,16 @@ pub const registerImageRGB8 = image_widget.registerImageRGB8;
 pub const releaseImage = image_widget.releaseImage;
 pub const imageById = image_widget.imageById;
 
+/// Content byte offset under a window point in a text input. Uses the
+/// transform the engine recorded when the input was drawn, falling back to
+/// the previous frame's bounds before the input has been drawn once.
+fn textInputHit(ctx: *c.mcore_context_t, clickable: frame_exchange_mod.ClickableRecord, ti: *state_mod.TextInputState, x: f32, y: f32) c_int {
+    const byte_offset = c.mcore_text_input_hit_test_root(ctx, clickable.id, 16, x, y);
+    if (byte_offset >= 0) return byte_offset;
+    const local_x = x - (clickable.bounds.x + text_input_widget.PADDING_X) + ti.scroll_offset;
+    return c.mcore_text_input_hit_test(ctx, clickable.id, 16, local_x);
+}
+
 pub const VstackOptions = struct {
     gap: f32 = 0,
     padding: f32 = 0,
@@ -15,6 +15,17 @@ pub const PADDING_Y: f32 = 8;
 pub const Options = struct {
     width: f32 = 200,
     height: f32 = 40,
+    /// Password field: draw bullets instead of the content
+    secure: bool = false,
+    /// Show each typed character this long before masking it (secure only)
+    reveal_ms: u32 = 0,
+    /// Maximum length in graphemes (0 = unlimited)
+    max_length: u32 = 0,
+    /// Accepted characters (c.MCORE_CHARS_*)
+    chars: u8 = c.MCORE_CHARS_ANY,
+    /// Keyboard and autocorrection hints for the platform (c.MCORE_KEYBOARD_*, c.MCORE_AUTOCORRECT_*)
+    keyboard: u8 = c.MCORE_KEYBOARD_DEFAULT,
+    autocorrect: u8 = c.MCORE_AUTOCORRECT_DEFAULT,
 };
 
 /// Measure text input dimensions
@@ -44,9 +55,13 @@ pub fn render(
     widget_state.x = x;
     widget_state.y = y;
 
+    c.mcore_text_input_set_secure(ctx.ctx, id, @intFromBool(opts.secure), opts.reveal_ms);
+    // The widget's content buffer holds 255 bytes
+    c.mcore_text_input_set_constraints(ctx.ctx, id, 255, opts.max_length, opts.chars);
+    c.mcore_text_input_set_hints(ctx.ctx, id, opts.keyboard, opts.autocorrect);
+
     // Get current text from Rust
     const len = c.mcore_text_input_get(ctx.ctx, id, &widget_state.buffer, 256);
-    const text = widget_state.buffer[0..@intCast(len)];
 
     // Draw background
     const bg_color = if (is_focused)
@@ -64,39 +79,34 @@ pub fn render(
         try cmd_buffer.roundedRect(x, y, opts.width, opts.height, 4, bg_color);
     }
 
+    // What to draw: the content with any IME preedit spliced in at the caret
+    var display_buf: [512]u8 = undefined;
+    var cursor_pos: c_int = 0;
+    var preedit_start: c_int = 0;
+    var preedit_end: c_int = 0;
+    const display_len = c.mcore_text_input_get_display(ctx.ctx, id, &display_buf, display_buf.len, &cursor_pos, &preedit_start, &preedit_end);
+    const display = display_buf[0..@intCast(display_len)];
+    const composing = preedit_start < preedit_end;
+
     // Measure text
     const max_width_no_wrap: f32 = 100000;
-    const text_ptr: [*:0]const u8 = if (text.len > 0) @ptrCast(text.ptr) else "";
-    const text_size = ctx.measureText(text, 16, max_width_no_wrap);
+    const text_ptr: [*:0]const u8 = if (display.len > 0) @ptrCast(display.ptr) else "";
+    const text_size = ctx.measureText(display, 16, max_width_no_wrap);
 
     const text_y = y + (opts.height - text_size.height) / 2.0;
 
-    // Calculate scroll offset
+    // The engine scrolls long content to keep the caret in view
     const visible_width = opts.width - (PADDING_X * 2);
-    if (is_focused) {
-        const cursor_pos = c.mcore_text_input_cursor(ctx.ctx, id);
-        const cursor_offset_x = c.mcore_measure_text_to_byte_offset(ctx.ctx, text_ptr, 16, cursor_pos);
-        const cursor_right_margin: f32 = 20;
-
-        if (cursor_offset_x - widget_state.scroll_offset > visible_width - cursor_right_margin) {
-            widget_state.scroll_offset = cursor_offset_x - visible_width + cursor_right_margin;
-        }
-        if (cursor_offset_x < widget_state.scroll_offset) {
-            widget_state.scroll_offset = cursor_offset_x;
-        }
-        if (widget_state.scroll_offset < 0) {
-            widget_state.scroll_offset = 0;
-        }
-    }
+    widget_state.scroll_offset = c.mcore_text_input_scroll_x(ctx.ctx, id, visible_width, 16);
 
     // Push clip rect
     try cmd_buffer.pushClip(x, y, opts.width, opts.height);
 
-    // Draw selection highlight
+    // Draw selection highlight (a composition replaces the selection)
     var sel_start: c_int = 0;
     var sel_end: c_int = 0;
-    const has_selection = c.mcore_text_input_get_selection(ctx.ctx, id, &sel_start, &sel_end);
-    if (has_selection != 0 and sel_start < sel_end) {
+    const has_selection = c.mcore_text_input_get_display_selection(ctx.ctx, id, &sel_start, &sel_end);
+    if (!composing and has_selection != 0 and sel_start < sel_end) {
         const sel_start_x = c.mcore_measure_text_to_byte_offset(ctx.ctx, text_ptr, 16, sel_start);
         const sel_end_x = c.mcore_measure_text_to_byte_offset(ctx.ctx, text_ptr, 16, sel_end);
 
@@ -110,14 +120,22 @@ pub fn render(
     // Draw text
     const text_color = color_mod.WHITE;
     const text_x = x + PADDING_X - widget_state.scroll_offset;
+    // Lets the engine answer caret/hit queries in window coordinates
+    c.mcore_text_input_set_origin(ctx.ctx, id, text_x, text_y);
     // Only draw text if there's content
-    if (text.len > 0) {
+    if (display.len > 0) {
         try ctx.drawText(text_ptr, text_x, text_y, 16, max_width_no_wrap, text_color);
     }
 
+    // Underline the IME preedit
+    if (composing) {
+        const preedit_start_x = c.mcore_measure_text_to_byte_offset(ctx.ctx, text_ptr, 16, preedit_start);
+        const preedit_end_x = c.mcore_measure_text_to_byte_offset(ctx.ctx, text_ptr, 16, preedit_end);
+        try cmd_buffer.roundedRect(text_x + preedit_start_x, text_y + text_size.height - 2, preedit_end_x - preedit_start_x, 1, 0, text_color);
+    }
+
     // Draw cursor
     if (is_focused) {
-        const cursor_pos = c.mcore_text_input_cursor(ctx.ctx, id);
         const cursor_offset_x = c.mcore_measure_text_to_byte_offset(ctx.ctx, text_ptr, 16, cursor_pos);
         const cursor_x = x + PADDING_X + cursor_offset_x - widget_state.scroll_offset;
         const cursor_color = color_mod.WHITE;
@@ -142,7 +160,10 @@ pub fn render(
     });
     a11y_node.setLabel(id_str);
 
-    if (len > 0) {
+    // Screen readers announce the bullets, never a password
+    if (opts.secure) {
+        if (display.len > 0) a11y_node.setValue(display);
+    } else if (len > 0) {
         a11y_node.setValue(widget_state.buffer[0..@intCast(len)]);
     }
 
@@ -165,6 +186,7 @@ pub fn handleKey(
     char_code: u32,
     shift: bool,
     cmd: bool,
+    option: bool,
 ) bool {
     _ = cmd; // TODO: Handle cmd+a, cmd+c, cmd+v
 
@@ -188,16 +210,17 @@ pub fn handleKey(
     const KEY_ESC = 53;
     const KEY_TAB = 48;
 
+    // Option modifies movement and deletion to work a word at a time
     if (key == KEY_BACKSPACE) {
-        event.kind = c.TEXT_EVENT_BACKSPACE;
+        event.kind = if (option) c.TEXT_EVENT_BACKSPACE_WORD else c.TEXT_EVENT_BACKSPACE;
     } else if (key == KEY_DELETE) {
-        event.kind = c.TEXT_EVENT_DELETE;
+        event.kind = if (option) c.TEXT_EVENT_DELETE_WORD else c.TEXT_EVENT_DELETE;
     } else if (key == KEY_LEFT) {
         event.kind = c.TEXT_EVENT_MOVE_CURSOR;
-        event.direction = c.CURSOR_LEFT;
+        event.direction = if (option) c.CURSOR_WORD_LEFT else c.CURSOR_LEFT;
     } else if (key == KEY_RIGHT) {
         event.kind = c.TEXT_EVENT_MOVE_CURSOR;
-        event.direction = c.CURSOR_RIGHT;
+        event.direction = if (option) c.CURSOR_WORD_RIGHT else c.CURSOR_RIGHT;
     } else if (key == KEY_HOME) {
         event.kind = c.TEXT_EVENT_MOVE_CURSOR;
         event.direction = c.CURSOR_HOME;